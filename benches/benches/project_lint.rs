@@ -0,0 +1,99 @@
+//! Project-wide lint pass on a large project, with one thread and with the
+//! default rayon pool.
+//!
+//! The project lint pass computes per-file structure, runs the project
+//! rules and converts their results on the current rayon pool. Comparing
+//! `threads/1` against `threads/default` shows the speedup from running that
+//! work in parallel.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use graphql_ide::{AnalysisHost, DocumentKind, FilePath, Language};
+
+const FILE_COUNT: usize = 1200;
+
+const SCHEMA: &str = r"
+type Query {
+  user(id: ID!): User
+  users: [User!]!
+}
+
+type User {
+  id: ID!
+  name: String!
+  email: String!
+  friends: [User!]!
+}
+";
+
+/// A document with a used and an unused fragment, and an operation that
+/// spreads a fragment from the previous file
+fn document(index: usize) -> String {
+    let previous = index.saturating_sub(1);
+    format!(
+        "query GetUser{index}($id: ID!) {{\n  user(id: $id) {{\n    ...UserFields{index}\n    friends {{\n      ...UserFields{previous}\n    }}\n  }}\n}}\n\n\
+         fragment UserFields{index} on User {{\n  id\n  name\n}}\n\n\
+         fragment UnusedFields{index} on User {{\n  email\n}}\n"
+    )
+}
+
+fn large_project() -> AnalysisHost {
+    let mut host = AnalysisHost::new();
+    host.set_lint_config(graphql_linter::LintConfig::recommended());
+
+    let documents: Vec<(FilePath, String)> = (0..FILE_COUNT)
+        .map(|i| {
+            (
+                FilePath::new(format!("file:///documents/query{i}.graphql")),
+                document(i),
+            )
+        })
+        .collect();
+    let mut files = vec![(
+        FilePath::new("file:///schema.graphql"),
+        SCHEMA,
+        Language::GraphQL,
+        DocumentKind::Schema,
+    )];
+    files.extend(documents.iter().map(|(path, text)| {
+        (
+            path.clone(),
+            text.as_str(),
+            Language::GraphQL,
+            DocumentKind::Executable,
+        )
+    }));
+    host.add_files_batch(&files);
+    host
+}
+
+fn bench_project_lint_cold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("project_lint_cold");
+    group.sample_size(10);
+
+    let default_threads = rayon::current_num_threads();
+    for (label, threads) in [("1", 1), ("default", default_threads)] {
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .expect("failed to build thread pool");
+        group.bench_function(BenchmarkId::new("threads", label), |b| {
+            b.iter_batched(
+                large_project,
+                |host| {
+                    let diagnostics = pool.install(|| host.snapshot().project_lint_diagnostics());
+                    black_box(diagnostics);
+                    // Returned so dropping the database isn't timed
+                    host
+                },
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_project_lint_cold);
+criterion_main!(benches);
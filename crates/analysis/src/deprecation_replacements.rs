@@ -0,0 +1,43 @@
+// Deprecation replacement validation
+//
+// Deprecation reasons conventionally point at the field to use instead
+// ("Use `fullName` instead"). Editors navigate to that field and offer to
+// swap it in, so a reference that doesn't resolve, usually left behind by a
+// rename, is reported where it's written.
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use graphql_hir::TypeDefMap;
+
+/// Report deprecation reasons in a schema block whose replacement isn't a
+/// field of the type they name.
+pub(crate) fn replacement_diagnostics(
+    types: &TypeDefMap,
+    tree: &apollo_parser::SyntaxTree,
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    graphql_linter::deprecation_replacement_references(tree)
+        .into_iter()
+        .filter(|reference| {
+            !types
+                .get(reference.target_type.as_str())
+                .is_some_and(|type_def| {
+                    type_def
+                        .fields
+                        .iter()
+                        .any(|field| field.name.as_ref() == reference.target_field)
+                })
+        })
+        .map(|reference| {
+            let mut diagnostic = Diagnostic::warning(
+                format!(
+                    "Deprecation reason refers to `{}`, which isn't a field of `{}`",
+                    reference.target_field, reference.target_type
+                ),
+                block.range(reference.range.0, reference.range.1),
+            );
+            diagnostic.code = Some("unknown-deprecation-replacement".into());
+            diagnostic
+        })
+        .collect()
+}
@@ -37,6 +37,16 @@ pub struct CodeSuggestion {
     pub fix: CodeFix,
 }
 
+/// Another location relevant to a diagnostic, possibly in a different
+/// file. The line/column equivalent of `graphql_linter::RelatedSpan`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedInformation {
+    /// URI of the file the range belongs to
+    pub uri: Arc<str>,
+    pub range: DiagnosticRange,
+    pub message: Arc<str>,
+}
+
 /// A diagnostic message (error, warning, or info)
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Diagnostic {
@@ -66,6 +76,8 @@ pub struct Diagnostic {
     pub url: Option<Arc<str>>,
     /// Diagnostic tags for additional classification
     pub tags: Vec<DiagnosticTag>,
+    /// Other locations involved in the issue
+    pub related: Vec<RelatedInformation>,
 }
 
 impl Diagnostic {
@@ -84,6 +96,7 @@ impl Diagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -102,6 +115,7 @@ impl Diagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -120,6 +134,7 @@ impl Diagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -144,6 +159,7 @@ impl Diagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 }
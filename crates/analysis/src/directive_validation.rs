@@ -0,0 +1,168 @@
+// Directive usage validation
+//
+// Implements the spec's "Directives Are Defined", "Directives Are In Valid
+// Locations" and "Directives Are Unique Per Location" rules for every
+// directive in a block, executable or type system. Locations and
+// repeatability come from the directive definitions in HIR, so client
+// directives from the configured builtins are known here too. The spec's
+// own directives are always known, even when no builtins file is loaded.
+//
+// Each problem is reported on the directive's name (`@` included) rather
+// than the whole directive, so long argument lists don't get underlined.
+// A repeated directive is reported on every use after the first.
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::{SyntaxKind, SyntaxNode};
+use graphql_hir::{DirectiveDefMap, DirectiveLocationKind};
+use std::collections::HashSet;
+
+/// Check every directive used in a document block against `directives`.
+pub(crate) fn directive_diagnostics(
+    directives: &DirectiveDefMap,
+    tree: &apollo_parser::SyntaxTree,
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for list in tree
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::Directives::cast)
+    {
+        let location = list.syntax().parent().and_then(|owner| location_of(&owner));
+        let mut seen: HashSet<String> = HashSet::new();
+
+        for directive in list.directives() {
+            let Some(name) = directive.name() else {
+                continue;
+            };
+            let name_text = name.text().to_string();
+            let start: usize = directive.syntax().text_range().start().into();
+            let range = block.range(start, name.syntax().text_range().end().into());
+            let first_use = seen.insert(name_text.clone());
+
+            let definition = directives
+                .get(name_text.as_str())
+                .map(|def| (def.locations.as_slice(), def.repeatable))
+                .or_else(|| spec_directive(&name_text));
+            let Some((locations, repeatable)) = definition else {
+                let mut diagnostic =
+                    Diagnostic::error(format!("Unknown directive `@{name_text}`"), range);
+                diagnostic.code = Some("unknown-directive".into());
+                diagnostics.push(diagnostic);
+                continue;
+            };
+
+            if let Some(location) = location {
+                if !locations.contains(&location) {
+                    let allowed: Vec<String> = locations.iter().map(ToString::to_string).collect();
+                    let mut diagnostic = Diagnostic::error(
+                        format!(
+                            "Directive `@{name_text}` can't be used on {location}; it's allowed on {}",
+                            allowed.join(", ")
+                        ),
+                        range,
+                    );
+                    diagnostic.code = Some("misplaced-directive".into());
+                    diagnostics.push(diagnostic);
+                }
+            }
+
+            if !first_use && !repeatable {
+                let mut diagnostic = Diagnostic::error(
+                    format!(
+                        "Directive `@{name_text}` isn't repeatable but is used more than once here"
+                    ),
+                    range,
+                );
+                diagnostic.code = Some("repeated-directive".into());
+                diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Returns true for apollo-compiler's directive diagnostics, which
+/// `directive_diagnostics` reports itself on the directive name.
+pub(crate) fn is_superseded_directive_message(message: &str) -> bool {
+    message.contains("cannot find directive")
+        || (message.contains("directive is not supported for") && message.contains("location"))
+        || message.contains("can only be used once")
+}
+
+/// Locations and repeatability of the directives the spec defines
+fn spec_directive(name: &str) -> Option<(&'static [DirectiveLocationKind], bool)> {
+    use DirectiveLocationKind::{
+        ArgumentDefinition, EnumValue, Field, FieldDefinition, FragmentSpread, InlineFragment,
+        InputFieldDefinition, InputObject, Scalar,
+    };
+    let locations: &'static [DirectiveLocationKind] = match name {
+        "skip" | "include" => &[Field, FragmentSpread, InlineFragment],
+        "deprecated" => &[
+            FieldDefinition,
+            ArgumentDefinition,
+            InputFieldDefinition,
+            EnumValue,
+        ],
+        "specifiedBy" => &[Scalar],
+        "oneOf" => &[InputObject],
+        _ => return None,
+    };
+    Some((locations, false))
+}
+
+/// The location a directive list applies to, from the node that owns it
+fn location_of(owner: &SyntaxNode) -> Option<DirectiveLocationKind> {
+    let location = match owner.kind() {
+        SyntaxKind::OPERATION_DEFINITION => {
+            let operation_type = cst::OperationDefinition::cast(owner.clone())?.operation_type();
+            match operation_type {
+                Some(t) if t.mutation_token().is_some() => DirectiveLocationKind::Mutation,
+                Some(t) if t.subscription_token().is_some() => DirectiveLocationKind::Subscription,
+                _ => DirectiveLocationKind::Query,
+            }
+        }
+        SyntaxKind::FIELD => DirectiveLocationKind::Field,
+        SyntaxKind::FRAGMENT_DEFINITION => DirectiveLocationKind::FragmentDefinition,
+        SyntaxKind::FRAGMENT_SPREAD => DirectiveLocationKind::FragmentSpread,
+        SyntaxKind::INLINE_FRAGMENT => DirectiveLocationKind::InlineFragment,
+        SyntaxKind::VARIABLE_DEFINITION => DirectiveLocationKind::VariableDefinition,
+        SyntaxKind::SCHEMA_DEFINITION | SyntaxKind::SCHEMA_EXTENSION => {
+            DirectiveLocationKind::Schema
+        }
+        SyntaxKind::SCALAR_TYPE_DEFINITION | SyntaxKind::SCALAR_TYPE_EXTENSION => {
+            DirectiveLocationKind::Scalar
+        }
+        SyntaxKind::OBJECT_TYPE_DEFINITION | SyntaxKind::OBJECT_TYPE_EXTENSION => {
+            DirectiveLocationKind::Object
+        }
+        SyntaxKind::FIELD_DEFINITION => DirectiveLocationKind::FieldDefinition,
+        SyntaxKind::INPUT_VALUE_DEFINITION => {
+            if owner.parent()?.kind() == SyntaxKind::INPUT_FIELDS_DEFINITION {
+                DirectiveLocationKind::InputFieldDefinition
+            } else {
+                DirectiveLocationKind::ArgumentDefinition
+            }
+        }
+        SyntaxKind::INTERFACE_TYPE_DEFINITION | SyntaxKind::INTERFACE_TYPE_EXTENSION => {
+            DirectiveLocationKind::Interface
+        }
+        SyntaxKind::UNION_TYPE_DEFINITION | SyntaxKind::UNION_TYPE_EXTENSION => {
+            DirectiveLocationKind::Union
+        }
+        SyntaxKind::ENUM_TYPE_DEFINITION | SyntaxKind::ENUM_TYPE_EXTENSION => {
+            DirectiveLocationKind::Enum
+        }
+        SyntaxKind::ENUM_VALUE_DEFINITION => DirectiveLocationKind::EnumValue,
+        SyntaxKind::INPUT_OBJECT_TYPE_DEFINITION | SyntaxKind::INPUT_OBJECT_TYPE_EXTENSION => {
+            DirectiveLocationKind::InputObject
+        }
+        _ => return None,
+    };
+    Some(location)
+}
@@ -47,7 +47,10 @@ pub fn validate_document_file(
 ) -> Arc<Vec<Diagnostic>> {
     let structure = graphql_hir::file_structure(db, metadata.file_id(db), content, metadata);
     let mut diagnostics = Vec::new();
-    let schema = graphql_hir::schema_types(db, project_files);
+    // Only type names and kinds are checked here, so depend on the kind index
+    // rather than the full type map: schema edits that don't add, remove or
+    // re-kind a type leave every document's validation cached.
+    let schema = graphql_hir::schema_type_kinds(db, project_files);
 
     // Only query the operation name index when this file has named operations.
     // This avoids creating a Salsa dependency on the project-wide index for files
@@ -131,7 +134,7 @@ pub fn validate_document_file(
 /// Validate that a variable's type exists and is a valid input type
 fn validate_variable_type(
     type_ref: &graphql_hir::TypeRef,
-    schema: &std::collections::HashMap<Arc<str>, graphql_hir::TypeDef>,
+    schema: &graphql_hir::TypeKindMap,
     range: DiagnosticRange,
     diagnostics: &mut Vec<Diagnostic>,
 ) {
@@ -140,9 +143,9 @@ fn validate_variable_type(
         return;
     }
 
-    if let Some(type_def) = schema.get(&type_ref.name) {
+    if let Some(kind) = schema.get(&type_ref.name) {
         use graphql_hir::TypeDefKind;
-        match type_def.kind {
+        match kind {
             TypeDefKind::Scalar | TypeDefKind::Enum | TypeDefKind::InputObject => {
                 // Valid input types for variables
             }
@@ -167,7 +170,7 @@ fn validate_variable_type(
 /// Validate that a fragment's type condition exists in the schema
 fn validate_fragment_type_condition(
     fragment: &graphql_hir::FragmentStructure,
-    schema: &std::collections::HashMap<Arc<str>, graphql_hir::TypeDef>,
+    schema: &graphql_hir::TypeKindMap,
     range: DiagnosticRange,
     diagnostics: &mut Vec<Diagnostic>,
 ) {
@@ -182,9 +185,9 @@ fn validate_fragment_type_condition(
         return;
     }
 
-    if let Some(type_def) = schema.get(&fragment.type_condition) {
+    if let Some(kind) = schema.get(&fragment.type_condition) {
         use graphql_hir::TypeDefKind;
-        match type_def.kind {
+        match kind {
             TypeDefKind::Object | TypeDefKind::Interface | TypeDefKind::Union => {
                 // Valid fragment type conditions
             }
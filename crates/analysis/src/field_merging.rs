@@ -0,0 +1,605 @@
+// Field merging validation (OverlappingFieldsCanBeMerged)
+//
+// Every field selected under the same response key in a selection set has
+// to resolve to a single value: the fields must be the same field with the
+// same arguments, unless their parent types are distinct object types that
+// can never apply at once, and their return types must have the same shape.
+// Fields are gathered through inline fragments and fragment spreads
+// (including fragments in other files), and the sub-selections of two
+// fields sharing a key are compared against each other.
+//
+// A conflict is reported in the file being validated: on the field when it's
+// in this file, otherwise on the spread that brings it in. The other field
+// is attached as related information.
+
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, RelatedInformation};
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::SyntaxKind;
+use graphql_apollo_ext::{OperationExt, TypeConditionExt};
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+use graphql_hir::{TypeDefKind, TypeDefMap, TypeRef};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// Sub-selections compared below this depth are assumed not to conflict, so
+/// fragments that spread themselves terminate
+const MAX_DEPTH: usize = 32;
+
+/// Check every operation and fragment in a file for fields that can't be
+/// merged.
+#[salsa::tracked]
+pub fn field_merging_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Arc<Vec<Diagnostic>> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let types =
+        graphql_hir::document_schema_types(db, project_files, metadata.file_id(db), content);
+    let uri: Arc<str> = Arc::from(metadata.uri(db).as_str());
+
+    let mut checker = Checker {
+        db,
+        types,
+        fragment_index: graphql_hir::fragment_file_index(db, project_files),
+        current_uri: uri.clone(),
+        blocks: Vec::new(),
+        fragments: HashMap::new(),
+        current_fragment: None,
+        reported: HashSet::new(),
+        diagnostics: Vec::new(),
+    };
+
+    for doc in parse.documents() {
+        let block = checker.block_index(&uri, &doc);
+        for definition in doc.tree.document().definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(op) => {
+                    checker.current_fragment = None;
+                    let root = op.operation_kind().root_type_name();
+                    if let Some(selection_set) = op.selection_set() {
+                        checker.check_selection_set(&selection_set, Some(root), block);
+                    }
+                }
+                cst::Definition::FragmentDefinition(frag) => {
+                    checker.current_fragment = frag
+                        .fragment_name()
+                        .and_then(|n| n.name())
+                        .map(|n| Arc::from(n.text().as_str()));
+                    let type_condition = frag.type_condition_name();
+                    if let Some(selection_set) = frag.selection_set() {
+                        checker.check_selection_set(
+                            &selection_set,
+                            type_condition.as_deref(),
+                            block,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Arc::new(checker.diagnostics)
+}
+
+/// Returns true for apollo-compiler diagnostics that
+/// `field_merging_diagnostics` reports itself (with both locations), so
+/// `validate_file` can drop the duplicates.
+pub(crate) fn is_superseded_merge_message(message: &str) -> bool {
+    message.starts_with("cannot select different fields into the same alias")
+        || message.starts_with("operation must not provide conflicting field arguments")
+        || message.starts_with("operation must not select different types using the same name")
+}
+
+/// A GraphQL block that selected fields come from
+struct Block {
+    uri: Arc<str>,
+    byte_offset: usize,
+    line_index: graphql_syntax::LineIndex,
+    origin: graphql_syntax::BlockOrigin,
+}
+
+impl Block {
+    fn range(&self, start: usize, end: usize) -> DiagnosticRange {
+        let position = |offset| {
+            let (line, character) = self.line_index.line_col(offset);
+            let (line, character) = self.origin.to_file(line as u32, character as u32);
+            Position { line, character }
+        };
+        DiagnosticRange::new(position(start), position(end))
+    }
+}
+
+/// A byte range within a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Loc {
+    block: usize,
+    start: usize,
+    end: usize,
+}
+
+/// An output type with its list and non-null wrappers
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OutputType {
+    name: Arc<str>,
+    is_list: bool,
+    is_non_null: bool,
+    inner_non_null: bool,
+}
+
+impl From<&TypeRef> for OutputType {
+    fn from(type_ref: &TypeRef) -> Self {
+        Self {
+            name: type_ref.name.clone(),
+            is_list: type_ref.is_list,
+            is_non_null: type_ref.is_non_null,
+            inner_non_null: type_ref.inner_non_null,
+        }
+    }
+}
+
+impl fmt::Display for OutputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_list {
+            let inner = if self.inner_non_null { "!" } else { "" };
+            write!(f, "[{}{inner}]", self.name)?;
+        } else {
+            write!(f, "{}", self.name)?;
+        }
+        if self.is_non_null {
+            write!(f, "!")?;
+        }
+        Ok(())
+    }
+}
+
+/// A field gathered from a selection set, fragments included
+struct SelectedField {
+    response_key: String,
+    name: String,
+    /// Type the field is selected on
+    parent_type: Option<String>,
+    /// Arguments sorted by name, with values normalized to their tokens
+    arguments: Vec<(String, String)>,
+    output_type: Option<OutputType>,
+    selection_set: Option<cst::SelectionSet>,
+    /// The response key's range (alias and name)
+    loc: Loc,
+    /// Where the field is reported in the file being validated: the field
+    /// itself, or the spread that brings it in
+    anchor: Loc,
+    /// The fragment definition the field is written in
+    fragment: Option<Arc<str>>,
+}
+
+/// Why two fields can't be merged
+enum Reason {
+    DifferentFields(String, String),
+    DifferentArguments,
+    DifferentTypes(OutputType, OutputType),
+    Subfields(Vec<(String, Reason)>),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DifferentFields(a, b) => write!(f, "`{a}` and `{b}` are different fields"),
+            Self::DifferentArguments => write!(f, "they have differing arguments"),
+            Self::DifferentTypes(a, b) => {
+                write!(f, "they return conflicting types `{a}` and `{b}`")
+            }
+            Self::Subfields(conflicts) => {
+                for (i, (key, reason)) in conflicts.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " and ")?;
+                    }
+                    write!(f, "subfields `{key}` conflict because {reason}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+type FragmentIndex = HashMap<Arc<str>, (FileContent, FileMetadata)>;
+
+struct Checker<'a> {
+    db: &'a dyn GraphQLAnalysisDatabase,
+    types: &'a TypeDefMap,
+    fragment_index: Arc<FragmentIndex>,
+    current_uri: Arc<str>,
+    blocks: Vec<Block>,
+    /// Resolved fragment definitions and the block each is in
+    fragments: HashMap<Arc<str>, Option<(cst::FragmentDefinition, usize)>>,
+    /// The fragment definition being checked, `None` for operations
+    current_fragment: Option<Arc<str>>,
+    /// Field pairs already reported
+    reported: HashSet<(Loc, Loc)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn block_index(&mut self, uri: &Arc<str>, doc: &graphql_syntax::DocumentRef<'_>) -> usize {
+        if let Some(index) = self
+            .blocks
+            .iter()
+            .position(|b| b.uri == *uri && b.byte_offset == doc.byte_offset)
+        {
+            return index;
+        }
+        self.blocks.push(Block {
+            uri: uri.clone(),
+            byte_offset: doc.byte_offset,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        });
+        self.blocks.len() - 1
+    }
+
+    fn is_current(&self, block: usize) -> bool {
+        self.blocks[block].uri == self.current_uri
+    }
+
+    fn fragment(&mut self, name: &Arc<str>) -> Option<(cst::FragmentDefinition, usize)> {
+        if let Some(cached) = self.fragments.get(name) {
+            return cached.clone();
+        }
+        let resolved = self.resolve_fragment(name);
+        self.fragments.insert(name.clone(), resolved.clone());
+        resolved
+    }
+
+    fn resolve_fragment(&mut self, name: &Arc<str>) -> Option<(cst::FragmentDefinition, usize)> {
+        let (content, metadata) = *self.fragment_index.get(name)?;
+        let parse = graphql_syntax::parse(self.db, content, metadata);
+        let uri: Arc<str> = Arc::from(metadata.uri(self.db).as_str());
+        for doc in parse.documents() {
+            for definition in doc.tree.document().definitions() {
+                let cst::Definition::FragmentDefinition(frag) = definition else {
+                    continue;
+                };
+                let matches = frag
+                    .fragment_name()
+                    .and_then(|n| n.name())
+                    .is_some_and(|n| n.text() == name.as_ref());
+                if matches {
+                    let block = self.block_index(&uri, &doc);
+                    return Some((frag, block));
+                }
+            }
+        }
+        None
+    }
+
+    /// Report conflicts between the fields of `selection_set`, then check
+    /// the selection sets nested in it.
+    fn check_selection_set(
+        &mut self,
+        selection_set: &cst::SelectionSet,
+        parent_type: Option<&str>,
+        block: usize,
+    ) {
+        let root = Loc {
+            block,
+            start: 0,
+            end: 0,
+        };
+        let current_fragment = self.current_fragment.clone();
+        let mut fields = Vec::new();
+        let mut visited = HashSet::new();
+        self.collect(
+            selection_set,
+            parent_type,
+            block,
+            current_fragment.as_ref(),
+            root,
+            &mut visited,
+            &mut fields,
+        );
+
+        for (i, a) in fields.iter().enumerate() {
+            for b in &fields[i + 1..] {
+                if a.response_key != b.response_key || a.loc == b.loc {
+                    continue;
+                }
+                // Conflicts inside another fragment are reported on that
+                // fragment's definition
+                if a.fragment.is_some()
+                    && a.fragment == b.fragment
+                    && a.fragment != self.current_fragment
+                {
+                    continue;
+                }
+                if let Some(reason) = self.find_conflict(a, b, false, 0) {
+                    self.report(a, b, &reason);
+                }
+            }
+        }
+
+        // Selection sets written in this definition; those of spread
+        // fragments are checked on the fragment's own definition
+        for field in &fields {
+            if field.fragment != self.current_fragment || !self.is_current(field.loc.block) {
+                continue;
+            }
+            if let Some(nested) = &field.selection_set {
+                let output = field.output_type.as_ref().map(|t| t.name.to_string());
+                self.check_selection_set(nested, output.as_deref(), field.loc.block);
+            }
+        }
+    }
+
+    /// Gather the fields of `selection_set`, flattening inline fragments and
+    /// fragment spreads. `anchor` is the spread through which fields outside
+    /// the current file are reached.
+    #[allow(clippy::too_many_arguments)]
+    fn collect(
+        &mut self,
+        selection_set: &cst::SelectionSet,
+        parent_type: Option<&str>,
+        block: usize,
+        fragment: Option<&Arc<str>>,
+        anchor: Loc,
+        visited: &mut HashSet<Arc<str>>,
+        out: &mut Vec<SelectedField>,
+    ) {
+        let types = self.types;
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let Some(name) = field.name() else {
+                        continue;
+                    };
+                    let name_text = name.text().to_string();
+                    let alias = field.alias().and_then(|a| a.name());
+                    let start: usize = alias
+                        .as_ref()
+                        .map_or_else(|| name.syntax().text_range(), |a| a.syntax().text_range())
+                        .start()
+                        .into();
+                    let loc = Loc {
+                        block,
+                        start,
+                        end: name.syntax().text_range().end().into(),
+                    };
+                    let output_type = if name_text == "__typename" {
+                        Some(OutputType {
+                            name: Arc::from("String"),
+                            is_list: false,
+                            is_non_null: true,
+                            inner_non_null: false,
+                        })
+                    } else {
+                        parent_type
+                            .and_then(|parent| types.get(parent))
+                            .and_then(|t| t.fields.iter().find(|f| f.name.as_ref() == name_text))
+                            .map(|f| OutputType::from(&f.type_ref))
+                    };
+                    out.push(SelectedField {
+                        response_key: alias
+                            .map_or_else(|| name_text.clone(), |a| a.text().to_string()),
+                        name: name_text,
+                        parent_type: parent_type.map(ToString::to_string),
+                        arguments: normalized_arguments(field.arguments()),
+                        output_type,
+                        selection_set: field.selection_set(),
+                        loc,
+                        anchor: if self.is_current(block) { loc } else { anchor },
+                        fragment: fragment.cloned(),
+                    });
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let type_condition = inline.type_condition_name();
+                    if let Some(nested) = inline.selection_set() {
+                        self.collect(
+                            &nested,
+                            type_condition.as_deref().or(parent_type),
+                            block,
+                            fragment,
+                            anchor,
+                            visited,
+                            out,
+                        );
+                    }
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    let Some(name) = spread.fragment_name().and_then(|n| n.name()) else {
+                        continue;
+                    };
+                    let name: Arc<str> = Arc::from(name.text().as_str());
+                    if !visited.insert(name.clone()) {
+                        continue;
+                    }
+                    let Some((definition, fragment_block)) = self.fragment(&name) else {
+                        continue;
+                    };
+                    let range = spread.syntax().text_range();
+                    let spread_anchor = if self.is_current(block) {
+                        Loc {
+                            block,
+                            start: range.start().into(),
+                            end: range.end().into(),
+                        }
+                    } else {
+                        anchor
+                    };
+                    let type_condition = definition.type_condition_name();
+                    if let Some(nested) = definition.selection_set() {
+                        self.collect(
+                            &nested,
+                            type_condition.as_deref(),
+                            fragment_block,
+                            Some(&name),
+                            spread_anchor,
+                            visited,
+                            out,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Why `a` and `b`, which share a response key, can't be merged.
+    /// `exclusive` is set when their parents can never apply together.
+    fn find_conflict(
+        &mut self,
+        a: &SelectedField,
+        b: &SelectedField,
+        exclusive: bool,
+        depth: usize,
+    ) -> Option<Reason> {
+        let exclusive = exclusive
+            || (a.parent_type != b.parent_type
+                && self.is_object(a.parent_type.as_deref())
+                && self.is_object(b.parent_type.as_deref()));
+
+        if !exclusive {
+            if a.name != b.name {
+                return Some(Reason::DifferentFields(a.name.clone(), b.name.clone()));
+            }
+            if a.arguments != b.arguments {
+                return Some(Reason::DifferentArguments);
+            }
+        }
+
+        if let (Some(type_a), Some(type_b)) = (&a.output_type, &b.output_type) {
+            if self.types_conflict(type_a, type_b) {
+                return Some(Reason::DifferentTypes(type_a.clone(), type_b.clone()));
+            }
+        }
+
+        let (Some(set_a), Some(set_b)) = (&a.selection_set, &b.selection_set) else {
+            return None;
+        };
+        if depth >= MAX_DEPTH {
+            return None;
+        }
+        let fields_a = self.sub_fields(set_a, a);
+        let fields_b = self.sub_fields(set_b, b);
+        let mut conflicts: Vec<(String, Reason)> = Vec::new();
+        for field_a in &fields_a {
+            if conflicts
+                .iter()
+                .any(|(key, _)| *key == field_a.response_key)
+            {
+                continue;
+            }
+            for field_b in fields_b
+                .iter()
+                .filter(|f| f.response_key == field_a.response_key)
+            {
+                if let Some(reason) = self.find_conflict(field_a, field_b, exclusive, depth + 1) {
+                    conflicts.push((field_a.response_key.clone(), reason));
+                    break;
+                }
+            }
+        }
+        (!conflicts.is_empty()).then_some(Reason::Subfields(conflicts))
+    }
+
+    fn sub_fields(
+        &mut self,
+        selection_set: &cst::SelectionSet,
+        field: &SelectedField,
+    ) -> Vec<SelectedField> {
+        let parent = field.output_type.as_ref().map(|t| t.name.to_string());
+        let mut fields = Vec::new();
+        self.collect(
+            selection_set,
+            parent.as_deref(),
+            field.loc.block,
+            field.fragment.as_ref(),
+            field.anchor,
+            &mut HashSet::new(),
+            &mut fields,
+        );
+        fields
+    }
+
+    fn is_object(&self, type_name: Option<&str>) -> bool {
+        type_name
+            .and_then(|name| self.types.get(name))
+            .is_some_and(|t| matches!(t.kind, TypeDefKind::Object))
+    }
+
+    /// Whether two return types have different shapes. Composite types only
+    /// need matching wrappers; their sub-selections are compared separately.
+    fn types_conflict(&self, a: &OutputType, b: &OutputType) -> bool {
+        if a.is_list != b.is_list
+            || a.is_non_null != b.is_non_null
+            || (a.is_list && a.inner_non_null != b.inner_non_null)
+        {
+            return true;
+        }
+        let is_composite = |name: &str| {
+            self.types.get(name).is_some_and(|t| {
+                matches!(
+                    t.kind,
+                    TypeDefKind::Object | TypeDefKind::Interface | TypeDefKind::Union
+                )
+            })
+        };
+        (!is_composite(&a.name) || !is_composite(&b.name)) && a.name != b.name
+    }
+
+    fn report(&mut self, a: &SelectedField, b: &SelectedField, reason: &Reason) {
+        if !self.reported.insert((a.loc, b.loc)) {
+            return;
+        }
+        let anchor = &self.blocks[b.anchor.block];
+        let mut diagnostic = Diagnostic::error(
+            format!("Fields `{}` conflict because {reason}", a.response_key),
+            anchor.range(b.anchor.start, b.anchor.end),
+        );
+        diagnostic.code = Some("conflicting-fields".into());
+        diagnostic.help = Some("Use different aliases on the fields to fetch both".into());
+
+        let mut related = vec![(a.loc, format!("`{}` is also selected here", a.response_key))];
+        if b.loc != b.anchor {
+            related.push((b.loc, format!("`{}` is selected here", b.response_key)));
+        }
+        for (loc, message) in related {
+            let block = &self.blocks[loc.block];
+            diagnostic.related.push(RelatedInformation {
+                uri: block.uri.clone(),
+                range: block.range(loc.start, loc.end),
+                message: message.into(),
+            });
+        }
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+/// Arguments sorted by name, each value reduced to its significant tokens so
+/// formatting differences don't count as different arguments
+fn normalized_arguments(arguments: Option<cst::Arguments>) -> Vec<(String, String)> {
+    let Some(arguments) = arguments else {
+        return Vec::new();
+    };
+    let mut normalized: Vec<(String, String)> = arguments
+        .arguments()
+        .filter_map(|argument| {
+            let name = argument.name()?.text().to_string();
+            let value = argument.value()?;
+            let tokens: Vec<String> = value
+                .syntax()
+                .descendants_with_tokens()
+                .filter_map(|element| element.into_token())
+                .filter(|token| {
+                    !matches!(
+                        token.kind(),
+                        SyntaxKind::WHITESPACE | SyntaxKind::COMMA | SyntaxKind::COMMENT
+                    )
+                })
+                .map(|token| token.text().to_string())
+                .collect();
+            Some((name, tokens.join(" ")))
+        })
+        .collect();
+    normalized.sort();
+    normalized
+}
@@ -0,0 +1,216 @@
+// `# import` diagnostics.
+//
+// Imports are parsed and scoped in `graphql-hir`. Once a document declares
+// an import, it only sees the fragments it defines or imports; this reports
+// spreads that fall outside that scope, along with imports that don't
+// resolve.
+
+use crate::{
+    CodeFix, Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Severity, TextEdit,
+};
+use apollo_parser::cst::{self, CstNode};
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+
+/// Unresolved imports, imported names the target doesn't define, and
+/// spreads of project fragments the document doesn't import.
+pub(crate) fn import_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Vec<Diagnostic> {
+    let file_id = metadata.file_id(db);
+    let Some(scope) =
+        graphql_hir::file_fragment_scope(db, file_id, content, metadata, project_files)
+    else {
+        return Vec::new();
+    };
+    let imports = graphql_hir::file_imports(db, content, metadata);
+    let uri_to_id = project_files.file_path_map(db).uri_to_id(db);
+    let line_index = graphql_syntax::line_index(db, content);
+    let range_of = |range: graphql_hir::TextRange| {
+        let (start_line, start_col) = line_index.line_col(range.start().into());
+        let (end_line, end_col) = line_index.line_col(range.end().into());
+        DiagnosticRange {
+            start: Position {
+                line: start_line as u32,
+                character: start_col as u32,
+            },
+            end: Position {
+                line: end_line as u32,
+                character: end_col as u32,
+            },
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    for import in imports.iter() {
+        let Some(&target) = uri_to_id.get(import.uri.as_ref()) else {
+            diagnostics.push(Diagnostic::with_source_and_code(
+                Severity::Warning,
+                format!(
+                    "Cannot resolve import `{}`: no project document at that path",
+                    import.path
+                ),
+                range_of(import.path_range),
+                "validation",
+                "unresolved-import",
+            ));
+            continue;
+        };
+        let Some(names) = &import.names else {
+            continue;
+        };
+        let Some((target_content, target_metadata)) =
+            graphql_base_db::file_lookup(db, project_files, target)
+        else {
+            continue;
+        };
+        let defined =
+            graphql_hir::file_defined_fragment_names(db, target, target_content, target_metadata);
+        for name in names {
+            if !defined.contains(&name.name) {
+                diagnostics.push(Diagnostic::with_source_and_code(
+                    Severity::Warning,
+                    format!(
+                        "Fragment `{}` is not defined in `{}`",
+                        name.name, import.path
+                    ),
+                    range_of(name.range),
+                    "validation",
+                    "unknown-import",
+                ));
+            }
+        }
+    }
+
+    // New imports go on the line after the last existing one
+    let insert_at = imports
+        .iter()
+        .map(|import| range_of(import.path_range).start.line + 1)
+        .max()
+        .unwrap_or_default();
+    let fragments = graphql_hir::all_fragments(db, project_files);
+    let importer = metadata.uri(db);
+    let parse = graphql_syntax::parse(db, content, metadata);
+    for doc in parse.documents() {
+        let block = crate::variable_flow::BlockPositions {
+            source: doc.source,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        };
+        for spread in doc
+            .tree
+            .document()
+            .syntax()
+            .descendants()
+            .filter_map(cst::FragmentSpread::cast)
+        {
+            let Some(name) = spread.fragment_name().and_then(|n| n.name()) else {
+                continue;
+            };
+            let name_text = name.text().to_string();
+            if scope.contains_key(name_text.as_str()) {
+                continue;
+            }
+            // Fragments that exist nowhere are reported by validation
+            let Some(uri) = fragments
+                .get(name_text.as_str())
+                .and_then(|f| graphql_base_db::uri_for_file_id(db, project_files, f.file_id))
+            else {
+                continue;
+            };
+
+            let path = relative_import_path(importer.as_str(), &uri, true);
+            let statement = format!("# import {name_text} from \"{path}\"");
+            let range = name.syntax().text_range();
+            let mut diagnostic = Diagnostic::with_source_and_code(
+                Severity::Error,
+                format!("Fragment `{name_text}` is not imported; it's defined in `{path}`"),
+                block.range(range.start().into(), range.end().into()),
+                "validation",
+                "missing-import",
+            );
+            diagnostic.fix = Some(CodeFix {
+                label: format!("Add `{statement}`"),
+                edits: vec![TextEdit {
+                    range: DiagnosticRange::at(Position {
+                        line: insert_at,
+                        character: 0,
+                    }),
+                    new_text: format!("{statement}\n"),
+                }],
+            });
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+/// Path from the directory of `importer` to `target`, both URIs, in the
+/// form import comments use. Paths that go up start with `../`; paths to the
+/// importer's directory or below start with `./` if `dot_prefix` is set.
+pub fn relative_import_path(importer: &str, target: &str, dot_prefix: bool) -> String {
+    let mut from: Vec<&str> = importer.split('/').collect();
+    from.pop();
+    let to: Vec<&str> = target.split('/').collect();
+
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(to.len() - 1);
+    let mut segments = vec![".."; from.len() - common];
+    if segments.is_empty() && dot_prefix {
+        segments.push(".");
+    }
+    segments.extend(&to[common..]);
+    segments.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_import_path() {
+        assert_eq!(
+            relative_import_path("file:///app/q.graphql", "file:///app/user.graphql", true),
+            "./user.graphql"
+        );
+        assert_eq!(
+            relative_import_path(
+                "file:///app/queries/q.graphql",
+                "file:///app/fragments/user.graphql",
+                true
+            ),
+            "../fragments/user.graphql"
+        );
+        assert_eq!(
+            relative_import_path(
+                "file:///app/q.graphql",
+                "file:///app/a/b/user.graphql",
+                true
+            ),
+            "./a/b/user.graphql"
+        );
+        assert_eq!(
+            relative_import_path(
+                "file:///app/src/query.graphql",
+                "file:///app/src/fragments/user.graphql",
+                false
+            ),
+            "fragments/user.graphql"
+        );
+        assert_eq!(
+            relative_import_path(
+                "file:///app/src/pages/query.graphql",
+                "file:///app/shared/user.graphql",
+                false
+            ),
+            "../../shared/user.graphql"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+// Incremental delivery validation
+//
+// Locations and argument types of `@defer` and `@stream` are checked like
+// any directive's, against their built-in or schema definitions. This pass
+// adds the incremental delivery RFC's own rules:
+//
+// - `@stream` only applies to list fields.
+// - A `label` is a string literal rather than a variable, since the server
+//   names payloads by it, and it's unique within the document.
+// - `initialCount` isn't negative.
+//
+// Problems are reported on the directive's name (`@` included).
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_compiler::ast::{Definition, Directive, Document, Selection, Value};
+use apollo_compiler::{Node, Schema};
+use std::collections::HashSet;
+
+/// Check every `@defer` and `@stream` in a document block.
+pub(crate) fn incremental_diagnostics(
+    schema: &Schema,
+    ast: &Document,
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    let mut checker = IncrementalChecker {
+        schema,
+        block,
+        labels: HashSet::new(),
+        diagnostics: Vec::new(),
+    };
+    for definition in &ast.definitions {
+        match definition {
+            Definition::OperationDefinition(operation) => {
+                let root = schema.root_operation(operation.operation_type);
+                checker.check_selection_set(&operation.selection_set, root.map(|r| r.as_str()));
+            }
+            Definition::FragmentDefinition(fragment) => {
+                checker.check_selection_set(
+                    &fragment.selection_set,
+                    Some(fragment.type_condition.as_str()),
+                );
+            }
+            _ => {}
+        }
+    }
+    checker.diagnostics
+}
+
+struct IncrementalChecker<'a> {
+    schema: &'a Schema,
+    block: &'a BlockPositions<'a>,
+    /// Labels used so far in the block
+    labels: HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl IncrementalChecker<'_> {
+    fn check_selection_set(&mut self, selection_set: &[Selection], parent: Option<&str>) {
+        let schema = self.schema;
+        for selection in selection_set {
+            match selection {
+                Selection::Field(field) => {
+                    let definition =
+                        parent.and_then(|parent| schema.type_field(parent, &field.name).ok());
+                    if let Some(stream) = field.directives.get("stream") {
+                        self.check_arguments(stream);
+                        if let (Some(parent), Some(definition)) = (parent, definition) {
+                            if !definition.ty.is_list() {
+                                self.report(
+                                    stream,
+                                    format!(
+                                        "`@stream` can only be used on list fields, but `{parent}.{}` returns `{}`",
+                                        field.name, definition.ty
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                    self.check_selection_set(
+                        &field.selection_set,
+                        definition.map(|d| d.ty.inner_named_type().as_str()),
+                    );
+                }
+                Selection::InlineFragment(inline) => {
+                    if let Some(defer) = inline.directives.get("defer") {
+                        self.check_arguments(defer);
+                    }
+                    let parent = inline
+                        .type_condition
+                        .as_ref()
+                        .map(|type_condition| type_condition.as_str())
+                        .or(parent);
+                    self.check_selection_set(&inline.selection_set, parent);
+                }
+                Selection::FragmentSpread(spread) => {
+                    if let Some(defer) = spread.directives.get("defer") {
+                        self.check_arguments(defer);
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_arguments(&mut self, directive: &Node<Directive>) {
+        let name = &directive.name;
+        for argument in &directive.arguments {
+            match (argument.name.as_str(), argument.value.as_ref()) {
+                ("label", Value::Variable(_)) => self.report(
+                    directive,
+                    format!("The `label` of `@{name}` must be a string, not a variable"),
+                ),
+                ("label", Value::String(label)) => {
+                    if !self.labels.insert(label.clone()) {
+                        self.report(
+                            directive,
+                            format!(
+                                "Label \"{label}\" is already used by another `@defer` or `@stream` in this document"
+                            ),
+                        );
+                    }
+                }
+                ("initialCount", Value::Int(count)) if count.as_str().starts_with('-') => self
+                    .report(
+                        directive,
+                        format!("The `initialCount` of `@{name}` can't be negative"),
+                    ),
+                _ => {}
+            }
+        }
+    }
+
+    fn report(&mut self, directive: &Node<Directive>, message: String) {
+        let (Some(span), Some(name)) = (directive.location(), directive.name.location()) else {
+            return;
+        };
+        let mut diagnostic =
+            Diagnostic::error(message, self.block.range(span.offset(), name.end_offset()));
+        diagnostic.code = Some("invalid-incremental-delivery".into());
+        self.diagnostics.push(diagnostic);
+    }
+}
@@ -0,0 +1,246 @@
+// Interface implementation validation
+//
+// Implements the spec's IsValidImplementation rules for every object and
+// interface type that declares interfaces, once all schema files and their
+// extensions are merged:
+//
+// - Every field of the interface is declared, reported on the type's name.
+// - The field's type is the interface field's type or a subtype of it:
+//   non-null where the interface is nullable, an implementing object for an
+//   interface, a member for a union.
+// - Every argument of the interface field is declared with the same type.
+// - Arguments the interface field doesn't have are optional, since a query
+//   through the interface can't provide them.
+//
+// Each problem is reported on the implementing field or argument, with the
+// interface field attached, so the mismatch can be followed to either side.
+// Missing fields and arguments are `missing-interface-field`; the rest are
+// `incompatible-interface-field`.
+// apollo-compiler only reports missing fields, on the whole type; those
+// reports are dropped in favor of these.
+
+use crate::{Diagnostic, DiagnosticRange, Position, RelatedInformation};
+use apollo_compiler::parser::SourceSpan;
+use apollo_compiler::schema::{ExtendedType, FieldDefinition};
+use apollo_compiler::{ast, Name, Schema};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Check every type of `schema` that implements interfaces, grouped by the
+/// URI of the file each problem is reported in.
+pub(crate) fn implementation_diagnostics(schema: &Schema) -> HashMap<Arc<str>, Vec<Diagnostic>> {
+    let mut checker = ImplementationChecker {
+        schema,
+        diagnostics: HashMap::new(),
+    };
+    for ty in schema.types.values() {
+        let (type_name, implements) = match ty {
+            ExtendedType::Object(object) => (&object.name, &object.implements_interfaces),
+            ExtendedType::Interface(interface) => {
+                (&interface.name, &interface.implements_interfaces)
+            }
+            _ => continue,
+        };
+        for interface_name in implements {
+            // Unknown interfaces are reported by apollo-compiler
+            if let Some(ExtendedType::Interface(interface)) =
+                schema.types.get(interface_name.name.as_str())
+            {
+                for interface_field in interface.fields.values() {
+                    checker.check_field(type_name, &interface.name, interface_field);
+                }
+            }
+        }
+    }
+    checker.diagnostics
+}
+
+/// Returns true for apollo-compiler's missing interface field diagnostics,
+/// which `implementation_diagnostics` reports itself with the interface
+/// field attached.
+pub(crate) fn is_superseded_implementation_message(message: &str) -> bool {
+    message.contains("does not satisfy interface") && message.contains("missing field")
+}
+
+struct ImplementationChecker<'a> {
+    schema: &'a Schema,
+    diagnostics: HashMap<Arc<str>, Vec<Diagnostic>>,
+}
+
+impl ImplementationChecker<'_> {
+    fn check_field(
+        &mut self,
+        type_name: &Name,
+        interface: &Name,
+        interface_field: &FieldDefinition,
+    ) {
+        let field_name = &interface_field.name;
+        let interface_site = format!("`{interface}.{field_name}` is defined here");
+
+        let Ok(field) = self.schema.type_field(type_name, field_name) else {
+            self.report(
+                type_name.location(),
+                format!(
+                    "`{type_name}` doesn't declare field `{field_name}: {}` of interface `{interface}`",
+                    interface_field.ty
+                ),
+                "missing-interface-field",
+                interface_field.name.location(),
+                interface_site,
+            );
+            return;
+        };
+
+        if !self.is_valid_implementation_type(&field.ty, &interface_field.ty) {
+            self.report(
+                field.name.location(),
+                format!(
+                    "`{type_name}.{field_name}` returns `{}`, which isn't `{}` or a subtype of it, so it doesn't implement `{interface}.{field_name}`",
+                    field.ty, interface_field.ty
+                ),
+                "incompatible-interface-field",
+                interface_field.name.location(),
+                interface_site.clone(),
+            );
+        }
+
+        for interface_argument in &interface_field.arguments {
+            let argument_name = &interface_argument.name;
+            match field.argument_by_name(argument_name) {
+                None => self.report(
+                    field.name.location(),
+                    format!(
+                        "`{type_name}.{field_name}` doesn't declare argument `{argument_name}: {}` of `{interface}.{field_name}`",
+                        interface_argument.ty
+                    ),
+                    "missing-interface-field",
+                    interface_argument.name.location(),
+                    format!("`{interface}.{field_name}({argument_name}:)` is defined here"),
+                ),
+                Some(argument) if argument.ty != interface_argument.ty => self.report(
+                    argument.name.location(),
+                    format!(
+                        "Argument `{argument_name}` of `{type_name}.{field_name}` has type `{}`, but `{interface}.{field_name}` declares it as `{}`",
+                        argument.ty, interface_argument.ty
+                    ),
+                    "incompatible-interface-field",
+                    interface_argument.name.location(),
+                    format!("`{interface}.{field_name}({argument_name}:)` is defined here"),
+                ),
+                Some(_) => {}
+            }
+        }
+
+        for argument in &field.arguments {
+            let declared_by_interface = interface_field
+                .arguments
+                .iter()
+                .any(|a| a.name == argument.name);
+            if !declared_by_interface && argument.is_required() {
+                self.report(
+                    argument.name.location(),
+                    format!(
+                        "Argument `{}` of `{type_name}.{field_name}` must be optional, since `{interface}.{field_name}` doesn't declare it",
+                        argument.name
+                    ),
+                    "incompatible-interface-field",
+                    interface_field.name.location(),
+                    interface_site.clone(),
+                );
+            }
+        }
+    }
+
+    /// Whether a field of type `ty` can implement an interface field of type
+    /// `implemented`
+    fn is_valid_implementation_type(&self, ty: &ast::Type, implemented: &ast::Type) -> bool {
+        use ast::Type;
+
+        match (ty, implemented) {
+            (Type::NonNullNamed(_) | Type::NonNullList(_), _) => {
+                self.is_valid_implementation_type(&nullable(ty), &nullable(implemented))
+            }
+            (_, Type::NonNullNamed(_) | Type::NonNullList(_)) => false,
+            (Type::List(item), Type::List(implemented_item)) => {
+                self.is_valid_implementation_type(item, implemented_item)
+            }
+            (Type::Named(name), Type::Named(implemented)) => {
+                name == implemented || self.is_subtype(name, implemented)
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `name` is an object or interface implementing `abstract_type`,
+    /// or an object that is a member of it
+    fn is_subtype(&self, name: &str, abstract_type: &str) -> bool {
+        match (
+            self.schema.types.get(name),
+            self.schema.types.get(abstract_type),
+        ) {
+            (Some(ExtendedType::Object(object)), Some(ExtendedType::Interface(_))) => object
+                .implements_interfaces
+                .iter()
+                .any(|i| i.name.as_str() == abstract_type),
+            (Some(ExtendedType::Interface(interface)), Some(ExtendedType::Interface(_))) => {
+                interface
+                    .implements_interfaces
+                    .iter()
+                    .any(|i| i.name.as_str() == abstract_type)
+            }
+            (Some(ExtendedType::Object(_)), Some(ExtendedType::Union(union_))) => {
+                union_.members.iter().any(|m| m.name.as_str() == name)
+            }
+            _ => false,
+        }
+    }
+
+    fn report(
+        &mut self,
+        location: Option<SourceSpan>,
+        message: String,
+        code: &str,
+        related_location: Option<SourceSpan>,
+        related_message: String,
+    ) {
+        let Some((uri, range)) = self.site(location) else {
+            return;
+        };
+        let mut diagnostic = Diagnostic::error(message, range);
+        diagnostic.code = Some(code.into());
+        if let Some((related_uri, related_range)) = self.site(related_location) {
+            diagnostic.related.push(RelatedInformation {
+                uri: related_uri,
+                range: related_range,
+                message: related_message.into(),
+            });
+        }
+        self.diagnostics.entry(uri).or_default().push(diagnostic);
+    }
+
+    /// The file URI and range of a location in the merged schema
+    fn site(&self, location: Option<SourceSpan>) -> Option<(Arc<str>, DiagnosticRange)> {
+        let location = location?;
+        let source_file = self.schema.sources.get(&location.file_id())?;
+        let range = location.line_column_range(&self.schema.sources)?;
+        let position = |line: usize, column: usize| Position {
+            line: line.saturating_sub(1) as u32,
+            character: column.saturating_sub(1) as u32,
+        };
+        Some((
+            Arc::from(source_file.path().to_string_lossy().to_string()),
+            DiagnosticRange::new(
+                position(range.start.line, range.start.column),
+                position(range.end.line, range.end.column),
+            ),
+        ))
+    }
+}
+
+fn nullable(ty: &ast::Type) -> ast::Type {
+    match ty {
+        ast::Type::NonNullNamed(name) => ast::Type::Named(name.clone()),
+        ast::Type::NonNullList(item) => ast::Type::List(item.clone()),
+        _ => ty.clone(),
+    }
+}
@@ -2,32 +2,90 @@
 // This crate provides validation and linting on top of the HIR layer.
 // All validation is query-based for automatic incrementality via Salsa.
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
+mod deprecation_replacements;
 mod diagnostics;
+mod directive_validation;
 mod document_validation;
+mod field_merging;
+mod imports;
+mod incremental_validation;
+mod interface_implementation;
 pub mod lint_integration;
+mod literal_validation;
 pub mod merged_schema;
+mod n_plus_one;
+mod nullability_validation;
+mod operation_printer;
+mod persisted;
+mod pragmas;
 mod project_lints;
+mod scalar_formats;
+mod schema_composition;
+mod spec_edition;
+mod spread_validation;
+mod subscription_validation;
 pub mod validation;
+mod validation_profile;
+mod variable_flow;
 
 pub use diagnostics::*;
 pub use document_validation::validate_document_file;
+pub use field_merging::field_merging_diagnostics;
+pub use imports::relative_import_path;
 pub use merged_schema::{
     merged_schema_diagnostics_for_file, merged_schema_with_diagnostics, DiagnosticsByFile,
     MergedSchemaResult,
 };
+pub use n_plus_one::{n_plus_one_findings, NPlusOneFinding};
+pub use operation_printer::{flatten_operation, print_operation, OperationPrintStyle};
+pub use persisted::{persisted_query_document, persisted_query_hash};
 pub use project_lints::{
     analyze_field_usage, field_usage_for_type, find_unused_fields, find_unused_fragments,
     FieldCoverageReport, FieldUsage, TypeCoverage,
 };
 pub use validation::validate_file;
+pub use validation_profile::VALIDATION_CHECKS;
+pub use variable_flow::{
+    fragment_variable_usages, variable_flow_diagnostics, variable_flows, FragmentVariables,
+    VariableFlow, VariableSite, VariableUsage,
+};
 
 #[salsa::db]
 pub trait GraphQLAnalysisDatabase: graphql_hir::GraphQLHirDatabase {
     fn lint_config(&self) -> Arc<graphql_linter::LintConfig> {
         Arc::new(graphql_linter::LintConfig::default())
     }
+
+    /// Compiled WebAssembly rules declared under `lint.plugins`.
+    fn lint_plugins(&self) -> Arc<graphql_linter::LintPlugins> {
+        Arc::new(graphql_linter::LintPlugins::default())
+    }
+
+    /// Severity profile and per-check overrides for spec validation.
+    fn validation_config(&self) -> Arc<graphql_config::ValidationConfig> {
+        Arc::new(graphql_config::ValidationConfig::default())
+    }
+
+    /// Persisted-query hashes operations are checked against; `None` when the
+    /// project has no allow-list.
+    fn persisted_operations(&self) -> Option<Arc<graphql_config::PersistedOperationAllowList>> {
+        None
+    }
+
+    /// Settings for reporting nested lists that resolve one query per item;
+    /// `None` leaves the check off.
+    fn n_plus_one_config(&self) -> Option<Arc<graphql_config::NPlusOneConfig>> {
+        None
+    }
+
+    /// Formats that string literals of custom scalars are checked against,
+    /// keyed by scalar name.
+    fn scalar_formats(&self) -> Arc<BTreeMap<String, graphql_config::ScalarFormat>> {
+        Arc::new(BTreeMap::new())
+    }
 }
 
 /// Get validation diagnostics for a file, including syntax errors and
@@ -84,12 +142,28 @@ fn syntax_diagnostics(
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         });
     }
+    diagnostics.extend(skipped_extraction_diagnostic(&parse));
 
     Arc::new(diagnostics)
 }
 
+/// Warning for a host-language file that extraction gave up on, so a file
+/// that was never checked doesn't look like a clean one
+fn skipped_extraction_diagnostic(parse: &graphql_syntax::Parse) -> Option<Diagnostic> {
+    parse.skipped_reason().map(|reason| {
+        Diagnostic::with_source_and_code(
+            Severity::Warning,
+            format!("GraphQL in this file was not analyzed: {reason}"),
+            DiagnosticRange::default(),
+            "extract",
+            "extraction-skipped",
+        )
+    })
+}
+
 /// Internal tracked function for validation with project files
 #[salsa::tracked]
 fn file_validation_diagnostics_impl(
@@ -133,8 +207,12 @@ fn file_validation_diagnostics_impl(
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         });
     }
+    diagnostics.extend(skipped_extraction_diagnostic(&parse));
+
+    diagnostics.extend(pragmas::pragma_diagnostics(db, content));
 
     let document_kind = metadata.document_kind(db);
     tracing::debug!(
@@ -154,6 +232,41 @@ fn file_validation_diagnostics_impl(
                 file_uri.as_str(),
             );
             diagnostics.extend(schema_diagnostics);
+
+            let directives = graphql_hir::schema_directives(db, project_files);
+            for doc in parse.documents() {
+                let block = variable_flow::BlockPositions {
+                    source: doc.source,
+                    line_index: doc.line_index(),
+                    origin: doc.origin(),
+                };
+                diagnostics.extend(directive_validation::directive_diagnostics(
+                    directives, doc.tree, &block,
+                ));
+            }
+        }
+
+        // Features newer than the configured spec edition are reported even
+        // against a resolved schema, since the source files are what the
+        // server is built from
+        let edition = db.validation_config().spec;
+        let schema = merged_schema::merged_schema_with_diagnostics(db, project_files).schema;
+        let types = graphql_hir::schema_types(db, project_files);
+        for doc in parse.documents() {
+            let block = variable_flow::BlockPositions {
+                source: doc.source,
+                line_index: doc.line_index(),
+                origin: doc.origin(),
+            };
+            diagnostics.extend(spec_edition::edition_diagnostics(
+                edition,
+                schema.as_deref(),
+                doc.tree,
+                &block,
+            ));
+            diagnostics.extend(deprecation_replacements::replacement_diagnostics(
+                types, doc.tree, &block,
+            ));
         }
     } else if metadata.is_document(db) {
         tracing::debug!("Running document validation");
@@ -163,9 +276,31 @@ fn file_validation_diagnostics_impl(
             "Document validation completed"
         );
         diagnostics.extend(doc_diagnostics.iter().cloned());
+        diagnostics.extend(imports::import_diagnostics(
+            db,
+            content,
+            metadata,
+            project_files,
+        ));
+        diagnostics.extend(persisted::allow_list_diagnostics(
+            db,
+            content,
+            metadata,
+            project_files,
+        ));
+        diagnostics.extend(n_plus_one::n_plus_one_diagnostics(
+            db,
+            content,
+            metadata,
+            project_files,
+        ));
     }
 
-    Arc::new(diagnostics)
+    let config = db.validation_config();
+    Arc::new(validation_profile::apply_validation_config(
+        &config,
+        diagnostics,
+    ))
 }
 
 /// Get all diagnostics for a file (validation + linting)
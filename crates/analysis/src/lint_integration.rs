@@ -1,5 +1,5 @@
 use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Severity};
-use graphql_base_db::{FileContent, FileId, FileMetadata, ProjectFiles};
+use graphql_base_db::{CancellationToken, FileContent, FileId, FileMetadata, ProjectFiles};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -67,6 +67,11 @@ fn lint_file_impl(
         return Arc::new(diagnostics);
     }
 
+    if graphql_syntax::file_pragmas(db, content).disable_lints {
+        tracing::debug!(uri = %uri, "Skipping linting due to disable-lints pragma");
+        return Arc::new(diagnostics);
+    }
+
     let file_id = metadata.file_id(db);
     let document_kind = metadata.document_kind(db);
 
@@ -95,6 +100,8 @@ fn lint_file_impl(
         diagnostics.extend(schema_lints(db, file_id, content, project_files));
     }
 
+    diagnostics.extend(plugin_lints(db, file_id, content, metadata, project_files));
+
     diagnostics.extend(unused_ignore_diagnostics(
         db,
         content,
@@ -146,6 +153,7 @@ fn standalone_document_lints(
             .map_or(Severity::Warning, convert_severity);
         diagnostics.extend(convert_lint_diagnostics(
             db,
+            project_files,
             content,
             lint_diags,
             rule.name(),
@@ -195,6 +203,7 @@ fn document_schema_lints(
             .map_or(Severity::Warning, convert_severity);
         diagnostics.extend(convert_lint_diagnostics(
             db,
+            project_files,
             content,
             lint_diags,
             rule.name(),
@@ -205,6 +214,61 @@ fn document_schema_lints(
     diagnostics
 }
 
+/// Run lint rules loaded from WebAssembly plugins
+///
+/// Plugins the config doesn't mention under `rules` run at `warn`.
+fn plugin_lints(
+    db: &dyn GraphQLAnalysisDatabase,
+    file_id: FileId,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Vec<Diagnostic> {
+    let plugins = db.lint_plugins();
+    if plugins.is_empty() {
+        return Vec::new();
+    }
+    let lint_config = db.lint_config();
+    let mut diagnostics = Vec::new();
+
+    for plugin in plugins.plugins() {
+        let severity = lint_config
+            .get_severity(plugin.name())
+            .unwrap_or(graphql_linter::LintSeverity::Warn);
+        if severity == graphql_linter::LintSeverity::Off {
+            continue;
+        }
+
+        let options = lint_config.get_options(plugin.name());
+        let lint_diags = plugin.check(db, file_id, content, metadata, options);
+        if !lint_diags.is_empty() {
+            tracing::debug!(
+                rule = plugin.name(),
+                count = lint_diags.len(),
+                "Found plugin lint issues"
+            );
+        }
+        let mut converted = convert_lint_diagnostics(
+            db,
+            project_files,
+            content,
+            lint_diags,
+            plugin.name(),
+            convert_severity(severity),
+        );
+        // Plugin rules have no page on the rules site unless they link one
+        let site_url = graphql_linter::rule_doc_url(plugin.name());
+        for diagnostic in &mut converted {
+            if diagnostic.url.as_deref() == Some(site_url.as_str()) {
+                diagnostic.url = None;
+            }
+        }
+        diagnostics.extend(converted);
+    }
+
+    diagnostics
+}
+
 /// Run schema lint rules
 fn schema_lints(
     db: &dyn GraphQLAnalysisDatabase,
@@ -246,6 +310,7 @@ fn schema_lints(
                 .map_or(Severity::Warning, convert_severity);
             diagnostics.extend(convert_lint_diagnostics(
                 db,
+                project_files,
                 content,
                 file_lint_diags.clone(),
                 rule.name(),
@@ -275,50 +340,65 @@ pub fn project_lint_diagnostics(
 }
 
 /// Internal tracked function for project-wide linting
+///
+/// Per-file work runs on the rayon pool: each file's structure is computed
+/// up front so the rules read it from the cache, the enabled rules run side
+/// by side, and their results are converted per file. `salsa::par_map`
+/// keeps input order, so the output doesn't depend on scheduling.
 #[salsa::tracked]
 fn project_lint_diagnostics_impl(
     db: &dyn GraphQLAnalysisDatabase,
     project_files: ProjectFiles,
 ) -> Arc<HashMap<FileId, Vec<Diagnostic>>> {
     let _span = tracing::debug_span!("project_lint_diagnostics").entered();
-    let lint_config = db.lint_config();
-    let mut diagnostics_by_file: HashMap<FileId, Vec<Diagnostic>> = HashMap::new();
-
-    for rule in graphql_linter::project_rules() {
-        let enabled = lint_config.is_enabled(rule.name());
-        if !enabled {
-            tracing::debug!(rule = rule.name(), "Project rule disabled, skipping");
-            continue;
-        }
-
-        let _rule_span = tracing::debug_span!("project_rule", rule_name = rule.name()).entered();
-
-        let options = lint_config.get_options(rule.name());
-        let lint_diags = rule.check(db, project_files, options);
-
-        tracing::debug!(
-            file_count = lint_diags.len(),
-            "Project-wide rule returned diagnostics"
-        );
 
+    let mut by_file: HashMap<FileId, Vec<(usize, Vec<graphql_linter::LintDiagnostic>)>> =
+        HashMap::new();
+    for (rule_index, lint_diags) in run_project_rules(db, project_files) {
         for (file_id, file_lint_diags) in lint_diags {
-            let Some((content, _)) = find_file_content_and_metadata(db, project_files, file_id)
-            else {
-                tracing::warn!(?file_id, "Could not find content for file");
-                continue;
-            };
-
-            let severity = lint_config
-                .get_severity(rule.name())
-                .map_or(Severity::Warning, convert_severity);
-            let converted =
-                convert_lint_diagnostics(db, content, file_lint_diags, rule.name(), severity);
-            diagnostics_by_file
+            by_file
                 .entry(file_id)
                 .or_default()
-                .extend(converted);
+                .push((rule_index, file_lint_diags));
         }
     }
+    let mut by_file: Vec<_> = by_file.into_iter().collect();
+    by_file.sort_unstable_by_key(|(file_id, _)| file_id.as_u32());
+
+    let token = CancellationToken::current();
+    let converted: Vec<Option<(FileId, Vec<Diagnostic>)>> =
+        salsa::par_map(db, by_file, |db, (file_id, rule_diags)| {
+            with_token(token.as_ref(), || {
+                let Some((content, _)) = find_file_content_and_metadata(db, project_files, file_id)
+                else {
+                    tracing::warn!(?file_id, "Could not find content for file");
+                    return None;
+                };
+                if graphql_syntax::file_pragmas(db, content).disable_lints {
+                    return None;
+                }
+
+                let lint_config = db.lint_config();
+                let mut diagnostics = Vec::new();
+                for (rule_index, file_lint_diags) in rule_diags {
+                    let rule_name = graphql_linter::project_rules()[rule_index].name();
+                    let severity = lint_config
+                        .get_severity(rule_name)
+                        .map_or(Severity::Warning, convert_severity);
+                    diagnostics.extend(convert_lint_diagnostics(
+                        db,
+                        project_files,
+                        content,
+                        file_lint_diags,
+                        rule_name,
+                        severity,
+                    ));
+                }
+                Some((file_id, diagnostics))
+            })
+        });
+    let diagnostics_by_file: HashMap<FileId, Vec<Diagnostic>> =
+        converted.into_iter().flatten().collect();
 
     tracing::info!(
         files = diagnostics_by_file.len(),
@@ -328,6 +408,73 @@ fn project_lint_diagnostics_impl(
     Arc::new(diagnostics_by_file)
 }
 
+/// Run every enabled project rule, returning each rule's results tagged
+/// with its index in `graphql_linter::project_rules()`, in registry order.
+fn run_project_rules(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+) -> Vec<(usize, HashMap<FileId, Vec<graphql_linter::LintDiagnostic>>)> {
+    let lint_config = db.lint_config();
+    let enabled: Vec<usize> = graphql_linter::project_rules()
+        .iter()
+        .enumerate()
+        .filter(|(_, rule)| {
+            let enabled = lint_config.is_enabled(rule.name());
+            if !enabled {
+                tracing::debug!(rule = rule.name(), "Project rule disabled, skipping");
+            }
+            enabled
+        })
+        .map(|(index, _)| index)
+        .collect();
+    if enabled.is_empty() {
+        return Vec::new();
+    }
+
+    // Rules walk every file's structure; computing it here spreads the
+    // parsing across threads instead of leaving it to whichever rule runs
+    // first
+    let mut file_ids = graphql_base_db::all_file_ids(db, project_files).to_vec();
+    file_ids.sort_unstable_by_key(|file_id| file_id.as_u32());
+    let token = CancellationToken::current();
+    let _: Vec<()> = salsa::par_map(db, file_ids, |db, file_id| {
+        with_token(token.as_ref(), || {
+            if let Some((content, metadata)) =
+                find_file_content_and_metadata(db, project_files, file_id)
+            {
+                graphql_hir::file_structure(db, file_id, content, metadata);
+            }
+        });
+    });
+
+    salsa::par_map(db, enabled, |db, rule_index| {
+        with_token(token.as_ref(), || {
+            let rule = &graphql_linter::project_rules()[rule_index];
+            let _rule_span =
+                tracing::debug_span!("project_rule", rule_name = rule.name()).entered();
+
+            let lint_config = db.lint_config();
+            let lint_diags = rule.check(db, project_files, lint_config.get_options(rule.name()));
+            tracing::debug!(
+                file_count = lint_diags.len(),
+                "Project-wide rule returned diagnostics"
+            );
+            (rule_index, lint_diags)
+        })
+    })
+}
+
+/// Run `f` under the calling request's cancellation token, if it has one.
+///
+/// The token lives in a thread-local, so work `salsa::par_map` hands to the
+/// rayon pool wouldn't see it and would keep running after `$/cancelRequest`.
+fn with_token<T>(token: Option<&CancellationToken>, f: impl FnOnce() -> T) -> T {
+    match token {
+        Some(token) => token.install(f),
+        None => f(),
+    }
+}
+
 /// Helper to find `FileContent` and `FileMetadata` for a given `FileId` from `ProjectFiles`
 fn find_file_content_and_metadata(
     db: &dyn GraphQLAnalysisDatabase,
@@ -357,7 +504,7 @@ pub fn lint_file_with_fixes(
 
     // Parse and check for errors
     let parse = graphql_syntax::parse(db, content, metadata);
-    if parse.has_errors() {
+    if parse.has_errors() || graphql_syntax::file_pragmas(db, content).disable_lints {
         return all_diagnostics;
     }
 
@@ -419,21 +566,11 @@ pub fn project_lint_diagnostics_with_fixes(
         return HashMap::new();
     };
 
-    let lint_config = db.lint_config();
     let mut diagnostics_by_file: HashMap<FileId, Vec<graphql_linter::LintDiagnostic>> =
         HashMap::new();
 
-    // Get all project rules from registry
-    for rule in graphql_linter::project_rules() {
-        if !lint_config.is_enabled(rule.name()) {
-            continue;
-        }
-
-        // Run the project-wide rule
-        let options = lint_config.get_options(rule.name());
-        let lint_diags = rule.check(db, project_files, options);
-
-        // Merge into result
+    // Merge in registry order
+    for (_, lint_diags) in run_project_rules(db, project_files) {
         for (file_id, file_lint_diags) in lint_diags {
             diagnostics_by_file
                 .entry(file_id)
@@ -445,15 +582,18 @@ pub fn project_lint_diagnostics_with_fixes(
     // Filter suppressed diagnostics per file
     for (file_id, diags) in &mut diagnostics_by_file {
         if let Some((content, _)) = find_file_content_and_metadata(db, project_files, *file_id) {
-            let filtered = filter_suppressed_diagnostics(db, content, std::mem::take(diags));
-            *diags = filtered;
+            let diags_for_file = std::mem::take(diags);
+            if !graphql_syntax::file_pragmas(db, content).disable_lints {
+                *diags = filter_suppressed_diagnostics(db, content, diags_for_file);
+            }
         }
     }
 
     diagnostics_by_file
 }
 
-/// Produce diagnostics for unused `# graphql-analyzer-ignore` comments.
+/// Produce diagnostics for unused `# graphql-analyzer-ignore` and
+/// `# graphql-lint-disable*` comments.
 ///
 /// Collects all raw lint diagnostics (before filtering) to determine which
 /// ignore directives actually suppressed something. Any directive that didn't
@@ -479,7 +619,7 @@ fn unused_ignore_diagnostics(
 
     let collect_line_and_rule = |diag: &graphql_linter::LintDiagnostic| -> (usize, String) {
         if let Some(ref block_source) = diag.span.source {
-            let idx = graphql_syntax::LineIndex::new(block_source);
+            let idx = graphql_syntax::LineIndex::from_shared(block_source.clone());
             let (sl, _) = idx.line_col(diag.span.start);
             (sl, diag.rule.clone())
         } else {
@@ -522,7 +662,7 @@ fn unused_ignore_diagnostics(
                 let (end_line, end_col) = file_line_index.line_col(d.byte_end);
                 vec![Diagnostic {
                     severity: Severity::Warning,
-                    message: "Unused graphql-analyzer-ignore directive".into(),
+                    message: format!("Unused {} directive", d.keyword).into(),
                     range: DiagnosticRange {
                         start: Position {
                             line: start_line as u32,
@@ -541,9 +681,10 @@ fn unused_ignore_diagnostics(
                     help: None,
                     url: None,
                     tags: vec![crate::DiagnosticTag::Unnecessary],
+                    related: Vec::new(),
                 }]
             }
-            graphql_linter::ignore::UnusedIgnore::UnusedRules { rules, .. } => rules
+            graphql_linter::ignore::UnusedIgnore::UnusedRules { directive, rules } => rules
                 .into_iter()
                 .map(|r| {
                     let (start_line, start_col) = file_line_index.line_col(r.byte_offset);
@@ -551,8 +692,8 @@ fn unused_ignore_diagnostics(
                     Diagnostic {
                         severity: Severity::Warning,
                         message: format!(
-                            "Unused rule '{}' in graphql-analyzer-ignore directive",
-                            r.name
+                            "Unused rule '{}' in {} directive",
+                            r.name, directive.keyword
                         )
                         .into(),
                         range: DiagnosticRange {
@@ -573,6 +714,7 @@ fn unused_ignore_diagnostics(
                         help: None,
                         url: None,
                         tags: vec![crate::DiagnosticTag::Unnecessary],
+                        related: Vec::new(),
                     }
                 })
                 .collect(),
@@ -581,7 +723,8 @@ fn unused_ignore_diagnostics(
 }
 
 /// Filter raw `LintDiagnostic`s, removing those suppressed by either
-/// `# graphql-analyzer-ignore` or `# eslint-disable*` directives.
+/// `# graphql-analyzer-ignore`, `# graphql-lint-disable*` or
+/// `# eslint-disable*` directives.
 fn filter_suppressed_diagnostics(
     db: &dyn GraphQLAnalysisDatabase,
     content: FileContent,
@@ -596,7 +739,7 @@ fn filter_suppressed_diagnostics(
         .into_iter()
         .filter(|ld| {
             if let Some(ref block_source) = ld.span.source {
-                let block_line_index = graphql_syntax::LineIndex::new(block_source);
+                let block_line_index = graphql_syntax::LineIndex::from_shared(block_source.clone());
                 let (sl, _) = block_line_index.line_col(ld.span.start);
                 let block_ignores = graphql_linter::ignore::parse_ignore_directives(block_source);
                 let block_suppressions =
@@ -620,11 +763,13 @@ fn filter_suppressed_diagnostics(
 /// (for embedded GraphQL in TS/JS). When block context is present:
 /// - `span.start/end` are relative to `span.source`, not the full file
 /// - We build a `LineIndex` from `span.source` to convert byte offsets to line/column
-/// - We add `span.line_offset` to get the correct position in the original file
+/// - We map through the block's origin in the file, so the first line picks up
+///   the column the block starts at
 ///
 /// For pure GraphQL files (no block context), we use the full file's `LineIndex`.
 fn convert_lint_diagnostics(
     db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
     content: FileContent,
     lint_diags: Vec<graphql_linter::LintDiagnostic>,
     rule_name: &str,
@@ -640,9 +785,10 @@ fn convert_lint_diagnostics(
     lint_diags
         .into_iter()
         .filter_map(|ld| {
-            let (line_offset, start_line, start_col, end_line, end_col, suppressed) =
+            let (origin, start_line, start_col, end_line, end_col, suppressed) =
                 if let Some(ref block_source) = ld.span.source {
-                    let block_line_index = graphql_syntax::LineIndex::new(block_source);
+                    let block_line_index =
+                        graphql_syntax::LineIndex::from_shared(block_source.clone());
                     let (sl, sc) = block_line_index.line_col(ld.span.start);
                     let (el, ec) = block_line_index.line_col(ld.span.end);
                     tracing::trace!(
@@ -664,14 +810,28 @@ fn convert_lint_diagnostics(
                     let suppressed =
                         graphql_linter::ignore::is_suppressed(&block_ignores, sl, rule_name)
                             || block_suppressions.is_suppressed(rule_name, sl as u32 + 1);
-                    (ld.span.line_offset, sl, sc, el, ec, suppressed)
+                    (
+                        file_line_index.block_origin(ld.span.byte_offset),
+                        sl,
+                        sc,
+                        el,
+                        ec,
+                        suppressed,
+                    )
                 } else {
                     let (sl, sc) = file_line_index.line_col(ld.span.start);
                     let (el, ec) = file_line_index.line_col(ld.span.end);
                     let suppressed =
                         graphql_linter::ignore::is_suppressed(&file_ignores, sl, rule_name)
                             || file_suppressions.is_suppressed(rule_name, sl as u32 + 1);
-                    (0u32, sl, sc, el, ec, suppressed)
+                    (
+                        graphql_syntax::BlockOrigin::default(),
+                        sl,
+                        sc,
+                        el,
+                        ec,
+                        suppressed,
+                    )
                 };
 
             if suppressed {
@@ -699,7 +859,8 @@ fn convert_lint_diagnostics(
                 for edit in &f.edits {
                     let (es_line, es_col, ee_line, ee_col) =
                         if let Some(ref block_source) = ld.span.source {
-                            let block_line_index = graphql_syntax::LineIndex::new(block_source);
+                            let block_line_index =
+                                graphql_syntax::LineIndex::from_shared(block_source.clone());
                             let (sl, sc) = block_line_index.line_col(edit.offset_range.start);
                             let (el, ec) = block_line_index.line_col(edit.offset_range.end);
                             (sl, sc, el, ec)
@@ -709,16 +870,7 @@ fn convert_lint_diagnostics(
                             (sl, sc, el, ec)
                         };
                     edits.push(crate::TextEdit {
-                        range: DiagnosticRange {
-                            start: Position {
-                                line: es_line as u32 + ld.span.line_offset,
-                                character: es_col as u32,
-                            },
-                            end: Position {
-                                line: ee_line as u32 + ld.span.line_offset,
-                                character: ee_col as u32,
-                            },
-                        },
+                        range: block_range(origin, (es_line, es_col), (ee_line, ee_col)),
                         new_text: edit.new_text.clone(),
                     });
                 }
@@ -737,19 +889,16 @@ fn convert_lint_diagnostics(
                 })
                 .collect();
 
+            let related = ld
+                .related
+                .iter()
+                .filter_map(|r| convert_related_span(db, project_files, r))
+                .collect();
+
             Some(Diagnostic {
                 severity,
                 message: ld.message.into(),
-                range: DiagnosticRange {
-                    start: Position {
-                        line: start_line as u32 + line_offset,
-                        character: start_col as u32,
-                    },
-                    end: Position {
-                        line: end_line as u32 + line_offset,
-                        character: end_col as u32,
-                    },
-                },
+                range: block_range(origin, (start_line, start_col), (end_line, end_col)),
                 source: "graphql-linter".into(),
                 code: Some(rule_name.to_string().into()),
                 message_id: ld.message_id.map(Into::into),
@@ -769,11 +918,65 @@ fn convert_lint_diagnostics(
                         }
                     })
                     .collect(),
+                related,
             })
         })
         .collect()
 }
 
+/// Resolve a related span to a URI and line/column range. Spans in
+/// embedded blocks are positioned the same way as the diagnostic itself.
+fn convert_related_span(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+    related: &graphql_linter::RelatedSpan,
+) -> Option<crate::RelatedInformation> {
+    let uri = graphql_base_db::uri_for_file_id(db, project_files, related.file_id)?;
+    let span = &related.span;
+    let (content, _) = find_file_content_and_metadata(db, project_files, related.file_id)?;
+    let file_line_index = graphql_syntax::line_index(db, content);
+    let (origin, start, end) = if let Some(ref block_source) = span.source {
+        let block_line_index = graphql_syntax::LineIndex::from_shared(block_source.clone());
+        (
+            file_line_index.block_origin(span.byte_offset),
+            block_line_index.line_col(span.start),
+            block_line_index.line_col(span.end),
+        )
+    } else {
+        (
+            graphql_syntax::BlockOrigin::default(),
+            file_line_index.line_col(span.start),
+            file_line_index.line_col(span.end),
+        )
+    };
+
+    Some(crate::RelatedInformation {
+        uri,
+        range: block_range(origin, start, end),
+        message: related.message.as_str().into(),
+    })
+}
+
+/// A range from block-relative (line, UTF-16 column) pairs, in file coordinates
+fn block_range(
+    origin: graphql_syntax::BlockOrigin,
+    start: (usize, usize),
+    end: (usize, usize),
+) -> DiagnosticRange {
+    let (start_line, start_character) = origin.to_file(start.0 as u32, start.1 as u32);
+    let (end_line, end_character) = origin.to_file(end.0 as u32, end.1 as u32);
+    DiagnosticRange {
+        start: Position {
+            line: start_line,
+            character: start_character,
+        },
+        end: Position {
+            line: end_line,
+            character: end_character,
+        },
+    }
+}
+
 /// Pick a documentation URL for a lint diagnostic, falling back to the
 /// canonical per-rule URL when the rule didn't set one explicitly.
 fn resolve_rule_url(explicit: Option<String>, rule_name: &str) -> String {
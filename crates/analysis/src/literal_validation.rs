@@ -0,0 +1,552 @@
+// Argument validation
+//
+// Checks the arguments of fields and directives: required arguments must be
+// provided, argument names must be defined, and values must fit their input
+// types. Variable default values are checked too. List and object literals
+// are checked element by element, so a bad item in `ids: [1, "two", 3]` is
+// reported on `"two"` rather than on the whole argument.
+//
+// Input coercion follows the spec: a single value is accepted where a list
+// is expected and checked against the item type, but a list is never
+// accepted where a single value is. Variables used in an operation must
+// have a type compatible with the position they're used in; variables
+// inside fragments are left to apollo-compiler, which validates each
+// fragment against the operations that spread it.
+//
+// String literals of custom scalars mapped to a `ScalarFormat` in the config
+// are checked against that format, under their own check so a project can
+// relax them separately.
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::Schema;
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use graphql_config::ScalarFormat;
+use graphql_syntax::edit::significant_end;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// An input type with its list and non-null wrappers
+enum InputType {
+    Named(String),
+    List(Box<InputType>),
+    NonNull(Box<InputType>),
+}
+
+impl InputType {
+    fn from_ast(ty: &apollo_compiler::ast::Type) -> Self {
+        use apollo_compiler::ast::Type;
+        match ty {
+            Type::Named(name) => Self::Named(name.to_string()),
+            Type::NonNullNamed(name) => Self::NonNull(Box::new(Self::Named(name.to_string()))),
+            Type::List(inner) => Self::List(Box::new(Self::from_ast(inner))),
+            Type::NonNullList(inner) => {
+                Self::NonNull(Box::new(Self::List(Box::new(Self::from_ast(inner)))))
+            }
+        }
+    }
+
+    fn from_cst(ty: &cst::Type) -> Option<Self> {
+        match ty {
+            cst::Type::NamedType(named) => Some(Self::Named(named.name()?.text().to_string())),
+            cst::Type::ListType(list) => Some(Self::List(Box::new(Self::from_cst(&list.ty()?)?))),
+            cst::Type::NonNullType(non_null) => {
+                let inner = if let Some(named) = non_null.named_type() {
+                    Self::Named(named.name()?.text().to_string())
+                } else {
+                    let list = non_null.list_type()?;
+                    Self::List(Box::new(Self::from_cst(&list.ty()?)?))
+                };
+                Some(Self::NonNull(Box::new(inner)))
+            }
+        }
+    }
+}
+
+impl fmt::Display for InputType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named(name) => write!(f, "{name}"),
+            Self::List(item) => write!(f, "[{item}]"),
+            Self::NonNull(inner) => write!(f, "{inner}!"),
+        }
+    }
+}
+
+/// Check every argument in a document block.
+///
+/// `dynamic_arg_directives` names directives whose arguments can't be
+/// declared statically (Relay's `@arguments`), so unknown arguments aren't
+/// reported on them. `scalar_formats` maps custom scalars to the format
+/// their string literals must follow.
+pub(crate) fn literal_diagnostics(
+    schema: &Schema,
+    tree: &apollo_parser::SyntaxTree,
+    block: &BlockPositions<'_>,
+    dynamic_arg_directives: &[&str],
+    scalar_formats: &BTreeMap<String, ScalarFormat>,
+) -> Vec<Diagnostic> {
+    let mut checker = LiteralChecker {
+        schema,
+        block,
+        dynamic_arg_directives,
+        scalar_formats,
+        variables: None,
+        diagnostics: Vec::new(),
+    };
+
+    for definition in tree.document().definitions() {
+        match definition {
+            cst::Definition::OperationDefinition(op) => {
+                let mut variables = HashMap::new();
+                if let Some(variable_definitions) = op.variable_definitions() {
+                    for variable_def in variable_definitions.variable_definitions() {
+                        let default = variable_def.default_value().and_then(|d| d.value());
+                        let ty = variable_def.ty().and_then(|ty| InputType::from_cst(&ty));
+                        // Defaults are checked before the operation's
+                        // variables are known, as they can't reference them
+                        checker.variables = None;
+                        if let (Some(value), Some(ty)) = (&default, &ty) {
+                            checker.check_value(value, ty);
+                        }
+                        checker.check_directives(variable_def.directives());
+                        let name = variable_def.variable().and_then(|v| v.name());
+                        if let (Some(name), Some(ty)) = (name, ty) {
+                            let has_default =
+                                default.is_some_and(|d| !matches!(d, cst::Value::NullValue(_)));
+                            variables.insert(name.text().to_string(), (ty, has_default));
+                        }
+                    }
+                }
+                checker.variables = Some(variables);
+                let root = op.operation_kind().root_type_name();
+                checker.check_directives(op.directives());
+                checker.check_selection_set(op.selection_set(), Some(root));
+            }
+            cst::Definition::FragmentDefinition(frag) => {
+                let type_condition = frag
+                    .type_condition()
+                    .and_then(|tc| tc.named_type())
+                    .and_then(|nt| nt.name())
+                    .map(|n| n.text().to_string());
+                checker.variables = None;
+                checker.check_directives(frag.directives());
+                checker.check_selection_set(frag.selection_set(), type_condition.as_deref());
+            }
+            _ => {}
+        }
+    }
+
+    checker.diagnostics
+}
+
+/// Returns true for apollo-compiler value diagnostics that
+/// `literal_diagnostics` reports itself with narrower ranges, so
+/// `validate_file` can drop the duplicates.
+pub(crate) fn is_superseded_literal_message(message: &str) -> bool {
+    message.starts_with("expected value of type")
+        || message.starts_with("int cannot represent")
+        || (message.starts_with("value `") && message.contains("does not exist on"))
+        || (message.starts_with("the required argument") && message.ends_with("is not provided"))
+        || (message.starts_with("the argument `") && message.contains("is not supported by"))
+}
+
+/// Returns true for apollo-compiler's variable type mismatches, which
+/// `literal_diagnostics` reports itself for variables used directly in an
+/// operation.
+pub(crate) fn is_variable_usage_message(message: &str) -> bool {
+    message.starts_with("variable `$") && message.contains("cannot be used for argument")
+}
+
+struct LiteralChecker<'a> {
+    schema: &'a Schema,
+    block: &'a BlockPositions<'a>,
+    dynamic_arg_directives: &'a [&'a str],
+    scalar_formats: &'a BTreeMap<String, ScalarFormat>,
+    /// Declared type of each variable of the operation being checked, and
+    /// whether it has a non-null default. `None` inside fragments.
+    variables: Option<HashMap<String, (InputType, bool)>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl LiteralChecker<'_> {
+    fn check_selection_set(
+        &mut self,
+        selection_set: Option<cst::SelectionSet>,
+        parent: Option<&str>,
+    ) {
+        let Some(selection_set) = selection_set else {
+            return;
+        };
+        let schema = self.schema;
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let field_def = parent.zip(field.name()).and_then(|(parent, name)| {
+                        schema
+                            .type_field(parent, name.text().to_string().as_str())
+                            .ok()
+                    });
+                    if let (Some(field_def), Some(parent), Some(name)) =
+                        (field_def, parent, field.name())
+                    {
+                        self.check_arguments(
+                            field.arguments(),
+                            &field_def.arguments,
+                            &format!("field `{parent}.{}`", field_def.name),
+                            name.syntax(),
+                            true,
+                        );
+                    }
+                    self.check_directives(field.directives());
+                    let field_type = field_def.map(|f| f.ty.inner_named_type().to_string());
+                    self.check_selection_set(field.selection_set(), field_type.as_deref());
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    self.check_directives(spread.directives());
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let type_condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|nt| nt.name())
+                        .map(|n| n.text().to_string());
+                    self.check_directives(inline.directives());
+                    self.check_selection_set(
+                        inline.selection_set(),
+                        type_condition.as_deref().or(parent),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_directives(&mut self, directives: Option<cst::Directives>) {
+        let Some(directives) = directives else {
+            return;
+        };
+        let schema = self.schema;
+        for directive in directives.directives() {
+            let Some(name) = directive.name() else {
+                continue;
+            };
+            let name_text = name.text().to_string();
+            let Some(directive_def) = schema.directive_definitions.get(name_text.as_str()) else {
+                continue;
+            };
+            let report_unknown = !self.dynamic_arg_directives.contains(&name_text.as_str());
+            self.check_arguments(
+                directive.arguments(),
+                &directive_def.arguments,
+                &format!("directive `@{name_text}`"),
+                name.syntax(),
+                report_unknown,
+            );
+        }
+    }
+
+    /// Check the arguments passed to a field or directive against its
+    /// definitions. Missing required arguments are reported on `name`.
+    fn check_arguments(
+        &mut self,
+        arguments: Option<cst::Arguments>,
+        definitions: &[apollo_compiler::Node<apollo_compiler::ast::InputValueDefinition>],
+        target: &str,
+        name: &apollo_parser::SyntaxNode,
+        report_unknown: bool,
+    ) {
+        let mut provided = Vec::new();
+        for argument in arguments.iter().flat_map(cst::Arguments::arguments) {
+            let Some(arg_name) = argument.name() else {
+                continue;
+            };
+            let arg_name_text = arg_name.text().to_string();
+            let Some(arg_def) = definitions
+                .iter()
+                .find(|d| d.name == arg_name_text.as_str())
+            else {
+                if report_unknown {
+                    let range = arg_name.syntax().text_range();
+                    let mut diagnostic = Diagnostic::error(
+                        format!("Unknown argument `{arg_name_text}` on {target}"),
+                        self.block.range(range.start().into(), range.end().into()),
+                    );
+                    diagnostic.code = Some("unknown-argument".into());
+                    self.diagnostics.push(diagnostic);
+                }
+                continue;
+            };
+            provided.push(arg_name_text);
+            if let Some(value) = argument.value() {
+                self.check_input(
+                    &value,
+                    &InputType::from_ast(&arg_def.ty),
+                    arg_def.default_value.is_some(),
+                );
+            }
+        }
+
+        for arg_def in definitions {
+            let required = arg_def.ty.is_non_null() && arg_def.default_value.is_none();
+            if !required || provided.iter().any(|p| arg_def.name == p.as_str()) {
+                continue;
+            }
+            let range = name.text_range();
+            let mut diagnostic = Diagnostic::error(
+                format!(
+                    "Missing required argument `{}: {}` on {target}",
+                    arg_def.name, arg_def.ty
+                ),
+                self.block.range(range.start().into(), range.end().into()),
+            );
+            diagnostic.code = Some("missing-required-argument".into());
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Check a value at a position that may declare a default, which lets a
+    /// nullable variable be used where a non-null value is expected
+    fn check_input(&mut self, value: &cst::Value, expected: &InputType, has_default: bool) {
+        match value {
+            cst::Value::Variable(variable) => self.check_variable(variable, expected, has_default),
+            _ => self.check_value(value, expected),
+        }
+    }
+
+    /// Check a variable's declared type against the type expected where it's
+    /// used (the spec's `IsVariableUsageAllowed`). Undeclared variables are
+    /// reported by the variable flow pass.
+    fn check_variable(
+        &mut self,
+        variable: &cst::Variable,
+        expected: &InputType,
+        location_has_default: bool,
+    ) {
+        let Some(name) = variable.name() else {
+            return;
+        };
+        let name = name.text().to_string();
+        let Some((declared, variable_has_default)) =
+            self.variables.as_ref().and_then(|vars| vars.get(&name))
+        else {
+            return;
+        };
+        let allowed = match (declared, expected) {
+            (InputType::NonNull(_), _) | (_, InputType::Named(_) | InputType::List(_)) => {
+                types_compatible(declared, expected)
+            }
+            (_, InputType::NonNull(inner)) => {
+                (*variable_has_default || location_has_default) && types_compatible(declared, inner)
+            }
+        };
+        if allowed {
+            return;
+        }
+        let message = format!(
+            "Variable `${name}` of type `{declared}` can't be used where `{expected}` is expected"
+        );
+        let range = variable.syntax().text_range();
+        let mut diagnostic = Diagnostic::error(
+            message,
+            self.block.range(range.start().into(), range.end().into()),
+        );
+        diagnostic.code = Some("invalid-value".into());
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn check_value(&mut self, value: &cst::Value, expected: &InputType) {
+        match (value, expected) {
+            (cst::Value::Variable(variable), _) => self.check_variable(variable, expected, false),
+            (cst::Value::NullValue(_), InputType::NonNull(_)) => {
+                self.report(
+                    value,
+                    format!("Expected a value of type `{expected}`, found null"),
+                );
+            }
+            (cst::Value::NullValue(_), _) => {}
+            (_, InputType::NonNull(inner)) => self.check_value(value, inner),
+            (cst::Value::ListValue(list), InputType::List(item)) => {
+                for item_value in list.values() {
+                    self.check_value(&item_value, item);
+                }
+            }
+            // List input coercion: `ids: 1` means `ids: [1]`
+            (_, InputType::List(item)) => self.check_value(value, item),
+            (cst::Value::ListValue(_), InputType::Named(name)) => {
+                self.report(
+                    value,
+                    format!("Expected a single value of type `{name}`, found a list"),
+                );
+            }
+            (_, InputType::Named(name)) => self.check_named(value, name),
+        }
+    }
+
+    fn check_named(&mut self, value: &cst::Value, type_name: &str) {
+        // Unknown types are reported where they're declared
+        let schema = self.schema;
+        let Some(type_def) = schema.types.get(type_name) else {
+            return;
+        };
+        match type_def {
+            ExtendedType::Scalar(_) => {
+                if !scalar_accepts(type_name, value) {
+                    self.report(
+                        value,
+                        format!(
+                            "Expected a value of type `{type_name}`, found {}",
+                            describe(value)
+                        ),
+                    );
+                    return;
+                }
+                let out_of_range = type_name == "Int"
+                    && int_text(value).is_some_and(|text| text.parse::<i32>().is_err());
+                if out_of_range {
+                    self.report(
+                        value,
+                        "Int values must fit in a 32-bit signed integer".to_string(),
+                    );
+                }
+                if let (Some(format), cst::Value::StringValue(string)) =
+                    (self.scalar_formats.get(type_name), value)
+                {
+                    let text = String::from(string);
+                    if !crate::scalar_formats::matches_format(*format, &text) {
+                        self.report_check(
+                            value,
+                            format!(
+                                "Expected {} for `{type_name}`, found \"{text}\"",
+                                format.expected()
+                            ),
+                            "invalid-scalar-format",
+                        );
+                    }
+                }
+            }
+            ExtendedType::Enum(enum_def) => match value {
+                cst::Value::EnumValue(enum_value) => {
+                    let Some(name) = enum_value.name() else {
+                        return;
+                    };
+                    let text = name.text().to_string();
+                    if !enum_def.values.contains_key(text.as_str()) {
+                        self.report(
+                            value,
+                            format!("`{text}` is not a value of enum `{type_name}`"),
+                        );
+                    }
+                }
+                cst::Value::StringValue(_) => self.report(
+                    value,
+                    format!(
+                        "Expected a value of enum `{type_name}`, found a string; enum values are written without quotes"
+                    ),
+                ),
+                _ => self.report(
+                    value,
+                    format!(
+                        "Expected a value of enum `{type_name}`, found {}",
+                        describe(value)
+                    ),
+                ),
+            },
+            ExtendedType::InputObject(input_def) => {
+                let cst::Value::ObjectValue(object) = value else {
+                    self.report(
+                        value,
+                        format!(
+                            "Expected an input object of type `{type_name}`, found {}",
+                            describe(value)
+                        ),
+                    );
+                    return;
+                };
+                // Unknown and missing fields are reported by apollo-compiler
+                for object_field in object.object_fields() {
+                    let field_def = object_field
+                        .name()
+                        .and_then(|name| input_def.fields.get(name.text().to_string().as_str()));
+                    if let (Some(field_value), Some(field_def)) = (object_field.value(), field_def)
+                    {
+                        self.check_input(
+                            &field_value,
+                            &InputType::from_ast(&field_def.ty),
+                            field_def.default_value.is_some(),
+                        );
+                    }
+                }
+            }
+            // Output types in input positions are reported on the definition
+            _ => {}
+        }
+    }
+
+    fn report(&mut self, value: &cst::Value, message: String) {
+        self.report_check(value, message, "invalid-value");
+    }
+
+    fn report_check(&mut self, value: &cst::Value, message: String, check: &'static str) {
+        let start: usize = value.syntax().text_range().start().into();
+        let end = significant_end(self.block.source, value.syntax());
+        let mut diagnostic = Diagnostic::error(message, self.block.range(start, end));
+        diagnostic.code = Some(check.into());
+        self.diagnostics.push(diagnostic);
+    }
+}
+
+/// Whether a variable of type `variable` may be passed where `location` is
+/// expected (the spec's `AreTypesCompatible`)
+fn types_compatible(variable: &InputType, location: &InputType) -> bool {
+    match (variable, location) {
+        (InputType::NonNull(variable), InputType::NonNull(location)) => {
+            types_compatible(variable, location)
+        }
+        (_, InputType::NonNull(_)) => false,
+        (InputType::NonNull(variable), _) => types_compatible(variable, location),
+        (InputType::List(variable), InputType::List(location)) => {
+            types_compatible(variable, location)
+        }
+        (InputType::Named(variable), InputType::Named(location)) => variable == location,
+        _ => false,
+    }
+}
+
+/// Whether a built-in scalar accepts a literal of this kind. Custom scalars
+/// define their own literal coercion, so they accept anything.
+fn scalar_accepts(type_name: &str, value: &cst::Value) -> bool {
+    match type_name {
+        "Int" => matches!(value, cst::Value::IntValue(_)),
+        "Float" => matches!(value, cst::Value::IntValue(_) | cst::Value::FloatValue(_)),
+        "String" => matches!(value, cst::Value::StringValue(_)),
+        "Boolean" => matches!(value, cst::Value::BooleanValue(_)),
+        "ID" => matches!(value, cst::Value::StringValue(_) | cst::Value::IntValue(_)),
+        _ => true,
+    }
+}
+
+fn int_text(value: &cst::Value) -> Option<String> {
+    match value {
+        cst::Value::IntValue(int) => Some(int.int_token()?.text().to_string()),
+        _ => None,
+    }
+}
+
+fn describe(value: &cst::Value) -> String {
+    match value {
+        cst::Value::Variable(_) => "a variable".to_string(),
+        cst::Value::StringValue(_) => "a string".to_string(),
+        cst::Value::FloatValue(_) => "a float".to_string(),
+        cst::Value::IntValue(_) => "an integer".to_string(),
+        cst::Value::BooleanValue(_) => "a boolean".to_string(),
+        cst::Value::NullValue(_) => "null".to_string(),
+        cst::Value::EnumValue(enum_value) => enum_value.name().map_or_else(
+            || "an enum value".to_string(),
+            |n| format!("enum value `{}`", n.text()),
+        ),
+        cst::Value::ListValue(_) => "a list".to_string(),
+        cst::Value::ObjectValue(_) => "an input object".to_string(),
+    }
+}
@@ -2,7 +2,7 @@ use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Seve
 use apollo_compiler::diagnostic::ToCliReport;
 use apollo_compiler::parser::{Parser, SourceOffset};
 use apollo_compiler::validation::DiagnosticList;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Diagnostics grouped by file URI
@@ -53,6 +53,19 @@ fn collect_apollo_diagnostics(errors: &DiagnosticList) -> HashMap<Arc<str>, Vec<
         };
 
         let message: Arc<str> = Arc::from(apollo_diag.error.to_string());
+        // Reported with both locations by the composition pass
+        if crate::schema_composition::is_superseded_collision_message(&message) {
+            continue;
+        }
+        // Reported on the directive name by the directive pass
+        if crate::directive_validation::is_superseded_directive_message(&message) {
+            continue;
+        }
+        // Reported per field, with the interface field attached, by the
+        // implementation pass
+        if crate::interface_implementation::is_superseded_implementation_message(&message) {
+            continue;
+        }
 
         diagnostics_by_file
             .entry(file_uri)
@@ -69,6 +82,7 @@ fn collect_apollo_diagnostics(errors: &DiagnosticList) -> HashMap<Arc<str>, Vec<
                 help: None,
                 url: None,
                 tags: Vec::new(),
+                related: Vec::new(),
             });
     }
 
@@ -84,10 +98,11 @@ fn collect_apollo_diagnostics(errors: &DiagnosticList) -> HashMap<Arc<str>, Vec<
 /// Changing document files will not invalidate this query.
 ///
 /// Validation includes:
-/// - Interface implementation validation (types must implement all interface fields)
+/// - Interface implementation validation (types must implement all interface
+///   fields, with compatible types and arguments)
 /// - Union member validation (union members must be object types)
 /// - Type reference validation
-/// - Duplicate definition detection
+/// - Duplicate definition detection, with the first definition attached
 #[salsa::tracked]
 pub fn merged_schema_with_diagnostics(
     db: &dyn GraphQLAnalysisDatabase,
@@ -101,18 +116,7 @@ pub fn merged_schema_with_diagnostics(
     let resolved_ids = project_files.resolved_schema_file_ids(db).ids(db);
     if !resolved_ids.is_empty() {
         // Include builtins from source schema IDs + resolved files
-        let source_ids = project_files.schema_file_ids(db).ids(db);
-        let mut combined: Vec<graphql_base_db::FileId> = source_ids
-            .iter()
-            .copied()
-            .filter(|fid| {
-                graphql_base_db::file_lookup(db, project_files, *fid).is_some_and(|(_, meta)| {
-                    let uri = meta.uri(db);
-                    uri.as_str().ends_with("schema_builtins.graphql")
-                        || uri.as_str().ends_with("client_builtins.graphql")
-                })
-            })
-            .collect();
+        let mut combined = builtin_schema_ids(db, project_files);
         combined.extend(resolved_ids.iter().copied());
         return build_merged_schema(db, project_files, &combined);
     }
@@ -121,6 +125,80 @@ pub fn merged_schema_with_diagnostics(
     build_merged_schema(db, project_files, &schema_ids)
 }
 
+/// Merge the files of a named schema variant (plus builtins).
+///
+/// Documents select a variant with a `schema-variant` pragma. Returns a result
+/// with no schema when the variant isn't configured or has no files loaded.
+#[salsa::tracked]
+#[allow(clippy::needless_pass_by_value)] // Arc<str> needed for Salsa tracking
+pub fn variant_schema_with_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    variant: Arc<str>,
+) -> MergedSchemaResult {
+    let Some(variant_ids) = db
+        .schema_variant_file_ids()
+        .and_then(|input| input.variants(db).get(&variant).cloned())
+    else {
+        return MergedSchemaResult {
+            schema: None,
+            diagnostics_by_file: Arc::new(HashMap::new()),
+        };
+    };
+
+    let mut combined = builtin_schema_ids(db, project_files);
+    combined.extend(variant_ids.iter().copied());
+    build_merged_schema(db, project_files, &combined)
+}
+
+/// Whether the project configures a schema variant with this name.
+pub fn has_schema_variant(db: &dyn GraphQLAnalysisDatabase, variant: &str) -> bool {
+    db.schema_variant_file_ids()
+        .is_some_and(|input| input.variants(db).contains_key(variant))
+}
+
+/// Whether `uri` is one of the built-in definition files loaded with every
+/// project
+fn is_builtin_uri(uri: &str) -> bool {
+    uri.ends_with("schema_builtins.graphql") || uri.ends_with("client_builtins.graphql")
+}
+
+/// Names of the directives declared by the non-builtin files among
+/// `schema_ids`
+fn declared_directives(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    schema_ids: &[graphql_base_db::FileId],
+) -> HashSet<Arc<str>> {
+    schema_ids
+        .iter()
+        .filter_map(|file_id| {
+            let (content, metadata) = graphql_base_db::file_lookup(db, project_files, *file_id)?;
+            (!is_builtin_uri(metadata.uri(db).as_str()))
+                .then(|| graphql_hir::file_directive_defs(db, *file_id, content, metadata))
+        })
+        .flat_map(|defs| defs.iter().map(|def| def.name.clone()).collect::<Vec<_>>())
+        .collect()
+}
+
+/// Builtin definition files from the source schema list. Alternate schemas
+/// (resolved, variants) are merged on top of these.
+fn builtin_schema_ids(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Vec<graphql_base_db::FileId> {
+    project_files
+        .schema_file_ids(db)
+        .ids(db)
+        .iter()
+        .copied()
+        .filter(|fid| {
+            graphql_base_db::file_lookup(db, project_files, *fid)
+                .is_some_and(|(_, meta)| is_builtin_uri(meta.uri(db).as_str()))
+        })
+        .collect()
+}
+
 /// Build a merged schema from the given file IDs and return validation diagnostics.
 fn build_merged_schema(
     db: &dyn GraphQLAnalysisDatabase,
@@ -132,9 +210,7 @@ fn build_merged_schema(
     // Check if we have any user schema files (excluding builtins)
     let has_user_schema = schema_ids.iter().any(|file_id| {
         if let Some((_, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id) {
-            let uri = metadata.uri(db);
-            !uri.as_str().ends_with("client_builtins.graphql")
-                && !uri.as_str().ends_with("schema_builtins.graphql")
+            !is_builtin_uri(metadata.uri(db).as_str())
         } else {
             false
         }
@@ -149,6 +225,7 @@ fn build_merged_schema(
     }
 
     let mut builder = apollo_compiler::schema::SchemaBuilder::new().adopt_orphan_extensions();
+    let declared = declared_directives(db, project_files, schema_ids);
 
     for file_id in schema_ids {
         let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
@@ -157,7 +234,21 @@ fn build_merged_schema(
         };
         let uri = metadata.uri(db);
 
-        if metadata.requires_extraction(db) {
+        if is_builtin_uri(uri.as_str()) {
+            // Schemas that declare a built-in directive themselves, as
+            // servers supporting `@defer` do, keep their own definition
+            let text = content.text(db);
+            let mut document = apollo_compiler::ast::Document::parse(text.as_ref(), uri.as_str())
+                .unwrap_or_else(|invalid| invalid.partial);
+            document.definitions.retain(|definition| {
+                !matches!(
+                    definition,
+                    apollo_compiler::ast::Definition::DirectiveDefinition(directive)
+                        if declared.contains(directive.name.as_str())
+                )
+            });
+            builder = builder.add_ast(&document);
+        } else if metadata.requires_extraction(db) {
             let parse = graphql_syntax::parse(db, content, metadata);
 
             for doc in parse.documents() {
@@ -176,6 +267,20 @@ fn build_merged_schema(
         }
     }
 
+    let composition =
+        crate::schema_composition::composition_diagnostics(db, project_files, schema_ids);
+    let with_passes = |mut diagnostics_by_file: HashMap<Arc<str>, Vec<Diagnostic>>,
+                       schema: &apollo_compiler::Schema| {
+        let implementation = crate::interface_implementation::implementation_diagnostics(schema);
+        for (uri, diagnostics) in composition.into_iter().chain(implementation) {
+            diagnostics_by_file
+                .entry(uri)
+                .or_default()
+                .extend(diagnostics);
+        }
+        Arc::new(diagnostics_by_file)
+    };
+
     match builder.build() {
         Ok(schema) => {
             // SchemaBuilder::build() is lenient - it succeeds even with validation errors.
@@ -193,9 +298,11 @@ fn build_merged_schema(
                         type_count = valid_schema.types.len(),
                         "Successfully merged and validated schema"
                     );
+                    let schema = valid_schema.into_inner();
+                    let diagnostics_by_file = with_passes(HashMap::new(), &schema);
                     MergedSchemaResult {
-                        schema: Some(Arc::new(valid_schema.into_inner())),
-                        diagnostics_by_file: Arc::new(HashMap::new()),
+                        schema: Some(Arc::new(schema)),
+                        diagnostics_by_file,
                     }
                 }
                 Err(with_errors) => {
@@ -206,10 +313,13 @@ fn build_merged_schema(
                     for apollo_diag in with_errors.errors.iter() {
                         tracing::debug!(error = %apollo_diag.error, "Schema validation error");
                     }
-                    let diagnostics_by_file = collect_apollo_diagnostics(&with_errors.errors);
+                    let diagnostics_by_file = with_passes(
+                        collect_apollo_diagnostics(&with_errors.errors),
+                        &with_errors.partial,
+                    );
                     MergedSchemaResult {
                         schema: Some(Arc::new(with_errors.partial)),
-                        diagnostics_by_file: Arc::new(diagnostics_by_file),
+                        diagnostics_by_file,
                     }
                 }
             }
@@ -222,10 +332,13 @@ fn build_merged_schema(
             for apollo_diag in with_errors.errors.iter() {
                 tracing::debug!(error = %apollo_diag.error, "Schema build error");
             }
-            let diagnostics_by_file = collect_apollo_diagnostics(&with_errors.errors);
+            let diagnostics_by_file = with_passes(
+                collect_apollo_diagnostics(&with_errors.errors),
+                &with_errors.partial,
+            );
             MergedSchemaResult {
                 schema: Some(Arc::new(with_errors.partial)),
-                diagnostics_by_file: Arc::new(diagnostics_by_file),
+                diagnostics_by_file,
             }
         }
     }
@@ -294,6 +407,7 @@ mod tests {
                 help: None,
                 url: None,
                 tags: Vec::new(),
+                related: Vec::new(),
             }],
         );
 
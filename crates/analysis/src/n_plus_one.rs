@@ -0,0 +1,361 @@
+// N+1-prone selection detection
+//
+// A list field selected inside another list usually resolves once per item
+// of the outer list, costing a backend query each unless the server batches
+// it. With `nPlusOne` configured, such nested lists are collected as
+// findings and reported as informational diagnostics when:
+//
+// - The nested field returns a list of objects, interfaces or unions, at
+//   most `maxDepth` fields below the enclosing list.
+// - It isn't paginated: it has no pagination argument, and isn't the
+//   `edges`/`nodes` list of a connection field that has one.
+// - Neither its item type nor its `Type.field` coordinate is allow-listed.
+//
+// Fragment spreads inside a list are followed across files, and what they
+// select is reported on the spread. Nested lists entirely inside a fragment
+// are reported in the fragment itself.
+
+use crate::variable_flow::BlockPositions;
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Severity};
+use apollo_compiler::ast::{self, Selection};
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::{Name, Schema};
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+use graphql_config::NPlusOneConfig;
+use std::sync::Arc;
+
+/// A list field selected inside another list without pagination
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NPlusOneFinding {
+    /// The operation or fragment the selection is in; `None` for an
+    /// anonymous operation
+    pub definition: Option<Arc<str>>,
+    /// Path of the enclosing list field from the definition's root, e.g.
+    /// `users`
+    pub list_path: Arc<str>,
+    /// Path of the nested list field, e.g. `users.posts`
+    pub nested_path: Arc<str>,
+    /// Item type of the nested list
+    pub nested_type: Arc<str>,
+    /// The fragment spread the nested list is selected through, if it isn't
+    /// selected directly
+    pub fragment: Option<Arc<str>>,
+    /// The nested field's name, or the fragment spread
+    pub range: DiagnosticRange,
+}
+
+/// Find the N+1-prone selections of a document file.
+///
+/// Empty unless the project configures `nPlusOne`, or when there's no
+/// schema to resolve field types against.
+#[salsa::tracked]
+pub fn n_plus_one_findings(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Arc<Vec<NPlusOneFinding>> {
+    let Some(config) = db.n_plus_one_config() else {
+        return Arc::new(Vec::new());
+    };
+    let Some(schema) =
+        crate::merged_schema::merged_schema_with_diagnostics(db, project_files).schema
+    else {
+        return Arc::new(Vec::new());
+    };
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let mut findings = Vec::new();
+    for doc in parse.documents() {
+        let block = BlockPositions {
+            source: doc.source,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        };
+        let mut walker = SelectionWalker {
+            db,
+            project_files,
+            schema: &schema,
+            config: &config,
+            block: &block,
+            definition: None,
+            spread: None,
+            spread_stack: Vec::new(),
+            findings: &mut findings,
+        };
+        for definition in &doc.ast.definitions {
+            match definition {
+                ast::Definition::OperationDefinition(operation) => {
+                    let Some(root) = schema.root_operation(operation.operation_type) else {
+                        continue;
+                    };
+                    walker.definition = operation.name.as_ref().map(|name| name.as_str().into());
+                    walker.walk(&operation.selection_set, root, "", None, false);
+                }
+                ast::Definition::FragmentDefinition(fragment) => {
+                    walker.definition = Some(fragment.name.as_str().into());
+                    walker.walk(
+                        &fragment.selection_set,
+                        &fragment.type_condition,
+                        "",
+                        None,
+                        false,
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    Arc::new(findings)
+}
+
+/// Report the N+1-prone selections of a document file.
+pub(crate) fn n_plus_one_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Vec<Diagnostic> {
+    n_plus_one_findings(db, content, metadata, project_files)
+        .iter()
+        .map(|finding| {
+            let selected = match &finding.fragment {
+                Some(fragment) => format!("`...{fragment}` selects `{}`, a list", finding.nested_path),
+                None => format!("`{}` is a list", finding.nested_path),
+            };
+            let mut diagnostic = Diagnostic::with_source_and_code(
+                Severity::Info,
+                format!(
+                    "{selected} fetched for each item of `{}`, which can resolve one query per item (N+1)",
+                    finding.list_path
+                ),
+                finding.range,
+                "performance",
+                "n-plus-one",
+            );
+            diagnostic.help = Some(
+                format!(
+                    "Paginate it, batch its resolver, or add `{}` to `nPlusOne.allowTypes` if it's already batched",
+                    finding.nested_type
+                )
+                .into(),
+            );
+            diagnostic
+        })
+        .collect()
+}
+
+/// The closest list field enclosing a selection set
+struct EnclosingList {
+    path: String,
+    /// Non-list fields between the list and the selection set
+    depth: usize,
+    /// Whether the list is selected in the definition being checked rather
+    /// than in a fragment it spreads
+    local: bool,
+}
+
+struct SelectionWalker<'a> {
+    db: &'a dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+    schema: &'a Schema,
+    config: &'a NPlusOneConfig,
+    block: &'a BlockPositions<'a>,
+    definition: Option<Arc<str>>,
+    /// The outermost fragment spread being followed, which findings inside
+    /// it are reported on
+    spread: Option<(Arc<str>, DiagnosticRange)>,
+    /// Fragments being followed, to stop at cycles
+    spread_stack: Vec<Name>,
+    findings: &'a mut Vec<NPlusOneFinding>,
+}
+
+impl SelectionWalker<'_> {
+    /// Walk a selection set on `parent`. `in_connection` is set below a
+    /// paginated field that isn't a list itself, until the next list: the
+    /// connection's page of results.
+    fn walk(
+        &mut self,
+        selection_set: &[Selection],
+        parent: &str,
+        path: &str,
+        enclosing: Option<&EnclosingList>,
+        in_connection: bool,
+    ) {
+        let schema = self.schema;
+        for selection in selection_set {
+            match selection {
+                Selection::Field(field) => {
+                    let Ok(field_def) = schema.type_field(parent, &field.name) else {
+                        continue;
+                    };
+                    let item_type = field_def.ty.inner_named_type();
+                    if !self.is_composite(item_type) {
+                        continue;
+                    }
+                    let field_path = if path.is_empty() {
+                        field.name.to_string()
+                    } else {
+                        format!("{path}.{}", field.name)
+                    };
+                    let paginated = field.arguments.iter().any(|argument| {
+                        self.config
+                            .pagination_arguments
+                            .iter()
+                            .any(|name| name == argument.name.as_str())
+                    });
+
+                    if !field_def.ty.is_list() {
+                        let deeper = enclosing.map(|list| EnclosingList {
+                            path: list.path.clone(),
+                            depth: list.depth + 1,
+                            local: list.local,
+                        });
+                        self.walk(
+                            &field.selection_set,
+                            item_type,
+                            &field_path,
+                            deeper.as_ref(),
+                            paginated || in_connection,
+                        );
+                        continue;
+                    }
+
+                    if let Some(list) = enclosing {
+                        let coordinate = format!("{parent}.{}", field.name);
+                        let allowed =
+                            self.config.allow_types.iter().any(|allowed| {
+                                allowed == item_type.as_str() || *allowed == coordinate
+                            });
+                        // Inside a spread, only lists enclosing the spread
+                        // count; the fragment's own nesting is reported in
+                        // the fragment
+                        let reportable = list.local || self.spread.is_none();
+                        if !paginated
+                            && !in_connection
+                            && !allowed
+                            && reportable
+                            && list.depth < self.config.max_depth
+                        {
+                            self.report(field, &list.path, &field_path, item_type);
+                        }
+                    }
+
+                    let list = EnclosingList {
+                        path: field_path.clone(),
+                        depth: 0,
+                        local: self.spread.is_none(),
+                    };
+                    self.walk(
+                        &field.selection_set,
+                        item_type,
+                        &field_path,
+                        Some(&list),
+                        false,
+                    );
+                }
+                Selection::InlineFragment(inline) => {
+                    let parent = inline
+                        .type_condition
+                        .as_ref()
+                        .map_or(parent, |type_condition| type_condition.as_str());
+                    self.walk(
+                        &inline.selection_set,
+                        parent,
+                        path,
+                        enclosing,
+                        in_connection,
+                    );
+                }
+                Selection::FragmentSpread(spread) => {
+                    // Only lists selected outside the fragment can make what
+                    // it selects N+1-prone here
+                    if enclosing.is_some_and(|list| list.local) {
+                        self.follow_spread(spread, path, enclosing, in_connection);
+                    }
+                }
+            }
+        }
+    }
+
+    fn follow_spread(
+        &mut self,
+        spread: &ast::FragmentSpread,
+        path: &str,
+        enclosing: Option<&EnclosingList>,
+        in_connection: bool,
+    ) {
+        let name = &spread.fragment_name;
+        if self.spread_stack.contains(name) {
+            return;
+        }
+        let Some(document) =
+            graphql_hir::fragment_ast(self.db, self.project_files, name.as_str().into())
+        else {
+            return;
+        };
+        let Some(fragment) = document
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                ast::Definition::FragmentDefinition(fragment) if fragment.name == *name => {
+                    Some(fragment)
+                }
+                _ => None,
+            })
+        else {
+            return;
+        };
+
+        let outermost = self.spread.is_none();
+        if outermost {
+            let Some(range) = spread
+                .fragment_name
+                .location()
+                .map(|span| self.block.range(span.offset(), span.end_offset()))
+            else {
+                return;
+            };
+            self.spread = Some((name.as_str().into(), range));
+        }
+        self.spread_stack.push(name.clone());
+        self.walk(
+            &fragment.selection_set,
+            &fragment.type_condition,
+            path,
+            enclosing,
+            in_connection,
+        );
+        self.spread_stack.pop();
+        if outermost {
+            self.spread = None;
+        }
+    }
+
+    fn report(&mut self, field: &ast::Field, list_path: &str, nested_path: &str, item_type: &str) {
+        let (fragment, range) = match &self.spread {
+            Some((fragment, range)) => (Some(fragment.clone()), *range),
+            None => {
+                let Some(span) = field.name.location() else {
+                    return;
+                };
+                (None, self.block.range(span.offset(), span.end_offset()))
+            }
+        };
+        self.findings.push(NPlusOneFinding {
+            definition: self.definition.clone(),
+            list_path: list_path.into(),
+            nested_path: nested_path.into(),
+            nested_type: item_type.into(),
+            fragment,
+            range,
+        });
+    }
+
+    fn is_composite(&self, type_name: &str) -> bool {
+        matches!(
+            self.schema.types.get(type_name),
+            Some(ExtendedType::Object(_) | ExtendedType::Interface(_) | ExtendedType::Union(_))
+        )
+    }
+}
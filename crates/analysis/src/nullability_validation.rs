@@ -0,0 +1,157 @@
+// Client-controlled nullability validation
+//
+// With client-controlled nullability on, `field!` and `field?` override a
+// field's nullability in the response. The parser only records where the
+// designators are, so this pass checks how they're used:
+//
+// - A designator must directly follow a field's name or arguments; one after
+//   a directive, fragment spread or selection set is an error.
+// - `!` on a field the schema already makes non-null, or `?` on one it
+//   already makes nullable, changes nothing and is reported as unnecessary,
+//   with a fix that removes it.
+
+use crate::variable_flow::BlockPositions;
+use crate::{CodeFix, Diagnostic, DiagnosticTag, TextEdit};
+use apollo_compiler::Schema;
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use graphql_syntax::{Nullability, NullabilityDesignator};
+use std::collections::HashSet;
+
+/// Check the nullability designators of a document block.
+///
+/// Without a schema, only misplaced designators are reported.
+pub(crate) fn nullability_diagnostics(
+    schema: Option<&Schema>,
+    tree: &apollo_parser::SyntaxTree,
+    designators: &[NullabilityDesignator],
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    if designators.is_empty() {
+        return Vec::new();
+    }
+
+    let mut checker = DesignatorChecker {
+        schema,
+        designators,
+        block,
+        placed: HashSet::new(),
+        diagnostics: Vec::new(),
+    };
+    for definition in tree.document().definitions() {
+        match definition {
+            cst::Definition::OperationDefinition(op) => {
+                let root = op.operation_kind().root_type_name();
+                checker.check_selection_set(op.selection_set(), Some(root));
+            }
+            cst::Definition::FragmentDefinition(frag) => {
+                let type_condition = frag
+                    .type_condition()
+                    .and_then(|tc| tc.named_type())
+                    .and_then(|nt| nt.name())
+                    .map(|n| n.text().to_string());
+                checker.check_selection_set(frag.selection_set(), type_condition.as_deref());
+            }
+            _ => {}
+        }
+    }
+
+    for designator in designators {
+        if checker.placed.contains(&designator.offset) {
+            continue;
+        }
+        let symbol = designator.nullability.symbol();
+        let mut diagnostic = Diagnostic::error(
+            format!("Designator `{symbol}` must directly follow a field name or its arguments"),
+            block.range(designator.offset, designator.offset + 1),
+        );
+        diagnostic.code = Some("misplaced-nullability-designator".into());
+        checker.diagnostics.push(diagnostic);
+    }
+
+    checker.diagnostics
+}
+
+struct DesignatorChecker<'a> {
+    schema: Option<&'a Schema>,
+    designators: &'a [NullabilityDesignator],
+    block: &'a BlockPositions<'a>,
+    /// Offsets of the designators that follow a field
+    placed: HashSet<usize>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DesignatorChecker<'_> {
+    fn check_selection_set(
+        &mut self,
+        selection_set: Option<cst::SelectionSet>,
+        parent: Option<&str>,
+    ) {
+        let Some(selection_set) = selection_set else {
+            return;
+        };
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let field_def = self.schema.zip(parent).zip(field.name()).and_then(
+                        |((schema, parent), name)| {
+                            schema.type_field(parent, name.text().as_str()).ok()
+                        },
+                    );
+                    if let Some(designator) = graphql_syntax::field_designator(
+                        self.block.source,
+                        self.designators,
+                        &field,
+                    ) {
+                        self.placed.insert(designator.offset);
+                        if let (Some(field_def), Some(name)) = (field_def, field.name()) {
+                            self.check_redundant(
+                                designator,
+                                &name.text(),
+                                field_def.ty.is_non_null(),
+                            );
+                        }
+                    }
+                    let field_type = field_def.map(|f| f.ty.inner_named_type().to_string());
+                    self.check_selection_set(field.selection_set(), field_type.as_deref());
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let type_condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|nt| nt.name())
+                        .map(|n| n.text().to_string());
+                    self.check_selection_set(
+                        inline.selection_set(),
+                        type_condition.as_deref().or(parent),
+                    );
+                }
+                cst::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn check_redundant(&mut self, designator: NullabilityDesignator, field: &str, non_null: bool) {
+        let already = match designator.nullability {
+            Nullability::Required if non_null => "non-null",
+            Nullability::Optional if !non_null => "nullable",
+            _ => return,
+        };
+        let symbol = designator.nullability.symbol();
+        let range = self.block.range(designator.offset, designator.offset + 1);
+        let mut diagnostic = Diagnostic::warning(
+            format!("`{field}` is already {already} in the schema, so `{symbol}` has no effect"),
+            range,
+        );
+        diagnostic.code = Some("redundant-nullability-designator".into());
+        diagnostic.tags.push(DiagnosticTag::Unnecessary);
+        diagnostic.fix = Some(CodeFix {
+            label: format!("Remove `{symbol}`"),
+            edits: vec![TextEdit {
+                range,
+                new_text: String::new(),
+            }],
+        });
+        self.diagnostics.push(diagnostic);
+    }
+}
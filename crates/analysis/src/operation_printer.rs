@@ -0,0 +1,326 @@
+// Printing operations the way a client sends them.
+//
+// Persisted-query mismatches are usually a difference in the document text
+// rather than in the operation: a client that strips whitespace, or one that
+// inlines fragments before sending. This prints an operation with its
+// fragments in either style so it can be compared with what went over the
+// wire. Both styles start from apollo-compiler's printer, which keeps
+// selections and arguments in source order.
+//
+// Flattening goes further than inlining, for tooling that can only send a
+// single definition: spreads on the enclosing type are spliced into the
+// selection set, and selections that would merge are printed once.
+
+use std::collections::HashMap;
+
+use apollo_compiler::ast::{
+    Definition, Document, FragmentDefinition, InlineFragment, NamedType, Selection,
+};
+use apollo_compiler::{Node, Schema};
+use apollo_parser::TokenKind;
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+
+use crate::persisted::operation_document;
+use crate::GraphQLAnalysisDatabase;
+
+/// Whitespace style of a printed operation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OperationPrintStyle {
+    /// apollo-compiler's printer: one selection per line, two-space indent
+    #[default]
+    Pretty,
+    /// Every ignored character that can go is removed, like graphql-js's
+    /// `stripIgnoredCharacters`
+    Minified,
+}
+
+/// Print the operation named `operation_name` in a file (or the first
+/// operation when `None`) with its transitive fragments.
+///
+/// With `inline_fragments`, every spread is replaced by an inline fragment
+/// on the fragment's type condition and no fragment definitions are printed.
+/// Returns `None` when the operation doesn't exist.
+pub fn print_operation(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+    operation_name: Option<&str>,
+    style: OperationPrintStyle,
+    inline_fragments: bool,
+) -> Option<String> {
+    let operation_index = operation_index(db, content, metadata, operation_name)?;
+    let mut document = operation_document(db, content, metadata, operation_index, project_files)?;
+    if inline_fragments {
+        document = inline_fragment_spreads(&document);
+    }
+
+    let pretty = document.to_string();
+    Some(match style {
+        OperationPrintStyle::Pretty => pretty,
+        OperationPrintStyle::Minified => minify(&pretty),
+    })
+}
+
+/// Print the operation named `operation_name` in a file (or the first
+/// operation when `None`) as a single self-contained definition.
+///
+/// Fragment spreads are inlined recursively. Those whose type condition is
+/// the enclosing type are spliced into its selection set, and fields (or
+/// inline fragments) that would merge are combined, so each appears once.
+/// Returns `None` when the operation doesn't exist.
+pub fn flatten_operation(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+    operation_name: Option<&str>,
+) -> Option<String> {
+    let operation_index = operation_index(db, content, metadata, operation_name)?;
+    let document = operation_document(db, content, metadata, operation_index, project_files)?;
+    let schema = crate::merged_schema_with_diagnostics(db, project_files).schema;
+
+    let mut flattened = inline_fragment_spreads(&document);
+    for def in &mut flattened.definitions {
+        if let Definition::OperationDefinition(op) = def {
+            let root = schema
+                .as_deref()
+                .and_then(|schema| schema.root_operation(op.operation_type))
+                .cloned();
+            let selection_set =
+                flatten_selections(&op.selection_set, root.as_ref(), schema.as_deref());
+            op.make_mut().selection_set = selection_set;
+        }
+    }
+    Some(flattened.to_string())
+}
+
+/// Index among the file's operations of the one named `operation_name`, or
+/// of the first operation
+fn operation_index(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    operation_name: Option<&str>,
+) -> Option<usize> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    parse
+        .documents()
+        .flat_map(|doc| doc.ast.definitions.iter())
+        .filter_map(|def| match def {
+            Definition::OperationDefinition(op) => Some(op),
+            _ => None,
+        })
+        .position(|op| match operation_name {
+            Some(name) => op.name.as_ref().is_some_and(|n| n.as_str() == name),
+            None => true,
+        })
+}
+
+/// The operation alone, with each fragment spread replaced by the fragment's
+/// selections in an inline fragment. Spreads of unknown fragments, and
+/// spreads that would recurse into a fragment already being inlined, are
+/// left as they are.
+fn inline_fragment_spreads(document: &Document) -> Document {
+    let fragments: HashMap<&str, &Node<FragmentDefinition>> = document
+        .definitions
+        .iter()
+        .filter_map(|def| match def {
+            Definition::FragmentDefinition(frag) => Some((frag.name.as_str(), frag)),
+            _ => None,
+        })
+        .collect();
+
+    let mut inlined = Document::new();
+    for def in &document.definitions {
+        if let Definition::OperationDefinition(op) = def {
+            let mut op = op.clone();
+            let selection_set = inline_selections(&op.selection_set, &fragments, &mut Vec::new());
+            op.make_mut().selection_set = selection_set;
+            inlined
+                .definitions
+                .push(Definition::OperationDefinition(op));
+        }
+    }
+    inlined
+}
+
+fn inline_selections<'a>(
+    selections: &[Selection],
+    fragments: &HashMap<&'a str, &'a Node<FragmentDefinition>>,
+    stack: &mut Vec<&'a str>,
+) -> Vec<Selection> {
+    selections
+        .iter()
+        .map(|selection| match selection {
+            Selection::Field(field) => {
+                let mut field = field.clone();
+                if !field.selection_set.is_empty() {
+                    let nested = inline_selections(&field.selection_set, fragments, stack);
+                    field.make_mut().selection_set = nested;
+                }
+                Selection::Field(field)
+            }
+            Selection::InlineFragment(inline) => {
+                let mut inline = inline.clone();
+                let nested = inline_selections(&inline.selection_set, fragments, stack);
+                inline.make_mut().selection_set = nested;
+                Selection::InlineFragment(inline)
+            }
+            Selection::FragmentSpread(spread) => {
+                let Some((&name, fragment)) =
+                    fragments.get_key_value(spread.fragment_name.as_str())
+                else {
+                    return selection.clone();
+                };
+                if stack.contains(&name) {
+                    return selection.clone();
+                }
+                stack.push(name);
+                let nested = inline_selections(&fragment.selection_set, fragments, stack);
+                stack.pop();
+                Selection::InlineFragment(Node::new(InlineFragment {
+                    type_condition: Some(fragment.type_condition.clone()),
+                    directives: spread.directives.clone(),
+                    selection_set: nested,
+                }))
+            }
+        })
+        .collect()
+}
+
+/// Merge `selections` made on `parent` (when the schema knows it) so that
+/// each field and inline fragment appears once.
+fn flatten_selections(
+    selections: &[Selection],
+    parent: Option<&NamedType>,
+    schema: Option<&Schema>,
+) -> Vec<Selection> {
+    let mut merged = Vec::new();
+    for selection in selections {
+        merge_selection(&mut merged, selection, parent, schema);
+    }
+    merged
+}
+
+fn merge_selection(
+    merged: &mut Vec<Selection>,
+    selection: &Selection,
+    parent: Option<&NamedType>,
+    schema: Option<&Schema>,
+) {
+    match selection {
+        Selection::Field(field) => {
+            let field_type = parent
+                .zip(schema)
+                .and_then(|(parent, schema)| schema.type_field(parent, &field.name).ok())
+                .map(|definition| definition.ty.inner_named_type().clone());
+            let existing = merged.iter_mut().find_map(|selection| match selection {
+                Selection::Field(other)
+                    if other.response_key() == field.response_key()
+                        && other.name == field.name
+                        && other.arguments == field.arguments
+                        && other.directives == field.directives =>
+                {
+                    Some(other)
+                }
+                _ => None,
+            });
+            if let Some(existing) = existing {
+                let combined = [existing.selection_set.as_slice(), &field.selection_set].concat();
+                existing.make_mut().selection_set =
+                    flatten_selections(&combined, field_type.as_ref(), schema);
+            } else {
+                let mut field = field.clone();
+                if !field.selection_set.is_empty() {
+                    let nested =
+                        flatten_selections(&field.selection_set, field_type.as_ref(), schema);
+                    field.make_mut().selection_set = nested;
+                }
+                merged.push(Selection::Field(field));
+            }
+        }
+        // A fragment on the enclosing type adds its selections directly
+        Selection::InlineFragment(inline)
+            if inline.directives.is_empty()
+                && inline
+                    .type_condition
+                    .as_ref()
+                    .is_none_or(|condition| Some(condition) == parent) =>
+        {
+            for nested in &inline.selection_set {
+                merge_selection(merged, nested, parent, schema);
+            }
+        }
+        Selection::InlineFragment(inline) => {
+            let condition = inline.type_condition.as_ref().or(parent).cloned();
+            let existing = merged.iter_mut().find_map(|selection| match selection {
+                Selection::InlineFragment(other)
+                    if other.type_condition == inline.type_condition
+                        && other.directives == inline.directives =>
+                {
+                    Some(other)
+                }
+                _ => None,
+            });
+            if let Some(existing) = existing {
+                let combined = [existing.selection_set.as_slice(), &inline.selection_set].concat();
+                existing.make_mut().selection_set =
+                    flatten_selections(&combined, condition.as_ref(), schema);
+            } else {
+                let mut inline = inline.clone();
+                let nested = flatten_selections(&inline.selection_set, condition.as_ref(), schema);
+                inline.make_mut().selection_set = nested;
+                merged.push(Selection::InlineFragment(inline));
+            }
+        }
+        // Spreads of unknown or recursive fragments are left in place
+        Selection::FragmentSpread(_) => {
+            if !merged.contains(selection) {
+                merged.push(selection.clone());
+            }
+        }
+    }
+}
+
+/// Remove ignored characters, keeping a single space only between two
+/// tokens that would otherwise run together
+fn minify(document: &str) -> String {
+    let (tokens, _errors) = apollo_parser::Lexer::new(document).lex();
+    let mut out = String::with_capacity(document.len());
+    let mut last_was_word = false;
+
+    for token in tokens {
+        let kind = token.kind();
+        if matches!(
+            kind,
+            TokenKind::Whitespace | TokenKind::Comment | TokenKind::Comma | TokenKind::Eof
+        ) {
+            continue;
+        }
+        let is_word = matches!(
+            kind,
+            TokenKind::Name | TokenKind::StringValue | TokenKind::Int | TokenKind::Float
+        );
+        if is_word && last_was_word {
+            out.push(' ');
+        }
+        out.push_str(token.data());
+        last_was_word = is_word;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_minify_keeps_only_required_spaces() {
+        let pretty = "query GetUser($id: ID!, $size: Int = 10) {\n  user(id: $id) {\n    ...UserFields\n    avatar(size: $size) @include(if: true)\n  }\n}\n";
+        assert_eq!(
+            minify(pretty),
+            "query GetUser($id:ID!$size:Int=10){user(id:$id){...UserFields avatar(size:$size)@include(if:true)}}"
+        );
+    }
+}
@@ -0,0 +1,173 @@
+// Persisted-query documents and hashes.
+//
+// A persisted query is identified by the SHA-256 of the document the client
+// sends: the operation plus every fragment it spreads. Editors, build tools
+// and servers all format that document differently, so we hash a normalized
+// form instead of the source text: apollo-compiler's printer, with fragments
+// in name order after the operation. Whitespace, comments and fragment
+// placement in the source don't affect the hash.
+//
+// With an allow-list configured, operations whose hash isn't in it are
+// reported, so changes that would be rejected by a persisted-only server
+// show up before they ship.
+
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Severity};
+use apollo_compiler::ast::{Definition, Document};
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+/// The normalized document for the `operation_index`-th operation in a file,
+/// including its transitive fragment dependencies.
+///
+/// Fragments that can't be resolved are left out; the operation won't
+/// validate until they exist anyway.
+#[salsa::tracked]
+pub fn persisted_query_document(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    operation_index: usize,
+    project_files: ProjectFiles,
+) -> Option<Arc<str>> {
+    operation_document(db, content, metadata, operation_index, project_files)
+        .map(|document| Arc::from(document.to_string()))
+}
+
+/// The `operation_index`-th operation in a file followed by its transitive
+/// fragments in name order.
+pub(crate) fn operation_document(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    operation_index: usize,
+    project_files: ProjectFiles,
+) -> Option<Document> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let operation = parse
+        .documents()
+        .flat_map(|doc| doc.ast.definitions.iter())
+        .filter(|def| matches!(def, Definition::OperationDefinition(_)))
+        .nth(operation_index)?
+        .clone();
+
+    let spreads_index = graphql_hir::fragment_spreads_index(db, project_files);
+    let body = graphql_hir::operation_body(db, content, metadata, operation_index);
+    let mut fragment_names: BTreeSet<Arc<str>> = BTreeSet::new();
+    let mut pending: Vec<Arc<str>> = body.fragment_spreads.iter().cloned().collect();
+    while let Some(name) = pending.pop() {
+        if fragment_names.insert(name.clone()) {
+            if let Some(spreads) = spreads_index.get(&name) {
+                pending.extend(spreads.iter().cloned());
+            }
+        }
+    }
+
+    let mut document = Document::new();
+    document.definitions.push(operation);
+    for name in fragment_names {
+        let Some(fragment_ast) = graphql_hir::fragment_ast(db, project_files, name.clone()) else {
+            continue;
+        };
+        let fragment = fragment_ast.definitions.iter().find(|def| {
+            matches!(def, Definition::FragmentDefinition(frag) if frag.name.as_str() == &*name)
+        });
+        if let Some(fragment) = fragment {
+            document.definitions.push(fragment.clone());
+        }
+    }
+
+    Some(document)
+}
+
+/// Lowercase hex SHA-256 of a persisted-query document, as used by
+/// automatic persisted queries and operation safelists.
+#[must_use]
+pub fn persisted_query_hash(document: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(document.as_bytes()))
+}
+
+/// Report the operations in a file that aren't on the project's persisted
+/// operation allow-list.
+pub(crate) fn allow_list_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Vec<Diagnostic> {
+    let Some(allow_list) = db.persisted_operations() else {
+        return Vec::new();
+    };
+    let structure = graphql_hir::file_structure(db, metadata.file_id(db), content, metadata);
+    let file_line_index = graphql_syntax::line_index(db, content);
+
+    structure
+        .operations
+        .iter()
+        .filter_map(|operation| {
+            let document = persisted_query_document(
+                db,
+                content,
+                metadata,
+                operation.index,
+                project_files,
+            )?;
+            let hash = persisted_query_hash(&document);
+            if allow_list.contains(&hash) {
+                return None;
+            }
+
+            let range = operation
+                .name_range
+                .unwrap_or_else(|| text_size::TextRange::empty(operation.operation_range.start()));
+            let block_line_index = operation
+                .block_source
+                .clone()
+                .map(graphql_syntax::LineIndex::from_shared);
+            let line_index = block_line_index.as_ref().unwrap_or(&*file_line_index);
+            let origin = operation.block_byte_offset.map_or_else(
+                graphql_syntax::BlockOrigin::default,
+                |offset| file_line_index.block_origin(offset),
+            );
+            let position = |offset: text_size::TextSize| {
+                let (line, character) = line_index.line_col(offset.into());
+                let (line, character) = origin.to_file(line as u32, character as u32);
+                Position { line, character }
+            };
+            let label = operation.name.as_deref().map_or_else(
+                || "Anonymous operation".to_string(),
+                |name| format!("Operation \"{name}\""),
+            );
+
+            let mut diagnostic = Diagnostic::with_source_and_code(
+                Severity::Info,
+                format!("{label} is not in the persisted operation allow-list"),
+                DiagnosticRange::new(position(range.start()), position(range.end())),
+                "persisted",
+                "not-persisted",
+            );
+            diagnostic.help = Some(
+                format!(
+                    "Its hash is {hash}; run `graphql persisted-operations` to regenerate the allow-list"
+                )
+                .into(),
+            );
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persisted_query_hash_is_sha256_hex() {
+        assert_eq!(
+            persisted_query_hash(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(persisted_query_hash("{ a }").len(), 64);
+    }
+}
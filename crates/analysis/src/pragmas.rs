@@ -0,0 +1,66 @@
+// File-level pragma diagnostics.
+//
+// Pragmas themselves are parsed in `graphql-syntax`; this reports the ones
+// that can't take effect so a typo doesn't silently leave a file on the
+// default behaviour.
+
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Severity};
+use graphql_base_db::FileContent;
+use graphql_syntax::PragmaValue;
+
+/// Warn about unrecognized pragmas and unknown schema variants.
+pub(crate) fn pragma_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+) -> Vec<Diagnostic> {
+    let pragmas = graphql_syntax::file_pragmas(db, content);
+    let line_index = graphql_syntax::line_index(db, content);
+    let range_of = |value: &PragmaValue| {
+        let (start_line, start_col) = line_index.line_col(value.start);
+        let (end_line, end_col) = line_index.line_col(value.end);
+        DiagnosticRange {
+            start: Position {
+                line: start_line as u32,
+                character: start_col as u32,
+            },
+            end: Position {
+                line: end_line as u32,
+                character: end_col as u32,
+            },
+        }
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = pragmas
+        .unknown
+        .iter()
+        .map(|value| {
+            Diagnostic::with_source_and_code(
+                Severity::Warning,
+                format!(
+                    "Unknown graphql-analyzer pragma `{}`; expected `disable-lints` or `schema-variant <name>`",
+                    value.value
+                ),
+                range_of(value),
+                "pragma",
+                "unknown-pragma",
+            )
+        })
+        .collect();
+
+    if let Some(variant) = &pragmas.schema_variant {
+        if !crate::merged_schema::has_schema_variant(db, &variant.value) {
+            diagnostics.push(Diagnostic::with_source_and_code(
+                Severity::Warning,
+                format!(
+                    "Unknown schema variant `{}`; add it under `schemaVariants` in the project config. Validating against the project schema instead",
+                    variant.value
+                ),
+                range_of(variant),
+                "pragma",
+                "unknown-schema-variant",
+            ));
+        }
+    }
+
+    diagnostics
+}
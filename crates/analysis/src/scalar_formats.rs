@@ -0,0 +1,232 @@
+// Literal formats of well-known custom scalars
+//
+// A custom scalar accepts any literal as far as the spec is concerned, so
+// `createdAt: "yesterday"` only fails once the server parses it. Projects
+// map scalars to a `ScalarFormat` under `scalars` in their config, and
+// `literal_diagnostics` checks string literals of those scalars with
+// `matches_format`. Each format is a hand-written check of the common
+// textual form rather than a full parser: anything it accepts should be
+// accepted by a server, not the other way around.
+
+use graphql_config::ScalarFormat;
+
+/// Whether `value` is a valid literal for a scalar with this format
+pub(crate) fn matches_format(format: ScalarFormat, value: &str) -> bool {
+    match format {
+        ScalarFormat::DateTime => is_date_time(value),
+        ScalarFormat::Uuid => is_uuid(value),
+        ScalarFormat::Url => is_url(value),
+        ScalarFormat::Email => is_email(value),
+    }
+}
+
+/// RFC 3339 `date-time`: `2024-01-15T09:30:00Z`, with optional fractional
+/// seconds and a `Z` or `±hh:mm` offset
+fn is_date_time(value: &str) -> bool {
+    let Some((date, time)) = value.split_once(['T', 't']) else {
+        return false;
+    };
+    is_full_date(date) && is_full_time(time)
+}
+
+fn is_full_date(date: &str) -> bool {
+    let b = date.as_bytes();
+    if b.len() != 10 || b[4] != b'-' || b[7] != b'-' {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) =
+        (digits(&b[0..4]), digits(&b[5..7]), digits(&b[8..10]))
+    else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+fn is_full_time(time: &str) -> bool {
+    let b = time.as_bytes();
+    if b.len() < 9 || b[2] != b':' || b[5] != b':' {
+        return false;
+    }
+    let (Some(hour), Some(minute), Some(second)) =
+        (digits(&b[0..2]), digits(&b[3..5]), digits(&b[6..8]))
+    else {
+        return false;
+    };
+    // 60 allows for leap seconds
+    if hour > 23 || minute > 59 || second > 60 {
+        return false;
+    }
+
+    let mut rest = &b[8..];
+    if let Some(fraction) = rest.strip_prefix(b".") {
+        let len = fraction.iter().take_while(|c| c.is_ascii_digit()).count();
+        if len == 0 {
+            return false;
+        }
+        rest = &fraction[len..];
+    }
+    match rest {
+        [b'Z' | b'z'] => true,
+        [b'+' | b'-', offset @ ..] => {
+            offset.len() == 5
+                && offset[2] == b':'
+                && digits(&offset[0..2]).is_some_and(|h| h <= 23)
+                && digits(&offset[3..5]).is_some_and(|m| m <= 59)
+        }
+        _ => false,
+    }
+}
+
+/// The value of a run of ASCII digits
+fn digits(bytes: &[u8]) -> Option<u32> {
+    bytes.iter().try_fold(0u32, |n, c| {
+        c.is_ascii_digit().then(|| n * 10 + u32::from(c - b'0'))
+    })
+}
+
+/// `123e4567-e89b-12d3-a456-426614174000`, in either case
+fn is_uuid(value: &str) -> bool {
+    let groups: Vec<&str> = value.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.bytes().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// An absolute URL: a scheme, then a non-empty remainder without spaces.
+/// A remainder starting with `//` needs a host.
+fn is_url(value: &str) -> bool {
+    let Some((scheme, rest)) = value.split_once(':') else {
+        return false;
+    };
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    if !valid_scheme || rest.is_empty() || rest.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return false;
+    }
+    match rest.strip_prefix("//") {
+        Some(authority) => authority
+            .split(['/', '?', '#'])
+            .next()
+            .is_some_and(|host| !host.is_empty()),
+        None => true,
+    }
+}
+
+/// `local@domain.tld`, where the domain is made of dot-separated labels of
+/// letters, digits and inner hyphens
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.rsplit_once('@') else {
+        return false;
+    };
+    let valid_local = !local.is_empty()
+        && !local.starts_with('.')
+        && !local.ends_with('.')
+        && !local.contains("..")
+        && local
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~.".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid_domain = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    valid_local && valid_domain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_time() {
+        for valid in [
+            "2024-01-15T09:30:00Z",
+            "2024-02-29T23:59:60.123+05:30",
+            "1999-12-31t00:00:00-08:00",
+        ] {
+            assert!(matches_format(ScalarFormat::DateTime, valid), "{valid}");
+        }
+        for invalid in [
+            "2024-01-15",
+            "2023-02-29T00:00:00Z",
+            "2024-13-01T00:00:00Z",
+            "2024-01-15T24:00:00Z",
+            "2024-01-15T09:30:00",
+            "2024-01-15T09:30:00.Z",
+            "2024-01-15 09:30:00Z",
+        ] {
+            assert!(
+                !matches_format(ScalarFormat::DateTime, invalid),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_uuid() {
+        assert!(matches_format(
+            ScalarFormat::Uuid,
+            "123E4567-e89b-12d3-a456-426614174000"
+        ));
+        assert!(!matches_format(
+            ScalarFormat::Uuid,
+            "123e4567e89b12d3a456426614174000"
+        ));
+        assert!(!matches_format(
+            ScalarFormat::Uuid,
+            "123e4567-e89b-12d3-a456-42661417400g"
+        ));
+    }
+
+    #[test]
+    fn test_url() {
+        for valid in [
+            "https://example.com/avatar.png?size=2",
+            "mailto:ada@example.com",
+            "urn:isbn:0451450523",
+        ] {
+            assert!(matches_format(ScalarFormat::Url, valid), "{valid}");
+        }
+        for invalid in [
+            "example.com",
+            "https://",
+            "https://example.com/a b",
+            "1http://x",
+        ] {
+            assert!(!matches_format(ScalarFormat::Url, invalid), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_email() {
+        assert!(matches_format(
+            ScalarFormat::Email,
+            "ada.lovelace+work@example.co.uk"
+        ));
+        for invalid in [
+            "ada",
+            "ada@example",
+            "@example.com",
+            "ada@-example.com",
+            "a..b@example.com",
+        ] {
+            assert!(!matches_format(ScalarFormat::Email, invalid), "{invalid}");
+        }
+    }
+}
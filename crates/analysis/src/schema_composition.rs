@@ -0,0 +1,262 @@
+// Schema composition checks
+//
+// A project's schema is merged from every schema file, so a type defined in
+// two files or a field added twice through `extend` only shows up once they
+// are combined. apollo-compiler reports these collisions at the second site
+// alone; this pass reports them with the first definition attached, so the
+// conflict can be followed from either file.
+//
+// Definitions are visited before extensions, in merge order, which is the
+// order the schema builder applies them in.
+
+use crate::variable_flow::BlockPositions;
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, RelatedInformation};
+use apollo_compiler::{ast, Node};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A place a type or member is defined
+struct Site {
+    uri: Arc<str>,
+    range: DiagnosticRange,
+}
+
+/// What has been merged into a type so far
+struct MergedType {
+    definition: Site,
+    /// Members by kind (`Field`, `Value`, `Member`) and name
+    members: HashMap<(&'static str, String), Site>,
+}
+
+/// A named thing declared by a definition or extension
+struct Declared<'a> {
+    name: &'a str,
+    location: Option<apollo_compiler::parser::SourceSpan>,
+}
+
+/// Report types defined more than once across `schema_ids`, and members
+/// declared more than once once a type's extensions are merged into it.
+pub(crate) fn composition_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    schema_ids: &[graphql_base_db::FileId],
+) -> HashMap<Arc<str>, Vec<Diagnostic>> {
+    let parses: Vec<(Arc<str>, graphql_syntax::Parse)> = schema_ids
+        .iter()
+        .filter_map(|file_id| {
+            let (content, metadata) = graphql_base_db::file_lookup(db, project_files, *file_id)?;
+            Some((
+                Arc::from(metadata.uri(db).as_str()),
+                graphql_syntax::parse(db, content, metadata),
+            ))
+        })
+        .collect();
+
+    let mut types: HashMap<String, MergedType> = HashMap::new();
+    let mut diagnostics: HashMap<Arc<str>, Vec<Diagnostic>> = HashMap::new();
+
+    for extensions in [false, true] {
+        for (uri, parse) in &parses {
+            for doc in parse.documents() {
+                let block = BlockPositions {
+                    source: doc.source,
+                    line_index: doc.line_index(),
+                    origin: doc.origin(),
+                };
+                for definition in &doc.ast.definitions {
+                    let Some(TypeDeclaration {
+                        name: type_name,
+                        keyword,
+                        is_extension,
+                        members,
+                    }) = type_declaration(definition)
+                    else {
+                        continue;
+                    };
+                    if is_extension != extensions {
+                        continue;
+                    }
+                    let site = Site {
+                        uri: uri.clone(),
+                        range: range_of(&block, type_name.location),
+                    };
+
+                    if !is_extension {
+                        if let Some(existing) = types.get(type_name.name) {
+                            let mut diagnostic = Diagnostic::error(
+                                format!(
+                                    "Type `{0}` is already defined; use `extend {keyword} {0}` to add to it",
+                                    type_name.name
+                                ),
+                                site.range,
+                            );
+                            diagnostic.code = Some("duplicate-type".into());
+                            diagnostic.related.push(related(
+                                &existing.definition,
+                                format!("`{}` is first defined here", type_name.name),
+                            ));
+                            diagnostics.entry(site.uri).or_default().push(diagnostic);
+                            // The schema keeps the first definition, so this
+                            // one's members aren't merged
+                            continue;
+                        }
+                    }
+                    // Orphan extensions are adopted as the type's definition
+                    let merged =
+                        types
+                            .entry(type_name.name.to_string())
+                            .or_insert_with(|| MergedType {
+                                definition: site,
+                                members: HashMap::new(),
+                            });
+
+                    for (label, member) in members {
+                        let member_site = Site {
+                            uri: uri.clone(),
+                            range: range_of(&block, member.location),
+                        };
+                        let key = (label, member.name.to_string());
+                        if let Some(previous) = merged.members.get(&key) {
+                            let verb = if label == "Member" {
+                                "part of"
+                            } else {
+                                "defined on"
+                            };
+                            let mut diagnostic = Diagnostic::error(
+                                format!(
+                                    "{label} `{}` is already {verb} `{}`",
+                                    member.name, type_name.name
+                                ),
+                                member_site.range,
+                            );
+                            diagnostic.code = Some("duplicate-member".into());
+                            diagnostic.related.push(related(
+                                previous,
+                                format!("`{}` is first declared here", member.name),
+                            ));
+                            diagnostics
+                                .entry(member_site.uri)
+                                .or_default()
+                                .push(diagnostic);
+                        } else {
+                            merged.members.insert(key, member_site);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Returns true for apollo-compiler build errors that `composition_diagnostics`
+/// reports itself (with the first definition attached), so the merge can drop
+/// the duplicates.
+pub(crate) fn is_superseded_collision_message(message: &str) -> bool {
+    (message.starts_with("the type `")
+        && message.ends_with("is defined multiple times in the schema"))
+        || (message.starts_with("duplicate definitions for the `")
+            && (message.contains(" field of ")
+                || message.contains(" value of enum type ")
+                || message.contains(" member of union type ")))
+}
+
+/// The type a definition declares or extends, and the members it adds
+struct TypeDeclaration<'a> {
+    name: Declared<'a>,
+    /// Keyword that extends the type (`type`, `enum`, ...)
+    keyword: &'static str,
+    is_extension: bool,
+    members: Vec<(&'static str, Declared<'a>)>,
+}
+
+fn type_declaration(definition: &ast::Definition) -> Option<TypeDeclaration<'_>> {
+    use ast::Definition;
+
+    let (name, keyword, is_extension, members) = match definition {
+        Definition::ObjectTypeDefinition(def) => (&def.name, "type", false, fields(&def.fields)),
+        Definition::ObjectTypeExtension(ext) => (&ext.name, "type", true, fields(&ext.fields)),
+        Definition::InterfaceTypeDefinition(def) => {
+            (&def.name, "interface", false, fields(&def.fields))
+        }
+        Definition::InterfaceTypeExtension(ext) => {
+            (&ext.name, "interface", true, fields(&ext.fields))
+        }
+        Definition::InputObjectTypeDefinition(def) => {
+            (&def.name, "input", false, input_fields(&def.fields))
+        }
+        Definition::InputObjectTypeExtension(ext) => {
+            (&ext.name, "input", true, input_fields(&ext.fields))
+        }
+        Definition::EnumTypeDefinition(def) => (&def.name, "enum", false, values(&def.values)),
+        Definition::EnumTypeExtension(ext) => (&ext.name, "enum", true, values(&ext.values)),
+        Definition::UnionTypeDefinition(def) => {
+            (&def.name, "union", false, union_members(&def.members))
+        }
+        Definition::UnionTypeExtension(ext) => {
+            (&ext.name, "union", true, union_members(&ext.members))
+        }
+        Definition::ScalarTypeDefinition(def) => (&def.name, "scalar", false, Vec::new()),
+        Definition::ScalarTypeExtension(ext) => (&ext.name, "scalar", true, Vec::new()),
+        _ => return None,
+    };
+    Some(TypeDeclaration {
+        name: declared(name),
+        keyword,
+        is_extension,
+        members,
+    })
+}
+
+fn declared(name: &ast::Name) -> Declared<'_> {
+    Declared {
+        name: name.as_str(),
+        location: name.location(),
+    }
+}
+
+fn fields(fields: &[Node<ast::FieldDefinition>]) -> Vec<(&'static str, Declared<'_>)> {
+    fields
+        .iter()
+        .map(|field| ("Field", declared(&field.name)))
+        .collect()
+}
+
+fn input_fields(fields: &[Node<ast::InputValueDefinition>]) -> Vec<(&'static str, Declared<'_>)> {
+    fields
+        .iter()
+        .map(|field| ("Field", declared(&field.name)))
+        .collect()
+}
+
+fn values(values: &[Node<ast::EnumValueDefinition>]) -> Vec<(&'static str, Declared<'_>)> {
+    values
+        .iter()
+        .map(|value| ("Value", declared(&value.value)))
+        .collect()
+}
+
+fn union_members(members: &[ast::NamedType]) -> Vec<(&'static str, Declared<'_>)> {
+    members
+        .iter()
+        .map(|member| ("Member", declared(member)))
+        .collect()
+}
+
+fn range_of(
+    block: &BlockPositions<'_>,
+    location: Option<apollo_compiler::parser::SourceSpan>,
+) -> DiagnosticRange {
+    location.map_or_else(DiagnosticRange::default, |location| {
+        block.range(location.offset(), location.end_offset())
+    })
+}
+
+fn related(site: &Site, message: String) -> RelatedInformation {
+    RelatedInformation {
+        uri: site.uri.clone(),
+        range: site.range,
+        message: message.into(),
+    }
+}
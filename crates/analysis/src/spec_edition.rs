@@ -0,0 +1,123 @@
+// Spec edition gating
+//
+// apollo-compiler validates against the latest spec, so a project whose
+// server only implements an older edition gets no warning when a schema or
+// operation relies on something newer. This pass reports those features
+// where they're used:
+//
+// - October 2021: interfaces implementing interfaces, `repeatable`
+//   directives (and using one more than once at a location), `@specifiedBy`
+// - Draft: `@oneOf` input objects
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_compiler::Schema;
+use apollo_parser::cst::{self, CstNode};
+use graphql_config::SpecEdition;
+use std::collections::HashSet;
+use text_size::TextRange;
+
+/// Report uses of features newer than `edition` in a document block.
+///
+/// `schema` tells which directives are repeatable. Without it, repeated
+/// directives aren't checked.
+pub(crate) fn edition_diagnostics(
+    edition: SpecEdition,
+    schema: Option<&Schema>,
+    tree: &apollo_parser::SyntaxTree,
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    if edition == SpecEdition::Draft {
+        return Vec::new();
+    }
+
+    let mut checker = EditionChecker {
+        edition,
+        block,
+        diagnostics: Vec::new(),
+    };
+
+    for node in tree.document().syntax().descendants() {
+        if let Some(iface) = cst::InterfaceTypeDefinition::cast(node.clone()) {
+            checker.check_implements(iface.implements_interfaces());
+        } else if let Some(ext) = cst::InterfaceTypeExtension::cast(node.clone()) {
+            checker.check_implements(ext.implements_interfaces());
+        } else if let Some(definition) = cst::DirectiveDefinition::cast(node.clone()) {
+            if let Some(token) = definition.repeatable_token() {
+                checker.require(
+                    SpecEdition::October2021,
+                    "`repeatable` directives",
+                    token.text_range(),
+                );
+            }
+        } else if let Some(directives) = cst::Directives::cast(node) {
+            checker.check_directives(&directives, schema);
+        }
+    }
+
+    checker.diagnostics
+}
+
+struct EditionChecker<'a> {
+    edition: SpecEdition,
+    block: &'a BlockPositions<'a>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl EditionChecker<'_> {
+    fn check_implements(&mut self, implements: Option<cst::ImplementsInterfaces>) {
+        if let Some(implements) = implements {
+            self.require(
+                SpecEdition::October2021,
+                "interfaces implementing interfaces",
+                implements.syntax().text_range(),
+            );
+        }
+    }
+
+    fn check_directives(&mut self, directives: &cst::Directives, schema: Option<&Schema>) {
+        let mut seen = HashSet::new();
+        for directive in directives.directives() {
+            let Some(name) = directive.name() else {
+                continue;
+            };
+            let name_text = name.text().to_string();
+            let range = name.syntax().text_range();
+            match name_text.as_str() {
+                "specifiedBy" => self.require(SpecEdition::October2021, "`@specifiedBy`", range),
+                "oneOf" => self.require(SpecEdition::Draft, "`@oneOf`", range),
+                _ => {}
+            }
+            // Non-repeatable directives used twice are already reported by
+            // the regular validation, whatever the edition
+            let repeatable = schema
+                .and_then(|schema| schema.directive_definitions.get(name_text.as_str()))
+                .is_some_and(|definition| definition.repeatable);
+            if !seen.insert(name_text.clone()) && repeatable {
+                self.require(
+                    SpecEdition::October2021,
+                    &format!("using `@{name_text}` more than once at a location"),
+                    range,
+                );
+            }
+        }
+    }
+
+    /// Report `feature` at `range` unless the configured edition includes
+    /// `introduced_in`.
+    fn require(&mut self, introduced_in: SpecEdition, feature: &str, range: TextRange) {
+        if self.edition >= introduced_in {
+            return;
+        }
+        let mut diagnostic = Diagnostic::error(
+            format!(
+                "Not available in the {} spec: {feature} (added in {})",
+                self.edition.label(),
+                introduced_in.label()
+            ),
+            self.block.range(range.start().into(), range.end().into()),
+        );
+        diagnostic.code = Some("unsupported-spec-feature".into());
+        self.diagnostics.push(diagnostic);
+    }
+}
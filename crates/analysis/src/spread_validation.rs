@@ -0,0 +1,205 @@
+// Fragment spread validation
+//
+// Implements the spec's "Fragment Spread Is Possible" rule: a fragment can
+// only be spread where its type condition shares at least one possible
+// object type with the enclosing type. Spreading `fragment on Dog` inside a
+// selection on `Cat` can never match anything, so it's reported.
+//
+// Inline fragments are reported on their type condition. Named spreads are
+// reported on the fragment name, since the type condition lives in the
+// fragment's definition, possibly in another file.
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_compiler::schema::ExtendedType;
+use apollo_compiler::Schema;
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use std::collections::{HashMap, HashSet};
+use text_size::TextRange;
+
+/// Check every fragment spread and inline fragment in a document block.
+///
+/// `fragment_types` maps fragment names to their type conditions, for the
+/// fragments defined in the block and those it spreads from other files.
+pub(crate) fn spread_diagnostics(
+    schema: &Schema,
+    tree: &apollo_parser::SyntaxTree,
+    block: &BlockPositions<'_>,
+    fragment_types: &HashMap<String, String>,
+) -> Vec<Diagnostic> {
+    let mut checker = SpreadChecker {
+        schema,
+        block,
+        fragment_types,
+        possible_types: HashMap::new(),
+        diagnostics: Vec::new(),
+    };
+
+    for definition in tree.document().definitions() {
+        match definition {
+            cst::Definition::OperationDefinition(op) => {
+                let root = op.operation_kind().root_type_name();
+                checker.check_selection_set(op.selection_set(), Some(root));
+            }
+            cst::Definition::FragmentDefinition(frag) => {
+                let type_condition = frag
+                    .type_condition()
+                    .and_then(|tc| tc.named_type())
+                    .and_then(|nt| nt.name())
+                    .map(|n| n.text().to_string());
+                checker.check_selection_set(frag.selection_set(), type_condition.as_deref());
+            }
+            _ => {}
+        }
+    }
+
+    checker.diagnostics
+}
+
+/// Returns true for apollo-compiler's impossible spread diagnostics, which
+/// `spread_diagnostics` reports itself on the type condition.
+pub(crate) fn is_superseded_spread_message(message: &str) -> bool {
+    message.contains("cannot be applied to")
+}
+
+struct SpreadChecker<'a> {
+    schema: &'a Schema,
+    block: &'a BlockPositions<'a>,
+    fragment_types: &'a HashMap<String, String>,
+    /// Object types each composite type can resolve to, computed on demand
+    possible_types: HashMap<String, Option<HashSet<String>>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl SpreadChecker<'_> {
+    fn check_selection_set(
+        &mut self,
+        selection_set: Option<cst::SelectionSet>,
+        parent: Option<&str>,
+    ) {
+        let Some(selection_set) = selection_set else {
+            return;
+        };
+        let schema = self.schema;
+        let fragment_types = self.fragment_types;
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let field_type = parent
+                        .zip(field.name())
+                        .and_then(|(parent, name)| {
+                            schema
+                                .type_field(parent, name.text().to_string().as_str())
+                                .ok()
+                        })
+                        .map(|f| f.ty.inner_named_type().to_string());
+                    self.check_selection_set(field.selection_set(), field_type.as_deref());
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    let Some(name) = spread.fragment_name().and_then(|n| n.name()) else {
+                        continue;
+                    };
+                    let name_text = name.text().to_string();
+                    let (Some(parent), Some(type_condition)) =
+                        (parent, fragment_types.get(&name_text))
+                    else {
+                        continue;
+                    };
+                    if !self.is_possible(type_condition, parent) {
+                        let range = name.syntax().text_range();
+                        self.report(
+                            format!(
+                                "Fragment `{name_text}` on `{type_condition}` cannot be spread on type `{parent}`"
+                            ),
+                            range,
+                        );
+                    }
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let named_type = inline.type_condition().and_then(|tc| tc.named_type());
+                    let type_condition = named_type
+                        .as_ref()
+                        .and_then(cst::NamedType::name)
+                        .map(|n| n.text().to_string());
+                    if let (Some(parent), Some(type_condition), Some(named_type)) =
+                        (parent, &type_condition, &named_type)
+                    {
+                        if !self.is_possible(type_condition, parent) {
+                            self.report(
+                                format!(
+                                    "Fragment on `{type_condition}` cannot be spread on type `{parent}`"
+                                ),
+                                named_type.syntax().text_range(),
+                            );
+                        }
+                    }
+                    self.check_selection_set(
+                        inline.selection_set(),
+                        type_condition.as_deref().or(parent),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Whether a fragment on `type_condition` can match anything inside a
+    /// selection on `parent`. Unknown or non-composite types are left to
+    /// the type condition checks, so they count as possible here.
+    fn is_possible(&mut self, type_condition: &str, parent: &str) -> bool {
+        if type_condition == parent {
+            return true;
+        }
+        let Some(fragment_types) = self.possible_types(type_condition) else {
+            return true;
+        };
+        let Some(parent_types) = self.possible_types(parent) else {
+            return true;
+        };
+        !fragment_types.is_disjoint(&parent_types)
+    }
+
+    fn possible_types(&mut self, type_name: &str) -> Option<HashSet<String>> {
+        let schema = self.schema;
+        self.possible_types
+            .entry(type_name.to_string())
+            .or_insert_with(|| match schema.types.get(type_name)? {
+                ExtendedType::Object(_) => Some(HashSet::from([type_name.to_string()])),
+                ExtendedType::Interface(_) => Some(
+                    schema
+                        .types
+                        .iter()
+                        .filter_map(|(name, ty)| match ty {
+                            ExtendedType::Object(object)
+                                if object
+                                    .implements_interfaces
+                                    .iter()
+                                    .any(|interface| interface.name == type_name) =>
+                            {
+                                Some(name.to_string())
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                ExtendedType::Union(union_type) => Some(
+                    union_type
+                        .members
+                        .iter()
+                        .map(|member| member.name.to_string())
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .clone()
+    }
+
+    fn report(&mut self, message: String, range: TextRange) {
+        let mut diagnostic = Diagnostic::error(
+            message,
+            self.block.range(range.start().into(), range.end().into()),
+        );
+        diagnostic.code = Some("impossible-fragment-spread".into());
+        self.diagnostics.push(diagnostic);
+    }
+}
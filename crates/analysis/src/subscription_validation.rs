@@ -0,0 +1,155 @@
+// Subscription root field validation
+//
+// Implements the spec's "Single Root Field" rule: a subscription's selection
+// set, once its fragments are expanded, must contain exactly one response
+// key, and that root field can't be an introspection field. Spreads are
+// followed into fragments from other files, so a fragment that adds a
+// second root field is caught where it's spread.
+//
+// Both problems are reported on the operation's selection set, since the
+// fields involved may come from several fragments.
+
+use crate::variable_flow::BlockPositions;
+use crate::Diagnostic;
+use apollo_compiler::ast::{Definition, Document, FragmentDefinition, OperationType, Selection};
+use apollo_compiler::Node;
+use apollo_parser::cst::{self, CstNode};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Check every subscription in a document block.
+///
+/// `fragment_documents` are the documents holding fragments the block
+/// spreads from other files; fragments the block defines come from `ast`.
+pub(crate) fn subscription_diagnostics(
+    tree: &apollo_parser::SyntaxTree,
+    ast: &Document,
+    fragment_documents: &[Arc<Document>],
+    block: &BlockPositions<'_>,
+) -> Vec<Diagnostic> {
+    let mut fragments: HashMap<&str, &Node<FragmentDefinition>> = HashMap::new();
+    for document in std::iter::once(ast).chain(fragment_documents.iter().map(AsRef::as_ref)) {
+        for definition in &document.definitions {
+            if let Definition::FragmentDefinition(fragment) = definition {
+                fragments.entry(fragment.name.as_str()).or_insert(fragment);
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for definition in tree.document().definitions() {
+        let cst::Definition::OperationDefinition(op) = definition else {
+            continue;
+        };
+        if !op
+            .operation_type()
+            .is_some_and(|t| t.subscription_token().is_some())
+        {
+            continue;
+        }
+        let Some(selection_set) = op.selection_set() else {
+            continue;
+        };
+        let name = op.name().map(|n| n.text().to_string());
+        let Some(operation) = ast.definitions.iter().find_map(|def| match def {
+            Definition::OperationDefinition(operation)
+                if operation.operation_type == OperationType::Subscription
+                    && operation.name.as_ref().map(|n| n.as_str()) == name.as_deref() =>
+            {
+                Some(operation)
+            }
+            _ => None,
+        }) else {
+            continue;
+        };
+
+        // Response key to field name, in selection order
+        let mut root_fields: Vec<(&str, &str)> = Vec::new();
+        collect_root_fields(
+            &operation.selection_set,
+            &fragments,
+            &mut HashSet::new(),
+            &mut root_fields,
+        );
+
+        let subject = match &name {
+            Some(name) => format!("Subscription `{name}`"),
+            None => "Anonymous subscription".to_string(),
+        };
+        let range = selection_set.syntax().text_range();
+        let range = block.range(range.start().into(), range.end().into());
+
+        if root_fields.len() > 1 {
+            let keys: Vec<String> = root_fields
+                .iter()
+                .map(|(key, _)| format!("`{key}`"))
+                .collect();
+            let mut diagnostic = Diagnostic::error(
+                format!(
+                    "{subject} must select exactly one root field, but selects {}: {}",
+                    root_fields.len(),
+                    keys.join(", ")
+                ),
+                range,
+            );
+            diagnostic.code = Some("subscription-root-field".into());
+            diagnostics.push(diagnostic);
+        }
+        for (_, field_name) in root_fields
+            .iter()
+            .filter(|(_, field_name)| field_name.starts_with("__"))
+        {
+            let mut diagnostic = Diagnostic::error(
+                format!(
+                    "{subject} cannot select introspection field `{field_name}` as its root field"
+                ),
+                range,
+            );
+            diagnostic.code = Some("subscription-root-field".into());
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+/// Returns true for apollo-compiler's subscription root field diagnostics,
+/// which `subscription_diagnostics` reports itself after following
+/// fragments across files.
+pub(crate) fn is_superseded_subscription_message(message: &str) -> bool {
+    message.contains("can only have one root field")
+        || message.contains("introspection field as a root field")
+}
+
+/// The distinct response keys `selections` contribute to the root, with
+/// the field each one selects. Unknown fragments contribute nothing, and a
+/// fragment already being expanded isn't expanded again.
+fn collect_root_fields<'a>(
+    selections: &'a [Selection],
+    fragments: &HashMap<&str, &'a Node<FragmentDefinition>>,
+    expanding: &mut HashSet<&'a str>,
+    root_fields: &mut Vec<(&'a str, &'a str)>,
+) {
+    for selection in selections {
+        match selection {
+            Selection::Field(field) => {
+                let key = field.response_key().as_str();
+                if !root_fields.iter().any(|(existing, _)| *existing == key) {
+                    root_fields.push((key, field.name.as_str()));
+                }
+            }
+            Selection::InlineFragment(inline) => {
+                collect_root_fields(&inline.selection_set, fragments, expanding, root_fields);
+            }
+            Selection::FragmentSpread(spread) => {
+                let Some(&fragment) = fragments.get(spread.fragment_name.as_str()) else {
+                    continue;
+                };
+                if expanding.insert(fragment.name.as_str()) {
+                    collect_root_fields(&fragment.selection_set, fragments, expanding, root_fields);
+                    expanding.remove(fragment.name.as_str());
+                }
+            }
+        }
+    }
+}
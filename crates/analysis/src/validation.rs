@@ -1,4 +1,4 @@
-use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Position, Severity};
+use crate::{Diagnostic, DiagnosticRange, GraphQLAnalysisDatabase, Severity};
 use graphql_base_db::{FileContent, FileMetadata};
 use std::sync::Arc;
 
@@ -7,10 +7,24 @@ use std::sync::Arc;
 ///
 /// This provides comprehensive validation including:
 /// - Field selection validation against schema types
-/// - Argument validation (required args, correct types)
-/// - Fragment spread resolution and type checking
-/// - Variable usage and type validation
+/// - Argument validation (required args; literal types come from
+///   `literal_diagnostics`, which checks list items and object fields
+///   individually)
+/// - Fragment spread resolution and type checking (impossible spreads come
+///   from `spread_diagnostics`, which reports them on the type condition)
+/// - Variable usage and type validation (unused and undeclared variables
+///   come from `variable_flow_diagnostics`, which carries fixes)
 /// - Circular fragment detection
+/// - Subscription root fields (reported by `subscription_diagnostics`, which
+///   expands fragments from other files)
+/// - Field merging (reported by `field_merging_diagnostics`, which follows
+///   fragments across files and points at both conflicting fields)
+/// - Directive existence, locations and repetition (reported on the
+///   directive name by `directive_diagnostics`)
+/// - Placement and effect of client-controlled nullability designators
+///   (`nullability_diagnostics`)
+/// - `@stream` targets, labels and initial counts
+///   (`incremental_diagnostics`)
 /// - Type coercion validation
 
 #[salsa::tracked]
@@ -22,9 +36,18 @@ pub fn validate_file(
 ) -> Arc<Vec<Diagnostic>> {
     let mut diagnostics = Vec::new();
 
-    let Some(schema) =
-        crate::merged_schema::merged_schema_with_diagnostics(db, project_files).schema
-    else {
+    // A `schema-variant` pragma or a variant's `documents` patterns swap in
+    // an alternate schema for this file. Unknown variant names are reported
+    // by `pragma_diagnostics` and fall back to the project schema.
+    let variant = graphql_hir::document_schema_variant(db, metadata.file_id(db), content);
+    let merged = match variant {
+        Some(name) => {
+            crate::merged_schema::variant_schema_with_diagnostics(db, project_files, name)
+        }
+        None => crate::merged_schema::merged_schema_with_diagnostics(db, project_files),
+    };
+
+    let Some(schema) = merged.schema else {
         // Without a schema, we can't validate documents
         // Return empty diagnostics (syntax errors are handled elsewhere)
         return Arc::new(diagnostics);
@@ -32,26 +55,81 @@ pub fn validate_file(
 
     // Detect Relay's @arguments/@argumentDefinitions directives which accept
     // dynamic arguments that can't be statically defined in the schema.
-    // When these directives are defined with no arguments, suppress
-    // "argument X is not supported" errors for them.
+    // When these directives are defined with no arguments,
+    // `literal_diagnostics` doesn't report unknown arguments on them.
+    // The spec-strict profile reports them like any other unknown argument.
+    let validation_config = db.validation_config();
+    let spec_strict = validation_config.profile == graphql_config::ValidationProfile::SpecStrict;
+    let spec_edition = validation_config.spec;
     let relay_dynamic_arg_directives: Vec<&str> = ["arguments", "argumentDefinitions"]
         .iter()
         .filter(|name| {
-            schema
-                .directive_definitions
-                .get(**name)
-                .is_some_and(|def| def.arguments.is_empty())
+            !spec_strict
+                && schema
+                    .directive_definitions
+                    .get(**name)
+                    .is_some_and(|def| def.arguments.is_empty())
         })
         .copied()
         .collect();
 
+    let scalar_formats = db.scalar_formats();
+
     let parse = graphql_syntax::parse(db, content, metadata);
     let doc_uri = metadata.uri(db);
+    let directives = graphql_hir::schema_directives(db, project_files);
+    let scope = graphql_hir::file_fragment_scope(
+        db,
+        metadata.file_id(db),
+        content,
+        metadata,
+        project_files,
+    );
 
     // Unified: process all documents (works for both pure GraphQL and TS/JS)
     for doc in parse.documents() {
-        // Use document's line offset from extraction (0 for pure GraphQL files)
-        let line_offset_val = doc.line_offset;
+        let block = crate::variable_flow::BlockPositions {
+            source: doc.source,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        };
+        diagnostics.extend(crate::literal_validation::literal_diagnostics(
+            &schema,
+            doc.tree,
+            &block,
+            &relay_dynamic_arg_directives,
+            &scalar_formats,
+        ));
+        diagnostics.extend(crate::spec_edition::edition_diagnostics(
+            spec_edition,
+            Some(&schema),
+            doc.tree,
+            &block,
+        ));
+        diagnostics.extend(crate::directive_validation::directive_diagnostics(
+            directives, doc.tree, &block,
+        ));
+        diagnostics.extend(crate::nullability_validation::nullability_diagnostics(
+            Some(schema.as_ref()),
+            doc.tree,
+            doc.designators,
+            &block,
+        ));
+        // Variables used directly in an operation are checked against their
+        // declared types by `literal_diagnostics`
+        let operation_ranges: Vec<DiagnosticRange> = doc
+            .tree
+            .document()
+            .definitions()
+            .filter_map(|definition| match definition {
+                apollo_parser::cst::Definition::OperationDefinition(op) => {
+                    use apollo_parser::cst::CstNode;
+                    let range = op.syntax().text_range();
+                    Some(block.range(range.start().into(), range.end().into()))
+                }
+                _ => None,
+            })
+            .collect();
 
         // Collect fragment names referenced by this document (transitively across files)
         // Uses the already-parsed tree to avoid redundant parsing
@@ -81,6 +159,10 @@ pub fn validate_file(
         // Without pointer tracking, we'd add the same AST multiple times, causing
         // "fragment defined multiple times" errors.
         let mut added_ast_ptrs: std::collections::HashSet<usize> = std::collections::HashSet::new();
+        // Type conditions of the fragments this block defines or spreads
+        let mut fragment_types = fragment_type_conditions(doc.ast);
+        // Documents holding the fragments spread from other files
+        let mut fragment_documents = Vec::new();
         for fragment_name in &referenced_fragments {
             // Skip fragments that are already in the current document block
             // This prevents duplicate definition errors when fragments in the same file
@@ -89,17 +171,45 @@ pub fn validate_file(
                 continue;
             }
             let key: Arc<str> = Arc::from(fragment_name.as_str());
+            // A document with `# import` comments resolves fragments through
+            // its imports, so a same-named fragment elsewhere doesn't shadow
+            // the imported one.
             // Fine-grained query: only creates dependency on this specific fragment
             // Uses cached AST instead of re-parsing source text
-            if let Some(fragment_ast) = graphql_hir::fragment_ast(db, project_files, key) {
+            let fragment_ast = match scope.as_ref().and_then(|scope| scope.get(&key)) {
+                Some(&file_id) => {
+                    graphql_hir::fragment_ast_in_file(db, project_files, file_id, &key)
+                }
+                None => graphql_hir::fragment_ast(db, project_files, key),
+            };
+            if let Some(fragment_ast) = fragment_ast {
                 // Use Arc pointer address to deduplicate - multiple fragments from the
                 // same gql block share the same Arc<Document>
                 let ptr = Arc::as_ptr(&fragment_ast) as usize;
                 if added_ast_ptrs.insert(ptr) {
                     builder.add_ast_document(&fragment_ast, false);
+                    for (name, type_condition) in fragment_type_conditions(&fragment_ast) {
+                        fragment_types.entry(name).or_insert(type_condition);
+                    }
+                    fragment_documents.push(fragment_ast);
                 }
             }
         }
+        diagnostics.extend(crate::spread_validation::spread_diagnostics(
+            &schema,
+            doc.tree,
+            &block,
+            &fragment_types,
+        ));
+        diagnostics.extend(crate::subscription_validation::subscription_diagnostics(
+            doc.tree,
+            doc.ast,
+            &fragment_documents,
+            &block,
+        ));
+        diagnostics.extend(crate::incremental_validation::incremental_diagnostics(
+            &schema, doc.ast, &block,
+        ));
 
         let doc_result = builder.build();
         match if errors.is_empty() {
@@ -123,37 +233,56 @@ pub fn validate_file(
                             }
                         }
                     }
-                    // Line offset adjusts positions since the AST was parsed without source offset
-                    let range = apollo_diag.line_column_range().map_or_else(
-                        DiagnosticRange::default,
-                        |loc_range| DiagnosticRange {
-                            start: Position {
-                                line: (loc_range.start.line.saturating_sub(1)
-                                    + line_offset_val as usize)
-                                    as u32,
-                                character: loc_range.start.column.saturating_sub(1) as u32,
-                            },
-                            end: Position {
-                                line: (loc_range.end.line.saturating_sub(1)
-                                    + line_offset_val as usize)
-                                    as u32,
-                                character: loc_range.end.column.saturating_sub(1) as u32,
-                            },
-                        },
-                    );
+                    // The AST was parsed from the block alone, so its byte
+                    // offsets map through the block like our own diagnostics
+                    let range = apollo_diag
+                        .error
+                        .location()
+                        .map_or_else(DiagnosticRange::default, |location| {
+                            block.range(location.offset(), location.end_offset())
+                        });
                     let message: Arc<str> = Arc::from(apollo_diag.error.to_string());
                     if message.contains("must be used in an operation") {
                         continue;
                     }
-                    // Relay's @arguments/@argumentDefinitions accept dynamic args
-                    // that mirror the target fragment's definitions, so they can't
-                    // be statically declared in the directive definition.
-                    if relay_dynamic_arg_directives
-                        .iter()
-                        .any(|d| message.contains(&format!("is not supported by `@{d}`")))
+                    // Unused and undefined variables are reported by the
+                    // variable flow pass below, which follows cross-file
+                    // fragment spreads and attaches fixes.
+                    if crate::variable_flow::is_superseded_variable_message(&message) {
+                        continue;
+                    }
+                    // Literal type errors are reported per list item and
+                    // object field by `literal_diagnostics` above, along
+                    // with missing and unknown arguments.
+                    if crate::literal_validation::is_superseded_literal_message(&message) {
+                        continue;
+                    }
+                    if crate::literal_validation::is_variable_usage_message(&message)
+                        && operation_ranges.iter().any(|op| contains(op, &range))
                     {
                         continue;
                     }
+                    // Impossible spreads are reported on the type
+                    // condition by `spread_diagnostics` above.
+                    if crate::spread_validation::is_superseded_spread_message(&message) {
+                        continue;
+                    }
+                    // Subscription root fields are checked with fragments
+                    // from other files expanded by `subscription_diagnostics`.
+                    if crate::subscription_validation::is_superseded_subscription_message(&message)
+                    {
+                        continue;
+                    }
+                    // Directives are checked against their HIR definitions
+                    // by `directive_diagnostics` above.
+                    if crate::directive_validation::is_superseded_directive_message(&message) {
+                        continue;
+                    }
+                    // Field merging conflicts are reported with both
+                    // locations by `field_merging_diagnostics` below.
+                    if crate::field_merging::is_superseded_merge_message(&message) {
+                        continue;
+                    }
                     diagnostics.push(Diagnostic {
                         severity: Severity::Error,
                         message,
@@ -166,15 +295,50 @@ pub fn validate_file(
                         help: None,
                         url: None,
                         tags: Vec::new(),
+                        related: Vec::new(),
                     });
                 }
             }
         }
     }
 
+    diagnostics.extend(
+        crate::variable_flow::variable_flow_diagnostics(db, content, metadata, project_files)
+            .iter()
+            .cloned(),
+    );
+    diagnostics.extend(
+        crate::field_merging::field_merging_diagnostics(db, content, metadata, project_files)
+            .iter()
+            .cloned(),
+    );
+
     Arc::new(diagnostics)
 }
 
+/// Map each fragment defined in `document` to its type condition
+fn fragment_type_conditions(
+    document: &apollo_compiler::ast::Document,
+) -> std::collections::HashMap<String, String> {
+    document
+        .definitions
+        .iter()
+        .filter_map(|definition| match definition {
+            apollo_compiler::ast::Definition::FragmentDefinition(frag) => {
+                Some((frag.name.to_string(), frag.type_condition.to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether `inner` starts within `outer`
+fn contains(outer: &DiagnosticRange, inner: &DiagnosticRange) -> bool {
+    let start = (inner.start.line, inner.start.character);
+    (outer.start.line, outer.start.character) <= start
+        && start <= (outer.end.line, outer.end.character)
+}
+
 /// Collect all fragment names referenced by a document transitively across files
 /// This resolves fragment dependencies by following fragment spreads to their definitions
 ///
@@ -0,0 +1,209 @@
+// Configurable severities for spec validation.
+//
+// Validation diagnostics are classified into named checks (`unknown-directive`,
+// `unused-variable`, ...). The configured profile gives each check a baseline
+// severity, and per-check overrides in the project config take precedence.
+
+use crate::{Diagnostic, Severity};
+use graphql_config::{ValidationConfig, ValidationProfile, ValidationSeverity};
+
+/// Names of the validation checks whose severity can be configured.
+pub const VALIDATION_CHECKS: &[&str] = &[
+    "unknown-type",
+    "unknown-field",
+    "unknown-argument",
+    "unknown-directive",
+    "unknown-fragment",
+    "missing-required-argument",
+    "misplaced-directive",
+    "repeated-directive",
+    "unused-variable",
+    "undefined-variable",
+    "invalid-value",
+    "invalid-scalar-format",
+    "conflicting-fields",
+    "impossible-fragment-spread",
+    "invalid-incremental-delivery",
+    "unsupported-spec-feature",
+    "duplicate-type",
+    "duplicate-member",
+    "missing-interface-field",
+    "incompatible-interface-field",
+];
+
+/// Apply the configured severities to `diagnostics`.
+///
+/// Each validation diagnostic that belongs to a known check gets the check
+/// name as its code, then takes the severity the config gives that check.
+/// Checks configured as `off` are dropped. Everything else passes through.
+pub(crate) fn apply_validation_config(
+    config: &ValidationConfig,
+    diagnostics: Vec<Diagnostic>,
+) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            let Some(check) = check_name(&diagnostic) else {
+                return Some(diagnostic);
+            };
+            let severity = config
+                .checks
+                .get(check)
+                .copied()
+                .unwrap_or_else(|| profile_severity(config.profile, check));
+            diagnostic.severity = match severity {
+                ValidationSeverity::Error => Severity::Error,
+                ValidationSeverity::Warn => Severity::Warning,
+                ValidationSeverity::Off => return None,
+            };
+            diagnostic.code.get_or_insert_with(|| check.into());
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Baseline severity of `check` under `profile`
+fn profile_severity(profile: ValidationProfile, check: &str) -> ValidationSeverity {
+    match profile {
+        ValidationProfile::SpecStrict | ValidationProfile::Recommended => ValidationSeverity::Error,
+        // These are the checks that tend to fire while a schema and its
+        // clients are still being brought in line, rather than on requests
+        // that reference things that don't exist
+        ValidationProfile::Relaxed => match check {
+            "unknown-directive"
+            | "unknown-argument"
+            | "misplaced-directive"
+            | "repeated-directive"
+            | "unused-variable" => ValidationSeverity::Warn,
+            _ => ValidationSeverity::Error,
+        },
+    }
+}
+
+/// The check a diagnostic belongs to. Our own passes set the code directly;
+/// apollo-compiler diagnostics are recognized by their message.
+fn check_name(diagnostic: &Diagnostic) -> Option<&'static str> {
+    if diagnostic.source.as_ref() != "validation" {
+        return None;
+    }
+    if let Some(code) = &diagnostic.code {
+        return VALIDATION_CHECKS
+            .iter()
+            .find(|check| **check == code.as_ref())
+            .copied();
+    }
+    classify_message(&diagnostic.message)
+}
+
+fn classify_message(message: &str) -> Option<&'static str> {
+    let check = if message.contains("cannot find directive") {
+        "unknown-directive"
+    } else if message.contains("cannot find type") {
+        "unknown-type"
+    } else if message.contains("cannot find fragment") {
+        "unknown-fragment"
+    } else if message.contains("does not have a field") {
+        "unknown-field"
+    } else if message.contains("required argument") && message.contains("is not provided") {
+        "missing-required-argument"
+    } else if message.contains("is not supported by") {
+        "unknown-argument"
+    } else if message.contains("is not supported for") {
+        "misplaced-directive"
+    } else if message.contains("can only be used once") {
+        "repeated-directive"
+    } else {
+        return None;
+    };
+    Some(check)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiagnosticRange;
+    use std::collections::BTreeMap;
+
+    fn validation_error(message: &str) -> Diagnostic {
+        Diagnostic::error(message, DiagnosticRange::default())
+    }
+
+    #[test]
+    fn test_classify_apollo_messages() {
+        assert_eq!(
+            classify_message("cannot find directive `@live` in this document"),
+            Some("unknown-directive")
+        );
+        assert_eq!(
+            classify_message("type `User` does not have a field `nmae`"),
+            Some("unknown-field")
+        );
+        assert_eq!(
+            classify_message("the required argument `Query.user(id:)` is not provided"),
+            Some("missing-required-argument")
+        );
+        assert_eq!(
+            classify_message("the argument `limit` is not supported by `Query.users`"),
+            Some("unknown-argument")
+        );
+        assert_eq!(
+            classify_message("`@skip` directive is not supported for `QUERY` location"),
+            Some("misplaced-directive")
+        );
+        assert_eq!(classify_message("something else entirely"), None);
+    }
+
+    #[test]
+    fn test_recommended_profile_keeps_errors() {
+        let diagnostics = apply_validation_config(
+            &ValidationConfig::default(),
+            vec![validation_error(
+                "cannot find directive `@live` in this document",
+            )],
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("unknown-directive"));
+    }
+
+    #[test]
+    fn test_relaxed_profile_downgrades_directive_checks() {
+        let config = ValidationConfig {
+            profile: ValidationProfile::Relaxed,
+            checks: BTreeMap::new(),
+        };
+        let diagnostics = apply_validation_config(
+            &config,
+            vec![
+                validation_error("cannot find directive `@live` in this document"),
+                validation_error("type `User` does not have a field `nmae`"),
+            ],
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_check_overrides_take_precedence() {
+        let config = ValidationConfig {
+            profile: ValidationProfile::SpecStrict,
+            checks: BTreeMap::from([
+                ("unknown-directive".to_string(), ValidationSeverity::Warn),
+                ("unknown-field".to_string(), ValidationSeverity::Off),
+            ]),
+        };
+        let diagnostics = apply_validation_config(
+            &config,
+            vec![
+                validation_error("cannot find directive `@live` in this document"),
+                validation_error("type `User` does not have a field `nmae`"),
+                validation_error("fragment `A` cannot reference itself"),
+            ],
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        // Unclassified diagnostics are untouched
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert_eq!(diagnostics[1].code, None);
+    }
+}
@@ -0,0 +1,786 @@
+// Variable flow analysis across fragment spreads
+//
+// Follows each operation's variables into the fields and directives that
+// consume them, including fragments spread from other files. Reports
+// declared-but-unused variables and variables referenced without a
+// declaration, each with a fix. Type compatibility at individual usage sites
+// is left to apollo-compiler in `validate_file`; the expected types collected
+// here write the missing declaration. `variable_flows` exposes the same walk,
+// with each variable's declared type and usage sites, to hover.
+
+use crate::{
+    CodeFix, Diagnostic, DiagnosticRange, DiagnosticTag, GraphQLAnalysisDatabase, Position,
+    TextEdit,
+};
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use graphql_base_db::{FileContent, FileMetadata, ProjectFiles};
+use graphql_hir::{DirectiveDefMap, TypeDefMap, TypeRef};
+use graphql_syntax::edit::significant_end;
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+/// A variable reference together with the input type expected where it appears
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableUsage {
+    /// Variable name, without the `$` sigil
+    pub name: Arc<str>,
+    /// Type expected at the usage site (e.g. `ID!`), when it can be resolved
+    /// against the schema
+    pub expected_type: Option<Arc<str>>,
+    /// What receives the variable, as a schema coordinate: `Query.user(id:)`,
+    /// `@include(if:)` or an input field like `UserFilter.name`
+    pub target: Option<Arc<str>>,
+}
+
+/// Variables referenced directly by a fragment, plus the fragments it spreads
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FragmentVariables {
+    pub usages: Vec<VariableUsage>,
+    pub fragment_spreads: Vec<Arc<str>>,
+}
+
+/// An operation variable and the sites it flows into
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableFlow {
+    /// The declaring operation; `None` when it's anonymous
+    pub operation: Option<Arc<str>>,
+    /// Variable name, without the `$` sigil
+    pub name: Arc<str>,
+    /// Declared type, as written
+    pub declared_type: Arc<str>,
+    pub has_default: bool,
+    /// The `$name` in the variable definition
+    pub range: DiagnosticRange,
+    pub usages: Vec<VariableSite>,
+}
+
+/// A place an operation variable is passed to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableSite {
+    /// What receives the variable; see [`VariableUsage::target`]
+    pub target: Option<Arc<str>>,
+    /// Type expected there, when it can be resolved against the schema
+    pub expected_type: Option<Arc<str>>,
+    /// The spread fragment the usage is in, if it isn't in the operation
+    pub fragment: Option<Arc<str>>,
+    /// The usage in the operation, or the top-level spread reaching it
+    pub range: DiagnosticRange,
+}
+
+impl VariableFlow {
+    /// The non-null type the variable can be declared with instead, when
+    /// it's nullable without a default but every usage requires a value:
+    /// `String!` for a `String` only passed where `String!` is expected.
+    #[must_use]
+    pub fn narrower_type(&self) -> Option<String> {
+        if self.has_default || self.declared_type.ends_with('!') || self.usages.is_empty() {
+            return None;
+        }
+        let narrower = format!("{}!", self.declared_type);
+        self.usages
+            .iter()
+            .all(|usage| usage.expected_type.as_deref() == Some(narrower.as_str()))
+            .then_some(narrower)
+    }
+}
+
+/// Direct variable usages of a single fragment, resolved against the schema.
+///
+/// Nested spreads are returned rather than followed so callers can walk the
+/// fragment graph with their own cycle detection, while each fragment's
+/// usages stay cached independently.
+#[salsa::tracked]
+#[allow(clippy::needless_pass_by_value)] // Arc<str> needed for Salsa tracking
+pub fn fragment_variable_usages(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+    fragment_name: Arc<str>,
+) -> Arc<FragmentVariables> {
+    let fragment_index = graphql_hir::fragment_file_index(db, project_files);
+    let Some((content, metadata)) = fragment_index.get(&fragment_name) else {
+        return Arc::new(FragmentVariables::default());
+    };
+
+    let parse = graphql_syntax::parse(db, *content, *metadata);
+    let types =
+        graphql_hir::document_schema_types(db, project_files, metadata.file_id(db), *content);
+    let directives = graphql_hir::schema_directives(db, project_files);
+
+    for doc in parse.documents() {
+        for definition in doc.tree.document().definitions() {
+            let cst::Definition::FragmentDefinition(frag) = definition else {
+                continue;
+            };
+            let is_target = frag
+                .fragment_name()
+                .and_then(|n| n.name())
+                .is_some_and(|n| n.text() == fragment_name.as_ref());
+            if !is_target {
+                continue;
+            }
+
+            let type_condition = frag
+                .type_condition()
+                .and_then(|tc| tc.named_type())
+                .and_then(|nt| nt.name())
+                .map(|n| n.text().to_string());
+
+            let mut collector = UsageCollector::new(types, directives);
+            collector.collect_directives(frag.directives());
+            collector.collect_selection_set(frag.selection_set(), type_condition.as_deref());
+
+            return Arc::new(FragmentVariables {
+                usages: collector.usages.into_iter().map(|u| u.usage).collect(),
+                fragment_spreads: collector.spreads.into_iter().map(|s| s.name).collect(),
+            });
+        }
+    }
+
+    Arc::new(FragmentVariables::default())
+}
+
+/// Check every operation in a file for unused and undeclared variables.
+///
+/// Variables are followed through fragment spreads transitively (and across
+/// files), so a variable consumed only inside a spread fragment counts as used,
+/// and a variable a fragment needs but the operation never declares is
+/// reported on the spread that pulls it in.
+#[salsa::tracked]
+pub fn variable_flow_diagnostics(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Arc<Vec<Diagnostic>> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let types =
+        graphql_hir::document_schema_types(db, project_files, metadata.file_id(db), content);
+    let directives = graphql_hir::schema_directives(db, project_files);
+    let mut diagnostics = Vec::new();
+
+    for doc in parse.documents() {
+        let block = BlockPositions {
+            source: doc.source,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        };
+
+        for definition in doc.tree.document().definitions() {
+            if let cst::Definition::OperationDefinition(op) = definition {
+                check_operation(
+                    db,
+                    project_files,
+                    &op,
+                    types,
+                    directives,
+                    &block,
+                    &mut diagnostics,
+                );
+            }
+        }
+    }
+
+    Arc::new(diagnostics)
+}
+
+/// Every operation variable declared in a file, with the sites it flows
+/// into through the operation and the fragments it spreads.
+///
+/// Built from the same walk as `variable_flow_diagnostics`, so a variable
+/// without usages here is the one the `unused-variable` check reports.
+#[salsa::tracked]
+pub fn variable_flows(
+    db: &dyn GraphQLAnalysisDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Arc<Vec<VariableFlow>> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let types =
+        graphql_hir::document_schema_types(db, project_files, metadata.file_id(db), content);
+    let directives = graphql_hir::schema_directives(db, project_files);
+    let mut flows = Vec::new();
+
+    for doc in parse.documents() {
+        let block = BlockPositions {
+            source: doc.source,
+            line_index: doc.line_index(),
+            origin: doc.origin(),
+        };
+
+        for definition in doc.tree.document().definitions() {
+            let cst::Definition::OperationDefinition(op) = definition else {
+                continue;
+            };
+            let operation: Option<Arc<str>> = op.name().map(|n| Arc::from(n.text().as_str()));
+            let variables =
+                collect_operation_variables(db, project_files, &op, types, directives, &block);
+
+            for var in &variables.declared {
+                let direct = variables
+                    .collector
+                    .usages
+                    .iter()
+                    .filter(|located| located.usage.name.as_ref() == var.name)
+                    .map(|located| VariableSite {
+                        target: located.usage.target.clone(),
+                        expected_type: located.usage.expected_type.clone(),
+                        fragment: None,
+                        range: block.range(located.start, located.end),
+                    });
+                let through_fragments = variables
+                    .fragment_usages
+                    .iter()
+                    .filter(|(_, _, usage)| usage.name.as_ref() == var.name)
+                    .map(|(spread_index, fragment_name, usage)| {
+                        let spread = &variables.collector.spreads[*spread_index];
+                        VariableSite {
+                            target: usage.target.clone(),
+                            expected_type: usage.expected_type.clone(),
+                            fragment: Some(fragment_name.clone()),
+                            range: block.range(spread.start, spread.end),
+                        }
+                    });
+
+                flows.push(VariableFlow {
+                    operation: operation.clone(),
+                    name: Arc::from(var.name.as_str()),
+                    declared_type: Arc::from(var.type_text.as_str()),
+                    has_default: var.has_default,
+                    range: block.range(var.name_start, var.name_end),
+                    usages: direct.chain(through_fragments).collect(),
+                });
+            }
+        }
+    }
+
+    Arc::new(flows)
+}
+
+/// Returns true for apollo-compiler diagnostics that `variable_flow_diagnostics`
+/// reports itself (with fixes), so `validate_file` can drop the duplicates.
+pub(crate) fn is_superseded_variable_message(message: &str) -> bool {
+    message.starts_with("unused variable")
+        || (message.starts_with("variable `$") && message.ends_with("is not defined"))
+}
+
+/// Converts block-relative byte offsets to file positions
+pub(crate) struct BlockPositions<'a> {
+    pub(crate) source: &'a str,
+    pub(crate) line_index: graphql_syntax::LineIndex,
+    pub(crate) origin: graphql_syntax::BlockOrigin,
+}
+
+impl BlockPositions<'_> {
+    fn position(&self, offset: usize) -> Position {
+        let (line, character) = self.line_index.line_col(offset);
+        let (line, character) = self.origin.to_file(line as u32, character as u32);
+        Position { line, character }
+    }
+
+    pub(crate) fn range(&self, start: usize, end: usize) -> DiagnosticRange {
+        DiagnosticRange::new(self.position(start), self.position(end))
+    }
+}
+
+/// A variable declared in an operation's variable definitions
+struct DeclaredVariable {
+    name: String,
+    /// The declared type with whitespace removed
+    type_text: String,
+    has_default: bool,
+    start: usize,
+    end: usize,
+    /// The `$name` part of the definition
+    name_start: usize,
+    name_end: usize,
+}
+
+/// A variable usage found in the operation body, with its location
+struct LocatedUsage {
+    usage: VariableUsage,
+    start: usize,
+    end: usize,
+}
+
+/// A fragment spread found in the operation body, with its location
+struct LocatedSpread {
+    name: Arc<str>,
+    start: usize,
+    end: usize,
+}
+
+/// The input type expected at a value position
+#[derive(Clone, Copy)]
+enum ExpectedType<'a> {
+    /// The value is the whole argument / input field
+    Whole(&'a TypeRef),
+    /// The value is an item inside a list-typed argument / input field
+    ListItem(&'a TypeRef),
+}
+
+impl<'a> ExpectedType<'a> {
+    const fn type_ref(self) -> &'a TypeRef {
+        match self {
+            Self::Whole(type_ref) | Self::ListItem(type_ref) => type_ref,
+        }
+    }
+
+    /// The expected type of the items of a list value at this position
+    const fn item(self) -> Option<Self> {
+        match self {
+            Self::Whole(type_ref) if type_ref.is_list => Some(Self::ListItem(type_ref)),
+            _ => None,
+        }
+    }
+
+    /// Whether a value at this position is itself a list
+    const fn is_list(self) -> bool {
+        matches!(self, Self::Whole(type_ref) if type_ref.is_list)
+    }
+
+    fn render(self) -> String {
+        let type_ref = self.type_ref();
+        let mut result = type_ref.name.to_string();
+        match self {
+            Self::Whole(_) => {
+                if type_ref.is_list {
+                    if type_ref.inner_non_null {
+                        result.push('!');
+                    }
+                    result = format!("[{result}]");
+                }
+                if type_ref.is_non_null {
+                    result.push('!');
+                }
+            }
+            Self::ListItem(_) => {
+                if type_ref.inner_non_null {
+                    result.push('!');
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Walks selection sets while tracking the parent type, recording each
+/// variable usage with the type expected at that position.
+struct UsageCollector<'a> {
+    types: &'a TypeDefMap,
+    directives: &'a DirectiveDefMap,
+    usages: Vec<LocatedUsage>,
+    spreads: Vec<LocatedSpread>,
+}
+
+impl<'a> UsageCollector<'a> {
+    const fn new(types: &'a TypeDefMap, directives: &'a DirectiveDefMap) -> Self {
+        Self {
+            types,
+            directives,
+            usages: Vec::new(),
+            spreads: Vec::new(),
+        }
+    }
+
+    fn collect_selection_set(
+        &mut self,
+        selection_set: Option<cst::SelectionSet>,
+        parent_type: Option<&str>,
+    ) {
+        let Some(selection_set) = selection_set else {
+            return;
+        };
+        let types = self.types;
+
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let field_name = field.name().map(|n| n.text().to_string());
+                    let field_def = parent_type
+                        .and_then(|parent| types.get(parent))
+                        .zip(field_name.as_deref())
+                        .and_then(|(type_def, name)| {
+                            type_def.fields.iter().find(|f| f.name.as_ref() == name)
+                        });
+
+                    if let Some(arguments) = field.arguments() {
+                        for argument in arguments.arguments() {
+                            let arg_name = argument.name().map(|n| n.text().to_string());
+                            let expected = field_def
+                                .and_then(|f| {
+                                    f.arguments
+                                        .iter()
+                                        .find(|a| Some(a.name.as_ref()) == arg_name.as_deref())
+                                })
+                                .map(|a| ExpectedType::Whole(&a.type_ref));
+                            let target: Option<Arc<str>> = field_name
+                                .as_deref()
+                                .zip(arg_name.as_deref())
+                                .map(|(field_name, arg_name)| {
+                                    let target = match parent_type {
+                                        Some(parent) => {
+                                            format!("{parent}.{field_name}({arg_name}:)")
+                                        }
+                                        None => format!("{field_name}({arg_name}:)"),
+                                    };
+                                    Arc::from(target)
+                                });
+                            if let Some(value) = argument.value() {
+                                self.collect_value(&value, expected, target.as_ref());
+                            }
+                        }
+                    }
+
+                    self.collect_directives(field.directives());
+                    self.collect_selection_set(
+                        field.selection_set(),
+                        field_def.map(|f| f.type_ref.name.as_ref()),
+                    );
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    self.collect_directives(spread.directives());
+                    if let Some(name) = spread.fragment_name().and_then(|n| n.name()) {
+                        let range = spread.syntax().text_range();
+                        self.spreads.push(LocatedSpread {
+                            name: Arc::from(name.text().to_string()),
+                            start: range.start().into(),
+                            end: range.end().into(),
+                        });
+                    }
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    self.collect_directives(inline.directives());
+                    let type_condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|nt| nt.name())
+                        .map(|n| n.text().to_string());
+                    self.collect_selection_set(
+                        inline.selection_set(),
+                        type_condition.as_deref().or(parent_type),
+                    );
+                }
+            }
+        }
+    }
+
+    fn collect_directives(&mut self, directives: Option<cst::Directives>) {
+        let Some(directives) = directives else {
+            return;
+        };
+        let directive_defs = self.directives;
+
+        for directive in directives.directives() {
+            let directive_name = directive.name().map(|n| n.text().to_string());
+            let directive_def = directive_name
+                .as_deref()
+                .and_then(|name| directive_defs.get(name));
+            let Some(arguments) = directive.arguments() else {
+                continue;
+            };
+            for argument in arguments.arguments() {
+                let arg_name = argument.name().map(|n| n.text().to_string());
+                let expected = directive_def
+                    .and_then(|d| {
+                        d.arguments
+                            .iter()
+                            .find(|a| Some(a.name.as_ref()) == arg_name.as_deref())
+                    })
+                    .map(|a| ExpectedType::Whole(&a.type_ref));
+                let target: Option<Arc<str>> = directive_name
+                    .as_deref()
+                    .zip(arg_name.as_deref())
+                    .map(|(directive_name, arg_name)| {
+                        Arc::from(format!("@{directive_name}({arg_name}:)"))
+                    });
+                if let Some(value) = argument.value() {
+                    self.collect_value(&value, expected, target.as_ref());
+                }
+            }
+        }
+    }
+
+    fn collect_value(
+        &mut self,
+        value: &cst::Value,
+        expected: Option<ExpectedType<'a>>,
+        target: Option<&Arc<str>>,
+    ) {
+        match value {
+            cst::Value::Variable(variable) => {
+                let Some(name) = variable.name() else {
+                    return;
+                };
+                let range = variable.syntax().text_range();
+                self.usages.push(LocatedUsage {
+                    usage: VariableUsage {
+                        name: Arc::from(name.text().to_string()),
+                        expected_type: expected.map(|e| Arc::from(e.render())),
+                        target: target.cloned(),
+                    },
+                    start: range.start().into(),
+                    end: range.end().into(),
+                });
+            }
+            cst::Value::ListValue(list) => {
+                // A single item may be passed where a list is expected, so
+                // only descend into the item type when a list is expected.
+                let item = expected.and_then(ExpectedType::item);
+                for item_value in list.values() {
+                    self.collect_value(&item_value, item, target);
+                }
+            }
+            cst::Value::ObjectValue(object) => {
+                let types = self.types;
+                let input_type = expected
+                    .filter(|e| !e.is_list())
+                    .and_then(|e| types.get(e.type_ref().name.as_ref()));
+                for object_field in object.object_fields() {
+                    let field_name = object_field.name().map(|n| n.text().to_string());
+                    let field_expected = input_type
+                        .and_then(|t| {
+                            t.fields
+                                .iter()
+                                .find(|f| Some(f.name.as_ref()) == field_name.as_deref())
+                        })
+                        .map(|f| ExpectedType::Whole(&f.type_ref));
+                    let field_target: Option<Arc<str>> = input_type
+                        .zip(field_name.as_deref())
+                        .map(|(t, field_name)| Arc::from(format!("{}.{field_name}", t.name)));
+                    if let Some(field_value) = object_field.value() {
+                        self.collect_value(&field_value, field_expected, field_target.as_ref());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An operation's declared variables and everything it passes them to
+struct OperationVariables<'a> {
+    declared: Vec<DeclaredVariable>,
+    collector: UsageCollector<'a>,
+    /// Usages in spread fragments, each with its fragment and the index of
+    /// the top-level spread that reaches it
+    fragment_usages: Vec<(usize, Arc<str>, VariableUsage)>,
+}
+
+fn collect_operation_variables<'a>(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+    op: &cst::OperationDefinition,
+    types: &'a TypeDefMap,
+    directives: &'a DirectiveDefMap,
+    block: &BlockPositions<'_>,
+) -> OperationVariables<'a> {
+    let mut declared = Vec::new();
+    if let Some(variable_definitions) = op.variable_definitions() {
+        for variable_def in variable_definitions.variable_definitions() {
+            let Some(variable) = variable_def.variable() else {
+                continue;
+            };
+            let Some(name) = variable.name() else {
+                continue;
+            };
+            let range = variable_def.syntax().text_range();
+            let name_range = variable.syntax().text_range();
+            let start: usize = range.start().into();
+            declared.push(DeclaredVariable {
+                name: name.text().to_string(),
+                type_text: variable_def
+                    .ty()
+                    .map(|ty| ty.syntax().text().to_string())
+                    .unwrap_or_default()
+                    .split_whitespace()
+                    .collect(),
+                has_default: variable_def.default_value().is_some(),
+                start,
+                end: significant_end(block.source, variable_def.syntax()),
+                name_start: name_range.start().into(),
+                name_end: name_range.end().into(),
+            });
+        }
+    }
+
+    let mut collector = UsageCollector::new(types, directives);
+    collector.collect_directives(op.directives());
+    collector.collect_selection_set(
+        op.selection_set(),
+        Some(op.operation_kind().root_type_name()),
+    );
+
+    // Follow spreads transitively. Each fragment is visited once per
+    // operation; its usages are attributed to the first top-level spread
+    // that reaches it so undeclared variables can point at that spread.
+    let mut fragment_usages: Vec<(usize, Arc<str>, VariableUsage)> = Vec::new();
+    let mut visited: HashSet<Arc<str>> = HashSet::new();
+    for (spread_index, spread) in collector.spreads.iter().enumerate() {
+        let mut queue = VecDeque::from([spread.name.clone()]);
+        while let Some(fragment_name) = queue.pop_front() {
+            if !visited.insert(fragment_name.clone()) {
+                continue;
+            }
+            let fragment_vars = fragment_variable_usages(db, project_files, fragment_name.clone());
+            for usage in &fragment_vars.usages {
+                fragment_usages.push((spread_index, fragment_name.clone(), usage.clone()));
+            }
+            queue.extend(fragment_vars.fragment_spreads.iter().cloned());
+        }
+    }
+
+    OperationVariables {
+        declared,
+        collector,
+        fragment_usages,
+    }
+}
+
+fn check_operation(
+    db: &dyn GraphQLAnalysisDatabase,
+    project_files: ProjectFiles,
+    op: &cst::OperationDefinition,
+    types: &TypeDefMap,
+    directives: &DirectiveDefMap,
+    block: &BlockPositions<'_>,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let operation_label = op.name().map_or_else(
+        || "the anonymous operation".to_string(),
+        |n| format!("operation `{}`", n.text()),
+    );
+
+    let OperationVariables {
+        declared,
+        collector,
+        fragment_usages,
+    } = collect_operation_variables(db, project_files, op, types, directives, block);
+
+    let used: HashSet<&str> = collector
+        .usages
+        .iter()
+        .map(|u| u.usage.name.as_ref())
+        .chain(fragment_usages.iter().map(|(_, _, u)| u.name.as_ref()))
+        .collect();
+
+    for (index, var) in declared.iter().enumerate() {
+        if used.contains(var.name.as_str()) {
+            continue;
+        }
+        let mut diagnostic = Diagnostic::error(
+            format!(
+                "Variable `${}` is declared by {operation_label} but never used",
+                var.name
+            ),
+            block.range(var.start, var.end),
+        );
+        diagnostic.code = Some("unused-variable".into());
+        diagnostic.fix = Some(removal_fix(&declared, index, op, block));
+        diagnostic.help = Some("Remove the variable or use it in an argument".into());
+        diagnostic.tags.push(DiagnosticTag::Unnecessary);
+        diagnostics.push(diagnostic);
+    }
+
+    let declared_names: HashSet<&str> = declared.iter().map(|v| v.name.as_str()).collect();
+    let mut reported: HashSet<&str> = HashSet::new();
+
+    for located in &collector.usages {
+        let name = located.usage.name.as_ref();
+        if declared_names.contains(name) || !reported.insert(name) {
+            continue;
+        }
+        let mut diagnostic = Diagnostic::error(
+            format!("Variable `${name}` is not defined by {operation_label}"),
+            block.range(located.start, located.end),
+        );
+        diagnostic.code = Some("undefined-variable".into());
+        diagnostic.fix = declaration_fix(name, &collector, &fragment_usages, op, block);
+        diagnostic.help = Some("Declare the variable in the operation's variable list".into());
+        diagnostics.push(diagnostic);
+    }
+
+    for (spread_index, fragment_name, usage) in &fragment_usages {
+        let name = usage.name.as_ref();
+        if declared_names.contains(name) || !reported.insert(name) {
+            continue;
+        }
+        let spread = &collector.spreads[*spread_index];
+        let mut diagnostic = Diagnostic::error(
+            format!(
+                "Variable `${name}` is used by fragment `{fragment_name}` but not defined by {operation_label}"
+            ),
+            block.range(spread.start, spread.end),
+        );
+        diagnostic.code = Some("undefined-variable".into());
+        diagnostic.fix = declaration_fix(name, &collector, &fragment_usages, op, block);
+        diagnostic.help = Some("Declare the variable in the operation's variable list".into());
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Fix that removes the variable at `index`, along with the separator that
+/// joins it to its neighbours. The parentheses go too when it's the only one.
+fn removal_fix(
+    declared: &[DeclaredVariable],
+    index: usize,
+    op: &cst::OperationDefinition,
+    block: &BlockPositions<'_>,
+) -> CodeFix {
+    let var = &declared[index];
+    let (start, end) = if declared.len() == 1 {
+        op.variable_definitions()
+            .map_or((var.start, var.end), |defs| {
+                let start: usize = defs.syntax().text_range().start().into();
+                (start, significant_end(block.source, defs.syntax()))
+            })
+    } else if let Some(next) = declared.get(index + 1) {
+        (var.start, next.start)
+    } else {
+        (declared[index - 1].end, var.end)
+    };
+
+    CodeFix {
+        label: format!("Remove unused variable `${}`", var.name),
+        edits: vec![TextEdit {
+            range: block.range(start, end),
+            new_text: String::new(),
+        }],
+    }
+}
+
+/// Fix that declares a missing variable using the type expected where it's
+/// used. Non-null expectations win so the declaration satisfies every site.
+/// Returns `None` when no usage site has a known type.
+fn declaration_fix(
+    name: &str,
+    collector: &UsageCollector<'_>,
+    fragment_usages: &[(usize, Arc<str>, VariableUsage)],
+    op: &cst::OperationDefinition,
+    block: &BlockPositions<'_>,
+) -> Option<CodeFix> {
+    let candidates: Vec<&str> = collector
+        .usages
+        .iter()
+        .map(|u| &u.usage)
+        .chain(fragment_usages.iter().map(|(_, _, u)| u))
+        .filter(|u| u.name.as_ref() == name)
+        .filter_map(|u| u.expected_type.as_deref())
+        .collect();
+    let type_name = candidates
+        .iter()
+        .find(|t| t.ends_with('!'))
+        .or_else(|| candidates.first())?;
+
+    let declaration = format!("${name}: {type_name}");
+    let edit = graphql_syntax::edit::add_variable_definition(block.source, op, &declaration);
+
+    Some(CodeFix {
+        label: format!("Declare variable `${name}: {type_name}`"),
+        edits: vec![TextEdit {
+            range: block.range(edit.offset_range.start, edit.offset_range.end),
+            new_text: edit.new_text,
+        }],
+    })
+}
@@ -7,7 +7,10 @@ use graphql_analysis::{
     find_unused_fragments, lint_integration, merged_schema::merged_schema_with_diagnostics,
     validate_document_file, validate_file, FieldCoverageReport, TypeCoverage,
 };
-use graphql_base_db::{DocumentKind, FileContent, FileId, FileMetadata, FileUri, Language};
+use graphql_base_db::{
+    CancellationToken, Cancelled, DocumentKind, FileContent, FileId, FileMetadata, FileUri,
+    Language,
+};
 use graphql_test_utils::{create_project_files, TestDatabase, TestDatabaseWithProject};
 use std::sync::Arc;
 
@@ -956,6 +959,177 @@ fn test_schema_build_error_attributed_to_correct_file() {
     );
 }
 
+fn composition_diagnostics_for(
+    files: &[(&str, &str)],
+) -> std::collections::HashMap<Arc<str>, Vec<graphql_analysis::Diagnostic>> {
+    let mut db = TestDatabase::default();
+    let schema_files: Vec<_> = files
+        .iter()
+        .enumerate()
+        .map(|(i, (uri, text))| {
+            let file_id = FileId::new(i as u32);
+            let content = FileContent::new(&db, Arc::from(*text));
+            let metadata = FileMetadata::new(
+                &db,
+                file_id,
+                FileUri::new(*uri),
+                Language::GraphQL,
+                DocumentKind::Schema,
+            );
+            (file_id, content, metadata)
+        })
+        .collect();
+    let project_files = create_project_files(&mut db, &schema_files, &[]);
+    let result = merged_schema_with_diagnostics(&db, project_files);
+    (*result.diagnostics_by_file).clone()
+}
+
+#[test]
+fn test_duplicate_type_across_files_reports_both_locations() {
+    let diagnostics = composition_diagnostics_for(&[
+        (
+            "file:///a.graphql",
+            "type Query { user: User }\ntype User { id: ID! }",
+        ),
+        ("file:///b.graphql", "type User { name: String }"),
+    ]);
+
+    let duplicates: Vec<_> = diagnostics
+        .values()
+        .flatten()
+        .filter(|d| d.code.as_deref() == Some("duplicate-type"))
+        .collect();
+    assert_eq!(duplicates.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        duplicates[0].message.as_ref(),
+        "Type `User` is already defined; use `extend type User` to add to it"
+    );
+    assert!(diagnostics["file:///b.graphql"].contains(duplicates[0]));
+    assert_eq!(duplicates[0].range.start.character, 5);
+
+    let related = &duplicates[0].related[0];
+    assert_eq!(related.uri.as_ref(), "file:///a.graphql");
+    assert_eq!(related.range.start.line, 1);
+    assert_eq!(related.range.start.character, 5);
+}
+
+#[test]
+fn test_duplicate_members_through_extensions() {
+    let diagnostics = composition_diagnostics_for(&[
+        (
+            "file:///schema.graphql",
+            "type Query { role: Role search: SearchResult }\n\
+             enum Role { ADMIN USER }\n\
+             union SearchResult = Query\n\
+             input Filter { name: String }\n\
+             interface Node { id: ID! }\n\
+             scalar Date",
+        ),
+        (
+            "file:///extensions.graphql",
+            "extend enum Role { ADMIN GUEST }\n\
+             extend union SearchResult = Query\n\
+             extend input Filter { name: String }\n\
+             extend interface Node { id: ID! }\n\
+             extend scalar Date @specifiedBy(url: \"https://example.com\")\n\
+             extend type Query { role: Role }",
+        ),
+    ]);
+
+    let mut messages: Vec<&str> = diagnostics["file:///extensions.graphql"]
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("duplicate-member"))
+        .map(|d| d.message.as_ref())
+        .collect();
+    messages.sort_unstable();
+    assert_eq!(
+        messages,
+        [
+            "Field `id` is already defined on `Node`",
+            "Field `name` is already defined on `Filter`",
+            "Field `role` is already defined on `Query`",
+            "Member `Query` is already part of `SearchResult`",
+            "Value `ADMIN` is already defined on `Role`",
+        ]
+    );
+    assert!(diagnostics["file:///extensions.graphql"]
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("duplicate-member"))
+        .all(|d| d.related.len() == 1 && d.related[0].uri.as_ref() == "file:///schema.graphql"));
+}
+
+#[test]
+fn test_interface_implementation_checks_fields_and_arguments() {
+    let diagnostics = composition_diagnostics_for(&[
+        (
+            "file:///interfaces.graphql",
+            "type Query { node: Node }\n\
+             interface Node { id: ID! owner: Node posts(first: Int): [Post] name: String }\n\
+             type Post { id: ID! }",
+        ),
+        (
+            "file:///user.graphql",
+            "type User implements Node {\n\
+             \x20 id: ID\n\
+             \x20 owner: User!\n\
+             \x20 posts(first: String, after: String!): [Post!]\n\
+             }",
+        ),
+    ]);
+
+    assert!(
+        !diagnostics.contains_key("file:///interfaces.graphql"),
+        "got: {diagnostics:?}"
+    );
+    let user: Vec<_> = diagnostics["file:///user.graphql"]
+        .iter()
+        .filter(|d| {
+            d.code
+                .as_deref()
+                .is_some_and(|c| c.ends_with("interface-field"))
+        })
+        .collect();
+    let mut found: Vec<(&str, u32, &str)> = user
+        .iter()
+        .map(|d| {
+            (
+                d.code.as_deref().unwrap_or_default(),
+                d.range.start.line,
+                d.message.as_ref(),
+            )
+        })
+        .collect();
+    found.sort_unstable();
+    assert_eq!(
+        found,
+        [
+            (
+                "incompatible-interface-field",
+                1,
+                "`User.id` returns `ID`, which isn't `ID!` or a subtype of it, so it doesn't implement `Node.id`"
+            ),
+            (
+                "incompatible-interface-field",
+                3,
+                "Argument `after` of `User.posts` must be optional, since `Node.posts` doesn't declare it"
+            ),
+            (
+                "incompatible-interface-field",
+                3,
+                "Argument `first` of `User.posts` has type `String`, but `Node.posts` declares it as `Int`"
+            ),
+            (
+                "missing-interface-field",
+                0,
+                "`User` doesn't declare field `name: String` of interface `Node`"
+            ),
+        ]
+    );
+    assert!(user.iter().all(|d| d.related.len() == 1
+        && d.related[0].uri.as_ref() == "file:///interfaces.graphql"
+        && d.related[0].range.start.line == 1));
+}
+
 // ============================================================================
 // project_lints tests (from project_lints.rs) - public API only
 // ============================================================================
@@ -1547,7 +1721,7 @@ fn test_relay_arguments_directive_errors_suppressed() {
     // Should NOT contain "is not supported by @arguments" errors
     let arg_errors: Vec<_> = diagnostics
         .iter()
-        .filter(|d| d.message.contains("is not supported by `@arguments`"))
+        .filter(|d| d.message.contains("on directive `@arguments`"))
         .collect();
 
     assert!(
@@ -1561,10 +1735,7 @@ fn test_relay_arguments_directive_errors_suppressed() {
 
     let arg_def_errors: Vec<_> = frag_diagnostics
         .iter()
-        .filter(|d| {
-            d.message
-                .contains("is not supported by `@argumentDefinitions`")
-        })
+        .filter(|d| d.message.contains("on directive `@argumentDefinitions`"))
         .collect();
 
     assert!(
@@ -1618,7 +1789,10 @@ fn test_non_relay_unknown_directive_args_still_reported() {
 
     let arg_errors: Vec<_> = diagnostics
         .iter()
-        .filter(|d| d.message.contains("is not supported by `@skip`"))
+        .filter(|d| {
+            d.message
+                .contains("Unknown argument `badArg` on directive `@skip`")
+        })
         .collect();
 
     assert!(
@@ -2034,6 +2208,96 @@ fn test_issue_644_body_edit_does_not_revalidate_other_files() {
     );
 }
 
+#[test]
+fn test_schema_field_edit_does_not_revalidate_documents() {
+    use graphql_test_utils::tracking::{queries, TrackedDatabase};
+    use salsa::Setter;
+
+    let mut db = TrackedDatabase::default();
+
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(&db, Arc::from("type Query { user: User }"));
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+
+    let user_id = FileId::new(1);
+    let user_content = FileContent::new(&db, Arc::from("type User { id: ID }"));
+    let user_metadata = FileMetadata::new(
+        &db,
+        user_id,
+        FileUri::new("user.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+
+    let doc_id = FileId::new(2);
+    let doc_content = FileContent::new(
+        &db,
+        Arc::from("query GetUser($filter: UserFilter) { user { id } }"),
+    );
+    let doc_metadata = FileMetadata::new(
+        &db,
+        doc_id,
+        FileUri::new("query.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+
+    let project_files = create_tracked_project_files(
+        &db,
+        &[
+            (schema_id, schema_content, schema_metadata),
+            (user_id, user_content, user_metadata),
+        ],
+        &[(doc_id, doc_content, doc_metadata)],
+    );
+
+    let initial = validate_document_file(&db, doc_content, doc_metadata, project_files);
+    assert!(
+        initial.iter().any(|d| d.message.contains("UserFilter")),
+        "Expected an unknown type diagnostic for UserFilter, got {initial:?}"
+    );
+
+    // Adding a field leaves every type name and kind unchanged
+    let checkpoint = db.checkpoint();
+    user_content
+        .set_text(&mut db)
+        .to(Arc::from("type User { id: ID name: String }"));
+    let _ = validate_document_file(&db, doc_content, doc_metadata, project_files);
+
+    assert_eq!(
+        db.count_since(queries::FILE_TYPE_KINDS, checkpoint),
+        1,
+        "Only the edited file's type kinds should be recomputed"
+    );
+    assert_eq!(
+        db.count_since(queries::VALIDATE_DOCUMENT_FILE, checkpoint),
+        0,
+        "A field edit should not re-run document validation"
+    );
+
+    // Defining a type the document refers to does change the outcome
+    let checkpoint = db.checkpoint();
+    user_content.set_text(&mut db).to(Arc::from(
+        "type User { id: ID name: String } input UserFilter { id: ID }",
+    ));
+    let after = validate_document_file(&db, doc_content, doc_metadata, project_files);
+
+    assert_eq!(
+        db.count_since(queries::VALIDATE_DOCUMENT_FILE, checkpoint),
+        1
+    );
+    assert!(
+        !after.iter().any(|d| d.message.contains("UserFilter")),
+        "UserFilter is now defined, got {after:?}"
+    );
+}
+
 #[test]
 #[allow(clippy::similar_names)]
 fn test_issue_644_structural_edit_only_affects_dependent_files() {
@@ -3052,3 +3316,1106 @@ fn test_unused_ignore_all_rules_unused_in_multi_rule() {
         unused[0].message
     );
 }
+
+#[test]
+fn test_file_level_disable_suppresses_every_operation() {
+    let db = LintTestDatabase::default();
+
+    let source = "query { user { id } }\n\n# graphql-lint-disable noAnonymousOperations, noDeprecated\n\nquery { user { id } }";
+    let diags = lint_test_file(&db, source);
+    assert!(
+        !diags
+            .iter()
+            .any(|d| d.code.as_deref() == Some("noAnonymousOperations")),
+        "Expected both anonymous operations to be suppressed, got: {diags:?}"
+    );
+
+    let unused: Vec<_> = diags
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("unused_ignore"))
+        .collect();
+    assert_eq!(
+        unused.len(),
+        1,
+        "Expected noDeprecated to be unused, got: {diags:?}"
+    );
+    assert_eq!(
+        unused[0].message.as_ref(),
+        "Unused rule 'noDeprecated' in graphql-lint-disable directive"
+    );
+}
+
+// ============================================================================
+// variable flow tests (from variable_flow.rs)
+// ============================================================================
+
+const VARIABLE_FLOW_SCHEMA: &str = "type Query { user(id: ID!): User users(ids: [ID!]): [User] }\n\
+     type User { id: ID! name: String! avatar(size: Int): String }";
+
+/// Build a project with the variable flow schema and the given document files,
+/// returning the first document's content and metadata.
+fn variable_flow_project(
+    db: &mut TestDatabase,
+    documents: &[(&str, &str)],
+) -> (FileContent, FileMetadata, graphql_base_db::ProjectFiles) {
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(&*db, Arc::from(VARIABLE_FLOW_SCHEMA));
+    let schema_metadata = FileMetadata::new(
+        &*db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+
+    let docs: Vec<_> = documents
+        .iter()
+        .enumerate()
+        .map(|(i, (uri, text))| {
+            let id = FileId::new(i as u32 + 1);
+            let content = FileContent::new(&*db, Arc::from(*text));
+            let metadata = FileMetadata::new(
+                &*db,
+                id,
+                FileUri::new(*uri),
+                Language::GraphQL,
+                DocumentKind::Executable,
+            );
+            (id, content, metadata)
+        })
+        .collect();
+
+    let project_files =
+        create_project_files(db, &[(schema_id, schema_content, schema_metadata)], &docs);
+    (docs[0].1, docs[0].2, project_files)
+}
+
+#[test]
+fn test_unused_variable_reported_once_with_removal_fix() {
+    let mut db = TestDatabase::default();
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[(
+            "file:///query.graphql",
+            "query GetUser($id: ID!, $unused: String) { user(id: $id) { id } }",
+        )],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let unused: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("$unused"))
+        .collect();
+    assert_eq!(
+        unused.len(),
+        1,
+        "Expected exactly one diagnostic for $unused, got: {diagnostics:?}"
+    );
+    assert_eq!(
+        unused[0].message.as_ref(),
+        "Variable `$unused` is declared by operation `GetUser` but never used"
+    );
+
+    let fix = unused[0]
+        .fix
+        .as_ref()
+        .expect("unused variable should have a fix");
+    assert_eq!(fix.edits.len(), 1);
+    assert_eq!(fix.edits[0].new_text, "");
+    // Removes `, $unused: String` (from the end of `$id: ID!` to the end of `String`)
+    assert_eq!(fix.edits[0].range.start.character, 22);
+    assert_eq!(fix.edits[0].range.end.character, 39);
+}
+
+#[test]
+fn test_variable_used_only_in_cross_file_fragment_is_not_unused() {
+    let mut db = TestDatabase::default();
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[
+            (
+                "file:///query.graphql",
+                "query GetUser($id: ID!, $size: Int) { user(id: $id) { ...UserAvatar } }",
+            ),
+            (
+                "file:///fragments.graphql",
+                "fragment UserAvatar on User { ...UserSized }\n\
+                 fragment UserSized on User { avatar(size: $size) }",
+            ),
+        ],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    assert!(
+        !diagnostics.iter().any(|d| d.message.contains("$size")),
+        "$size is used through a nested fragment spread, got: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_fragment_variable_missing_from_operation_is_reported_on_spread() {
+    let mut db = TestDatabase::default();
+    let query = "query GetUser { user(id: \"1\") { ...UserAvatar } }";
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[
+            ("file:///query.graphql", query),
+            (
+                "file:///fragments.graphql",
+                "fragment UserAvatar on User { avatar(size: $size) }",
+            ),
+        ],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let missing: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("$size"))
+        .collect();
+    assert_eq!(missing.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        missing[0].message.as_ref(),
+        "Variable `$size` is used by fragment `UserAvatar` but not defined by operation `GetUser`"
+    );
+    let spread_start = query.find("...UserAvatar").unwrap() as u32;
+    assert_eq!(missing[0].range.start.character, spread_start);
+
+    let fix = missing[0]
+        .fix
+        .as_ref()
+        .expect("missing variable should have a fix");
+    assert_eq!(fix.label, "Declare variable `$size: Int`");
+    assert_eq!(fix.edits[0].new_text, "($size: Int)");
+    assert_eq!(
+        fix.edits[0].range.start.character,
+        "query GetUser".len() as u32
+    );
+}
+
+#[test]
+fn test_undeclared_variable_fix_appends_to_existing_definitions() {
+    let mut db = TestDatabase::default();
+    let query = "query GetUsers($size: Int) { users(ids: [$id]) { avatar(size: $size) } }";
+    let (content, metadata, project_files) =
+        variable_flow_project(&mut db, &[("file:///query.graphql", query)]);
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let missing: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("$id"))
+        .collect();
+    assert_eq!(missing.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        missing[0].message.as_ref(),
+        "Variable `$id` is not defined by operation `GetUsers`"
+    );
+
+    // `$id` sits inside a `[ID!]` list, so the declaration uses the item type
+    let fix = missing[0]
+        .fix
+        .as_ref()
+        .expect("missing variable should have a fix");
+    assert_eq!(fix.edits[0].new_text, ", $id: ID!");
+    assert_eq!(
+        fix.edits[0].range.start.character,
+        "query GetUsers($size: Int".len() as u32
+    );
+}
+
+#[test]
+fn test_fragment_variable_usages_handles_cycles() {
+    let mut db = TestDatabase::default();
+    let (_, _, project_files) = variable_flow_project(
+        &mut db,
+        &[(
+            "file:///fragments.graphql",
+            "fragment A on User { avatar(size: $size) ...B }\nfragment B on User { ...A }",
+        )],
+    );
+
+    let usages = graphql_analysis::fragment_variable_usages(&db, project_files, Arc::from("A"));
+    assert_eq!(usages.usages.len(), 1);
+    assert_eq!(usages.usages[0].name.as_ref(), "size");
+    assert_eq!(usages.usages[0].expected_type.as_deref(), Some("Int"));
+    assert_eq!(usages.fragment_spreads, vec![Arc::<str>::from("B")]);
+}
+
+// ============================================================================
+// literal validation tests (from literal_validation.rs)
+// ============================================================================
+
+const LITERAL_SCHEMA: &str =
+    "type Query { users(ids: [ID!], filter: UserFilter, first: Int): [User] }\n\
+     input UserFilter { roles: [Role!] name: String }\n\
+     enum Role { ADMIN USER }\n\
+     type User { id: ID! }";
+
+fn literal_diagnostics_for(document: &str) -> Vec<graphql_analysis::Diagnostic> {
+    let mut db = TestDatabase::default();
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(&db, Arc::from(LITERAL_SCHEMA));
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+    let doc_id = FileId::new(1);
+    let doc_content = FileContent::new(&db, Arc::from(document));
+    let doc_metadata = FileMetadata::new(
+        &db,
+        doc_id,
+        FileUri::new("file:///query.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let project_files = create_project_files(
+        &mut db,
+        &[(schema_id, schema_content, schema_metadata)],
+        &[(doc_id, doc_content, doc_metadata)],
+    );
+    validate_file(&db, doc_content, doc_metadata, project_files).to_vec()
+}
+
+#[test]
+fn test_list_literal_items_are_reported_individually() {
+    let source = "query { users(ids: [1, true, \"3\"]) { id } }";
+    let diagnostics = literal_diagnostics_for(source);
+
+    assert_eq!(
+        diagnostics.len(),
+        1,
+        "Expected one diagnostic for the boolean item, got: {diagnostics:?}"
+    );
+    assert_eq!(
+        diagnostics[0].message.as_ref(),
+        "Expected a value of type `ID`, found a boolean"
+    );
+    let start = source.find("true").unwrap() as u32;
+    assert_eq!(diagnostics[0].range.start.character, start);
+    assert_eq!(diagnostics[0].range.end.character, start + 4);
+}
+
+#[test]
+fn test_string_where_int_expected_is_reported_on_the_value() {
+    let source = "query { users(first: \"10\", filter: { name: 1 }) { id } }";
+    let diagnostics = literal_diagnostics_for(source);
+    let found: Vec<(&str, u32, u32)> = diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.message.as_ref(),
+                d.range.start.character,
+                d.range.end.character,
+            )
+        })
+        .collect();
+
+    let first = source.find("\"10\"").unwrap() as u32;
+    let name = source.find("1 }").unwrap() as u32;
+    assert_eq!(
+        found,
+        [
+            (
+                "Expected a value of type `Int`, found a string",
+                first,
+                first + 4
+            ),
+            (
+                "Expected a value of type `String`, found an integer",
+                name,
+                name + 1
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_single_value_coerces_to_list() {
+    let diagnostics =
+        literal_diagnostics_for("query { users(ids: 1, filter: { roles: ADMIN }) { id } }");
+    assert!(
+        diagnostics.is_empty(),
+        "Single values should coerce to lists, got: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_list_where_single_value_expected() {
+    let source = "query { users(first: [1]) { id } }";
+    let diagnostics = literal_diagnostics_for(source);
+    let list_errors: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("found a list"))
+        .collect();
+    assert_eq!(list_errors.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        list_errors[0].message.as_ref(),
+        "Expected a single value of type `Int`, found a list"
+    );
+    assert_eq!(
+        list_errors[0].range.start.character,
+        source.find("[1]").unwrap() as u32
+    );
+}
+
+#[test]
+fn test_enum_items_inside_input_object_lists() {
+    let source = "query { users(filter: { roles: [ADMIN, OWNER, \"USER\"] }) { id } }";
+    let diagnostics = literal_diagnostics_for(source);
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_ref()).collect();
+
+    assert!(
+        messages.contains(&"`OWNER` is not a value of enum `Role`"),
+        "got: {messages:?}"
+    );
+    assert!(
+        messages.contains(
+            &"Expected a value of enum `Role`, found a string; enum values are written without quotes"
+        ),
+        "got: {messages:?}"
+    );
+    let owner = diagnostics
+        .iter()
+        .find(|d| d.message.contains("OWNER"))
+        .unwrap();
+    assert_eq!(
+        owner.range.start.character,
+        source.find("OWNER").unwrap() as u32
+    );
+}
+
+// ============================================================================
+// field merging tests (from field_merging.rs)
+// ============================================================================
+
+fn conflicts(diagnostics: &[graphql_analysis::Diagnostic]) -> Vec<&graphql_analysis::Diagnostic> {
+    diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("conflicting-fields"))
+        .collect()
+}
+
+#[test]
+fn test_alias_selecting_different_fields_conflicts() {
+    let mut db = TestDatabase::default();
+    let query = "query { user(id: \"1\") { name: id name } }";
+    let (content, metadata, project_files) =
+        variable_flow_project(&mut db, &[("file:///query.graphql", query)]);
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let found = conflicts(&diagnostics);
+    assert_eq!(found.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        found[0].message.as_ref(),
+        "Fields `name` conflict because `id` and `name` are different fields"
+    );
+    assert!(
+        !diagnostics.iter().any(|d| d.message.contains("same alias")),
+        "apollo-compiler's duplicate should be dropped, got: {diagnostics:?}"
+    );
+    assert_eq!(found[0].related.len(), 1);
+    assert_eq!(
+        found[0].related[0].range.start.character,
+        query.find("name: id").unwrap() as u32
+    );
+}
+
+#[test]
+fn test_differing_arguments_through_cross_file_fragment() {
+    let mut db = TestDatabase::default();
+    let query = "query { user(id: \"1\") { avatar(size: 1) ...UserAvatar } }";
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[
+            ("file:///query.graphql", query),
+            (
+                "file:///fragments.graphql",
+                "fragment UserAvatar on User { avatar(size: 2) }",
+            ),
+        ],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let found = conflicts(&diagnostics);
+    assert_eq!(found.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        found[0].message.as_ref(),
+        "Fields `avatar` conflict because they have differing arguments"
+    );
+    // Reported on the spread that brings the fragment's field in
+    assert_eq!(
+        found[0].range.start.character,
+        query.find("...UserAvatar").unwrap() as u32
+    );
+    assert!(
+        found[0]
+            .related
+            .iter()
+            .any(|r| r.uri.as_ref() == "file:///fragments.graphql"),
+        "got: {:?}",
+        found[0].related
+    );
+}
+
+#[test]
+fn test_conflicting_subfields_are_reported_on_parent() {
+    let mut db = TestDatabase::default();
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[(
+            "file:///query.graphql",
+            "query { u: user(id: \"1\") { id } u: user(id: \"1\") { id: name } }",
+        )],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    let found = conflicts(&diagnostics);
+    assert_eq!(found.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        found[0].message.as_ref(),
+        "Fields `u` conflict because subfields `id` conflict because `id` and `name` are different fields"
+    );
+}
+
+#[test]
+fn test_identical_fields_from_fragment_merge() {
+    let mut db = TestDatabase::default();
+    let (content, metadata, project_files) = variable_flow_project(
+        &mut db,
+        &[
+            (
+                "file:///query.graphql",
+                "query { user(id: \"1\") { name avatar(size: 1) ...UserName } }",
+            ),
+            (
+                "file:///fragments.graphql",
+                "fragment UserName on User { name avatar(size: 1) }",
+            ),
+        ],
+    );
+
+    let diagnostics = validate_file(&db, content, metadata, project_files);
+    assert!(conflicts(&diagnostics).is_empty(), "got: {diagnostics:?}");
+}
+
+// ============================================================================
+// argument validation tests (from literal_validation.rs)
+// ============================================================================
+
+fn argument_diagnostics_for(query: &str) -> Vec<graphql_analysis::Diagnostic> {
+    let mut db = TestDatabase::default();
+    let (content, metadata, project_files) =
+        variable_flow_project(&mut db, &[("file:///query.graphql", query)]);
+    validate_file(&db, content, metadata, project_files).to_vec()
+}
+
+#[test]
+fn test_missing_required_argument_reported_on_field_name() {
+    let query = "query { user { id } }";
+    let diagnostics = argument_diagnostics_for(query);
+    let missing: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("missing-required-argument"))
+        .collect();
+    assert_eq!(missing.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        missing[0].message.as_ref(),
+        "Missing required argument `id: ID!` on field `Query.user`"
+    );
+    let start = query.find("user").unwrap() as u32;
+    assert_eq!(missing[0].range.start.character, start);
+    assert_eq!(missing[0].range.end.character, start + 4);
+    assert!(
+        !diagnostics
+            .iter()
+            .any(|d| d.message.contains("is not provided")),
+        "apollo-compiler's duplicate should be dropped, got: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_unknown_argument_reported_on_argument_name() {
+    let query = "query { users(ids: [\"1\"], limit: 2) { id } }";
+    let diagnostics = argument_diagnostics_for(query);
+    let unknown: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("unknown-argument"))
+        .collect();
+    assert_eq!(unknown.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        unknown[0].message.as_ref(),
+        "Unknown argument `limit` on field `Query.users`"
+    );
+    assert_eq!(
+        unknown[0].range.start.character,
+        query.find("limit").unwrap() as u32
+    );
+}
+
+#[test]
+fn test_variable_type_mismatch_reported_on_variable() {
+    let query = "query GetUser($id: String) { user(id: $id) { id } }";
+    let diagnostics = argument_diagnostics_for(query);
+    let mismatches: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.message.contains("can't be used where"))
+        .collect();
+    assert_eq!(mismatches.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        mismatches[0].message.as_ref(),
+        "Variable `$id` of type `String` can't be used where `ID!` is expected"
+    );
+    assert_eq!(
+        mismatches[0].range.start.character,
+        query.rfind("$id").unwrap() as u32
+    );
+    assert!(
+        !diagnostics
+            .iter()
+            .any(|d| d.message.contains("cannot be used for argument")),
+        "apollo-compiler's duplicate should be dropped, got: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_nullable_variable_in_list_item_needs_default() {
+    let diagnostics =
+        argument_diagnostics_for("query GetUsers($id: ID) { users(ids: [$id]) { id } }");
+    assert!(
+        diagnostics.iter().any(|d| d.message.as_ref()
+            == "Variable `$id` of type `ID` can't be used where `ID!` is expected"),
+        "got: {diagnostics:?}"
+    );
+
+    let diagnostics =
+        argument_diagnostics_for("query GetUsers($id: ID = \"1\") { users(ids: [$id]) { id } }");
+    assert!(
+        !diagnostics
+            .iter()
+            .any(|d| d.message.contains("can't be used where")),
+        "A variable with a default may fill a non-null position, got: {diagnostics:?}"
+    );
+}
+
+#[test]
+fn test_import_comments_scope_fragment_spreads() {
+    let mut db = TestDatabase::default();
+    let file = |db: &TestDatabase, id: u32, uri: &str, text: &str, kind: DocumentKind| {
+        let file_id = FileId::new(id);
+        let content = FileContent::new(db, Arc::from(text));
+        let metadata = FileMetadata::new(db, file_id, FileUri::new(uri), Language::GraphQL, kind);
+        (file_id, content, metadata)
+    };
+
+    let schema = file(
+        &db,
+        0,
+        "file:///app/schema.graphql",
+        "type Query { user: User } type User { id: ID! name: String! }",
+        DocumentKind::Schema,
+    );
+    let user = file(
+        &db,
+        1,
+        "file:///app/fragments/user.graphql",
+        "fragment UserFields on User { id }",
+        DocumentKind::Executable,
+    );
+    let names = file(
+        &db,
+        2,
+        "file:///app/fragments/names.graphql",
+        "fragment NameFields on User { name }",
+        DocumentKind::Executable,
+    );
+    let query = file(
+        &db,
+        3,
+        "file:///app/query.graphql",
+        "# import UserFields, Missing from \"./fragments/user.graphql\"\n\
+         # import \"./nowhere.graphql\"\n\
+         query Q { user { ...UserFields ...NameFields } }",
+        DocumentKind::Executable,
+    );
+    let project_files = create_project_files(&mut db, &[schema], &[user, names, query]);
+
+    let diagnostics = file_validation_diagnostics(&db, query.1, query.2, Some(project_files));
+    let code = |code: &str| {
+        diagnostics
+            .iter()
+            .filter(|d| d.code.as_deref() == Some(code))
+            .collect::<Vec<_>>()
+    };
+
+    let missing = code("missing-import");
+    assert_eq!(missing.len(), 1, "{diagnostics:?}");
+    assert!(missing[0].message.contains("NameFields"));
+    let fix = missing[0].fix.as_ref().unwrap();
+    assert_eq!(fix.edits[0].range.start.line, 2);
+    assert_eq!(
+        fix.edits[0].new_text,
+        "# import NameFields from \"./fragments/names.graphql\"\n"
+    );
+
+    assert_eq!(code("unknown-import").len(), 1);
+    assert_eq!(code("unresolved-import").len(), 1);
+}
+
+// ============================================================================
+// fragment spread validation tests (from spread_validation.rs)
+// ============================================================================
+
+const SPREAD_SCHEMA: &str = "type Query { pet: Pet dog: Dog cat: Cat search: SearchResult }\n\
+     interface Pet { name: String }\n\
+     type Dog implements Pet { name: String barks: Boolean }\n\
+     type Cat implements Pet { name: String meows: Boolean }\n\
+     type Human { name: String }\n\
+     union SearchResult = Dog | Human";
+
+fn spread_diagnostics_for(document: &str) -> Vec<graphql_analysis::Diagnostic> {
+    let mut db = TestDatabase::default();
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(&db, Arc::from(SPREAD_SCHEMA));
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+    let doc_id = FileId::new(1);
+    let doc_content = FileContent::new(&db, Arc::from(document));
+    let doc_metadata = FileMetadata::new(
+        &db,
+        doc_id,
+        FileUri::new("file:///query.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let project_files = create_project_files(
+        &mut db,
+        &[(schema_id, schema_content, schema_metadata)],
+        &[(doc_id, doc_content, doc_metadata)],
+    );
+    validate_file(&db, doc_content, doc_metadata, project_files)
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("impossible-fragment-spread"))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_impossible_inline_fragment_reported_on_type_condition() {
+    let query = "query { cat { ... on Dog { barks } } }";
+    let diagnostics = spread_diagnostics_for(query);
+    assert_eq!(diagnostics.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message.as_ref(),
+        "Fragment on `Dog` cannot be spread on type `Cat`"
+    );
+    let start = query.find("Dog").unwrap() as u32;
+    assert_eq!(diagnostics[0].range.start.character, start);
+    assert_eq!(diagnostics[0].range.end.character, start + 3);
+}
+
+#[test]
+fn test_impossible_named_spread_reported_on_fragment_name() {
+    let query = "query { cat { ...DogFields } }\nfragment DogFields on Dog { barks }";
+    let diagnostics = spread_diagnostics_for(query);
+    assert_eq!(diagnostics.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message.as_ref(),
+        "Fragment `DogFields` on `Dog` cannot be spread on type `Cat`"
+    );
+    assert_eq!(
+        diagnostics[0].range.start.character,
+        query.find("DogFields").unwrap() as u32
+    );
+}
+
+#[test]
+fn test_abstract_spreads_with_overlapping_types_are_possible() {
+    let query = "query {\n\
+         pet { ... on Dog { barks } ... on Cat { meows } }\n\
+         search { ... on Pet { name } ... on Human { name } }\n\
+         dog { ... on Pet { name } }\n\
+         }";
+    let diagnostics = spread_diagnostics_for(query);
+    assert!(diagnostics.is_empty(), "got: {diagnostics:?}");
+}
+
+#[test]
+fn test_impossible_spreads_on_abstract_types() {
+    let query = "query {\n\
+         pet { ... on Human { name } }\n\
+         search { ... on Cat { meows } }\n\
+         }";
+    let diagnostics = spread_diagnostics_for(query);
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_ref()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Fragment on `Human` cannot be spread on type `Pet`",
+            "Fragment on `Cat` cannot be spread on type `SearchResult`",
+        ]
+    );
+}
+
+// ============================================================================
+// subscription root field tests (from subscription_validation.rs)
+// ============================================================================
+
+const SUBSCRIPTION_SCHEMA: &str = "type Query { ok: Boolean }\n\
+     type Subscription { messageAdded: Message userJoined: String }\n\
+     type Message { id: ID! body: String }";
+
+fn subscription_diagnostics_for(
+    document: &str,
+    fragments: &str,
+) -> Vec<graphql_analysis::Diagnostic> {
+    let mut db = TestDatabase::default();
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(&db, Arc::from(SUBSCRIPTION_SCHEMA));
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+    let doc_id = FileId::new(1);
+    let doc_content = FileContent::new(&db, Arc::from(document));
+    let doc_metadata = FileMetadata::new(
+        &db,
+        doc_id,
+        FileUri::new("file:///subscription.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let fragments_id = FileId::new(2);
+    let fragments_content = FileContent::new(&db, Arc::from(fragments));
+    let fragments_metadata = FileMetadata::new(
+        &db,
+        fragments_id,
+        FileUri::new("file:///fragments.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let project_files = create_project_files(
+        &mut db,
+        &[(schema_id, schema_content, schema_metadata)],
+        &[
+            (doc_id, doc_content, doc_metadata),
+            (fragments_id, fragments_content, fragments_metadata),
+        ],
+    );
+    let diagnostics = validate_file(&db, doc_content, doc_metadata, project_files);
+    assert!(
+        !diagnostics
+            .iter()
+            .any(|d| d.code.is_none() && d.message.contains("root field")),
+        "apollo-compiler's report should be superseded: {diagnostics:?}"
+    );
+    diagnostics
+        .iter()
+        .filter(|d| d.code.as_deref() == Some("subscription-root-field"))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_subscription_single_root_field() {
+    let diagnostics = subscription_diagnostics_for(
+        "subscription OnMessage { messageAdded { id } messageAdded { body } }",
+        "",
+    );
+    assert!(diagnostics.is_empty(), "got: {diagnostics:?}");
+
+    let subscription = "subscription OnMessage { messageAdded { id } other: messageAdded { id } }";
+    let diagnostics = subscription_diagnostics_for(subscription, "");
+    assert_eq!(diagnostics.len(), 1, "got: {diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message.as_ref(),
+        "Subscription `OnMessage` must select exactly one root field, but selects 2: `messageAdded`, `other`"
+    );
+    assert_eq!(
+        diagnostics[0].range.start.character,
+        subscription.find('{').unwrap() as u32
+    );
+    assert_eq!(
+        diagnostics[0].range.end.character,
+        subscription.len() as u32
+    );
+}
+
+#[test]
+fn test_subscription_root_fields_follow_fragments_across_files() {
+    let diagnostics = subscription_diagnostics_for(
+        "subscription { ...Events }",
+        "fragment Events on Subscription { messageAdded { id } ... on Subscription { userJoined } }",
+    );
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_ref()).collect();
+    assert_eq!(
+        messages,
+        vec!["Anonymous subscription must select exactly one root field, but selects 2: `messageAdded`, `userJoined`"]
+    );
+}
+
+#[test]
+fn test_subscription_introspection_root_field() {
+    let diagnostics = subscription_diagnostics_for(
+        "subscription OnType { ...TypeName }",
+        "fragment TypeName on Subscription { __typename }",
+    );
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_ref()).collect();
+    assert_eq!(
+        messages,
+        vec!["Subscription `OnType` cannot select introspection field `__typename` as its root field"]
+    );
+}
+
+// ============================================================================
+// directive usage tests (from directive_validation.rs)
+// ============================================================================
+
+const DIRECTIVE_SCHEMA: &str = "directive @tag(name: String) repeatable on FIELD | OBJECT\n\
+     directive @live on QUERY\n\
+     type Query { user: User }\n\
+     type User @tag(name: \"a\") @tag(name: \"b\") { id: ID! name: String }";
+
+/// Directive diagnostics for `source`, as a schema file or a document
+/// against `DIRECTIVE_SCHEMA`, with the code and the text they cover.
+fn directive_diagnostics_for(source: &str, kind: DocumentKind) -> Vec<(String, String)> {
+    let mut db = TestDatabase::default();
+    let schema_id = FileId::new(0);
+    let schema_text = if kind == DocumentKind::Schema {
+        format!("{DIRECTIVE_SCHEMA}\n{source}")
+    } else {
+        DIRECTIVE_SCHEMA.to_string()
+    };
+    let schema_content = FileContent::new(&db, Arc::from(schema_text.as_str()));
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+    let doc_id = FileId::new(1);
+    let doc_content = FileContent::new(&db, Arc::from(source));
+    let doc_metadata = FileMetadata::new(
+        &db,
+        doc_id,
+        FileUri::new("file:///query.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let documents = if kind == DocumentKind::Schema {
+        vec![]
+    } else {
+        vec![(doc_id, doc_content, doc_metadata)]
+    };
+    let project_files = create_project_files(
+        &mut db,
+        &[(schema_id, schema_content, schema_metadata)],
+        &documents,
+    );
+
+    let (diagnostics, text) = if kind == DocumentKind::Schema {
+        (
+            file_validation_diagnostics(&db, schema_content, schema_metadata, Some(project_files)),
+            schema_text,
+        )
+    } else {
+        (
+            file_validation_diagnostics(&db, doc_content, doc_metadata, Some(project_files)),
+            source.to_string(),
+        )
+    };
+    assert!(
+        !diagnostics
+            .iter()
+            .any(|d| d.code.is_none() && d.message.contains("directive")),
+        "apollo-compiler's report should be superseded: {diagnostics:?}"
+    );
+    let lines: Vec<&str> = text.lines().collect();
+    diagnostics
+        .iter()
+        .filter(|d| {
+            d.code.as_deref().is_some_and(|code| {
+                [
+                    "unknown-directive",
+                    "misplaced-directive",
+                    "repeated-directive",
+                ]
+                .contains(&code)
+            })
+        })
+        .map(|d| {
+            assert_eq!(d.range.start.line, d.range.end.line);
+            let line = lines[d.range.start.line as usize];
+            let covered = &line[d.range.start.character as usize..d.range.end.character as usize];
+            (d.code.as_deref().unwrap().to_string(), covered.to_string())
+        })
+        .collect()
+}
+
+#[test]
+fn test_directive_in_wrong_executable_location() {
+    let diagnostics = directive_diagnostics_for(
+        "query Q @include(if: true) { user @live { id } }",
+        DocumentKind::Executable,
+    );
+    assert_eq!(
+        diagnostics,
+        vec![
+            ("misplaced-directive".to_string(), "@include".to_string()),
+            ("misplaced-directive".to_string(), "@live".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_directive_in_wrong_schema_location() {
+    let diagnostics = directive_diagnostics_for(
+        "type Extra @include(if: true) { id: ID @deprecated }",
+        DocumentKind::Schema,
+    );
+    assert_eq!(
+        diagnostics,
+        vec![("misplaced-directive".to_string(), "@include".to_string())]
+    );
+}
+
+#[test]
+fn test_non_repeatable_directive_used_twice() {
+    let diagnostics = directive_diagnostics_for(
+        "query Q($skip: Boolean!) { user @skip(if: $skip) @skip(if: true) @tag(name: \"x\") @tag(name: \"y\") { id } }",
+        DocumentKind::Executable,
+    );
+    // `@tag` is repeatable; only the second `@skip` is reported
+    assert_eq!(
+        diagnostics,
+        vec![("repeated-directive".to_string(), "@skip".to_string())]
+    );
+}
+
+#[test]
+fn test_unknown_directive_reported_on_name() {
+    let diagnostics = directive_diagnostics_for(
+        "query Q { user @cached(ttl: 60, scope: PRIVATE) { id } }",
+        DocumentKind::Executable,
+    );
+    assert_eq!(
+        diagnostics,
+        vec![("unknown-directive".to_string(), "@cached".to_string())]
+    );
+}
+
+// ============================================================================
+// project lint cancellation tests
+// ============================================================================
+
+/// Lint test database whose Salsa event hook checks the request's
+/// cancellation token, as the IDE database does. It cancels `token` as soon
+/// as a query executes off the thread that created it, so only the rayon
+/// threads running the project rules can notice.
+#[salsa::db]
+#[derive(Clone)]
+struct CancellingLintDatabase {
+    storage: salsa::Storage<Self>,
+}
+
+impl CancellingLintDatabase {
+    fn new(token: CancellationToken) -> Self {
+        let test_thread = std::thread::current().id();
+        Self {
+            storage: salsa::Storage::new(Some(Box::new(move |event: salsa::Event| {
+                match event.kind {
+                    salsa::EventKind::WillExecute { .. }
+                        if std::thread::current().id() != test_thread =>
+                    {
+                        token.cancel();
+                    }
+                    salsa::EventKind::WillCheckCancellation => {
+                        graphql_base_db::cancellation::unwind_if_cancelled();
+                    }
+                    _ => {}
+                }
+            }))),
+        }
+    }
+}
+
+#[salsa::db]
+impl salsa::Database for CancellingLintDatabase {}
+
+#[salsa::db]
+impl graphql_syntax::GraphQLSyntaxDatabase for CancellingLintDatabase {}
+
+#[salsa::db]
+impl graphql_hir::GraphQLHirDatabase for CancellingLintDatabase {}
+
+#[salsa::db]
+impl graphql_analysis::GraphQLAnalysisDatabase for CancellingLintDatabase {
+    fn lint_config(&self) -> Arc<graphql_linter::LintConfig> {
+        Arc::new(graphql_linter::LintConfig::recommended())
+    }
+}
+
+#[test]
+fn test_cancelled_token_stops_parallel_project_lint() {
+    let token = CancellationToken::new();
+    let mut db = CancellingLintDatabase::new(token.clone());
+
+    let schema_id = FileId::new(0);
+    let schema_content = FileContent::new(
+        &db,
+        Arc::from("type Query { user: User } type User { id: ID! name: String }"),
+    );
+    let schema_metadata = FileMetadata::new(
+        &db,
+        schema_id,
+        FileUri::new("file:///schema.graphql"),
+        Language::GraphQL,
+        DocumentKind::Schema,
+    );
+    let documents: Vec<_> = (1..=4)
+        .map(|i| {
+            let file_id = FileId::new(i);
+            let content = FileContent::new(
+                &db,
+                Arc::from(format!("fragment Unused{i} on User {{ id name }}").as_str()),
+            );
+            let metadata = FileMetadata::new(
+                &db,
+                file_id,
+                FileUri::new(format!("file:///doc{i}.graphql")),
+                Language::GraphQL,
+                DocumentKind::Executable,
+            );
+            (file_id, content, metadata)
+        })
+        .collect();
+    let project_files = create_project_files(
+        &mut db,
+        &[(schema_id, schema_content, schema_metadata)],
+        &documents,
+    );
+
+    let result = token.run(|| {
+        lint_integration::project_lint_diagnostics(&db, Some(project_files));
+    });
+
+    assert!(
+        token.is_cancelled(),
+        "project rules should have run on the rayon pool"
+    );
+    assert_eq!(
+        result,
+        Err(Cancelled),
+        "the rayon threads should see the cancelled token and unwind"
+    );
+}
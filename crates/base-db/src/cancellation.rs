@@ -0,0 +1,126 @@
+//! Cooperative cancellation for snapshot queries.
+//!
+//! Salsa already unwinds every snapshot when the host is about to mutate an
+//! input: the next query call on the snapshot's thread panics with
+//! `salsa::Cancelled`. That covers edits, but not a client cancelling one
+//! request while the inputs stay the same, so a [`CancellationToken`] adds a
+//! per-request flag on top.
+//!
+//! The token is installed for the current thread by [`CancellationToken::run`].
+//! The database checks it on the same event Salsa uses for its own
+//! cancellation check, so both kinds unwind at the next query boundary and
+//! surface as [`Cancelled`]. Queries that fan work out to other threads carry
+//! the token along with [`CancellationToken::current`] and
+//! [`CancellationToken::install`].
+
+use std::cell::RefCell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+thread_local! {
+    static ACTIVE_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// A snapshot query stopped before finishing, either because the host is
+/// applying a change or because its [`CancellationToken`] was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Cancelled {
+    /// Run `f`, turning a cancellation unwind into `Err(Cancelled)`
+    ///
+    /// Any other panic keeps unwinding.
+    pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Cancelled> {
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(value) => Ok(value),
+            Err(payload) if payload.is::<Cancelled>() || payload.is::<salsa::Cancelled>() => {
+                Err(Cancelled)
+            }
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("query cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// A flag that stops the queries run under it
+///
+/// Clones share the flag, so one clone can be handed to the worker running
+/// the queries and another kept to cancel them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask queries running under this token to stop at their next query call
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Run `f` on this thread with the token installed
+    ///
+    /// Returns `Err(Cancelled)` if the token is cancelled or the snapshot's
+    /// revision is invalidated before `f` finishes.
+    pub fn run<T>(&self, f: impl FnOnce() -> T) -> Result<T, Cancelled> {
+        Cancelled::catch(|| self.install(f))
+    }
+
+    /// The token installed on this thread, if any
+    #[must_use]
+    pub fn current() -> Option<Self> {
+        ACTIVE_TOKEN.with(|active| active.borrow().clone())
+    }
+
+    /// Run `f` on this thread with the token installed, letting a
+    /// cancellation unwind out of it
+    ///
+    /// For work a query hands to other threads, such as a rayon pool's, so
+    /// it stops along with the query that started it.
+    #[must_use]
+    pub fn install<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = ACTIVE_TOKEN.with(|active| active.replace(Some(self.clone())));
+        // Restored on drop, so a panic that isn't a cancellation doesn't leave
+        // this token installed for the next job on the thread
+        let _restore = RestoreToken(previous);
+        f()
+    }
+}
+
+struct RestoreToken(Option<CancellationToken>);
+
+impl Drop for RestoreToken {
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        ACTIVE_TOKEN.with(|active| *active.borrow_mut() = previous);
+    }
+}
+
+/// Unwind with [`Cancelled`] if the token installed on this thread has been
+/// cancelled. Called from the database's Salsa event hook.
+pub fn unwind_if_cancelled() {
+    let cancelled = ACTIVE_TOKEN.with(|active| {
+        active
+            .borrow()
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    });
+    if cancelled {
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
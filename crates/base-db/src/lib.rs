@@ -13,6 +13,9 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+pub mod cancellation;
+
+pub use cancellation::{CancellationToken, Cancelled};
 // Re-export types from graphql-types
 pub use graphql_types::{DocumentKind, FileId, FileUri, Language};
 
@@ -87,6 +90,20 @@ pub struct ResolvedSchemaFileIds {
     pub ids: Arc<Vec<FileId>>,
 }
 
+/// Input: Schema variant file IDs, keyed by variant name (identity only).
+///
+/// Variants are alternate schemas (e.g. `staging`, or a separate admin API)
+/// that documents opt into with a `# graphql-analyzer: schema-variant <name>`
+/// pragma, or are assigned to by the variant's `documents` patterns. Their
+/// files are excluded from `SchemaFileIds` so they never merge into the
+/// project schema.
+#[salsa::input]
+pub struct SchemaVariantFileIds {
+    pub variants: Arc<HashMap<Arc<str>, Arc<Vec<FileId>>>>,
+    /// Documents matched by a variant's `documents` patterns
+    pub documents: Arc<HashMap<FileId, Arc<str>>>,
+}
+
 /// A single file's entry - bundles content and metadata as one Salsa input.
 /// This enables true per-file granular caching: when file A changes, only
 /// file A's FileEntry is updated, and queries for file B remain cached.
@@ -119,8 +136,9 @@ pub struct FileEntryMap {
 /// is what makes `Analysis` snapshots truly immutable: they observe the path
 /// table at their snapshot revision, not the host's live state.
 ///
-/// This input changes ONLY when files are added or removed. Content edits do
-/// not bump it, so path-lookup queries stay cached across keystroke edits.
+/// This input changes ONLY when files are added, removed or renamed. Content
+/// edits do not bump it, so path-lookup queries stay cached across keystroke
+/// edits.
 #[salsa::input]
 pub struct FilePathMap {
     /// URI string → FileId. Keys are `Arc<str>` so we can interchange with
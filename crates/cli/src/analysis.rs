@@ -62,7 +62,10 @@ impl CliAnalysisHost {
                         lint_config.is_enabled("uniqueNames"),
                         lint_config.is_enabled("noUnusedFields"),
                         lint_config.is_enabled("redundantFields"));
+                    let lint_plugins = graphql_linter::LintPlugins::load(&lint_config, base_dir)
+                        .map_err(|e| anyhow::anyhow!("Invalid lint configuration:\n\n{e}"))?;
                     host.set_lint_config(lint_config);
+                    host.set_lint_plugins(lint_plugins);
                 }
                 Err(e) => {
                     return Err(anyhow::anyhow!(
@@ -79,6 +82,35 @@ impl CliAnalysisHost {
             tracing::debug!("No lint configuration found in project config, using defaults");
         }
 
+        host.set_complexity_config(project_config.complexity().unwrap_or_default());
+        host.set_validation_config(project_config.validation().unwrap_or_default());
+        host.set_client_controlled_nullability(project_config.client_controlled_nullability());
+
+        if let Some(report_path) = project_config.operation_usage() {
+            let report_path = base_dir.join(report_path);
+            let report = graphql_config::OperationUsageReport::load(&report_path).map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to load operation usage report '{}': {e}",
+                    report_path.display()
+                )
+            })?;
+            host.set_operation_usage(Some(report));
+        }
+
+        if let Some(list_path) = project_config.persisted_operations() {
+            let list_path = base_dir.join(list_path);
+            let allow_list = graphql_config::PersistedOperationAllowList::load(&list_path)
+                .map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to load persisted operation allow-list '{}': {e}",
+                        list_path.display()
+                    )
+                })?;
+            host.set_persisted_operations(Some(allow_list));
+        }
+        host.set_n_plus_one_config(project_config.n_plus_one());
+        host.set_scalar_config(project_config.scalars());
+
         let extract_config = Self::resolve_extract_config(project_config)?;
         tracing::debug!(
             global_gql_identifier_name = ?extract_config.global_gql_identifier_name,
@@ -124,6 +156,10 @@ impl CliAnalysisHost {
                 let (language, _) = match path.extension().and_then(|e| e.to_str()) {
                     Some("ts" | "tsx") => (Language::TypeScript, DocumentKind::Executable),
                     Some("js" | "jsx") => (Language::JavaScript, DocumentKind::Executable),
+                    Some("vue") => (Language::Vue, DocumentKind::Executable),
+                    Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+                    Some("py") => (Language::Python, DocumentKind::Executable),
+                    Some("rb") => (Language::Ruby, DocumentKind::Executable),
                     _ => (Language::GraphQL, DocumentKind::Executable),
                 };
 
@@ -193,6 +229,10 @@ impl CliAnalysisHost {
                     {
                         Some("ts" | "tsx") => (Language::TypeScript, DocumentKind::Executable),
                         Some("js" | "jsx") => (Language::JavaScript, DocumentKind::Executable),
+                        Some("vue") => (Language::Vue, DocumentKind::Executable),
+                        Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+                        Some("py") => (Language::Python, DocumentKind::Executable),
+                        Some("rb") => (Language::Ruby, DocumentKind::Executable),
                         _ => (Language::GraphQL, DocumentKind::Executable),
                     };
                     document_files.push(path.clone());
@@ -225,38 +265,15 @@ impl CliAnalysisHost {
     fn load_document_files(
         documents_config: &graphql_config::DocumentsConfig,
         base_dir: &Path,
-        _project_config: &ProjectConfig,
+        project_config: &ProjectConfig,
     ) -> Result<Vec<(PathBuf, String)>> {
-        let patterns: Vec<_> = documents_config.patterns().into_iter().collect();
-        let mut file_paths = std::collections::HashSet::new();
-
-        for pattern in patterns {
-            let expanded = Self::expand_braces(pattern);
-
-            for expanded_pattern in expanded {
-                let full_pattern = base_dir.join(&expanded_pattern).display().to_string();
-
-                for entry in glob::glob(&full_pattern)
-                    .with_context(|| format!("Invalid glob pattern: {full_pattern}"))?
-                {
-                    match entry {
-                        Ok(path) if path.is_file() => {
-                            if path.components().any(|c| c.as_os_str() == "node_modules") {
-                                continue;
-                            }
-                            file_paths.insert(path);
-                        }
-                        Ok(_) => {}
-                        Err(e) => {
-                            return Err(anyhow::anyhow!("Glob error: {e}"));
-                        }
-                    }
-                }
-            }
+        let globs = project_config.file_globs(&documents_config.patterns());
+        if let Some(error) = globs.errors().first() {
+            return Err(anyhow::anyhow!("{error}"));
         }
 
         let mut files = Vec::new();
-        for path in file_paths {
+        for path in globs.walk(base_dir).paths() {
             let content = std::fs::read_to_string(&path)
                 .with_context(|| format!("Failed to read file: {}", path.display()))?;
             files.push((path, content));
@@ -283,25 +300,6 @@ impl CliAnalysisHost {
         ))
     }
 
-    /// Expand brace patterns like "src/**/*.{ts,tsx}" into separate patterns
-    fn expand_braces(pattern: &str) -> Vec<String> {
-        // Simple brace expansion - handles single brace group
-        if let Some(start) = pattern.find('{') {
-            if let Some(end) = pattern.find('}') {
-                let before = &pattern[..start];
-                let after = &pattern[end + 1..];
-                let options = &pattern[start + 1..end];
-
-                return options
-                    .split(',')
-                    .map(|opt| format!("{}{}{}", before, opt.trim(), after))
-                    .collect();
-            }
-        }
-
-        vec![pattern.to_string()]
-    }
-
     /// Get only validation diagnostics for all loaded files (excludes custom lint rules)
     ///
     /// Returns only GraphQL spec validation errors, not custom lint rule violations.
@@ -581,6 +579,76 @@ impl CliAnalysisHost {
         let snapshot = self.host.snapshot();
         snapshot.complexity_analysis()
     }
+
+    /// Every usage of a deprecated schema element in the project's documents
+    pub fn deprecated_usages(&self) -> Vec<graphql_ide::DeprecatedUsage> {
+        let snapshot = self.host.snapshot();
+        snapshot.deprecated_usages()
+    }
+
+    /// Every operation's persisted-query document, keyed by hash
+    pub fn persisted_operation_manifest(&self) -> std::collections::BTreeMap<String, String> {
+        let snapshot = self.host.snapshot();
+        snapshot.persisted_operation_manifest()
+    }
+
+    /// Compute the document edits that migrate the project across a set of
+    /// schema renames. Nothing is written to disk.
+    pub fn migrate_documents(
+        &self,
+        manifest: &graphql_ide::SchemaChangeManifest,
+    ) -> graphql_ide::MigrationResult {
+        let snapshot = self.host.snapshot();
+        snapshot.migrate_documents(manifest)
+    }
+
+    /// Compute the document edits that move every selection of
+    /// `type_name.old_field` to `new_field`. `None` when either field isn't
+    /// defined on the type.
+    pub fn migrate_field(
+        &self,
+        type_name: &str,
+        old_field: &str,
+        new_field: &str,
+    ) -> Option<graphql_ide::MigrationResult> {
+        self.host
+            .snapshot()
+            .migrate_field(type_name, old_field, new_field)
+    }
+
+    /// Schema SDL as the project composes it, for sending to a registry
+    pub fn schema_sdl(&self) -> String {
+        self.host
+            .snapshot()
+            .with_schema_types(graphql_ide::print_schema_sdl)
+    }
+
+    /// Place registry findings on the schema files defining what they
+    /// concern. Also returns the findings with no local definition.
+    pub fn schema_change_diagnostics(
+        &self,
+        findings: &[graphql_ide::SchemaChangeFinding],
+    ) -> (
+        HashMap<PathBuf, Vec<Diagnostic>>,
+        Vec<graphql_ide::SchemaChangeFinding>,
+    ) {
+        let mut result = self.host.snapshot().schema_change_diagnostics(findings);
+        let mut by_path = HashMap::new();
+        for path in &self.schema_files {
+            if let Some(diagnostics) = result.diagnostics.remove(&schema_file_path(path)) {
+                by_path.insert(path.clone(), diagnostics);
+            }
+        }
+        (by_path, result.unlocated)
+    }
+
+    /// Filesystem path of a loaded document
+    pub fn document_path(&self, file: &FilePath) -> Option<&Path> {
+        self.document_files
+            .iter()
+            .find(|path| &FilePath::from_path(path) == file)
+            .map(PathBuf::as_path)
+    }
 }
 
 /// Count fragment spreads in a selection set
@@ -621,31 +689,31 @@ mod tests {
 
     #[test]
     fn test_expand_braces_single_brace_group() {
-        let result = CliAnalysisHost::expand_braces("src/**/*.{ts,tsx}");
+        let result = graphql_config::expand_braces("src/**/*.{ts,tsx}");
         assert_eq!(result, vec!["src/**/*.ts", "src/**/*.tsx"]);
     }
 
     #[test]
     fn test_expand_braces_three_options() {
-        let result = CliAnalysisHost::expand_braces("**/*.{js,jsx,ts}");
+        let result = graphql_config::expand_braces("**/*.{js,jsx,ts}");
         assert_eq!(result, vec!["**/*.js", "**/*.jsx", "**/*.ts"]);
     }
 
     #[test]
     fn test_expand_braces_no_braces() {
-        let result = CliAnalysisHost::expand_braces("src/**/*.graphql");
+        let result = graphql_config::expand_braces("src/**/*.graphql");
         assert_eq!(result, vec!["src/**/*.graphql"]);
     }
 
     #[test]
     fn test_expand_braces_with_spaces() {
-        let result = CliAnalysisHost::expand_braces("src/**/*.{ts, tsx}");
+        let result = graphql_config::expand_braces("src/**/*.{ts, tsx}");
         assert_eq!(result, vec!["src/**/*.ts", "src/**/*.tsx"]);
     }
 
     #[test]
     fn test_expand_braces_single_option() {
-        let result = CliAnalysisHost::expand_braces("src/**/*.{graphql}");
+        let result = graphql_config::expand_braces("src/**/*.{graphql}");
         assert_eq!(result, vec!["src/**/*.graphql"]);
     }
 
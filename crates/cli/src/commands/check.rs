@@ -142,26 +142,30 @@ pub fn run(
 
     for (file_path, diagnostics) in validation_diagnostics {
         for diag in diagnostics {
-            if diag.severity == DiagnosticSeverity::Error {
-                all_issues.push(DiagnosticOutput {
-                    file_path: file_path.to_string_lossy().to_string(),
-                    line: (diag.range.start.line + 1) as usize,
-                    column: (diag.range.start.character + 1) as usize,
-                    end_line: (diag.range.end.line + 1) as usize,
-                    end_column: (diag.range.end.character + 1) as usize,
-                    message: diag.message.clone(),
-                    severity: "error".to_string(),
-                    source: DiagnosticSource::Validation,
-                    rule: diag.code.clone(),
-                    help: diag.help.clone(),
-                    url: diag.url.clone(),
-                    tags: diag.tags.clone(),
-                });
-                original_diagnostics
-                    .entry(file_path.clone())
-                    .or_default()
-                    .push(diag);
-            }
+            // Checks downgraded by the validation profile surface as warnings
+            let severity = match diag.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+                DiagnosticSeverity::Information | DiagnosticSeverity::Hint => continue,
+            };
+            all_issues.push(DiagnosticOutput {
+                file_path: file_path.to_string_lossy().to_string(),
+                line: (diag.range.start.line + 1) as usize,
+                column: (diag.range.start.character + 1) as usize,
+                end_line: (diag.range.end.line + 1) as usize,
+                end_column: (diag.range.end.character + 1) as usize,
+                message: diag.message.clone(),
+                severity: severity.to_string(),
+                source: DiagnosticSource::Validation,
+                rule: diag.code.clone(),
+                help: diag.help.clone(),
+                url: diag.url.clone(),
+                tags: diag.tags.clone(),
+            });
+            original_diagnostics
+                .entry(file_path.clone())
+                .or_default()
+                .push(diag);
         }
     }
 
@@ -6,8 +6,7 @@ use colored::Colorize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-/// A single usage of a deprecated field
-#[derive(Clone)]
+/// A single usage of a deprecated element
 struct DeprecatedUsage {
     file_path: String,
     line: usize,
@@ -56,7 +55,6 @@ pub fn run(
         println!("{}", "✓ Documents loaded successfully".green());
     }
 
-    // Get lint diagnostics and filter for noDeprecated rule
     let spinner = if matches!(format, OutputFormat::Human) {
         Some(crate::progress::spinner(
             "Scanning for deprecated usages...",
@@ -66,7 +64,7 @@ pub fn run(
     };
 
     let scan_start = std::time::Instant::now();
-    let all_diagnostics = host.all_lint_diagnostics();
+    let usages = host.deprecated_usages();
 
     if let Some(pb) = spinner {
         pb.finish_and_clear();
@@ -74,36 +72,30 @@ pub fn run(
 
     let scan_duration = scan_start.elapsed();
 
-    // Group deprecated usages by field/element name
+    // Group deprecated usages by schema coordinate
     let mut deprecated_elements: HashMap<String, DeprecatedElement> = HashMap::new();
 
-    for (file_path, diagnostics) in all_diagnostics {
-        for diag in diagnostics {
-            // Only process noDeprecated rule diagnostics
-            if diag.code.as_deref() != Some("noDeprecated") {
-                continue;
-            }
-
-            // Parse the diagnostic message to extract field name and reason
-            // Message format: "Field 'name' is deprecated" or "Field 'name' is deprecated: reason"
-            let (name, reason) = parse_deprecation_message(&diag.message);
-
-            let usage = DeprecatedUsage {
-                file_path: file_path.to_string_lossy().to_string(),
-                // Convert from 0-based to 1-based for display
-                line: (diag.range.start.line + 1) as usize,
-                column: (diag.range.start.character + 1) as usize,
-            };
-
-            deprecated_elements
-                .entry(name.clone())
-                .and_modify(|e| e.usages.push(usage.clone()))
-                .or_insert_with(|| DeprecatedElement {
-                    name,
-                    reason,
-                    usages: vec![usage],
-                });
-        }
+    for usage in usages {
+        let file_path = host.document_path(&usage.location.file).map_or_else(
+            || usage.location.file.as_str().to_string(),
+            |p| p.display().to_string(),
+        );
+        let location = DeprecatedUsage {
+            file_path,
+            // Convert from 0-based to 1-based for display
+            line: (usage.location.range.start.line + 1) as usize,
+            column: (usage.location.range.start.character + 1) as usize,
+        };
+
+        deprecated_elements
+            .entry(usage.coordinate.clone())
+            .or_insert_with(|| DeprecatedElement {
+                name: usage.coordinate,
+                reason: usage.reason,
+                usages: Vec::new(),
+            })
+            .usages
+            .push(location);
     }
 
     // Sort elements by name
@@ -192,66 +184,3 @@ pub fn run(
 
     Ok(())
 }
-
-/// Parse deprecation message to extract element name and reason
-fn parse_deprecation_message(message: &str) -> (String, Option<String>) {
-    // Message format examples:
-    // "Field 'legacyId' is deprecated"
-    // "Field 'legacyId' is deprecated: Use id instead"
-    // "Argument 'oldArg' is deprecated"
-    // "Enum value 'OLD_VALUE' is deprecated: Use NEW_VALUE"
-
-    // Extract the name between single quotes
-    let name = message
-        .split('\'')
-        .nth(1)
-        .map_or_else(|| "unknown".to_string(), String::from);
-
-    // Extract the reason after ": "
-    let reason = message.split(": ").nth(1).map(String::from);
-
-    (name, reason)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_parse_deprecation_message_with_reason() {
-        let (name, reason) =
-            parse_deprecation_message("Field 'legacyId' is deprecated: Use id instead");
-        assert_eq!(name, "legacyId");
-        assert_eq!(reason, Some("Use id instead".to_string()));
-    }
-
-    #[test]
-    fn test_parse_deprecation_message_without_reason() {
-        let (name, reason) = parse_deprecation_message("Field 'oldField' is deprecated");
-        assert_eq!(name, "oldField");
-        assert_eq!(reason, None);
-    }
-
-    #[test]
-    fn test_parse_deprecation_message_argument() {
-        let (name, reason) =
-            parse_deprecation_message("Argument 'oldArg' is deprecated: Use newArg");
-        assert_eq!(name, "oldArg");
-        assert_eq!(reason, Some("Use newArg".to_string()));
-    }
-
-    #[test]
-    fn test_parse_deprecation_message_enum_value() {
-        let (name, reason) =
-            parse_deprecation_message("Enum value 'OLD_VALUE' is deprecated: Use NEW_VALUE");
-        assert_eq!(name, "OLD_VALUE");
-        assert_eq!(reason, Some("Use NEW_VALUE".to_string()));
-    }
-
-    #[test]
-    fn test_parse_deprecation_message_no_quotes() {
-        let (name, reason) = parse_deprecation_message("Some other message format");
-        assert_eq!(name, "unknown");
-        assert_eq!(reason, None);
-    }
-}
@@ -0,0 +1,326 @@
+use crate::analysis::CliAnalysisHost;
+use crate::commands::common::CommandContext;
+use crate::OutputFormat;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use graphql_ide::{MigrationResult, SchemaChangeManifest, TextEdit};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// On-disk shape of a schema change manifest
+///
+/// ```json
+/// {
+///   "types": { "User": "Account" },
+///   "fields": { "User.fullName": "displayName" },
+///   "enumValues": { "Status.ACTIVE": "ENABLED" }
+/// }
+/// ```
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ManifestFile {
+    #[serde(default)]
+    types: HashMap<String, String>,
+    #[serde(default)]
+    fields: HashMap<String, String>,
+    #[serde(default)]
+    enum_values: HashMap<String, String>,
+}
+
+impl From<ManifestFile> for SchemaChangeManifest {
+    fn from(file: ManifestFile) -> Self {
+        Self {
+            types: file.types,
+            fields: file.fields,
+            enum_values: file.enum_values,
+        }
+    }
+}
+
+pub fn run(
+    config_path: Option<PathBuf>,
+    project_name: Option<&str>,
+    manifest_path: &Path,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let manifest = load_manifest(manifest_path)?;
+
+    // Load config and validate project requirement
+    let ctx = CommandContext::load(config_path, project_name, "migrate")?;
+    let project_config = ctx.get_project_config(project_name)?;
+
+    let spinner = if matches!(format, OutputFormat::Human) {
+        Some(crate::progress::spinner("Loading schema and documents..."))
+    } else {
+        None
+    };
+
+    let host = CliAnalysisHost::from_project_config(&project_config, &ctx.base_dir)?;
+    let result = host.migrate_documents(&manifest);
+
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+
+    apply_result(
+        &host,
+        &result,
+        dry_run,
+        format,
+        "No documents use the renamed schema elements",
+    )
+}
+
+/// Move every selection of the `from` field (`Type.field`) to the `to`
+/// field on the same type
+pub fn run_field(
+    config_path: Option<PathBuf>,
+    project_name: Option<&str>,
+    from: &str,
+    to: &str,
+    dry_run: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let (type_name, old_field, new_field) = parse_field_pair(from, to)?;
+
+    let ctx = CommandContext::load(config_path, project_name, "migrate-field")?;
+    let project_config = ctx.get_project_config(project_name)?;
+
+    let spinner = if matches!(format, OutputFormat::Human) {
+        Some(crate::progress::spinner("Loading schema and documents..."))
+    } else {
+        None
+    };
+
+    let host = CliAnalysisHost::from_project_config(&project_config, &ctx.base_dir)?;
+    let result = host.migrate_field(type_name, old_field, new_field);
+
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+
+    let Some(result) = result else {
+        anyhow::bail!(
+            "`{type_name}.{old_field}` and `{type_name}.{new_field}` must both be defined in the schema"
+        );
+    };
+    apply_result(
+        &host,
+        &result,
+        dry_run,
+        format,
+        &format!("No documents select {from}"),
+    )
+}
+
+/// Split `Type.oldField` and `Type.newField` (or just `newField`) into the
+/// type and both field names
+fn parse_field_pair<'a>(from: &'a str, to: &'a str) -> Result<(&'a str, &'a str, &'a str)> {
+    let Some((type_name, old_field)) = from.split_once('.') else {
+        anyhow::bail!("Expected the field to migrate as `Type.field`, got `{from}`");
+    };
+    let new_field = match to.split_once('.') {
+        Some((to_type, new_field)) if to_type == type_name => new_field,
+        Some((to_type, _)) => {
+            anyhow::bail!("The replacement must be a field of `{type_name}`, not `{to_type}`")
+        }
+        None => to,
+    };
+    Ok((type_name, old_field, new_field))
+}
+
+/// Write (or preview) the edits of a migration and report the usages it
+/// left alone, exiting non-zero if there are any
+fn apply_result(
+    host: &CliAnalysisHost,
+    result: &MigrationResult,
+    dry_run: bool,
+    format: OutputFormat,
+    nothing_to_do: &str,
+) -> Result<()> {
+    let mut changes: Vec<_> = result
+        .changes
+        .iter()
+        .filter_map(|(file, edits)| Some((host.document_path(file)?, edits)))
+        .collect();
+    changes.sort_by_key(|(path, _)| *path);
+
+    for (path, edits) in changes {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let Some(migrated) = apply_edits(&content, edits) else {
+            tracing::warn!(file = %path.display(), "Migration edit out of bounds, skipping file");
+            continue;
+        };
+        if !dry_run {
+            std::fs::write(path, migrated)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+
+        match format {
+            OutputFormat::Human => {
+                let marker = if dry_run { "→".cyan() } else { "✓".green() };
+                println!(
+                    "{} {} ({})",
+                    marker,
+                    path.display(),
+                    format!("{} edit(s)", edits.len()).dimmed()
+                );
+            }
+            OutputFormat::Json | OutputFormat::Github | OutputFormat::Sarif => {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "action": if dry_run { "would_migrate" } else { "migrated" },
+                        "file": path.to_string_lossy(),
+                        "edits": edits.len(),
+                    })
+                );
+            }
+        }
+    }
+
+    for usage in &result.unmigrated {
+        let file = host.document_path(&usage.location.file).map_or_else(
+            || usage.location.file.as_str().to_string(),
+            |p| p.display().to_string(),
+        );
+        // Convert from 0-based to 1-based for display
+        let line = usage.location.range.start.line + 1;
+        let column = usage.location.range.start.character + 1;
+        match format {
+            OutputFormat::Human => {
+                println!(
+                    "{} {}:{}:{} {}",
+                    "⚠".yellow(),
+                    file,
+                    line,
+                    column,
+                    usage.message
+                );
+            }
+            OutputFormat::Json | OutputFormat::Github | OutputFormat::Sarif => {
+                println!(
+                    "{}",
+                    serde_json::json!({
+                        "action": "unmigrated",
+                        "file": file,
+                        "line": line,
+                        "column": column,
+                        "message": usage.message,
+                    })
+                );
+            }
+        }
+    }
+
+    if matches!(format, OutputFormat::Human) {
+        if result.changes.is_empty() && result.unmigrated.is_empty() {
+            println!("{}", format!("✓ {nothing_to_do}").green());
+        } else if !result.unmigrated.is_empty() {
+            println!(
+                "\n{}",
+                format!(
+                    "{} usage(s) need a manual migration",
+                    result.unmigrated.len()
+                )
+                .yellow()
+            );
+        }
+    }
+
+    if !result.unmigrated.is_empty() {
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+fn load_manifest(path: &Path) -> Result<SchemaChangeManifest> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read change manifest {}", path.display()))?;
+    parse_manifest(&content).with_context(|| format!("Invalid change manifest {}", path.display()))
+}
+
+fn parse_manifest(content: &str) -> Result<SchemaChangeManifest> {
+    let file: ManifestFile = serde_json::from_str(content)?;
+    Ok(file.into())
+}
+
+/// Apply line/column edits to `content`, or `None` if any edit falls
+/// outside it.
+fn apply_edits(content: &str, edits: &[TextEdit]) -> Option<String> {
+    let line_index = graphql_syntax::LineIndex::new(content);
+    let offset = |line: u32, character: u32| line_index.utf16_to_offset(line as usize, character);
+
+    let mut ranges = edits
+        .iter()
+        .map(|edit| {
+            let start = offset(edit.range.start.line, edit.range.start.character)?;
+            let end = offset(edit.range.end.line, edit.range.end.character)?;
+            Some((start, end, edit.new_text.as_str()))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    // Apply from the end so earlier offsets stay valid
+    ranges.sort_by_key(|(start, _, _)| std::cmp::Reverse(*start));
+
+    let mut result = content.to_string();
+    for (start, end, new_text) in ranges {
+        result.replace_range(start..end, new_text);
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_ide::{Position, Range};
+
+    #[test]
+    fn test_parse_field_pair() {
+        assert_eq!(
+            parse_field_pair("User.fullName", "User.displayName").unwrap(),
+            ("User", "fullName", "displayName")
+        );
+        assert_eq!(
+            parse_field_pair("User.fullName", "displayName").unwrap(),
+            ("User", "fullName", "displayName")
+        );
+        assert!(parse_field_pair("fullName", "displayName").is_err());
+        assert!(parse_field_pair("User.fullName", "Account.displayName").is_err());
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(
+            r#"{ "types": { "User": "Account" }, "enumValues": { "Status.ACTIVE": "ENABLED" } }"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.types["User"], "Account");
+        assert!(manifest.fields.is_empty());
+        assert_eq!(manifest.enum_values["Status.ACTIVE"], "ENABLED");
+
+        assert!(parse_manifest(r#"{ "typos": {} }"#).is_err());
+    }
+
+    #[test]
+    fn test_apply_edits_multiline() {
+        let content = "query {\n  user { fullName }\n}\n";
+        let edits = vec![
+            TextEdit::new(
+                Range::new(Position::new(1, 2), Position::new(1, 6)),
+                "account",
+            ),
+            TextEdit::new(
+                Range::new(Position::new(1, 9), Position::new(1, 17)),
+                "displayName",
+            ),
+        ];
+        assert_eq!(
+            apply_edits(content, &edits).unwrap(),
+            "query {\n  account { displayName }\n}\n"
+        );
+    }
+}
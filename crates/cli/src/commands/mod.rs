@@ -10,6 +10,8 @@ pub mod lint;
 pub mod list_rules;
 pub mod lsp;
 pub mod mcp;
+pub mod migrate;
+pub mod persisted_operations;
 pub(crate) mod sarif;
 pub mod schema;
 pub mod stats;
@@ -0,0 +1,65 @@
+use crate::analysis::CliAnalysisHost;
+use crate::commands::common::CommandContext;
+use crate::OutputOptions;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+pub fn run(
+    config_path: Option<PathBuf>,
+    project_name: Option<&str>,
+    output: Option<PathBuf>,
+    output_opts: OutputOptions,
+) -> Result<()> {
+    let ctx = CommandContext::load(config_path, project_name, "persisted-operations")?;
+    let project_config = ctx.get_project_config(project_name)?;
+
+    // Without `--output`, the configured allow-list is regenerated in place
+    let output = output.or_else(|| {
+        project_config
+            .persisted_operations()
+            .map(|path| ctx.base_dir.join(path))
+    });
+
+    let spinner = output_opts
+        .show_progress
+        .then(|| crate::progress::spinner("Loading schema and documents..."));
+    let host = CliAnalysisHost::from_project_config(&project_config, &ctx.base_dir)?;
+    let manifest = host.persisted_operation_manifest();
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)?;
+    let Some(output) = output else {
+        println!("{json}");
+        return Ok(());
+    };
+
+    let previous = std::fs::read_to_string(&output)
+        .ok()
+        .and_then(|contents| graphql_config::PersistedOperationAllowList::from_json(&contents).ok())
+        .unwrap_or_default();
+    std::fs::write(&output, format!("{json}\n"))
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    if output_opts.show_info {
+        let added = manifest
+            .keys()
+            .filter(|hash| !previous.contains(hash))
+            .count();
+        let removed = previous
+            .hashes
+            .iter()
+            .filter(|hash| !manifest.contains_key(*hash))
+            .count();
+        println!(
+            "{} Wrote {} operation(s) to {} ({})",
+            "✓".green(),
+            manifest.len(),
+            output.display(),
+            format!("{added} added, {removed} removed").dimmed()
+        );
+    }
+    Ok(())
+}
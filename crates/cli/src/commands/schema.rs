@@ -1,10 +1,17 @@
 //! Schema-related CLI commands.
 
+use crate::analysis::CliAnalysisHost;
+use crate::commands::common::CommandContext;
+use crate::{rendering, ExitCode, OutputFormat};
 use anyhow::{Context, Result};
 use clap::Subcommand;
 use colored::Colorize;
-use graphql_config::{find_config, load_config, IntrospectionSchemaConfig};
-use graphql_introspect::{introspection_to_sdl, IntrospectionClient};
+use graphql_config::{find_config, load_config, IntrospectionSchemaConfig, RegistryProvider};
+use graphql_ide::{DiagnosticSeverity, SchemaChangeFinding};
+use graphql_introspect::{
+    introspection_to_sdl, ChangeCriticality, HiveRegistry, IntrospectionClient, SchemaCheckResult,
+    SchemaRegistry,
+};
 use std::io::Write;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -72,6 +79,31 @@ Examples:
         #[arg(long)]
         retry: Option<u32>,
     },
+
+    /// Check the local schema against a schema registry for breaking changes
+    ///
+    /// The registry is configured under `extensions.graphql-analyzer.registry`.
+    /// Breaking changes are reported as errors and dangerous ones as warnings,
+    /// on the schema definitions they concern.
+    #[command(after_help = "\
+Examples:
+  graphql schema check
+  graphql schema check --project users
+  graphql schema check --format json
+")]
+    Check {
+        /// Path to GraphQL config file
+        #[arg(short, long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Project whose schema and registry settings to use
+        #[arg(short, long)]
+        project: Option<String>,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
 }
 
 /// Run a schema subcommand.
@@ -92,11 +124,15 @@ pub async fn run(command: SchemaCommands) -> Result<()> {
             )
             .await
         }
+        SchemaCommands::Check {
+            config,
+            project,
+            format,
+        } => run_check(config, project.as_deref(), format).await,
     }
 }
 
 /// Resolved introspection settings from config file and CLI arguments.
-#[derive(Debug)]
 struct IntrospectionSettings {
     url: String,
     headers: Vec<(String, String)>,
@@ -104,6 +140,22 @@ struct IntrospectionSettings {
     retry: u32,
 }
 
+impl std::fmt::Debug for IntrospectionSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntrospectionSettings")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &graphql_config::RedactedHeaders::new(
+                    self.headers.iter().map(|(name, _)| name.as_str()),
+                ),
+            )
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
 /// Load introspection settings from config file.
 fn load_from_config(
     config_path: Option<PathBuf>,
@@ -205,12 +257,14 @@ fn resolve_settings(
     // Load from config file
     let introspection_config = load_from_config(config_path, project)?;
 
-    // Start with headers from config
-    let mut headers: Vec<(String, String)> = introspection_config
-        .headers
-        .unwrap_or_default()
-        .into_iter()
-        .collect();
+    // Start with headers from config, whose `${secret:NAME}` references are
+    // read from the environment here
+    let config_headers = graphql_config::resolve_header_secrets(
+        &introspection_config.headers.unwrap_or_default(),
+        &graphql_config::EnvSecrets,
+    )
+    .context("Failed to resolve introspection headers")?;
+    let mut headers: Vec<(String, String)> = config_headers.into_iter().collect();
 
     // Parse and merge CLI headers (CLI takes precedence)
     let cli_parsed: Vec<(String, String)> = cli_headers
@@ -334,10 +388,197 @@ async fn run_download(
     Ok(())
 }
 
+/// Registry findings that are worth a diagnostic. Safe changes are left out.
+fn registry_findings(result: &SchemaCheckResult) -> Vec<SchemaChangeFinding> {
+    result
+        .changes
+        .iter()
+        .filter_map(|change| {
+            let severity = match change.criticality {
+                ChangeCriticality::Breaking => DiagnosticSeverity::Error,
+                ChangeCriticality::Dangerous => DiagnosticSeverity::Warning,
+                ChangeCriticality::Safe => return None,
+            };
+            Some(SchemaChangeFinding {
+                coordinate: change.coordinate.clone(),
+                message: change.message.clone(),
+                severity,
+            })
+        })
+        .collect()
+}
+
+async fn run_check(
+    config_path: Option<PathBuf>,
+    project_name: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let ctx = CommandContext::load(config_path, project_name, "schema check")?;
+    let project_config = ctx.get_project_config(project_name)?;
+    let Some(registry_config) = project_config.registry() else {
+        anyhow::bail!(
+            "No schema registry configured.\n\n\
+            Add one under extensions.graphql-analyzer.registry:\n\n  \
+            registry:\n    provider: hive\n    token: \"${{HIVE_TOKEN}}\""
+        );
+    };
+
+    let host = CliAnalysisHost::from_project_config(&project_config, &ctx.base_dir)?;
+    if !host.schema_loaded() {
+        anyhow::bail!("No schema loaded for this project");
+    }
+    let sdl = host.schema_sdl();
+
+    let registry: Box<dyn SchemaRegistry> = match registry_config.provider {
+        RegistryProvider::Hive => {
+            let mut hive = HiveRegistry::new(registry_config.token);
+            if let Some(url) = registry_config.url {
+                hive = hive.with_url(url);
+            }
+            if let Some(service) = registry_config.service {
+                hive = hive.with_service(service);
+            }
+            if let Some(timeout) = registry_config.timeout {
+                hive = hive.with_timeout(Duration::from_secs(timeout));
+            }
+            Box::new(hive)
+        }
+    };
+
+    let spinner = matches!(format, OutputFormat::Human)
+        .then(|| crate::progress::spinner("Checking schema against the registry..."));
+    let result = registry.check(&sdl).await;
+    if let Some(pb) = spinner {
+        pb.finish_and_clear();
+    }
+    let result = result.context("Schema check failed")?;
+
+    let findings = registry_findings(&result);
+    let (diagnostics, unlocated) = host.schema_change_diagnostics(&findings);
+
+    match format {
+        OutputFormat::Human => {
+            for (path, file_diagnostics) in &diagnostics {
+                let path_str = path.to_string_lossy();
+                let source_text = std::fs::read_to_string(path).ok();
+                for diag in file_diagnostics {
+                    if let Some(ref source) = source_text {
+                        if rendering::render_diagnostic(&path_str, source, diag) {
+                            continue;
+                        }
+                    }
+                    let line = diag.range.start.line + 1;
+                    let col = diag.range.start.character + 1;
+                    println!("\n{path_str}:{line}:{col}: {}", diag.message);
+                }
+            }
+            for finding in &unlocated {
+                let label = match finding.severity {
+                    DiagnosticSeverity::Error => "error:".red().bold(),
+                    _ => "warning:".yellow().bold(),
+                };
+                println!("\n{label} {}", finding.message);
+            }
+            for error in &result.errors {
+                println!("\n{} {error}", "error:".red().bold());
+            }
+
+            println!();
+            if result.valid {
+                println!("{}", "✓ Schema check passed".green().bold());
+            } else {
+                println!("{}", "✗ Schema check failed".red().bold());
+            }
+            println!(
+                "  {} change(s), {} breaking",
+                result.changes.len(),
+                result
+                    .changes
+                    .iter()
+                    .filter(|c| c.criticality == ChangeCriticality::Breaking)
+                    .count()
+            );
+            if let Some(url) = &result.details_url {
+                println!("  {} {}", "details:".dimmed(), url.cyan());
+            }
+        }
+        OutputFormat::Json | OutputFormat::Github | OutputFormat::Sarif => {
+            let located: Vec<_> = diagnostics
+                .iter()
+                .flat_map(|(path, file_diagnostics)| {
+                    file_diagnostics.iter().map(move |diag| {
+                        serde_json::json!({
+                            "file": path.display().to_string(),
+                            "line": diag.range.start.line + 1,
+                            "column": diag.range.start.character + 1,
+                            "severity": severity_name(diag.severity),
+                            "message": diag.message,
+                        })
+                    })
+                })
+                .collect();
+            let unlocated: Vec<_> = unlocated
+                .iter()
+                .map(|finding| {
+                    serde_json::json!({
+                        "coordinate": finding.coordinate,
+                        "severity": severity_name(finding.severity),
+                        "message": finding.message,
+                    })
+                })
+                .collect();
+            let output = serde_json::json!({
+                "valid": result.valid,
+                "changes": located.into_iter().chain(unlocated).collect::<Vec<_>>(),
+                "errors": result.errors,
+                "detailsUrl": result.details_url,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+    }
+
+    if !result.valid {
+        ExitCode::ValidationError.exit();
+    }
+    Ok(())
+}
+
+fn severity_name(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Warning => "warning",
+        DiagnosticSeverity::Information | DiagnosticSeverity::Hint => "info",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_registry_findings_skip_safe_changes() {
+        let change = |criticality, coordinate: &str| graphql_introspect::SchemaChange {
+            criticality,
+            message: format!("{coordinate} changed"),
+            coordinate: Some(coordinate.to_string()),
+        };
+        let result = SchemaCheckResult {
+            valid: false,
+            changes: vec![
+                change(ChangeCriticality::Breaking, "User.email"),
+                change(ChangeCriticality::Safe, "User.avatar"),
+                change(ChangeCriticality::Dangerous, "Role.GUEST"),
+            ],
+            ..SchemaCheckResult::default()
+        };
+
+        let findings = registry_findings(&result);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(findings[1].coordinate.as_deref(), Some("Role.GUEST"));
+        assert_eq!(findings[1].severity, DiagnosticSeverity::Warning);
+    }
+
     #[test]
     fn test_parse_header_valid() {
         let (name, value) = parse_header("Authorization: Bearer token").unwrap();
@@ -154,13 +154,14 @@ Examples:
         format: OutputFormat,
     },
 
-    /// Schema-related commands (download, etc.)
+    /// Schema-related commands (download, registry check)
     #[command(after_help = "\
 Examples:
   graphql schema download https://api.example.com/graphql
   graphql schema download --project my-api
   graphql schema download https://api.example.com/graphql -o schema.graphql
   graphql schema download https://api.example.com/graphql -H \"Authorization: Bearer token\"
+  graphql schema check --project my-api
 ")]
     Schema {
         #[command(subcommand)]
@@ -230,6 +231,87 @@ Examples:
         breakdown: bool,
     },
 
+    /// Rewrite project documents for renamed schema types, fields and enum values
+    ///
+    /// Reads a JSON change manifest mapping old names to new ones and updates
+    /// every operation and fragment. Usages that can't be rewritten from the
+    /// document alone, such as enum values passed through variables, are
+    /// reported and make the command exit non-zero.
+    #[command(after_help = "\
+Examples:
+  graphql migrate renames.json              Apply the renames to all documents
+  graphql migrate renames.json --dry-run    Preview which files would change
+
+Manifest format:
+  {
+    \"types\": { \"User\": \"Account\" },
+    \"fields\": { \"User.fullName\": \"displayName\" },
+    \"enumValues\": { \"Status.ACTIVE\": \"ENABLED\" }
+  }
+")]
+    Migrate {
+        /// Path to the change manifest (JSON)
+        manifest: PathBuf,
+
+        /// Show what would change without modifying files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// Move every selection of a field to its replacement on the same type
+    ///
+    /// For a field superseded by another one that already exists in the
+    /// schema. A usage is left alone and reported, making the command exit
+    /// non-zero, when its response key would collide with another selection
+    /// or when the replacement takes different arguments.
+    #[command(
+        name = "migrate-field",
+        after_help = "\
+Examples:
+  graphql migrate-field User.fullName User.displayName            Move usages to displayName
+  graphql migrate-field User.fullName displayName --dry-run       Preview which files would change
+"
+    )]
+    MigrateField {
+        /// The field to move off, as `Type.field`
+        from: String,
+
+        /// The replacement field, as `Type.field` or just `field`
+        to: String,
+
+        /// Show what would change without modifying files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "human")]
+        format: OutputFormat,
+    },
+
+    /// Regenerate the persisted operation allow-list from the project's documents
+    ///
+    /// Writes every operation's persisted-query document keyed by its hash,
+    /// the format `extensions.graphql-analyzer.persistedOperations` reads.
+    /// Operations missing from that list get an informational diagnostic.
+    #[command(
+        name = "persisted-operations",
+        after_help = "\
+Examples:
+  graphql persisted-operations                         Rewrite the configured allow-list
+  graphql persisted-operations -o persisted.json       Write the allow-list to a file
+"
+    )]
+    PersistedOperations {
+        /// Where to write the allow-list. Defaults to the configured
+        /// `persistedOperations` path, or stdout when there is none
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+
     /// Start an MCP server for AI agent integration
     ///
     /// This command starts a Model Context Protocol (MCP) server that exposes
@@ -386,6 +468,36 @@ async fn main() -> anyhow::Result<()> {
             threshold,
             breakdown,
         ),
+        Commands::Migrate {
+            manifest,
+            dry_run,
+            format,
+        } => commands::migrate::run(
+            cli.config,
+            cli.project.as_deref(),
+            &manifest,
+            dry_run,
+            format,
+        ),
+        Commands::MigrateField {
+            from,
+            to,
+            dry_run,
+            format,
+        } => commands::migrate::run_field(
+            cli.config,
+            cli.project.as_deref(),
+            &from,
+            &to,
+            dry_run,
+            format,
+        ),
+        Commands::PersistedOperations { output } => commands::persisted_operations::run(
+            cli.config,
+            cli.project.as_deref(),
+            output,
+            output_opts,
+        ),
         Commands::Mcp {
             workspace,
             no_preload,
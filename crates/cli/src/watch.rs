@@ -221,7 +221,7 @@ impl FileWatcher {
         let extension = path.extension().and_then(|e| e.to_str());
         matches!(
             extension,
-            Some("graphql" | "gql" | "ts" | "tsx" | "js" | "jsx")
+            Some("graphql" | "gql" | "ts" | "tsx" | "js" | "jsx" | "vue" | "svelte" | "py" | "rb")
         )
     }
 
@@ -235,6 +235,10 @@ impl FileWatcher {
                 let (language, document_kind) = match path.extension().and_then(|e| e.to_str()) {
                     Some("ts" | "tsx") => (Language::TypeScript, DocumentKind::Executable),
                     Some("js" | "jsx") => (Language::JavaScript, DocumentKind::Executable),
+                    Some("vue") => (Language::Vue, DocumentKind::Executable),
+                    Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+                    Some("py") => (Language::Python, DocumentKind::Executable),
+                    Some("rb") => (Language::Ruby, DocumentKind::Executable),
                     _ => (Language::GraphQL, DocumentKind::Executable),
                 };
 
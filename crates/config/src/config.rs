@@ -1,6 +1,6 @@
-use crate::ConfigError;
+use crate::{ConfigError, FileGlobs, RedactedHeaders};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
 use std::sync::OnceLock;
 
@@ -58,6 +58,19 @@ impl GraphQLConfig {
         }
     }
 
+    /// Remember the values interpolated from the environment into the file
+    /// this config was loaded from
+    pub(crate) fn set_env_values(&mut self, values: &[String]) {
+        match self {
+            Self::Single(config) => config.env_values = values.to_vec(),
+            Self::Multi { projects } => {
+                for config in projects.values_mut() {
+                    config.env_values = values.to_vec();
+                }
+            }
+        }
+    }
+
     /// Get lint configuration from the first/default project
     /// For single-project configs, returns the project's lint config from extensions
     /// For multi-project configs, returns None (each project has its own)
@@ -177,21 +190,20 @@ impl GraphQLConfig {
     fn match_file_type(rel_path: &str, config: &ProjectConfig) -> Option<crate::FileType> {
         let compiled = config.compiled_patterns();
 
-        if compiled.exclude.iter().any(|p| p.matches(rel_path)) {
+        if compiled.exclude.is_match(rel_path) {
             return None;
         }
 
-        let in_include_scope =
-            config.include.is_none() || compiled.include.iter().any(|p| p.matches(rel_path));
+        let in_include_scope = config.include.is_none() || compiled.include.is_match(rel_path);
         if !in_include_scope {
             return None;
         }
 
-        if compiled.schema.iter().any(|p| p.matches(rel_path)) {
+        if compiled.schema.is_match(rel_path) {
             return Some(crate::FileType::Schema);
         }
 
-        if compiled.documents.iter().any(|p| p.matches(rel_path)) {
+        if compiled.documents.is_match(rel_path) {
             return Some(crate::FileType::Document);
         }
 
@@ -222,45 +234,23 @@ impl GraphQLConfig {
         let compiled = config.compiled_patterns();
 
         // Check explicit excludes first
-        if compiled.exclude.iter().any(|p| p.matches(&rel_path_str)) {
+        if compiled.exclude.is_match(&rel_path_str) {
             return false;
         }
 
-        // Determine if file is in project scope based on include/exclude patterns
-        let in_include_scope = config.include.is_none()
-            || compiled.include.iter().any(|p| {
-                let matched = p.matches(&rel_path_str);
-                if matched {
-                    tracing::debug!("    Matched include pattern: {}", p);
-                }
-                matched
-            });
-
         // If file is not in include scope, it doesn't match this project
-        if !in_include_scope {
+        if config.include.is_some() && !compiled.include.is_match(&rel_path_str) {
             tracing::debug!("No include patterns matched, file excluded");
             return false;
         }
 
-        // Check if file matches schema patterns
-        if compiled.schema.iter().any(|p| {
-            let matched = p.matches(&rel_path_str);
-            if matched {
-                tracing::debug!("    Matched schema pattern: {}", p);
-            }
-            matched
-        }) {
+        if compiled.schema.is_match(&rel_path_str) {
+            tracing::debug!("    Matched schema patterns");
             return true;
         }
 
-        // Check if file matches document patterns
-        if compiled.documents.iter().any(|p| {
-            let matched = p.matches(&rel_path_str);
-            if matched {
-                tracing::debug!("    Matched document pattern: {}", p);
-            }
-            matched
-        }) {
+        if compiled.documents.is_match(&rel_path_str) {
+            tracing::debug!("    Matched document patterns");
             return true;
         }
 
@@ -296,59 +286,39 @@ impl GraphQLConfig {
 
         normalized
     }
-
-    /// Expand brace patterns like "src/**/*.{ts,tsx}" into separate patterns
-    /// Also normalizes patterns for consistent matching
-    fn expand_braces(pattern: &str) -> Vec<String> {
-        // Normalize pattern first
-        let normalized = Self::normalize_pattern(pattern);
-
-        // Simple brace expansion - handles single brace group
-        if let Some(start) = normalized.find('{') {
-            if let Some(end) = normalized.find('}') {
-                let before = &normalized[..start];
-                let after = &normalized[end + 1..];
-                let options = &normalized[start + 1..end];
-
-                return options
-                    .split(',')
-                    .map(|opt| format!("{}{}{}", before, opt.trim(), after))
-                    .collect();
-            }
-        }
-
-        vec![normalized]
-    }
 }
 
 /// Pre-compiled glob patterns for a project, cached to avoid repeated
 /// brace expansion and pattern compilation on every file-match check.
 #[derive(Clone)]
 struct CompiledPatterns {
-    exclude: Vec<glob::Pattern>,
-    include: Vec<glob::Pattern>,
-    schema: Vec<glob::Pattern>,
-    documents: Vec<glob::Pattern>,
+    exclude: FileGlobs,
+    include: FileGlobs,
+    schema: FileGlobs,
+    documents: FileGlobs,
 }
 
 impl CompiledPatterns {
     fn compile(config: &ProjectConfig) -> Self {
-        let compile_list = |raw_patterns: &[String]| -> Vec<glob::Pattern> {
-            raw_patterns
+        let compile_list = |raw_patterns: &[String]| -> FileGlobs {
+            let normalized: Vec<String> = raw_patterns
                 .iter()
-                .flat_map(|p| GraphQLConfig::expand_braces(p))
-                .filter_map(|expanded| glob::Pattern::new(&expanded).ok())
-                .collect()
+                .map(|pattern| match pattern.trim().strip_prefix('!') {
+                    Some(negated) => format!("!{}", GraphQLConfig::normalize_pattern(negated)),
+                    None => GraphQLConfig::normalize_pattern(pattern),
+                })
+                .collect();
+            FileGlobs::new(&normalized)
         };
 
         let exclude = config
             .exclude
             .as_deref()
-            .map_or_else(Vec::new, compile_list);
+            .map_or_else(FileGlobs::default, compile_list);
         let include = config
             .include
             .as_deref()
-            .map_or_else(Vec::new, compile_list);
+            .map_or_else(FileGlobs::default, compile_list);
 
         let schema_paths: Vec<String> = config
             .schema
@@ -398,6 +368,10 @@ pub struct ProjectConfig {
     /// Cached compiled glob patterns (lazily initialized on first use)
     #[serde(skip)]
     compiled_patterns: OnceLock<CompiledPatterns>,
+
+    /// Values interpolated from the environment into the config file
+    #[serde(skip)]
+    env_values: Vec<String>,
 }
 
 impl std::fmt::Debug for ProjectConfig {
@@ -421,6 +395,7 @@ impl Clone for ProjectConfig {
             exclude: self.exclude.clone(),
             extensions: self.extensions.clone(),
             compiled_patterns: OnceLock::new(),
+            env_values: self.env_values.clone(),
         }
     }
 }
@@ -454,9 +429,19 @@ impl ProjectConfig {
             exclude,
             extensions,
             compiled_patterns: OnceLock::new(),
+            env_values: Vec::new(),
         }
     }
 
+    /// Whether `text` contains a value interpolated from an environment
+    /// variable into the config file, as `url: https://${HOST}/graphql` does.
+    #[must_use]
+    pub fn contains_env_value(&self, text: &str) -> bool {
+        self.env_values
+            .iter()
+            .any(|value| !value.is_empty() && text.contains(value.as_str()))
+    }
+
     /// Get the compiled glob patterns, lazily initializing on first access.
     fn compiled_patterns(&self) -> &CompiledPatterns {
         self.compiled_patterns
@@ -479,16 +464,14 @@ impl ProjectConfig {
         let rel_path_str = rel_path.to_string_lossy();
         let compiled = self.compiled_patterns();
 
-        if compiled.exclude.iter().any(|p| p.matches(&rel_path_str)) {
+        if compiled.exclude.is_match(&rel_path_str) {
             return false;
         }
-        let in_include_scope =
-            self.include.is_none() || compiled.include.iter().any(|p| p.matches(&rel_path_str));
+        let in_include_scope = self.include.is_none() || compiled.include.is_match(&rel_path_str);
         if !in_include_scope {
             return false;
         }
-        compiled.schema.iter().any(|p| p.matches(&rel_path_str))
-            || compiled.documents.iter().any(|p| p.matches(&rel_path_str))
+        compiled.schema.is_match(&rel_path_str) || compiled.documents.is_match(&rel_path_str)
     }
 
     /// Whether this project has any include/exclude/schema/document
@@ -537,6 +520,181 @@ impl ProjectConfig {
         self.analyzer_extensions()?.resolved_schema
     }
 
+    /// Get the schema variants from extensions, keyed by variant name.
+    ///
+    /// Documents opt into a variant with a
+    /// `# graphql-analyzer: schema-variant <name>` pragma, or are assigned to
+    /// one by its `documents` patterns, and are validated against that schema
+    /// instead of the project schema.
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     schemaVariants:
+    ///       staging: "schema/staging.graphql"
+    ///       admin:
+    ///         schema: "admin/schema/**/*.graphql"
+    ///         documents: "admin/src/**/*.ts"
+    /// ```
+    #[must_use]
+    pub fn schema_variants(&self) -> BTreeMap<String, SchemaVariantConfig> {
+        self.analyzer_extensions()
+            .and_then(|ext| ext.schema_variants)
+            .unwrap_or_default()
+    }
+
+    /// Get the complexity cost model from `extensions.graphql-analyzer.complexity`.
+    #[must_use]
+    pub fn complexity(&self) -> Option<ComplexityConfig> {
+        self.analyzer_extensions()?.complexity
+    }
+
+    /// Get the validation severity settings from
+    /// `extensions.graphql-analyzer.validation`.
+    ///
+    /// Selects a profile and optionally overrides individual checks:
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     validation:
+    ///       profile: relaxed
+    ///       checks:
+    ///         unknown-directive: warn
+    /// ```
+    #[must_use]
+    pub fn validation(&self) -> Option<ValidationConfig> {
+        self.analyzer_extensions()?.validation
+    }
+
+    /// Get the operation usage report path from
+    /// `extensions.graphql-analyzer.operationUsage`.
+    ///
+    /// The report is exported from a gateway or router and records how often
+    /// each operation was called (see [`OperationUsageReport`]):
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     operationUsage: "telemetry/operation-usage.json"
+    /// ```
+    #[must_use]
+    pub fn operation_usage(&self) -> Option<String> {
+        self.analyzer_extensions()?.operation_usage
+    }
+
+    /// Get the persisted operation allow-list path from
+    /// `extensions.graphql-analyzer.persistedOperations`.
+    ///
+    /// When set, operations whose persisted-query hash isn't in the list (see
+    /// [`PersistedOperationAllowList`]) get an informational diagnostic:
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     persistedOperations: "persisted-operations.json"
+    /// ```
+    #[must_use]
+    pub fn persisted_operations(&self) -> Option<String> {
+        self.analyzer_extensions()?.persisted_operations
+    }
+
+    /// Get the N+1 selection check from `extensions.graphql-analyzer.nPlusOne`.
+    ///
+    /// The check only runs when this is configured; an empty block turns it
+    /// on with the defaults (see [`NPlusOneConfig`]).
+    #[must_use]
+    pub fn n_plus_one(&self) -> Option<NPlusOneConfig> {
+        self.analyzer_extensions()?.n_plus_one
+    }
+
+    /// Get the schema registry from `extensions.graphql-analyzer.registry`.
+    ///
+    /// Used by `graphql schema check` to compare the local schema with the
+    /// one published to the registry:
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     registry:
+    ///       provider: hive
+    ///       token: "${HIVE_TOKEN}"
+    ///       service: users
+    /// ```
+    #[must_use]
+    pub fn registry(&self) -> Option<RegistryConfig> {
+        self.analyzer_extensions()?.registry
+    }
+
+    /// Get custom scalar documentation from `extensions.graphql-analyzer.scalars`,
+    /// keyed by scalar name.
+    ///
+    /// Hover, inlay hints and completion details show how each scalar is
+    /// serialized:
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     scalars:
+    ///       DateTime:
+    ///         serialization: "ISO-8601 string"
+    ///         example: "2024-01-15T09:30:00Z"
+    /// ```
+    #[must_use]
+    pub fn scalars(&self) -> BTreeMap<String, ScalarConfig> {
+        self.analyzer_extensions()
+            .and_then(|ext| ext.scalars)
+            .unwrap_or_default()
+    }
+
+    /// Formats that custom scalar literals are validated against, for the
+    /// scalars under `scalars` that set one:
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     scalars:
+    ///       UUID:
+    ///         format: uuid
+    /// ```
+    #[must_use]
+    pub fn scalar_formats(&self) -> BTreeMap<String, ScalarFormat> {
+        self.scalars()
+            .into_iter()
+            .filter_map(|(name, scalar)| Some((name, scalar.format?)))
+            .collect()
+    }
+
+    /// Whether files ignored by `.gitignore` are skipped when loading schemas
+    /// and documents, from `extensions.graphql-analyzer.respectGitignore`.
+    /// Off by default, so generated files that are ignored still load.
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     respectGitignore: true
+    /// ```
+    #[must_use]
+    pub fn respect_gitignore(&self) -> bool {
+        self.analyzer_extensions()
+            .and_then(|ext| ext.respect_gitignore)
+            .unwrap_or(false)
+    }
+
+    /// Whether documents may use the proposed client-controlled nullability
+    /// syntax, from `extensions.graphql-analyzer.clientControlledNullability`.
+    /// Off by default, since servers have to support it too.
+    /// ```yaml
+    /// extensions:
+    ///   graphql-analyzer:
+    ///     clientControlledNullability: true
+    /// ```
+    #[must_use]
+    pub fn client_controlled_nullability(&self) -> bool {
+        self.analyzer_extensions()
+            .and_then(|ext| ext.client_controlled_nullability)
+            .unwrap_or(false)
+    }
+
+    /// Build the file globs for `patterns`, honoring
+    /// [`respect_gitignore`](Self::respect_gitignore).
+    #[must_use]
+    pub fn file_globs<S: AsRef<str>>(&self, patterns: &[S]) -> FileGlobs {
+        FileGlobs::new(patterns).respect_gitignore(self.respect_gitignore())
+    }
+
     /// Get the extract configuration from `extensions.graphql-analyzer.extractConfig`,
     /// or its `pluckConfig` alias (provided for users migrating from
     /// `@graphql-tools/graphql-tag-pluck`).
@@ -562,6 +720,9 @@ pub struct AnalyzerExtensions {
     /// Path to a build-generated resolved schema file.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub resolved_schema: Option<String>,
+    /// Alternate schemas, keyed by variant name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_variants: Option<BTreeMap<String, SchemaVariantConfig>>,
     /// Client library configuration.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client: Option<ClientConfig>,
@@ -577,6 +738,389 @@ pub struct AnalyzerExtensions {
     /// `pluckConfig` on the same project is a configuration error.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pluck_config: Option<serde_json::Value>,
+    /// Cost model for operation complexity analysis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub complexity: Option<ComplexityConfig>,
+    /// Severity profile for spec validation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationConfig>,
+    /// Path to an operation usage report.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_usage: Option<String>,
+    /// Path to a persisted operation allow-list.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub persisted_operations: Option<String>,
+    /// Detection of N+1-prone selections.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_plus_one: Option<NPlusOneConfig>,
+    /// Schema registry to run schema checks against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<RegistryConfig>,
+    /// Documentation for custom scalars, keyed by scalar name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scalars: Option<BTreeMap<String, ScalarConfig>>,
+    /// Skip files ignored by `.gitignore` when loading schemas and documents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub respect_gitignore: Option<bool>,
+    /// Accept client-controlled nullability designators in documents.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_controlled_nullability: Option<bool>,
+}
+
+/// How a custom scalar is represented on the wire, for scalars such as
+/// `DateTime`, `JSON` or `UUID` whose SDL says nothing about their format.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScalarConfig {
+    /// Wire format, e.g. `ISO-8601 string`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serialization: Option<String>,
+    /// Longer explanation, shown alongside the schema's own description.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// An example serialized value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<String>,
+    /// Format that string literals of the scalar are validated against.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ScalarFormat>,
+}
+
+/// A well-known string format for custom scalar literals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScalarFormat {
+    /// An RFC 3339 date-time, e.g. `2024-01-15T09:30:00Z`.
+    DateTime,
+    /// A hyphenated UUID, e.g. `123e4567-e89b-12d3-a456-426614174000`.
+    Uuid,
+    /// An absolute URL, e.g. `https://example.com/avatar.png`.
+    Url,
+    /// An email address, e.g. `ada@example.com`.
+    Email,
+}
+
+impl ScalarFormat {
+    /// What a valid literal looks like, for diagnostics
+    #[must_use]
+    pub fn expected(self) -> &'static str {
+        match self {
+            ScalarFormat::DateTime => "an RFC 3339 date-time such as `2024-01-15T09:30:00Z`",
+            ScalarFormat::Uuid => "a UUID such as `123e4567-e89b-12d3-a456-426614174000`",
+            ScalarFormat::Url => "an absolute URL such as `https://example.com`",
+            ScalarFormat::Email => "an email address such as `ada@example.com`",
+        }
+    }
+}
+
+/// An alternate schema that documents validate against instead of the
+/// project schema.
+///
+/// Variant schemas never merge into the project schema, so a repo holding
+/// two unrelated APIs keeps their types apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SchemaVariantConfig {
+    /// A single schema file. Documents opt in with a `schema-variant` pragma.
+    Path(String),
+    /// Schema files plus the documents that target them.
+    Scoped(ScopedSchemaVariant),
+}
+
+/// A schema variant with the document patterns assigned to it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScopedSchemaVariant {
+    /// Schema file paths or glob patterns. Remote schemas aren't supported.
+    pub schema: DocumentsConfig,
+    /// Documents validated against this variant without a pragma.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documents: Option<DocumentsConfig>,
+}
+
+impl SchemaVariantConfig {
+    /// The variant's schema file paths or glob patterns.
+    #[must_use]
+    pub fn schema_paths(&self) -> Vec<&str> {
+        match self {
+            Self::Path(path) => vec![path.as_str()],
+            Self::Scoped(scoped) => scoped.schema.patterns(),
+        }
+    }
+
+    /// Patterns of the documents assigned to the variant.
+    #[must_use]
+    pub fn document_patterns(&self) -> Vec<&str> {
+        match self {
+            Self::Path(_) => Vec::new(),
+            Self::Scoped(scoped) => scoped
+                .documents
+                .as_ref()
+                .map_or_else(Vec::new, DocumentsConfig::patterns),
+        }
+    }
+}
+
+/// Cost model for operation complexity analysis.
+///
+/// Every selected field costs 1 unless overridden, multiplied by the
+/// estimated size of each list it is nested in. A list's size comes from its
+/// pagination argument (`first: 50`) when one is given as a literal,
+/// otherwise from `defaultListSize`.
+/// ```yaml
+/// extensions:
+///   graphql-analyzer:
+///     complexity:
+///       defaultListSize: 20
+///       typeCosts:
+///         SearchResult: 5
+///       fieldCosts:
+///         Query.search: 10
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ComplexityConfig {
+    /// Assumed size of list fields without a pagination argument.
+    pub default_list_size: u32,
+    /// Cost of selecting any field that returns the named type.
+    pub type_costs: BTreeMap<String, u32>,
+    /// Cost of selecting a specific field, keyed by `Type.field`. Takes
+    /// precedence over `type_costs`.
+    pub field_costs: BTreeMap<String, u32>,
+    /// Arguments whose integer value gives the size of the returned list,
+    /// or of the `edges`/`nodes` list for connection fields.
+    pub pagination_arguments: Vec<String>,
+}
+
+impl Default for ComplexityConfig {
+    fn default() -> Self {
+        Self {
+            default_list_size: 10,
+            type_costs: BTreeMap::new(),
+            field_costs: BTreeMap::new(),
+            pagination_arguments: vec!["first".to_string(), "last".to_string()],
+        }
+    }
+}
+
+/// Detection of selections likely to resolve one query per list item.
+///
+/// Opt-in: configuring it turns the check on. A list field of object type
+/// selected inside another list, within `maxDepth` fields of it and without
+/// a pagination argument, gets an informational diagnostic.
+/// ```yaml
+/// extensions:
+///   graphql-analyzer:
+///     nPlusOne:
+///       maxDepth: 3
+///       allowTypes: [Tag, User.roles]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NPlusOneConfig {
+    /// How many fields below a list field a nested list is still reported.
+    pub max_depth: usize,
+    /// Nested lists that are known to be batched, as type names (matching
+    /// the list's item type) or `Type.field` coordinates.
+    pub allow_types: Vec<String>,
+    /// Arguments that mark a list field as paginated, which exempts it and
+    /// the `edges`/`nodes` lists of its connection.
+    pub pagination_arguments: Vec<String>,
+}
+
+impl Default for NPlusOneConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 2,
+            allow_types: Vec::new(),
+            pagination_arguments: vec!["first".to_string(), "last".to_string()],
+        }
+    }
+}
+
+/// Call counts per operation over a recent window, as exported from a
+/// gateway or router.
+///
+/// Keys are operation names or persisted-query hashes (lowercase hex
+/// SHA-256). An operation that appears under neither wasn't called during
+/// the window.
+/// ```json
+/// {
+///   "windowDays": 30,
+///   "operations": {
+///     "GetUser": 1520,
+///     "5f1d7c7a...": 3
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationUsageReport {
+    /// Length of the reporting window in days.
+    #[serde(default = "default_usage_window_days")]
+    pub window_days: u32,
+    /// Calls during the window, keyed by operation name or hash.
+    pub operations: BTreeMap<String, u64>,
+}
+
+fn default_usage_window_days() -> u32 {
+    30
+}
+
+impl OperationUsageReport {
+    /// Read a report from a JSON file.
+    pub fn load(path: &Path) -> std::result::Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+/// Persisted-query hashes (lowercase hex SHA-256) of the operations a server
+/// accepts, for teams that only allow persisted operations in production.
+///
+/// Either an array of hashes or an object keyed by hash, such as the
+/// manifest `graphql persisted-operations` writes, where each value is the
+/// operation's document:
+/// ```json
+/// {
+///   "5f1d7c7a...": "query GetUser { user { id } }"
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PersistedOperationAllowList {
+    pub hashes: BTreeSet<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AllowListFile {
+    Hashes(Vec<String>),
+    Manifest(BTreeMap<String, serde_json::Value>),
+}
+
+impl PersistedOperationAllowList {
+    /// Read an allow-list from a JSON file.
+    pub fn load(path: &Path) -> std::result::Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)?;
+        Self::from_json(&contents)
+    }
+
+    /// Parse an allow-list from JSON.
+    pub fn from_json(contents: &str) -> std::result::Result<Self, ConfigError> {
+        let hashes = match serde_json::from_str(contents)? {
+            AllowListFile::Hashes(hashes) => hashes,
+            AllowListFile::Manifest(entries) => entries.into_keys().collect(),
+        };
+        Ok(Self {
+            hashes: hashes
+                .into_iter()
+                .map(|hash| hash.to_ascii_lowercase())
+                .collect(),
+        })
+    }
+
+    #[must_use]
+    pub fn contains(&self, hash: &str) -> bool {
+        self.hashes.contains(hash)
+    }
+}
+
+/// A schema registry that can check the local schema for breaking changes.
+///
+/// `token` is sent as a bearer token; use `${ENV_VAR}` interpolation rather
+/// than committing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RegistryConfig {
+    pub provider: RegistryProvider,
+    /// API endpoint. Defaults to the provider's hosted API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub token: String,
+    /// Service name, for registries that compose several subgraphs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+    /// Request timeout in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+/// Registry APIs with a built-in client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryProvider {
+    /// GraphQL Hive
+    Hive,
+}
+
+/// Which validation checks are reported as errors, warnings, or not at all.
+///
+/// `profile` picks the baseline; `checks` overrides individual checks by
+/// name (for example `unknown-directive`), whatever the profile says.
+/// `spec` picks the spec edition the project's server implements.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValidationConfig {
+    pub profile: ValidationProfile,
+    pub checks: BTreeMap<String, ValidationSeverity>,
+    pub spec: SpecEdition,
+}
+
+/// GraphQL spec edition the project targets.
+///
+/// Features newer than the edition are reported where they're used, so a
+/// schema or operation doesn't rely on something the server won't accept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SpecEdition {
+    /// No interfaces implementing interfaces, `repeatable` directives or
+    /// `@specifiedBy`.
+    #[serde(rename = "june-2018")]
+    June2018,
+    /// Adds interfaces implementing interfaces, `repeatable` directives and
+    /// `@specifiedBy`.
+    #[serde(rename = "october-2021")]
+    October2021,
+    /// The working draft, including `@oneOf` input objects.
+    #[default]
+    #[serde(rename = "draft")]
+    Draft,
+}
+
+impl SpecEdition {
+    /// Human-readable edition name for diagnostics
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            SpecEdition::June2018 => "June 2018",
+            SpecEdition::October2021 => "October 2021",
+            SpecEdition::Draft => "draft",
+        }
+    }
+}
+
+/// Baseline severities for validation checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ValidationProfile {
+    /// Every spec violation is an error, including ones the analyzer
+    /// otherwise tolerates for client conventions such as Relay's
+    /// `@arguments`.
+    SpecStrict,
+    /// Every spec violation is an error; client conventions are tolerated.
+    #[default]
+    Recommended,
+    /// Checks that commonly fail during gradual adoption (directives,
+    /// arguments, unused variables) are warnings.
+    Relaxed,
+}
+
+/// Severity of a single validation check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationSeverity {
+    Error,
+    Warn,
+    Off,
 }
 
 /// GraphQL client library configuration.
@@ -709,7 +1253,9 @@ fn try_url_as_key_from_array(arr: &[serde_json::Value]) -> Option<IntrospectionS
 }
 
 /// Configuration for introspecting a remote GraphQL endpoint
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// `Debug` output leaves out header values, which usually hold credentials.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct IntrospectionSchemaConfig {
     /// The GraphQL endpoint URL to introspect
@@ -728,6 +1274,23 @@ pub struct IntrospectionSchemaConfig {
     pub retry: Option<u32>,
 }
 
+impl std::fmt::Debug for IntrospectionSchemaConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntrospectionSchemaConfig")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .as_ref()
+                    .map(|headers| RedactedHeaders::new(headers.keys().map(String::as_str))),
+            )
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .finish()
+    }
+}
+
 impl SchemaConfig {
     /// Get all schema paths/patterns as a slice
     /// For introspection configs, returns an empty vec (use `introspection_config()` instead)
@@ -802,6 +1365,7 @@ mod tests {
             exclude: None,
             extensions: None,
             compiled_patterns: OnceLock::new(),
+            env_values: Vec::new(),
         }));
 
         assert!(!config.is_multi_project());
@@ -822,6 +1386,7 @@ mod tests {
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
         projects.insert(
@@ -833,6 +1398,7 @@ mod tests {
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
 
@@ -882,6 +1448,186 @@ mod tests {
         assert_eq!(multiple.patterns(), vec!["**/*.graphql", "**/*.ts"]);
     }
 
+    #[test]
+    fn test_complexity_config() {
+        let yaml = r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    complexity:
+      defaultListSize: 25
+      fieldCosts:
+        Query.search: 10
+";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let complexity = config.complexity().unwrap();
+        assert_eq!(complexity.default_list_size, 25);
+        assert_eq!(complexity.field_costs.get("Query.search"), Some(&10));
+        assert!(complexity.type_costs.is_empty());
+        assert_eq!(complexity.pagination_arguments, vec!["first", "last"]);
+    }
+
+    #[test]
+    fn test_n_plus_one_config() {
+        let yaml = r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    nPlusOne:
+      allowTypes: [Tag]
+";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let n_plus_one = config.n_plus_one().unwrap();
+        assert_eq!(n_plus_one.max_depth, 2);
+        assert_eq!(n_plus_one.allow_types, vec!["Tag"]);
+        assert_eq!(n_plus_one.pagination_arguments, vec!["first", "last"]);
+
+        let yaml = "schema: schema.graphql";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert_eq!(config.n_plus_one(), None);
+    }
+
+    #[test]
+    fn test_operation_usage_report() {
+        let yaml = r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    operationUsage: "telemetry/usage.json"
+"#;
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert_eq!(
+            config.operation_usage().as_deref(),
+            Some("telemetry/usage.json")
+        );
+
+        let report: OperationUsageReport =
+            serde_json::from_str(r#"{ "operations": { "GetUser": 1520 } }"#).unwrap();
+        assert_eq!(report.window_days, 30);
+        assert_eq!(report.operations.get("GetUser"), Some(&1520));
+    }
+
+    #[test]
+    fn test_persisted_operation_allow_list() {
+        let yaml = r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    persistedOperations: "persisted-operations.json"
+"#;
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert_eq!(
+            config.persisted_operations().as_deref(),
+            Some("persisted-operations.json")
+        );
+
+        let list = PersistedOperationAllowList::from_json(r#"["ABC123", "def456"]"#).unwrap();
+        assert!(list.contains("abc123"));
+        assert!(list.contains("def456"));
+
+        let manifest =
+            PersistedOperationAllowList::from_json(r#"{ "abc123": "query A { a }" }"#).unwrap();
+        assert!(manifest.contains("abc123"));
+        assert!(!manifest.contains("def456"));
+
+        assert!(PersistedOperationAllowList::from_json(r#"{ "operations": 1 "#).is_err());
+    }
+
+    #[test]
+    fn test_registry_config() {
+        let yaml = r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    registry:
+      provider: hive
+      token: "secret"
+      service: users
+"#;
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let registry = config.registry().unwrap();
+        assert_eq!(registry.provider, RegistryProvider::Hive);
+        assert_eq!(registry.token, "secret");
+        assert_eq!(registry.service.as_deref(), Some("users"));
+        assert_eq!(registry.url, None);
+    }
+
+    #[test]
+    fn test_scalars_config() {
+        let yaml = r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    scalars:
+      DateTime:
+        serialization: "ISO-8601 string"
+        example: "2024-01-15T09:30:00Z"
+        format: date-time
+      JSON:
+        description: "Arbitrary JSON value"
+"#;
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let scalars = config.scalars();
+        assert_eq!(scalars.len(), 2);
+        assert_eq!(
+            config.scalar_formats(),
+            BTreeMap::from([("DateTime".to_string(), ScalarFormat::DateTime)])
+        );
+        let date_time = &scalars["DateTime"];
+        assert_eq!(date_time.serialization.as_deref(), Some("ISO-8601 string"));
+        assert_eq!(date_time.example.as_deref(), Some("2024-01-15T09:30:00Z"));
+        assert_eq!(
+            scalars["JSON"].description.as_deref(),
+            Some("Arbitrary JSON value")
+        );
+    }
+
+    #[test]
+    fn test_validation_config() {
+        let yaml = r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    validation:
+      profile: spec-strict
+      checks:
+        unknown-directive: warn
+        unused-variable: off
+";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let validation = config.validation().unwrap();
+        assert_eq!(validation.profile, ValidationProfile::SpecStrict);
+        assert_eq!(
+            validation.checks.get("unknown-directive"),
+            Some(&ValidationSeverity::Warn)
+        );
+        assert_eq!(
+            validation.checks.get("unused-variable"),
+            Some(&ValidationSeverity::Off)
+        );
+        assert_eq!(validation.spec, SpecEdition::Draft);
+
+        let yaml = r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    validation: {}
+";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert_eq!(config.validation(), Some(ValidationConfig::default()));
+
+        let yaml = r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    validation:
+      spec: june-2018
+";
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert_eq!(config.validation().unwrap().spec, SpecEdition::June2018);
+        assert!(SpecEdition::June2018 < SpecEdition::October2021);
+    }
+
     #[test]
     fn test_client_config_apollo() {
         let yaml = r"
@@ -957,6 +1703,7 @@ extensions:
             exclude: None,
             extensions: None,
             compiled_patterns: OnceLock::new(),
+            env_values: Vec::new(),
         }));
 
         let workspace_root = PathBuf::from("/workspace");
@@ -982,6 +1729,7 @@ extensions:
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
         projects.insert(
@@ -993,6 +1741,7 @@ extensions:
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
 
@@ -1032,6 +1781,7 @@ extensions:
                 exclude: Some(vec!["**/__tests__/**".to_string()]),
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
 
@@ -1107,6 +1857,7 @@ extensions:
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
 
@@ -1127,6 +1878,38 @@ extensions:
         );
     }
 
+    #[test]
+    fn test_document_negation_patterns() {
+        use std::path::PathBuf;
+
+        let config = GraphQLConfig::Single(ProjectConfig::new(
+            SchemaConfig::Path("schema.graphql".to_string()),
+            Some(DocumentsConfig::Patterns(vec![
+                "src/**/*.{ts,{j,t}sx}".to_string(),
+                "!**/__tests__/**".to_string(),
+            ])),
+            None,
+            None,
+            None,
+        ));
+        let workspace_root = PathBuf::from("/workspace");
+
+        assert_eq!(
+            config.find_project_for_document(
+                &PathBuf::from("/workspace/src/components/User.jsx"),
+                &workspace_root
+            ),
+            Some("default")
+        );
+        assert_eq!(
+            config.find_project_for_document(
+                &PathBuf::from("/workspace/src/components/__tests__/User.tsx"),
+                &workspace_root
+            ),
+            None
+        );
+    }
+
     #[test]
     fn test_schema_files_match_project() {
         use std::path::PathBuf;
@@ -1147,6 +1930,7 @@ extensions:
                 exclude: None,
                 extensions: None,
                 compiled_patterns: OnceLock::new(),
+                env_values: Vec::new(),
             },
         );
 
@@ -1207,6 +1991,11 @@ documents: "**/*.graphql"
             Some(&"Bearer token".to_string())
         );
         assert_eq!(headers.get("X-API-Key"), Some(&"my-key".to_string()));
+
+        let debug = format!("{config:?}");
+        assert!(debug.contains("\"Authorization\": \"<redacted>\""));
+        assert!(!debug.contains("Bearer token"));
+        assert!(!debug.contains("my-key"));
     }
 
     #[test]
@@ -1335,6 +2124,36 @@ schema:
         assert_eq!(introspection.url, "https://api.example.com/graphql");
         assert!(introspection.headers.is_none());
     }
+
+    #[test]
+    fn test_scoped_schema_variant() {
+        let yaml = r#"
+schema: public/schema.graphql
+extensions:
+  graphql-analyzer:
+    schemaVariants:
+      staging: schema/staging.graphql
+      admin:
+        schema: "admin/schema/*.graphql"
+        documents: ["admin/**/*.graphql"]
+"#;
+        let config: ProjectConfig = serde_saphyr::from_str(yaml).unwrap();
+        let variants = config.schema_variants();
+
+        assert_eq!(
+            variants["staging"].schema_paths(),
+            vec!["schema/staging.graphql"]
+        );
+        assert!(variants["staging"].document_patterns().is_empty());
+        assert_eq!(
+            variants["admin"].schema_paths(),
+            vec!["admin/schema/*.graphql"]
+        );
+        assert_eq!(
+            variants["admin"].document_patterns(),
+            vec!["admin/**/*.graphql"]
+        );
+    }
 }
 
 /// Tests that validate the JSON schema stays in sync with Rust types.
@@ -1570,18 +2389,21 @@ extensions:
     #[test]
     fn sync_extract_config() {
         assert_sync(
-            r#"
+            r##"
 schema: schema.graphql
 extensions:
   graphql-analyzer:
     client: apollo
     extractConfig:
       gqlMagicComment: graphql
+      gqlTemplateComment: "#graphql"
       modules:
         - graphql-tag
         - { name: "@apollo/client", identifier: gql }
       globalGqlIdentifierName: ["gql", "graphql"]
-"#,
+      maxFileBytes: 1048576
+      timeoutMs: 2000
+"##,
             "extract config (pluck shape)",
         );
     }
@@ -1630,6 +2452,135 @@ extensions:
         );
     }
 
+    #[test]
+    fn sync_schema_variants() {
+        assert_sync(
+            r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    schemaVariants:
+      staging: schema/staging.graphql
+",
+            "schema variants",
+        );
+    }
+
+    #[test]
+    fn sync_scoped_schema_variants() {
+        assert_sync(
+            r#"
+schema: public/schema.graphql
+documents: "src/**/*.ts"
+extensions:
+  graphql-analyzer:
+    schemaVariants:
+      admin:
+        schema: ["admin/schema/*.graphql"]
+        documents: "admin/src/**/*.ts"
+"#,
+            "scoped schema variants",
+        );
+    }
+
+    #[test]
+    fn sync_complexity() {
+        assert_sync(
+            r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    complexity:
+      defaultListSize: 20
+      typeCosts:
+        SearchResult: 5
+      fieldCosts:
+        Query.search: 10
+      paginationArguments: [first, last, limit]
+",
+            "complexity cost model",
+        );
+    }
+
+    #[test]
+    fn sync_operation_usage() {
+        assert_sync(
+            r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    operationUsage: "telemetry/operation-usage.json"
+"#,
+            "operation usage report",
+        );
+    }
+
+    #[test]
+    fn sync_persisted_operations() {
+        assert_sync(
+            r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    persistedOperations: "persisted-operations.json"
+"#,
+            "persisted operation allow-list",
+        );
+    }
+
+    #[test]
+    fn sync_registry() {
+        assert_sync(
+            r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    registry:
+      provider: hive
+      url: "https://hive.example.com/graphql"
+      token: "secret"
+      service: users
+      timeout: 60
+"#,
+            "schema registry",
+        );
+    }
+
+    #[test]
+    fn sync_scalars() {
+        assert_sync(
+            r#"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    scalars:
+      DateTime:
+        serialization: "ISO-8601 string"
+        description: "A point in time"
+        example: "2024-01-15T09:30:00Z"
+"#,
+            "custom scalar documentation",
+        );
+    }
+
+    #[test]
+    fn sync_validation() {
+        assert_sync(
+            r"
+schema: schema.graphql
+extensions:
+  graphql-analyzer:
+    validation:
+      profile: relaxed
+      checks:
+        unknown-directive: warn
+        unused-variable: off
+      spec: october-2021
+",
+            "validation profile",
+        );
+    }
+
     #[test]
     fn sync_arbitrary_extensions() {
         // extensions should allow arbitrary keys
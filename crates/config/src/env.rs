@@ -8,10 +8,22 @@ use std::env;
 ///
 /// This matches the graphql-config standard behavior where environment
 /// variables can be used in endpoint URLs and auth headers.
+///
+/// `${secret:NAME}` is left as is, to be resolved by a
+/// [`SecretsProvider`](crate::SecretsProvider) when the request that needs
+/// it is sent.
 pub fn interpolate_env_vars(input: &str) -> Result<String, EnvInterpolationError> {
     interpolate_env_vars_with(input, |name| env::var(name).ok())
 }
 
+/// Like [`interpolate_env_vars`], also returning the values that came from
+/// the environment. Defaults written in the input aren't included.
+pub(crate) fn interpolate_env_vars_recording(
+    input: &str,
+) -> Result<(String, Vec<String>), EnvInterpolationError> {
+    interpolate_recording(input, |name| env::var(name).ok())
+}
+
 /// Interpolation error for environment variables.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EnvInterpolationError {
@@ -31,19 +43,47 @@ impl std::fmt::Display for EnvInterpolationError {
 
 impl std::error::Error for EnvInterpolationError {}
 
+/// Opens a secret reference inside `${...}`
+const SECRET_PREFIX: &str = "secret:";
+
 /// Interpolate environment variables using a custom lookup function.
 /// Useful for testing without modifying actual env vars.
 fn interpolate_env_vars_with(
     input: &str,
     lookup: impl Fn(&str) -> Option<String>,
 ) -> Result<String, EnvInterpolationError> {
+    interpolate_recording(input, lookup).map(|(result, _)| result)
+}
+
+fn interpolate_recording(
+    input: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<(String, Vec<String>), EnvInterpolationError> {
     let mut result = String::with_capacity(input.len());
+    let mut env_values = Vec::new();
     let mut chars = input.chars().peekable();
 
     while let Some(ch) = chars.next() {
         if ch == '$' && chars.peek() == Some(&'{') {
             chars.next(); // consume '{'
 
+            // `${secret:NAME}` is its own token, not a variable with a
+            // default: copy it through for the secrets provider
+            if chars
+                .clone()
+                .take(SECRET_PREFIX.len())
+                .eq(SECRET_PREFIX.chars())
+            {
+                result.push_str("${");
+                for c in chars.by_ref() {
+                    result.push(c);
+                    if c == '}' {
+                        break;
+                    }
+                }
+                continue;
+            }
+
             // Read until '}' or ':' (for default value)
             let mut var_name = String::new();
             let mut default_value = None;
@@ -87,7 +127,10 @@ fn interpolate_env_vars_with(
             }
 
             match lookup(&var_name) {
-                Some(value) => result.push_str(&value),
+                Some(value) => {
+                    result.push_str(&value);
+                    env_values.push(value);
+                }
                 None => match default_value {
                     Some(default) => result.push_str(&default),
                     None => {
@@ -100,7 +143,7 @@ fn interpolate_env_vars_with(
         }
     }
 
-    Ok(result)
+    Ok((result, env_values))
 }
 
 #[cfg(test)]
@@ -153,6 +196,39 @@ mod tests {
         assert_eq!(err.variable, "API_URL");
     }
 
+    #[test]
+    fn secrets_left_for_provider() {
+        let lookup = make_lookup(&[("secret", "env")]);
+        let result =
+            interpolate_env_vars_with("Authorization: Bearer ${secret:API_TOKEN}", lookup).unwrap();
+        assert_eq!(result, "Authorization: Bearer ${secret:API_TOKEN}");
+    }
+
+    #[test]
+    fn variable_named_secret_is_an_env_var() {
+        let lookup = make_lookup(&[("secret", "from-env")]);
+        let result = interpolate_env_vars_with("a: ${secret} b: ${secret}", lookup).unwrap();
+        assert_eq!(result, "a: from-env b: from-env");
+
+        let result = interpolate_env_vars_with("a: ${secret}", |_| None);
+        assert_eq!(result.unwrap_err().variable, "secret");
+    }
+
+    #[test]
+    fn records_values_from_the_environment() {
+        let lookup = make_lookup(&[("HOST", "api.example.com")]);
+        let (result, env_values) = interpolate_recording(
+            "url: https://${HOST}/${PATH:graphql}?t=${secret:TOKEN}",
+            lookup,
+        )
+        .unwrap();
+        assert_eq!(
+            result,
+            "url: https://api.example.com/graphql?t=${secret:TOKEN}"
+        );
+        assert_eq!(env_values, vec!["api.example.com".to_string()]);
+    }
+
     #[test]
     fn multiple_vars() {
         let lookup = make_lookup(&[("HOST", "api.example.com"), ("TOKEN", "secret123")]);
@@ -0,0 +1,470 @@
+//! File globbing shared by schema and document loading.
+//!
+//! Config patterns follow graphql-config: braces expand (including nested
+//! and repeated groups, `src/**/*.{ts,{j,t}sx}`), and patterns starting with
+//! `!` remove files matched by the others. Walking a directory optionally
+//! respects `.gitignore` files.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+
+/// Directories never searched for files
+const SKIPPED_DIRS: &[&str] = &["node_modules", ".git"];
+
+/// A pattern that failed to compile.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid glob pattern '{pattern}': {message}")]
+pub struct GlobError {
+    /// The pattern as written in the config
+    pub pattern: String,
+    pub message: String,
+}
+
+/// A file found by [`FileGlobs::walk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobMatch {
+    pub path: PathBuf,
+    /// The first pattern (as written in the config) that matched the file
+    pub pattern: String,
+}
+
+/// Result of [`FileGlobs::walk`].
+#[derive(Debug, Clone, Default)]
+pub struct GlobWalk {
+    /// Matched files, each listed once
+    pub matches: Vec<GlobMatch>,
+    /// Patterns (excluding negations) that matched no files
+    pub unmatched: Vec<String>,
+}
+
+impl GlobWalk {
+    /// The matched paths, in walk order.
+    #[must_use]
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.matches.iter().map(|m| m.path.clone()).collect()
+    }
+}
+
+/// A compiled list of include and `!` negation patterns, relative to a root
+/// directory.
+#[derive(Debug, Clone, Default)]
+pub struct FileGlobs {
+    includes: Vec<IncludeGlob>,
+    /// Patterns as written, in config order (negations excluded)
+    patterns: Vec<String>,
+    excludes: GlobSet,
+    has_excludes: bool,
+    errors: Vec<GlobError>,
+    respect_gitignore: bool,
+}
+
+/// One brace expansion of an include pattern
+#[derive(Debug, Clone)]
+struct IncludeGlob {
+    /// Index into `FileGlobs::patterns`
+    pattern: usize,
+    /// Leading components without glob syntax, where walking starts
+    base: String,
+    /// Components below `base` a match can be at, `None` when the pattern
+    /// contains `**`
+    depth: Option<usize>,
+    matcher: GlobMatcher,
+}
+
+impl FileGlobs {
+    /// Compile `patterns`. Patterns starting with `!` are negations.
+    ///
+    /// Invalid patterns are skipped and reported by [`errors`](Self::errors).
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let mut globs = Self::default();
+        let mut excludes = GlobSetBuilder::new();
+
+        for raw in patterns {
+            let raw = raw.as_ref().trim();
+            if let Some(negated) = raw.strip_prefix('!') {
+                for expanded in expand_braces(strip_dot_slash(negated.trim())) {
+                    match build_glob(&expanded) {
+                        Ok(glob) => {
+                            excludes.add(glob);
+                            globs.has_excludes = true;
+                        }
+                        Err(message) => globs.errors.push(GlobError {
+                            pattern: raw.to_string(),
+                            message,
+                        }),
+                    }
+                }
+                continue;
+            }
+
+            let index = globs.patterns.len();
+            globs.patterns.push(raw.to_string());
+            for expanded in expand_braces(strip_dot_slash(raw)) {
+                match build_glob(&expanded) {
+                    Ok(glob) => {
+                        let (base, depth) = split_base(&expanded);
+                        globs.includes.push(IncludeGlob {
+                            pattern: index,
+                            base,
+                            depth,
+                            matcher: glob.compile_matcher(),
+                        });
+                    }
+                    Err(message) => globs.errors.push(GlobError {
+                        pattern: raw.to_string(),
+                        message,
+                    }),
+                }
+            }
+        }
+
+        globs.excludes = excludes.build().unwrap_or_else(|e| {
+            tracing::warn!("Failed to combine negation patterns: {e}");
+            globs.has_excludes = false;
+            GlobSet::empty()
+        });
+        globs
+    }
+
+    /// Skip files ignored by `.gitignore` (and `.git/info/exclude`) when
+    /// walking.
+    #[must_use]
+    pub fn respect_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+
+    /// Patterns that failed to compile
+    #[must_use]
+    pub fn errors(&self) -> &[GlobError] {
+        &self.errors
+    }
+
+    /// Whether there are no patterns at all
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty() && !self.has_excludes
+    }
+
+    /// Whether `rel_path` matches an include pattern and no negation.
+    #[must_use]
+    pub fn is_match(&self, rel_path: &str) -> bool {
+        let rel_path = strip_dot_slash(rel_path);
+        self.includes
+            .iter()
+            .any(|include| include.matcher.is_match(rel_path))
+            && !self.is_excluded(rel_path)
+    }
+
+    /// Whether `rel_path` matches a negation pattern.
+    #[must_use]
+    pub fn is_excluded(&self, rel_path: &str) -> bool {
+        self.has_excludes && self.excludes.is_match(strip_dot_slash(rel_path))
+    }
+
+    /// Find the files under `root` matching the patterns.
+    ///
+    /// Only the directories named by each pattern's literal prefix are
+    /// searched, and `node_modules` never is.
+    #[must_use]
+    pub fn walk(&self, root: &Path) -> GlobWalk {
+        let mut pattern_matched = vec![false; self.patterns.len()];
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+
+        let mut walked_bases = HashSet::new();
+        for include in &self.includes {
+            if !walked_bases.insert(include.base.as_str()) {
+                continue;
+            }
+            let same_base = self
+                .includes
+                .iter()
+                .filter(|other| other.base == include.base);
+            let depth = same_base
+                .clone()
+                .try_fold(0, |max, other| other.depth.map(|depth| max.max(depth)));
+
+            let walk_root = root.join(&include.base);
+            if !walk_root.exists() {
+                continue;
+            }
+            for entry in self.walker(&walk_root, depth).flatten() {
+                if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+                    continue;
+                }
+                let rel_path = relative_to_base(&include.base, &walk_root, entry.path());
+                if self.is_excluded(&rel_path) {
+                    continue;
+                }
+
+                // Includes are in config order, so the first match is the
+                // earliest pattern
+                let mut first_match = None;
+                for other in same_base.clone() {
+                    if other.matcher.is_match(&rel_path) {
+                        pattern_matched[other.pattern] = true;
+                        first_match.get_or_insert(other.pattern);
+                    }
+                }
+                if let Some(pattern) = first_match {
+                    if seen.insert(entry.path().to_path_buf()) {
+                        matches.push(GlobMatch {
+                            path: entry.into_path(),
+                            pattern: self.patterns[pattern].clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let unmatched = self
+            .patterns
+            .iter()
+            .zip(pattern_matched)
+            .filter(|(_, matched)| !matched)
+            .map(|(pattern, _)| pattern.clone())
+            .collect();
+        GlobWalk { matches, unmatched }
+    }
+
+    fn walker(&self, walk_root: &Path, depth: Option<usize>) -> ignore::Walk {
+        ignore::WalkBuilder::new(walk_root)
+            .standard_filters(false)
+            .git_ignore(self.respect_gitignore)
+            .git_exclude(self.respect_gitignore)
+            .parents(self.respect_gitignore)
+            .require_git(false)
+            .follow_links(true)
+            .max_depth(depth)
+            .filter_entry(|entry| {
+                !(entry.file_type().is_some_and(|kind| kind.is_dir())
+                    && SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir))
+            })
+            .sort_by_file_name(std::cmp::Ord::cmp)
+            .build()
+    }
+}
+
+/// Expand brace groups into separate patterns.
+///
+/// Handles several groups and nested groups: `{src,lib}/**/*.{ts,{j,t}sx}`
+/// expands to eight patterns. Alternatives are trimmed, a `{` without a
+/// closing `}` is kept literally, and `\{` escapes a brace.
+#[must_use]
+pub fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some((open, close)) = find_brace_group(pattern) else {
+        return vec![pattern.to_string()];
+    };
+    let before = &pattern[..open];
+    let after = &pattern[close + 1..];
+
+    let mut expanded = Vec::new();
+    for alternative in split_alternatives(&pattern[open + 1..close]) {
+        for pattern in expand_braces(&format!("{before}{}{after}", alternative.trim())) {
+            if !expanded.contains(&pattern) {
+                expanded.push(pattern);
+            }
+        }
+    }
+    expanded
+}
+
+/// The byte offsets of the first `{` that has a matching `}`
+fn find_brace_group(pattern: &str) -> Option<(usize, usize)> {
+    let bytes = pattern.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'{' => {
+                if let Some(close) = matching_brace(bytes, index) {
+                    return Some((index, close));
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+fn matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut index = open;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 1,
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Split a brace group's body at its top-level commas
+fn split_alternatives(body: &str) -> Vec<&str> {
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, ch) in body.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(&body[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(&body[start..]);
+    alternatives
+}
+
+fn build_glob(pattern: &str) -> Result<Glob, String> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .backslash_escape(true)
+        .build()
+        .map_err(|e| e.kind().to_string())
+}
+
+fn strip_dot_slash(pattern: &str) -> &str {
+    pattern.strip_prefix("./").unwrap_or(pattern)
+}
+
+/// Split a pattern into its literal leading directories and how deep below
+/// them it can match
+fn split_base(pattern: &str) -> (String, Option<usize>) {
+    let components: Vec<&str> = pattern.split('/').collect();
+    let Some(first_glob) = components
+        .iter()
+        .position(|component| component.contains(['*', '?', '[']) || component.contains('\\'))
+    else {
+        // A literal path: the file itself, or everything in the directory
+        return (pattern.to_string(), None);
+    };
+
+    let depth = if components[first_glob..].contains(&"**") {
+        None
+    } else {
+        Some(components.len() - first_glob)
+    };
+    (components[..first_glob].join("/"), depth)
+}
+
+/// `path`'s location under `walk_root`, spelled the way patterns with the
+/// literal prefix `base` are written
+fn relative_to_base(base: &str, walk_root: &Path, path: &Path) -> String {
+    let below = path
+        .strip_prefix(walk_root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    match (base.is_empty(), below.is_empty()) {
+        (true, _) => below,
+        (false, true) => base.to_string(),
+        (false, false) => format!("{}/{below}", base.trim_end_matches('/')),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+
+    fn walked(globs: &FileGlobs, root: &Path) -> Vec<String> {
+        globs
+            .walk(root)
+            .matches
+            .iter()
+            .map(|m| relative_to_base("", root, &m.path))
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("src/**/*.graphql"), vec!["src/**/*.graphql"]);
+        assert_eq!(
+            expand_braces("src/**/*.{ts, tsx}"),
+            vec!["src/**/*.ts", "src/**/*.tsx"]
+        );
+        assert_eq!(
+            expand_braces("{src,lib}/*.{ts,{j,t}sx}"),
+            vec![
+                "src/*.ts",
+                "src/*.jsx",
+                "src/*.tsx",
+                "lib/*.ts",
+                "lib/*.jsx",
+                "lib/*.tsx",
+            ]
+        );
+        assert_eq!(expand_braces("src/{a.graphql"), vec!["src/{a.graphql"]);
+    }
+
+    #[test]
+    fn test_walk_applies_negations() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        write(root, "src/a.ts");
+        write(root, "src/nested/b.jsx");
+        write(root, "src/__tests__/c.ts");
+        write(root, "src/node_modules/d.ts");
+        write(root, "other/e.ts");
+
+        let globs = FileGlobs::new(&["src/**/*.{ts,tsx,js,jsx}", "!**/__tests__/**"]);
+        assert!(globs.errors().is_empty());
+        assert_eq!(walked(&globs, root), vec!["src/a.ts", "src/nested/b.jsx"]);
+        assert!(globs.is_match("src/a.ts"));
+        assert!(!globs.is_match("src/__tests__/c.ts"));
+    }
+
+    #[test]
+    fn test_walk_reports_unmatched_patterns() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        write(root, "schema.graphql");
+
+        let walk = FileGlobs::new(&["./schema.graphql", "missing/*.graphql"]).walk(root);
+        assert_eq!(walk.matches.len(), 1);
+        assert_eq!(walk.matches[0].pattern, "./schema.graphql");
+        assert_eq!(walk.unmatched, vec!["missing/*.graphql"]);
+    }
+
+    #[test]
+    fn test_walk_respects_gitignore() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        std::fs::write(root.join(".gitignore"), "generated/\n").unwrap();
+        write(root, "src/query.graphql");
+        write(root, "src/generated/query.graphql");
+
+        let globs = FileGlobs::new(&["src/**/*.graphql"]);
+        assert_eq!(walked(&globs, root).len(), 2);
+        assert_eq!(
+            walked(&globs.respect_gitignore(true), root),
+            vec!["src/query.graphql"]
+        );
+    }
+}
@@ -1,17 +1,33 @@
 mod config;
 mod env;
 mod error;
+mod globs;
 mod loader;
+mod secrets;
+mod settings;
 pub mod suggestions;
 mod validation;
 
 pub use config::{
-    ClientConfig, DocumentsConfig, GraphQLConfig, IntrospectionSchemaConfig, ProjectConfig,
-    SchemaConfig,
+    ClientConfig, ComplexityConfig, DocumentsConfig, GraphQLConfig, IntrospectionSchemaConfig,
+    NPlusOneConfig, OperationUsageReport, PersistedOperationAllowList, ProjectConfig,
+    RegistryConfig, RegistryProvider, ScalarConfig, ScalarFormat, SchemaConfig,
+    SchemaVariantConfig, ScopedSchemaVariant, SpecEdition, ValidationConfig, ValidationProfile,
+    ValidationSeverity,
 };
 pub use env::{interpolate_env_vars, EnvInterpolationError};
 pub use error::{ConfigError, Result};
+pub use globs::{expand_braces, FileGlobs, GlobError, GlobMatch, GlobWalk};
 pub use loader::{find_config, load_config, load_config_from_str, CONFIG_FILES};
+pub use secrets::{
+    resolve_header_secrets, resolve_secrets, EnvSecrets, MissingSecretError, RedactedHeaders,
+    SecretsProvider,
+};
+pub use settings::{
+    analyzer_settings_path, load_analyzer_settings, load_analyzer_settings_from_str,
+    AnalyzerSettings, CacheSettings, CompletionSettings, FeatureFlags, FormatSettings, IndentStyle,
+    InlayHintSettings, MemorySettings, ANALYZER_SETTINGS_FILE, DEFAULT_CACHE_DIRECTORY,
+};
 pub use validation::{
     extension_namespace_warnings, validate, ConfigValidationError, FileType, LintValidationContext,
     Location, Severity,
@@ -2,10 +2,11 @@ use crate::{ConfigError, GraphQLConfig, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-/// Wrapper to apply environment variable interpolation to config contents.
-/// Errors in interpolation are converted to `ConfigError::Invalid`.
-fn apply_env_interpolation(contents: &str, path: &Path) -> Result<String> {
-    crate::env::interpolate_env_vars(contents).map_err(|e| ConfigError::Invalid {
+/// Wrapper to apply environment variable interpolation to config contents,
+/// returning the interpolated contents and the values taken from the
+/// environment. Errors in interpolation are converted to `ConfigError::Invalid`.
+fn apply_env_interpolation(contents: &str, path: &Path) -> Result<(String, Vec<String>)> {
+    crate::env::interpolate_env_vars_recording(contents).map_err(|e| ConfigError::Invalid {
         path: path.to_path_buf(),
         message: format!("Environment variable interpolation failed: {e}"),
     })
@@ -101,7 +102,7 @@ pub fn load_config(path: &Path) -> Result<GraphQLConfig> {
 /// interpolated before parsing. This matches graphql-config standard behavior.
 #[tracing::instrument(skip(contents), fields(path = %path.display(), size = contents.len()))]
 pub fn load_config_from_str(contents: &str, path: &Path) -> Result<GraphQLConfig> {
-    let contents = apply_env_interpolation(contents, path)?;
+    let (contents, env_values) = apply_env_interpolation(contents, path)?;
     let contents = contents.as_str();
     let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("");
 
@@ -112,7 +113,7 @@ pub fn load_config_from_str(contents: &str, path: &Path) -> Result<GraphQLConfig
 
     tracing::debug!(extension, file_name, "Detecting config format");
 
-    let config = match extension {
+    let mut config = match extension {
         "yml" | "yaml" => {
             tracing::trace!("Parsing as YAML");
             parse_yaml(contents, path)?
@@ -139,6 +140,7 @@ pub fn load_config_from_str(contents: &str, path: &Path) -> Result<GraphQLConfig
 
     tracing::debug!("Validating config");
     validate_config(&config, path)?;
+    config.set_env_values(&env_values);
 
     Ok(config)
 }
@@ -492,6 +494,9 @@ schema:
             headers.get("Authorization"),
             Some(&"Bearer default-token".to_string())
         );
+        // The URL came from the environment; the header's default didn't
+        assert!(project.contains_env_value(&introspection.url));
+        assert!(!project.contains_env_value("Bearer default-token"));
         // Clean up
         std::env::remove_var("GRAPHQL_TEST_URL");
     }
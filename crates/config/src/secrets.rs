@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+/// Supplies the values of `${secret:NAME}` references in introspection
+/// headers.
+///
+/// Unlike `${VAR}`, which is replaced when the config is loaded, a secret is
+/// looked up just before the request that needs it is sent, so it never
+/// ends up in the loaded config. Editors and other embedders can install a
+/// provider backed by their own secret storage; [`EnvSecrets`] reads
+/// environment variables.
+pub trait SecretsProvider: Send + Sync {
+    /// The value of the secret called `name`, if there is one
+    fn secret(&self, name: &str) -> Option<String>;
+}
+
+/// Resolves each secret from the environment variable of the same name
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EnvSecrets;
+
+impl SecretsProvider for EnvSecrets {
+    fn secret(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+/// A `${secret:NAME}` reference the provider had no value for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingSecretError {
+    /// The secret's name
+    pub name: String,
+}
+
+impl std::fmt::Display for MissingSecretError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "secret '{}' is not available", self.name)
+    }
+}
+
+impl std::error::Error for MissingSecretError {}
+
+const SECRET_PREFIX: &str = "${secret:";
+
+/// Replace every `${secret:NAME}` in `value` with the secret from
+/// `provider`.
+pub fn resolve_secrets(
+    value: &str,
+    provider: &dyn SecretsProvider,
+) -> Result<String, MissingSecretError> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find(SECRET_PREFIX) {
+        let after = &rest[start + SECRET_PREFIX.len()..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        let secret = provider.secret(name).ok_or_else(|| MissingSecretError {
+            name: name.to_string(),
+        })?;
+        result.push_str(&rest[..start]);
+        result.push_str(&secret);
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Resolve the secrets in every header value
+pub fn resolve_header_secrets(
+    headers: &HashMap<String, String>,
+    provider: &dyn SecretsProvider,
+) -> Result<HashMap<String, String>, MissingSecretError> {
+    headers
+        .iter()
+        .map(|(name, value)| Ok((name.clone(), resolve_secrets(value, provider)?)))
+        .collect()
+}
+
+/// Formats HTTP headers for `Debug` output with their values hidden, so
+/// credentials don't end up in logs and traces.
+pub struct RedactedHeaders<'a>(Vec<&'a str>);
+
+impl<'a> RedactedHeaders<'a> {
+    #[must_use]
+    pub fn new(names: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut names: Vec<&str> = names.into_iter().collect();
+        names.sort_unstable();
+        Self(names)
+    }
+}
+
+impl std::fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|name| (name, "<redacted>")))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Secrets(&'static [(&'static str, &'static str)]);
+
+    impl SecretsProvider for Secrets {
+        fn secret(&self, name: &str) -> Option<String> {
+            self.0
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, value)| (*value).to_string())
+        }
+    }
+
+    #[test]
+    fn test_resolve_secrets() {
+        let secrets = Secrets(&[("API_TOKEN", "s3cret"), ("TENANT", "acme")]);
+        assert_eq!(
+            resolve_secrets("Bearer ${secret:API_TOKEN}", &secrets).unwrap(),
+            "Bearer s3cret"
+        );
+        assert_eq!(
+            resolve_secrets("${secret:TENANT}:${secret:API_TOKEN}", &secrets).unwrap(),
+            "acme:s3cret"
+        );
+        assert_eq!(
+            resolve_secrets("no secrets ${here", &secrets).unwrap(),
+            "no secrets ${here"
+        );
+        assert_eq!(
+            resolve_secrets("Bearer ${secret:OTHER}", &secrets),
+            Err(MissingSecretError {
+                name: "OTHER".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_redacted_headers() {
+        let headers = HashMap::from([
+            ("X-Api-Key".to_string(), "key".to_string()),
+            ("Authorization".to_string(), "Bearer token".to_string()),
+        ]);
+        assert_eq!(
+            format!(
+                "{:?}",
+                RedactedHeaders::new(headers.keys().map(String::as_str))
+            ),
+            r#"{"Authorization": "<redacted>", "X-Api-Key": "<redacted>"}"#
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Analyzer settings from `.graphqlanalyzer.toml`.
+//!
+//! graphql-config describes a project's schema and documents; this file
+//! holds settings that only mean something to the analyzer, such as which
+//! editor features are on and how on-type formatting indents. Settings that
+//! also exist under `extensions.graphql-analyzer` (lint, complexity,
+//! validation and nPlusOne) take precedence over the extension when both
+//! are set.
+//!
+//! ```toml
+//! [lint]
+//! extends = "recommended"
+//!
+//! [lint.rules]
+//! noDeprecated = "error"
+//!
+//! [complexity]
+//! defaultListSize = 20
+//!
+//! [inlayHints]
+//! selectionSets = false
+//!
+//! [format]
+//! indentStyle = "spaces"
+//! indentWidth = 4
+//!
+//! [features]
+//! codeLens = false
+//!
+//! [completion]
+//! usageWeight = 0.5
+//!
+//! [memory]
+//! lazyParsing = true
+//! budgetMb = 256
+//!
+//! [cache]
+//! persist = true
+//! directory = ".graphql-analyzer/cache"
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ComplexityConfig, ConfigError, NPlusOneConfig, Result, ValidationConfig};
+
+/// File name of the analyzer settings file, looked up at the workspace root.
+pub const ANALYZER_SETTINGS_FILE: &str = ".graphqlanalyzer.toml";
+
+/// Contents of `.graphqlanalyzer.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct AnalyzerSettings {
+    /// Lint configuration, in the same shape as `extensions.graphql-analyzer.lint`.
+    pub lint: Option<serde_json::Value>,
+    /// Cost model for complexity analysis.
+    pub complexity: Option<ComplexityConfig>,
+    /// Validation severity profile and per-check overrides.
+    pub validation: Option<ValidationConfig>,
+    /// N+1 selection check; off unless set.
+    pub n_plus_one: Option<NPlusOneConfig>,
+    pub inlay_hints: InlayHintSettings,
+    pub format: FormatSettings,
+    pub features: FeatureFlags,
+    pub completion: CompletionSettings,
+    pub memory: MemorySettings,
+    pub cache: CacheSettings,
+}
+
+/// Which inlay hints are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct InlayHintSettings {
+    /// Hints after leaf fields, e.g. `name: String!`.
+    pub leaf_fields: bool,
+    /// Hints before the selection set of composite fields.
+    pub selection_sets: bool,
+    /// Hints after `__typename`.
+    pub typename: bool,
+    /// Hints after variables passed as arguments, e.g. `id: $id: ID!`.
+    pub variable_types: bool,
+    /// Hints after fragment spreads, e.g. `...UserFields on User`.
+    pub fragment_spreads: bool,
+    /// Hints for omitted arguments and input fields that have a default,
+    /// e.g. `users(limit = 10)`.
+    pub argument_defaults: bool,
+}
+
+impl Default for InlayHintSettings {
+    fn default() -> Self {
+        Self {
+            leaf_fields: true,
+            selection_sets: true,
+            typename: true,
+            variable_types: true,
+            fragment_spreads: true,
+            argument_defaults: true,
+        }
+    }
+}
+
+/// Indentation used by on-type formatting and generated selection sets.
+///
+/// Unset fields fall back to the editor's formatting options.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct FormatSettings {
+    pub indent_style: Option<IndentStyle>,
+    /// Spaces per indentation level. Ignored for tab indentation.
+    pub indent_width: Option<u32>,
+}
+
+impl FormatSettings {
+    /// One indentation level, or `None` when the editor's options should
+    /// decide. `editor_width` fills in a missing `indent_width`.
+    #[must_use]
+    pub fn indent(&self, editor_width: u32) -> Option<String> {
+        match (self.indent_style, self.indent_width) {
+            (Some(IndentStyle::Tabs), _) => Some("\t".to_string()),
+            (Some(IndentStyle::Spaces), width) => {
+                Some(" ".repeat(width.unwrap_or(editor_width) as usize))
+            }
+            (None, Some(width)) => Some(" ".repeat(width as usize)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IndentStyle {
+    Spaces,
+    Tabs,
+}
+
+/// Editor features that can be switched off. All are on by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct FeatureFlags {
+    pub code_lens: bool,
+    pub inlay_hints: bool,
+    pub semantic_tokens: bool,
+    pub on_type_formatting: bool,
+    /// Lint rules that look at the whole project, such as unused fields.
+    pub project_lints: bool,
+    /// Warnings in complexity analysis, such as nested pagination.
+    pub complexity_warnings: bool,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self {
+            code_lens: true,
+            inlay_hints: true,
+            semantic_tokens: true,
+            on_type_formatting: true,
+            project_lints: true,
+            complexity_warnings: true,
+        }
+    }
+}
+
+/// How completions are ordered.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CompletionSettings {
+    /// How much a field's use across the project's operations counts
+    /// against its position in the schema when ranking field completions,
+    /// from 0 (schema order only) to 1 (most used first). Deprecated fields
+    /// always sort last.
+    pub usage_weight: f64,
+}
+
+impl Default for CompletionSettings {
+    fn default() -> Self {
+        Self { usage_weight: 0.8 }
+    }
+}
+
+/// How much memory parsed documents may hold.
+///
+/// By default every document stays parsed for the life of the session. With
+/// `lazy_parsing` on, parse results are kept in a least-recently-used cache
+/// sized to fit `budget_mb`, and evicted files are re-parsed when next
+/// needed. Project indexes such as fragment and operation names are derived
+/// once per edit and don't count against the budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct MemorySettings {
+    pub lazy_parsing: bool,
+    /// Approximate memory, in megabytes, for parsed documents.
+    pub budget_mb: u32,
+}
+
+impl Default for MemorySettings {
+    fn default() -> Self {
+        Self {
+            lazy_parsing: false,
+            budget_mb: 512,
+        }
+    }
+}
+
+/// On-disk cache of parsed file summaries.
+///
+/// With `persist` on, the language server saves what it extracted from each
+/// document when it shuts down and reuses it for files whose content hasn't
+/// changed on the next start.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct CacheSettings {
+    pub persist: bool,
+    /// Where cache files are written, relative to the workspace root.
+    /// Defaults to [`DEFAULT_CACHE_DIRECTORY`].
+    pub directory: Option<String>,
+}
+
+/// Cache directory used when `[cache] directory` isn't set.
+pub const DEFAULT_CACHE_DIRECTORY: &str = ".graphql-analyzer/cache";
+
+impl CacheSettings {
+    /// The cache directory for a workspace rooted at `workspace_root`.
+    #[must_use]
+    pub fn resolve_directory(&self, workspace_root: &Path) -> PathBuf {
+        workspace_root.join(self.directory.as_deref().unwrap_or(DEFAULT_CACHE_DIRECTORY))
+    }
+}
+
+/// Path of the settings file for a workspace rooted at `workspace_root`.
+#[must_use]
+pub fn analyzer_settings_path(workspace_root: &Path) -> PathBuf {
+    workspace_root.join(ANALYZER_SETTINGS_FILE)
+}
+
+/// Load the settings file at `path`, or `None` if it doesn't exist.
+pub fn load_analyzer_settings(path: &Path) -> Result<Option<AnalyzerSettings>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(path)?;
+    load_analyzer_settings_from_str(&contents, path).map(Some)
+}
+
+/// Parse settings from TOML. `path` is only used in error messages.
+pub fn load_analyzer_settings_from_str(contents: &str, path: &Path) -> Result<AnalyzerSettings> {
+    toml::from_str(contents).map_err(|e| ConfigError::Invalid {
+        path: path.to_path_buf(),
+        message: format!("TOML parse error: {e}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(toml: &str) -> AnalyzerSettings {
+        load_analyzer_settings_from_str(toml, Path::new(ANALYZER_SETTINGS_FILE)).unwrap()
+    }
+
+    #[test]
+    fn test_empty_settings_use_defaults() {
+        let settings = parse("");
+        assert_eq!(settings, AnalyzerSettings::default());
+        assert!(settings.features.code_lens);
+        assert!(settings.inlay_hints.leaf_fields);
+        assert_eq!(settings.format.indent(2), None);
+        assert!(!settings.memory.lazy_parsing);
+        assert!(!settings.cache.persist);
+    }
+
+    #[test]
+    fn test_parse_all_sections() {
+        let settings = parse(
+            r#"
+[lint]
+extends = "recommended"
+
+[lint.rules]
+noDeprecated = "error"
+
+[complexity]
+defaultListSize = 20
+
+[validation]
+profile = "spec-strict"
+
+[inlayHints]
+selectionSets = false
+argumentDefaults = false
+
+[format]
+indentStyle = "tabs"
+
+[features]
+codeLens = false
+projectLints = false
+
+[completion]
+usageWeight = 0.25
+
+[memory]
+lazyParsing = true
+budgetMb = 128
+
+[cache]
+persist = true
+directory = "tmp/gql-cache"
+"#,
+        );
+
+        let lint = settings.lint.unwrap();
+        assert_eq!(lint["extends"], "recommended");
+        assert_eq!(lint["rules"]["noDeprecated"], "error");
+        assert_eq!(settings.complexity.unwrap().default_list_size, 20);
+        assert_eq!(
+            settings.validation.unwrap().profile,
+            crate::ValidationProfile::SpecStrict
+        );
+        assert!(!settings.inlay_hints.selection_sets);
+        assert!(!settings.inlay_hints.argument_defaults);
+        assert!(settings.inlay_hints.leaf_fields);
+        assert_eq!(settings.format.indent(2).as_deref(), Some("\t"));
+        assert!(!settings.features.code_lens);
+        assert!(settings.features.inlay_hints);
+        assert!(!settings.features.project_lints);
+        assert!(settings.features.complexity_warnings);
+        assert!((settings.completion.usage_weight - 0.25).abs() < f64::EPSILON);
+        assert!(settings.memory.lazy_parsing);
+        assert_eq!(settings.memory.budget_mb, 128);
+        assert!(settings.cache.persist);
+        assert_eq!(
+            settings.cache.resolve_directory(Path::new("/ws")),
+            Path::new("/ws/tmp/gql-cache")
+        );
+    }
+
+    #[test]
+    fn test_indent_width_falls_back_to_editor() {
+        let spaces = FormatSettings {
+            indent_style: Some(IndentStyle::Spaces),
+            indent_width: None,
+        };
+        assert_eq!(spaces.indent(3).as_deref(), Some("   "));
+
+        let width_only = FormatSettings {
+            indent_style: None,
+            indent_width: Some(4),
+        };
+        assert_eq!(width_only.indent(2).as_deref(), Some("    "));
+    }
+
+    #[test]
+    fn test_unknown_key_is_an_error() {
+        let err = load_analyzer_settings_from_str(
+            "[features]\ncodeLenses = false\n",
+            Path::new(ANALYZER_SETTINGS_FILE),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid { .. }));
+    }
+
+    #[test]
+    fn test_missing_file_is_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = analyzer_settings_path(dir.path());
+        assert!(load_analyzer_settings(&path).unwrap().is_none());
+    }
+}
@@ -4,7 +4,7 @@
 //! errors that can be easily converted to diagnostics by consumers.
 
 use crate::suggestions::did_you_mean;
-use crate::GraphQLConfig;
+use crate::{GraphQLConfig, ProjectConfig};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
@@ -308,7 +308,13 @@ fn validate_extension_namespace(config: &GraphQLConfig) -> Vec<ConfigValidationE
     // the top level of `extensions:` they almost certainly meant to nest them
     // under `graphql-analyzer:`. Generic graphql-config keys (e.g. `endpoints`,
     // `codegen`) are intentionally not in this list.
-    const ANALYZER_KEYS: &[&str] = &["lint", "client", "extractConfig", "resolvedSchema"];
+    const ANALYZER_KEYS: &[&str] = &[
+        "lint",
+        "client",
+        "extractConfig",
+        "resolvedSchema",
+        "schemaVariants",
+    ];
 
     let mut errors = Vec::new();
     for (project_name, project_config) in config.projects() {
@@ -344,12 +350,15 @@ fn validate_file_uniqueness(
     let mut file_to_projects: HashMap<PathBuf, Vec<(String, String, FileType)>> = HashMap::new();
 
     for (project_name, project_config) in config.projects() {
-        for pattern in project_config.schema.paths() {
+        let schema_patterns = project_config.schema.paths();
+        for &pattern in &schema_patterns {
             if pattern.starts_with("http://") || pattern.starts_with("https://") {
                 continue;
             }
 
-            for file_path in resolve_pattern_to_files(pattern, workspace_path) {
+            for file_path in
+                resolve_pattern_to_files(project_config, pattern, &schema_patterns, workspace_path)
+            {
                 file_to_projects.entry(file_path).or_default().push((
                     project_name.to_string(),
                     pattern.to_string(),
@@ -359,12 +368,18 @@ fn validate_file_uniqueness(
         }
 
         if let Some(documents_config) = &project_config.documents {
-            for pattern in documents_config.patterns() {
+            let document_patterns = documents_config.patterns();
+            for &pattern in &document_patterns {
                 if pattern.trim().starts_with('!') {
                     continue;
                 }
 
-                for file_path in resolve_pattern_to_files(pattern, workspace_path) {
+                for file_path in resolve_pattern_to_files(
+                    project_config,
+                    pattern,
+                    &document_patterns,
+                    workspace_path,
+                ) {
                     file_to_projects.entry(file_path).or_default().push((
                         project_name.to_string(),
                         pattern.to_string(),
@@ -469,13 +484,15 @@ fn validate_unmatched_patterns(
     for (project_name, project_config) in config.projects() {
         // Schema patterns
         let mut any_schema_matched = false;
-        for pattern in project_config.schema.paths() {
+        let schema_patterns = project_config.schema.paths();
+        for &pattern in &schema_patterns {
             if pattern.starts_with("http://") || pattern.starts_with("https://") {
                 any_schema_matched = true;
                 continue;
             }
 
-            let files = resolve_pattern_to_files(pattern, workspace_path);
+            let files =
+                resolve_pattern_to_files(project_config, pattern, &schema_patterns, workspace_path);
             if files.is_empty() {
                 errors.push(ConfigValidationError::UnmatchedPattern {
                     project: project_name.to_string(),
@@ -497,12 +514,18 @@ fn validate_unmatched_patterns(
         // Document patterns
         if let Some(documents_config) = &project_config.documents {
             let mut any_docs_matched = false;
-            for pattern in documents_config.patterns() {
+            let document_patterns = documents_config.patterns();
+            for &pattern in &document_patterns {
                 if pattern.trim().starts_with('!') {
                     continue;
                 }
 
-                let files = resolve_pattern_to_files(pattern, workspace_path);
+                let files = resolve_pattern_to_files(
+                    project_config,
+                    pattern,
+                    &document_patterns,
+                    workspace_path,
+                );
                 if files.is_empty() {
                     errors.push(ConfigValidationError::UnmatchedPattern {
                         project: project_name.to_string(),
@@ -661,50 +684,29 @@ fn validate_lint_value(
     }
 }
 
-/// Resolve a glob pattern to actual file paths.
-fn resolve_pattern_to_files(pattern: &str, workspace_path: &Path) -> Vec<PathBuf> {
-    let mut files = Vec::new();
-
-    for expanded_pattern in expand_braces(pattern) {
-        let full_pattern = workspace_path.join(&expanded_pattern);
-
-        if let Ok(paths) = glob::glob(&full_pattern.display().to_string()) {
-            for entry in paths.flatten() {
-                if entry.is_file() {
-                    // Skip node_modules
-                    if entry.components().any(|c| c.as_os_str() == "node_modules") {
-                        continue;
-                    }
-
-                    if let Ok(canonical) = entry.canonicalize() {
-                        files.push(canonical);
-                    } else {
-                        files.push(entry);
-                    }
-                }
-            }
-        }
-    }
-
-    files
-}
-
-/// Expand brace patterns like `{ts,tsx}` into multiple patterns.
-fn expand_braces(pattern: &str) -> Vec<String> {
-    if let Some(start) = pattern.find('{') {
-        if let Some(end) = pattern.find('}') {
-            let before = &pattern[..start];
-            let after = &pattern[end + 1..];
-            let options = &pattern[start + 1..end];
-
-            return options
-                .split(',')
-                .map(|opt| format!("{before}{opt}{after}"))
-                .collect();
-        }
-    }
-
-    vec![pattern.to_string()]
+/// Resolve a glob pattern to actual file paths, leaving out files removed
+/// by the negations among `patterns`.
+fn resolve_pattern_to_files(
+    project_config: &ProjectConfig,
+    pattern: &str,
+    patterns: &[&str],
+    workspace_path: &Path,
+) -> Vec<PathBuf> {
+    let mut globs = vec![pattern];
+    globs.extend(
+        patterns
+            .iter()
+            .copied()
+            .filter(|p| p.trim().starts_with('!')),
+    );
+
+    project_config
+        .file_globs(&globs)
+        .walk(workspace_path)
+        .matches
+        .into_iter()
+        .map(|m| m.path.canonicalize().unwrap_or(m.path))
+        .collect()
 }
 
 #[cfg(test)]
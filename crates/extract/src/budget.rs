@@ -0,0 +1,226 @@
+//! Per-file resource limits for extraction.
+
+use crate::extractor::{extract_cancellable, ExtractConfig, ExtractedGraphQL};
+use crate::{ExtractError, Language, Result};
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Limits on the work spent extracting GraphQL from a single file.
+///
+/// Host-language parsers can stall on malformed or adversarial input, and a
+/// stalled parse would otherwise hold up whatever thread asked for it. Files
+/// larger than `max_source_bytes` are rejected before parsing, which bounds
+/// both the parse time and the memory the parser's AST can take. Extraction
+/// that runs past `timeout` stops at its next deadline check and the file is
+/// skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractBudget {
+    /// Largest file, in bytes, that is handed to a host-language parser
+    pub max_source_bytes: usize,
+    /// Wall-clock time allowed for extracting one file
+    pub timeout: Duration,
+}
+
+impl Default for ExtractBudget {
+    fn default() -> Self {
+        Self {
+            max_source_bytes: DEFAULT_MAX_SOURCE_BYTES,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+pub(crate) const DEFAULT_MAX_SOURCE_BYTES: usize = 5 * 1024 * 1024;
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Deadline checked by an extraction in progress.
+///
+/// The extractors check it between statements, expressions and lines; the
+/// host-language parser itself can't be interrupted, so an expired
+/// extraction stops at the first check after parsing returns. A check that
+/// finds the deadline passed is remembered, which tells an extraction that
+/// was cut short apart from one that finished just after the deadline.
+#[derive(Debug, Default)]
+pub(crate) struct Cancellation {
+    deadline: Option<Instant>,
+    interrupted: Cell<bool>,
+}
+
+impl Cancellation {
+    fn at(deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            interrupted: Cell::new(false),
+        }
+    }
+
+    /// Whether the deadline has passed; extractors stop once this is true
+    pub(crate) fn is_cancelled(&self) -> bool {
+        let cancelled = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        if cancelled {
+            self.interrupted.set(true);
+        }
+        cancelled
+    }
+
+    /// Whether an extractor stopped early because of the deadline
+    fn was_interrupted(&self) -> bool {
+        self.interrupted.get()
+    }
+}
+
+/// Extract GraphQL from source code within `budget`.
+///
+/// Host-language files are extracted on the calling thread against a
+/// deadline. If the deadline passes the extractor stops at its next check
+/// and this returns [`ExtractError::TimedOut`]; an extraction that finished
+/// before noticing the deadline is returned as usual. A panic in the
+/// extractor is reported as a parse error rather than unwinding into the
+/// caller. Raw GraphQL needs no extraction and is returned directly.
+pub fn extract_from_source_with_budget(
+    source: &str,
+    language: Language,
+    config: &ExtractConfig,
+    path: &str,
+    budget: &ExtractBudget,
+) -> Result<Vec<ExtractedGraphQL>> {
+    if !language.requires_extraction() {
+        return extract_cancellable(source, language, config, path, &Cancellation::default());
+    }
+    if source.len() > budget.max_source_bytes {
+        return Err(ExtractError::BudgetExceeded {
+            path: PathBuf::from(path),
+            reason: format!(
+                "file is {} bytes, over the {} byte limit",
+                source.len(),
+                budget.max_source_bytes
+            ),
+        });
+    }
+
+    // A deadline too far out to represent is no deadline at all
+    let cancel = Instant::now()
+        .checked_add(budget.timeout)
+        .map_or_else(Cancellation::default, Cancellation::at);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        extract_cancellable(source, language, config, path, &cancel)
+    }));
+    match result {
+        // Whatever an interrupted extraction found is partial, so it's discarded
+        Ok(_) if cancel.was_interrupted() => Err(ExtractError::TimedOut {
+            path: PathBuf::from(path),
+            timeout: budget.timeout,
+        }),
+        Ok(result) => result,
+        Err(_) => Err(ExtractError::Parse {
+            path: PathBuf::from(path),
+            message: "extractor panicked".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extracts_within_budget() {
+        let source = "const q = gql`query { user { id } }`;";
+        let result = extract_from_source_with_budget(
+            source,
+            Language::TypeScript,
+            &ExtractConfig::default(),
+            "test.ts",
+            &ExtractBudget::default(),
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].source, "query { user { id } }");
+    }
+
+    #[test]
+    fn test_oversized_file_is_skipped() {
+        let source = "const q = gql`query { user { id } }`;";
+        let budget = ExtractBudget {
+            max_source_bytes: 10,
+            ..ExtractBudget::default()
+        };
+        let result = extract_from_source_with_budget(
+            source,
+            Language::TypeScript,
+            &ExtractConfig::default(),
+            "test.ts",
+            &budget,
+        );
+        assert!(matches!(result, Err(ExtractError::BudgetExceeded { .. })));
+    }
+
+    #[test]
+    fn test_size_limit_does_not_apply_to_graphql() {
+        let budget = ExtractBudget {
+            max_source_bytes: 10,
+            ..ExtractBudget::default()
+        };
+        let result = extract_from_source_with_budget(
+            "query { user { id } }",
+            Language::GraphQL,
+            &ExtractConfig::default(),
+            "test.graphql",
+            &budget,
+        )
+        .unwrap();
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn test_slow_extraction_times_out() {
+        let source = "x = gql(\"query { a }\")\n".repeat(200_000);
+        let budget = ExtractBudget {
+            timeout: Duration::ZERO,
+            ..ExtractBudget::default()
+        };
+        let result = extract_from_source_with_budget(
+            &source,
+            Language::Python,
+            &ExtractConfig::default(),
+            "test.py",
+            &budget,
+        );
+        assert!(matches!(result, Err(ExtractError::TimedOut { .. })));
+    }
+
+    #[test]
+    fn test_finished_extraction_is_kept_past_deadline() {
+        // Nothing to scan, so no check ever sees the expired deadline
+        let budget = ExtractBudget {
+            timeout: Duration::ZERO,
+            ..ExtractBudget::default()
+        };
+        let result = extract_from_source_with_budget(
+            "",
+            Language::Python,
+            &ExtractConfig::default(),
+            "test.py",
+            &budget,
+        );
+        assert!(matches!(result, Ok(blocks) if blocks.is_empty()));
+    }
+
+    #[test]
+    fn test_cancelled_extraction_stops_early() {
+        let cancel = Cancellation::at(Instant::now());
+        let result = extract_cancellable(
+            "x = gql(\"query { a }\")",
+            Language::Python,
+            &ExtractConfig::default(),
+            "test.py",
+            &cancel,
+        )
+        .unwrap();
+        assert!(result.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use graphql_types::Language;
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, ExtractError>;
@@ -19,4 +20,10 @@ pub enum ExtractError {
 
     #[error("Language not supported: {0:?}")]
     UnsupportedLanguage(Language),
+
+    #[error("Extraction budget exceeded for {path}: {reason}")]
+    BudgetExceeded { path: PathBuf, reason: String },
+
+    #[error("Extraction of {path} took longer than {timeout:?}")]
+    TimedOut { path: PathBuf, timeout: Duration },
 }
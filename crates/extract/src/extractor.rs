@@ -1,9 +1,11 @@
+use crate::budget::{Cancellation, ExtractBudget, DEFAULT_MAX_SOURCE_BYTES, DEFAULT_TIMEOUT};
 use crate::{ExtractError, Language, Position, Range, Result, SourceLocation};
 use serde::de::{self, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 /// Configuration for extracting GraphQL from TypeScript/JavaScript.
 ///
@@ -26,10 +28,17 @@ pub struct ExtractConfig {
     pub modules: Vec<ModuleConfig>,
 
     /// Magic comment recognized for ``/* graphql */ `...` `` extraction.
+    /// Matched case-insensitively, so `/* GraphQL */` works too.
     /// Default: `"graphql"` (matches pluck).
     #[serde(default = "default_gql_magic_comment")]
     pub gql_magic_comment: String,
 
+    /// Comment that marks an untagged template literal as GraphQL when it
+    /// opens the template, as in `` `#graphql query { ... }` ``. Not part of
+    /// pluck's schema. JSON `null` disables it. Default: `"#graphql"`.
+    #[serde(default = "default_gql_template_comment")]
+    pub gql_template_comment: Option<String>,
+
     /// Names of identifiers recognized as GraphQL tags without an import.
     /// JSON accepts a string, an array of strings, or `false` (disable bare
     /// extraction entirely). Default: `["gql", "graphql"]`.
@@ -49,6 +58,78 @@ pub struct ExtractConfig {
     /// minimum common leading whitespace from each line.
     #[serde(default)]
     pub skip_indent: bool,
+
+    /// Largest host-language file, in bytes, that is parsed for GraphQL.
+    /// Larger files are skipped. Raw `.graphql` files are never limited.
+    /// Default: 5 MiB.
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: usize,
+
+    /// Milliseconds allowed for extracting GraphQL from one host-language
+    /// file before it is skipped. Default: `5000`.
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl ExtractConfig {
+    /// The per-file limits set by `maxFileBytes` and `timeoutMs`
+    #[must_use]
+    pub fn budget(&self) -> ExtractBudget {
+        ExtractBudget {
+            max_source_bytes: self.max_file_bytes,
+            timeout: Duration::from_millis(self.timeout_ms),
+        }
+    }
+
+    /// A stable encoding of the settings that decide what gets extracted,
+    /// for keying caches of extraction results.
+    ///
+    /// The budget is left out: it only decides whether a file is extracted
+    /// at all, and skipped files shouldn't be cached.
+    #[must_use]
+    pub fn fingerprint(&self) -> Vec<u8> {
+        fn push_str(out: &mut Vec<u8>, s: &str) {
+            out.extend_from_slice(&(s.len() as u64).to_le_bytes());
+            out.extend_from_slice(s.as_bytes());
+        }
+        fn push_opt(out: &mut Vec<u8>, s: Option<&str>) {
+            match s {
+                Some(s) => {
+                    out.push(1);
+                    push_str(out, s);
+                }
+                None => out.push(0),
+            }
+        }
+
+        // Destructured so a new setting can't be left out of the key
+        let Self {
+            modules,
+            gql_magic_comment,
+            gql_template_comment,
+            global_gql_identifier_name,
+            gql_vue_block,
+            skip_indent,
+            max_file_bytes: _,
+            timeout_ms: _,
+        } = self;
+
+        let mut out = Vec::new();
+        out.extend_from_slice(&(modules.len() as u64).to_le_bytes());
+        for module in modules {
+            push_str(&mut out, &module.name);
+            push_opt(&mut out, module.identifier.as_deref());
+        }
+        push_str(&mut out, gql_magic_comment);
+        push_opt(&mut out, gql_template_comment.as_deref());
+        out.extend_from_slice(&(global_gql_identifier_name.len() as u64).to_le_bytes());
+        for name in global_gql_identifier_name {
+            push_str(&mut out, name);
+        }
+        push_opt(&mut out, gql_vue_block.as_deref());
+        out.push(u8::from(*skip_indent));
+        out
+    }
 }
 
 /// One entry in `modules`. JSON accepts either a bare string (shorthand for
@@ -173,10 +254,24 @@ fn default_gql_magic_comment() -> String {
     "graphql".to_string()
 }
 
+#[allow(clippy::unnecessary_wraps)]
+fn default_gql_template_comment() -> Option<String> {
+    Some("#graphql".to_string())
+}
+
 fn default_global_gql_identifier_name() -> Vec<String> {
     vec!["gql".to_string(), "graphql".to_string()]
 }
 
+fn default_max_file_bytes() -> usize {
+    DEFAULT_MAX_SOURCE_BYTES
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn default_timeout_ms() -> u64 {
+    DEFAULT_TIMEOUT.as_millis() as u64
+}
+
 fn default_modules() -> Vec<ModuleConfig> {
     // Mirrors `@graphql-tools/graphql-tag-pluck`'s defaults minus the legacy
     // unscoped `apollo-*` packages (apollo-server*, apollo-boost, apollo-angular)
@@ -215,9 +310,12 @@ impl Default for ExtractConfig {
         Self {
             modules: default_modules(),
             gql_magic_comment: default_gql_magic_comment(),
+            gql_template_comment: default_gql_template_comment(),
             global_gql_identifier_name: default_global_gql_identifier_name(),
             gql_vue_block: None,
             skip_indent: false,
+            max_file_bytes: default_max_file_bytes(),
+            timeout_ms: default_timeout_ms(),
         }
     }
 }
@@ -284,6 +382,20 @@ pub fn extract_from_source(
     language: Language,
     config: &ExtractConfig,
     path: &str,
+) -> Result<Vec<ExtractedGraphQL>> {
+    extract_cancellable(source, language, config, path, &Cancellation::default())
+}
+
+/// Extract GraphQL from source, stopping early once `cancel` is set.
+///
+/// A cancelled extraction returns whatever it found so far; callers that
+/// cancel are expected to discard it.
+pub(crate) fn extract_cancellable(
+    source: &str,
+    language: Language,
+    config: &ExtractConfig,
+    path: &str,
+    cancel: &Cancellation,
 ) -> Result<Vec<ExtractedGraphQL>> {
     match language {
         Language::GraphQL => {
@@ -304,10 +416,12 @@ pub fn extract_from_source(
             }])
         }
         Language::TypeScript | Language::JavaScript => {
-            extract_from_js_family(source, language, config, path)
+            extract_from_js_family(source, language, config, path, cancel)
         }
-        Language::Vue | Language::Svelte => extract_from_sfc(source, config, path),
-        Language::Astro => extract_from_astro(source, config, path),
+        Language::Vue | Language::Svelte => extract_from_sfc(source, config, path, cancel),
+        Language::Astro => extract_from_astro(source, config, path, cancel),
+        Language::Python => Ok(extract_from_python(source, config, cancel)),
+        Language::Ruby => Ok(extract_from_ruby(source, cancel)),
     }
 }
 
@@ -317,12 +431,37 @@ fn extract_from_js_family(
     language: Language,
     config: &ExtractConfig,
     path: &str,
+    cancel: &Cancellation,
 ) -> Result<Vec<ExtractedGraphQL>> {
+    use swc_common::comments::SingleThreadedComments;
+    use swc_core::ecma::visit::VisitWith;
+
+    let comments = SingleThreadedComments::default();
+    let module = parse_js_module(source, language, path, Some(&comments))?;
+    if cancel.is_cancelled() {
+        return Ok(Vec::new());
+    }
+
+    let mut visitor = GraphQLVisitor::new(source, config, &comments, cancel);
+    module.visit_with(&mut visitor);
+
+    Ok(visitor.extracted)
+}
+
+/// Parse JavaScript or TypeScript source into an SWC module.
+///
+/// SWC spans start at byte position 1, so node offsets into `source` are
+/// `span.lo.0 - 1`.
+pub(crate) fn parse_js_module(
+    source: &str,
+    language: Language,
+    path: &str,
+    comments: Option<&swc_common::comments::SingleThreadedComments>,
+) -> Result<swc_core::ecma::ast::Module> {
     use swc_common::sync::Lrc;
     use swc_common::{FileName, SourceMap};
     use swc_core::ecma::ast::EsVersion;
     use swc_core::ecma::parser::{parse_file_as_module, Syntax};
-    use swc_core::ecma::visit::VisitWith;
 
     let source_map = Lrc::new(SourceMap::default());
     let source_file = source_map.new_source_file(
@@ -347,19 +486,20 @@ fn extract_from_js_family(
             jsx: ext == "jsx",
             ..Default::default()
         }),
-        _ => unreachable!("extract_from_js_family only handles JS/TS"),
+        _ => unreachable!("parse_js_module only handles JS/TS"),
     };
 
-    let module = parse_file_as_module(&source_file, syntax, EsVersion::EsNext, None, &mut vec![])
-        .map_err(|e| ExtractError::Parse {
+    parse_file_as_module(
+        &source_file,
+        syntax,
+        EsVersion::EsNext,
+        comments.map(|c| c as &dyn swc_common::comments::Comments),
+        &mut vec![],
+    )
+    .map_err(|e| ExtractError::Parse {
         path: std::path::PathBuf::from(path),
         message: format!("SWC parse error: {e:?}"),
-    })?;
-
-    let mut visitor = GraphQLVisitor::new(source, config);
-    module.visit_with(&mut visitor);
-
-    Ok(visitor.extracted)
+    })
 }
 
 /// A script block extracted from a Vue/Svelte SFC or Astro frontmatter.
@@ -380,6 +520,7 @@ fn extract_from_sfc(
     source: &str,
     config: &ExtractConfig,
     path: &str,
+    cancel: &Cancellation,
 ) -> Result<Vec<ExtractedGraphQL>> {
     let blocks = find_script_blocks(source);
     if blocks.is_empty() {
@@ -388,12 +529,15 @@ fn extract_from_sfc(
 
     let mut results = Vec::new();
     for block in &blocks {
+        if cancel.is_cancelled() {
+            break;
+        }
         let script_lang = if block.is_typescript {
             Language::TypeScript
         } else {
             Language::JavaScript
         };
-        let extracted = extract_from_js_family(block.content, script_lang, config, path)?;
+        let extracted = extract_from_js_family(block.content, script_lang, config, path, cancel)?;
         for mut item in extracted {
             item.location.offset += block.offset;
             item.location.range = Range::new(
@@ -414,12 +558,14 @@ fn extract_from_astro(
     source: &str,
     config: &ExtractConfig,
     path: &str,
+    cancel: &Cancellation,
 ) -> Result<Vec<ExtractedGraphQL>> {
     let Some(block) = find_astro_frontmatter(source) else {
         return Ok(Vec::new());
     };
 
-    let extracted = extract_from_js_family(block.content, Language::TypeScript, config, path)?;
+    let extracted =
+        extract_from_js_family(block.content, Language::TypeScript, config, path, cancel)?;
     let mut results = Vec::new();
     for mut item in extracted {
         item.location.offset += block.offset;
@@ -433,6 +579,237 @@ fn extract_from_astro(
     Ok(results)
 }
 
+/// Extract GraphQL from strings passed to `gql(...)` in Python source.
+///
+/// A call matches when the callee is one of `globalGqlIdentifierName` and
+/// its first argument is a single plain or raw string literal, as written
+/// with the `gql` client: `gql("""query { ... }""")`. f-strings, byte
+/// strings and concatenations are skipped since their value isn't known
+/// statically. Comments and other strings are stepped over so a `gql(`
+/// inside them doesn't match.
+fn extract_from_python(
+    source: &str,
+    config: &ExtractConfig,
+    cancel: &Cancellation,
+) -> Vec<ExtractedGraphQL> {
+    let bytes = source.as_bytes();
+    let mut results = Vec::new();
+    let mut pos = 0;
+
+    while pos < bytes.len() && !cancel.is_cancelled() {
+        match bytes[pos] {
+            b'#' => pos = source[pos..].find('\n').map_or(bytes.len(), |i| pos + i),
+            b'"' | b'\'' => pos = python_string(source, pos).map_or(bytes.len(), |lit| lit.end),
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = pos;
+                while pos < bytes.len()
+                    && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_')
+                {
+                    pos += 1;
+                }
+                let ident = &source[start..pos];
+
+                // String prefixes (`f"..."`, `rb'...'`) start a literal we skip whole
+                if matches!(bytes.get(pos), Some(b'"' | b'\''))
+                    && ident.len() <= 2
+                    && ident.bytes().all(|c| b"rRbBfFuU".contains(&c))
+                {
+                    pos = python_string(source, pos).map_or(bytes.len(), |lit| lit.end);
+                    continue;
+                }
+
+                // Method calls only match through the module (`gql.gql(...)`)
+                let is_method = start > 0 && bytes[start - 1] == b'.';
+                if (is_method && !source[..start - 1].ends_with("gql"))
+                    || !config.global_gql_identifier_name.iter().any(|n| n == ident)
+                {
+                    continue;
+                }
+                let open = skip_whitespace(source, pos);
+                if bytes.get(open) != Some(&b'(') {
+                    continue;
+                }
+                let arg = skip_whitespace(source, open + 1);
+                let quote = match bytes.get(arg) {
+                    Some(b'r' | b'R') => arg + 1,
+                    _ => arg,
+                };
+                let Some(lit) = python_string(source, quote) else {
+                    continue;
+                };
+                pos = lit.end;
+                // Implicit concatenation (`gql("a" "b")`) has no single source range
+                if !matches!(
+                    bytes.get(skip_whitespace(source, lit.end)),
+                    Some(b')' | b',')
+                ) {
+                    continue;
+                }
+                results.push(extracted_range(
+                    source,
+                    lit.content_start,
+                    lit.content_end,
+                    Some(ident.to_string()),
+                ));
+            }
+            _ => pos += 1,
+        }
+    }
+
+    results
+}
+
+/// A Python string literal, as byte offsets into the source
+struct PythonString {
+    content_start: usize,
+    content_end: usize,
+    /// Offset just past the closing quote
+    end: usize,
+}
+
+/// Scan the string literal whose opening quote is at `quote_pos`. Returns
+/// `None` if there is no quote there or the literal is unterminated.
+fn python_string(source: &str, quote_pos: usize) -> Option<PythonString> {
+    let bytes = source.as_bytes();
+    let quote = *bytes.get(quote_pos)?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let triple = bytes.get(quote_pos..quote_pos + 3) == Some(&[quote; 3][..]);
+    let content_start = quote_pos + if triple { 3 } else { 1 };
+
+    let mut pos = content_start;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            // Escapes can't close the literal, raw strings included
+            b'\\' => pos += 2,
+            b'\n' if !triple => return None,
+            b if b == quote && !triple => {
+                return Some(PythonString {
+                    content_start,
+                    content_end: pos,
+                    end: pos + 1,
+                });
+            }
+            b if b == quote && bytes.get(pos..pos + 3) == Some(&[quote; 3][..]) => {
+                return Some(PythonString {
+                    content_start,
+                    content_end: pos,
+                    end: pos + 3,
+                });
+            }
+            _ => pos += 1,
+        }
+    }
+    None
+}
+
+fn skip_whitespace(source: &str, pos: usize) -> usize {
+    source[pos..]
+        .find(|c: char| !c.is_whitespace())
+        .map_or(source.len(), |i| pos + i)
+}
+
+/// Extract GraphQL from Ruby `<<~GRAPHQL` heredocs.
+///
+/// `<<-GRAPHQL`, `<<GRAPHQL` and quoted terminators (`<<~'GRAPHQL'`) are
+/// recognized too. The body runs from the line after the opener up to the
+/// line holding only the terminator; only the `~` and `-` forms allow that
+/// line to be indented. Interpolating heredocs (`#{...}`) are skipped, like
+/// template literals with expressions.
+fn extract_from_ruby(source: &str, cancel: &Cancellation) -> Vec<ExtractedGraphQL> {
+    const TERMINATOR: &str = "GRAPHQL";
+
+    let mut results = Vec::new();
+    let mut lines = source.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line))
+    });
+
+    while let Some((line_start, line)) = lines.next() {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let Some(opener) = line.find("<<") else {
+            continue;
+        };
+        // Openers inside a trailing comment don't start a heredoc
+        if line[..opener].contains('#') {
+            continue;
+        }
+        let rest = &line[opener + 2..];
+        let (indented, rest) = match rest.as_bytes().first() {
+            Some(b'~' | b'-') => (true, &rest[1..]),
+            _ => (false, rest),
+        };
+        let (quote, rest) = match rest.as_bytes().first() {
+            Some(q @ (b'\'' | b'"')) => (Some(*q), &rest[1..]),
+            _ => (None, rest),
+        };
+        let Some(rest) = rest.strip_prefix(TERMINATOR) else {
+            continue;
+        };
+        match quote {
+            Some(q) if rest.as_bytes().first() != Some(&q) => continue,
+            None if rest.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_') => continue,
+            _ => {}
+        }
+
+        let body_start = line_start + line.len();
+        let mut body_end = None;
+        for (start, body_line) in lines.by_ref() {
+            let candidate = if indented {
+                body_line.trim()
+            } else {
+                body_line.trim_end()
+            };
+            if candidate == TERMINATOR {
+                body_end = Some(start);
+                break;
+            }
+        }
+        let Some(body_end) = body_end else {
+            break;
+        };
+
+        let body = &source[body_start..body_end];
+        if quote != Some(b'\'') && body.contains("#{") {
+            continue;
+        }
+        results.push(extracted_range(
+            source,
+            body_start,
+            body_end,
+            Some(TERMINATOR.to_string()),
+        ));
+    }
+
+    results
+}
+
+/// Build an `ExtractedGraphQL` for `source[start..end]`
+fn extracted_range(
+    source: &str,
+    start: usize,
+    end: usize,
+    tag_name: Option<String>,
+) -> ExtractedGraphQL {
+    ExtractedGraphQL {
+        source: source[start..end].to_string(),
+        location: SourceLocation::new(
+            start,
+            end - start,
+            Range::new(
+                position_from_offset(source, start),
+                position_from_offset(source, end),
+            ),
+        ),
+        tag_name,
+        declaration_range: None,
+    }
+}
+
 /// Find the Astro frontmatter block (content between the first `---` pair).
 fn find_astro_frontmatter(source: &str) -> Option<ScriptBlock<'_>> {
     // Astro frontmatter starts at the very beginning with `---`
@@ -562,21 +939,29 @@ struct GraphQLVisitor<'a> {
     /// the module's identifier rule. Pluck-aligned: only entries here plus
     /// `globalGqlIdentifierName` are accepted as GraphQL tags.
     defined_identifiers: std::collections::HashSet<String>,
-    /// Track comments for magic comment detection
-    pending_comments: Vec<(usize, String)>,
+    /// Comments collected by the parser, for magic comment detection
+    comments: &'a swc_common::comments::SingleThreadedComments,
     /// Declaration range set by `visit_var_decl`/`visit_export_decl` for single-declarator statements
     current_declaration_range: Option<(usize, usize)>,
+    /// Checked at each statement and expression so a cancelled walk unwinds quickly
+    cancel: &'a Cancellation,
 }
 
 impl<'a> GraphQLVisitor<'a> {
-    fn new(source: &'a str, config: &'a ExtractConfig) -> Self {
+    fn new(
+        source: &'a str,
+        config: &'a ExtractConfig,
+        comments: &'a swc_common::comments::SingleThreadedComments,
+        cancel: &'a Cancellation,
+    ) -> Self {
         Self {
             source,
             config,
             extracted: Vec::new(),
             defined_identifiers: std::collections::HashSet::new(),
-            pending_comments: Vec::new(),
+            comments,
             current_declaration_range: None,
+            cancel,
         }
     }
 
@@ -627,13 +1012,69 @@ impl<'a> GraphQLVisitor<'a> {
         None
     }
 
-    /// Check if there's a magic comment before this position
-    fn check_magic_comment(&self, pos: usize) -> bool {
-        // Look for a comment that precedes this position
-        self.pending_comments.iter().any(|(comment_pos, content)| {
-            *comment_pos < pos && content.trim() == self.config.gql_magic_comment
+    /// Extract the value of a string literal
+    fn extract_string_literal(&self, str_lit: &swc_core::ecma::ast::Str) -> ExtractedGraphQL {
+        // SWC positions start at 1; the content starts one byte after the quote
+        let start_offset = str_lit.span.lo.0 as usize;
+        let content = String::from_utf8_lossy(str_lit.value.as_bytes()).to_string();
+        let length = content.len();
+
+        let start_pos = position_from_offset(self.source, start_offset);
+        let end_pos = position_from_offset(self.source, start_offset + length);
+
+        ExtractedGraphQL {
+            source: content,
+            location: SourceLocation::new(start_offset, length, Range::new(start_pos, end_pos)),
+            tag_name: None,
+            declaration_range: self.current_declaration_range,
+        }
+    }
+
+    /// Record an extracted block. A template can be reached both as a tag
+    /// argument and as a commented expression; it's only kept once.
+    fn push(&mut self, extracted: ExtractedGraphQL) {
+        if !self
+            .extracted
+            .iter()
+            .any(|e| e.location.offset == extracted.location.offset)
+        {
+            self.extracted.push(extracted);
+        }
+    }
+
+    /// Check if the node starting at `pos` is preceded by the magic comment,
+    /// e.g. ``/* GraphQL */ `...` ``
+    fn check_magic_comment(&self, pos: swc_common::BytePos) -> bool {
+        use swc_common::comments::Comments;
+
+        self.comments.get_leading(pos).is_some_and(|comments| {
+            comments.iter().any(|comment| {
+                comment
+                    .text
+                    .trim()
+                    .eq_ignore_ascii_case(&self.config.gql_magic_comment)
+            })
         })
     }
+
+    /// Check if a template literal opens with the template comment, e.g.
+    /// `` `#graphql query { ... }` ``
+    fn has_template_comment(&self, tpl: &swc_core::ecma::ast::Tpl) -> bool {
+        let Some(marker) = self.config.gql_template_comment.as_deref() else {
+            return false;
+        };
+        let Some(quasi) = tpl.quasis.first() else {
+            return false;
+        };
+        let raw = String::from_utf8_lossy(quasi.raw.as_bytes());
+        let raw = raw.trim_start();
+        raw.get(..marker.len())
+            .is_some_and(|head| head.eq_ignore_ascii_case(marker))
+            && raw[marker.len()..]
+                .chars()
+                .next()
+                .is_none_or(char::is_whitespace)
+    }
 }
 
 /// Extend a byte range to cover the full line(s), including leading whitespace,
@@ -663,6 +1104,41 @@ fn extend_to_line_bounds(source: &str, start: usize, end: usize) -> (usize, usiz
 }
 
 impl swc_core::ecma::visit::Visit for GraphQLVisitor<'_> {
+    fn visit_stmt(&mut self, stmt: &swc_core::ecma::ast::Stmt) {
+        use swc_core::ecma::visit::VisitWith;
+
+        if !self.cancel.is_cancelled() {
+            stmt.visit_children_with(self);
+        }
+    }
+
+    /// Untagged strings and templates marked as GraphQL by a comment, e.g.
+    /// ``/* GraphQL */ `...` ``, `gql(/* GraphQL */ "...")` or
+    /// `` `#graphql ...` ``, wherever they appear as an expression
+    fn visit_expr(&mut self, expr: &swc_core::ecma::ast::Expr) {
+        use swc_core::ecma::ast::{Expr, Lit};
+        use swc_core::ecma::visit::VisitWith;
+
+        if self.cancel.is_cancelled() {
+            return;
+        }
+        match expr {
+            Expr::Tpl(tpl) => {
+                if self.check_magic_comment(tpl.span.lo) || self.has_template_comment(tpl) {
+                    if let Some(extracted) = self.extract_template_literal(tpl, None) {
+                        self.push(extracted);
+                    }
+                }
+            }
+            Expr::Lit(Lit::Str(str_lit)) if self.check_magic_comment(str_lit.span.lo) => {
+                let extracted = self.extract_string_literal(str_lit);
+                self.push(extracted);
+            }
+            _ => {}
+        }
+        expr.visit_children_with(self);
+    }
+
     /// Track single-declarator variable declarations so we can capture
     /// the full declaration range for single-definition GraphQL blocks.
     fn visit_var_decl(&mut self, decl: &swc_core::ecma::ast::VarDecl) {
@@ -785,7 +1261,7 @@ impl swc_core::ecma::visit::Visit for GraphQLVisitor<'_> {
         }
 
         if let Some(extracted) = self.extract_template_literal(&tagged.tpl, Some(tag_name)) {
-            self.extracted.push(extracted);
+            self.push(extracted);
         }
 
         // Continue traversal into child nodes
@@ -793,10 +1269,13 @@ impl swc_core::ecma::visit::Visit for GraphQLVisitor<'_> {
     }
 
     /// Visit call expressions to handle cases like:
-    /// - gql(/* GraphQL */ "query")
+    /// - gql(`query { ... }`)
     /// - graphql(`query { ... }`, [fragment1, fragment2])
+    ///
+    /// String arguments such as gql(/* GraphQL */ "query") are picked up by
+    /// `visit_expr` through their magic comment.
     fn visit_call_expr(&mut self, call: &swc_core::ecma::ast::CallExpr) {
-        use swc_core::ecma::ast::{Callee, Expr, Lit};
+        use swc_core::ecma::ast::{Callee, Expr};
         use swc_core::ecma::visit::VisitWith;
 
         let tag_name = match &call.callee {
@@ -822,63 +1301,9 @@ impl swc_core::ecma::visit::Visit for GraphQLVisitor<'_> {
         // If this is a valid GraphQL tag function call, check the first argument
         if let Some(tag) = tag_name {
             if let Some(first_arg) = call.args.first() {
-                match &*first_arg.expr {
-                    // Handle template literal: graphql(`query { ... }`)
-                    Expr::Tpl(tpl) => {
-                        if let Some(extracted) = self.extract_template_literal(tpl, Some(tag)) {
-                            self.extracted.push(extracted);
-                        }
-                    }
-                    // Handle string literal with magic comment: gql(/* GraphQL */ "query")
-                    Expr::Lit(Lit::Str(str_lit)) => {
-                        let pos = str_lit.span.lo.0 as usize;
-                        if self.check_magic_comment(pos) {
-                            let start_offset = str_lit.span.lo.0 as usize - 1;
-                            let content =
-                                String::from_utf8_lossy(str_lit.value.as_bytes()).to_string();
-                            let length = content.len();
-
-                            let start_pos = position_from_offset(self.source, start_offset);
-                            let end_pos = position_from_offset(self.source, start_offset + length);
-
-                            self.extracted.push(ExtractedGraphQL {
-                                source: content,
-                                location: SourceLocation::new(
-                                    start_offset,
-                                    length,
-                                    Range::new(start_pos, end_pos),
-                                ),
-                                tag_name: None,
-                                declaration_range: self.current_declaration_range,
-                            });
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        } else {
-            // Not a GraphQL tag function, check for magic comments in string arguments
-            for arg in &call.args {
-                if let Expr::Lit(Lit::Str(str_lit)) = &*arg.expr {
-                    let pos = str_lit.span.lo.0 as usize;
-                    if self.check_magic_comment(pos) {
-                        let start_offset = str_lit.span.lo.0 as usize - 1;
-                        let content = String::from_utf8_lossy(str_lit.value.as_bytes()).to_string();
-                        let length = content.len();
-
-                        let start_pos = position_from_offset(self.source, start_offset);
-                        let end_pos = position_from_offset(self.source, start_offset + length);
-
-                        self.extracted.push(ExtractedGraphQL {
-                            source: content,
-                            location: SourceLocation::new(
-                                start_offset,
-                                length,
-                                Range::new(start_pos, end_pos),
-                            ),
-                            tag_name: None,
-                            declaration_range: self.current_declaration_range,
-                        });
+                if let Expr::Tpl(tpl) = &*first_arg.expr {
+                    if let Some(extracted) = self.extract_template_literal(tpl, Some(tag)) {
+                        self.push(extracted);
                     }
                 }
             }
@@ -887,50 +1312,6 @@ impl swc_core::ecma::visit::Visit for GraphQLVisitor<'_> {
         // Continue traversal into child nodes
         call.visit_children_with(self);
     }
-
-    /// Visit variable declarations to handle magic comments
-    fn visit_var_declarator(&mut self, decl: &swc_core::ecma::ast::VarDeclarator) {
-        use swc_core::ecma::ast::{Expr, Lit};
-        use swc_core::ecma::visit::VisitWith;
-        if let Some(init) = &decl.init {
-            match &**init {
-                Expr::Lit(Lit::Str(str_lit)) => {
-                    let pos = str_lit.span.lo.0 as usize;
-                    if self.check_magic_comment(pos) {
-                        let start_offset = str_lit.span.lo.0 as usize - 1;
-                        let content = String::from_utf8_lossy(str_lit.value.as_bytes()).to_string();
-                        let length = content.len();
-
-                        let start_pos = position_from_offset(self.source, start_offset);
-                        let end_pos = position_from_offset(self.source, start_offset + length);
-
-                        self.extracted.push(ExtractedGraphQL {
-                            source: content,
-                            location: SourceLocation::new(
-                                start_offset,
-                                length,
-                                Range::new(start_pos, end_pos),
-                            ),
-                            tag_name: None,
-                            declaration_range: self.current_declaration_range,
-                        });
-                    }
-                }
-                Expr::Tpl(tpl) => {
-                    let pos = tpl.span.lo.0 as usize;
-                    if self.check_magic_comment(pos) {
-                        if let Some(extracted) = self.extract_template_literal(tpl, None) {
-                            self.extracted.push(extracted);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        // Continue traversal into child nodes
-        decl.visit_children_with(self);
-    }
 }
 
 /// Calculate position from byte offset
@@ -1035,6 +1416,42 @@ mod tests {
         assert_eq!(cfg.gql_magic_comment, "GQL");
     }
 
+    #[test]
+    fn test_resolve_for_documents_template_comment() {
+        let cfg = resolve_for_documents(None);
+        assert_eq!(cfg.gql_template_comment.as_deref(), Some("#graphql"));
+
+        let user = serde_json::json!({ "gqlTemplateComment": null });
+        let cfg = resolve_for_documents(Some(&user));
+        assert_eq!(cfg.gql_template_comment, None);
+    }
+
+    #[test]
+    fn test_resolve_for_documents_budget() {
+        let cfg = resolve_for_documents(None);
+        assert_eq!(cfg.budget(), crate::ExtractBudget::default());
+
+        let user = serde_json::json!({ "maxFileBytes": 1024, "timeoutMs": 250 });
+        let budget = resolve_for_documents(Some(&user)).budget();
+        assert_eq!(budget.max_source_bytes, 1024);
+        assert_eq!(budget.timeout, Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_budget() {
+        let base = ExtractConfig::default();
+        let budget_only = ExtractConfig {
+            timeout_ms: 1,
+            ..ExtractConfig::default()
+        };
+        let renamed = ExtractConfig {
+            gql_magic_comment: "gql".to_string(),
+            ..ExtractConfig::default()
+        };
+        assert_eq!(base.fingerprint(), budget_only.fingerprint());
+        assert_ne!(base.fingerprint(), renamed.fingerprint());
+    }
+
     #[test]
     fn test_resolve_for_documents_accepts_global_identifier_string_form() {
         let user = serde_json::json!({ "globalGqlIdentifierName": "myTag" });
@@ -1659,6 +2076,129 @@ const query = gql`query GetUser { user { id } }`;
             assert_eq!(result.len(), 1);
             assert!(result[0].source.contains("query GetUser"));
         }
+
+        #[test]
+        fn test_magic_comment_template_in_typescript() {
+            let source = r"
+const query = /* GraphQL */ `query GetUser { user { id } }`;
+const other = `query NotGraphQL { user { id } }`;
+";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].source, "query GetUser { user { id } }");
+            assert_eq!(result[0].tag_name, None);
+            let offset = result[0].location.offset;
+            assert_eq!(
+                &source[offset..offset + result[0].location.length],
+                result[0].source
+            );
+        }
+
+        #[test]
+        fn test_magic_comment_in_javascript_expressions() {
+            // The comment marks the literal wherever it appears, not only in
+            // variable initializers
+            let source = r#"
+export default {
+  query: /* graphql */ `query A { a }`,
+};
+request(/* GraphQL */ "query B { b }");
+"#;
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::JavaScript, &config, "test.js").unwrap();
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].source, "query A { a }");
+            assert_eq!(result[1].source, "query B { b }");
+            let offset = result[1].location.offset;
+            assert_eq!(
+                &source[offset..offset + result[1].location.length],
+                "query B { b }"
+            );
+        }
+
+        #[test]
+        fn test_magic_comment_must_precede_literal() {
+            let source = r"
+/* GraphQL */
+function run() {}
+const query = `query GetUser { user { id } }`;
+";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert!(result.is_empty());
+        }
+
+        #[test]
+        fn test_custom_magic_comment() {
+            let source = "const query = /* gql-query */ `query GetUser { user { id } }`;";
+            let config = ExtractConfig {
+                gql_magic_comment: "gql-query".to_string(),
+                ..Default::default()
+            };
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert_eq!(result.len(), 1);
+        }
+
+        #[test]
+        fn test_magic_comment_on_tag_argument_extracted_once() {
+            let source = "const query = graphql(/* GraphQL */ `query GetUser { user { id } }`);";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].tag_name, Some("graphql".to_string()));
+        }
+
+        #[test]
+        fn test_template_comment_in_typescript() {
+            let source = r"
+const typeDefs = `#graphql
+  type Query { hello: String }
+`;
+const notes = `#graphqlish is not a marker`;
+";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert!(result[0].source.starts_with("#graphql"));
+            assert!(result[0].source.contains("type Query"));
+        }
+
+        #[test]
+        fn test_template_comment_in_javascript() {
+            let source = "server.start({ typeDefs: `  #graphql type Query { hello: String }` });";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::JavaScript, &config, "test.js").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert!(result[0].source.contains("type Query"));
+        }
+
+        #[test]
+        fn test_template_comment_can_be_disabled() {
+            let source = "const typeDefs = `#graphql\ntype Query { hello: String }`;";
+            let config = ExtractConfig {
+                gql_template_comment: None,
+                ..Default::default()
+            };
+            let result =
+                extract_from_source(source, Language::TypeScript, &config, "test.ts").unwrap();
+
+            assert!(result.is_empty());
+        }
     }
 
     mod vue_tests {
@@ -1986,6 +2526,126 @@ const Q = gql`query Test { field }`;
         }
     }
 
+    mod python_tests {
+        use super::*;
+
+        #[test]
+        fn test_extract_from_gql_call() {
+            let source = r#"from gql import gql
+
+GET_USER = gql("""
+    query GetUser {
+      user { id name }
+    }
+""")
+"#;
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Python, &config, "queries.py").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert!(result[0].source.contains("query GetUser"));
+            assert_eq!(result[0].tag_name.as_deref(), Some("gql"));
+            let loc = &result[0].location;
+            assert_eq!(
+                &source[loc.offset..loc.offset + loc.length],
+                result[0].source
+            );
+        }
+
+        #[test]
+        fn test_python_single_quoted_and_raw_strings() {
+            let source = r#"Q1 = gql('query Q1 { a }')
+Q2 = gql.gql(r"query Q2 { b }")
+"#;
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Python, &config, "queries.py").unwrap();
+
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].source, "query Q1 { a }");
+            assert_eq!(result[1].source, "query Q2 { b }");
+            assert_eq!(result[1].location.range.start, Position::new(1, 15));
+        }
+
+        #[test]
+        fn test_python_skips_non_literal_arguments() {
+            let source = r#"# gql("query Commented { a }")
+text = "gql('query InString { a }')"
+F = gql(f"query {name} {{ a }}")
+C = gql("query " "Split { a }")
+result = graphql(schema, "query Executed { a }")
+other.gql("query Method { a }")
+"#;
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Python, &config, "queries.py").unwrap();
+            assert!(result.is_empty(), "got: {result:?}");
+        }
+    }
+
+    mod ruby_tests {
+        use super::*;
+
+        #[test]
+        fn test_extract_from_squiggly_heredoc() {
+            let source = r"class UserQuery
+  QUERY = <<~GRAPHQL
+    query GetUser {
+      user { id name }
+    }
+  GRAPHQL
+end
+";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Ruby, &config, "user_query.rb").unwrap();
+
+            assert_eq!(result.len(), 1);
+            assert!(result[0].source.contains("query GetUser"));
+            assert_eq!(result[0].location.range.start, Position::new(2, 0));
+            let loc = &result[0].location;
+            assert_eq!(
+                &source[loc.offset..loc.offset + loc.length],
+                result[0].source
+            );
+        }
+
+        #[test]
+        fn test_ruby_heredoc_variants() {
+            let source = r"A = client.query(<<-'GRAPHQL')
+  query Dash { a }
+  GRAPHQL
+B = <<GRAPHQL
+query Plain { b }
+GRAPHQL
+";
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Ruby, &config, "queries.rb").unwrap();
+
+            assert_eq!(result.len(), 2);
+            assert!(result[0].source.contains("query Dash"));
+            assert!(result[1].source.contains("query Plain"));
+        }
+
+        #[test]
+        fn test_ruby_skips_interpolated_and_other_heredocs() {
+            let source = r##"# QUERY = <<~GRAPHQL
+A = <<~GRAPHQL
+  query Interpolated { user(id: "#{id}") { id } }
+GRAPHQL
+B = <<~SQL
+  SELECT 1
+SQL
+"##;
+            let config = ExtractConfig::default();
+            let result =
+                extract_from_source(source, Language::Ruby, &config, "queries.rb").unwrap();
+            assert!(result.is_empty(), "got: {result:?}");
+        }
+    }
+
     mod script_block_tests {
         use super::*;
 
@@ -1,12 +1,18 @@
+mod budget;
 mod error;
 mod extractor;
 mod source_location;
+mod symbols;
 
+pub use budget::{extract_from_source_with_budget, ExtractBudget};
 pub use error::{ExtractError, Result};
 pub use extractor::{
     extract_from_file, extract_from_source, resolve_for_documents, ExtractConfig, ExtractedGraphQL,
 };
 pub use source_location::SourceLocation;
+pub use symbols::{
+    module_symbols, ConstBinding, ExportBinding, ExportTarget, ImportBinding, ModuleSymbols,
+};
 
 // Re-export types from graphql-types for convenience
 pub use graphql_types::{Language, Position, Range};
@@ -0,0 +1,233 @@
+//! Top-level bindings of JavaScript/TypeScript modules.
+//!
+//! Records the `const` declarations, exports and imports of a module, so a
+//! reference to a GraphQL document constant can be followed across files to
+//! the block it's initialized with.
+
+use swc_core::ecma::ast::{
+    Decl, ExportSpecifier, ImportSpecifier, ModuleDecl, ModuleExportName, ModuleItem, Pat, Stmt,
+    VarDecl,
+};
+
+use crate::extractor::parse_js_module;
+use crate::{Language, Result};
+
+/// The top-level bindings of a module
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleSymbols {
+    /// `const`, `let` and `var` declarations with an initializer
+    pub consts: Vec<ConstBinding>,
+    /// Local bindings exported by the module
+    pub exports: Vec<ExportBinding>,
+    /// Named imports, and names re-exported from another module
+    pub imports: Vec<ImportBinding>,
+}
+
+/// A variable declared with an initializer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstBinding {
+    pub name: String,
+    /// Byte range of the initializer expression
+    pub init_range: (usize, usize),
+}
+
+/// A local binding exported under a (possibly different) name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportBinding {
+    pub exported: String,
+    pub local: String,
+}
+
+/// A binding imported from another module.
+///
+/// For `export { X as Y } from './m'`, `local` is the re-exported name `Y`
+/// and `reexport` is set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportBinding {
+    pub local: String,
+    pub imported: String,
+    /// The module specifier, as written
+    pub module: String,
+    pub reexport: bool,
+}
+
+impl ModuleSymbols {
+    /// The local binding named `name`
+    #[must_use]
+    pub fn binding(&self, name: &str) -> Option<&ConstBinding> {
+        self.consts.iter().find(|c| c.name == name)
+    }
+
+    /// The binding imported as `local`. Re-exports don't bind a local name
+    /// and aren't returned.
+    #[must_use]
+    pub fn import(&self, local: &str) -> Option<&ImportBinding> {
+        self.imports
+            .iter()
+            .find(|import| !import.reexport && import.local == local)
+    }
+
+    /// What the module exports as `name`: a local binding, or a re-export
+    /// from another module.
+    #[must_use]
+    pub fn export(&self, name: &str) -> Option<ExportTarget<'_>> {
+        if let Some(export) = self.exports.iter().find(|e| e.exported == name) {
+            return self
+                .binding(&export.local)
+                .map(ExportTarget::Binding)
+                .or_else(|| self.import(&export.local).map(ExportTarget::Import));
+        }
+        self.imports
+            .iter()
+            .find(|import| import.reexport && import.local == name)
+            .map(ExportTarget::Import)
+    }
+}
+
+/// What an exported name resolves to within its module
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportTarget<'a> {
+    Binding(&'a ConstBinding),
+    Import(&'a ImportBinding),
+}
+
+/// Collect the top-level bindings of a JavaScript or TypeScript module.
+///
+/// Other languages have no module bindings and return an empty set.
+pub fn module_symbols(source: &str, language: Language, path: &str) -> Result<ModuleSymbols> {
+    if !language.is_js_family() {
+        return Ok(ModuleSymbols::default());
+    }
+    let module = parse_js_module(source, language, path, None)?;
+
+    let mut symbols = ModuleSymbols::default();
+    for item in &module.body {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => {
+                push_var_decl(&mut symbols, var, false);
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                if let Decl::Var(var) = &export.decl {
+                    push_var_decl(&mut symbols, var, true);
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(named)) => {
+                let module = named
+                    .src
+                    .as_ref()
+                    .map(|src| String::from_utf8_lossy(src.value.as_bytes()).to_string());
+                for specifier in &named.specifiers {
+                    let ExportSpecifier::Named(specifier) = specifier else {
+                        continue;
+                    };
+                    let orig = export_name(&specifier.orig);
+                    let exported = specifier
+                        .exported
+                        .as_ref()
+                        .map_or_else(|| orig.clone(), export_name);
+                    match &module {
+                        Some(module) => symbols.imports.push(ImportBinding {
+                            local: exported,
+                            imported: orig,
+                            module: module.clone(),
+                            reexport: true,
+                        }),
+                        None => symbols.exports.push(ExportBinding {
+                            exported,
+                            local: orig,
+                        }),
+                    }
+                }
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                let module = String::from_utf8_lossy(import.src.value.as_bytes()).to_string();
+                for specifier in &import.specifiers {
+                    let ImportSpecifier::Named(named) = specifier else {
+                        continue;
+                    };
+                    let local = String::from_utf8_lossy(named.local.sym.as_bytes()).to_string();
+                    let imported = named
+                        .imported
+                        .as_ref()
+                        .map_or_else(|| local.clone(), export_name);
+                    symbols.imports.push(ImportBinding {
+                        local,
+                        imported,
+                        module: module.clone(),
+                        reexport: false,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(symbols)
+}
+
+fn push_var_decl(symbols: &mut ModuleSymbols, var: &VarDecl, exported: bool) {
+    for declarator in &var.decls {
+        let (Pat::Ident(binding), Some(init)) = (&declarator.name, &declarator.init) else {
+            continue;
+        };
+        let name = String::from_utf8_lossy(binding.id.sym.as_bytes()).to_string();
+        let span = swc_common::Spanned::span(&**init);
+        symbols.consts.push(ConstBinding {
+            name: name.clone(),
+            init_range: (span.lo.0 as usize - 1, span.hi.0 as usize - 1),
+        });
+        if exported {
+            symbols.exports.push(ExportBinding {
+                exported: name.clone(),
+                local: name,
+            });
+        }
+    }
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => String::from_utf8_lossy(ident.sym.as_bytes()).to_string(),
+        ModuleExportName::Str(s) => String::from_utf8_lossy(s.value.as_bytes()).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_symbols() {
+        let source = r#"import { gql } from "@apollo/client";
+import { USER_FIELDS as FIELDS } from "./fragments";
+
+const LOCAL = gql`query Local { a }`;
+export const GET_POKEMON = gql`query GetPokemon { pokemon { name } }`;
+export { LOCAL as RENAMED };
+export { GET_TRAINER } from "./trainer";
+"#;
+        let symbols = module_symbols(source, Language::TypeScript, "queries.ts").unwrap();
+
+        let Some(ExportTarget::Binding(binding)) = symbols.export("GET_POKEMON") else {
+            panic!("expected a local binding");
+        };
+        let init = &source[binding.init_range.0..binding.init_range.1];
+        assert!(init.starts_with("gql`query GetPokemon"));
+
+        let Some(ExportTarget::Binding(renamed)) = symbols.export("RENAMED") else {
+            panic!("expected a local binding");
+        };
+        assert_eq!(renamed.name, "LOCAL");
+
+        let Some(ExportTarget::Import(reexport)) = symbols.export("GET_TRAINER") else {
+            panic!("expected a re-export");
+        };
+        assert_eq!(reexport.module, "./trainer");
+        assert!(symbols.import("GET_TRAINER").is_none());
+
+        let import = symbols.import("FIELDS").unwrap();
+        assert_eq!(import.imported, "USER_FIELDS");
+        assert_eq!(import.module, "./fragments");
+        assert!(symbols.export("LOCAL").is_none());
+    }
+}
@@ -0,0 +1,221 @@
+// `# import` comments and per-document fragment scopes.
+//
+// Projects built with graphql-import style loaders declare fragment
+// dependencies in comments:
+//
+//     # import UserFields, PostFields from "./fragments.graphql"
+//     # import "./shared.graphql"
+//
+// A document with at least one import sees only its own fragments and the
+// ones it imports. Documents without imports keep project-wide visibility.
+
+use crate::{file_defined_fragment_names, GraphQLHirDatabase, TextRange, TextSize};
+use graphql_base_db::{FileContent, FileId, FileMetadata, ProjectFiles};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A single `# import …` comment.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FragmentImport {
+    /// Imported fragments; `None` for `# import "path"`, which imports every
+    /// fragment the target file defines.
+    pub names: Option<Vec<ImportedName>>,
+    /// The path as written, without quotes.
+    pub path: Arc<str>,
+    /// Byte range of `path` within the file.
+    pub path_range: TextRange,
+    /// URI of the target, resolved against the importing file.
+    pub uri: Arc<str>,
+}
+
+/// A fragment name listed in a named import.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ImportedName {
+    pub name: Arc<str>,
+    /// Byte range of the name within the file.
+    pub range: TextRange,
+}
+
+impl FragmentImport {
+    /// Whether this import brings in the fragment `name`, assuming the
+    /// target file defines it.
+    #[must_use]
+    pub fn imports(&self, name: &str) -> bool {
+        self.names
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n.name.as_ref() == name))
+    }
+}
+
+/// Fragments visible from a document, mapped to the file defining each.
+pub type FragmentScope = HashMap<Arc<str>, FileId>;
+
+/// Parse the `# import` comments in a file.
+///
+/// Derived from file content, so adding an import takes effect on the next
+/// edit.
+#[salsa::tracked]
+pub fn file_imports(
+    db: &dyn GraphQLHirDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+) -> Arc<Vec<FragmentImport>> {
+    Arc::new(parse_imports(&content.text(db), metadata.uri(db).as_str()))
+}
+
+/// The fragments a document can spread, or `None` when the document has no
+/// imports and every project fragment is visible.
+///
+/// Local fragments shadow imported ones. Imports whose target isn't a
+/// project file contribute nothing.
+#[salsa::tracked]
+pub fn file_fragment_scope(
+    db: &dyn GraphQLHirDatabase,
+    file_id: FileId,
+    content: FileContent,
+    metadata: FileMetadata,
+    project_files: ProjectFiles,
+) -> Option<Arc<FragmentScope>> {
+    let imports = file_imports(db, content, metadata);
+    if imports.is_empty() {
+        return None;
+    }
+
+    let uri_to_id = project_files.file_path_map(db).uri_to_id(db);
+    let mut scope = FragmentScope::new();
+    for import in imports.iter() {
+        let Some(&target) = uri_to_id.get(import.uri.as_ref()) else {
+            continue;
+        };
+        let Some((target_content, target_metadata)) =
+            graphql_base_db::file_lookup(db, project_files, target)
+        else {
+            continue;
+        };
+        for name in file_defined_fragment_names(db, target, target_content, target_metadata).iter()
+        {
+            if import.imports(name) {
+                scope.insert(name.clone(), target);
+            }
+        }
+    }
+    for name in file_defined_fragment_names(db, file_id, content, metadata).iter() {
+        scope.insert(name.clone(), file_id);
+    }
+
+    Some(Arc::new(scope))
+}
+
+/// The AST of the document defining fragment `name` in a specific file.
+pub fn fragment_ast_in_file(
+    db: &dyn GraphQLHirDatabase,
+    project_files: ProjectFiles,
+    file_id: FileId,
+    name: &str,
+) -> Option<Arc<apollo_compiler::ast::Document>> {
+    let (content, metadata) = graphql_base_db::file_lookup(db, project_files, file_id)?;
+    crate::file_fragment_asts(db, file_id, content, metadata)
+        .get(name)
+        .cloned()
+}
+
+/// Parse `# import` comments:
+///
+/// - Named: `# import Foo from "path"`, `# import A, B from 'path'`
+/// - Default: `# import "path"`
+fn parse_imports(source: &str, uri: &str) -> Vec<FragmentImport> {
+    let mut imports = Vec::new();
+    let mut line_start = 0;
+
+    for line in source.split('\n') {
+        let this_line_start = line_start;
+        line_start += line.len() + 1;
+
+        let Some(rest) = line.trim_start().strip_prefix('#') else {
+            continue;
+        };
+        let Some(rest) = rest.trim_start().strip_prefix("import") else {
+            continue;
+        };
+        // `#importFoo` isn't an import
+        if !rest.starts_with(char::is_whitespace) {
+            continue;
+        }
+        // Every piece below is a subslice of `line`
+        let range_of = |text: &str| {
+            let start = this_line_start + (text.as_ptr() as usize - line.as_ptr() as usize);
+            TextRange::new(
+                TextSize::from(start as u32),
+                TextSize::from((start + text.len()) as u32),
+            )
+        };
+        let rest = rest.trim();
+
+        let (names, quoted) = if rest.starts_with(['"', '\'']) {
+            (None, rest)
+        } else if let Some((names, quoted)) = rest.split_once(" from ") {
+            let names: Vec<ImportedName> = names
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(|name| ImportedName {
+                    name: Arc::from(name),
+                    range: range_of(name),
+                })
+                .collect();
+            if names.is_empty() {
+                continue;
+            }
+            (Some(names), quoted.trim())
+        } else {
+            continue;
+        };
+
+        let Some(path) = unquote(quoted) else {
+            continue;
+        };
+        imports.push(FragmentImport {
+            names,
+            path: Arc::from(path),
+            path_range: range_of(path),
+            uri: Arc::from(resolve_import_uri(uri, path)),
+        });
+    }
+
+    imports
+}
+
+/// The contents of a leading `"…"` or `'…'` string, borrowed from `text`.
+fn unquote(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let inner = &text[1..];
+    Some(&inner[..inner.find(quote)?])
+}
+
+/// Resolve an import path against the URI of the importing file.
+#[must_use]
+pub fn resolve_import_uri(importer: &str, path: &str) -> String {
+    let (scheme, importer_path) = importer
+        .find("://")
+        .map_or(("", importer), |i| importer.split_at(i + 3));
+    let parent = importer_path
+        .rfind('/')
+        .map_or("", |slash| &importer_path[..slash]);
+    let joined = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{parent}/{path}")
+    };
+
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            other => segments.push(other),
+        }
+    }
+    format!("{scheme}/{}", segments.join("/"))
+}
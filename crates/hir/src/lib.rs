@@ -7,10 +7,14 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 mod body;
+mod imports;
 mod structure;
+mod structure_cache;
 
 pub use body::*;
+pub use imports::*;
 pub use structure::*;
+pub use structure_cache::StructureCache;
 
 // Type aliases for commonly used HashMap types.
 // These improve readability in function signatures and provide
@@ -19,6 +23,9 @@ pub use structure::*;
 /// Map from type name to type definition.
 pub type TypeDefMap = HashMap<Arc<str>, TypeDef>;
 
+/// Map from type name to the kind of type it is.
+pub type TypeKindMap = HashMap<Arc<str>, TypeDefKind>;
+
 /// Map from fragment name to fragment structure.
 pub type FragmentMap = HashMap<Arc<str>, FragmentStructure>;
 
@@ -105,6 +112,33 @@ pub trait GraphQLHirDatabase: graphql_syntax::GraphQLSyntaxDatabase {
     fn project_files(&self) -> Option<graphql_base_db::ProjectFiles> {
         None
     }
+
+    /// Recorded traffic for the `operation_index`-th operation in a file,
+    /// from the project's operation usage report
+    ///
+    /// Returns `None` when no report is loaded, and a zero count when one is
+    /// loaded but doesn't mention the operation.
+    fn operation_calls(&self, _file_id: FileId, _operation_index: usize) -> Option<OperationCalls> {
+        None
+    }
+
+    /// Schema variants configured for the project, if any.
+    fn schema_variant_file_ids(&self) -> Option<graphql_base_db::SchemaVariantFileIds> {
+        None
+    }
+
+    /// Structures kept from previous sessions, consulted by `file_structure`
+    /// before parsing a file. `None` when persistence is off.
+    fn structure_cache(&self) -> Option<&StructureCache> {
+        None
+    }
+}
+
+/// How often an operation was called over the usage report's window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationCalls {
+    pub count: u64,
+    pub window_days: u32,
 }
 
 // ============================================================================
@@ -125,6 +159,27 @@ pub fn file_type_defs(
     Arc::clone(&structure.type_defs)
 }
 
+/// Get the name and kind of each type a single schema file defines or extends
+///
+/// Unlike `file_type_defs`, this result only changes when a type is added,
+/// removed or changes kind, so edits to fields, descriptions or positions
+/// stop here instead of invalidating `schema_type_kinds`.
+#[salsa::tracked]
+pub fn file_type_kinds(
+    db: &dyn GraphQLHirDatabase,
+    file_id: FileId,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+) -> Arc<Vec<(Arc<str>, TypeDefKind)>> {
+    let type_defs = file_type_defs(db, file_id, content, metadata);
+    Arc::new(
+        type_defs
+            .iter()
+            .map(|type_def| (type_def.name.clone(), type_def.kind))
+            .collect(),
+    )
+}
+
 /// Get fragments from a single document file
 /// This query is cached per-file - editing another file won't invalidate it
 #[salsa::tracked]
@@ -243,26 +298,66 @@ pub fn schema_types(
     project_files: graphql_base_db::ProjectFiles,
 ) -> TypeDefMap {
     if has_resolved_schema(db, project_files) {
-        // Collect builtin file IDs from the source schema list
-        let source_ids = project_files.schema_file_ids(db).ids(db);
-        let resolved_ids = project_files.resolved_schema_file_ids(db).ids(db);
-
-        let mut combined: Vec<FileId> = source_ids
-            .iter()
-            .copied()
-            .filter(|fid| {
-                graphql_base_db::file_lookup(db, project_files, *fid)
-                    .is_some_and(|(_, meta)| is_builtin_uri(meta.uri(db).as_str()))
-            })
-            .collect();
-        combined.extend(resolved_ids.iter().copied());
-
-        build_type_map(db, project_files, &combined)
+        build_type_map(
+            db,
+            project_files,
+            &resolved_type_file_ids(db, project_files),
+        )
     } else {
         source_schema_types(db, project_files).clone()
     }
 }
 
+/// Get the kind of every type in `schema_types`, without building the types.
+///
+/// Merged from the per-file `file_type_kinds` contributions, so editing a
+/// field in one schema file doesn't re-run this query, let alone the queries
+/// that only need to know whether a type exists and what kind it is.
+#[salsa::tracked(returns(ref))]
+pub fn schema_type_kinds(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> TypeKindMap {
+    let file_ids = if has_resolved_schema(db, project_files) {
+        resolved_type_file_ids(db, project_files)
+    } else {
+        project_files.schema_file_ids(db).ids(db).to_vec()
+    };
+
+    let mut kinds = TypeKindMap::new();
+    for file_id in &file_ids {
+        if let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        {
+            for (name, kind) in file_type_kinds(db, *file_id, content, metadata).iter() {
+                // Extensions carry the kind of the type they extend, so the
+                // first definition seen is as good as any
+                kinds.entry(name.clone()).or_insert(*kind);
+            }
+        }
+    }
+    kinds
+}
+
+/// Builtin files from the source schema list plus the resolved schema files
+fn resolved_type_file_ids(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Vec<FileId> {
+    let source_ids = project_files.schema_file_ids(db).ids(db);
+    let resolved_ids = project_files.resolved_schema_file_ids(db).ids(db);
+
+    let mut combined: Vec<FileId> = source_ids
+        .iter()
+        .copied()
+        .filter(|fid| {
+            graphql_base_db::file_lookup(db, project_files, *fid)
+                .is_some_and(|(_, meta)| is_builtin_uri(meta.uri(db).as_str()))
+        })
+        .collect();
+    combined.extend(resolved_ids.iter().copied());
+    combined
+}
+
 /// Get all types from the source schema files (always uses `schema_file_ids`).
 ///
 /// Used for navigation (goto-definition, hover) which should prefer source files.
@@ -275,6 +370,72 @@ pub fn source_schema_types(
     build_type_map(db, project_files, &schema_ids)
 }
 
+/// Get all types of a named schema variant, plus builtins.
+///
+/// Empty when the variant isn't configured.
+#[salsa::tracked(returns(ref))]
+#[allow(clippy::needless_pass_by_value)] // Arc<str> needed for Salsa tracking
+pub fn variant_schema_types(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    variant: Arc<str>,
+) -> TypeDefMap {
+    let Some(variant_ids) = db
+        .schema_variant_file_ids()
+        .and_then(|input| input.variants(db).get(&variant).cloned())
+    else {
+        return TypeDefMap::new();
+    };
+
+    let mut file_ids: Vec<FileId> = project_files
+        .schema_file_ids(db)
+        .ids(db)
+        .iter()
+        .copied()
+        .filter(|fid| {
+            graphql_base_db::file_lookup(db, project_files, *fid)
+                .is_some_and(|(_, meta)| is_builtin_uri(meta.uri(db).as_str()))
+        })
+        .collect();
+    file_ids.extend(variant_ids.iter().copied());
+    build_type_map(db, project_files, &file_ids)
+}
+
+/// The schema variant a document targets, if any.
+///
+/// A `schema-variant` pragma naming a configured variant wins; otherwise the
+/// variant whose `documents` patterns matched the file.
+pub fn document_schema_variant(
+    db: &dyn GraphQLHirDatabase,
+    file_id: FileId,
+    content: graphql_base_db::FileContent,
+) -> Option<Arc<str>> {
+    let input = db.schema_variant_file_ids()?;
+    if let Some(pragma) = graphql_syntax::file_pragmas(db, content)
+        .schema_variant
+        .as_ref()
+    {
+        if input.variants(db).contains_key(&pragma.value) {
+            return Some(pragma.value.clone());
+        }
+    }
+    input.documents(db).get(&file_id).cloned()
+}
+
+/// Get the types a document is validated and completed against: its schema
+/// variant's when it targets one, otherwise `schema_types`.
+pub fn document_schema_types(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    file_id: FileId,
+    content: graphql_base_db::FileContent,
+) -> &TypeDefMap {
+    match document_schema_variant(db, file_id, content) {
+        Some(variant) => variant_schema_types(db, project_files, variant),
+        None => schema_types(db, project_files),
+    }
+}
+
 /// Shared logic for building a type definition map from a set of file IDs.
 fn build_type_map(
     db: &dyn GraphQLHirDatabase,
@@ -406,9 +567,18 @@ fn build_directive_map(
     for file_id in file_ids {
         if let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
         {
+            let builtin = is_builtin_uri(metadata.uri(db).as_str());
             let file_dirs = file_directive_defs(db, *file_id, content, metadata);
             for dir_def in file_dirs.iter() {
-                directives.insert(dir_def.name.clone(), dir_def.clone());
+                // A directive the schema declares itself replaces the
+                // built-in definition, whichever file comes first
+                if builtin {
+                    directives
+                        .entry(dir_def.name.clone())
+                        .or_insert_with(|| dir_def.clone());
+                } else {
+                    directives.insert(dir_def.name.clone(), dir_def.clone());
+                }
             }
         }
     }
@@ -416,6 +586,33 @@ fn build_directive_map(
     directives
 }
 
+/// Whether the project's own schema declares the directive `name`, rather
+/// than it only coming from the built-in definitions.
+///
+/// Servers advertise optional features such as incremental delivery by
+/// declaring their directives, which the built-ins define regardless.
+pub fn schema_declares_directive(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    name: &str,
+) -> bool {
+    let file_ids = if has_resolved_schema(db, project_files) {
+        project_files.resolved_schema_file_ids(db).ids(db)
+    } else {
+        project_files.schema_file_ids(db).ids(db)
+    };
+    file_ids.iter().any(|file_id| {
+        graphql_base_db::file_lookup(db, project_files, *file_id).is_some_and(
+            |(content, metadata)| {
+                !is_builtin_uri(metadata.uri(db).as_str())
+                    && file_directive_defs(db, *file_id, content, metadata)
+                        .iter()
+                        .any(|def| def.name.as_ref() == name)
+            },
+        )
+    })
+}
+
 /// Get all fragments in the project
 ///
 /// This query uses granular dependencies:
@@ -518,7 +715,7 @@ pub fn file_fragment_sources(
 
     // Unified: iterate over all documents (works for both pure GraphQL and TS/JS)
     for doc in parse.documents() {
-        let source: Arc<str> = Arc::from(doc.source);
+        let source = doc.shared_source();
         for def in &doc.ast.definitions {
             if let apollo_compiler::ast::Definition::FragmentDefinition(frag) = def {
                 let name: Arc<str> = Arc::from(frag.name.as_str());
@@ -757,6 +954,26 @@ pub fn interface_implementors(
     Arc::new(map)
 }
 
+/// Map from type name to the unions that list it as a member.
+/// The union-side counterpart of `interface_implementors`, used to answer
+/// "which abstract types contain this object" without scanning every union.
+#[salsa::tracked]
+pub fn union_memberships(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Arc<HashMap<Arc<str>, Vec<Arc<str>>>> {
+    let types = schema_types(db, project_files);
+    let mut map: HashMap<Arc<str>, Vec<Arc<str>>> = HashMap::new();
+    for (type_name, type_def) in types {
+        for member in &type_def.union_members {
+            map.entry(Arc::clone(member))
+                .or_default()
+                .push(Arc::clone(type_name));
+        }
+    }
+    Arc::new(map)
+}
+
 /// Get all operations in the project
 /// Uses granular per-file caching for efficient invalidation.
 #[salsa::tracked]
@@ -811,6 +1028,52 @@ pub fn project_operation_name_index(
     Arc::new(name_counts)
 }
 
+/// Index mapping operation names to the file defining them.
+///
+/// When several files define an operation with the same name, the first
+/// document file wins; `unique_names` reports the duplicates.
+#[salsa::tracked]
+pub fn operation_file_location_index(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Arc<HashMap<Arc<str>, FileId>> {
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+    let mut index = HashMap::new();
+
+    for file_id in doc_ids.iter() {
+        if let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        {
+            let op_names = file_operation_names(db, *file_id, content, metadata);
+            for op_info in op_names.iter() {
+                index.entry(op_info.name.clone()).or_insert(*file_id);
+            }
+        }
+    }
+
+    Arc::new(index)
+}
+
+/// Get a single operation by name.
+///
+/// Like `fragment_source`, this only depends on the file defining the
+/// operation, so edits elsewhere don't invalidate it beyond the name index.
+#[salsa::tracked]
+#[allow(clippy::needless_pass_by_value)] // Salsa tracked functions require owned arguments
+pub fn operation_by_name(
+    db: &dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    operation_name: Arc<str>,
+) -> Option<OperationStructure> {
+    let location_index = operation_file_location_index(db, project_files);
+    let file_id = location_index.get(&operation_name)?;
+    let (content, metadata) = graphql_base_db::file_lookup(db, project_files, *file_id)?;
+
+    file_operations(db, *file_id, content, metadata)
+        .iter()
+        .find(|op| op.name.as_ref() == Some(&operation_name))
+        .cloned()
+}
+
 // ============================================================================
 // Per-file contribution queries for project-wide lint rules
 // These enable incremental computation: editing one file only recomputes that
@@ -819,7 +1082,7 @@ pub fn project_operation_name_index(
 
 /// Per-file query for fragment names used (spread) in a file.
 /// Returns all fragment spread names found in operations and fragments.
-/// This enables incremental computation for the `no_unused_fragments` lint rule.
+/// Feeds reference search and the project-wide used-fragment set.
 #[salsa::tracked]
 #[allow(clippy::items_after_statements)]
 pub fn file_used_fragment_names(
@@ -1025,6 +1288,126 @@ pub struct SchemaCoordinate {
     pub field_name: Arc<str>,
 }
 
+/// Collects the schema coordinates selected by operations and fragments,
+/// following fragment spreads into their definitions.
+struct CoordinateCollector<'a> {
+    db: &'a dyn GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    schema_types: &'a TypeDefMap,
+    fragments: &'a FragmentMap,
+    visited_fragments: std::collections::HashSet<Arc<str>>,
+    coordinates: std::collections::HashSet<SchemaCoordinate>,
+}
+
+impl<'a> CoordinateCollector<'a> {
+    fn new(db: &'a dyn GraphQLHirDatabase, project_files: graphql_base_db::ProjectFiles) -> Self {
+        Self {
+            db,
+            project_files,
+            schema_types: schema_types(db, project_files),
+            fragments: all_fragments(db, project_files),
+            visited_fragments: std::collections::HashSet::new(),
+            coordinates: std::collections::HashSet::new(),
+        }
+    }
+
+    /// Root type an operation's selection set is resolved against, if the
+    /// schema defines it
+    fn root_type(&self, operation_type: apollo_compiler::ast::OperationType) -> Option<Arc<str>> {
+        let name = match operation_type {
+            apollo_compiler::ast::OperationType::Query => "Query",
+            apollo_compiler::ast::OperationType::Mutation => "Mutation",
+            apollo_compiler::ast::OperationType::Subscription => "Subscription",
+        };
+        self.schema_types
+            .contains_key(name)
+            .then(|| Arc::from(name))
+    }
+
+    fn collect_from_selections(
+        &mut self,
+        selections: &[apollo_compiler::ast::Selection],
+        parent_type: &Arc<str>,
+    ) {
+        for selection in selections {
+            match selection {
+                apollo_compiler::ast::Selection::Field(field) => {
+                    let field_name: Arc<str> = Arc::from(field.name.as_str());
+
+                    // Record this schema coordinate
+                    self.coordinates.insert(SchemaCoordinate {
+                        type_name: parent_type.clone(),
+                        field_name: field_name.clone(),
+                    });
+
+                    // Recursively process nested selections
+                    if !field.selection_set.is_empty() {
+                        // Find the field's return type from schema
+                        if let Some(type_def) = self.schema_types.get(parent_type) {
+                            if let Some(field_sig) = type_def
+                                .fields
+                                .iter()
+                                .find(|f| f.name.as_ref() == field_name.as_ref())
+                            {
+                                let nested_type: Arc<str> =
+                                    Arc::from(field_sig.type_ref.name.as_ref());
+                                self.collect_from_selections(&field.selection_set, &nested_type);
+                            }
+                        }
+                    }
+                }
+                apollo_compiler::ast::Selection::FragmentSpread(spread) => {
+                    let frag_name: Arc<str> = Arc::from(spread.fragment_name.as_str());
+
+                    // Prevent infinite recursion from circular fragment references
+                    if self.visited_fragments.contains(&frag_name) {
+                        continue;
+                    }
+
+                    // Look up the fragment structure for type condition
+                    if let Some(frag_structure) = self.fragments.get(&frag_name) {
+                        self.visited_fragments.insert(frag_name.clone());
+
+                        // Use fragment_ast to get the parsed AST (handles both embedded and pure GraphQL)
+                        if let Some(ast) =
+                            fragment_ast(self.db, self.project_files, frag_name.clone())
+                        {
+                            // Find the fragment definition in the AST
+                            for def in &ast.definitions {
+                                if let apollo_compiler::ast::Definition::FragmentDefinition(frag) =
+                                    def
+                                {
+                                    if frag.name.as_str() == frag_name.as_ref() {
+                                        self.collect_from_selections(
+                                            &frag.selection_set,
+                                            &frag_structure.type_condition,
+                                        );
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                apollo_compiler::ast::Selection::InlineFragment(inline) => {
+                    let inline_type = inline
+                        .type_condition
+                        .as_ref()
+                        .map_or_else(|| parent_type.clone(), |tc| Arc::from(tc.as_str()));
+                    self.collect_from_selections(&inline.selection_set, &inline_type);
+                }
+            }
+        }
+    }
+
+    /// Take the coordinates collected so far, starting the next collection
+    /// with no fragments visited
+    fn take(&mut self) -> std::collections::HashSet<SchemaCoordinate> {
+        self.visited_fragments.clear();
+        std::mem::take(&mut self.coordinates)
+    }
+}
+
 /// Per-file query for schema coordinates used in a file.
 /// Returns all `Type.field` coordinates referenced in operations and fragments.
 /// This enables incremental computation for the `no_unused_fields` lint rule.
@@ -1036,7 +1419,6 @@ pub struct SchemaCoordinate {
 /// and `FragmentA` is defined elsewhere and uses fields, those fields are
 /// included in this file's coordinates.
 #[salsa::tracked]
-#[allow(clippy::items_after_statements)]
 pub fn file_schema_coordinates(
     db: &dyn GraphQLHirDatabase,
     _file_id: FileId,
@@ -1045,147 +1427,97 @@ pub fn file_schema_coordinates(
     project_files: graphql_base_db::ProjectFiles,
 ) -> Arc<std::collections::HashSet<SchemaCoordinate>> {
     let parse = graphql_syntax::parse(db, content, metadata);
-    let schema_types = schema_types(db, project_files);
-    let fragments = all_fragments(db, project_files);
-
-    // Get root type names
-    let query_type = schema_types
-        .contains_key("Query")
-        .then(|| Arc::from("Query"));
-    let mutation_type = schema_types
-        .contains_key("Mutation")
-        .then(|| Arc::from("Mutation"));
-    let subscription_type = schema_types
-        .contains_key("Subscription")
-        .then(|| Arc::from("Subscription"));
-
-    // Context for collecting coordinates - allows passing db and project_files to helper
-    struct CollectContext<'a> {
-        db: &'a dyn GraphQLHirDatabase,
-        project_files: graphql_base_db::ProjectFiles,
-        schema_types: &'a TypeDefMap,
-        fragments: &'a FragmentMap,
-        visited_fragments: std::collections::HashSet<Arc<str>>,
-        coordinates: std::collections::HashSet<SchemaCoordinate>,
-    }
-
-    impl CollectContext<'_> {
-        fn collect_from_selections(
-            &mut self,
-            selections: &[apollo_compiler::ast::Selection],
-            parent_type: &Arc<str>,
-        ) {
-            for selection in selections {
-                match selection {
-                    apollo_compiler::ast::Selection::Field(field) => {
-                        let field_name: Arc<str> = Arc::from(field.name.as_str());
-
-                        // Record this schema coordinate
-                        self.coordinates.insert(SchemaCoordinate {
-                            type_name: parent_type.clone(),
-                            field_name: field_name.clone(),
-                        });
-
-                        // Recursively process nested selections
-                        if !field.selection_set.is_empty() {
-                            // Find the field's return type from schema
-                            if let Some(type_def) = self.schema_types.get(parent_type) {
-                                if let Some(field_sig) = type_def
-                                    .fields
-                                    .iter()
-                                    .find(|f| f.name.as_ref() == field_name.as_ref())
-                                {
-                                    let nested_type: Arc<str> =
-                                        Arc::from(field_sig.type_ref.name.as_ref());
-                                    self.collect_from_selections(
-                                        &field.selection_set,
-                                        &nested_type,
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    apollo_compiler::ast::Selection::FragmentSpread(spread) => {
-                        let frag_name: Arc<str> = Arc::from(spread.fragment_name.as_str());
-
-                        // Prevent infinite recursion from circular fragment references
-                        if self.visited_fragments.contains(&frag_name) {
-                            continue;
-                        }
-
-                        // Look up the fragment structure for type condition
-                        if let Some(frag_structure) = self.fragments.get(&frag_name) {
-                            self.visited_fragments.insert(frag_name.clone());
-
-                            // Use fragment_ast to get the parsed AST (handles both embedded and pure GraphQL)
-                            if let Some(ast) =
-                                fragment_ast(self.db, self.project_files, frag_name.clone())
-                            {
-                                // Find the fragment definition in the AST
-                                for def in &ast.definitions {
-                                    if let apollo_compiler::ast::Definition::FragmentDefinition(
-                                        frag,
-                                    ) = def
-                                    {
-                                        if frag.name.as_str() == frag_name.as_ref() {
-                                            self.collect_from_selections(
-                                                &frag.selection_set,
-                                                &frag_structure.type_condition,
-                                            );
-                                            break;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    apollo_compiler::ast::Selection::InlineFragment(inline) => {
-                        let inline_type = inline
-                            .type_condition
-                            .as_ref()
-                            .map_or_else(|| parent_type.clone(), |tc| Arc::from(tc.as_str()));
-                        self.collect_from_selections(&inline.selection_set, &inline_type);
-                    }
-                }
-            }
-        }
-    }
-
-    let mut ctx = CollectContext {
-        db,
-        project_files,
-        schema_types,
-        fragments,
-        visited_fragments: std::collections::HashSet::new(),
-        coordinates: std::collections::HashSet::new(),
-    };
+    let mut collector = CoordinateCollector::new(db, project_files);
 
     // Unified: process all documents (works for both pure GraphQL and TS/JS)
     for doc in parse.documents() {
         for definition in &doc.ast.definitions {
             match definition {
                 apollo_compiler::ast::Definition::OperationDefinition(op) => {
-                    let root_type = match op.operation_type {
-                        apollo_compiler::ast::OperationType::Query => query_type.as_ref(),
-                        apollo_compiler::ast::OperationType::Mutation => mutation_type.as_ref(),
-                        apollo_compiler::ast::OperationType::Subscription => {
-                            subscription_type.as_ref()
-                        }
-                    };
-                    if let Some(root) = root_type {
-                        ctx.collect_from_selections(&op.selection_set, root);
+                    if let Some(root) = collector.root_type(op.operation_type) {
+                        collector.collect_from_selections(&op.selection_set, &root);
                     }
                 }
                 apollo_compiler::ast::Definition::FragmentDefinition(frag) => {
                     let frag_type = Arc::from(frag.type_condition.as_str());
-                    ctx.collect_from_selections(&frag.selection_set, &frag_type);
+                    collector.collect_from_selections(&frag.selection_set, &frag_type);
                 }
                 _ => {}
             }
         }
     }
 
-    Arc::new(ctx.coordinates)
+    Arc::new(collector.take())
+}
+
+/// Schema coordinates selected by each operation in a file, indexed like
+/// [`operation_body`], including those reached through fragment spreads.
+///
+/// Unlike [`file_schema_coordinates`], fragment definitions don't contribute
+/// on their own: a field only counts for the operations that actually
+/// select it.
+#[salsa::tracked]
+pub fn operation_schema_coordinates(
+    db: &dyn GraphQLHirDatabase,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Arc<Vec<std::collections::HashSet<SchemaCoordinate>>> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let mut collector = CoordinateCollector::new(db, project_files);
+
+    let mut operations = Vec::new();
+    for doc in parse.documents() {
+        for definition in &doc.ast.definitions {
+            if let apollo_compiler::ast::Definition::OperationDefinition(op) = definition {
+                if let Some(root) = collector.root_type(op.operation_type) {
+                    collector.collect_from_selections(&op.selection_set, &root);
+                }
+                operations.push(collector.take());
+            }
+        }
+    }
+
+    Arc::new(operations)
+}
+
+/// Schema types each operation in a file touches, indexed like
+/// [`operation_body`]: the types its fields are selected on and the types
+/// those fields return, through fragment spreads too.
+#[salsa::tracked]
+pub fn operation_type_usage(
+    db: &dyn GraphQLHirDatabase,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Arc<Vec<std::collections::BTreeSet<Arc<str>>>> {
+    let types = schema_types(db, project_files);
+    let coordinates = operation_schema_coordinates(db, content, metadata, project_files);
+
+    let usage = coordinates
+        .iter()
+        .map(|operation_coordinates| {
+            let mut touched = std::collections::BTreeSet::new();
+            for coordinate in operation_coordinates {
+                let Some(type_def) = types.get(&coordinate.type_name) else {
+                    continue;
+                };
+                touched.insert(coordinate.type_name.clone());
+                if let Some(field) = type_def
+                    .fields
+                    .iter()
+                    .find(|f| f.name == coordinate.field_name)
+                {
+                    if types.contains_key(&field.type_ref.name) {
+                        touched.insert(field.type_ref.name.clone());
+                    }
+                }
+            }
+            touched
+        })
+        .collect();
+
+    Arc::new(usage)
 }
 
 /// Index mapping type names to the files that reference them.
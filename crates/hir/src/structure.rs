@@ -1,6 +1,7 @@
 use apollo_compiler::ast;
 use apollo_compiler::Node;
 use graphql_base_db::FileId;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 pub use text_size::{TextRange, TextSize};
 
@@ -8,8 +9,14 @@ pub use text_size::{TextRange, TextSize};
 /// Each block's operations get offset by `block_index * BLOCK_INDEX_OFFSET`.
 const BLOCK_INDEX_OFFSET: usize = 1000;
 
+/// File ids aren't stable across sessions, so they're left out of persisted
+/// structures and filled in when one is restored for a file.
+fn unassigned_file_id() -> FileId {
+    FileId::new(0)
+}
+
 /// Structure of a type definition (no field bodies)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypeDef {
     pub name: Arc<str>,
     pub kind: TypeDefKind,
@@ -19,6 +26,7 @@ pub struct TypeDef {
     pub enum_values: Vec<EnumValue>,
     pub description: Option<Arc<str>>,
     pub directives: Vec<DirectiveUsage>,
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
     /// The text range of the type name
     pub name_range: TextRange,
@@ -28,7 +36,7 @@ pub struct TypeDef {
     pub is_extension: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum TypeDefKind {
     Object,
@@ -40,11 +48,13 @@ pub enum TypeDefKind {
 }
 
 /// Signature of a field (no resolver, no body)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FieldSignature {
     pub name: Arc<str>,
     pub type_ref: TypeRef,
     pub arguments: Vec<ArgumentDef>,
+    /// Default value of an input object field; always `None` on output fields
+    pub default_value: Option<Arc<str>>,
     pub description: Option<Arc<str>>,
     pub is_deprecated: bool,
     pub deprecation_reason: Option<Arc<str>>,
@@ -57,11 +67,52 @@ pub struct FieldSignature {
     /// `fixer.remove(node)` semantics.
     pub definition_range: TextRange,
     /// The file this field was defined in
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
 }
 
+impl FieldSignature {
+    /// The levels `@semanticNonNull` marks as non-null except when an error
+    /// nulls them: `0` is the field itself, `1` the items of a list field.
+    ///
+    /// Empty when the field doesn't carry the directive. Levels the type
+    /// already marks non-null, and levels deeper than the type, are left out.
+    #[must_use]
+    pub fn semantic_non_null_levels(&self) -> Vec<u32> {
+        let Some(directive) = self
+            .directives
+            .iter()
+            .find(|d| d.name.as_ref() == "semanticNonNull")
+        else {
+            return Vec::new();
+        };
+        let levels: Vec<u32> = directive
+            .arguments
+            .iter()
+            .find(|arg| arg.name.as_ref() == "levels")
+            .map_or_else(
+                || vec![0],
+                |arg| {
+                    arg.value
+                        .trim_matches(|c| c == '[' || c == ']')
+                        .split([',', ' '])
+                        .filter_map(|level| level.trim().parse().ok())
+                        .collect()
+                },
+            );
+        levels
+            .into_iter()
+            .filter(|level| match level {
+                0 => !self.type_ref.is_non_null,
+                1 => self.type_ref.is_list && !self.type_ref.inner_non_null,
+                _ => false,
+            })
+            .collect()
+    }
+}
+
 /// Reference to a type (with list/non-null wrappers)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypeRef {
     pub name: Arc<str>,
     pub is_list: bool,
@@ -73,7 +124,7 @@ pub struct TypeRef {
 }
 
 /// Argument definition
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ArgumentDef {
     pub name: Arc<str>,
     pub type_ref: TypeRef,
@@ -89,11 +140,12 @@ pub struct ArgumentDef {
     /// surface a "remove this whole argument" fix.
     pub definition_range: TextRange,
     /// The file this argument was defined in
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
 }
 
 /// Enum value definition
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EnumValue {
     pub name: Arc<str>,
     pub description: Option<Arc<str>>,
@@ -110,7 +162,7 @@ pub struct EnumValue {
 }
 
 /// A directive applied to a schema element
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DirectiveUsage {
     pub name: Arc<str>,
     pub arguments: Vec<DirectiveArgument>,
@@ -122,7 +174,7 @@ pub struct DirectiveUsage {
 }
 
 /// An argument passed to a directive
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DirectiveArgument {
     pub name: Arc<str>,
     /// Serialized value (e.g. `"hello"`, `true`, `ENUM_VALUE`)
@@ -135,13 +187,14 @@ pub struct DirectiveArgument {
 }
 
 /// A directive definition from the schema (e.g. `directive @cacheControl on FIELD_DEFINITION`)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DirectiveDef {
     pub name: Arc<str>,
     pub description: Option<Arc<str>>,
     pub locations: Vec<DirectiveLocationKind>,
     pub arguments: Vec<ArgumentDef>,
     pub repeatable: bool,
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
     pub name_range: TextRange,
     /// The text range of the entire directive definition node (keyword through
@@ -151,7 +204,7 @@ pub struct DirectiveDef {
 }
 
 /// Locations where a directive can be applied
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum DirectiveLocationKind {
     // Executable locations
     Query,
@@ -247,11 +300,12 @@ impl From<ast::DirectiveLocation> for DirectiveLocationKind {
 }
 
 /// Operation structure (name and variables, no selection set details)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct OperationStructure {
     pub name: Option<Arc<str>>,
     pub operation_type: OperationType,
     pub variables: Vec<VariableSignature>,
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
     pub index: usize,
     /// The text range of the operation name (if named)
@@ -266,7 +320,7 @@ pub struct OperationStructure {
     pub block_source: Option<Arc<str>>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum OperationType {
     Query,
@@ -274,8 +328,18 @@ pub enum OperationType {
     Subscription,
 }
 
+impl From<OperationType> for graphql_apollo_ext::OperationType {
+    fn from(operation_type: OperationType) -> Self {
+        match operation_type {
+            OperationType::Query => Self::Query,
+            OperationType::Mutation => Self::Mutation,
+            OperationType::Subscription => Self::Subscription,
+        }
+    }
+}
+
 /// Variable signature
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct VariableSignature {
     pub name: Arc<str>,
     pub type_ref: TypeRef,
@@ -283,10 +347,11 @@ pub struct VariableSignature {
 }
 
 /// Fragment structure (name and type, no selection set details)
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FragmentStructure {
     pub name: Arc<str>,
     pub type_condition: Arc<str>,
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
     /// The text range of the fragment name
     pub name_range: TextRange,
@@ -308,8 +373,9 @@ pub struct FragmentStructure {
 /// Fields use `Arc<Vec<...>>` to enable cheap cloning without copying data.
 /// This is critical for performance: queries like `file_fragments` can return
 /// a clone of the Arc instead of cloning the entire vector.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FileStructureData {
+    #[serde(skip, default = "unassigned_file_id")]
     pub file_id: FileId,
     pub type_defs: Arc<Vec<TypeDef>>,
     pub operations: Arc<Vec<OperationStructure>>,
@@ -381,6 +447,19 @@ pub fn file_structure(
     content: graphql_base_db::FileContent,
     metadata: graphql_base_db::FileMetadata,
 ) -> Arc<FileStructureData> {
+    let cache = db.structure_cache().map(|cache| {
+        (
+            cache,
+            crate::structure_cache::cache_key(db, content, metadata),
+        )
+    });
+    if let Some(cached) = cache
+        .as_ref()
+        .and_then(|(cache, key)| cache.get(key, file_id))
+    {
+        return cached;
+    }
+
     let parse = graphql_syntax::parse(db, content, metadata);
 
     let mut type_defs = Vec::new();
@@ -392,7 +471,7 @@ pub fn file_structure(
         // For embedded GraphQL (byte_offset > 0), include block context
         // For pure GraphQL (byte_offset == 0), no block context needed
         let block_ctx = if doc.byte_offset > 0 {
-            BlockContext::embedded(doc.line_offset, doc.byte_offset, Arc::from(doc.source))
+            BlockContext::embedded(doc.line_offset, doc.byte_offset, doc.shared_source())
         } else {
             BlockContext::pure_graphql()
         };
@@ -414,13 +493,18 @@ pub fn file_structure(
         }
     }
 
-    Arc::new(FileStructureData {
+    let structure = Arc::new(FileStructureData {
         file_id,
         type_defs: Arc::new(type_defs),
         operations: Arc::new(operations),
         fragments: Arc::new(fragments),
         directive_defs: Arc::new(directive_defs),
-    })
+    });
+    // A file skipped over its extraction budget might fit next time
+    if let Some((cache, key)) = cache.filter(|_| parse.skipped_reason().is_none()) {
+        cache.insert(key, file_id, &structure);
+    }
+    structure
 }
 
 fn extract_from_document(
@@ -981,6 +1065,7 @@ fn extract_field_signature(field: &Node<ast::FieldDefinition>, file_id: FileId)
         name,
         type_ref,
         arguments,
+        default_value: None,
         description,
         is_deprecated,
         deprecation_reason,
@@ -997,6 +1082,10 @@ fn extract_input_field_signature(
 ) -> FieldSignature {
     let name = Arc::from(field.name.as_str());
     let type_ref = extract_type_ref(&field.ty);
+    let default_value = field
+        .default_value
+        .as_ref()
+        .map(|v| Arc::from(v.to_string().as_str()));
     let description = field.description.as_ref().map(|d| Arc::from(d.as_str()));
 
     let (is_deprecated, deprecation_reason) = extract_deprecation(&field.directives);
@@ -1005,6 +1094,7 @@ fn extract_input_field_signature(
         name,
         type_ref,
         arguments: Vec::new(),
+        default_value,
         description,
         is_deprecated,
         deprecation_reason,
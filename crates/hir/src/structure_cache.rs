@@ -0,0 +1,224 @@
+// Content-addressed cache of file structures
+//
+// Project-wide indexes (types, fragments, operations) are built from each
+// file's `file_structure`, so on a large repo startup is dominated by
+// parsing files that haven't changed since the last session. This cache
+// keeps structures keyed by a hash of everything the parse depends on, and
+// can be written to disk and restored on the next start.
+//
+// A hit returns exactly what parsing the file would have, so the cache is
+// invisible to Salsa: it only decides whether `file_structure` parses.
+
+use crate::FileStructureData;
+use graphql_base_db::{FileContent, FileId, FileMetadata};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+
+/// Bumped whenever the shape of `FileStructureData` or the way it's
+/// extracted changes, so caches written by older builds are discarded.
+const FORMAT_VERSION: u32 = 1;
+
+/// File structures from this and previous sessions, by content hash.
+#[derive(Debug, Default)]
+pub struct StructureCache {
+    /// Entries restored from disk, by content hash
+    restored: RwLock<HashMap<String, Arc<FileStructureData>>>,
+    /// Each file's latest structure this session, with its content hash.
+    /// Only these are saved, and an edit replaces the file's entry, so
+    /// structures of deleted or edited files don't pile up across restarts
+    current: RwLock<HashMap<FileId, (String, Arc<FileStructureData>)>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFile {
+    version: String,
+    entries: HashMap<String, FileStructureData>,
+}
+
+impl StructureCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The structure cached for `key`, with its file ids set to `file_id`.
+    pub fn get(&self, key: &str, file_id: FileId) -> Option<Arc<FileStructureData>> {
+        let current = self
+            .current
+            .read()
+            .expect("structure cache lock poisoned")
+            .get(&file_id)
+            .filter(|(current_key, _)| current_key == key)
+            .map(|(_, structure)| structure.clone());
+        if current.is_some() {
+            return current;
+        }
+        // Restored entries are kept, since files with the same content
+        // share one
+        let restored = self
+            .restored
+            .read()
+            .expect("structure cache lock poisoned")
+            .get(key)
+            .cloned()?;
+        let structure = Arc::new(with_file_id(&restored, file_id));
+        self.insert(key.to_string(), file_id, &structure);
+        Some(structure)
+    }
+
+    /// Record `structure` as the latest for `file_id`, replacing the entry
+    /// for its previous content.
+    pub fn insert(&self, key: String, file_id: FileId, structure: &Arc<FileStructureData>) {
+        self.current
+            .write()
+            .expect("structure cache lock poisoned")
+            .insert(file_id, (key, structure.clone()));
+    }
+
+    /// Drop the entries of files that are no longer in the project, so they
+    /// aren't saved.
+    pub fn retain_files(&self, live: &HashSet<FileId>) {
+        self.current
+            .write()
+            .expect("structure cache lock poisoned")
+            .retain(|file_id, _| live.contains(file_id));
+    }
+
+    /// Number of distinct structures held, restored or computed
+    #[must_use]
+    pub fn len(&self) -> usize {
+        let restored = self.restored.read().expect("structure cache lock poisoned");
+        let current = self.current.read().expect("structure cache lock poisoned");
+        let computed: HashSet<&str> = current
+            .values()
+            .map(|(key, _)| key.as_str())
+            .filter(|key| !restored.contains_key(*key))
+            .collect();
+        restored.len() + computed.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        self.restored
+            .write()
+            .expect("structure cache lock poisoned")
+            .clear();
+        self.current
+            .write()
+            .expect("structure cache lock poisoned")
+            .clear();
+    }
+
+    /// Serialize the structures used this session, returning them with the
+    /// number of entries written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> serde_json::Result<(Vec<u8>, usize)> {
+        let entries: HashMap<String, FileStructureData> = self
+            .current
+            .read()
+            .expect("structure cache lock poisoned")
+            .values()
+            .map(|(key, structure)| (key.clone(), FileStructureData::clone(structure)))
+            .collect();
+        let len = entries.len();
+        let bytes = serde_json::to_vec(&CacheFile {
+            version: cache_version(),
+            entries,
+        })?;
+        Ok((bytes, len))
+    }
+
+    /// Restore a cache written by [`StructureCache::to_json`].
+    ///
+    /// Returns an empty cache when `bytes` can't be read or were written by a
+    /// different version of the analyzer.
+    #[must_use]
+    pub fn from_json(bytes: &[u8]) -> Self {
+        let Ok(file) = serde_json::from_slice::<CacheFile>(bytes) else {
+            return Self::new();
+        };
+        if file.version != cache_version() {
+            return Self::new();
+        }
+        Self {
+            restored: RwLock::new(
+                file.entries
+                    .into_iter()
+                    .map(|(key, structure)| (key, Arc::new(structure)))
+                    .collect(),
+            ),
+            current: RwLock::default(),
+        }
+    }
+}
+
+/// Structure extraction can change between releases without a format
+/// change, so the analyzer version is part of the cache version too
+fn cache_version() -> String {
+    format!("{FORMAT_VERSION}-{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Hash of everything a file's structure depends on: its text, language,
+/// whether nullability designators are parsed and, for embedded GraphQL, the
+/// extraction settings.
+pub(crate) fn cache_key(
+    db: &dyn crate::GraphQLHirDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+) -> String {
+    let language = metadata.language(db);
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{language:?}\0").as_bytes());
+    if db.client_controlled_nullability() {
+        hasher.update(b"client-controlled-nullability\0");
+    }
+    #[cfg(feature = "extract")]
+    if language.requires_extraction() {
+        // No config means the defaults, which always hash the same
+        let fingerprint = db
+            .extract_config()
+            .map(|config| config.fingerprint())
+            .unwrap_or_default();
+        hasher.update(&fingerprint);
+        hasher.update(b"\0");
+    }
+    hasher.update(content.text(db).as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn with_file_id(structure: &FileStructureData, file_id: FileId) -> FileStructureData {
+    let mut structure = structure.clone();
+    structure.file_id = file_id;
+    for type_def in Arc::make_mut(&mut structure.type_defs) {
+        type_def.file_id = file_id;
+        for field in &mut type_def.fields {
+            field.file_id = file_id;
+            for argument in &mut field.arguments {
+                argument.file_id = file_id;
+            }
+        }
+    }
+    for operation in Arc::make_mut(&mut structure.operations) {
+        operation.file_id = file_id;
+    }
+    for fragment in Arc::make_mut(&mut structure.fragments) {
+        fragment.file_id = file_id;
+    }
+    for directive in Arc::make_mut(&mut structure.directive_defs) {
+        directive.file_id = file_id;
+        for argument in &mut directive.arguments {
+            argument.file_id = file_id;
+        }
+    }
+    structure
+}
@@ -5,9 +5,9 @@
 
 use graphql_base_db::{DocumentKind, FileContent, FileId, FileMetadata, FileUri, Language};
 use graphql_hir::{
-    all_fragments, all_used_schema_coordinates, file_defined_fragment_names, file_operation_names,
-    file_schema_coordinates, file_structure, file_used_fragment_names, fragment_source,
-    schema_types, SchemaCoordinate,
+    all_fragments, all_used_schema_coordinates, file_defined_fragment_names, file_fragment_scope,
+    file_imports, file_operation_names, file_schema_coordinates, file_structure,
+    file_used_fragment_names, fragment_source, operation_by_name, schema_types, SchemaCoordinate,
 };
 use graphql_test_utils::{create_project_files, TestDatabase};
 use salsa::Setter;
@@ -188,6 +188,53 @@ fn test_fragment_source_granular_invalidation() {
     );
 }
 
+#[test]
+fn test_operation_by_name_follows_edits() {
+    let mut db = TestDatabase::default();
+
+    let file1_id = FileId::new(0);
+    let file1_content = FileContent::new(&db, Arc::from("query GetUser { user { id } }"));
+    let file1_metadata = FileMetadata::new(
+        &db,
+        file1_id,
+        FileUri::new("user.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+
+    let file2_id = FileId::new(1);
+    let file2_content = FileContent::new(
+        &db,
+        Arc::from("query GetPosts { posts { id } }\nmutation AddPost { addPost { id } }"),
+    );
+    let file2_metadata = FileMetadata::new(
+        &db,
+        file2_id,
+        FileUri::new("posts.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+
+    let doc_files = [
+        (file1_id, file1_content, file1_metadata),
+        (file2_id, file2_content, file2_metadata),
+    ];
+    let project_files = create_project_files(&mut db, &[], &doc_files);
+
+    let add_post = operation_by_name(&db, project_files, Arc::from("AddPost")).unwrap();
+    assert_eq!(add_post.file_id, file2_id);
+    assert_eq!(add_post.index, 1);
+    assert!(operation_by_name(&db, project_files, Arc::from("Missing")).is_none());
+
+    file1_content
+        .set_text(&mut db)
+        .to(Arc::from("query GetViewer { viewer { id } }"));
+
+    assert!(operation_by_name(&db, project_files, Arc::from("GetUser")).is_none());
+    let viewer = operation_by_name(&db, project_files, Arc::from("GetViewer")).unwrap();
+    assert_eq!(viewer.file_id, file1_id);
+}
+
 #[test]
 fn test_file_structure_finds_fragments_in_typescript() {
     let db = TestDatabase::default();
@@ -2086,3 +2133,90 @@ mod issue_648_find_references_prefiltering {
         );
     }
 }
+
+#[test]
+fn test_file_imports_parses_named_and_default_imports() {
+    let db = TestDatabase::default();
+    let source = "# import UserFields, PostFields from \"./fragments.graphql\"\n\
+                  #import '../shared.graphql'\n\
+                  #importFoo from \"./nope.graphql\"\n\
+                  query Q { ...UserFields }\n";
+    let content = FileContent::new(&db, Arc::from(source));
+    let metadata = FileMetadata::new(
+        &db,
+        FileId::new(0),
+        FileUri::new("file:///app/queries/q.graphql"),
+        Language::GraphQL,
+        DocumentKind::Executable,
+    );
+    let slice = |range: graphql_hir::TextRange| {
+        &source[usize::from(range.start())..usize::from(range.end())]
+    };
+
+    let imports = file_imports(&db, content, metadata);
+    assert_eq!(imports.len(), 2);
+    let names = imports[0].names.as_ref().unwrap();
+    assert_eq!(names.len(), 2);
+    assert_eq!(slice(names[1].range), "PostFields");
+    assert_eq!(slice(imports[0].path_range), "./fragments.graphql");
+    assert_eq!(
+        imports[0].uri.as_ref(),
+        "file:///app/queries/fragments.graphql"
+    );
+    assert!(imports[0].imports("UserFields"));
+    assert!(!imports[0].imports("Other"));
+
+    assert!(imports[1].names.is_none());
+    assert!(imports[1].imports("Anything"));
+    assert_eq!(imports[1].uri.as_ref(), "file:///app/shared.graphql");
+}
+
+#[test]
+fn test_file_fragment_scope_follows_imports() {
+    let mut db = TestDatabase::default();
+    let document = |db: &TestDatabase, id: u32, uri: &str, text: &str| {
+        let file_id = FileId::new(id);
+        let content = FileContent::new(db, Arc::from(text));
+        let metadata = FileMetadata::new(
+            db,
+            file_id,
+            FileUri::new(uri),
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+        (file_id, content, metadata)
+    };
+
+    let user = document(
+        &db,
+        0,
+        "file:///app/user.graphql",
+        "fragment UserFields on User { id }\nfragment Extra on User { name }",
+    );
+    let other = document(
+        &db,
+        1,
+        "file:///app/other/user.graphql",
+        "fragment UserFields on User { name }",
+    );
+    let scoped = document(
+        &db,
+        2,
+        "file:///app/query.graphql",
+        "# import UserFields from \"./user.graphql\"\nquery Q { user { ...UserFields } }\nfragment Local on User { id }",
+    );
+    let global = document(
+        &db,
+        3,
+        "file:///app/global.graphql",
+        "query G { user { id } }",
+    );
+    let project_files = create_project_files(&mut db, &[], &[user, other, scoped, global]);
+
+    let scope = file_fragment_scope(&db, scoped.0, scoped.1, scoped.2, project_files).unwrap();
+    assert_eq!(scope.get("UserFields"), Some(&user.0));
+    assert_eq!(scope.get("Local"), Some(&scoped.0));
+    assert!(!scope.contains_key("Extra"));
+
+    assert!(file_fragment_scope(&db, global.0, global.1, global.2, project_files).is_none());
+}
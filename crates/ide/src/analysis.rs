@@ -6,22 +6,31 @@ use std::sync::Arc;
 static CLONE_SNAPSHOT_ID: AtomicU64 = AtomicU64::new(1_000_000);
 
 use crate::database::IdeDatabase;
-use crate::db_files::DbFiles;
+use crate::db_files::{DbFiles, ResolvedFile};
+use crate::edit_merge::EditMerger;
 use crate::helpers;
-use crate::helpers::{adjust_range_for_line_offset, convert_diagnostic, offset_range_to_range};
+use crate::helpers::{adjust_range_for_block, convert_diagnostic, offset_range_to_range};
 use crate::symbol::{find_fragment_definition_full_range, find_operation_definition_ranges};
 use crate::types::{
-    CodeLens, CodeLensInfo, ComplexityAnalysis, Diagnostic, DocumentSymbol, FieldComplexity,
-    FieldCoverageReport, FieldUsageInfo, FilePath, FoldingRange, FragmentReference, FragmentUsage,
-    HoverResult, InlayHint, Location, OperationSummary, OperationVariableInfo, Position,
-    ProjectStatus, Range, RenameResult, SchemaStats, SchemaTypeEntry, SelectionRange,
-    SignatureHelp, TypeArgumentInfo, TypeDirectiveArgumentInfo, TypeDirectiveInfo,
-    TypeEnumValueInfo, TypeFieldInfo, TypeInfo, WorkspaceSymbol,
+    CodeLens, CodeLensInfo, ComplexityAnalysis, CursorContext, DeprecatedUsage, Diagnostic,
+    DiagnosticId, DocsOptions, DocumentHighlight, DocumentLink, DocumentSymbol, FieldComplexity,
+    FieldCoverageReport, FieldImpact, FieldImpactLensInfo, FieldUsageInfo, FilePath, FixAllResult,
+    FixPreview, FoldingRange, FragmentReference, FragmentUsage, HoverResult, InlayHint, Location,
+    MemoryMetrics, MigrationResult, NPlusOneSelection, OperationCopyLensInfo, OperationGraph,
+    OperationLocation, OperationSummary, OperationVariableInfo, OrganizeFragmentsResult, Position,
+    PositionContext, ProjectStatus, PrunedSchema, Range, RenameResult, SchemaChangeDiagnostics,
+    SchemaChangeFinding, SchemaChangeManifest, SchemaGroup, SchemaStats, SchemaTypeEntry,
+    SelectionRange, SelectionSkeleton, SignatureHelp, TextEdit, TypeArgumentInfo,
+    TypeDirectiveArgumentInfo, TypeDirectiveInfo, TypeEnumValueInfo, TypeFieldInfo,
+    TypeHierarchyItem, TypeInfo, TypeUsageLensInfo, UsageReportFormat, WorkspaceSymbol,
 };
 use crate::{
-    code_lenses, completion, folding_ranges, goto_definition, hover, inlay_hints, references,
-    rename, selection_range, semantic_tokens, signature_help, symbols, CompletionItem,
-    SemanticToken,
+    code_lenses, completion, cursor_context, deprecations, document_highlight, document_links,
+    field_migration, file_rename, folding_ranges, goto_definition, hover, impact, inlay_hints,
+    inline_fragment, migration, on_type_formatting, operation_graph, organize_fragments,
+    organize_schema, references, rename, schema_changes, schema_docs, schema_pruning,
+    selection_range, selection_skeleton, semantic_tokens, signature_help, symbols, type_hierarchy,
+    usage_report, CompletionItem, SemanticToken,
 };
 
 /// Immutable snapshot of the analysis state.
@@ -34,9 +43,12 @@ use crate::{
 /// # Lifecycle Warning
 ///
 /// This snapshot shares Salsa storage with its parent
-/// [`AnalysisHost`](crate::AnalysisHost). **You must drop all `Analysis`
-/// instances before calling any mutating method on the host.** Salsa setters
-/// block until all outstanding snapshots have been dropped.
+/// [`AnalysisHost`](crate::AnalysisHost). Salsa setters block until all
+/// outstanding snapshots have been dropped, so a snapshot never sees a
+/// mutation: one running on another thread unwinds with
+/// [`Cancelled`](crate::Cancelled) at its next query call and is dropped by
+/// its owner. **A snapshot held on the mutating thread itself can't unwind,
+/// so drop it before calling any mutating method on the host.**
 pub struct Analysis {
     pub(crate) db: IdeDatabase,
     /// Cached `ProjectFiles` snapshot, captured at the moment this `Analysis`
@@ -397,8 +409,23 @@ impl Analysis {
     /// Returns tokens for syntax highlighting with semantic information,
     /// including deprecation status for fields.
     pub fn semantic_tokens(&self, file: &FilePath) -> Vec<SemanticToken> {
+        if !self.analyzer_settings().features.semantic_tokens {
+            return Vec::new();
+        }
+        let registry = DbFiles::new(&self.db, self.project_files);
+        semantic_tokens::semantic_tokens(&self.db, registry, self.project_files, file, None)
+    }
+
+    /// Get semantic tokens for the lines covered by `range`
+    ///
+    /// Definitions entirely outside the range aren't walked, so editors can
+    /// highlight the visible part of a large schema file cheaply.
+    pub fn semantic_tokens_in_range(&self, file: &FilePath, range: Range) -> Vec<SemanticToken> {
+        if !self.analyzer_settings().features.semantic_tokens {
+            return Vec::new();
+        }
         let registry = DbFiles::new(&self.db, self.project_files);
-        semantic_tokens::semantic_tokens(&self.db, registry, self.project_files, file)
+        semantic_tokens::semantic_tokens(&self.db, registry, self.project_files, file, Some(range))
     }
 
     /// Get folding ranges for a file
@@ -413,6 +440,29 @@ impl Analysis {
         folding_ranges::folding_ranges(&self.db, registry, file)
     }
 
+    /// Get document links (fragment spreads and type references) for a file
+    pub fn document_links(&self, file: &FilePath) -> Vec<DocumentLink> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        document_links::document_links(&self.db, registry, self.project_files, file)
+    }
+
+    /// Get formatting edits after `ch` was typed at `position`
+    ///
+    /// Handles `{`, `}` and newlines; `indent` is one indentation level.
+    pub fn on_type_formatting(
+        &self,
+        file: &FilePath,
+        position: Position,
+        ch: char,
+        indent: &str,
+    ) -> Vec<TextEdit> {
+        if !self.analyzer_settings().features.on_type_formatting {
+            return Vec::new();
+        }
+        let registry = DbFiles::new(&self.db, self.project_files);
+        on_type_formatting::on_type_formatting(&self.db, registry, file, position, ch, indent)
+    }
+
     /// Get inlay hints for a file within an optional range.
     ///
     /// Returns inlay hints showing return types after scalar field selections.
@@ -420,8 +470,20 @@ impl Analysis {
     ///
     /// If `range` is provided, only returns hints within that range for efficiency.
     pub fn inlay_hints(&self, file: &FilePath, range: Option<Range>) -> Vec<InlayHint> {
+        let settings = self.analyzer_settings();
+        if !settings.features.inlay_hints {
+            return Vec::new();
+        }
         let registry = DbFiles::new(&self.db, self.project_files);
-        inlay_hints::inlay_hints(&self.db, registry, self.project_files, file, range)
+        inlay_hints::inlay_hints(
+            &self.db,
+            registry,
+            self.project_files,
+            file,
+            range,
+            &settings.inlay_hints,
+            &self.scalar_config(),
+        )
     }
 
     /// Get project-wide lint diagnostics (e.g., unused fields, unique names)
@@ -429,6 +491,9 @@ impl Analysis {
     /// Returns a map of file paths -> diagnostics for project-wide lint rules.
     /// These are expensive rules that analyze the entire project.
     pub fn project_lint_diagnostics(&self) -> HashMap<FilePath, Vec<Diagnostic>> {
+        if !self.analyzer_settings().features.project_lints {
+            return HashMap::new();
+        }
         let diagnostics_by_file_id = graphql_analysis::lint_integration::project_lint_diagnostics(
             &self.db,
             self.project_files,
@@ -564,6 +629,9 @@ impl Analysis {
     pub fn project_lint_diagnostics_with_fixes(
         &self,
     ) -> HashMap<FilePath, Vec<graphql_linter::LintDiagnostic>> {
+        if !self.analyzer_settings().features.project_lints {
+            return HashMap::new();
+        }
         let diagnostics_by_file_id =
             graphql_analysis::lint_integration::project_lint_diagnostics_with_fixes(
                 &self.db,
@@ -587,11 +655,11 @@ impl Analysis {
     /// Get the content of a file
     ///
     /// Returns the text content of the file if it exists in the registry.
-    pub fn file_content(&self, file: &FilePath) -> Option<String> {
+    pub fn file_content(&self, file: &FilePath) -> Option<Arc<str>> {
         let registry = DbFiles::new(&self.db, self.project_files);
         let file_id = registry.get_file_id(file)?;
         let content = registry.get_content(file_id)?;
-        Some(content.text(&self.db).to_string())
+        Some(content.text(&self.db))
     }
 
     /// Get the status of the project (file counts, schema loaded, etc.)
@@ -624,6 +692,27 @@ impl Analysis {
         ))
     }
 
+    /// Report how many operations select each object and interface field
+    ///
+    /// Meant for documentation tooling: the JSON form is keyed by
+    /// `Type.field` with usage counts and operation names, the Markdown form
+    /// is a table per type. `None` when no project is loaded.
+    pub fn usage_report(&self, format: UsageReportFormat) -> Option<String> {
+        let project_files = self.project_files?;
+        Some(usage_report::usage_report(&self.db, project_files, format))
+    }
+
+    /// Render reference documentation for the project schema
+    ///
+    /// Covers every type, field, argument and enum value with its
+    /// description, deprecation and applied directives, plus the custom
+    /// directive definitions. `None` when no project is loaded or the schema
+    /// only has built-in definitions.
+    pub fn generate_docs(&self, options: &DocsOptions) -> Option<String> {
+        let project_files = self.project_files?;
+        schema_docs::generate_docs(&self.db, project_files, options)
+    }
+
     /// Get field usage for a specific field
     ///
     /// Returns usage information for a field if it exists in the schema.
@@ -654,93 +743,192 @@ impl Analysis {
             return Vec::new();
         };
 
-        // Get all operations in the project
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let config = self.complexity_config();
         let operations = graphql_hir::all_operations(&self.db, project_files);
-        let schema_types = graphql_hir::schema_types(&self.db, project_files);
+        let files = registry.resolve_many(operations.iter().map(|op| op.file_id));
+        operations
+            .iter()
+            .filter_map(|operation| {
+                let file = files.get(&operation.file_id)?;
+                operation_complexity(&self.db, file, project_files, &config, operation)
+            })
+            .map(|analysis| self.with_enabled_warnings(analysis))
+            .collect()
+    }
 
-        let mut results = Vec::new();
+    /// Drop a complexity analysis's warnings when they're switched off
+    fn with_enabled_warnings(&self, mut analysis: ComplexityAnalysis) -> ComplexityAnalysis {
+        if !self.analyzer_settings().features.complexity_warnings {
+            analysis.warnings.clear();
+        }
+        analysis
+    }
 
-        for operation in operations.iter() {
-            // Get file information for this operation
-            let registry = DbFiles::new(&self.db, self.project_files);
-            let Some(file_path) = registry.get_path(operation.file_id) else {
-                continue;
-            };
-            let Some(content) = registry.get_content(operation.file_id) else {
-                continue;
-            };
-            let Some(metadata) = registry.get_metadata(operation.file_id) else {
-                continue;
-            };
+    fn complexity_config(&self) -> Arc<graphql_config::ComplexityConfig> {
+        self.db.complexity_config_input.map_or_else(
+            || Arc::new(graphql_config::ComplexityConfig::default()),
+            |input| input.config(&self.db).clone(),
+        )
+    }
 
-            // Get operation body
-            let body = graphql_hir::operation_body(&self.db, content, metadata, operation.index);
-
-            // Get operation location for the range
-            let range = if let Some(ref name) = operation.name {
-                let parse = graphql_syntax::parse(&self.db, content, metadata);
-                let mut found_range = None;
-                for doc in parse.documents() {
-                    if let Some(ranges) = find_operation_definition_ranges(doc.tree, name) {
-                        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-                        let doc_line_offset = doc.line_offset;
-                        found_range = Some(adjust_range_for_line_offset(
-                            offset_range_to_range(
-                                &doc_line_index,
-                                ranges.def_start,
-                                ranges.def_end,
-                            ),
-                            doc_line_offset,
-                        ));
-                        break;
-                    }
-                }
-                found_range.unwrap_or_else(|| Range::new(Position::new(0, 0), Position::new(0, 0)))
-            } else {
-                Range::new(Position::new(0, 0), Position::new(0, 0))
-            };
+    fn scalar_config(
+        &self,
+    ) -> Arc<std::collections::BTreeMap<String, graphql_config::ScalarConfig>> {
+        self.db.scalar_config_input.map_or_else(
+            || Arc::new(std::collections::BTreeMap::new()),
+            |input| input.scalars(&self.db).clone(),
+        )
+    }
 
-            // Create complexity analysis
-            let op_name = operation
-                .name
-                .as_ref()
-                .map_or_else(|| "<anonymous>".to_string(), ToString::to_string);
-
-            #[allow(clippy::match_same_arms)]
-            let op_type = match operation.operation_type {
-                graphql_hir::OperationType::Query => "query",
-                graphql_hir::OperationType::Mutation => "mutation",
-                graphql_hir::OperationType::Subscription => "subscription",
-                _ => "query", // fallback for future operation types
-            };
+    /// Editor settings from `.graphqlanalyzer.toml`, or the defaults
+    #[must_use]
+    pub fn analyzer_settings(&self) -> Arc<graphql_config::AnalyzerSettings> {
+        self.db.analyzer_settings_input.map_or_else(
+            || Arc::new(graphql_config::AnalyzerSettings::default()),
+            |input| input.settings(&self.db).clone(),
+        )
+    }
 
-            let mut analysis = ComplexityAnalysis::new(op_name, op_type, file_path, range);
+    /// Estimated memory held by parsed files, and the parse cache capacity
+    /// the `[memory]` settings produced
+    pub fn memory_metrics(&self) -> MemoryMetrics {
+        crate::memory::memory_metrics(
+            &self.db,
+            self.project_files,
+            self.analyzer_settings().memory,
+            self.db.parse_cache_capacity,
+        )
+    }
 
-            // Get the root type for this operation
-            #[allow(clippy::match_same_arms)]
-            let root_type_name = match operation.operation_type {
-                graphql_hir::OperationType::Query => "Query",
-                graphql_hir::OperationType::Mutation => "Mutation",
-                graphql_hir::OperationType::Subscription => "Subscription",
-                _ => "Query", // fallback for future operation types
-            };
+    /// Print an operation and its fragments as a client would send it
+    ///
+    /// `operation_name` picks the operation in `file`, or the first one when
+    /// `None`. With `inline_fragments`, spreads become inline fragments and
+    /// no fragment definitions are printed. Meant for comparing against the
+    /// body a persisted query was registered with.
+    pub fn print_operation(
+        &self,
+        file: &FilePath,
+        operation_name: Option<&str>,
+        style: graphql_analysis::OperationPrintStyle,
+        inline_fragments: bool,
+    ) -> Option<String> {
+        let project_files = self.project_files?;
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let file_id = registry.get_file_id(file)?;
+        let content = registry.get_content(file_id)?;
+        let metadata = registry.get_metadata(file_id)?;
+        graphql_analysis::print_operation(
+            &self.db,
+            content,
+            metadata,
+            project_files,
+            operation_name,
+            style,
+            inline_fragments,
+        )
+    }
 
-            // Analyze the operation body
-            analyze_selections(
-                &body.selections,
-                schema_types,
-                root_type_name,
-                "",
-                0,
-                1,
-                &mut analysis,
-                false,
-            );
+    /// Print an operation as a single definition with every fragment spread
+    /// inlined
+    ///
+    /// `operation_name` picks the operation in `file`, or the first one when
+    /// `None`. Spreads on the enclosing type are spliced into its selection
+    /// set and repeated fields are merged, for tooling that can't send
+    /// fragment definitions alongside the operation.
+    pub fn inline_fragments(
+        &self,
+        file: &FilePath,
+        operation_name: Option<&str>,
+    ) -> Option<String> {
+        let project_files = self.project_files?;
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let file_id = registry.get_file_id(file)?;
+        let content = registry.get_content(file_id)?;
+        let metadata = registry.get_metadata(file_id)?;
+        graphql_analysis::flatten_operation(
+            &self.db,
+            content,
+            metadata,
+            project_files,
+            operation_name,
+        )
+    }
 
-            results.push(analysis);
-        }
+    /// The edit that replaces the fragment spread at `position` with an
+    /// inline fragment holding the fragment's selections
+    ///
+    /// Returns `None` when the cursor isn't on a spread of a known
+    /// fragment; nothing is applied.
+    pub fn inline_fragment_spread(&self, file: &FilePath, position: Position) -> Option<TextEdit> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        inline_fragment::inline_fragment_spread(
+            &self.db,
+            registry,
+            self.project_files,
+            file,
+            position,
+        )
+    }
 
-        results
+    /// Every operation's persisted-query document, keyed by hash
+    ///
+    /// This is the allow-list format read from `persistedOperations`, so
+    /// writing it out regenerates the list from the current documents.
+    pub fn persisted_operation_manifest(&self) -> std::collections::BTreeMap<String, String> {
+        let Some(project_files) = self.project_files else {
+            return std::collections::BTreeMap::new();
+        };
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let operations = graphql_hir::all_operations(&self.db, project_files);
+        operations
+            .iter()
+            .filter_map(|operation| {
+                let content = registry.get_content(operation.file_id)?;
+                let metadata = registry.get_metadata(operation.file_id)?;
+                let document = graphql_analysis::persisted_query_document(
+                    &self.db,
+                    content,
+                    metadata,
+                    operation.index,
+                    project_files,
+                )?;
+                Some((
+                    graphql_analysis::persisted_query_hash(&document),
+                    document.to_string(),
+                ))
+            })
+            .collect()
+    }
+
+    /// Generate TypeScript result and variables types for each named
+    /// operation in a file
+    ///
+    /// Returns the types as text with the range of the operation they
+    /// belong to; writing them anywhere is up to the caller.
+    #[cfg(feature = "codegen")]
+    pub fn generate_operation_types(&self, file: &FilePath) -> Vec<crate::OperationTypes> {
+        let Some(project_files) = self.project_files else {
+            return Vec::new();
+        };
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let Some(file_id) = registry.get_file_id(file) else {
+            return Vec::new();
+        };
+        let (Some(content), Some(metadata)) = (
+            registry.get_content(file_id),
+            registry.get_metadata(file_id),
+        ) else {
+            return Vec::new();
+        };
+        crate::operation_types::generate_operation_types(
+            &self.db,
+            project_files,
+            file_id,
+            content,
+            metadata,
+        )
     }
 
     /// Get completions at a position
@@ -748,7 +936,81 @@ impl Analysis {
     /// Returns a list of completion items appropriate for the context.
     pub fn completions(&self, file: &FilePath, position: Position) -> Option<Vec<CompletionItem>> {
         let registry = DbFiles::new(&self.db, self.project_files);
-        completion::completions(&self.db, registry, self.project_files, file, position)
+        let scalars = self.scalar_config();
+        let settings = self.analyzer_settings();
+        completion::completions(
+            &self.db,
+            registry,
+            self.project_files,
+            &scalars,
+            &settings.completion,
+            file,
+            position,
+        )
+    }
+
+    /// Describe the analyzer's context at a position
+    ///
+    /// A debugging aid: reports the embedded block, enclosing definition,
+    /// resolved types and completion path at `position`.
+    pub fn cursor_context(&self, file: &FilePath, position: Position) -> Option<CursorContext> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        cursor_context::cursor_context(&self.db, registry, self.project_files, file, position)
+    }
+
+    /// Describe where `position` sits: enclosing definition, parent type,
+    /// field and argument list
+    ///
+    /// Returns `None` outside every GraphQL block.
+    pub fn context_at(&self, file: &FilePath, position: Position) -> Option<PositionContext> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        cursor_context::context_at(&self.db, registry, self.project_files, file, position)
+    }
+
+    /// Generate a selection set for the field at `position`
+    ///
+    /// Expands composite fields `depth` levels deep; `indent` is one
+    /// indentation level of the returned snippet.
+    pub fn selection_skeleton(
+        &self,
+        file: &FilePath,
+        position: Position,
+        depth: usize,
+        indent: &str,
+    ) -> Option<SelectionSkeleton> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        selection_skeleton::selection_skeleton(
+            &self.db,
+            registry,
+            self.project_files,
+            file,
+            position,
+            depth,
+            indent,
+        )
+    }
+
+    /// Fill the selection set around `position` with the fields of its
+    /// type it doesn't select yet
+    ///
+    /// `depth` and `indent` work as for [`Analysis::selection_skeleton`].
+    pub fn fill_selection_set(
+        &self,
+        file: &FilePath,
+        position: Position,
+        depth: usize,
+        indent: &str,
+    ) -> Option<TextEdit> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        selection_skeleton::fill_selection_set(
+            &self.db,
+            registry,
+            self.project_files,
+            file,
+            position,
+            depth,
+            indent,
+        )
     }
 
     /// Get hover information at a position
@@ -756,7 +1018,17 @@ impl Analysis {
     /// Returns documentation, type information, etc.
     pub fn hover(&self, file: &FilePath, position: Position) -> Option<HoverResult> {
         let registry = DbFiles::new(&self.db, self.project_files);
-        hover::hover(&self.db, registry, self.project_files, file, position)
+        let config = self.complexity_config();
+        let scalars = self.scalar_config();
+        hover::hover(
+            &self.db,
+            registry,
+            self.project_files,
+            &config,
+            &scalars,
+            file,
+            position,
+        )
     }
 
     /// Get signature help at a position
@@ -775,6 +1047,35 @@ impl Analysis {
         goto_definition::goto_definition(&self.db, registry, self.project_files, file, position)
     }
 
+    /// Resolve the object, interface or union type at a position as the
+    /// root of a type hierarchy
+    pub fn prepare_type_hierarchy(
+        &self,
+        file: &FilePath,
+        position: Position,
+    ) -> Option<Vec<TypeHierarchyItem>> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        type_hierarchy::prepare_type_hierarchy(
+            &self.db,
+            registry,
+            self.project_files,
+            file,
+            position,
+        )
+    }
+
+    /// Get the interfaces a type implements and the unions it belongs to
+    pub fn type_hierarchy_supertypes(&self, type_name: &str) -> Vec<TypeHierarchyItem> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        type_hierarchy::supertypes(&self.db, registry, self.project_files, type_name)
+    }
+
+    /// Get an interface's implementors or a union's members
+    pub fn type_hierarchy_subtypes(&self, type_name: &str) -> Vec<TypeHierarchyItem> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        type_hierarchy::subtypes(&self.db, registry, self.project_files, type_name)
+    }
+
     /// Find all references to the symbol at a position
     ///
     /// Returns locations of all usages of types, fields, fragments, etc.
@@ -819,6 +1120,127 @@ impl Analysis {
         )
     }
 
+    /// The `# import` path edits that keep imports resolving to the same
+    /// files when each `from` file moves to `to`.
+    ///
+    /// Edits are keyed by the files' current paths; nothing is applied.
+    pub fn import_edits_for_renames(
+        &self,
+        renames: &[(FilePath, FilePath)],
+    ) -> HashMap<FilePath, Vec<TextEdit>> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        file_rename::import_edits_for_renames(&self.db, registry, self.project_files, renames)
+    }
+
+    /// Rewrite every project document across a set of schema renames.
+    ///
+    /// Returns the edits per file plus the usages that need a manual
+    /// follow-up; nothing is applied.
+    pub fn migrate_documents(&self, manifest: &SchemaChangeManifest) -> MigrationResult {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        migration::migrate_documents(&self.db, registry, self.project_files, manifest)
+    }
+
+    /// Move every selection of `type_name.old_field` to `new_field`, a
+    /// replacement field on the same type.
+    ///
+    /// Returns the edits per file plus the usages the move would change the
+    /// meaning of, which are left alone; nothing is applied. `None` when
+    /// either field isn't defined on the type.
+    pub fn migrate_field(
+        &self,
+        type_name: &str,
+        old_field: &str,
+        new_field: &str,
+    ) -> Option<MigrationResult> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        field_migration::migrate_field(
+            &self.db,
+            registry,
+            self.project_files,
+            type_name,
+            old_field,
+            new_field,
+        )
+    }
+
+    /// Hoist selection sets duplicated across project documents into
+    /// fragments in `fragment_file`.
+    ///
+    /// Returns the edits per file; nothing is applied.
+    pub fn organize_fragments(&self, fragment_file: &FilePath) -> OrganizeFragmentsResult {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        organize_fragments::organize_fragments(
+            &self.db,
+            registry,
+            self.project_files,
+            fragment_file,
+        )
+    }
+
+    /// Sort and deduplicate the definitions and fields of a schema file,
+    /// grouping definitions by kind in the order of `groups`.
+    ///
+    /// Returns a single edit replacing the whole file, or none when it's
+    /// already organized or can't be organized safely.
+    pub fn organize_schema(&self, file: &FilePath, groups: &[SchemaGroup]) -> Vec<TextEdit> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        organize_schema::organize_schema(&self.db, registry, file, groups)
+    }
+
+    /// The edits the fix of diagnostic `id` would make.
+    ///
+    /// Returns `None` if the diagnostic no longer exists or has no fix;
+    /// nothing is applied.
+    pub fn preview_fix(&self, id: &DiagnosticId) -> Option<FixPreview> {
+        let fix = self
+            .all_diagnostics_for_file(&id.file)
+            .into_iter()
+            .find(|d| d.code.as_deref() == Some(id.code.as_str()) && d.range == id.range)?
+            .fix?;
+        Some(FixPreview {
+            label: fix.label,
+            changes: HashMap::from([(id.file.clone(), fix.edits)]),
+        })
+    }
+
+    /// Merge the fixes of every `rule_code` diagnostic in the project.
+    ///
+    /// Fixes are taken in file and position order; one that overlaps a fix
+    /// already taken is skipped and reported. Nothing is applied.
+    pub fn fix_all(&self, rule_code: &str) -> FixAllResult {
+        let mut files: Vec<_> = self.all_diagnostics().into_iter().collect();
+        files.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+        let mut merger = EditMerger::new();
+        let mut applied = 0;
+        let mut skipped = Vec::new();
+        for (file, mut diagnostics) in files {
+            diagnostics.retain(|d| d.fix.is_some() && d.code.as_deref() == Some(rule_code));
+            diagnostics.sort_by_key(|d| (d.range.start.line, d.range.start.character));
+            for diagnostic in diagnostics {
+                let Some(fix) = diagnostic.fix else {
+                    continue;
+                };
+                if merger.try_add(&file, &fix.edits) {
+                    applied += 1;
+                } else {
+                    skipped.push(DiagnosticId {
+                        file: file.clone(),
+                        code: rule_code.to_string(),
+                        range: diagnostic.range,
+                    });
+                }
+            }
+        }
+
+        FixAllResult {
+            changes: merger.into_changes(),
+            applied,
+            skipped,
+        }
+    }
+
     /// Find all references to a fragment
     pub fn find_fragment_references(
         &self,
@@ -850,6 +1272,68 @@ impl Analysis {
         selection_range::selection_ranges(&self.db, registry, file, positions)
     }
 
+    /// Occurrences of the symbol at a position in the same file
+    ///
+    /// Covers variables, fragment names and fields sharing a response key.
+    /// Works on the file's syntax alone, so it stays cheap enough to run
+    /// whenever the cursor moves.
+    pub fn document_highlights(
+        &self,
+        file: &FilePath,
+        position: Position,
+    ) -> Vec<DocumentHighlight> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        document_highlight::document_highlights(&self.db, registry, file, position)
+    }
+
+    /// Every usage of a deprecated field, argument, enum value or input
+    /// object field across the project's documents, with its deprecation
+    /// reason.
+    ///
+    /// Independent of the lint config; the `noDeprecated` rule reports the
+    /// same usages as per-file diagnostics.
+    pub fn deprecated_usages(&self) -> Vec<DeprecatedUsage> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        deprecations::deprecated_usages(&self.db, registry, self.project_files)
+    }
+
+    /// Every N+1-prone selection in the project's documents, ordered by
+    /// file and position: unpaginated lists selected inside other lists.
+    ///
+    /// Empty unless the project configures `nPlusOne`. The same selections
+    /// are reported as informational diagnostics in each file.
+    pub fn n_plus_one_selections(&self) -> Vec<NPlusOneSelection> {
+        let Some(project_files) = self.project_files else {
+            return Vec::new();
+        };
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let doc_ids = project_files.document_file_ids(&self.db).ids(&self.db);
+        let mut files: Vec<_> = registry
+            .resolve_many(doc_ids.iter().copied())
+            .into_values()
+            .collect();
+        files.sort_by(|a, b| a.path.as_str().cmp(b.path.as_str()));
+
+        let mut selections = Vec::new();
+        for file in files {
+            let findings = graphql_analysis::n_plus_one_findings(
+                &self.db,
+                file.content,
+                file.metadata,
+                project_files,
+            );
+            selections.extend(findings.iter().map(|finding| NPlusOneSelection {
+                location: Location::new(file.path.clone(), helpers::convert_range(finding.range)),
+                definition: finding.definition.as_deref().map(str::to_string),
+                list_path: finding.list_path.to_string(),
+                nested_path: finding.nested_path.to_string(),
+                nested_type: finding.nested_type.to_string(),
+                fragment: finding.fragment.as_deref().map(str::to_string),
+            }));
+        }
+        selections
+    }
+
     /// Get code lenses for deprecated fields in a schema file
     ///
     /// Returns code lens information for each deprecated field definition,
@@ -859,6 +1343,76 @@ impl Analysis {
         code_lenses::deprecated_field_code_lenses(&self.db, registry, self.project_files, file)
     }
 
+    /// Get code lenses for the object and interface types in a schema file
+    ///
+    /// Each lens counts the operations selecting at least one of the type's
+    /// fields, with their locations for navigation.
+    pub fn type_usage_code_lenses(&self, file: &FilePath) -> Vec<TypeUsageLensInfo> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        code_lenses::type_usage_code_lenses(&self.db, registry, self.project_files, file)
+    }
+
+    /// Get code lenses for the fields of the object and interface types in a
+    /// schema file
+    ///
+    /// Each lens counts the operations that depend on the field, with their
+    /// locations for navigation.
+    pub fn field_impact_code_lenses(&self, file: &FilePath) -> Vec<FieldImpactLensInfo> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        code_lenses::field_impact_code_lenses(&self.db, registry, self.project_files, file)
+    }
+
+    /// The operations that would break if `type_name.field_name` were
+    /// removed or its type changed
+    ///
+    /// These are the operations selecting the field on that type, directly
+    /// or through fragments. Returns `None` when the type has no such field.
+    pub fn impact_of_change(&self, type_name: &str, field_name: &str) -> Option<FieldImpact> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        impact::impact_of_change(
+            &self.db,
+            registry,
+            self.project_files,
+            type_name,
+            field_name,
+        )
+    }
+
+    /// Get code lenses for copying each operation in a file along with the
+    /// fragments it spreads
+    pub fn operation_copy_code_lenses(&self, file: &FilePath) -> Vec<OperationCopyLensInfo> {
+        let registry = DbFiles::new(&self.db, self.project_files);
+        code_lenses::operation_copy_code_lenses(&self.db, registry, self.project_files, file)
+    }
+
+    /// The source of an operation followed by every fragment it spreads,
+    /// directly or through other fragments
+    ///
+    /// The result is a self-contained document, printed the same way as a
+    /// persisted query, that can be pasted into an in-browser GraphQL IDE.
+    /// `operation_index` numbers the operations in the file, as in
+    /// [`OperationCopyLensInfo`]. Returns `None` when the file has no such
+    /// operation.
+    pub fn operation_with_fragments(
+        &self,
+        file: &FilePath,
+        operation_index: usize,
+    ) -> Option<String> {
+        let project_files = self.project_files?;
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let file_id = registry.get_file_id(file)?;
+        let content = registry.get_content(file_id)?;
+        let metadata = registry.get_metadata(file_id)?;
+        let document = graphql_analysis::persisted_query_document(
+            &self.db,
+            content,
+            metadata,
+            operation_index,
+            project_files,
+        )?;
+        Some(document.to_string())
+    }
+
     /// Get document symbols for a file (hierarchical outline)
     ///
     /// Returns types, operations, and fragments with their fields as children.
@@ -946,11 +1500,16 @@ impl Analysis {
         };
 
         let fragments = graphql_hir::all_fragments(&self.db, project_files);
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let files = registry.resolve_many(fragments.values().map(|f| f.file_id));
         let mut results = Vec::new();
 
         for (name, fragment) in fragments {
             // Get definition location
-            let Some((def_file, def_range)) = self.get_fragment_def_info(fragment) else {
+            let Some((def_file, def_range)) = files
+                .get(&fragment.file_id)
+                .and_then(|file| self.get_fragment_def_info(file, fragment))
+            else {
                 continue;
             };
 
@@ -981,23 +1540,19 @@ impl Analysis {
     /// Get fragment definition file and range
     fn get_fragment_def_info(
         &self,
+        file: &ResolvedFile,
         fragment: &graphql_hir::FragmentStructure,
     ) -> Option<(FilePath, Range)> {
-        let registry = DbFiles::new(&self.db, self.project_files);
-        let file_path = registry.get_path(fragment.file_id)?;
-        let content = registry.get_content(fragment.file_id)?;
-        let metadata = registry.get_metadata(fragment.file_id)?;
-
-        let parse = graphql_syntax::parse(&self.db, content, metadata);
+        let parse = graphql_syntax::parse(&self.db, file.content, file.metadata);
 
         for doc in parse.documents() {
             if let Some(ranges) = find_fragment_definition_full_range(doc.tree, &fragment.name) {
-                let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-                let range = adjust_range_for_line_offset(
+                let doc_line_index = doc.line_index();
+                let range = adjust_range_for_block(
                     offset_range_to_range(&doc_line_index, ranges.name_start, ranges.name_end),
-                    doc.line_offset,
+                    doc.origin(),
                 );
-                return Some((file_path, range));
+                return Some((file.path.clone(), range));
             }
         }
 
@@ -1050,6 +1605,47 @@ impl Analysis {
         f(types)
     }
 
+    /// Compute the schema subset reachable from a set of operations, as SDL
+    ///
+    /// Pass operation names to scope the subset, or `None` for every
+    /// operation in the project. Useful for reduced schemas in tests and
+    /// mocks, or for reviewing exactly what a client can reach. Returns
+    /// `None` when no schema is loaded.
+    pub fn pruned_schema(&self, operation_names: Option<&[String]>) -> Option<PrunedSchema> {
+        let project_files = self.project_files?;
+        schema_pruning::prune_schema(&self.db, project_files, operation_names)
+    }
+
+    /// Build the graph of operations, the fragments they spread and the
+    /// schema types they touch
+    ///
+    /// Export it with [`OperationGraph::to_dot`] or [`OperationGraph::to_json`]
+    /// to see which operations a schema change can reach.
+    pub fn operation_graph(&self) -> OperationGraph {
+        let Some(project_files) = self.project_files else {
+            return OperationGraph::default();
+        };
+        operation_graph::operation_graph(&self.db, project_files)
+    }
+
+    /// Place schema registry findings on the schema definitions they concern
+    ///
+    /// Findings are matched by schema coordinate; see
+    /// [`SchemaChangeDiagnostics::unlocated`] for those that can't be.
+    pub fn schema_change_diagnostics(
+        &self,
+        findings: &[SchemaChangeFinding],
+    ) -> SchemaChangeDiagnostics {
+        let Some(project_files) = self.project_files else {
+            return SchemaChangeDiagnostics {
+                diagnostics: HashMap::new(),
+                unlocated: findings.to_vec(),
+            };
+        };
+        let registry = DbFiles::new(&self.db, self.project_files);
+        schema_changes::schema_change_diagnostics(&self.db, registry, project_files, findings)
+    }
+
     /// List all schema types with lightweight metadata
     pub fn schema_type_list(
         &self,
@@ -1167,67 +1763,109 @@ impl Analysis {
 
         let operations = graphql_hir::all_operations(&self.db, project_files);
         let registry = DbFiles::new(&self.db, self.project_files);
+        let files = registry.resolve_many(operations.iter().map(|op| op.file_id));
 
-        let mut results = Vec::new();
-        for op in operations.iter() {
-            let Some(file_path) = registry.get_path(op.file_id) else {
-                continue;
-            };
-
-            if let Some(filter) = file_filter {
-                if file_path.as_str() != filter.as_str() {
-                    continue;
+        operations
+            .iter()
+            .filter_map(|op| {
+                let file = files.get(&op.file_id)?;
+                if let Some(filter) = file_filter {
+                    if file.path.as_str() != filter.as_str() {
+                        return None;
+                    }
                 }
-            }
+                Some(self.operation_summary(file, op))
+            })
+            .collect()
+    }
 
-            let Some(content) = registry.get_content(op.file_id) else {
-                continue;
-            };
-            let Some(metadata) = registry.get_metadata(op.file_id) else {
-                continue;
-            };
+    /// Find the operation named `name` anywhere in the project
+    ///
+    /// Resolves through the operation name index, so only the file defining
+    /// the operation is read. With duplicate names, the operation from the
+    /// first document file is returned.
+    pub fn find_operation(&self, name: &str) -> Option<OperationLocation> {
+        let project_files = self.project_files?;
+        let operation = graphql_hir::operation_by_name(&self.db, project_files, Arc::from(name))?;
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let file = registry.resolve(operation.file_id)?;
 
-            // Get fragment dependencies from the operation body
-            let body = graphql_hir::operation_body(&self.db, content, metadata, op.index);
-            let mut fragment_deps: Vec<String> = body
-                .fragment_spreads
-                .iter()
-                .map(ToString::to_string)
-                .collect();
-            fragment_deps.sort();
-
-            #[allow(clippy::match_same_arms)]
-            let op_type = match op.operation_type {
-                graphql_hir::OperationType::Query => "query",
-                graphql_hir::OperationType::Mutation => "mutation",
-                graphql_hir::OperationType::Subscription => "subscription",
-                _ => "query",
-            };
+        let parse = graphql_syntax::parse(&self.db, file.content, file.metadata);
+        let range = parse.documents().find_map(|doc| {
+            helpers::find_operation_definition_in_tree(
+                doc.tree,
+                name,
+                &doc.line_index(),
+                doc.origin(),
+            )
+        })?;
 
-            results.push(OperationSummary {
-                name: op.name.as_ref().map(ToString::to_string),
-                operation_type: op_type.to_string(),
-                file: file_path,
-                variables: op
-                    .variables
-                    .iter()
-                    .map(|v| OperationVariableInfo {
-                        name: v.name.to_string(),
-                        type_ref: helpers::format_type_ref(&v.type_ref),
-                        default_value: v.default_value.as_ref().map(ToString::to_string),
-                    })
-                    .collect(),
-                fragment_dependencies: fragment_deps,
-            });
-        }
+        Some(OperationLocation {
+            location: Location::new(file.path.clone(), range),
+            summary: self.operation_summary(&file, &operation),
+        })
+    }
 
-        results
+    /// Complexity analysis for the operation named `name`
+    ///
+    /// Like [`Analysis::find_operation`], this doesn't analyze any other
+    /// operation.
+    pub fn complexity_analysis_for(&self, name: &str) -> Option<ComplexityAnalysis> {
+        let project_files = self.project_files?;
+        let operation = graphql_hir::operation_by_name(&self.db, project_files, Arc::from(name))?;
+        let registry = DbFiles::new(&self.db, self.project_files);
+        let file = registry.resolve(operation.file_id)?;
+        let config = self.complexity_config();
+        operation_complexity(&self.db, &file, project_files, &config, &operation)
+            .map(|analysis| self.with_enabled_warnings(analysis))
+    }
+
+    fn operation_summary(
+        &self,
+        file: &ResolvedFile,
+        op: &graphql_hir::OperationStructure,
+    ) -> OperationSummary {
+        // Get fragment dependencies from the operation body
+        let body = graphql_hir::operation_body(&self.db, file.content, file.metadata, op.index);
+        let mut fragment_deps: Vec<String> = body
+            .fragment_spreads
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        fragment_deps.sort();
+
+        #[allow(clippy::match_same_arms)]
+        let op_type = match op.operation_type {
+            graphql_hir::OperationType::Query => "query",
+            graphql_hir::OperationType::Mutation => "mutation",
+            graphql_hir::OperationType::Subscription => "subscription",
+            _ => "query",
+        };
+
+        OperationSummary {
+            name: op.name.as_ref().map(ToString::to_string),
+            operation_type: op_type.to_string(),
+            file: file.path.clone(),
+            variables: op
+                .variables
+                .iter()
+                .map(|v| OperationVariableInfo {
+                    name: v.name.to_string(),
+                    type_ref: helpers::format_type_ref(&v.type_ref),
+                    default_value: v.default_value.as_ref().map(ToString::to_string),
+                })
+                .collect(),
+            fragment_dependencies: fragment_deps,
+        }
     }
 
     /// Get code lenses for a file
     ///
     /// Returns code lenses for fragment definitions showing reference counts.
     pub fn code_lenses(&self, file: &FilePath) -> Vec<CodeLens> {
+        if !self.analyzer_settings().features.code_lens {
+            return Vec::new();
+        }
         let fragment_usages = self.fragment_usages();
         let registry = DbFiles::new(&self.db, self.project_files);
         code_lenses::code_lenses(
@@ -1242,17 +1880,93 @@ impl Analysis {
 
 // Private helper functions for complexity analysis
 
+/// Complexity analysis for a single operation
+pub(crate) fn operation_complexity(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    file: &ResolvedFile,
+    project_files: graphql_base_db::ProjectFiles,
+    config: &graphql_config::ComplexityConfig,
+    operation: &graphql_hir::OperationStructure,
+) -> Option<ComplexityAnalysis> {
+    let schema_types = graphql_hir::schema_types(db, project_files);
+    let file_path = file.path.clone();
+    let content = file.content;
+    let metadata = file.metadata;
+
+    // Get operation body
+    let body = graphql_hir::operation_body(db, content, metadata, operation.index);
+
+    // Get operation location for the range
+    let range = if let Some(ref name) = operation.name {
+        let parse = graphql_syntax::parse(db, content, metadata);
+        let mut found_range = None;
+        for doc in parse.documents() {
+            if let Some(ranges) = find_operation_definition_ranges(doc.tree, name) {
+                let doc_line_index = doc.line_index();
+                found_range = Some(adjust_range_for_block(
+                    offset_range_to_range(&doc_line_index, ranges.def_start, ranges.def_end),
+                    doc.origin(),
+                ));
+                break;
+            }
+        }
+        found_range.unwrap_or_else(|| Range::new(Position::new(0, 0), Position::new(0, 0)))
+    } else {
+        Range::new(Position::new(0, 0), Position::new(0, 0))
+    };
+
+    // Create complexity analysis
+    let op_name = operation
+        .name
+        .as_ref()
+        .map_or_else(|| "<anonymous>".to_string(), ToString::to_string);
+
+    #[allow(clippy::match_same_arms)]
+    let op_type = match operation.operation_type {
+        graphql_hir::OperationType::Query => "query",
+        graphql_hir::OperationType::Mutation => "mutation",
+        graphql_hir::OperationType::Subscription => "subscription",
+        _ => "query", // fallback for future operation types
+    };
+
+    let mut analysis = ComplexityAnalysis::new(op_name, op_type, file_path, range);
+
+    let root_type_name =
+        graphql_apollo_ext::OperationType::from(operation.operation_type).root_type_name();
+
+    // Analyze the operation body
+    analyze_selections(
+        &body.selections,
+        schema_types,
+        config,
+        root_type_name,
+        "",
+        0,
+        1,
+        &mut analysis,
+        false,
+        None,
+    );
+
+    Some(analysis)
+}
+
 /// Analyze selections recursively to calculate complexity
+///
+/// `page_size` is the pagination argument of the enclosing connection field,
+/// which sizes its `edges`/`nodes` lists.
 #[allow(clippy::too_many_arguments)]
 fn analyze_selections(
     selections: &[graphql_hir::Selection],
     schema_types: &std::collections::HashMap<Arc<str>, graphql_hir::TypeDef>,
+    config: &graphql_config::ComplexityConfig,
     parent_type_name: &str,
     path_prefix: &str,
     depth: u32,
     multiplier: u32,
     analysis: &mut ComplexityAnalysis,
     in_connection: bool,
+    page_size: Option<u32>,
 ) {
     // Update max depth
     if depth > analysis.depth {
@@ -1263,6 +1977,7 @@ fn analyze_selections(
         match selection {
             graphql_hir::Selection::Field {
                 name,
+                arguments,
                 selection_set,
                 ..
             } => {
@@ -1277,12 +1992,15 @@ fn analyze_selections(
                 let (is_list, inner_type_name) =
                     get_type_info(schema_types, parent_type_name, &field_name);
 
-                // Calculate field multiplier
-                let field_multiplier = if is_list {
-                    multiplier * 10 // Default list multiplier
+                // A list's size comes from its own pagination argument, then
+                // from the connection it belongs to, then the configured default
+                let pagination = pagination_size(arguments, config);
+                let list_size = if is_list {
+                    pagination.or(page_size).unwrap_or(config.default_list_size)
                 } else {
-                    multiplier
+                    1
                 };
+                let field_multiplier = multiplier.saturating_mul(list_size);
 
                 // Check for connection pattern
                 let field_is_connection =
@@ -1296,12 +2014,19 @@ fn analyze_selections(
                 }
 
                 // Calculate complexity for this field
-                let field_complexity = field_multiplier;
-                analysis.total_complexity += field_complexity;
+                let cost = config
+                    .field_costs
+                    .get(&format!("{parent_type_name}.{field_name}"))
+                    .or_else(|| config.type_costs.get(&inner_type_name))
+                    .copied()
+                    .unwrap_or(1);
+                let field_complexity = cost.saturating_mul(field_multiplier);
+                analysis.total_complexity =
+                    analysis.total_complexity.saturating_add(field_complexity);
 
                 // Add to breakdown
                 let mut fc = FieldComplexity::new(&path, &field_name, field_complexity)
-                    .with_multiplier(if is_list { 10 } else { 1 })
+                    .with_multiplier(list_size)
                     .with_depth(depth)
                     .with_connection(field_is_connection);
 
@@ -1316,12 +2041,18 @@ fn analyze_selections(
                     analyze_selections(
                         selection_set,
                         schema_types,
+                        config,
                         &inner_type_name,
                         &path,
                         depth + 1,
                         field_multiplier,
                         analysis,
                         field_is_connection || in_connection,
+                        if field_is_connection {
+                            pagination
+                        } else {
+                            None
+                        },
                     );
                 }
             }
@@ -1334,6 +2065,23 @@ fn analyze_selections(
     }
 }
 
+/// The list size requested by a literal pagination argument (`first: 50`).
+/// Variables can't be resolved statically, so they fall back to the default.
+fn pagination_size(
+    arguments: &[(Arc<str>, Arc<str>)],
+    config: &graphql_config::ComplexityConfig,
+) -> Option<u32> {
+    arguments
+        .iter()
+        .filter(|(name, _)| {
+            config
+                .pagination_arguments
+                .iter()
+                .any(|arg| **arg == **name)
+        })
+        .find_map(|(_, value)| value.parse().ok())
+}
+
 /// Check if a field follows the Relay connection pattern (edges/nodes/pageInfo)
 fn is_connection_pattern(
     _field_name: &str,
@@ -3,16 +3,31 @@
 //! This module provides IDE code lens functionality:
 //! - Fragment reference counts
 //! - Deprecated field usage counts
+//! - Operations selecting each schema type
+//! - Operations depending on each schema field
+//! - Copying an operation together with the fragments it needs
+//! - Recorded calls per operation, and per deprecated field, when an
+//!   operation usage report is loaded
 
-use crate::helpers::{adjust_range_for_line_offset, offset_range_to_range};
+use std::collections::HashMap;
+
+use apollo_parser::cst::{self, CstNode};
+
+use crate::helpers::{adjust_range_for_block, offset_range_to_range};
+use crate::impact::{for_each_operation, operations_by_field};
 use crate::references::find_field_references;
 use crate::symbol::find_fragment_definition_full_range;
-use crate::types::{CodeLens, CodeLensCommand, CodeLensInfo, FilePath, FragmentUsage};
+use crate::types::{
+    CodeLens, CodeLensCommand, CodeLensInfo, FieldImpact, FieldImpactLensInfo, FilePath,
+    FragmentUsage, Location, OperationCopyLensInfo, RecordedCalls, TypeUsageLensInfo,
+};
 use crate::DbFiles;
 
 /// Get code lenses for a file.
 ///
-/// Returns code lenses for fragment definitions showing reference counts.
+/// Returns code lenses for fragment definitions showing reference counts,
+/// and for operations showing their recorded calls when an operation usage
+/// report is loaded.
 pub fn code_lenses(
     db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
     registry: DbFiles<'_>,
@@ -52,10 +67,10 @@ pub fn code_lenses(
 
         for doc in parse.documents() {
             if let Some(ranges) = find_fragment_definition_full_range(doc.tree, &fragment.name) {
-                let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-                let range = adjust_range_for_line_offset(
+                let doc_line_index = doc.line_index();
+                let range = adjust_range_for_block(
                     offset_range_to_range(&doc_line_index, ranges.def_start, ranges.def_start),
-                    doc.line_offset,
+                    doc.origin(),
                 );
 
                 let title = if usage_count == 1 {
@@ -77,6 +92,30 @@ pub fn code_lenses(
         }
     }
 
+    // Operations are numbered across the file's documents, the same way
+    // `operation_calls` indexes them
+    let mut operation_index = 0;
+    for doc in parse.documents() {
+        let doc_line_index = doc.line_index();
+        for definition in doc.tree.document().definitions() {
+            let cst::Definition::OperationDefinition(operation) = definition else {
+                continue;
+            };
+            let calls = db.operation_calls(file_id, operation_index);
+            operation_index += 1;
+            let Some(calls) = calls else {
+                continue;
+            };
+
+            let start: usize = operation.syntax().text_range().start().into();
+            let range = adjust_range_for_block(
+                offset_range_to_range(&doc_line_index, start, start),
+                doc.origin(),
+            );
+            lenses.push(CodeLens::new(range, RecordedCalls::from(calls).to_string()));
+        }
+    }
+
     tracing::debug!(lens_count = lenses.len(), "code_lenses: returning");
     lenses
 }
@@ -113,6 +152,7 @@ pub fn deprecated_field_code_lenses(
     };
 
     let line_index = graphql_syntax::line_index(db, content);
+    let mut field_calls = None;
 
     for type_def in schema_types.values() {
         for field in &type_def.fields {
@@ -149,6 +189,18 @@ pub fn deprecated_field_code_lenses(
                 code_lens = code_lens.with_deprecation_reason(reason.as_ref());
             }
 
+            let calls = field_calls.get_or_insert_with(|| calls_by_field(db, project_files));
+            if let Some((window_days, counts)) = calls {
+                let coordinate = graphql_hir::SchemaCoordinate {
+                    type_name: type_def.name.clone(),
+                    field_name: field.name.clone(),
+                };
+                code_lens = code_lens.with_recorded_calls(RecordedCalls {
+                    count: counts.get(&coordinate).copied().unwrap_or(0),
+                    window_days: *window_days,
+                });
+            }
+
             code_lenses.push(code_lens);
         }
     }
@@ -156,6 +208,229 @@ pub fn deprecated_field_code_lenses(
     code_lenses
 }
 
+/// Get code lenses for the object and interface type definitions in a
+/// schema file.
+///
+/// Each lens counts the operations that select at least one field of the
+/// type, directly or through fragments, with their locations for navigation.
+pub fn type_usage_code_lenses(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+) -> Vec<TypeUsageLensInfo> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let Some(content) = registry.get_content(file_id) else {
+        return Vec::new();
+    };
+
+    let schema_types = graphql_hir::schema_types(db, project_files);
+    let mut type_defs: Vec<&graphql_hir::TypeDef> = schema_types
+        .values()
+        .filter(|type_def| {
+            type_def.file_id == file_id
+                && matches!(
+                    type_def.kind,
+                    graphql_hir::TypeDefKind::Object | graphql_hir::TypeDefKind::Interface
+                )
+        })
+        .collect();
+    if type_defs.is_empty() {
+        return Vec::new();
+    }
+    type_defs.sort_by_key(|type_def| type_def.name_range.start());
+
+    let operations = operations_by_type(db, registry, project_files);
+    let line_index = graphql_syntax::line_index(db, content);
+    type_defs
+        .into_iter()
+        .map(|type_def| {
+            let range = offset_range_to_range(
+                &line_index,
+                type_def.name_range.start().into(),
+                type_def.name_range.end().into(),
+            );
+            TypeUsageLensInfo {
+                range,
+                type_name: type_def.name.to_string(),
+                operation_locations: operations.get(&type_def.name).cloned().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+/// Get code lenses for the fields of the object and interface types in a
+/// schema file.
+///
+/// Each lens lists the operations that depend on the field, as
+/// [`Analysis::impact_of_change`](crate::Analysis::impact_of_change) reports
+/// them.
+pub fn field_impact_code_lenses(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+) -> Vec<FieldImpactLensInfo> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let Some(content) = registry.get_content(file_id) else {
+        return Vec::new();
+    };
+
+    let schema_types = graphql_hir::schema_types(db, project_files);
+    let mut fields: Vec<(&graphql_hir::TypeDef, &graphql_hir::FieldSignature)> = schema_types
+        .values()
+        .filter(|type_def| {
+            matches!(
+                type_def.kind,
+                graphql_hir::TypeDefKind::Object | graphql_hir::TypeDefKind::Interface
+            )
+        })
+        .flat_map(|type_def| type_def.fields.iter().map(move |field| (type_def, field)))
+        .filter(|(_, field)| field.file_id == file_id)
+        .collect();
+    if fields.is_empty() {
+        return Vec::new();
+    }
+    fields.sort_by_key(|(_, field)| field.name_range.start());
+
+    let mut operations = operations_by_field(db, registry, project_files);
+    let line_index = graphql_syntax::line_index(db, content);
+    fields
+        .into_iter()
+        .map(|(type_def, field)| {
+            let range = offset_range_to_range(
+                &line_index,
+                field.name_range.start().into(),
+                field.name_range.end().into(),
+            );
+            let coordinate = graphql_hir::SchemaCoordinate {
+                type_name: type_def.name.clone(),
+                field_name: field.name.clone(),
+            };
+            FieldImpactLensInfo {
+                range,
+                impact: FieldImpact {
+                    type_name: type_def.name.to_string(),
+                    field_name: field.name.to_string(),
+                    operations: operations.remove(&coordinate).unwrap_or_default(),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Get "copy with fragments" code lenses for the operations in an
+/// executable file.
+///
+/// Each lens sits at the start of an operation and carries its index, which
+/// numbers operations across the file's documents.
+pub fn operation_copy_code_lenses(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+) -> Vec<OperationCopyLensInfo> {
+    if project_files.is_none() {
+        return Vec::new();
+    }
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let (Some(content), Some(metadata)) = (
+        registry.get_content(file_id),
+        registry.get_metadata(file_id),
+    ) else {
+        return Vec::new();
+    };
+    if metadata.is_schema(db) {
+        return Vec::new();
+    }
+
+    let mut lenses = Vec::new();
+    let parse = graphql_syntax::parse(db, content, metadata);
+    for doc in parse.documents() {
+        let doc_line_index = doc.line_index();
+        for definition in doc.tree.document().definitions() {
+            let cst::Definition::OperationDefinition(operation) = definition else {
+                continue;
+            };
+            let start: usize = operation.syntax().text_range().start().into();
+            let range = adjust_range_for_block(
+                offset_range_to_range(&doc_line_index, start, start),
+                doc.origin(),
+            );
+            lenses.push(OperationCopyLensInfo {
+                range,
+                operation_index: lenses.len(),
+            });
+        }
+    }
+    lenses
+}
+
+/// Locations of the operations selecting a field of each type
+fn operations_by_type(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+) -> HashMap<std::sync::Arc<str>, Vec<Location>> {
+    let mut operations: HashMap<std::sync::Arc<str>, Vec<Location>> = HashMap::new();
+    for_each_operation(db, registry, project_files, |operation, selected| {
+        let mut type_names: Vec<&std::sync::Arc<str>> =
+            selected.iter().map(|c| &c.type_name).collect();
+        type_names.sort_unstable();
+        type_names.dedup();
+        for type_name in type_names {
+            operations
+                .entry(type_name.clone())
+                .or_default()
+                .push(operation.location.clone());
+        }
+    });
+    operations
+}
+
+/// Calls per schema coordinate, summed over the operations that select it,
+/// or `None` when no operation usage report is loaded
+///
+/// A project without operations has nothing to look up, and reports `None`
+/// too; every deprecated field in it is unused anyway.
+fn calls_by_field(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> Option<(u32, HashMap<graphql_hir::SchemaCoordinate, u64>)> {
+    let mut window_days = None;
+    let mut counts: HashMap<graphql_hir::SchemaCoordinate, u64> = HashMap::new();
+
+    for file_id in project_files.document_file_ids(db).ids(db).iter() {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        else {
+            continue;
+        };
+        let operations =
+            graphql_hir::operation_schema_coordinates(db, content, metadata, project_files);
+        for (index, coordinates) in operations.iter().enumerate() {
+            let calls = db.operation_calls(*file_id, index)?;
+            window_days = Some(calls.window_days);
+            for coordinate in coordinates {
+                *counts.entry(coordinate.clone()).or_default() += calls.count;
+            }
+        }
+    }
+
+    Some((window_days?, counts))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,17 +1,26 @@
 //! Completion feature implementation.
 //!
 //! This module provides IDE auto-completion functionality including:
-//! - Field completions in selection sets
+//! - Field completions in selection sets, ranked by how often the project's
+//!   operations select each field, with deprecated fields last
 //! - Fragment spread completions
 //! - Inline fragment completions for unions and interfaces
 //! - Argument completions for fields
-//! - Enum value completions in argument positions
+//! - Enum value and input field completions in argument values, including
+//!   inside list and input object literals, skipping keys already written
 //! - Directive completions after `@`
+//! - In schema files, type names in field and argument definitions,
+//!   interfaces after `implements`, object types in union member lists and
+//!   locations in directive definitions
+//! - Operation scaffold snippets for root fields at document level
+
+use apollo_parser::cst::CstNode;
+use apollo_parser::{SyntaxElement, SyntaxKind, SyntaxToken};
 
 use crate::helpers::{
     find_argument_context_at_offset, find_block_for_position,
     find_directive_argument_context_at_offset, find_operation_variables_at_offset, format_type_ref,
-    position_to_offset,
+    format_type_with_scalar, position_to_offset, specified_object_fields, ValuePath,
 };
 use crate::symbol::{
     find_parent_type_at_offset, find_symbol_at_offset, is_in_selection_set, Symbol,
@@ -23,9 +32,11 @@ use crate::DbFiles;
 ///
 /// Returns a list of completion items appropriate for the context.
 pub fn completions(
-    db: &dyn graphql_hir::GraphQLHirDatabase,
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
     registry: DbFiles<'_>,
     project_files: Option<graphql_base_db::ProjectFiles>,
+    scalars: &std::collections::BTreeMap<String, graphql_config::ScalarConfig>,
+    settings: &graphql_config::CompletionSettings,
     file: &FilePath,
     position: Position,
 ) -> Option<Vec<CompletionItem>> {
@@ -41,9 +52,14 @@ pub fn completions(
 
     let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
+    // Nothing to complete inside a comment
+    if is_in_comment(block_context.tree, offset) {
+        return Some(Vec::new());
+    }
+
     let symbol = find_symbol_at_offset(block_context.tree, offset);
 
     // Check if cursor follows `@` - offer directive completions
@@ -68,10 +84,26 @@ pub fn completions(
         return Some(variable_completions(block_context.tree, offset));
     }
 
+    // In schema files, `:`, `implements`, `union X =` and a directive
+    // definition's `on` each take their own kind of name
+    if is_schema {
+        if let Some(context) = sdl_context(block_context.block_source, offset) {
+            let types = project_files.map(|project_files| {
+                graphql_hir::document_schema_types(db, project_files, metadata.file_id(db), content)
+            });
+            return Some(sdl_completions(types, &context));
+        }
+    }
+
     // Check if cursor is in a type name position (after `on` keyword or after `:` in variable def)
-    if is_in_type_position(block_context.block_source, offset) {
+    if is_in_type_position(block_context.tree, offset) {
         if let Some(project_files) = project_files {
-            let types = graphql_hir::schema_types(db, project_files);
+            let types = graphql_hir::document_schema_types(
+                db,
+                project_files,
+                metadata.file_id(db),
+                content,
+            );
             return Some(type_name_completions(types));
         }
         return Some(Vec::new());
@@ -100,13 +132,30 @@ pub fn completions(
             let Some(project_files) = project_files else {
                 return Some(Vec::new());
             };
-            let types = graphql_hir::schema_types(db, project_files);
+            let types = graphql_hir::document_schema_types(
+                db,
+                project_files,
+                metadata.file_id(db),
+                content,
+            );
 
             let in_selection_set = is_in_selection_set(block_context.tree, offset);
             if in_selection_set {
-                field_completions(db, project_files, block_context.tree, types, offset)
+                field_completions(
+                    db,
+                    project_files,
+                    block_context.tree,
+                    types,
+                    scalars,
+                    settings.usage_weight,
+                    offset,
+                )
             } else {
-                Some(keyword_completions(is_schema))
+                let mut items = keyword_completions(is_schema);
+                if !is_schema {
+                    items.extend(operation_scaffold_completions(types));
+                }
+                Some(items)
             }
         }
         _ => Some(Vec::new()),
@@ -117,7 +166,8 @@ pub fn completions(
 ///
 /// Handles two cases:
 /// 1. Cursor at argument name position -> suggest argument names
-/// 2. Cursor at argument value position (after `:`) -> suggest enum values if applicable
+/// 2. Cursor at argument value position (after `:`) -> suggest enum values or
+///    input object fields, following list and object literals to the cursor
 ///
 /// Returns `Some(items)` if the cursor is in an arguments context, `None` otherwise.
 fn try_argument_completions(
@@ -147,15 +197,13 @@ fn try_argument_completions(
             .iter()
             .find(|a| a.name.as_ref() == arg_name)
         {
-            let base_type_name = arg_def.type_ref.name.as_ref();
-            if let Some(type_def) = types.get(base_type_name) {
-                if type_def.kind == graphql_hir::TypeDefKind::Enum {
-                    return Some(enum_value_completions(type_def));
-                }
-                if type_def.kind == graphql_hir::TypeDefKind::InputObject {
-                    return Some(input_field_completions(type_def));
-                }
-            }
+            return Some(value_completions(
+                types,
+                &arg_def.type_ref.name,
+                &arg_ctx.value_path,
+                tree,
+                offset,
+            ));
         }
         // In value position but not an enum/input type - return empty to avoid showing arg names
         return Some(Vec::new());
@@ -187,7 +235,8 @@ fn try_argument_completions(
 ///
 /// Handles two cases:
 /// 1. Cursor at argument name position -> suggest directive argument names
-/// 2. Cursor at argument value position (after `:`) -> suggest enum values if applicable
+/// 2. Cursor at argument value position (after `:`) -> suggest enum values or
+///    input object fields
 fn try_directive_argument_completions(
     db: &dyn graphql_hir::GraphQLHirDatabase,
     project_files: Option<graphql_base_db::ProjectFiles>,
@@ -208,12 +257,13 @@ fn try_directive_argument_completions(
             .find(|a| a.name.as_ref() == arg_name)
         {
             let types = graphql_hir::schema_types(db, project_files);
-            let base_type_name = arg_def.type_ref.name.as_ref();
-            if let Some(type_def) = types.get(base_type_name) {
-                if type_def.kind == graphql_hir::TypeDefKind::Enum {
-                    return Some(enum_value_completions(type_def));
-                }
-            }
+            return Some(value_completions(
+                types,
+                &arg_def.type_ref.name,
+                &dir_ctx.value_path,
+                tree,
+                offset,
+            ));
         }
         return Some(Vec::new());
     }
@@ -239,11 +289,142 @@ fn try_directive_argument_completions(
     Some(items)
 }
 
-/// Generate completion items for input object fields.
-fn input_field_completions(type_def: &graphql_hir::TypeDef) -> Vec<CompletionItem> {
+/// Completions inside an argument value whose type is `type_name`.
+///
+/// Follows `path` through nested input objects; list items share the list's
+/// named type, so `[ADMIN, |` completes enum values like a bare value does.
+/// At a field name, fields the object literal already has are left out.
+fn value_completions(
+    types: &graphql_hir::TypeDefMap,
+    type_name: &str,
+    path: &ValuePath,
+    tree: &apollo_parser::SyntaxTree,
+    offset: usize,
+) -> Vec<CompletionItem> {
+    let Some(type_def) = resolve_value_type(types, type_name, path) else {
+        return Vec::new();
+    };
+
+    match type_def.kind {
+        graphql_hir::TypeDefKind::InputObject => {
+            let specified = if path.at_field_name {
+                specified_object_fields(tree, offset)
+            } else {
+                Vec::new()
+            };
+            input_field_completions(type_def, &specified)
+        }
+        graphql_hir::TypeDefKind::Enum if !path.at_field_name => enum_value_completions(type_def),
+        _ => Vec::new(),
+    }
+}
+
+/// The type at the end of `path` inside a value of type `type_name`
+fn resolve_value_type<'a>(
+    types: &'a graphql_hir::TypeDefMap,
+    type_name: &str,
+    path: &ValuePath,
+) -> Option<&'a graphql_hir::TypeDef> {
+    let mut type_def = types.get(type_name);
+    for field_name in &path.fields {
+        type_def = type_def
+            .filter(|t| t.kind == graphql_hir::TypeDefKind::InputObject)
+            .and_then(|t| t.fields.iter().find(|f| f.name.as_ref() == field_name))
+            .and_then(|f| types.get(f.type_ref.name.as_ref()));
+    }
+    type_def
+}
+
+/// Name of the branch `completions` takes at `offset`.
+///
+/// The checks run in the same order as in `completions`, so this reports
+/// the path that actually produced (or failed to produce) the items.
+pub(crate) fn completion_path(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    block_source: &str,
+    tree: &apollo_parser::SyntaxTree,
+    offset: usize,
+) -> &'static str {
+    if is_in_comment(tree, offset) {
+        return "comment";
+    }
+    if is_after_at_sign(block_source, offset) {
+        return "directive";
+    }
+    if try_directive_argument_completions(db, project_files, tree, offset).is_some() {
+        return "directive-argument";
+    }
+    if is_after_dollar_sign(block_source, offset) {
+        return "variable";
+    }
+    if is_in_type_position(tree, offset) {
+        return "type-name";
+    }
+    if try_argument_completions(db, project_files, tree, offset).is_some() {
+        return "argument";
+    }
+    match find_symbol_at_offset(tree, offset) {
+        Some(Symbol::FragmentSpread { .. }) => "fragment-spread",
+        None | Some(Symbol::FieldName { .. }) if project_files.is_some() => {
+            if is_in_selection_set(tree, offset) {
+                "field"
+            } else {
+                "keyword"
+            }
+        }
+        _ => "none",
+    }
+}
+
+/// The input type expected at `offset` inside a field or directive
+/// argument value, following nested input objects.
+pub(crate) fn expected_input_type(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    tree: &apollo_parser::SyntaxTree,
+    offset: usize,
+) -> Option<String> {
+    let types = graphql_hir::schema_types(db, project_files);
+    let (type_name, path) = if let Some(dir_ctx) =
+        find_directive_argument_context_at_offset(tree, offset)
+    {
+        let arg_name = dir_ctx.argument_name?;
+        let directives = graphql_hir::schema_directives(db, project_files);
+        let arg_def = directives
+            .get(dir_ctx.directive_name.as_str())?
+            .arguments
+            .iter()
+            .find(|a| a.name.as_ref() == arg_name)?;
+        (arg_def.type_ref.name.clone(), dir_ctx.value_path)
+    } else {
+        let arg_ctx = find_argument_context_at_offset(tree, offset)?;
+        let arg_name = arg_ctx.argument_name?;
+        let parent_ctx = find_parent_type_at_offset(tree, offset)?;
+        let parent_type_name =
+            crate::symbol::walk_type_stack_to_offset(tree, types, offset, &parent_ctx.root_type)?;
+        let arg_def = types
+            .get(parent_type_name.as_str())?
+            .fields
+            .iter()
+            .find(|f| f.name.as_ref() == arg_ctx.field_name)?
+            .arguments
+            .iter()
+            .find(|a| a.name.as_ref() == arg_name)?;
+        (arg_def.type_ref.name.clone(), arg_ctx.value_path)
+    };
+    resolve_value_type(types, &type_name, &path).map(|t| t.name.to_string())
+}
+
+/// Generate completion items for input object fields not in `specified`.
+fn input_field_completions(
+    type_def: &graphql_hir::TypeDef,
+    specified: &[String],
+) -> Vec<CompletionItem> {
     type_def
         .fields
         .iter()
+        .filter(|field| !specified.iter().any(|name| name == field.name.as_ref()))
         .map(|field| {
             let mut item = CompletionItem::new(field.name.to_string(), CompletionKind::Field)
                 .with_detail(format_type_ref(&field.type_ref));
@@ -313,7 +494,7 @@ fn directive_locations_at_offset(
     tree: &apollo_parser::SyntaxTree,
     offset: usize,
 ) -> Vec<graphql_hir::DirectiveLocationKind> {
-    use apollo_parser::cst::{self, CstNode};
+    use apollo_parser::cst;
     use graphql_hir::DirectiveLocationKind;
 
     let doc = tree.document();
@@ -417,43 +598,73 @@ fn directive_completions(
 
 /// Check if the cursor is in a type name position.
 ///
-/// Returns true if the cursor follows:
-/// - `on ` (fragment/inline fragment type condition)
-/// - `: ` in a variable definition context
-fn is_in_type_position(source: &str, offset: usize) -> bool {
-    let before = source.get(..offset).unwrap_or("");
-    let trimmed = before.trim_end();
-    // Check for `on` keyword (fragment type condition or inline fragment)
-    if trimmed.ends_with(" on") || trimmed.ends_with("\ton") || trimmed.ends_with("\non") {
-        return true;
-    }
-    // Also check if trimmed itself is just "on" (start of line)
-    if trimmed == "on" {
-        return true;
-    }
-    false
+/// Returns true if the last token before the cursor is the `on` of a
+/// fragment or inline fragment type condition. Comments in between don't
+/// count, so a type condition can continue on the line after one.
+fn is_in_type_position(tree: &apollo_parser::SyntaxTree, offset: usize) -> bool {
+    last_token_before(tree, offset).is_some_and(|token| token.text() == "on")
+}
+
+/// The last token ending at or before `offset`, skipping whitespace, commas
+/// and comments.
+fn last_token_before(tree: &apollo_parser::SyntaxTree, offset: usize) -> Option<SyntaxToken> {
+    tree.document()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .take_while(|token| usize::from(token.text_range().end()) <= offset)
+        .filter(|token| {
+            !matches!(
+                token.kind(),
+                SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::COMMA
+            )
+        })
+        .last()
+}
+
+/// Check if the cursor is inside a `#` comment, including at its end.
+fn is_in_comment(tree: &apollo_parser::SyntaxTree, offset: usize) -> bool {
+    tree.document()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .any(|token| {
+            let range = token.text_range();
+            token.kind() == SyntaxKind::COMMENT
+                && offset > usize::from(range.start())
+                && offset <= usize::from(range.end())
+        })
 }
 
 /// Generate completion items for type names from the schema.
 fn type_name_completions(types: &graphql_hir::TypeDefMap) -> Vec<CompletionItem> {
+    // Fragment type conditions only accept composite types
+    type_completions(types, |t| {
+        matches!(
+            t.kind,
+            graphql_hir::TypeDefKind::Object
+                | graphql_hir::TypeDefKind::Interface
+                | graphql_hir::TypeDefKind::Union
+        )
+    })
+}
+
+/// Completions for the named types accepted by `filter`
+fn type_completions(
+    types: &graphql_hir::TypeDefMap,
+    filter: impl Fn(&graphql_hir::TypeDef) -> bool,
+) -> Vec<CompletionItem> {
     types
         .values()
-        .filter(|t| {
-            // Only suggest types that can appear in type positions
-            // Exclude InputObject types for fragment type conditions
-            matches!(
-                t.kind,
-                graphql_hir::TypeDefKind::Object
-                    | graphql_hir::TypeDefKind::Interface
-                    | graphql_hir::TypeDefKind::Union
-            )
-        })
+        .filter(|t| filter(t))
         .map(|t| {
             let kind_label = match t.kind {
                 graphql_hir::TypeDefKind::Object => "object",
                 graphql_hir::TypeDefKind::Interface => "interface",
                 graphql_hir::TypeDefKind::Union => "union",
-                _ => "type",
+                graphql_hir::TypeDefKind::Enum => "enum",
+                graphql_hir::TypeDefKind::Scalar => "scalar",
+                graphql_hir::TypeDefKind::InputObject => "input",
             };
             let mut item = CompletionItem::new(t.name.to_string(), CompletionKind::Type)
                 .with_detail(kind_label.to_string());
@@ -465,6 +676,281 @@ fn type_name_completions(types: &graphql_hir::TypeDefMap) -> Vec<CompletionItem>
         .collect()
 }
 
+/// Where the cursor sits in a type system definition
+#[derive(Debug, PartialEq, Eq)]
+enum SdlContext {
+    /// The type of a field, input field or argument definition, after `:`
+    FieldType(TypePosition),
+    /// An `implements` list, after `implements` or `&`
+    Implements {
+        type_name: String,
+        listed: Vec<String>,
+    },
+    /// The members of a union, after `=` or `|`
+    UnionMembers { listed: Vec<String> },
+    /// The locations of a directive definition, after `on` or `|`
+    DirectiveLocations { listed: Vec<String> },
+}
+
+/// Which named types a field definition accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TypePosition {
+    /// Fields of object and interface types
+    Output,
+    /// Arguments and input object fields
+    Input,
+    /// Root operation types in a `schema` definition
+    RootOperation,
+}
+
+const DIRECTIVE_LOCATIONS: [graphql_hir::DirectiveLocationKind; 19] = {
+    use graphql_hir::DirectiveLocationKind as L;
+    [
+        L::Query,
+        L::Mutation,
+        L::Subscription,
+        L::Field,
+        L::FragmentDefinition,
+        L::FragmentSpread,
+        L::InlineFragment,
+        L::VariableDefinition,
+        L::Schema,
+        L::Scalar,
+        L::Object,
+        L::FieldDefinition,
+        L::ArgumentDefinition,
+        L::Interface,
+        L::Union,
+        L::Enum,
+        L::EnumValue,
+        L::InputObject,
+        L::InputFieldDefinition,
+    ]
+};
+
+/// Find the type system completion context at `offset`, ignoring the name
+/// being typed
+fn sdl_context(source: &str, offset: usize) -> Option<SdlContext> {
+    let before = source.get(..offset)?;
+    let head = before.trim_end_matches(|c: char| c == '_' || c.is_ascii_alphanumeric());
+    let tokens = sdl_tokens(head);
+    match *tokens.last()? {
+        ":" | "[" => {
+            let end = tokens.iter().rposition(|t| *t != "[")?;
+            (tokens[end] == ":")
+                .then(|| field_type_position(&tokens[..end]))
+                .flatten()
+                .map(SdlContext::FieldType)
+        }
+        "implements" | "&" => {
+            let (before, listed) = split_list(&tokens, "implements", "&")?;
+            match before {
+                [.., "type" | "interface", type_name] => Some(SdlContext::Implements {
+                    type_name: (*type_name).to_string(),
+                    listed,
+                }),
+                _ => None,
+            }
+        }
+        "=" | "|" | "on" => {
+            if let Some((before, listed)) = split_list(&tokens, "=", "|") {
+                if matches!(strip_directives(before), [.., "union", _]) {
+                    return Some(SdlContext::UnionMembers { listed });
+                }
+            }
+            let (before, listed) = split_list(&tokens, "on", "|")?;
+            let before = before.strip_suffix(&["repeatable"]).unwrap_or(before);
+            let before = strip_balanced(before, "(", ")").unwrap_or(before);
+            matches!(before, [.., "directive", "@", _])
+                .then_some(SdlContext::DirectiveLocations { listed })
+        }
+        _ => None,
+    }
+}
+
+/// Names and punctuators of `source`, skipping comments, strings and commas
+fn sdl_tokens(source: &str) -> Vec<&str> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        if c == b'#' {
+            while i < bytes.len() && bytes[i] != b'\n' {
+                i += 1;
+            }
+        } else if bytes[i..].starts_with(b"\"\"\"") {
+            i = source[i + 3..]
+                .find("\"\"\"")
+                .map_or(bytes.len(), |end| i + 3 + end + 3);
+        } else if c == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' && bytes[i] != b'\n' {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i += 1;
+        } else if c == b'_' || c.is_ascii_alphanumeric() {
+            let start = i;
+            while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                i += 1;
+            }
+            tokens.push(&source[start..i]);
+        } else if b"{}()[]:=|&@!".contains(&c) {
+            tokens.push(&source[i..=i]);
+            i += 1;
+        } else {
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// Split a trailing `intro A | B |` list off `tokens`, returning the tokens
+/// before `intro` and the names already listed
+fn split_list<'t, 'a>(
+    tokens: &'t [&'a str],
+    intro: &'a str,
+    separator: &str,
+) -> Option<(&'t [&'a str], Vec<String>)> {
+    let mut end = tokens.len();
+    let mut listed = Vec::new();
+    while end > 0 && tokens[end - 1] == separator {
+        end -= 1;
+        if end > 0 && tokens[end - 1] != intro && is_sdl_name(tokens[end - 1]) {
+            listed.push(tokens[end - 1].to_string());
+            end -= 1;
+        }
+    }
+    let before = tokens[..end].strip_suffix(&[intro])?;
+    Some((before, listed))
+}
+
+fn is_sdl_name(token: &str) -> bool {
+    token.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic())
+}
+
+/// Drop a trailing `open ... close` group, if `tokens` ends with one
+fn strip_balanced<'t, 'a>(tokens: &'t [&'a str], open: &str, close: &str) -> Option<&'t [&'a str]> {
+    if tokens.last() != Some(&close) {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().rev() {
+        if *token == close {
+            depth += 1;
+        } else if *token == open {
+            depth -= 1;
+            if depth == 0 {
+                return Some(&tokens[..i]);
+            }
+        }
+    }
+    None
+}
+
+/// Drop trailing `@directive(...)` applications
+fn strip_directives<'t, 'a>(mut tokens: &'t [&'a str]) -> &'t [&'a str] {
+    loop {
+        let without_args = strip_balanced(tokens, "(", ")").unwrap_or(tokens);
+        match without_args {
+            [rest @ .., "@", name] if is_sdl_name(name) => tokens = rest,
+            _ => return tokens,
+        }
+    }
+}
+
+/// What kind of type the definition enclosing the end of `tokens` expects
+fn field_type_position(tokens: &[&str]) -> Option<TypePosition> {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate().rev() {
+        match *token {
+            "}" | ")" => depth += 1,
+            "{" | "(" if depth > 0 => depth -= 1,
+            // Arguments of an applied directive take values, not types
+            "(" => {
+                let before = &tokens[..i];
+                let applied_directive =
+                    matches!(before, [.., "@", _]) && !matches!(before, [.., "directive", "@", _]);
+                return (!applied_directive).then_some(TypePosition::Input);
+            }
+            "{" => {
+                let keyword = tokens[..i]
+                    .iter()
+                    .rev()
+                    .take_while(|t| **t != "}")
+                    .find(|t| matches!(**t, "type" | "interface" | "input" | "schema"))?;
+                return Some(match *keyword {
+                    "input" => TypePosition::Input,
+                    "schema" => TypePosition::RootOperation,
+                    _ => TypePosition::Output,
+                });
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn sdl_completions(
+    types: Option<&graphql_hir::TypeDefMap>,
+    context: &SdlContext,
+) -> Vec<CompletionItem> {
+    use graphql_hir::TypeDefKind;
+
+    let is_listed = |name: &str, listed: &[String]| listed.iter().any(|l| l == name);
+    match context {
+        SdlContext::DirectiveLocations { listed: used } => DIRECTIVE_LOCATIONS
+            .iter()
+            .map(|location| {
+                (
+                    *location,
+                    crate::hover::format_directive_location(*location),
+                )
+            })
+            .filter(|(_, name)| !is_listed(name, used))
+            .map(|(location, name)| {
+                let detail = if location.is_executable() {
+                    "executable location"
+                } else {
+                    "type system location"
+                };
+                CompletionItem::new(name.to_string(), CompletionKind::EnumValue)
+                    .with_detail(detail.to_string())
+            })
+            .collect(),
+        _ => {
+            let Some(types) = types else {
+                return Vec::new();
+            };
+            match context {
+                SdlContext::FieldType(position) => type_completions(types, |t| match position {
+                    TypePosition::Output => t.kind != TypeDefKind::InputObject,
+                    TypePosition::Input => matches!(
+                        t.kind,
+                        TypeDefKind::Scalar | TypeDefKind::Enum | TypeDefKind::InputObject
+                    ),
+                    TypePosition::RootOperation => t.kind == TypeDefKind::Object,
+                }),
+                SdlContext::Implements {
+                    type_name,
+                    listed: used,
+                } => type_completions(types, |t| {
+                    t.kind == TypeDefKind::Interface
+                        && t.name.as_ref() != type_name.as_str()
+                        && !is_listed(&t.name, used)
+                }),
+                SdlContext::UnionMembers { listed: used } => type_completions(types, |t| {
+                    t.kind == TypeDefKind::Object && !is_listed(&t.name, used)
+                }),
+                SdlContext::DirectiveLocations { .. } => Vec::new(),
+            }
+        }
+    }
+}
+
 /// Generate completion items for top-level GraphQL keywords.
 ///
 /// Returns operation keywords (query, mutation, etc.) for executable documents,
@@ -482,23 +968,129 @@ fn executable_keyword_completions() -> Vec<CompletionItem> {
     vec![
         CompletionItem::new("query", CompletionKind::Keyword)
             .with_detail("Define a query operation")
-            .with_insert_text("query $1 {\n  $0\n}")
+            .with_insert_text("query ${1:Name} {\n  $0\n}")
             .with_insert_text_format(InsertTextFormat::Snippet),
         CompletionItem::new("mutation", CompletionKind::Keyword)
             .with_detail("Define a mutation operation")
-            .with_insert_text("mutation $1 {\n  $0\n}")
+            .with_insert_text("mutation ${1:Name} {\n  $0\n}")
             .with_insert_text_format(InsertTextFormat::Snippet),
         CompletionItem::new("subscription", CompletionKind::Keyword)
             .with_detail("Define a subscription operation")
-            .with_insert_text("subscription $1 {\n  $0\n}")
+            .with_insert_text("subscription ${1:Name} {\n  $0\n}")
             .with_insert_text_format(InsertTextFormat::Snippet),
         CompletionItem::new("fragment", CompletionKind::Keyword)
             .with_detail("Define a fragment")
-            .with_insert_text("fragment $1 on $2 {\n  $0\n}")
+            .with_insert_text("fragment ${1:Name} on ${2:Type} {\n  $0\n}")
             .with_insert_text_format(InsertTextFormat::Snippet),
     ]
 }
 
+/// Whole-operation snippets, one per root field.
+///
+/// Each scaffold declares a variable for every required argument and passes
+/// it through, e.g. `query GetUser($id: ID!) { user(id: $id) { $0 } }`.
+/// The operation name is the first tab stop so it can be renamed in place.
+fn operation_scaffold_completions(types: &graphql_hir::TypeDefMap) -> Vec<CompletionItem> {
+    let mut items = Vec::new();
+    for (keyword, root_type_name) in [
+        ("query", "Query"),
+        ("mutation", "Mutation"),
+        ("subscription", "Subscription"),
+    ] {
+        let Some(root_type) = types.get(root_type_name) else {
+            continue;
+        };
+        for field in &root_type.fields {
+            if field.name.starts_with("__") {
+                continue;
+            }
+            let mut item =
+                CompletionItem::new(format!("{keyword} {}", field.name), CompletionKind::Snippet)
+                    .with_detail(format!(
+                        "{root_type_name}.{}: {}",
+                        field.name,
+                        format_type_ref(&field.type_ref)
+                    ))
+                    .with_insert_text(operation_scaffold(keyword, field, types))
+                    .with_insert_text_format(InsertTextFormat::Snippet)
+                    .with_deprecated(field.is_deprecated);
+            if let Some(desc) = &field.description {
+                item = item.with_documentation(desc.to_string());
+            }
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// Build the snippet body for a single root field scaffold.
+fn operation_scaffold(
+    keyword: &str,
+    field: &graphql_hir::FieldSignature,
+    types: &graphql_hir::TypeDefMap,
+) -> String {
+    let required: Vec<&graphql_hir::ArgumentDef> = field
+        .arguments
+        .iter()
+        .filter(|arg| arg.type_ref.is_non_null && arg.default_value.is_none())
+        .collect();
+
+    // `$` is a snippet metacharacter, so variable sigils are escaped
+    let (variables, arguments) = if required.is_empty() {
+        (String::new(), String::new())
+    } else {
+        let variables = required
+            .iter()
+            .map(|arg| format!("\\${}: {}", arg.name, format_type_ref(&arg.type_ref)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let arguments = required
+            .iter()
+            .map(|arg| format!("{}: \\${}", arg.name, arg.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        (format!("({variables})"), format!("({arguments})"))
+    };
+
+    let is_composite = types.get(&field.type_ref.name).is_some_and(|t| {
+        matches!(
+            t.kind,
+            graphql_hir::TypeDefKind::Object
+                | graphql_hir::TypeDefKind::Interface
+                | graphql_hir::TypeDefKind::Union
+        )
+    });
+    let selection = if is_composite {
+        " {\n    $0\n  }"
+    } else {
+        "$0"
+    };
+
+    format!(
+        "{keyword} ${{1:{}}}{variables} {{\n  {}{arguments}{selection}\n}}",
+        default_operation_name(keyword, &field.name),
+        field.name
+    )
+}
+
+/// Suggested operation name for a root field: `user` becomes `GetUser` for
+/// queries, `createUser` becomes `CreateUser` for mutations, and
+/// `userUpdated` becomes `OnUserUpdated` for subscriptions.
+fn default_operation_name(keyword: &str, field_name: &str) -> String {
+    let mut chars = field_name.chars();
+    let pascal: String = chars
+        .next()
+        .map(|first| first.to_ascii_uppercase())
+        .into_iter()
+        .chain(chars)
+        .collect();
+    match keyword {
+        "query" => format!("Get{pascal}"),
+        "subscription" => format!("On{pascal}"),
+        _ => pascal,
+    }
+}
+
 /// Keywords for schema documents (type definitions).
 fn schema_keyword_completions() -> Vec<CompletionItem> {
     vec![
@@ -543,10 +1135,12 @@ fn schema_keyword_completions() -> Vec<CompletionItem> {
 
 /// Provide field completions in a selection set.
 fn field_completions(
-    db: &dyn graphql_hir::GraphQLHirDatabase,
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
     project_files: graphql_base_db::ProjectFiles,
     tree: &apollo_parser::SyntaxTree,
     types: &graphql_hir::TypeDefMap,
+    scalars: &std::collections::BTreeMap<String, graphql_config::ScalarConfig>,
+    usage_weight: f64,
     offset: usize,
 ) -> Option<Vec<CompletionItem>> {
     let parent_ctx = find_parent_type_at_offset(tree, offset)?;
@@ -569,12 +1163,16 @@ fn field_completions(
                 return Some(items);
             }
 
+            let ranks = rank_fields(db, project_files, parent_type, usage_weight);
             let mut items: Vec<CompletionItem> = parent_type
                 .fields
                 .iter()
-                .map(|field| {
+                .zip(ranks)
+                .map(|(field, rank)| {
                     CompletionItem::new(field.name.to_string(), CompletionKind::Field)
-                        .with_detail(format_type_ref(&field.type_ref))
+                        .with_detail(format_type_with_scalar(&field.type_ref, scalars))
+                        .with_deprecated(field.is_deprecated)
+                        .with_sort_text(format!("{}{rank:04}", u8::from(field.is_deprecated)))
                 })
                 .collect();
 
@@ -597,6 +1195,58 @@ fn field_completions(
     )
 }
 
+/// The sort position of each of `type_def`'s fields, in declaration order.
+///
+/// Each field scores a blend of how often the project's operations select
+/// it, relative to the type's most selected field, and how early the schema
+/// declares it. `usage_weight` is the share given to usage, so 0 keeps schema
+/// order. Deprecated fields are ranked among themselves and placed after the
+/// rest by the caller's sort text.
+#[allow(clippy::cast_precision_loss)]
+fn rank_fields(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    type_def: &graphql_hir::TypeDef,
+    usage_weight: f64,
+) -> Vec<usize> {
+    let usage_weight = usage_weight.clamp(0.0, 1.0);
+    let usage_counts: Vec<usize> = if usage_weight > 0.0 {
+        let coverage = graphql_analysis::analyze_field_usage(db, project_files);
+        type_def
+            .fields
+            .iter()
+            .map(|field| {
+                coverage
+                    .field_usages
+                    .get(&(type_def.name.clone(), field.name.clone()))
+                    .map_or(0, |usage| usage.usage_count)
+            })
+            .collect()
+    } else {
+        vec![0; type_def.fields.len()]
+    };
+    let max_usage = usage_counts.iter().copied().max().unwrap_or(0).max(1) as f64;
+    let field_count = type_def.fields.len().max(1) as f64;
+
+    let scores: Vec<f64> = usage_counts
+        .iter()
+        .enumerate()
+        .map(|(index, &count)| {
+            let usage = count as f64 / max_usage;
+            let position = 1.0 - index as f64 / field_count;
+            usage_weight * usage + (1.0 - usage_weight) * position
+        })
+        .collect();
+
+    let mut order: Vec<usize> = (0..scores.len()).collect();
+    order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+    let mut ranks = vec![0; scores.len()];
+    for (rank, index) in order.into_iter().enumerate() {
+        ranks[index] = rank;
+    }
+    ranks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -640,6 +1290,19 @@ mod tests {
         assert_eq!(CompletionKind::EnumValue, CompletionKind::EnumValue);
     }
 
+    #[test]
+    fn test_default_operation_name() {
+        assert_eq!(default_operation_name("query", "user"), "GetUser");
+        assert_eq!(
+            default_operation_name("mutation", "createUser"),
+            "CreateUser"
+        );
+        assert_eq!(
+            default_operation_name("subscription", "userUpdated"),
+            "OnUserUpdated"
+        );
+    }
+
     #[test]
     fn test_insert_text_format_variants() {
         assert_eq!(InsertTextFormat::PlainText, InsertTextFormat::PlainText);
@@ -656,4 +1319,43 @@ mod tests {
         assert_eq!(item.detail, Some("User!".to_string()));
         assert_eq!(item.sort_text, Some("aaa_user".to_string()));
     }
+
+    #[test]
+    fn test_sdl_context() {
+        let context = |source: &str| sdl_context(source, source.len());
+
+        assert_eq!(
+            context("type User {\n  friends(first: Int): [Us"),
+            Some(SdlContext::FieldType(TypePosition::Output))
+        );
+        assert_eq!(
+            context("type User {\n  friends(first: "),
+            Some(SdlContext::FieldType(TypePosition::Input))
+        );
+        assert_eq!(
+            context("input Filter {\n  \"\"\"Use { and :\"\"\"\n  name: "),
+            Some(SdlContext::FieldType(TypePosition::Input))
+        );
+        assert_eq!(context("type User @key(fields: "), None);
+        assert_eq!(
+            context("type User implements Node & "),
+            Some(SdlContext::Implements {
+                type_name: "User".to_string(),
+                listed: vec!["Node".to_string()],
+            })
+        );
+        assert_eq!(
+            context("union SearchResult @tag(name: \"a\") = User | P"),
+            Some(SdlContext::UnionMembers {
+                listed: vec!["User".to_string()],
+            })
+        );
+        assert_eq!(
+            context("directive @auth(role: String) repeatable on FIELD_DEFINITION | "),
+            Some(SdlContext::DirectiveLocations {
+                listed: vec!["FIELD_DEFINITION".to_string()],
+            })
+        );
+        assert_eq!(context("type User {\n  name: String\n  "), None);
+    }
 }
@@ -0,0 +1,219 @@
+//! Cursor context inspection.
+//!
+//! Reports what the analyzer sees at a position: the embedded block the
+//! cursor maps into, the definition and symbol around it, the types in
+//! scope, and which path completion takes there. It exists for debugging
+//! reports like "completion shows the wrong fields here" without needing
+//! the reporter's project.
+//!
+//! [`context_at`] is the structured counterpart for embedding clients: the
+//! same resolution, reported as definition, parent type, field and argument
+//! rather than debug strings.
+
+use apollo_parser::cst::{self, CstNode};
+
+use crate::completion::{completion_path, expected_input_type};
+use crate::helpers::{
+    find_argument_context_at_offset, find_block_for_position,
+    find_directive_argument_context_at_offset, position_to_offset,
+};
+use crate::symbol::{find_parent_type_at_offset, find_symbol_at_offset, walk_type_stack_to_offset};
+use crate::types::{
+    ArgumentOwner, CursorArgument, CursorBlock, CursorContext, CursorDefinition, CursorField,
+    FilePath, Position, PositionContext,
+};
+use crate::DbFiles;
+
+/// Describe the analyzer's context at `position` in `file`.
+///
+/// Returns `None` only when the file isn't known. A position outside every
+/// GraphQL block still yields the file-level fields.
+pub fn cursor_context(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+) -> Option<CursorContext> {
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    let mut context = CursorContext {
+        language: metadata.language(db),
+        document_kind: metadata.document_kind(db),
+        has_project: project_files.is_some(),
+        block_count: parse.document_count(),
+        extraction_skipped: parse.skipped_reason().map(str::to_string),
+        block: None,
+        enclosing_definition: None,
+        symbol: None,
+        parent_type: None,
+        expected_input_type: None,
+        completion_path: "none",
+    };
+
+    let Some((block, adjusted)) = find_block_for_position(&parse, position) else {
+        return Some(context);
+    };
+    let offset = position_to_offset(&block.line_index(), adjusted);
+    context.block = Some(CursorBlock {
+        index: parse
+            .documents()
+            .position(|doc| std::ptr::eq(doc.tree, block.tree))
+            .unwrap_or_default(),
+        file_offset: block.byte_offset(),
+        start: Position::new(block.line_offset, block.column_offset()),
+        length: block.block_source.len(),
+        position: adjusted,
+        offset,
+    });
+    let Some(offset) = offset else {
+        return Some(context);
+    };
+
+    context.enclosing_definition = definition_at(block.tree, offset).map(|d| label(&d));
+    context.symbol = find_symbol_at_offset(block.tree, offset).map(|s| format!("{s:?}"));
+    context.completion_path =
+        completion_path(db, project_files, block.block_source, block.tree, offset);
+    if let Some(project_files) = project_files {
+        let types = graphql_hir::schema_types(db, project_files);
+        context.parent_type = find_parent_type_at_offset(block.tree, offset)
+            .and_then(|ctx| walk_type_stack_to_offset(block.tree, types, offset, &ctx.root_type));
+        context.expected_input_type = expected_input_type(db, project_files, block.tree, offset);
+    }
+
+    Some(context)
+}
+
+/// Describe the cursor context at `position` in `file`.
+///
+/// Returns `None` when the file isn't known or the position falls outside
+/// every GraphQL block. Type information is only filled in when the file
+/// belongs to a project.
+pub fn context_at(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+) -> Option<PositionContext> {
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    let (block, adjusted) = find_block_for_position(&parse, position)?;
+    let offset = position_to_offset(&block.line_index(), adjusted)?;
+    let tree = block.tree;
+    let types = project_files.map(|project_files| graphql_hir::schema_types(db, project_files));
+    let parent_type_at = |offset: usize| {
+        let types = types?;
+        find_parent_type_at_offset(tree, offset)
+            .and_then(|ctx| walk_type_stack_to_offset(tree, types, offset, &ctx.root_type))
+    };
+
+    let field = field_at(tree, offset).and_then(|field| {
+        let start = usize::from(field.syntax().text_range().start());
+        Some(CursorField {
+            name: field.name()?.text().to_string(),
+            alias: field
+                .alias()
+                .and_then(|alias| alias.name())
+                .map(|name| name.text().to_string()),
+            parent_type: parent_type_at(start),
+        })
+    });
+
+    let argument = find_directive_argument_context_at_offset(tree, offset)
+        .map(|ctx| CursorArgument {
+            owner: ArgumentOwner::Directive(ctx.directive_name),
+            name: ctx.argument_name,
+        })
+        .or_else(|| {
+            find_argument_context_at_offset(tree, offset).map(|ctx| CursorArgument {
+                owner: ArgumentOwner::Field(ctx.field_name),
+                name: ctx.argument_name,
+            })
+        });
+
+    Some(PositionContext {
+        definition: definition_at(tree, offset),
+        parent_type: parent_type_at(offset),
+        field,
+        argument,
+        expected_input_type: project_files
+            .and_then(|project_files| expected_input_type(db, project_files, tree, offset)),
+    })
+}
+
+/// The definition containing `offset`
+fn definition_at(tree: &apollo_parser::SyntaxTree, offset: usize) -> Option<CursorDefinition> {
+    let definition = tree.document().definitions().find(|definition| {
+        let range = definition.syntax().text_range();
+        usize::from(range.start()) <= offset && offset <= usize::from(range.end())
+    })?;
+    let name = |name: Option<cst::Name>| name.map(|name| name.text().to_string());
+
+    let (keyword, def_name) = match &definition {
+        cst::Definition::OperationDefinition(op) => {
+            return Some(CursorDefinition::Operation {
+                kind: op.operation_type().map_or_else(
+                    || "query".to_string(),
+                    |op_type| op_type.syntax().text().to_string(),
+                ),
+                name: name(op.name()),
+            });
+        }
+        cst::Definition::FragmentDefinition(frag) => {
+            return Some(CursorDefinition::Fragment {
+                name: name(frag.fragment_name().and_then(|n| n.name())),
+                type_condition: name(
+                    frag.type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name()),
+                ),
+            });
+        }
+        cst::Definition::ObjectTypeDefinition(def) => ("type".to_string(), def.name()),
+        cst::Definition::InterfaceTypeDefinition(def) => ("interface".to_string(), def.name()),
+        cst::Definition::UnionTypeDefinition(def) => ("union".to_string(), def.name()),
+        cst::Definition::EnumTypeDefinition(def) => ("enum".to_string(), def.name()),
+        cst::Definition::InputObjectTypeDefinition(def) => ("input".to_string(), def.name()),
+        cst::Definition::ScalarTypeDefinition(def) => ("scalar".to_string(), def.name()),
+        cst::Definition::DirectiveDefinition(def) => ("directive".to_string(), def.name()),
+        _ => (format!("{:?}", definition.syntax().kind()), None),
+    };
+
+    Some(CursorDefinition::TypeSystem {
+        keyword,
+        name: name(def_name),
+    })
+}
+
+/// Keyword and name of a definition, e.g. `query GetUser`
+fn label(definition: &CursorDefinition) -> String {
+    let (keyword, name) = match definition {
+        CursorDefinition::Operation { kind, name } => (kind.as_str(), name),
+        CursorDefinition::Fragment { name, .. } => ("fragment", name),
+        CursorDefinition::TypeSystem { keyword, name } => (keyword.as_str(), name),
+    };
+    match name {
+        Some(name) => format!("{keyword} {name}"),
+        None => keyword.to_string(),
+    }
+}
+
+/// The innermost selected field whose range contains `offset`
+fn field_at(tree: &apollo_parser::SyntaxTree, offset: usize) -> Option<cst::Field> {
+    tree.document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::Field::cast)
+        .filter(|field| {
+            let range = field.syntax().text_range();
+            usize::from(range.start()) <= offset && offset <= usize::from(range.end())
+        })
+        .last()
+}
@@ -1,4 +1,9 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::metrics::{MetricsEvent, SharedSink};
+use crate::types::QueryMetrics;
 
 /// Input: Lint configuration
 ///
@@ -15,6 +20,15 @@ pub(crate) struct LintConfigInput {
     pub config: Arc<graphql_linter::LintConfig>,
 }
 
+/// Input: Compiled WebAssembly lint rules
+///
+/// Kept apart from `LintConfigInput` so that swapping in rule overrides,
+/// as the napi binding does per call, keeps the compiled modules.
+#[salsa::input]
+pub(crate) struct LintPluginsInput {
+    pub plugins: Arc<graphql_linter::LintPlugins>,
+}
+
 /// Input: Extract configuration for TypeScript/JavaScript extraction
 ///
 /// This is a Salsa input so that config changes properly invalidate dependent queries.
@@ -30,6 +44,138 @@ pub(crate) struct ExtractConfigInput {
     pub config: Arc<graphql_extract::ExtractConfig>,
 }
 
+/// Input: Whether documents may use client-controlled nullability
+///
+/// Read by every parse, so flipping it re-parses the project's files.
+#[salsa::input]
+pub(crate) struct NullabilityConfigInput {
+    pub enabled: bool,
+}
+
+/// Input: Cost model for operation complexity analysis
+///
+/// Stored as a Salsa input alongside the lint and extract configs so a
+/// config reload swaps it atomically with the rest of the project state.
+#[salsa::input]
+pub(crate) struct ComplexityConfigInput {
+    pub config: Arc<graphql_config::ComplexityConfig>,
+}
+
+/// Input: Validation severity profile
+///
+/// Read by the analysis layer when assembling validation diagnostics, so
+/// switching profiles re-runs only the severity mapping queries.
+#[salsa::input]
+pub(crate) struct ValidationConfigInput {
+    pub config: Arc<graphql_config::ValidationConfig>,
+}
+
+/// Input: Operation usage report loaded from `operationUsage`
+///
+/// `None` until a project configures a report, so traffic-aware features
+/// can tell "no data" apart from "never called".
+#[salsa::input]
+pub(crate) struct OperationUsageInput {
+    pub report: Option<Arc<graphql_config::OperationUsageReport>>,
+}
+
+/// Input: Persisted operation allow-list loaded from `persistedOperations`
+///
+/// `None` when the project doesn't restrict operations, which turns the
+/// allow-list diagnostic off.
+#[salsa::input]
+pub(crate) struct PersistedOperationsInput {
+    pub allow_list: Option<Arc<graphql_config::PersistedOperationAllowList>>,
+}
+
+/// Input: N+1 detection settings from `nPlusOne`
+///
+/// `None` when the project hasn't opted in, which turns the check off.
+#[salsa::input]
+pub(crate) struct NPlusOneConfigInput {
+    pub config: Option<Arc<graphql_config::NPlusOneConfig>>,
+}
+
+/// Input: Custom scalar documentation from `scalars`
+///
+/// Read by hover, inlay hints and completion only, so documenting a scalar
+/// never re-runs validation.
+#[salsa::input]
+pub(crate) struct ScalarConfigInput {
+    pub scalars: Arc<BTreeMap<String, graphql_config::ScalarConfig>>,
+}
+
+/// Input: The `format` of each scalar under `scalars` that sets one
+///
+/// Split from `ScalarConfigInput` because validation reads it.
+#[salsa::input]
+pub(crate) struct ScalarFormatsInput {
+    pub formats: Arc<BTreeMap<String, graphql_config::ScalarFormat>>,
+}
+
+/// Input: Editor-facing settings from `.graphqlanalyzer.toml`
+///
+/// Feature toggles, inlay hint kinds and formatting style. Diagnostics
+/// don't read it, so changing a toggle never re-runs validation.
+#[salsa::input]
+pub(crate) struct AnalyzerSettingsInput {
+    pub settings: Arc<graphql_config::AnalyzerSettings>,
+}
+
+/// Per-query execution and reuse counts, fed by the Salsa event callback
+///
+/// Off by default: formatting every database key on the hot path isn't free,
+/// so events are only counted while a caller has asked for metrics, and
+/// only forwarded while a metrics sink is installed.
+#[derive(Default)]
+pub(crate) struct QueryMetricsLog {
+    enabled: AtomicBool,
+    counts: Mutex<HashMap<String, QueryMetrics>>,
+    pub(crate) sink: SharedSink,
+}
+
+impl QueryMetricsLog {
+    pub(crate) fn set_enabled(&self, enabled: bool) {
+        self.counts
+            .lock()
+            .expect("query metrics mutex poisoned")
+            .clear();
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<String, QueryMetrics> {
+        self.counts
+            .lock()
+            .expect("query metrics mutex poisoned")
+            .clone()
+    }
+
+    fn record(&self, database_key: &dyn std::fmt::Debug, executed: bool) {
+        let counting = self.enabled.load(Ordering::Relaxed);
+        if !counting && !self.sink.is_installed() {
+            return;
+        }
+        let debug_str = format!("{database_key:?}");
+        let without_args = debug_str.split('(').next().unwrap_or(&debug_str);
+        let name = without_args.rsplit("::").next().unwrap_or(without_args);
+
+        self.sink.record(&MetricsEvent::Query {
+            name,
+            cache_hit: !executed,
+        });
+        if !counting {
+            return;
+        }
+        let mut counts = self.counts.lock().expect("query metrics mutex poisoned");
+        let entry = counts.entry(name.to_string()).or_default();
+        if executed {
+            entry.executed += 1;
+        } else {
+            entry.reused += 1;
+        }
+    }
+}
+
 /// Custom database that implements config traits
 ///
 /// All configuration is now stored as Salsa inputs (`LintConfigInput`, `ExtractConfigInput`,
@@ -43,31 +189,72 @@ pub(crate) struct ExtractConfigInput {
 pub(crate) struct IdeDatabase {
     pub(crate) storage: salsa::Storage<Self>,
     pub(crate) lint_config_input: Option<LintConfigInput>,
+    pub(crate) lint_plugins_input: Option<LintPluginsInput>,
     #[cfg(feature = "extract")]
     pub(crate) extract_config_input: Option<ExtractConfigInput>,
+    pub(crate) nullability_config_input: Option<NullabilityConfigInput>,
+    pub(crate) complexity_config_input: Option<ComplexityConfigInput>,
+    pub(crate) validation_config_input: Option<ValidationConfigInput>,
+    pub(crate) operation_usage_input: Option<OperationUsageInput>,
+    pub(crate) persisted_operations_input: Option<PersistedOperationsInput>,
+    pub(crate) n_plus_one_config_input: Option<NPlusOneConfigInput>,
+    pub(crate) scalar_config_input: Option<ScalarConfigInput>,
+    pub(crate) scalar_formats_input: Option<ScalarFormatsInput>,
+    pub(crate) analyzer_settings_input: Option<AnalyzerSettingsInput>,
     /// Project files input - stores the current `ProjectFiles` Salsa input directly.
     /// Unlike the old `Arc<RwLock<...>>` approach, this enables proper Salsa dependency
     /// tracking: queries that call `db.project_files()` and then access fields like
     /// `project_files.schema_file_ids(db)` will have their dependencies tracked.
     pub(crate) project_files_input: Option<graphql_base_db::ProjectFiles>,
+    /// Schema variant files, synced from the registry alongside `project_files_input`.
+    pub(crate) schema_variants_input: Option<graphql_base_db::SchemaVariantFileIds>,
+    /// Shared with the storage's event callback, and with every snapshot.
+    pub(crate) query_metrics: Arc<QueryMetricsLog>,
+    /// Parse results kept in memory under lazy parsing; `None` keeps all.
+    pub(crate) parse_cache_capacity: Option<usize>,
+    /// File structures persisted across sessions; `None` when off.
+    pub(crate) structure_cache: Option<Arc<graphql_hir::StructureCache>>,
 }
 
 impl Default for IdeDatabase {
     fn default() -> Self {
+        let query_metrics = Arc::new(QueryMetricsLog::default());
+        let metrics_for_callback = Arc::clone(&query_metrics);
         let mut db = Self {
-            storage: salsa::Storage::new(Some(Box::new(|event: salsa::Event| match event.kind {
-                salsa::EventKind::WillExecute { database_key, .. } => {
-                    tracing::debug!("query cache miss (executing): {database_key:?}");
-                }
-                salsa::EventKind::DidValidateMemoizedValue { database_key } => {
-                    tracing::debug!("query cache hit (memoized): {database_key:?}");
+            storage: salsa::Storage::new(Some(Box::new(move |event: salsa::Event| {
+                match event.kind {
+                    salsa::EventKind::WillExecute { database_key, .. } => {
+                        tracing::debug!("query cache miss (executing): {database_key:?}");
+                        metrics_for_callback.record(&database_key, true);
+                    }
+                    salsa::EventKind::DidValidateMemoizedValue { database_key } => {
+                        tracing::debug!("query cache hit (memoized): {database_key:?}");
+                        metrics_for_callback.record(&database_key, false);
+                    }
+                    salsa::EventKind::WillCheckCancellation => {
+                        graphql_base_db::cancellation::unwind_if_cancelled();
+                    }
+                    _ => {}
                 }
-                _ => {}
             }))),
             lint_config_input: None,
+            lint_plugins_input: None,
             #[cfg(feature = "extract")]
             extract_config_input: None,
+            nullability_config_input: None,
+            complexity_config_input: None,
+            validation_config_input: None,
+            operation_usage_input: None,
+            persisted_operations_input: None,
+            n_plus_one_config_input: None,
+            scalar_config_input: None,
+            scalar_formats_input: None,
+            analyzer_settings_input: None,
             project_files_input: None,
+            schema_variants_input: None,
+            query_metrics,
+            parse_cache_capacity: None,
+            structure_cache: None,
         };
 
         // Initialize with default configs as Salsa inputs
@@ -75,6 +262,24 @@ impl Default for IdeDatabase {
             &db,
             Arc::new(graphql_linter::LintConfig::default()),
         ));
+        db.nullability_config_input = Some(NullabilityConfigInput::new(&db, false));
+        db.complexity_config_input = Some(ComplexityConfigInput::new(
+            &db,
+            Arc::new(graphql_config::ComplexityConfig::default()),
+        ));
+        db.validation_config_input = Some(ValidationConfigInput::new(
+            &db,
+            Arc::new(graphql_config::ValidationConfig::default()),
+        ));
+        db.operation_usage_input = Some(OperationUsageInput::new(&db, None));
+        db.persisted_operations_input = Some(PersistedOperationsInput::new(&db, None));
+        db.n_plus_one_config_input = Some(NPlusOneConfigInput::new(&db, None));
+        db.scalar_config_input = Some(ScalarConfigInput::new(&db, Arc::new(BTreeMap::new())));
+        db.scalar_formats_input = Some(ScalarFormatsInput::new(&db, Arc::new(BTreeMap::new())));
+        db.analyzer_settings_input = Some(AnalyzerSettingsInput::new(
+            &db,
+            Arc::new(graphql_config::AnalyzerSettings::default()),
+        ));
         #[cfg(feature = "extract")]
         {
             db.extract_config_input = Some(ExtractConfigInput::new(
@@ -97,6 +302,11 @@ impl graphql_syntax::GraphQLSyntaxDatabase for IdeDatabase {
         self.extract_config_input
             .map(|input| input.config(self).clone())
     }
+
+    fn client_controlled_nullability(&self) -> bool {
+        self.nullability_config_input
+            .is_some_and(|input| input.enabled(self))
+    }
 }
 
 #[salsa::db]
@@ -104,6 +314,50 @@ impl graphql_hir::GraphQLHirDatabase for IdeDatabase {
     fn project_files(&self) -> Option<graphql_base_db::ProjectFiles> {
         self.project_files_input
     }
+
+    fn schema_variant_file_ids(&self) -> Option<graphql_base_db::SchemaVariantFileIds> {
+        self.schema_variants_input
+    }
+
+    fn structure_cache(&self) -> Option<&graphql_hir::StructureCache> {
+        self.structure_cache.as_deref()
+    }
+
+    fn operation_calls(
+        &self,
+        file_id: graphql_base_db::FileId,
+        operation_index: usize,
+    ) -> Option<graphql_hir::OperationCalls> {
+        let report = self.operation_usage_input?.report(self).clone()?;
+        let project_files = self.project_files_input?;
+        let (content, metadata) = graphql_base_db::file_lookup(self, project_files, file_id)?;
+
+        // Reports key operations by name when clients send one, and by
+        // persisted-query hash otherwise
+        let structure = graphql_hir::file_structure(self, file_id, content, metadata);
+        let by_name = structure
+            .operations
+            .iter()
+            .find(|op| op.index == operation_index)
+            .and_then(|op| op.name.as_deref())
+            .and_then(|name| report.operations.get(name).copied());
+        let count = by_name.or_else(|| {
+            let document = graphql_analysis::persisted_query_document(
+                self,
+                content,
+                metadata,
+                operation_index,
+                project_files,
+            )?;
+            let hash = graphql_analysis::persisted_query_hash(&document);
+            report.operations.get(&hash).copied()
+        });
+
+        Some(graphql_hir::OperationCalls {
+            count: count.unwrap_or(0),
+            window_days: report.window_days,
+        })
+    }
 }
 
 #[salsa::db]
@@ -114,4 +368,33 @@ impl graphql_analysis::GraphQLAnalysisDatabase for IdeDatabase {
             |input| input.config(self).clone(),
         )
     }
+
+    fn lint_plugins(&self) -> Arc<graphql_linter::LintPlugins> {
+        self.lint_plugins_input.map_or_else(
+            || Arc::new(graphql_linter::LintPlugins::default()),
+            |input| input.plugins(self).clone(),
+        )
+    }
+
+    fn validation_config(&self) -> Arc<graphql_config::ValidationConfig> {
+        self.validation_config_input.map_or_else(
+            || Arc::new(graphql_config::ValidationConfig::default()),
+            |input| input.config(self).clone(),
+        )
+    }
+
+    fn persisted_operations(&self) -> Option<Arc<graphql_config::PersistedOperationAllowList>> {
+        self.persisted_operations_input?.allow_list(self).clone()
+    }
+
+    fn n_plus_one_config(&self) -> Option<Arc<graphql_config::NPlusOneConfig>> {
+        self.n_plus_one_config_input?.config(self).clone()
+    }
+
+    fn scalar_formats(&self) -> Arc<BTreeMap<String, graphql_config::ScalarFormat>> {
+        self.scalar_formats_input.map_or_else(
+            || Arc::new(BTreeMap::new()),
+            |input| input.formats(self).clone(),
+        )
+    }
 }
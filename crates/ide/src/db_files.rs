@@ -9,12 +9,21 @@
 //! second lock that a snapshot can park on while the host's Salsa setter waits
 //! for the snapshot to drop.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use graphql_base_db::{FileContent, FileId, FileMetadata, ProjectFiles};
 
 use crate::FilePath;
 
+/// A file's path, content and metadata, looked up together.
+#[derive(Clone)]
+pub struct ResolvedFile {
+    pub path: FilePath,
+    pub content: FileContent,
+    pub metadata: FileMetadata,
+}
+
 /// Read-only view over the project's files, backed entirely by Salsa.
 ///
 /// Exposes the same `get_file_id` / `get_path` / `get_content` / `get_metadata`
@@ -53,6 +62,56 @@ impl<'a> DbFiles<'a> {
         graphql_base_db::file_lookup(self.db, pf, file_id).map(|(_, m)| m)
     }
 
+    /// Resolve path, content and metadata in one go. Prefer this over separate
+    /// `get_path` / `get_content` / `get_metadata` calls, which each repeat
+    /// the lookup.
+    pub fn resolve(&self, file_id: FileId) -> Option<ResolvedFile> {
+        let pf = self.project_files?;
+        let uri = graphql_base_db::uri_for_file_id(self.db, pf, file_id)?;
+        let (content, metadata) = graphql_base_db::file_lookup(self.db, pf, file_id)?;
+        Some(ResolvedFile {
+            path: FilePath::new(uri.as_ref().to_string()),
+            content,
+            metadata,
+        })
+    }
+
+    /// Resolve a batch of files for a feature loop, reading the path and entry
+    /// maps once rather than once per item. Files that aren't in the project
+    /// are left out; duplicate ids are resolved once.
+    ///
+    /// This depends on the whole file maps, so use it for project-wide
+    /// features (complexity, fragment usages), not single-file requests.
+    pub fn resolve_many(
+        &self,
+        file_ids: impl IntoIterator<Item = FileId>,
+    ) -> HashMap<FileId, ResolvedFile> {
+        let Some(pf) = self.project_files else {
+            return HashMap::new();
+        };
+        let id_to_uri = pf.file_path_map(self.db).id_to_uri(self.db);
+        let entries = pf.file_entry_map(self.db).entries(self.db);
+
+        let mut resolved = HashMap::new();
+        for file_id in file_ids {
+            if resolved.contains_key(&file_id) {
+                continue;
+            }
+            let (Some(uri), Some(entry)) = (id_to_uri.get(&file_id), entries.get(&file_id)) else {
+                continue;
+            };
+            resolved.insert(
+                file_id,
+                ResolvedFile {
+                    path: FilePath::new(uri.as_ref().to_string()),
+                    content: entry.content(self.db),
+                    metadata: entry.metadata(self.db),
+                },
+            );
+        }
+        resolved
+    }
+
     pub fn all_file_ids(&self) -> Vec<FileId> {
         match self.project_files {
             Some(pf) => graphql_base_db::all_file_ids(self.db, pf).as_ref().clone(),
@@ -0,0 +1,75 @@
+//! Project-wide report of deprecated schema elements in use.
+//!
+//! Walks every project document with the same traversal as the
+//! `noDeprecated` lint rule, but returns structured usages: the schema
+//! coordinate and deprecation reason of each element, located in its file.
+//! This works regardless of the lint config, so a report is available even
+//! where the rule is turned off.
+
+use crate::helpers::offset_range_to_range;
+use crate::types::{DeprecatedElementKind, DeprecatedUsage, Location};
+use crate::DbFiles;
+
+/// Every usage of a deprecated field, argument, enum value or input object
+/// field in the project's documents, ordered by file and position.
+pub fn deprecated_usages(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+) -> Vec<DeprecatedUsage> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+
+    let mut files: Vec<_> = doc_ids
+        .iter()
+        .filter_map(|file_id| Some((registry.get_path(*file_id)?, *file_id)))
+        .collect();
+    files.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    let mut usages = Vec::new();
+    for (file, file_id) in files {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, file_id)
+        else {
+            continue;
+        };
+        let parse = graphql_syntax::parse(db, content, metadata);
+        let line_index = graphql_syntax::LineIndex::new(&content.text(db));
+
+        for doc in parse.documents() {
+            for usage in graphql_linter::find_deprecated_usages(doc.tree, types) {
+                let (start, end) = usage.name_range;
+                let range = offset_range_to_range(
+                    &line_index,
+                    doc.byte_offset + start,
+                    doc.byte_offset + end,
+                );
+                usages.push(DeprecatedUsage {
+                    location: Location::new(file.clone(), range),
+                    kind: match usage.kind {
+                        graphql_linter::DeprecatedElementKind::Field => {
+                            DeprecatedElementKind::Field
+                        }
+                        graphql_linter::DeprecatedElementKind::Argument => {
+                            DeprecatedElementKind::Argument
+                        }
+                        graphql_linter::DeprecatedElementKind::EnumValue => {
+                            DeprecatedElementKind::EnumValue
+                        }
+                        graphql_linter::DeprecatedElementKind::InputField => {
+                            DeprecatedElementKind::InputField
+                        }
+                    },
+                    name: usage.name,
+                    coordinate: usage.coordinate,
+                    reason: usage.reason,
+                });
+            }
+        }
+    }
+
+    usages
+}
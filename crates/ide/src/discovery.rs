@@ -80,155 +80,85 @@ pub fn discover_document_files(
         return FileDiscoveryResult::default();
     };
 
-    let patterns: Vec<String> = documents_config
-        .patterns()
-        .into_iter()
-        .map(std::string::ToString::to_string)
-        .collect();
+    let globs = config.file_globs(&documents_config.patterns());
+    for error in globs.errors() {
+        tracing::error!("{error}");
+    }
+    let walk = globs.walk(workspace_path);
 
     let mut result = FileDiscoveryResult::default();
+    for graphql_config::GlobMatch { path, pattern } in walk.matches {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read file {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let path_str = path.display().to_string();
+        let (language, document_kind) = determine_document_file_kind(&path_str, &content);
+        let file_path = path_to_file_path(&path);
+
+        // Validate content matches expected kind (Executable)
+        // For TS/JS files, we need to extract GraphQL first
+        let graphql_content = if language.requires_extraction() {
+            #[cfg(feature = "extract")]
+            {
+                // Extract and concatenate all GraphQL blocks
+                graphql_extract::extract_from_source(&content, language, extract_config, &path_str)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|block| block.source.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            #[cfg(not(feature = "extract"))]
+            {
+                // No extractor available; treat as no GraphQL.
+                String::new()
+            }
+        } else {
+            content.clone()
+        };
 
-    for pattern in patterns {
-        // Skip negation patterns
-        if pattern.trim().starts_with('!') {
+        // Skip files that require extraction but contain no GraphQL
+        if language.requires_extraction() && graphql_content.is_empty() {
             continue;
         }
 
-        let expanded_patterns = expand_braces(&pattern);
-        let mut pattern_matched_any_files = false;
-
-        for expanded_pattern in expanded_patterns {
-            let full_pattern = workspace_path.join(&expanded_pattern);
-
-            match glob::glob(&full_pattern.display().to_string()) {
-                Ok(paths) => {
-                    for entry in paths {
-                        match entry {
-                            Ok(path) if path.is_file() => {
-                                // Skip node_modules
-                                if path.components().any(|c| c.as_os_str() == "node_modules") {
-                                    continue;
-                                }
-
-                                // Read file content
-                                match std::fs::read_to_string(&path) {
-                                    Ok(content) => {
-                                        let path_str = path.display().to_string();
-                                        let (language, document_kind) =
-                                            determine_document_file_kind(&path_str, &content);
-                                        let file_path = path_to_file_path(&path);
-
-                                        // Validate content matches expected kind (Executable)
-                                        // For TS/JS files, we need to extract GraphQL first
-                                        let graphql_content = if language.requires_extraction() {
-                                            #[cfg(feature = "extract")]
-                                            {
-                                                // Extract and concatenate all GraphQL blocks
-                                                graphql_extract::extract_from_source(
-                                                    &content,
-                                                    language,
-                                                    extract_config,
-                                                    &path_str,
-                                                )
-                                                .unwrap_or_default()
-                                                .iter()
-                                                .map(|block| block.source.as_str())
-                                                .collect::<Vec<_>>()
-                                                .join("\n")
-                                            }
-                                            #[cfg(not(feature = "extract"))]
-                                            {
-                                                // No extractor available; treat as no GraphQL.
-                                                String::new()
-                                            }
-                                        } else {
-                                            content.clone()
-                                        };
-
-                                        // Skip files that require extraction but contain no GraphQL
-                                        if language.requires_extraction()
-                                            && graphql_content.is_empty()
-                                        {
-                                            continue;
-                                        }
-
-                                        // Check for schema definitions in document files
-                                        if let Some(mismatch) =
-                                            graphql_syntax::validate_content_matches_kind(
-                                                &graphql_content,
-                                                DocumentKind::Executable,
-                                            )
-                                        {
-                                            let definitions = match mismatch {
-                                                graphql_syntax::ContentMismatch::ExpectedExecutableFoundSchema { definitions } => definitions,
-                                                graphql_syntax::ContentMismatch::ExpectedSchemaFoundExecutable { .. } => Vec::new(),
-                                            };
-                                            result.errors.push(ContentMismatchError {
-                                                pattern: pattern.clone(),
-                                                file_path: path.clone(),
-                                                expected: graphql_config::FileType::Document,
-                                                unexpected_definitions: definitions,
-                                            });
-                                        }
-
-                                        pattern_matched_any_files = true;
-                                        result.files.push(DiscoveredFile {
-                                            path: file_path,
-                                            content,
-                                            language,
-                                            document_kind,
-                                        });
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!(
-                                            "Failed to read file {}: {}",
-                                            path.display(),
-                                            e
-                                        );
-                                    }
-                                }
-                            }
-                            Ok(_) => {}
-                            Err(e) => {
-                                tracing::warn!("Glob entry error: {}", e);
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Invalid glob pattern '{}': {}", expanded_pattern, e);
+        // Check for schema definitions in document files
+        if let Some(mismatch) = graphql_syntax::validate_content_matches_kind(
+            &graphql_content,
+            DocumentKind::Executable,
+        ) {
+            let definitions = match mismatch {
+                graphql_syntax::ContentMismatch::ExpectedExecutableFoundSchema { definitions } => {
+                    definitions
                 }
-            }
+                graphql_syntax::ContentMismatch::ExpectedSchemaFoundExecutable { .. } => Vec::new(),
+            };
+            result.errors.push(ContentMismatchError {
+                pattern,
+                file_path: path.clone(),
+                expected: graphql_config::FileType::Document,
+                unexpected_definitions: definitions,
+            });
         }
 
-        if !pattern_matched_any_files {
-            tracing::debug!("Document pattern matched no files: {}", pattern);
-            result.unmatched_patterns.push(pattern.clone());
-        }
+        result.files.push(DiscoveredFile {
+            path: file_path,
+            content,
+            language,
+            document_kind,
+        });
     }
 
-    result
-}
-
-/// Expand brace patterns like `{ts,tsx}` into multiple patterns
-///
-/// This is needed because the glob crate doesn't support brace expansion.
-/// For example, `**/*.{ts,tsx}` expands to `["**/*.ts", "**/*.tsx"]`.
-pub(crate) fn expand_braces(pattern: &str) -> Vec<String> {
-    if let Some(start) = pattern.find('{') {
-        if let Some(end) = pattern.find('}') {
-            let before = &pattern[..start];
-            let after = &pattern[end + 1..];
-            let options = &pattern[start + 1..end];
-
-            return options
-                .split(',')
-                .map(|opt| format!("{before}{opt}{after}"))
-                .collect();
-        }
+    for pattern in walk.unmatched {
+        tracing::debug!("Document pattern matched no files: {}", pattern);
+        result.unmatched_patterns.push(pattern);
     }
 
-    vec![pattern.to_string()]
+    result
 }
 
 /// Check if a path has a given extension (case-insensitive)
@@ -242,6 +172,8 @@ pub(crate) fn has_extension(path: &str, ext: &str) -> bool {
 /// This is used for files loaded from the `documents` configuration.
 /// - `.ts`/`.tsx` files -> TypeScript
 /// - `.js`/`.jsx` files -> JavaScript
+/// - `.vue`/`.svelte` files -> Vue/Svelte (GraphQL comes from their `<script>` blocks)
+/// - `.py` files -> Python (`gql(...)` strings), `.rb` files -> Ruby (`<<~GRAPHQL` heredocs)
 /// - `.graphql`/`.gql` files -> `ExecutableGraphQL`
 ///
 /// Note: Files from the `schema` configuration are always `Language::GraphQL, DocumentKind::Schema`,
@@ -251,6 +183,14 @@ pub(crate) fn determine_document_file_kind(path: &str, _content: &str) -> (Langu
         (Language::TypeScript, DocumentKind::Executable)
     } else if has_extension(path, ".js") || has_extension(path, ".jsx") {
         (Language::JavaScript, DocumentKind::Executable)
+    } else if has_extension(path, ".vue") {
+        (Language::Vue, DocumentKind::Executable)
+    } else if has_extension(path, ".svelte") {
+        (Language::Svelte, DocumentKind::Executable)
+    } else if has_extension(path, ".py") {
+        (Language::Python, DocumentKind::Executable)
+    } else if has_extension(path, ".rb") {
+        (Language::Ruby, DocumentKind::Executable)
     } else {
         (Language::GraphQL, DocumentKind::Executable)
     }
@@ -335,4 +275,59 @@ export function add(a: number, b: number): number {
                 .collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn test_discover_document_files_single_file_components() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        std::fs::create_dir_all(&src_dir).unwrap();
+
+        std::fs::write(
+            src_dir.join("User.vue"),
+            r"<template><div /></template>
+<script setup lang='ts'>
+import { gql } from '@apollo/client';
+const query = gql`query GetUser { user { id } }`;
+</script>
+",
+        )
+        .unwrap();
+        std::fs::write(
+            src_dir.join("Post.svelte"),
+            r"<script>
+import { gql } from '@urql/svelte';
+const query = gql`query GetPost { post { id } }`;
+</script>
+<h1>Post</h1>
+",
+        )
+        .unwrap();
+
+        let config = graphql_config::ProjectConfig::new(
+            graphql_config::SchemaConfig::Path("schema.graphql".to_string()),
+            Some(graphql_config::DocumentsConfig::Patterns(vec![
+                "src/**/*.{vue,svelte}".to_string(),
+            ])),
+            None,
+            None,
+            None,
+        );
+
+        let extract_config = graphql_extract::ExtractConfig::default();
+        let result = discover_document_files(&config, temp_dir.path(), &extract_config);
+
+        let mut languages: Vec<_> = result
+            .files
+            .iter()
+            .map(|f| (f.language, f.document_kind))
+            .collect();
+        languages.sort_by_key(|(language, _)| format!("{language:?}"));
+        assert_eq!(
+            languages,
+            vec![
+                (Language::Svelte, DocumentKind::Executable),
+                (Language::Vue, DocumentKind::Executable),
+            ]
+        );
+    }
 }
@@ -0,0 +1,226 @@
+//! Document highlight feature implementation.
+//!
+//! Highlights the occurrences of the symbol under the cursor in the same
+//! file. Unlike find references, this only looks at the file's syntax:
+//! - Variables: the definition and usages in the enclosing operation or
+//!   fragment
+//! - Fragments: the definition and every spread, across all blocks
+//! - Fields: fields merged into the same response key, so repeated aliases
+//!   line up
+
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::{SyntaxKind, SyntaxNode, TextRange};
+
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
+use crate::types::{DocumentHighlight, DocumentHighlightKind, FilePath, Position};
+use crate::DbFiles;
+
+/// Get the highlights for the symbol at a position.
+///
+/// Returns an empty list when the cursor isn't on a variable, fragment name,
+/// field name or alias.
+pub fn document_highlights(
+    db: &dyn graphql_syntax::GraphQLSyntaxDatabase,
+    registry: DbFiles<'_>,
+    file: &FilePath,
+    position: Position,
+) -> Vec<DocumentHighlight> {
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let (Some(content), Some(metadata)) = (
+        registry.get_content(file_id),
+        registry.get_metadata(file_id),
+    ) else {
+        return Vec::new();
+    };
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let Some((block_context, adjusted_position)) = find_block_for_position(&parse, position) else {
+        return Vec::new();
+    };
+    let line_index = block_context.line_index();
+    let Some(offset) = position_to_offset(&line_index, adjusted_position) else {
+        return Vec::new();
+    };
+    let Some(name) = name_at_offset(block_context.tree, offset) else {
+        return Vec::new();
+    };
+    let Some(owner) = name.syntax().parent() else {
+        return Vec::new();
+    };
+
+    // Fragments can be spread from any block in the file
+    if owner.kind() == SyntaxKind::FRAGMENT_NAME {
+        return parse
+            .documents()
+            .flat_map(|doc| {
+                let line_index = doc.line_index();
+                fragment_occurrences(doc.tree, &name.text())
+                    .into_iter()
+                    .map(move |(range, kind)| {
+                        let range = offset_range_to_range(
+                            &line_index,
+                            range.start().into(),
+                            range.end().into(),
+                        );
+                        DocumentHighlight::new(adjust_range_for_block(range, doc.origin()), kind)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+    }
+
+    let occurrences = match owner.kind() {
+        SyntaxKind::VARIABLE => variable_occurrences(&owner, &name.text()),
+        SyntaxKind::FIELD | SyntaxKind::ALIAS => {
+            let field = if owner.kind() == SyntaxKind::ALIAS {
+                owner.parent().and_then(cst::Field::cast)
+            } else {
+                cst::Field::cast(owner)
+            };
+            field
+                .map(|field| field_occurrences(&field))
+                .unwrap_or_default()
+        }
+        _ => Vec::new(),
+    };
+
+    occurrences
+        .into_iter()
+        .map(|(range, kind)| {
+            let range =
+                offset_range_to_range(&line_index, range.start().into(), range.end().into());
+            DocumentHighlight::new(adjust_range_for_block(range, block_context.origin()), kind)
+        })
+        .collect()
+}
+
+/// The name whose identifier contains or ends at `offset`
+fn name_at_offset(tree: &apollo_parser::SyntaxTree, offset: usize) -> Option<cst::Name> {
+    tree.document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::Name::cast)
+        .find(|name| {
+            let range = name.syntax().text_range();
+            offset >= usize::from(range.start()) && offset <= usize::from(range.end())
+        })
+}
+
+/// The variable's definition and usages in the enclosing operation, or its
+/// usages in the enclosing fragment
+fn variable_occurrences(
+    variable: &SyntaxNode,
+    name: &str,
+) -> Vec<(TextRange, DocumentHighlightKind)> {
+    let Some(scope) = variable.ancestors().find(|node| {
+        matches!(
+            node.kind(),
+            SyntaxKind::OPERATION_DEFINITION | SyntaxKind::FRAGMENT_DEFINITION
+        )
+    }) else {
+        return Vec::new();
+    };
+
+    scope
+        .descendants()
+        .filter_map(cst::Variable::cast)
+        .filter(|variable| variable.name().is_some_and(|n| n.text() == name))
+        .map(|variable| {
+            let is_definition = variable
+                .syntax()
+                .parent()
+                .is_some_and(|parent| parent.kind() == SyntaxKind::VARIABLE_DEFINITION);
+            let kind = if is_definition {
+                DocumentHighlightKind::Write
+            } else {
+                DocumentHighlightKind::Read
+            };
+            (variable.syntax().text_range(), kind)
+        })
+        .collect()
+}
+
+/// The fragment's definition name and the names in its spreads
+fn fragment_occurrences(
+    tree: &apollo_parser::SyntaxTree,
+    name: &str,
+) -> Vec<(TextRange, DocumentHighlightKind)> {
+    tree.document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::FragmentName::cast)
+        .filter_map(|fragment_name| {
+            let name_node = fragment_name.name().filter(|n| n.text() == name)?;
+            let is_definition = fragment_name
+                .syntax()
+                .parent()
+                .is_some_and(|parent| parent.kind() == SyntaxKind::FRAGMENT_DEFINITION);
+            let kind = if is_definition {
+                DocumentHighlightKind::Write
+            } else {
+                DocumentHighlightKind::Read
+            };
+            Some((name_node.syntax().text_range(), kind))
+        })
+        .collect()
+}
+
+/// Fields sharing `field`'s response key in its selection set, including
+/// those in inline fragments that merge into it
+fn field_occurrences(field: &cst::Field) -> Vec<(TextRange, DocumentHighlightKind)> {
+    let Some(key) = response_key(field) else {
+        return Vec::new();
+    };
+
+    // Inline fragments merge into the selection set that contains them
+    let mut scope = field.syntax().parent().and_then(cst::SelectionSet::cast);
+    while let Some(outer) = scope
+        .as_ref()
+        .and_then(|set| set.syntax().parent())
+        .filter(|parent| parent.kind() == SyntaxKind::INLINE_FRAGMENT)
+        .and_then(|fragment| fragment.parent())
+        .and_then(cst::SelectionSet::cast)
+    {
+        scope = Some(outer);
+    }
+
+    let mut occurrences = Vec::new();
+    if let Some(scope) = scope {
+        collect_response_key(&scope, &key, &mut occurrences);
+    }
+    occurrences
+}
+
+fn collect_response_key(
+    selection_set: &cst::SelectionSet,
+    key: &cst::Name,
+    occurrences: &mut Vec<(TextRange, DocumentHighlightKind)>,
+) {
+    for selection in selection_set.selections() {
+        match selection {
+            cst::Selection::Field(field) => {
+                if let Some(name) = response_key(&field).filter(|name| name.text() == key.text()) {
+                    occurrences.push((name.syntax().text_range(), DocumentHighlightKind::Text));
+                }
+            }
+            cst::Selection::InlineFragment(fragment) => {
+                if let Some(nested) = fragment.selection_set() {
+                    collect_response_key(&nested, key, occurrences);
+                }
+            }
+            cst::Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// The alias if there is one, otherwise the field name
+fn response_key(field: &cst::Field) -> Option<cst::Name> {
+    field
+        .alias()
+        .and_then(|alias| alias.name())
+        .or_else(|| field.name())
+}
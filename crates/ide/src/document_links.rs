@@ -0,0 +1,239 @@
+//! Document links feature implementation.
+//!
+//! Turns fragment spreads and named type references (fragment and inline
+//! fragment type conditions, variable types) in executable documents into
+//! links to their definitions. Some clients handle document links in
+//! embedded GraphQL better than goto definition.
+
+use std::collections::HashMap;
+
+use apollo_parser::cst::{self, CstNode};
+
+use crate::helpers::{
+    adjust_range_for_block, find_fragment_definition_in_parse, offset_range_to_range,
+};
+use crate::types::{DocumentLink, FilePath, Location};
+use crate::DbFiles;
+
+/// Get document links for an executable document.
+pub fn document_links(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+) -> Vec<DocumentLink> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let (Some(content), Some(metadata)) = (
+        registry.get_content(file_id),
+        registry.get_metadata(file_id),
+    ) else {
+        return Vec::new();
+    };
+    if !metadata.is_document(db) {
+        return Vec::new();
+    }
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let mut resolver = TargetResolver {
+        db,
+        registry,
+        project_files,
+        fragments: HashMap::new(),
+        types: HashMap::new(),
+    };
+    let mut links = Vec::new();
+
+    for doc in parse.documents() {
+        let mut names = Vec::new();
+        for definition in doc.tree.document().definitions() {
+            collect_definition_names(&definition, &mut names);
+        }
+
+        let line_index = doc.line_index();
+        for name in names {
+            let target = match name.kind {
+                NameKind::Fragment => resolver.fragment(&name.text),
+                NameKind::Type => resolver.type_definition(&name.text),
+            };
+            if let Some(target) = target {
+                links.push(DocumentLink {
+                    range: adjust_range_for_block(
+                        offset_range_to_range(&line_index, name.start, name.end),
+                        doc.origin(),
+                    ),
+                    target,
+                });
+            }
+        }
+    }
+
+    links
+}
+
+enum NameKind {
+    Fragment,
+    Type,
+}
+
+struct LinkedName {
+    kind: NameKind,
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+impl LinkedName {
+    fn new(kind: NameKind, name: &cst::Name) -> Self {
+        let range = name.syntax().text_range();
+        Self {
+            kind,
+            text: name.text().to_string(),
+            start: range.start().into(),
+            end: range.end().into(),
+        }
+    }
+}
+
+fn collect_definition_names(definition: &cst::Definition, names: &mut Vec<LinkedName>) {
+    match definition {
+        cst::Definition::OperationDefinition(op) => {
+            if let Some(variable_definitions) = op.variable_definitions() {
+                for variable_def in variable_definitions.variable_definitions() {
+                    if let Some(ty) = variable_def.ty() {
+                        collect_type_name(&ty, names);
+                    }
+                }
+            }
+            if let Some(selection_set) = op.selection_set() {
+                collect_selection_names(&selection_set, names);
+            }
+        }
+        cst::Definition::FragmentDefinition(frag) => {
+            collect_type_condition(frag.type_condition(), names);
+            if let Some(selection_set) = frag.selection_set() {
+                collect_selection_names(&selection_set, names);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_selection_names(selection_set: &cst::SelectionSet, names: &mut Vec<LinkedName>) {
+    for selection in selection_set.selections() {
+        match selection {
+            cst::Selection::Field(field) => {
+                if let Some(nested) = field.selection_set() {
+                    collect_selection_names(&nested, names);
+                }
+            }
+            cst::Selection::FragmentSpread(spread) => {
+                if let Some(name) = spread.fragment_name().and_then(|f| f.name()) {
+                    names.push(LinkedName::new(NameKind::Fragment, &name));
+                }
+            }
+            cst::Selection::InlineFragment(inline) => {
+                collect_type_condition(inline.type_condition(), names);
+                if let Some(nested) = inline.selection_set() {
+                    collect_selection_names(&nested, names);
+                }
+            }
+        }
+    }
+}
+
+fn collect_type_condition(type_condition: Option<cst::TypeCondition>, names: &mut Vec<LinkedName>) {
+    if let Some(name) = type_condition
+        .and_then(|tc| tc.named_type())
+        .and_then(|nt| nt.name())
+    {
+        names.push(LinkedName::new(NameKind::Type, &name));
+    }
+}
+
+fn collect_type_name(ty: &cst::Type, names: &mut Vec<LinkedName>) {
+    match ty {
+        cst::Type::NamedType(named) => {
+            if let Some(name) = named.name() {
+                names.push(LinkedName::new(NameKind::Type, &name));
+            }
+        }
+        cst::Type::ListType(list) => {
+            if let Some(inner) = list.ty() {
+                collect_type_name(&inner, names);
+            }
+        }
+        cst::Type::NonNullType(non_null) => {
+            if let Some(name) = non_null.named_type().and_then(|nt| nt.name()) {
+                names.push(LinkedName::new(NameKind::Type, &name));
+            } else if let Some(inner) = non_null.list_type().and_then(|list| list.ty()) {
+                collect_type_name(&inner, names);
+            }
+        }
+    }
+}
+
+/// Resolves link targets, caching by name since a document usually spreads
+/// the same fragment or names the same type more than once.
+struct TargetResolver<'a> {
+    db: &'a dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'a>,
+    project_files: graphql_base_db::ProjectFiles,
+    fragments: HashMap<String, Option<Location>>,
+    types: HashMap<String, Option<Location>>,
+}
+
+impl TargetResolver<'_> {
+    fn fragment(&mut self, name: &str) -> Option<Location> {
+        if let Some(cached) = self.fragments.get(name) {
+            return cached.clone();
+        }
+        let location = graphql_hir::all_fragments(self.db, self.project_files)
+            .get(name)
+            .and_then(|fragment| {
+                let file = self.registry.resolve(fragment.file_id)?;
+                let parse = graphql_syntax::parse(self.db, file.content, file.metadata);
+                let range = find_fragment_definition_in_parse(&parse, name)?;
+                Some(Location::new(file.path, range))
+            });
+        self.fragments.insert(name.to_string(), location.clone());
+        location
+    }
+
+    /// The type's first source definition, falling back to the resolved
+    /// schema like goto definition does.
+    fn type_definition(&mut self, name: &str) -> Option<Location> {
+        if let Some(cached) = self.types.get(name) {
+            return cached.clone();
+        }
+        let type_index = graphql_hir::type_definition_location_index(self.db, self.project_files);
+        let location = type_index
+            .get(name)
+            .and_then(|entries| entries.first().copied())
+            .or_else(|| {
+                if !graphql_hir::has_resolved_schema(self.db, self.project_files) {
+                    return None;
+                }
+                let types = graphql_hir::schema_types(self.db, self.project_files);
+                types
+                    .get(name)
+                    .map(|type_def| (type_def.file_id, type_def.name_range))
+            })
+            .and_then(|(file_id, name_range)| {
+                let file = self.registry.resolve(file_id)?;
+                let line_index = graphql_syntax::line_index(self.db, file.content);
+                let range = offset_range_to_range(
+                    &line_index,
+                    name_range.start().into(),
+                    name_range.end().into(),
+                );
+                Some(Location::new(file.path, range))
+            });
+        self.types.insert(name.to_string(), location.clone());
+        location
+    }
+}
@@ -0,0 +1,129 @@
+//! Merging the edits of several fixes into a single change set.
+//!
+//! Each fix is added atomically: either all of its edits are accepted or
+//! none are. A fix whose edits touch a range already claimed by an accepted
+//! fix in the same file is rejected, so the merged result can be applied in
+//! one go without one fix corrupting another.
+
+use std::collections::HashMap;
+
+use crate::types::{FilePath, Position, Range, TextEdit};
+
+/// Accumulates non-conflicting edits per file.
+#[derive(Debug, Default)]
+pub struct EditMerger {
+    changes: HashMap<FilePath, Vec<TextEdit>>,
+}
+
+impl EditMerger {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the edits of one fix to `file`.
+    ///
+    /// Returns `false`, leaving the merger unchanged, if any edit conflicts
+    /// with one accepted earlier.
+    pub fn try_add(&mut self, file: &FilePath, edits: &[TextEdit]) -> bool {
+        if let Some(accepted) = self.changes.get(file) {
+            let conflict = edits.iter().any(|edit| {
+                accepted
+                    .iter()
+                    .any(|other| ranges_conflict(edit.range, other.range))
+            });
+            if conflict {
+                return false;
+            }
+        }
+        self.changes
+            .entry(file.clone())
+            .or_default()
+            .extend(edits.iter().cloned());
+        true
+    }
+
+    /// The accepted edits per file, in document order.
+    pub fn into_changes(self) -> HashMap<FilePath, Vec<TextEdit>> {
+        self.changes
+            .into_iter()
+            .map(|(file, mut edits)| {
+                edits.sort_by_key(|e| (key(e.range.start), key(e.range.end)));
+                (file, edits)
+            })
+            .collect()
+    }
+}
+
+const fn key(position: Position) -> (u32, u32) {
+    (position.line, position.character)
+}
+
+/// Whether two edits can't both be applied unambiguously.
+///
+/// Replacements conflict when they overlap; touching end to start is fine.
+/// An insertion conflicts with any edit sharing its position, since the
+/// order the two land in would be up to the client.
+fn ranges_conflict(a: Range, b: Range) -> bool {
+    let (a_start, a_end) = (key(a.start), key(a.end));
+    let (b_start, b_end) = (key(b.start), key(b.end));
+    if a_start == a_end || b_start == b_end {
+        a_start <= b_end && b_start <= a_end
+    } else {
+        a_start < b_end && b_start < a_end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: (u32, u32), end: (u32, u32), text: &str) -> TextEdit {
+        TextEdit::new(
+            Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+            text,
+        )
+    }
+
+    #[test]
+    fn test_disjoint_fixes_merge_in_document_order() {
+        let file = FilePath::new("file:///a.graphql");
+        let mut merger = EditMerger::new();
+        assert!(merger.try_add(&file, &[edit((2, 0), (2, 4), "")]));
+        assert!(merger.try_add(&file, &[edit((0, 0), (0, 4), "")]));
+        // Adjacent replacements don't overlap
+        assert!(merger.try_add(&file, &[edit((0, 4), (0, 8), "x")]));
+
+        let changes = merger.into_changes();
+        let starts: Vec<_> = changes[&file].iter().map(|e| e.range.start).collect();
+        assert_eq!(
+            starts,
+            vec![
+                Position::new(0, 0),
+                Position::new(0, 4),
+                Position::new(2, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_conflicting_fix_is_rejected_whole() {
+        let file = FilePath::new("file:///a.graphql");
+        let mut merger = EditMerger::new();
+        assert!(merger.try_add(&file, &[edit((1, 0), (1, 10), "")]));
+        // The second edit overlaps, so the first one isn't kept either
+        assert!(!merger.try_add(
+            &file,
+            &[edit((3, 0), (3, 1), ""), edit((1, 5), (1, 12), "")]
+        ));
+        // Inserting where another edit starts is ambiguous
+        assert!(!merger.try_add(&file, &[edit((1, 0), (1, 0), "x")]));
+        // Other files are independent
+        let other = FilePath::new("file:///b.graphql");
+        assert!(merger.try_add(&other, &[edit((1, 0), (1, 10), "")]));
+
+        let changes = merger.into_changes();
+        assert_eq!(changes[&file].len(), 1);
+        assert_eq!(changes[&other].len(), 1);
+    }
+}
@@ -0,0 +1,288 @@
+//! Moving client documents off a field onto its replacement.
+//!
+//! When `Type.oldField` is superseded by `Type.newField`, every selection of
+//! the old field is rewritten to select the new one, on the type itself and,
+//! for an interface field, on the types implementing it. Both fields exist,
+//! so unlike a [`SchemaChangeManifest`](crate::SchemaChangeManifest) rename,
+//! each usage is checked against the new field and left alone when the
+//! rewrite would change what the operation asks for:
+//!
+//! - an unaliased selection whose new response key is already selected in
+//!   the same selection set
+//! - an argument the new field doesn't accept, or accepts with another type
+//! - a required argument of the new field that isn't passed
+//! - nested selections under a field whose return type changes
+//!
+//! These are reported as [`UnmigratedUsage`]s. Aliased selections keep
+//! their response key; unaliased ones change it along with the field.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use graphql_hir::{FieldSignature, TypeDef};
+
+use crate::helpers::{
+    adjust_range_for_block, format_type_ref, offset_range_to_range, type_matches_or_implements,
+};
+use crate::types::{FilePath, Location, MigrationResult, TextEdit, UnmigratedUsage};
+use crate::DbFiles;
+
+/// Compute the edits that move every selection of `type_name.old_field` to
+/// `new_field`.
+///
+/// Returns `None` when either field isn't defined on the type.
+pub fn migrate_field(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    type_name: &str,
+    old_field: &str,
+    new_field: &str,
+) -> Option<MigrationResult> {
+    let project_files = project_files?;
+    let types = graphql_hir::schema_types(db, project_files);
+    let type_def = types.get(type_name)?;
+    let find = |name: &str| type_def.fields.iter().find(|f| f.name.as_ref() == name);
+    let (old_def, new_def) = (find(old_field)?, find(new_field)?);
+
+    let mut result = MigrationResult::default();
+    if old_field == new_field {
+        return Some(result);
+    }
+
+    // Only files selecting the old field, on the type or an implementor
+    let old_name: Arc<str> = Arc::from(old_field);
+    let mut targets = vec![graphql_hir::SchemaCoordinate {
+        type_name: type_def.name.clone(),
+        field_name: old_name.clone(),
+    }];
+    if type_def.kind == graphql_hir::TypeDefKind::Interface {
+        let implementors = graphql_hir::interface_implementors(db, project_files);
+        for implementor in implementors.get(&type_def.name).into_iter().flatten() {
+            targets.push(graphql_hir::SchemaCoordinate {
+                type_name: implementor.clone(),
+                field_name: old_name.clone(),
+            });
+        }
+    }
+
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+    for file_id in doc_ids.iter() {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        else {
+            continue;
+        };
+        let coordinates =
+            graphql_hir::file_schema_coordinates(db, *file_id, content, metadata, project_files);
+        if !targets.iter().any(|target| coordinates.contains(target)) {
+            continue;
+        }
+        let Some(file) = registry.get_path(*file_id) else {
+            continue;
+        };
+
+        let parse = graphql_syntax::parse(db, content, metadata);
+        let mut migrator = FieldMigrator {
+            types,
+            type_name,
+            old_def,
+            new_def,
+            file: &file,
+            line_index: None,
+            origin: graphql_syntax::BlockOrigin::default(),
+            edits: Vec::new(),
+            unmigrated: Vec::new(),
+        };
+        for doc in parse.documents() {
+            migrator.line_index = Some(doc.line_index());
+            migrator.origin = doc.origin();
+            migrator.migrate_document(doc.tree);
+        }
+
+        if !migrator.edits.is_empty() {
+            result.changes.insert(file.clone(), migrator.edits);
+        }
+        result.unmigrated.extend(migrator.unmigrated);
+    }
+
+    Some(result)
+}
+
+struct FieldMigrator<'a> {
+    types: &'a HashMap<Arc<str>, TypeDef>,
+    type_name: &'a str,
+    old_def: &'a FieldSignature,
+    new_def: &'a FieldSignature,
+    file: &'a FilePath,
+    line_index: Option<graphql_syntax::LineIndex>,
+    origin: graphql_syntax::BlockOrigin,
+    edits: Vec<TextEdit>,
+    unmigrated: Vec<UnmigratedUsage>,
+}
+
+impl FieldMigrator<'_> {
+    fn migrate_document(&mut self, tree: &apollo_parser::SyntaxTree) {
+        for definition in tree.document().definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(op) => {
+                    let root = op.operation_kind().root_type_name();
+                    if let Some(selection_set) = op.selection_set() {
+                        self.migrate_selection_set(&selection_set, root);
+                    }
+                }
+                cst::Definition::FragmentDefinition(fragment) => {
+                    let type_condition = fragment
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map(|name| name.text().to_string());
+                    if let (Some(type_condition), Some(selection_set)) =
+                        (type_condition, fragment.selection_set())
+                    {
+                        self.migrate_selection_set(&selection_set, &type_condition);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn migrate_selection_set(&mut self, selection_set: &cst::SelectionSet, parent: &str) {
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let Some(name) = field.name() else {
+                        continue;
+                    };
+                    let field_name = name.text().to_string();
+                    if field_name == self.old_def.name.as_ref()
+                        && type_matches_or_implements(parent, self.type_name, self.types)
+                    {
+                        self.migrate_usage(&field, &name, selection_set);
+                    }
+
+                    let field_type = self
+                        .types
+                        .get(parent)
+                        .and_then(|t| t.fields.iter().find(|f| f.name.as_ref() == field_name))
+                        .map(|f| f.type_ref.name.clone());
+                    if let (Some(field_type), Some(nested)) = (field_type, field.selection_set()) {
+                        self.migrate_selection_set(&nested, &field_type);
+                    }
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map_or_else(|| parent.to_string(), |name| name.text().to_string());
+                    if let Some(nested) = inline.selection_set() {
+                        self.migrate_selection_set(&nested, &condition);
+                    }
+                }
+                cst::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn migrate_usage(
+        &mut self,
+        field: &cst::Field,
+        name: &cst::Name,
+        selection_set: &cst::SelectionSet,
+    ) {
+        let (old_def, new_def) = (self.old_def, self.new_def);
+        let new_name = new_def.name.as_ref();
+        let mut problems = Vec::new();
+
+        if field.alias().is_none() {
+            let range = field.syntax().text_range();
+            let key_taken = selection_set.selections().any(|selection| {
+                let cst::Selection::Field(sibling) = selection else {
+                    return false;
+                };
+                let key = sibling
+                    .alias()
+                    .and_then(|alias| alias.name())
+                    .or_else(|| sibling.name());
+                sibling.syntax().text_range() != range
+                    && key.is_some_and(|key| key.text() == new_name)
+            });
+            if key_taken {
+                problems.push(format!(
+                    "`{new_name}` is already selected here, so one of them needs an alias"
+                ));
+            }
+        }
+
+        let mut passed = Vec::new();
+        for argument in field.arguments().iter().flat_map(cst::Arguments::arguments) {
+            let Some(arg_name) = argument.name() else {
+                continue;
+            };
+            let arg_name = arg_name.text().to_string();
+            let find = |def: &FieldSignature| {
+                def.arguments
+                    .iter()
+                    .find(|a| a.name.as_ref() == arg_name)
+                    .map(|a| format_type_ref(&a.type_ref))
+            };
+            match (find(old_def), find(new_def)) {
+                (_, None) => problems.push(format!("`{new_name}` has no `{arg_name}` argument")),
+                (Some(old_type), Some(new_type)) if old_type != new_type => {
+                    problems.push(format!(
+                        "`{arg_name}` is `{old_type}` on `{}` but `{new_type}` on `{new_name}`",
+                        old_def.name
+                    ));
+                }
+                _ => {}
+            }
+            passed.push(arg_name);
+        }
+        for argument in &new_def.arguments {
+            let required = argument.type_ref.is_non_null && argument.default_value.is_none();
+            if required && !passed.iter().any(|p| p == argument.name.as_ref()) {
+                problems.push(format!(
+                    "`{new_name}` requires `{}: {}`",
+                    argument.name,
+                    format_type_ref(&argument.type_ref)
+                ));
+            }
+        }
+
+        if field.selection_set().is_some() && old_def.type_ref.name != new_def.type_ref.name {
+            problems.push(format!(
+                "`{new_name}` returns `{}` rather than `{}`, so the nested selections need updating",
+                new_def.type_ref.name, old_def.type_ref.name
+            ));
+        }
+
+        let Some(location) = self.location(name.syntax()) else {
+            return;
+        };
+        if problems.is_empty() {
+            self.edits.push(TextEdit::new(location.range, new_name));
+        } else {
+            self.unmigrated.push(UnmigratedUsage {
+                location,
+                message: format!(
+                    "Can't move `{}` to `{new_name}`: {}",
+                    old_def.name,
+                    problems.join("; ")
+                ),
+            });
+        }
+    }
+
+    fn location(&self, node: &apollo_parser::SyntaxNode) -> Option<Location> {
+        let line_index = self.line_index.as_ref()?;
+        let range = node.text_range();
+        let range = offset_range_to_range(line_index, range.start().into(), range.end().into());
+        Some(Location::new(
+            self.file.clone(),
+            adjust_range_for_block(range, self.origin),
+        ))
+    }
+}
@@ -14,10 +14,25 @@
 //!
 //! This is a significant improvement over the old `FileMap` approach where
 //! any file change would invalidate queries for ALL files.
+//!
+//! ## Snapshots
+//!
+//! `Analysis` snapshots never see the registry. Everything they look up lives
+//! in Salsa inputs whose tables are immutable `Arc`s: a rebuild swaps in new
+//! tables rather than mutating the old ones, so cloning the database captures
+//! the file set by value in O(1). The registry is the host's private, mutable
+//! index for building those tables.
+//!
+//! The path and entry tables are themselves held in `Arc`s and handed to the
+//! `FilePathMap` and `FileEntryMap` inputs as-is, so a rebuild costs O(1) in
+//! the number of files. The registry writes through `Arc::make_mut`: the
+//! first add, remove or rename after a rebuild copies the table once, and
+//! the copy the inputs hold stays untouched.
 
 use graphql_base_db::{
     DocumentFileIds, DocumentKind, FileContent, FileEntry, FileEntryMap, FileId, FileMetadata,
     FilePathMap, FileUri, Language, ProjectFiles, ResolvedSchemaFileIds, SchemaFileIds,
+    SchemaVariantFileIds,
 };
 use salsa::Setter;
 use std::collections::HashMap;
@@ -34,12 +49,15 @@ use crate::FilePath;
 #[derive(Default)]
 pub struct FileRegistry {
     next_id: u32,
-    uri_to_id: HashMap<String, FileId>,
-    id_to_uri: HashMap<FileId, String>,
+    /// Shared with the `FilePathMap` input
+    uri_to_id: Arc<HashMap<Arc<str>, FileId>>,
+    /// Shared with the `FilePathMap` input
+    id_to_uri: Arc<HashMap<FileId, Arc<str>>>,
     id_to_content: HashMap<FileId, FileContent>,
     id_to_metadata: HashMap<FileId, FileMetadata>,
-    /// Per-file `FileEntry` for granular invalidation
-    id_to_entry: HashMap<FileId, FileEntry>,
+    /// Per-file `FileEntry` for granular invalidation, shared with the
+    /// `FileEntryMap` input
+    id_to_entry: Arc<HashMap<FileId, FileEntry>>,
     /// Granular input tracking schema file IDs only - changes on file add/remove
     schema_file_ids: Option<SchemaFileIds>,
     /// Granular input tracking document file IDs only - changes on file add/remove
@@ -55,6 +73,27 @@ pub struct FileRegistry {
     project_files: Option<ProjectFiles>,
     /// File IDs that belong to the resolved schema (not the source schema)
     resolved_file_ids: std::collections::HashSet<FileId>,
+    /// Granular input tracking schema variant file IDs
+    schema_variant_file_ids: Option<SchemaVariantFileIds>,
+    /// File IDs that belong to a named schema variant (not the source schema)
+    variant_file_ids: HashMap<FileId, Arc<str>>,
+    /// The documents assigned to each schema variant, in the order set
+    variant_documents: Vec<VariantDocuments>,
+}
+
+/// The `documents` patterns of a schema variant
+struct VariantDocuments {
+    variant: Arc<str>,
+    /// URI of the directory the patterns are relative to, ending in `/`
+    base_uri: String,
+    globs: graphql_config::FileGlobs,
+}
+
+impl VariantDocuments {
+    fn matches(&self, uri: &str) -> bool {
+        uri.strip_prefix(&self.base_uri)
+            .is_some_and(|rel_path| self.globs.is_match(rel_path))
+    }
 }
 
 impl FileRegistry {
@@ -87,7 +126,6 @@ impl FileRegistry {
         DB: salsa::Database,
     {
         let uri_str = path.as_str();
-        let content_arc: Arc<str> = Arc::from(content);
 
         // Check if file already exists
         if let Some(&existing_id) = self.uri_to_id.get(uri_str) {
@@ -96,8 +134,9 @@ impl FileRegistry {
             // identical content would invalidate all dependent queries and trigger
             // expensive recomputation across the project.
             if let Some(&existing_content) = self.id_to_content.get(&existing_id) {
-                if *existing_content.text(db) != *content_arc {
-                    existing_content.set_text(db).to(content_arc);
+                // Compare before allocating so unchanged saves don't copy the text
+                if *existing_content.text(db) != *content {
+                    existing_content.set_text(db).to(Arc::from(content));
                 }
 
                 // Update metadata if needed (language or document_kind changed)
@@ -120,11 +159,12 @@ impl FileRegistry {
         // New file - create new FileId
         let file_id = FileId::new(self.next_id);
         self.next_id += 1;
-        self.uri_to_id.insert(uri_str.to_string(), file_id);
-        self.id_to_uri.insert(file_id, uri_str.to_string());
+        let shared_uri: Arc<str> = Arc::from(uri_str);
+        Arc::make_mut(&mut self.uri_to_id).insert(shared_uri.clone(), file_id);
+        Arc::make_mut(&mut self.id_to_uri).insert(file_id, shared_uri);
 
         // Create new FileContent
-        let file_content = FileContent::new(db, content_arc);
+        let file_content = FileContent::new(db, Arc::from(content));
         self.id_to_content.insert(file_id, file_content);
 
         // Create new FileMetadata
@@ -134,7 +174,7 @@ impl FileRegistry {
 
         // Create new FileEntry (for granular caching)
         let file_entry = FileEntry::new(db, file_content, metadata);
-        self.id_to_entry.insert(file_id, file_entry);
+        Arc::make_mut(&mut self.id_to_entry).insert(file_id, file_entry);
 
         (file_id, file_content, metadata, true)
     }
@@ -150,16 +190,49 @@ impl FileRegistry {
     pub fn get_path(&self, file_id: FileId) -> Option<FilePath> {
         self.id_to_uri
             .get(&file_id)
-            .map(|s| FilePath::new(s.clone()))
+            .map(|s| FilePath::new(s.to_string()))
     }
 
     /// Remove a file from the registry
     pub fn remove_file(&mut self, file_id: FileId) {
-        if let Some(uri) = self.id_to_uri.remove(&file_id) {
-            self.uri_to_id.remove(&uri);
-        }
+        // Checked first so removing an unknown file doesn't copy the tables
+        let Some(uri) = self.id_to_uri.get(&file_id).cloned() else {
+            return;
+        };
+        Arc::make_mut(&mut self.id_to_uri).remove(&file_id);
+        Arc::make_mut(&mut self.uri_to_id).remove(&uri);
         self.id_to_content.remove(&file_id);
         self.id_to_metadata.remove(&file_id);
+        Arc::make_mut(&mut self.id_to_entry).remove(&file_id);
+        self.resolved_file_ids.remove(&file_id);
+        self.variant_file_ids.remove(&file_id);
+    }
+
+    /// Move a file to a new path, keeping its `FileId`, content and
+    /// metadata inputs so its cached queries stay valid.
+    ///
+    /// Returns `false` if `from` isn't registered or `to` already is. The
+    /// caller should call `rebuild_project_files()` so path lookups see the
+    /// new path.
+    pub fn rename_file<DB>(&mut self, db: &mut DB, from: &FilePath, to: &FilePath) -> bool
+    where
+        DB: salsa::Database,
+    {
+        if self.uri_to_id.contains_key(to.as_str()) {
+            return false;
+        }
+        let Some(file_id) = self.uri_to_id.get(from.as_str()).copied() else {
+            return false;
+        };
+        let shared_uri: Arc<str> = Arc::from(to.as_str());
+        let uri_to_id = Arc::make_mut(&mut self.uri_to_id);
+        uri_to_id.remove(from.as_str());
+        uri_to_id.insert(shared_uri.clone(), file_id);
+        Arc::make_mut(&mut self.id_to_uri).insert(file_id, shared_uri);
+        if let Some(metadata) = self.id_to_metadata.get(&file_id) {
+            metadata.set_uri(db).to(FileUri::new(to.as_str()));
+        }
+        true
     }
 
     /// Get all file IDs
@@ -182,6 +255,43 @@ impl FileRegistry {
         self.resolved_file_ids.insert(file_id);
     }
 
+    /// Mark a file as belonging to the named schema variant.
+    ///
+    /// Variant files are tracked in a separate `SchemaVariantFileIds` input
+    /// and excluded from the source `SchemaFileIds` list.
+    pub fn mark_as_schema_variant(&mut self, file_id: FileId, variant: &str) {
+        self.variant_file_ids.insert(file_id, Arc::from(variant));
+    }
+
+    /// Assign documents matching `globs`, relative to `base_dir`, to the
+    /// named schema variant.
+    ///
+    /// Variants are checked in the order they were set; the first match wins.
+    pub fn set_variant_documents(
+        &mut self,
+        variant: &str,
+        base_dir: &std::path::Path,
+        globs: graphql_config::FileGlobs,
+    ) {
+        let mut base_uri = crate::helpers::path_to_file_uri(base_dir);
+        if !base_uri.ends_with('/') {
+            base_uri.push('/');
+        }
+        self.variant_documents
+            .retain(|documents| documents.variant.as_ref() != variant);
+        self.variant_documents.push(VariantDocuments {
+            variant: Arc::from(variant),
+            base_uri,
+            globs,
+        });
+    }
+
+    /// Get the `SchemaVariantFileIds` input
+    #[must_use]
+    pub const fn schema_variant_file_ids(&self) -> Option<SchemaVariantFileIds> {
+        self.schema_variant_file_ids
+    }
+
     /// Rebuild the `ProjectFiles` input from current state
     /// This should be called after files are added or removed
     ///
@@ -200,7 +310,8 @@ impl FileRegistry {
         let mut schema_ids = Vec::new();
         let mut document_ids = Vec::new();
         let mut resolved_ids = Vec::new();
-        let mut file_entries: HashMap<FileId, FileEntry> = HashMap::new();
+        let mut variant_ids: HashMap<Arc<str>, Vec<FileId>> = HashMap::new();
+        let mut variant_documents: HashMap<FileId, Arc<str>> = HashMap::new();
 
         // Collect all file data first without calling db methods
         let file_data: Vec<_> = self
@@ -208,24 +319,35 @@ impl FileRegistry {
             .iter()
             .filter_map(|(&file_id, _content)| {
                 let metadata = self.id_to_metadata.get(&file_id)?;
-                let entry = self.id_to_entry.get(&file_id)?;
-                Some((file_id, *metadata, *entry))
+                Some((file_id, *metadata))
             })
             .collect();
 
         // Now query document kinds and categorize - this may trigger salsa queries
-        for (file_id, metadata, entry) in file_data {
-            file_entries.insert(file_id, entry);
-
+        for (file_id, metadata) in file_data {
             // Categorize by document kind for ID lists
             if metadata.is_schema(db) {
                 if self.resolved_file_ids.contains(&file_id) {
                     resolved_ids.push(file_id);
+                } else if let Some(variant) = self.variant_file_ids.get(&file_id) {
+                    variant_ids
+                        .entry(variant.clone())
+                        .or_default()
+                        .push(file_id);
                 } else {
                     schema_ids.push(file_id);
                 }
             } else if metadata.is_document(db) {
                 document_ids.push(file_id);
+                let variant = self.id_to_uri.get(&file_id).and_then(|uri| {
+                    self.variant_documents
+                        .iter()
+                        .find(|documents| documents.matches(uri))
+                        .map(|documents| documents.variant.clone())
+                });
+                if let Some(variant) = variant {
+                    variant_documents.insert(file_id, variant);
+                }
             }
         }
 
@@ -267,54 +389,61 @@ impl FileRegistry {
         };
         self.resolved_schema_file_ids = Some(resolved_schema_file_ids);
 
+        // Create or update the SchemaVariantFileIds input
+        let variants: HashMap<Arc<str>, Arc<Vec<FileId>>> = variant_ids
+            .into_iter()
+            .map(|(name, mut ids)| {
+                ids.sort_unstable_by_key(|id| id.as_u32());
+                (name, Arc::new(ids))
+            })
+            .collect();
+        let schema_variant_file_ids = if let Some(existing) = self.schema_variant_file_ids {
+            if *existing.variants(db) != variants {
+                existing.set_variants(db).to(Arc::new(variants));
+            }
+            if *existing.documents(db) != variant_documents {
+                existing.set_documents(db).to(Arc::new(variant_documents));
+            }
+            existing
+        } else {
+            SchemaVariantFileIds::new(db, Arc::new(variants), Arc::new(variant_documents))
+        };
+        self.schema_variant_file_ids = Some(schema_variant_file_ids);
+
         // Create or update the FileEntryMap input
-        // Only update if the set of files has changed (entries point to same FileEntry objects)
+        // Only update if the set of files has changed (entries point to same FileEntry objects).
+        // An untouched table is still the one the input holds, so most
+        // rebuilds skip the comparison.
         let file_entry_map = if let Some(existing) = self.file_entry_map {
             let existing_entries = existing.entries(db);
-            // Compare file IDs only - the FileEntry values are the same objects
-            let keys_match = existing_entries.len() == file_entries.len()
-                && existing_entries
-                    .keys()
-                    .all(|k| file_entries.contains_key(k));
-            if !keys_match {
-                existing.set_entries(db).to(Arc::new(file_entries));
+            if !Arc::ptr_eq(&existing_entries, &self.id_to_entry)
+                && *existing_entries != *self.id_to_entry
+            {
+                existing.set_entries(db).to(self.id_to_entry.clone());
             }
             existing
         } else {
-            FileEntryMap::new(db, Arc::new(file_entries))
+            FileEntryMap::new(db, self.id_to_entry.clone())
         };
         self.file_entry_map = Some(file_entry_map);
 
-        // Build the URI ↔ FileId tables. Snapshots will resolve paths via these
-        // through Salsa, never through the registry parking_lot lock.
-        let uri_to_id_map: HashMap<Arc<str>, FileId> = self
-            .uri_to_id
-            .iter()
-            .map(|(k, v)| (Arc::<str>::from(k.as_str()), *v))
-            .collect();
-        let id_to_uri_map: HashMap<FileId, Arc<str>> = self
-            .id_to_uri
-            .iter()
-            .map(|(k, v)| (*k, Arc::<str>::from(v.as_str())))
-            .collect();
-
-        // Create or update the FilePathMap input.
-        // Only bump it when the key set actually changes (file add/remove); pure
-        // content edits leave the URI ↔ FileId mapping untouched and must not
+        // Create or update the FilePathMap input, the URI ↔ FileId tables
+        // snapshots resolve paths through. The tables are shared with the
+        // registry, not copied.
+        // Only bump it when the mapping actually changes (file add/remove/rename);
+        // pure content edits leave the URI ↔ FileId mapping untouched and must not
         // invalidate path-lookup queries.
         let file_path_map = if let Some(existing) = self.file_path_map {
             let existing_id_to_uri = existing.id_to_uri(db);
-            let keys_match = existing_id_to_uri.len() == id_to_uri_map.len()
-                && existing_id_to_uri
-                    .keys()
-                    .all(|k| id_to_uri_map.contains_key(k));
-            if !keys_match {
-                existing.set_uri_to_id(db).to(Arc::new(uri_to_id_map));
-                existing.set_id_to_uri(db).to(Arc::new(id_to_uri_map));
+            if !Arc::ptr_eq(&existing_id_to_uri, &self.id_to_uri)
+                && *existing_id_to_uri != *self.id_to_uri
+            {
+                existing.set_uri_to_id(db).to(self.uri_to_id.clone());
+                existing.set_id_to_uri(db).to(self.id_to_uri.clone());
             }
             existing
         } else {
-            FilePathMap::new(db, Arc::new(uri_to_id_map), Arc::new(id_to_uri_map))
+            FilePathMap::new(db, self.uri_to_id.clone(), self.id_to_uri.clone())
         };
         self.file_path_map = Some(file_path_map);
 
@@ -467,4 +596,56 @@ mod tests {
         assert!(all_ids.contains(&file_id1));
         assert!(all_ids.contains(&file_id2));
     }
+
+    #[test]
+    fn test_file_path_map_shares_registry_paths() {
+        let mut db = RootDatabase::new();
+        let mut registry = FileRegistry::new();
+
+        let path1 = FilePath::new("file:///test1.graphql");
+        let path2 = FilePath::new("file:///test2.graphql");
+        let (file_id1, _, _, _) = registry.add_file(
+            &mut db,
+            &path1,
+            "type Query { hello: String }",
+            Language::GraphQL,
+            DocumentKind::Schema,
+        );
+        let (file_id2, _, _, _) = registry.add_file(
+            &mut db,
+            &path2,
+            "query { hello }",
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+        registry.rebuild_project_files(&mut db);
+
+        let path_map = registry.file_path_map.unwrap();
+        let before = path_map.id_to_uri(&db);
+        assert!(Arc::ptr_eq(&before, &registry.id_to_uri));
+        assert!(Arc::ptr_eq(
+            &registry.file_entry_map.unwrap().entries(&db),
+            &registry.id_to_entry
+        ));
+
+        registry.remove_file(file_id2);
+        registry.rebuild_project_files(&mut db);
+
+        // The rebuild swaps in a new table; the old one is left intact
+        let after = path_map.id_to_uri(&db);
+        assert!(!after.contains_key(&file_id2));
+        assert!(before.contains_key(&file_id2));
+        assert!(!registry.id_to_entry.contains_key(&file_id2));
+        assert!(Arc::ptr_eq(&after, &registry.id_to_uri));
+
+        // A rename keeps the key set but still reaches the path map
+        let renamed = FilePath::new("file:///renamed.graphql");
+        assert!(registry.rename_file(&mut db, &path1, &renamed));
+        registry.rebuild_project_files(&mut db);
+        assert_eq!(
+            path_map.uri_to_id(&db).get(renamed.as_str()),
+            Some(&file_id1)
+        );
+        assert!(!path_map.uri_to_id(&db).contains_key(path1.as_str()));
+    }
 }
@@ -0,0 +1,94 @@
+//! Keeping `# import` paths valid when files move.
+//!
+//! When files are renamed, every import whose path would stop resolving to
+//! the same file is rewritten: imports of a moved file, and the relative
+//! imports of a moved file itself. Absolute paths stay absolute; relative
+//! ones are recomputed from the importer's new location, keeping a leading
+//! `./` if the old path had one.
+
+use std::collections::HashMap;
+
+use graphql_base_db::Language;
+
+use crate::helpers::offset_range_to_range;
+use crate::types::{FilePath, TextEdit};
+use crate::DbFiles;
+
+/// Compute the import path edits for moving each `from` file to `to`.
+///
+/// Edits are keyed by the files' current paths, since they're applied before
+/// the files move.
+pub fn import_edits_for_renames(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    renames: &[(FilePath, FilePath)],
+) -> HashMap<FilePath, Vec<TextEdit>> {
+    let mut changes = HashMap::new();
+    let Some(project_files) = project_files else {
+        return changes;
+    };
+    let moved: HashMap<&str, &str> = renames
+        .iter()
+        .map(|(from, to)| (from.as_str(), to.as_str()))
+        .collect();
+    let new_uri = |uri: &str| moved.get(uri).copied().unwrap_or(uri).to_string();
+
+    let file_ids = project_files
+        .schema_file_ids(db)
+        .ids(db)
+        .iter()
+        .chain(project_files.document_file_ids(db).ids(db).iter());
+    for &file_id in file_ids {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, file_id)
+        else {
+            continue;
+        };
+        if metadata.language(db) != Language::GraphQL {
+            continue;
+        }
+        let imports = graphql_hir::file_imports(db, content, metadata);
+        if imports.is_empty() {
+            continue;
+        }
+        let Some(file) = registry.get_path(file_id) else {
+            continue;
+        };
+
+        let importer = new_uri(file.as_str());
+        let line_index = graphql_syntax::line_index(db, content);
+        let mut edits = Vec::new();
+        for import in imports.iter() {
+            let target = new_uri(&import.uri);
+            if graphql_hir::resolve_import_uri(&importer, &import.path) == target {
+                continue;
+            }
+            let path = if import.path.starts_with('/') {
+                uri_path(&target).to_string()
+            } else {
+                graphql_analysis::relative_import_path(
+                    &importer,
+                    &target,
+                    import.path.starts_with("./"),
+                )
+            };
+            edits.push(TextEdit {
+                range: offset_range_to_range(
+                    &line_index,
+                    import.path_range.start().into(),
+                    import.path_range.end().into(),
+                ),
+                new_text: path,
+            });
+        }
+        if !edits.is_empty() {
+            changes.insert(file, edits);
+        }
+    }
+    changes
+}
+
+/// The path part of a URI, after its scheme
+fn uri_path(uri: &str) -> &str {
+    uri.find("://").map_or(uri, |i| &uri[i + 3..])
+}
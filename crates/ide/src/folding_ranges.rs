@@ -2,22 +2,27 @@
 //!
 //! This module provides IDE folding range functionality for GraphQL documents:
 //! - Selection sets `{ ... }`
+//! - Inline fragments (`... on User { ... }`)
 //! - Operation definitions (query, mutation, subscription)
 //! - Fragment definitions
-//! - Multi-line block comments
+//! - Type definitions, starting below their description
+//! - Multi-line block descriptions
+//! - Runs of `#import` lines
+//! - Each embedded GraphQL block in TS/JS files
 
-use crate::helpers::{adjust_range_for_line_offset, offset_range_to_range};
 use crate::types::{FilePath, FoldingRange, FoldingRangeKind};
 use crate::DbFiles;
 use apollo_parser::cst::{CstNode, Definition};
+use apollo_parser::SyntaxKind;
 
 /// Get folding ranges for a file.
 ///
 /// Returns folding ranges for:
 /// - Operation definitions (query, mutation, subscription)
 /// - Fragment definitions
-/// - Selection sets
-/// - Block comments
+/// - Selection sets and inline fragments
+/// - Block descriptions and `#import` runs
+/// - Embedded blocks, from the opening to the closing backtick
 pub fn folding_ranges(
     db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
     registry: DbFiles<'_>,
@@ -36,12 +41,26 @@ pub fn folding_ranges(
     };
 
     let parse = graphql_syntax::parse(db, content, metadata);
+    let is_embedded = metadata.language(db).is_js_family();
     let mut ranges = Vec::new();
 
     for doc in parse.documents() {
-        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
+        let doc_line_index = doc.line_index();
         let line_offset = doc.line_offset;
 
+        // The GraphQL starts right after the opening backtick and ends at
+        // the closing one, so the block spans exactly the source's lines
+        if is_embedded {
+            let last_line = line_offset + doc.source.matches('\n').count() as u32;
+            if last_line > line_offset {
+                ranges.push(FoldingRange::new(
+                    line_offset,
+                    last_line,
+                    FoldingRangeKind::Region,
+                ));
+            }
+        }
+
         let doc_cst = doc.tree.document();
 
         // Collect folding ranges from definitions
@@ -120,7 +139,7 @@ fn collect_definition_folding_ranges(
             }
         }
         Definition::ObjectTypeDefinition(obj) => {
-            let obj_range = obj.syntax().text_range();
+            let obj_range = range_after_description(obj.syntax());
             add_multiline_range(
                 obj_range,
                 line_index,
@@ -141,7 +160,7 @@ fn collect_definition_folding_ranges(
             }
         }
         Definition::InterfaceTypeDefinition(iface) => {
-            let iface_range = iface.syntax().text_range();
+            let iface_range = range_after_description(iface.syntax());
             add_multiline_range(
                 iface_range,
                 line_index,
@@ -162,7 +181,7 @@ fn collect_definition_folding_ranges(
             }
         }
         Definition::InputObjectTypeDefinition(input) => {
-            let input_range = input.syntax().text_range();
+            let input_range = range_after_description(input.syntax());
             add_multiline_range(
                 input_range,
                 line_index,
@@ -183,7 +202,7 @@ fn collect_definition_folding_ranges(
             }
         }
         Definition::EnumTypeDefinition(enum_def) => {
-            let enum_range = enum_def.syntax().text_range();
+            let enum_range = range_after_description(enum_def.syntax());
             add_multiline_range(
                 enum_range,
                 line_index,
@@ -204,7 +223,7 @@ fn collect_definition_folding_ranges(
             }
         }
         Definition::UnionTypeDefinition(union_def) => {
-            let union_range = union_def.syntax().text_range();
+            let union_range = range_after_description(union_def.syntax());
             add_multiline_range(
                 union_range,
                 line_index,
@@ -214,7 +233,7 @@ fn collect_definition_folding_ranges(
             );
         }
         Definition::ScalarTypeDefinition(scalar) => {
-            let scalar_range = scalar.syntax().text_range();
+            let scalar_range = range_after_description(scalar.syntax());
             add_multiline_range(
                 scalar_range,
                 line_index,
@@ -224,7 +243,7 @@ fn collect_definition_folding_ranges(
             );
         }
         Definition::DirectiveDefinition(directive) => {
-            let directive_range = directive.syntax().text_range();
+            let directive_range = range_after_description(directive.syntax());
             add_multiline_range(
                 directive_range,
                 line_index,
@@ -247,6 +266,25 @@ fn collect_definition_folding_ranges(
     }
 }
 
+/// The range of a definition from its first line after the description, so
+/// folding a described type keeps its name visible and leaves the
+/// description to fold on its own
+fn range_after_description(node: &apollo_parser::SyntaxNode) -> apollo_parser::TextRange {
+    let start = node
+        .children_with_tokens()
+        .find(|element| {
+            !matches!(
+                element.kind(),
+                SyntaxKind::DESCRIPTION | SyntaxKind::WHITESPACE | SyntaxKind::COMMENT
+            )
+        })
+        .map_or_else(
+            || node.text_range().start(),
+            |element| element.text_range().start(),
+        );
+    apollo_parser::TextRange::new(start, node.text_range().end())
+}
+
 /// Recursively collect folding ranges from selection sets
 fn collect_selection_set_folding_ranges(
     selection_set: &apollo_parser::cst::SelectionSet,
@@ -277,6 +315,15 @@ fn collect_selection_set_folding_ranges(
                 }
             }
             apollo_parser::cst::Selection::InlineFragment(inline) => {
+                // From the `...` line, so directives or a type condition on
+                // their own lines fold away with the body
+                add_multiline_range(
+                    inline.syntax().text_range(),
+                    line_index,
+                    line_offset,
+                    FoldingRangeKind::Region,
+                    ranges,
+                );
                 if let Some(nested_set) = inline.selection_set() {
                     collect_selection_set_folding_ranges(
                         &nested_set,
@@ -293,18 +340,19 @@ fn collect_selection_set_folding_ranges(
     }
 }
 
-/// Collect block comment folding ranges from the syntax tree
+/// Collect block description and `#import` folding ranges from the syntax tree
 fn collect_comment_folding_ranges(
     tree: &apollo_parser::SyntaxTree,
     line_index: &graphql_syntax::LineIndex,
     line_offset: u32,
     ranges: &mut Vec<FoldingRange>,
 ) {
-    // Walk through all tokens looking for comments
+    let mut import_lines = Vec::new();
+
+    // Walk through all tokens looking for block strings and comments
     for token in tree.document().syntax().descendants_with_tokens() {
         if let apollo_parser::SyntaxElement::Token(token) = token {
-            // Check if this is a comment token
-            // Block comments in GraphQL are enclosed in triple quotes: """..."""
+            // Block descriptions are enclosed in triple quotes: """..."""
             let text = token.text();
             if text.starts_with("\"\"\"") && text.ends_with("\"\"\"") && text.len() > 6 {
                 let token_range = token.text_range();
@@ -315,8 +363,24 @@ fn collect_comment_folding_ranges(
                     FoldingRangeKind::Comment,
                     ranges,
                 );
+            } else if token.kind() == SyntaxKind::COMMENT && text.starts_with("#import") {
+                let (line, _) = line_index.line_col(token.text_range().start().into());
+                import_lines.push(line as u32 + line_offset);
             }
-            // Single-line comments starting with # don't need folding
+            // Other single-line comments don't need folding
+        }
+    }
+
+    // Fold each run of consecutive `#import` lines
+    let mut run_start = None;
+    for (i, &line) in import_lines.iter().enumerate() {
+        let start = *run_start.get_or_insert(line);
+        let run_continues = import_lines.get(i + 1) == Some(&(line + 1));
+        if !run_continues {
+            if line > start {
+                ranges.push(FoldingRange::new(start, line, FoldingRangeKind::Imports));
+            }
+            run_start = None;
         }
     }
 }
@@ -329,17 +393,15 @@ fn add_multiline_range(
     kind: FoldingRangeKind,
     ranges: &mut Vec<FoldingRange>,
 ) {
-    let start: usize = text_range.start().into();
-    let end: usize = text_range.end().into();
-
-    let ide_range = offset_range_to_range(line_index, start, end);
-    let adjusted_range = adjust_range_for_line_offset(ide_range, line_offset);
+    // Folding is by whole lines, so the block's first-line column doesn't matter
+    let (start_line, _) = line_index.line_col(text_range.start().into());
+    let (end_line, _) = line_index.line_col(text_range.end().into());
 
     // Only add if it spans multiple lines
-    if adjusted_range.start.line < adjusted_range.end.line {
+    if start_line < end_line {
         ranges.push(FoldingRange::new(
-            adjusted_range.start.line,
-            adjusted_range.end.line,
+            start_line as u32 + line_offset,
+            end_line as u32 + line_offset,
             kind,
         ));
     }
@@ -427,6 +489,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_folding_ranges_inline_fragment() {
+        let mut host = AnalysisHost::new();
+
+        let query_path = FilePath::new("file:///query.graphql");
+        host.add_file(
+            &query_path,
+            r"query GetNode {
+  node {
+    ... on User
+      @include(if: true) {
+      name
+    }
+  }
+}",
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+        host.rebuild_project_files();
+
+        let snapshot = host.snapshot();
+        let ranges = snapshot.folding_ranges(&query_path);
+
+        // Folds from the `...` line, not just the selection set's brace
+        assert!(
+            ranges.iter().any(|r| r.start_line == 2 && r.end_line >= 5),
+            "Should fold the inline fragment from its first line, got: {ranges:?}"
+        );
+    }
+
     #[test]
     fn test_folding_ranges_fragment() {
         let mut host = AnalysisHost::new();
@@ -526,6 +618,90 @@ type User {
         );
     }
 
+    #[test]
+    fn test_folding_ranges_described_type_starts_below_description() {
+        let mut host = AnalysisHost::new();
+
+        let schema_path = FilePath::new("file:///schema.graphql");
+        host.add_file(
+            &schema_path,
+            r#""""
+A user
+"""
+type User {
+  id: ID!
+}"#,
+            Language::GraphQL,
+            DocumentKind::Schema,
+        );
+        host.rebuild_project_files();
+
+        let ranges = host.snapshot().folding_ranges(&schema_path);
+        assert!(ranges.contains(&FoldingRange::new(0, 2, FoldingRangeKind::Comment)));
+        assert!(ranges.contains(&FoldingRange::new(3, 5, FoldingRangeKind::Region)));
+        assert!(
+            !ranges
+                .iter()
+                .any(|r| r.kind == FoldingRangeKind::Region && r.start_line == 0),
+            "type fold should not include the description, got: {ranges:?}"
+        );
+    }
+
+    #[test]
+    fn test_folding_ranges_import_runs() {
+        let mut host = AnalysisHost::new();
+
+        let query_path = FilePath::new("file:///query.graphql");
+        host.add_file(
+            &query_path,
+            r#"#import "./user.graphql"
+#import "./post.graphql"
+#import "./comment.graphql"
+
+#import "./single.graphql"
+query Q { a }"#,
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+        host.rebuild_project_files();
+
+        let ranges = host.snapshot().folding_ranges(&query_path);
+        let imports: Vec<_> = ranges
+            .iter()
+            .filter(|r| r.kind == FoldingRangeKind::Imports)
+            .collect();
+        assert_eq!(
+            imports,
+            [&FoldingRange::new(0, 2, FoldingRangeKind::Imports)]
+        );
+    }
+
+    #[cfg(feature = "extract")]
+    #[test]
+    fn test_folding_ranges_embedded_blocks() {
+        let mut host = AnalysisHost::new();
+
+        let ts_path = FilePath::new("file:///queries.ts");
+        host.add_file(
+            &ts_path,
+            "import { gql } from '@apollo/client';\n\nconst A = gql`\n  query A {\n    a\n  }\n`;\n\nconst B = gql`query B { b }`;\n",
+            Language::TypeScript,
+            DocumentKind::Executable,
+        );
+        host.rebuild_project_files();
+
+        let ranges = host.snapshot().folding_ranges(&ts_path);
+        assert!(
+            ranges.contains(&FoldingRange::new(2, 6, FoldingRangeKind::Region)),
+            "expected a fold for the whole block, got: {ranges:?}"
+        );
+        assert!(ranges.contains(&FoldingRange::new(3, 5, FoldingRangeKind::Region)));
+        assert!(
+            !ranges.iter().any(|r| r.start_line == 8),
+            "single-line block should not fold, got: {ranges:?}"
+        );
+    }
+
     #[test]
     fn test_folding_ranges_single_line_no_fold() {
         let mut host = AnalysisHost::new();
@@ -7,13 +7,16 @@
 //! - Variable definitions
 //! - Argument definitions
 //! - Operation definitions
+//! - Replacement fields named in `@deprecated` reasons
+//! - Files and fragments named in `# import` comments
+//! - GraphQL document constants referenced from JS/TS code, across imports
 
 use crate::helpers::{
     find_fragment_definition_in_parse, find_operation_definition_in_tree,
     find_variable_definition_in_tree, offset_range_to_range, position_to_offset,
 };
 use crate::symbol::{find_parent_type_at_offset, find_symbol_at_offset, Symbol};
-use crate::types::{FilePath, Location, Position};
+use crate::types::{FilePath, Location, Position, Range};
 use crate::{helpers::find_block_for_position, symbol, DbFiles};
 
 /// Get goto definition locations for the symbol at a position.
@@ -26,15 +29,27 @@ pub fn goto_definition(
     file: &FilePath,
     position: Position,
 ) -> Option<Vec<Location>> {
-    let (content, metadata) = {
-        let file_id = registry.get_file_id(file)?;
-        let content = registry.get_content(file_id)?;
-        let metadata = registry.get_metadata(file_id)?;
-        (content, metadata)
-    };
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
 
     let parse = graphql_syntax::parse(db, content, metadata);
 
+    #[cfg(feature = "extract")]
+    if metadata.language(db).is_js_family() {
+        if let Some(locations) =
+            goto_document_constant(db, registry, file, content, metadata, &parse, position)
+        {
+            return Some(locations);
+        }
+    }
+
+    if let Some(locations) =
+        project_files.and_then(|pf| goto_import(db, registry, pf, content, metadata, position))
+    {
+        return Some(locations);
+    }
+
     let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
 
     tracing::debug!(
@@ -44,9 +59,15 @@ pub fn goto_definition(
         adjusted_position
     );
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
+    if let Some(locations) = project_files
+        .and_then(|pf| goto_deprecation_replacement(db, registry, pf, block_context.tree, offset))
+    {
+        return Some(locations);
+    }
+
     let symbol = find_symbol_at_offset(block_context.tree, offset)?;
 
     let project_files = project_files?;
@@ -112,33 +133,23 @@ pub fn goto_definition(
                 fragments.keys().collect::<Vec<_>>()
             );
 
-            let fragment = fragments.get(name.as_str())?;
+            // With `# import` comments, the imported fragment wins over
+            // same-named fragments elsewhere in the project
+            let fragment_file_id =
+                graphql_hir::file_fragment_scope(db, file_id, content, metadata, project_files)
+                    .and_then(|scope| scope.get(name.as_str()).copied())
+                    .or_else(|| fragments.get(name.as_str()).map(|f| f.file_id))?;
 
             tracing::debug!(
                 "Looking up path for fragment '{}' with FileId {:?}",
                 name,
-                fragment.file_id
+                fragment_file_id
             );
             let all_ids = registry.all_file_ids();
             tracing::debug!("Registry has {} files", all_ids.len());
             tracing::debug!("Registry FileIds: {:?}", all_ids);
 
-            let Some(file_path) = registry.get_path(fragment.file_id) else {
-                tracing::error!(
-                    "FileId {:?} not found in registry for fragment '{}'",
-                    fragment.file_id,
-                    name
-                );
-                return None;
-            };
-            let def_content = registry.get_content(fragment.file_id)?;
-            let def_metadata = registry.get_metadata(fragment.file_id)?;
-
-            let def_parse = graphql_syntax::parse(db, def_content, def_metadata);
-
-            let range = find_fragment_definition_in_parse(&def_parse, &name)?;
-
-            Some(vec![Location::new(file_path, range)])
+            fragment_location(db, registry, fragment_file_id, &name).map(|loc| vec![loc])
         }
         Symbol::TypeName { name } => {
             // Try source schema type locations first
@@ -180,12 +191,12 @@ pub fn goto_definition(
             }
         }
         Symbol::VariableReference { name } => {
-            let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+            let block_line_index = block_context.line_index();
             let range = find_variable_definition_in_tree(
                 block_context.tree,
                 &name,
                 &block_line_index,
-                block_context.line_offset,
+                block_context.origin(),
             );
 
             if let Some(range) = range {
@@ -229,12 +240,12 @@ pub fn goto_definition(
             Some(vec![Location::new(file_path, range)])
         }
         Symbol::OperationName { name } => {
-            let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+            let block_line_index = block_context.line_index();
             let range = find_operation_definition_in_tree(
                 block_context.tree,
                 &name,
                 &block_line_index,
-                block_context.line_offset,
+                block_context.origin(),
             );
 
             if let Some(range) = range {
@@ -282,5 +293,269 @@ pub fn goto_definition(
 
             Some(vec![Location::new(file_path, range)])
         }
+        // Only found on the definition itself
+        Symbol::EnumValue { .. } => None,
+    }
+}
+
+/// Goto definition on an `# import` comment: the path opens the imported
+/// file, an imported name jumps to that fragment in it.
+fn goto_import(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    position: Position,
+) -> Option<Vec<Location>> {
+    let imports = graphql_hir::file_imports(db, content, metadata);
+    if imports.is_empty() {
+        return None;
+    }
+    let line_index = graphql_syntax::line_index(db, content);
+    let offset = graphql_hir::TextSize::from(position_to_offset(&line_index, position)? as u32);
+    let uri_to_id = project_files.file_path_map(db).uri_to_id(db);
+
+    for import in imports.iter() {
+        let target = || uri_to_id.get(import.uri.as_ref()).copied();
+        if import.path_range.contains_inclusive(offset) {
+            let file_path = registry.get_path(target()?)?;
+            let start = Position::new(0, 0);
+            return Some(vec![Location::new(file_path, Range::new(start, start))]);
+        }
+        let name = import
+            .names
+            .iter()
+            .flatten()
+            .find(|name| name.range.contains_inclusive(offset));
+        if let Some(name) = name {
+            return fragment_location(db, registry, target()?, &name.name).map(|loc| vec![loc]);
+        }
     }
+    None
+}
+
+/// Goto definition on the replacement a deprecation reason names, such as
+/// `name` in `@deprecated(reason: "Use `name` instead")`.
+fn goto_deprecation_replacement(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+    tree: &apollo_parser::SyntaxTree,
+    offset: usize,
+) -> Option<Vec<Location>> {
+    let reference = graphql_linter::deprecation_replacement_references(tree)
+        .into_iter()
+        .find(|reference| offset >= reference.range.0 && offset <= reference.range.1)?;
+
+    let find_field = |types: &graphql_hir::TypeDefMap| {
+        types
+            .get(reference.target_type.as_str())?
+            .fields
+            .iter()
+            .find(|f| f.name.as_ref() == reference.target_field)
+            .map(|f| (f.file_id, f.name_range))
+    };
+    let (file_id, name_range) = find_field(graphql_hir::source_schema_types(db, project_files))
+        .or_else(|| find_field(graphql_hir::schema_types(db, project_files)))?;
+
+    let file_path = registry.get_path(file_id)?;
+    let content = registry.get_content(file_id)?;
+    let line_index = graphql_syntax::line_index(db, content);
+    let range = offset_range_to_range(
+        &line_index,
+        name_range.start().into(),
+        name_range.end().into(),
+    );
+    Some(vec![Location::new(file_path, range)])
+}
+
+/// Location of fragment `name`'s definition in `file_id`
+fn fragment_location(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    file_id: graphql_base_db::FileId,
+    name: &str,
+) -> Option<Location> {
+    let Some(file_path) = registry.get_path(file_id) else {
+        tracing::error!(
+            "FileId {:?} not found in registry for fragment '{}'",
+            file_id,
+            name
+        );
+        return None;
+    };
+    let def_content = registry.get_content(file_id)?;
+    let def_metadata = registry.get_metadata(file_id)?;
+    let def_parse = graphql_syntax::parse(db, def_content, def_metadata);
+    let range = find_fragment_definition_in_parse(&def_parse, name)?;
+    Some(Location::new(file_path, range))
+}
+
+/// Imports and re-exports followed before giving up, so cyclic re-exports
+/// terminate
+#[cfg(feature = "extract")]
+const MAX_IMPORT_HOPS: usize = 8;
+
+/// Goto definition on an identifier in JS/TS code, such as `GET_POKEMON` in
+/// `useQuery(GET_POKEMON)`: follows the binding through imports and
+/// re-exports to the GraphQL block it's initialized with, and returns the
+/// block's first definition.
+#[cfg(feature = "extract")]
+fn goto_document_constant(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    file: &FilePath,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    parse: &graphql_syntax::Parse,
+    position: Position,
+) -> Option<Vec<Location>> {
+    use graphql_extract::ExportTarget;
+
+    let line_index = graphql_syntax::line_index(db, content);
+    let offset = position_to_offset(&line_index, position)?;
+    // Positions inside a GraphQL block resolve GraphQL symbols instead
+    if parse
+        .documents()
+        .any(|doc| offset >= doc.byte_offset && offset <= doc.byte_offset + doc.source.len())
+    {
+        return None;
+    }
+    let text = content.text(db);
+    let name = identifier_at(&text, offset)?;
+
+    let symbols = graphql_syntax::module_symbols(db, content, metadata);
+    let mut import = match symbols
+        .binding(name)
+        .map(ExportTarget::Binding)
+        .or_else(|| symbols.import(name).map(ExportTarget::Import))?
+    {
+        ExportTarget::Binding(binding) => {
+            return document_in_range(db, file, content, metadata, binding.init_range)
+                .map(|location| vec![location]);
+        }
+        ExportTarget::Import(import) => import.clone(),
+    };
+    let mut path = file.clone();
+
+    for _ in 0..MAX_IMPORT_HOPS {
+        let file_id = resolve_module(registry, &path, &import.module)?;
+        path = registry.get_path(file_id)?;
+        let content = registry.get_content(file_id)?;
+        let metadata = registry.get_metadata(file_id)?;
+        let symbols = graphql_syntax::module_symbols(db, content, metadata);
+        import = match symbols.export(&import.imported)? {
+            ExportTarget::Binding(binding) => {
+                return document_in_range(db, &path, content, metadata, binding.init_range)
+                    .map(|location| vec![location]);
+            }
+            ExportTarget::Import(next) => next.clone(),
+        };
+    }
+    None
+}
+
+/// The JS identifier touching `offset`
+#[cfg(feature = "extract")]
+fn identifier_at(text: &str, offset: usize) -> Option<&str> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    let start = text
+        .get(..offset)?
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident(*c))
+        .last()
+        .map_or(offset, |(i, _)| i);
+    let end = text[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_ident(*c))
+        .map_or(text.len(), |(i, _)| offset + i);
+    let ident = &text[start..end];
+    ident
+        .chars()
+        .next()
+        .filter(|c| !c.is_ascii_digit())
+        .map(|_| ident)
+}
+
+/// Resolve a relative module specifier against the importing file's URI,
+/// trying the extensions and index files TypeScript module resolution would.
+/// Package imports aren't followed.
+#[cfg(feature = "extract")]
+fn resolve_module(
+    registry: DbFiles<'_>,
+    from: &FilePath,
+    specifier: &str,
+) -> Option<graphql_base_db::FileId> {
+    if !specifier.starts_with('.') {
+        return None;
+    }
+    let (mut base, _) = from.as_str().rsplit_once('/')?;
+    let mut segments = Vec::new();
+    for segment in specifier.split('/') {
+        match segment {
+            "." | "" => {}
+            ".." => {
+                if segments.pop().is_none() {
+                    base = base.rsplit_once('/')?.0;
+                }
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let resolved = format!("{base}/{}", segments.join("/"));
+
+    // `./queries.js` may name the `./queries.ts` source under ESM resolution
+    let stem = resolved
+        .strip_suffix(".js")
+        .or_else(|| resolved.strip_suffix(".jsx"))
+        .unwrap_or(&resolved);
+    let with_extension = ["ts", "tsx", "js", "jsx"]
+        .iter()
+        .map(|ext| format!("{stem}.{ext}"));
+    let index = ["ts", "tsx", "js", "jsx"]
+        .iter()
+        .map(|ext| format!("{resolved}/index.{ext}"));
+    std::iter::once(resolved.clone())
+        .chain(with_extension)
+        .chain(index)
+        .find_map(|candidate| registry.get_file_id(&FilePath::new(candidate)))
+}
+
+/// The first definition's name in the GraphQL block within `range` of a
+/// JS/TS file, or the block's start when it has no named definition
+#[cfg(feature = "extract")]
+fn document_in_range(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    path: &FilePath,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    range: (usize, usize),
+) -> Option<Location> {
+    use apollo_parser::cst::{self, CstNode};
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let doc = parse
+        .documents()
+        .find(|doc| doc.byte_offset >= range.0 && doc.byte_offset <= range.1)?;
+
+    let name_range = doc
+        .tree
+        .document()
+        .definitions()
+        .find_map(|definition| match definition {
+            cst::Definition::OperationDefinition(op) => op.name().map(|n| n.syntax().text_range()),
+            cst::Definition::FragmentDefinition(frag) => frag
+                .fragment_name()
+                .and_then(|n| n.name())
+                .map(|n| n.syntax().text_range()),
+            _ => None,
+        });
+    let (start, end): (usize, usize) =
+        name_range.map_or((0, 0), |r| (r.start().into(), r.end().into()));
+
+    let line_index = graphql_syntax::line_index(db, content);
+    let range = offset_range_to_range(&line_index, doc.byte_offset + start, doc.byte_offset + end);
+    Some(Location::new(path.clone(), range))
 }
@@ -34,19 +34,18 @@ pub fn offset_range_to_range(
     Range::new(start, end)
 }
 
-/// Add line offset to a range (used when returning positions from extracted GraphQL)
+/// Map a block-relative range to the file the block was extracted from
 ///
-/// When returning positions for document symbols in TypeScript/JavaScript files,
-/// we need to add the `line_offset` to convert from GraphQL-relative positions
-/// back to original file positions.
-pub const fn adjust_range_for_line_offset(range: Range, line_offset: u32) -> Range {
-    if line_offset == 0 {
-        return range;
-    }
-
+/// Lines are shifted by the block's line, and columns on the block's first
+/// line by the UTF-16 column it starts at, so ranges in TypeScript or
+/// JavaScript files land on the right characters. Pure GraphQL files have
+/// the default origin and are returned unchanged.
+pub const fn adjust_range_for_block(range: Range, origin: graphql_syntax::BlockOrigin) -> Range {
+    let (start_line, start_character) = origin.to_file(range.start.line, range.start.character);
+    let (end_line, end_character) = origin.to_file(range.end.line, range.end.character);
     Range::new(
-        Position::new(range.start.line + line_offset, range.start.character),
-        Position::new(range.end.line + line_offset, range.end.character),
+        Position::new(start_line, start_character),
+        Position::new(end_line, end_character),
     )
 }
 
@@ -113,6 +112,17 @@ pub fn convert_diagnostic(diag: &graphql_analysis::Diagnostic) -> crate::types::
                 }
             })
             .collect(),
+        related: diag
+            .related
+            .iter()
+            .map(|r| crate::types::RelatedInformation {
+                location: crate::types::Location::new(
+                    crate::types::FilePath::new(r.uri.to_string()),
+                    convert_range(r.range),
+                ),
+                message: r.message.to_string(),
+            })
+            .collect(),
     }
 }
 
@@ -138,6 +148,53 @@ pub struct BlockContext<'a> {
     pub line_offset: u32,
     /// The block source for building `LineIndex`
     pub block_source: &'a str,
+    document: graphql_syntax::DocumentRef<'a>,
+}
+
+impl BlockContext<'_> {
+    /// Line index over the block source, sharing the parsed text.
+    pub fn line_index(&self) -> graphql_syntax::LineIndex {
+        self.document.line_index()
+    }
+
+    /// Column the block starts at on its first line (0 for pure GraphQL files)
+    pub fn column_offset(&self) -> u32 {
+        self.document.column_offset
+    }
+
+    /// Byte offset of the block in the file (0 for pure GraphQL files)
+    pub fn byte_offset(&self) -> usize {
+        self.document.byte_offset
+    }
+
+    /// Where the block starts in the file, for mapping positions back
+    pub fn origin(&self) -> graphql_syntax::BlockOrigin {
+        self.document.origin()
+    }
+
+    /// The client-controlled nullability designator written after the
+    /// field whose name contains `offset`, if any
+    pub fn field_designator_at_offset(&self, offset: usize) -> Option<graphql_syntax::Nullability> {
+        use apollo_parser::cst::{CstNode, Field};
+
+        if self.document.designators.is_empty() {
+            return None;
+        }
+        let field = self
+            .tree
+            .document()
+            .syntax()
+            .descendants()
+            .filter_map(Field::cast)
+            .find(|field| {
+                field.name().is_some_and(|name| {
+                    let range = name.syntax().text_range();
+                    offset >= usize::from(range.start()) && offset <= usize::from(range.end())
+                })
+            })?;
+        graphql_syntax::field_designator(self.block_source, self.document.designators, &field)
+            .map(|designator| designator.nullability)
+    }
 }
 
 /// Find which GraphQL block contains the given position
@@ -153,16 +210,11 @@ pub fn find_block_for_position(
     // Iterate through all documents to find the one containing the position
     for doc in parse.documents() {
         let doc_start_line = doc.line_offset;
-        let doc_start_col = doc.column_offset;
         let doc_lines = doc.source.chars().filter(|&c| c == '\n').count() as u32;
 
         if position.line >= doc_start_line && position.line <= doc_start_line + doc_lines {
-            let adjusted_line = position.line - doc_start_line;
-            let adjusted_col = if adjusted_line == 0 && doc_start_line > 0 {
-                position.character.saturating_sub(doc_start_col)
-            } else {
-                position.character
-            };
+            let (adjusted_line, adjusted_col) =
+                doc.origin().to_block(position.line, position.character);
             let adjusted_pos = Position::new(adjusted_line, adjusted_col);
 
             return Some((
@@ -170,6 +222,7 @@ pub fn find_block_for_position(
                     tree: doc.tree,
                     line_offset: doc_start_line,
                     block_source: doc.source,
+                    document: doc,
                 },
                 adjusted_pos,
             ));
@@ -188,9 +241,9 @@ pub fn find_fragment_definition_in_parse(
         if let Some((start_offset, end_offset)) =
             find_fragment_definition_range(doc.tree, fragment_name)
         {
-            let line_index = graphql_syntax::LineIndex::new(doc.source);
+            let line_index = doc.line_index();
             let range = offset_range_to_range(&line_index, start_offset, end_offset);
-            return Some(adjust_range_for_line_offset(range, doc.line_offset));
+            return Some(adjust_range_for_block(range, doc.origin()));
         }
     }
 
@@ -204,9 +257,9 @@ pub fn find_type_definition_in_parse(
 ) -> Option<Range> {
     for doc in parse.documents() {
         if let Some((start_offset, end_offset)) = find_type_definition_range(doc.tree, type_name) {
-            let line_index = graphql_syntax::LineIndex::new(doc.source);
+            let line_index = doc.line_index();
             let range = offset_range_to_range(&line_index, start_offset, end_offset);
-            return Some(adjust_range_for_line_offset(range, doc.line_offset));
+            return Some(adjust_range_for_block(range, doc.origin()));
         }
     }
 
@@ -222,11 +275,11 @@ pub fn find_fragment_spreads_in_parse(
 
     for doc in parse.documents() {
         if let Some(offsets) = find_fragment_spreads(doc.tree, fragment_name) {
-            let line_index = graphql_syntax::LineIndex::new(doc.source);
+            let line_index = doc.line_index();
             for offset in offsets {
                 let end_offset = offset + fragment_name.len();
                 let range = offset_range_to_range(&line_index, offset, end_offset);
-                results.push(adjust_range_for_line_offset(range, doc.line_offset));
+                results.push(adjust_range_for_block(range, doc.origin()));
             }
         }
     }
@@ -240,11 +293,11 @@ pub fn find_type_references_in_parse(parse: &graphql_syntax::Parse, type_name: &
 
     for doc in parse.documents() {
         if let Some(offsets) = find_type_references_in_tree(doc.tree, type_name) {
-            let line_index = graphql_syntax::LineIndex::new(doc.source);
+            let line_index = doc.line_index();
             for offset in offsets {
                 let end_offset = offset + type_name.len();
                 let range = offset_range_to_range(&line_index, offset, end_offset);
-                results.push(adjust_range_for_line_offset(range, doc.line_offset));
+                results.push(adjust_range_for_block(range, doc.origin()));
             }
         }
     }
@@ -262,11 +315,11 @@ pub fn find_field_usages_in_parse(
     let mut results = Vec::new();
 
     for doc in parse.documents() {
-        let line_index = graphql_syntax::LineIndex::new(doc.source);
+        let line_index = doc.line_index();
         let ranges = find_field_usages_in_tree(doc.tree, type_name, field_name, schema_types);
         for (start, end) in ranges {
             let range = offset_range_to_range(&line_index, start, end);
-            results.push(adjust_range_for_line_offset(range, doc.line_offset));
+            results.push(adjust_range_for_block(range, doc.origin()));
         }
     }
 
@@ -281,11 +334,11 @@ pub fn find_directive_usages_in_parse(
     let mut results = Vec::new();
 
     for doc in parse.documents() {
-        let line_index = graphql_syntax::LineIndex::new(doc.source);
+        let line_index = doc.line_index();
         let ranges = find_directive_usages_in_tree(doc.tree, directive_name);
         for (start, end) in ranges {
             let range = offset_range_to_range(&line_index, start, end);
-            results.push(adjust_range_for_line_offset(range, doc.line_offset));
+            results.push(adjust_range_for_block(range, doc.origin()));
         }
     }
 
@@ -307,9 +360,9 @@ pub fn find_directive_definition_in_parse(
                         let range = name.syntax().text_range();
                         let start: usize = range.start().into();
                         let end: usize = range.end().into();
-                        let line_index = graphql_syntax::LineIndex::new(doc.source);
+                        let line_index = doc.line_index();
                         let pos_range = offset_range_to_range(&line_index, start, end);
-                        return Some(adjust_range_for_line_offset(pos_range, doc.line_offset));
+                        return Some(adjust_range_for_block(pos_range, doc.origin()));
                     }
                 }
             }
@@ -319,6 +372,45 @@ pub fn find_directive_definition_in_parse(
     None
 }
 
+/// Find an enum value's name ranges in a parsed file, in the enum's
+/// definition and any extensions of it
+pub fn find_enum_value_definitions_in_parse(
+    parse: &graphql_syntax::Parse,
+    enum_name: &str,
+    value: &str,
+) -> Vec<Range> {
+    use apollo_parser::cst::{CstNode, Definition};
+
+    let mut results = Vec::new();
+    for doc in parse.documents() {
+        let line_index = doc.line_index();
+        for definition in doc.tree.document().definitions() {
+            let (name, values) = match definition {
+                Definition::EnumTypeDefinition(def) => (def.name(), def.enum_values_definition()),
+                Definition::EnumTypeExtension(ext) => (ext.name(), ext.enum_values_definition()),
+                _ => continue,
+            };
+            if !name.is_some_and(|n| n.text() == enum_name) {
+                continue;
+            }
+            for value_def in values.iter().flat_map(|v| v.enum_value_definitions()) {
+                if let Some(value_name) = value_def.enum_value().and_then(|v| v.name()) {
+                    if value_name.text() == value {
+                        let range = value_name.syntax().text_range();
+                        let pos_range = offset_range_to_range(
+                            &line_index,
+                            range.start().into(),
+                            range.end().into(),
+                        );
+                        results.push(adjust_range_for_block(pos_range, doc.origin()));
+                    }
+                }
+            }
+        }
+    }
+    results
+}
+
 /// Find all usages of a directive by name in a single syntax tree.
 /// Returns `(start_offset, end_offset)` pairs for each directive name occurrence.
 fn find_directive_usages_in_tree(
@@ -540,7 +632,7 @@ fn find_directive_usages_in_tree(
 }
 
 /// Check if `current_type` matches `target_type` directly or implements it as an interface
-fn type_matches_or_implements(
+pub(crate) fn type_matches_or_implements(
     current_type: &str,
     target_type: &str,
     schema_types: &std::collections::HashMap<std::sync::Arc<str>, graphql_hir::TypeDef>,
@@ -687,7 +779,7 @@ pub fn find_variable_definition_in_tree(
     tree: &apollo_parser::SyntaxTree,
     var_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
 ) -> Option<Range> {
     use apollo_parser::cst::{CstNode, Definition};
 
@@ -703,7 +795,7 @@ pub fn find_variable_definition_in_tree(
                                 let start: usize = range.start().into();
                                 let end: usize = range.end().into();
                                 let pos_range = offset_range_to_range(line_index, start, end);
-                                return Some(adjust_range_for_line_offset(pos_range, line_offset));
+                                return Some(adjust_range_for_block(pos_range, origin));
                             }
                         }
                     }
@@ -719,7 +811,7 @@ pub fn find_operation_definition_in_tree(
     tree: &apollo_parser::SyntaxTree,
     op_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
 ) -> Option<Range> {
     use apollo_parser::cst::{CstNode, Definition};
 
@@ -732,7 +824,7 @@ pub fn find_operation_definition_in_tree(
                     let start: usize = range.start().into();
                     let end: usize = range.end().into();
                     let pos_range = offset_range_to_range(line_index, start, end);
-                    return Some(adjust_range_for_line_offset(pos_range, line_offset));
+                    return Some(adjust_range_for_block(pos_range, origin));
                 }
             }
         }
@@ -759,10 +851,7 @@ pub fn find_field_name_at_offset(
 
                 if byte_offset >= start && byte_offset <= end {
                     if let Some(args) = field.arguments() {
-                        let args_range = args.syntax().text_range();
-                        let args_start: usize = args_range.start().into();
-                        let args_end: usize = args_range.end().into();
-                        if byte_offset >= args_start && byte_offset <= args_end {
+                        if is_inside_parens(&args, byte_offset) {
                             return field.name().map(|n| n.text().to_string());
                         }
                     }
@@ -773,6 +862,12 @@ pub fn find_field_name_at_offset(
                         }
                     }
                 }
+            } else if let Selection::InlineFragment(inline_frag) = selection {
+                if let Some(nested) = inline_frag.selection_set() {
+                    if let Some(name) = check_selection_set(&nested, byte_offset) {
+                        return Some(name);
+                    }
+                }
             }
         }
         None
@@ -808,6 +903,8 @@ pub struct ArgumentContext {
     pub field_name: String,
     /// The argument name, if the cursor is inside a specific argument's value
     pub argument_name: Option<String>,
+    /// Where the cursor sits inside that argument's value
+    pub value_path: ValuePath,
 }
 
 /// Find the argument context at a given offset.
@@ -838,10 +935,8 @@ pub fn find_argument_context_at_offset(
 
                 if byte_offset >= start && byte_offset <= end {
                     if let Some(args) = field.arguments() {
-                        let args_range = args.syntax().text_range();
-                        let args_start: usize = args_range.start().into();
-                        let args_end: usize = args_range.end().into();
-                        if byte_offset >= args_start && byte_offset <= args_end {
+                        let args_start: usize = args.syntax().text_range().start().into();
+                        if is_inside_parens(&args, byte_offset) {
                             let field_name = field.name()?.text().to_string();
 
                             // Check if cursor is inside a specific argument's value
@@ -858,6 +953,11 @@ pub fn find_argument_context_at_offset(
                                             return Some(ArgumentContext {
                                                 field_name,
                                                 argument_name: Some(name.text().to_string()),
+                                                value_path: value_path_at(
+                                                    source,
+                                                    args_start,
+                                                    byte_offset,
+                                                ),
                                             });
                                         }
                                     }
@@ -873,12 +973,14 @@ pub fn find_argument_context_at_offset(
                                 return Some(ArgumentContext {
                                     field_name,
                                     argument_name: Some(arg_name),
+                                    value_path: value_path_at(source, args_start, byte_offset),
                                 });
                             }
 
                             return Some(ArgumentContext {
                                 field_name,
                                 argument_name: None,
+                                value_path: ValuePath::default(),
                             });
                         }
                     }
@@ -889,6 +991,12 @@ pub fn find_argument_context_at_offset(
                         }
                     }
                 }
+            } else if let Selection::InlineFragment(inline_frag) = selection {
+                if let Some(nested) = inline_frag.selection_set() {
+                    if let Some(ctx) = check_selection_set(&nested, byte_offset, source) {
+                        return Some(ctx);
+                    }
+                }
             }
         }
         None
@@ -918,6 +1026,24 @@ pub fn find_argument_context_at_offset(
     None
 }
 
+/// Check if the byte offset is between an argument list's parentheses.
+///
+/// Trivia after the `)` is part of the list's node, so bounding by the node
+/// would put a cursor on the lines after `@include(if: $flag)` inside its
+/// arguments. An unclosed list extends to the end of its node.
+fn is_inside_parens(args: &apollo_parser::cst::Arguments, byte_offset: usize) -> bool {
+    use apollo_parser::cst::CstNode;
+
+    let after_open = args
+        .l_paren_token()
+        .is_some_and(|token| byte_offset >= usize::from(token.text_range().end()));
+    let before_close = args.r_paren_token().map_or_else(
+        || byte_offset <= usize::from(args.syntax().text_range().end()),
+        |token| byte_offset <= usize::from(token.text_range().start()),
+    );
+    after_open && before_close
+}
+
 /// Scan backwards from cursor to find an `argName:` pattern.
 /// Returns the argument name if found.
 ///
@@ -973,6 +1099,173 @@ fn extract_arg_name(before_colon: &str) -> Option<String> {
     None
 }
 
+/// Where the cursor sits inside an argument value literal
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ValuePath {
+    /// Input object fields entered between the argument and the cursor,
+    /// outermost first. List brackets don't add a step: items share the
+    /// list's named type.
+    pub fields: Vec<String>,
+    /// Whether the cursor is at an input object field name rather than a value
+    pub at_field_name: bool,
+}
+
+/// A bracket the value scanner is inside of
+enum ValueFrame {
+    /// `(` or `{`: `name: value` pairs. `field` is the name whose value is
+    /// being written, or `None` while at a name.
+    Object {
+        field: Option<String>,
+    },
+    List,
+}
+
+/// Trace the literal between an argument list's `(` and the cursor.
+///
+/// Works on text rather than the CST so half-typed values such as
+/// `filter: { roles: [ADMIN, ` still resolve. `args_start` is the offset of
+/// the `(`.
+fn value_path_at(source: &str, args_start: usize, cursor_offset: usize) -> ValuePath {
+    let Some(text) = source.get(args_start..cursor_offset) else {
+        return ValuePath::default();
+    };
+
+    let mut frames: Vec<ValueFrame> = Vec::new();
+    let mut pending_name: Option<String> = None;
+    let mut chars = text.char_indices().peekable();
+
+    // A finished value returns the enclosing object to name position
+    let value_done = |frames: &mut Vec<ValueFrame>| {
+        if let Some(ValueFrame::Object { field }) = frames.last_mut() {
+            *field = None;
+        }
+    };
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '#' => while chars.next_if(|&(_, c)| c != '\n').is_some() {},
+            '"' => {
+                let block = text[i..].starts_with("\"\"\"");
+                if block {
+                    chars.next();
+                    chars.next();
+                }
+                let mut closed = false;
+                while let Some((j, c)) = chars.next() {
+                    if c == '\\' {
+                        chars.next();
+                    } else if c == '"' && (!block || text[j..].starts_with("\"\"\"")) {
+                        if block {
+                            chars.next();
+                            chars.next();
+                        }
+                        closed = true;
+                        break;
+                    }
+                }
+                if closed {
+                    value_done(&mut frames);
+                }
+            }
+            '(' | '{' => frames.push(ValueFrame::Object { field: None }),
+            '[' => frames.push(ValueFrame::List),
+            ')' | '}' | ']' => {
+                frames.pop();
+                value_done(&mut frames);
+            }
+            ':' => {
+                if let Some(ValueFrame::Object { field }) = frames.last_mut() {
+                    *field = pending_name.take();
+                }
+            }
+            c if c.is_alphanumeric() || matches!(c, '_' | '$' | '-' | '.') => {
+                let mut end = i + c.len_utf8();
+                while let Some((j, c)) = chars.next_if(|&(_, c)| {
+                    c.is_alphanumeric() || matches!(c, '_' | '$' | '-' | '.' | '+')
+                }) {
+                    end = j + c.len_utf8();
+                }
+                // A token touching the cursor is still being typed
+                if end == text.len() {
+                    break;
+                }
+                match frames.last() {
+                    Some(ValueFrame::Object { field: None }) => {
+                        pending_name = Some(text[i..end].to_string());
+                    }
+                    _ => value_done(&mut frames),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // The first frame is the argument list itself; its field is the argument
+    let mut path = ValuePath::default();
+    let Some((innermost, outer)) = frames.split_last() else {
+        return path;
+    };
+    for frame in outer.iter().skip(1) {
+        if let ValueFrame::Object { field: Some(field) } = frame {
+            path.fields.push(field.clone());
+        }
+    }
+    if frames.len() > 1 {
+        if let ValueFrame::Object { field } = innermost {
+            match field {
+                Some(field) => path.fields.push(field.clone()),
+                None => path.at_field_name = true,
+            }
+        }
+    }
+    path
+}
+
+/// Names already written in the input object literal around `byte_offset`.
+///
+/// Walks the CST, so keys after the cursor count too. A name under the
+/// cursor is the one being typed and is left out.
+pub fn specified_object_fields(
+    tree: &apollo_parser::SyntaxTree,
+    byte_offset: usize,
+) -> Vec<String> {
+    use apollo_parser::cst::{self, CstNode};
+
+    let contains = |range: apollo_parser::TextRange| {
+        let start: usize = range.start().into();
+        let end: usize = range.end().into();
+        byte_offset >= start && byte_offset <= end
+    };
+
+    // Descendants come in preorder, so the last enclosing object is the innermost
+    let Some(object) = tree
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::ObjectValue::cast)
+        .filter(|object| {
+            let range = object.syntax().text_range();
+            let after_open = object
+                .l_curly_token()
+                .is_some_and(|token| byte_offset >= usize::from(token.text_range().end()));
+            let before_close = object
+                .r_curly_token()
+                .is_none_or(|token| byte_offset <= usize::from(token.text_range().start()));
+            contains(range) && after_open && before_close
+        })
+        .last()
+    else {
+        return Vec::new();
+    };
+
+    object
+        .object_fields()
+        .filter_map(|field| field.name())
+        .filter(|name| !contains(name.syntax().text_range()))
+        .map(|name| name.text().to_string())
+        .collect()
+}
+
 /// Context about a directive argument at a cursor position
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DirectiveArgumentContext {
@@ -980,6 +1273,8 @@ pub struct DirectiveArgumentContext {
     pub directive_name: String,
     /// The argument name, if the cursor is inside a specific argument's value
     pub argument_name: Option<String>,
+    /// Where the cursor sits inside that argument's value
+    pub value_path: ValuePath,
 }
 
 /// Find the directive argument context at a given offset.
@@ -1000,10 +1295,8 @@ pub fn find_directive_argument_context_at_offset(
     ) -> Option<DirectiveArgumentContext> {
         for directive in directives.directives() {
             if let Some(args) = directive.arguments() {
-                let args_range = args.syntax().text_range();
-                let args_start: usize = args_range.start().into();
-                let args_end: usize = args_range.end().into();
-                if byte_offset >= args_start && byte_offset <= args_end {
+                let args_start: usize = args.syntax().text_range().start().into();
+                if is_inside_parens(&args, byte_offset) {
                     let directive_name = directive.name()?.text().to_string();
 
                     // Check CST argument nodes for value position
@@ -1018,6 +1311,7 @@ pub fn find_directive_argument_context_at_offset(
                                     return Some(DirectiveArgumentContext {
                                         directive_name,
                                         argument_name: Some(name.text().to_string()),
+                                        value_path: value_path_at(source, args_start, byte_offset),
                                     });
                                 }
                             }
@@ -1030,12 +1324,14 @@ pub fn find_directive_argument_context_at_offset(
                         return Some(DirectiveArgumentContext {
                             directive_name,
                             argument_name: Some(arg_name),
+                            value_path: value_path_at(source, args_start, byte_offset),
                         });
                     }
 
                     return Some(DirectiveArgumentContext {
                         directive_name,
                         argument_name: None,
+                        value_path: ValuePath::default(),
                     });
                 }
             }
@@ -1157,6 +1453,22 @@ pub fn find_operation_variables_at_offset(
     None
 }
 
+/// The named type inside a CST type reference, through list and non-null wrappers
+pub fn innermost_named_type(
+    ty: &apollo_parser::cst::Type,
+) -> Option<apollo_parser::cst::NamedType> {
+    use apollo_parser::cst::Type;
+
+    match ty {
+        Type::NamedType(named) => Some(named.clone()),
+        Type::ListType(list) => innermost_named_type(&list.ty()?),
+        Type::NonNullType(non_null) => non_null.named_type().or_else(|| {
+            let list = non_null.list_type()?;
+            innermost_named_type(&list.ty()?)
+        }),
+    }
+}
+
 /// Unwrap a `TypeRef` to get just the base type name (without List or `NonNull` wrappers)
 #[must_use]
 pub fn unwrap_type_to_name(type_ref: &graphql_hir::TypeRef) -> String {
@@ -1181,6 +1493,37 @@ pub fn format_type_ref(type_ref: &graphql_hir::TypeRef) -> String {
     result
 }
 
+/// The type a field has in the response once a client-controlled
+/// nullability designator overrides the schema's nullability
+pub fn designated_type_ref(
+    type_ref: &graphql_hir::TypeRef,
+    designator: Option<graphql_syntax::Nullability>,
+) -> graphql_hir::TypeRef {
+    let mut type_ref = type_ref.clone();
+    match designator {
+        Some(graphql_syntax::Nullability::Required) => type_ref.is_non_null = true,
+        Some(graphql_syntax::Nullability::Optional) => type_ref.is_non_null = false,
+        None => {}
+    }
+    type_ref
+}
+
+/// Format a type reference like [`format_type_ref`], followed by the wire
+/// format of a documented custom scalar (e.g. "DateTime! (ISO-8601 string)")
+pub fn format_type_with_scalar(
+    type_ref: &graphql_hir::TypeRef,
+    scalars: &std::collections::BTreeMap<String, graphql_config::ScalarConfig>,
+) -> String {
+    let formatted = format_type_ref(type_ref);
+    match scalars
+        .get(type_ref.name.as_ref())
+        .and_then(|scalar| scalar.serialization.as_deref())
+    {
+        Some(serialization) => format!("{formatted} ({serialization})"),
+        None => formatted,
+    }
+}
+
 /// Convert a filesystem path to a file:// URI
 ///
 /// Handles both Unix and Windows paths:
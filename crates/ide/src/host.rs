@@ -1,5 +1,8 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 
 use salsa::Setter;
 
@@ -15,14 +18,20 @@ fn next_snapshot_id() -> u64 {
 use crate::analysis::Analysis;
 #[cfg(feature = "extract")]
 use crate::database::ExtractConfigInput;
-use crate::database::{IdeDatabase, LintConfigInput};
+use crate::database::{
+    AnalyzerSettingsInput, ComplexityConfigInput, IdeDatabase, LintConfigInput, LintPluginsInput,
+    NPlusOneConfigInput, NullabilityConfigInput, OperationUsageInput, PersistedOperationsInput,
+    ScalarConfigInput, ScalarFormatsInput, ValidationConfigInput,
+};
 use crate::discovery::{
-    determine_document_file_kind, expand_braces, path_to_file_path, DiscoveredFile, LoadedFile,
+    determine_document_file_kind, path_to_file_path, DiscoveredFile, LoadedFile,
 };
 use crate::file_registry::FileRegistry;
 use crate::helpers::path_to_file_uri;
+use crate::metrics::{MetricsEvent, MetricsSink};
 use crate::types::{
-    DocumentLoadResult, FilePath, PendingIntrospection, SchemaContentError, SchemaLoadResult,
+    DocumentLoadResult, FilePath, PendingIntrospection, QueryMetrics, SchemaContentError,
+    SchemaLoadResult,
 };
 
 /// The main analysis host
@@ -59,6 +68,8 @@ pub struct AnalysisHost {
     /// reach back into the registry — they read everything via Salsa inputs
     /// (`FilePathMap`, `FileEntryMap`).
     registry: FileRegistry,
+    /// Where file structures are persisted, when that's on
+    structure_cache_path: Option<PathBuf>,
 }
 
 impl AnalysisHost {
@@ -68,6 +79,7 @@ impl AnalysisHost {
         Self {
             db: IdeDatabase::default(),
             registry: FileRegistry::new(),
+            structure_cache_path: None,
         }
     }
 
@@ -137,8 +149,99 @@ impl AnalysisHost {
     /// Internal: rebuild the `ProjectFiles` index and sync the cached input
     /// reference on the database.
     fn sync_project_files(&mut self) {
+        let started = Instant::now();
         self.registry.rebuild_project_files(&mut self.db);
         self.db.project_files_input = self.registry.project_files();
+        self.db.schema_variants_input = self.registry.schema_variant_file_ids();
+        self.apply_memory_settings();
+
+        let sink = &self.db.query_metrics.sink;
+        if sink.is_installed() {
+            sink.record(&MetricsEvent::ProjectRebuilt {
+                files: self.registry.all_file_ids().len(),
+                duration: started.elapsed(),
+            });
+        }
+    }
+
+    /// Persist file structures to `path`, reusing those an earlier session
+    /// saved there for files whose content hasn't changed.
+    ///
+    /// Only files whose structure hasn't been computed yet benefit, so this
+    /// should be called before documents are loaded. A missing, unreadable
+    /// or outdated cache file starts an empty cache.
+    pub fn enable_structure_cache(&mut self, path: PathBuf) {
+        if self.structure_cache_path.as_ref() == Some(&path) {
+            return;
+        }
+        let cache = std::fs::read(&path)
+            .map(|bytes| graphql_hir::StructureCache::from_json(&bytes))
+            .unwrap_or_default();
+        tracing::debug!(
+            path = %path.display(),
+            entries = cache.len(),
+            "Restored file structure cache"
+        );
+        self.db.structure_cache = Some(Arc::new(cache));
+        self.structure_cache_path = Some(path);
+    }
+
+    /// Stop persisting file structures. The cache file is left in place.
+    pub fn disable_structure_cache(&mut self) {
+        self.db.structure_cache = None;
+        self.structure_cache_path = None;
+    }
+
+    /// Write the structures of the project's files to the cache file,
+    /// returning how many were written. Does nothing when persistence is off.
+    pub fn save_structure_cache(&self) -> std::io::Result<usize> {
+        let (Some(cache), Some(path)) = (&self.db.structure_cache, &self.structure_cache_path)
+        else {
+            return Ok(0);
+        };
+        cache.retain_files(&self.registry.all_file_ids().into_iter().collect());
+        let (bytes, saved) = cache.to_json().map_err(std::io::Error::other)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Written beside the target and renamed into place, so a crash
+        // mid-write can't leave a truncated cache behind
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(saved)
+    }
+
+    /// Forget every persisted structure and delete the cache file.
+    ///
+    /// Structures already computed this session stay in the database, so the
+    /// next save only holds files re-parsed after the clear.
+    pub fn clear_structure_cache(&self) -> std::io::Result<()> {
+        if let Some(cache) = &self.db.structure_cache {
+            cache.clear();
+        }
+        match &self.structure_cache_path {
+            Some(path) => match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+
+    /// Internal: size the parse cache from the `[memory]` settings and the
+    /// current project. Only re-applied when the capacity changes.
+    fn apply_memory_settings(&mut self) {
+        let Some(input) = self.db.analyzer_settings_input else {
+            return;
+        };
+        let memory = input.settings(&self.db).memory;
+        let capacity =
+            crate::memory::parse_cache_capacity(&self.db, self.db.project_files_input, memory);
+        if capacity != self.db.parse_cache_capacity {
+            graphql_syntax::set_parse_cache_capacity(&mut self.db, capacity.unwrap_or(0));
+            self.db.parse_cache_capacity = capacity;
+        }
     }
 
     /// Add multiple files in batch, then rebuild the project index once.
@@ -196,6 +299,26 @@ impl AnalysisHost {
         }
     }
 
+    /// Move files to new paths in one step, then rebuild the project index
+    /// once.
+    ///
+    /// Each file keeps its content and cached analysis under its new path,
+    /// so snapshots never see both paths, or neither, as they would with a
+    /// `remove_file`/`add_file` pair. Renames whose source isn't loaded or
+    /// whose target already is are skipped. Returns how many files moved.
+    pub fn rename_files(&mut self, renames: &[(FilePath, FilePath)]) -> usize {
+        let mut moved = 0;
+        for (from, to) in renames {
+            if self.registry.rename_file(&mut self.db, from, to) {
+                moved += 1;
+            }
+        }
+        if moved > 0 {
+            self.sync_project_files();
+        }
+        moved
+    }
+
     /// Load schema files from a project configuration
     ///
     /// This method:
@@ -262,16 +385,21 @@ impl AnalysisHost {
                     "Found remote schema introspection config: {}",
                     introspection.url
                 );
-                pending_introspections.push(PendingIntrospection::from_config(introspection));
+                pending_introspections.push(PendingIntrospection {
+                    url_from_env: config.contains_env_value(&introspection.url),
+                    ..PendingIntrospection::from_config(introspection)
+                });
                 vec![]
             }
         };
 
+        let mut file_patterns = Vec::new();
         for pattern in patterns {
             // Collect URL patterns as pending introspections for async fetching
             if pattern.starts_with("http://") || pattern.starts_with("https://") {
                 tracing::info!("Found remote schema URL: {}", pattern);
                 pending_introspections.push(PendingIntrospection {
+                    url_from_env: config.contains_env_value(&pattern),
                     url: pattern,
                     headers: None,
                     timeout: None,
@@ -279,147 +407,49 @@ impl AnalysisHost {
                 });
                 continue;
             }
+            file_patterns.push(pattern);
+        }
 
-            // Treat as file glob pattern
-            let full_pattern = base_dir.join(&pattern).display().to_string();
-
-            match glob::glob(&full_pattern) {
-                Ok(paths) => {
-                    let mut pattern_matched_files = false;
-                    for entry in paths.flatten() {
-                        if entry.is_file() {
-                            pattern_matched_files = true;
-                            match std::fs::read_to_string(&entry) {
-                                Ok(content) => {
-                                    let file_uri = path_to_file_uri(&entry);
-                                    #[cfg(feature = "extract")]
-                                    let language = graphql_extract::Language::from_path(&entry);
-
-                                    // Check if this is a TS/JS file that needs extraction
-                                    #[cfg(feature = "extract")]
-                                    if let Some(lang) = language {
-                                        if lang.requires_extraction() {
-                                            // Extract GraphQL from TS/JS file
-                                            let extract_config = self.get_extract_config();
-                                            match graphql_extract::extract_from_source(
-                                                &content,
-                                                lang,
-                                                &extract_config,
-                                                &file_uri,
-                                            ) {
-                                                Ok(blocks) => {
-                                                    // Validate all blocks for executable definitions
-                                                    let all_sources: String = blocks
-                                                        .iter()
-                                                        .map(|b| b.source.as_str())
-                                                        .collect::<Vec<_>>()
-                                                        .join("\n");
-                                                    if let Some(mismatch) = graphql_syntax::validate_content_matches_kind(
-                                                        &all_sources,
-                                                        DocumentKind::Schema,
-                                                    ) {
-                                                        let definitions = match mismatch {
-                                                            graphql_syntax::ContentMismatch::ExpectedSchemaFoundExecutable { definitions } => definitions,
-                                                            graphql_syntax::ContentMismatch::ExpectedExecutableFoundSchema { .. } => Vec::new(),
-                                                        };
-                                                        content_errors.push(SchemaContentError {
-                                                            pattern: pattern.clone(),
-                                                            file_path: entry.clone(),
-                                                            unexpected_definitions: definitions,
-                                                        });
-                                                    }
-
-                                                    if blocks.len() == 1 {
-                                                        // Single block: store original TS/JS content
-                                                        // so the syntax crate can handle extraction
-                                                        // with proper line offsets
-                                                        self.add_file(
-                                                            &FilePath::new(file_uri.clone()),
-                                                            &content,
-                                                            lang,
-                                                            DocumentKind::Schema,
-                                                        );
-                                                        count += 1;
-                                                    } else {
-                                                        // Multiple blocks: create separate entries
-                                                        // with line range URIs for each block
-                                                        for block in &blocks {
-                                                            let start_line =
-                                                                block.location.range.start.line + 1;
-                                                            let end_line =
-                                                                block.location.range.end.line + 1;
-                                                            let block_uri = format!(
-                                                                "{file_uri}#L{start_line}-L{end_line}"
-                                                            );
-
-                                                            self.add_file(
-                                                                &FilePath::new(block_uri),
-                                                                &block.source,
-                                                                Language::GraphQL,
-                                                                DocumentKind::Schema,
-                                                            );
-                                                            count += 1;
-                                                        }
-                                                    }
-                                                    if blocks.is_empty() {
-                                                        tracing::debug!(
-                                                            "No GraphQL blocks found in {}",
-                                                            entry.display()
-                                                        );
-                                                    } else {
-                                                        loaded_paths.push(entry.clone());
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    tracing::warn!(
-                                                        "Failed to extract GraphQL from {}: {}",
-                                                        entry.display(),
-                                                        e
-                                                    );
-                                                }
-                                            }
-                                            continue;
-                                        }
-                                    }
-
-                                    // JSON introspection result file support
-                                    #[cfg(feature = "introspect")]
-                                    if entry.extension().and_then(|e| e.to_str()) == Some("json")
-                                        && graphql_introspect::is_introspection_json(&content)
-                                    {
-                                        match graphql_introspect::introspection_json_to_sdl(
-                                            &content,
-                                        ) {
-                                            Ok(sdl) => {
-                                                tracing::info!(
-                                                    "Loaded JSON introspection result from {}",
-                                                    entry.display()
-                                                );
-                                                self.add_file(
-                                                    &FilePath::new(file_uri),
-                                                    &sdl,
-                                                    Language::GraphQL,
-                                                    DocumentKind::Schema,
-                                                );
-                                                loaded_paths.push(entry.clone());
-                                                count += 1;
-                                            }
-                                            Err(e) => {
-                                                tracing::warn!(
-                                                    "Failed to parse JSON introspection result from {}: {}",
-                                                    entry.display(),
-                                                    e
-                                                );
-                                            }
-                                        }
-                                        continue;
-                                    }
+        // Treat the rest as file globs
+        let globs = config.file_globs(&file_patterns);
+        if let Some(error) = globs.errors().first() {
+            tracing::error!("{error}");
+            return Err(anyhow::anyhow!("{error}"));
+        }
+        let walk = globs.walk(base_dir);
+        for graphql_config::GlobMatch {
+            path: entry,
+            pattern,
+        } in walk.matches
+        {
+            match std::fs::read_to_string(&entry) {
+                Ok(content) => {
+                    let file_uri = path_to_file_uri(&entry);
+                    #[cfg(feature = "extract")]
+                    let language = graphql_extract::Language::from_path(&entry);
 
-                                    // Pure GraphQL file - validate and add
-                                    // Check for executable definitions (operations/fragments)
+                    // Check if this is a TS/JS file that needs extraction
+                    #[cfg(feature = "extract")]
+                    if let Some(lang) = language {
+                        if lang.requires_extraction() {
+                            // Extract GraphQL from TS/JS file
+                            let extract_config = self.get_extract_config();
+                            match graphql_extract::extract_from_source(
+                                &content,
+                                lang,
+                                &extract_config,
+                                &file_uri,
+                            ) {
+                                Ok(blocks) => {
+                                    // Validate all blocks for executable definitions
+                                    let all_sources: String = blocks
+                                        .iter()
+                                        .map(|b| b.source.as_str())
+                                        .collect::<Vec<_>>()
+                                        .join("\n");
                                     if let Some(mismatch) =
                                         graphql_syntax::validate_content_matches_kind(
-                                            &content,
+                                            &all_sources,
                                             DocumentKind::Schema,
                                         )
                                     {
@@ -434,44 +464,130 @@ impl AnalysisHost {
                                         });
                                     }
 
-                                    self.add_file(
-                                        &FilePath::new(file_uri),
-                                        &content,
-                                        Language::GraphQL,
-                                        DocumentKind::Schema,
-                                    );
-                                    loaded_paths.push(entry.clone());
-                                    count += 1;
+                                    if blocks.len() == 1 {
+                                        // Single block: store original TS/JS content
+                                        // so the syntax crate can handle extraction
+                                        // with proper line offsets
+                                        self.add_file(
+                                            &FilePath::new(file_uri.clone()),
+                                            &content,
+                                            lang,
+                                            DocumentKind::Schema,
+                                        );
+                                        count += 1;
+                                    } else {
+                                        // Multiple blocks: create separate entries
+                                        // with line range URIs for each block
+                                        for block in &blocks {
+                                            let start_line = block.location.range.start.line + 1;
+                                            let end_line = block.location.range.end.line + 1;
+                                            let block_uri =
+                                                format!("{file_uri}#L{start_line}-L{end_line}");
+
+                                            self.add_file(
+                                                &FilePath::new(block_uri),
+                                                &block.source,
+                                                Language::GraphQL,
+                                                DocumentKind::Schema,
+                                            );
+                                            count += 1;
+                                        }
+                                    }
+                                    if blocks.is_empty() {
+                                        tracing::debug!(
+                                            "No GraphQL blocks found in {}",
+                                            entry.display()
+                                        );
+                                    } else {
+                                        loaded_paths.push(entry.clone());
+                                    }
                                 }
                                 Err(e) => {
-                                    let path_display = entry.display().to_string();
-                                    tracing::error!(
-                                        "Failed to read schema file {path_display}: {e}"
+                                    tracing::warn!(
+                                        "Failed to extract GraphQL from {}: {}",
+                                        entry.display(),
+                                        e
                                     );
-                                    return Err(anyhow::anyhow!(
-                                        "Failed to read schema file {path_display}: {e}"
-                                    ));
                                 }
                             }
+                            continue;
                         }
                     }
-                    if !pattern_matched_files {
-                        tracing::debug!(
-                            "Schema pattern matched no files: {} (expanded: {})",
-                            pattern,
-                            full_pattern
-                        );
-                        unmatched_patterns.push(pattern.clone());
+
+                    // JSON introspection result file support
+                    #[cfg(feature = "introspect")]
+                    if entry.extension().and_then(|e| e.to_str()) == Some("json")
+                        && graphql_introspect::is_introspection_json(&content)
+                    {
+                        match graphql_introspect::introspection_json_to_sdl(&content) {
+                            Ok(sdl) => {
+                                tracing::info!(
+                                    "Loaded JSON introspection result from {}",
+                                    entry.display()
+                                );
+                                self.add_file(
+                                    &FilePath::new(file_uri),
+                                    &sdl,
+                                    Language::GraphQL,
+                                    DocumentKind::Schema,
+                                );
+                                loaded_paths.push(entry.clone());
+                                count += 1;
+                            }
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Failed to parse JSON introspection result from {}: {}",
+                                    entry.display(),
+                                    e
+                                );
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Pure GraphQL file - validate and add
+                    // Check for executable definitions (operations/fragments)
+                    if let Some(mismatch) = graphql_syntax::validate_content_matches_kind(
+                        &content,
+                        DocumentKind::Schema,
+                    ) {
+                        let definitions = match mismatch {
+                            graphql_syntax::ContentMismatch::ExpectedSchemaFoundExecutable {
+                                definitions,
+                            } => definitions,
+                            graphql_syntax::ContentMismatch::ExpectedExecutableFoundSchema {
+                                ..
+                            } => Vec::new(),
+                        };
+                        content_errors.push(SchemaContentError {
+                            pattern: pattern.clone(),
+                            file_path: entry.clone(),
+                            unexpected_definitions: definitions,
+                        });
                     }
+
+                    self.add_file(
+                        &FilePath::new(file_uri),
+                        &content,
+                        Language::GraphQL,
+                        DocumentKind::Schema,
+                    );
+                    loaded_paths.push(entry.clone());
+                    count += 1;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to expand glob pattern {full_pattern}: {e}");
+                    let path_display = entry.display().to_string();
+                    tracing::error!("Failed to read schema file {path_display}: {e}");
                     return Err(anyhow::anyhow!(
-                        "Failed to expand glob pattern {full_pattern}: {e}"
+                        "Failed to read schema file {path_display}: {e}"
                     ));
                 }
             }
         }
+        for pattern in walk.unmatched {
+            tracing::debug!("Schema pattern matched no files: {}", pattern);
+            unmatched_patterns.push(pattern);
+        }
 
         // Load resolved schema file if configured
         if let Some(resolved_path) = config.resolved_schema() {
@@ -509,6 +625,62 @@ impl AnalysisHost {
             }
         }
 
+        // Load schema variants. Documents opt in with a `schema-variant` pragma
+        // or by matching one of the variant's `documents` patterns.
+        for (variant, variant_config) in config.schema_variants() {
+            let globs = config.file_globs(&variant_config.schema_paths());
+            for error in globs.errors() {
+                tracing::warn!("{} (schema variant '{}')", error, variant);
+            }
+            let walk = globs.walk(base_dir);
+            for pattern in &walk.unmatched {
+                tracing::warn!(
+                    "Schema variant '{}' pattern matched no files: {}",
+                    variant,
+                    pattern
+                );
+            }
+            for variant_full in walk.paths() {
+                match std::fs::read_to_string(&variant_full) {
+                    Ok(variant_content) => {
+                        let file_uri = path_to_file_uri(&variant_full);
+                        let file_path = FilePath::new(file_uri);
+                        let (file_id, _, _, _) = self.registry.add_file(
+                            &mut self.db,
+                            &file_path,
+                            &variant_content,
+                            Language::GraphQL,
+                            DocumentKind::Schema,
+                        );
+                        self.registry.mark_as_schema_variant(file_id, &variant);
+                        tracing::info!(
+                            "Loaded schema variant '{}' from '{}'",
+                            variant,
+                            variant_full.display()
+                        );
+                        loaded_paths.push(variant_full);
+                        count += 1;
+                    }
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to read schema variant '{}' at '{}': {}",
+                            variant,
+                            variant_full.display(),
+                            e
+                        );
+                    }
+                }
+            }
+
+            let document_globs = config.file_globs(&variant_config.document_patterns());
+            for error in document_globs.errors() {
+                tracing::warn!("{} (schema variant '{}' documents)", error, variant);
+            }
+            self.registry
+                .set_variant_documents(&variant, base_dir, document_globs);
+        }
+        self.sync_project_files();
+
         tracing::info!(
             "Loaded {} schema file(s) ({} paths tracked), {} pending introspection(s)",
             count,
@@ -568,6 +740,16 @@ impl AnalysisHost {
         }
     }
 
+    /// Set the WebAssembly lint rules loaded for the project's `lint.plugins`
+    pub fn set_lint_plugins(&mut self, plugins: graphql_linter::LintPlugins) {
+        if let Some(input) = self.db.lint_plugins_input {
+            input.set_plugins(&mut self.db).to(Arc::new(plugins));
+        } else {
+            let input = LintPluginsInput::new(&self.db, Arc::new(plugins));
+            self.db.lint_plugins_input = Some(input);
+        }
+    }
+
     /// Read the currently-installed lint configuration.
     ///
     /// Used by callers (e.g. the napi binding) that swap in a per-call
@@ -581,6 +763,141 @@ impl AnalysisHost {
         )
     }
 
+    /// Cancel every outstanding snapshot, waiting until they've unwound
+    ///
+    /// Input setters already do this on their own. Call it before dropping
+    /// a host that snapshots may still be running against, so their
+    /// project-wide queries stop instead of running to completion on a
+    /// database nobody will read again.
+    pub fn request_cancellation(&mut self) {
+        salsa::Database::trigger_cancellation(&mut self.db);
+    }
+
+    /// Start or stop counting query executions, clearing any previous counts
+    ///
+    /// Meant for verifying incremental reuse, e.g. that a schema edit only
+    /// re-ran the validations it affected. Snapshots taken from this host
+    /// share the same counters.
+    pub fn set_query_metrics_enabled(&self, enabled: bool) {
+        self.db.query_metrics.set_enabled(enabled);
+    }
+
+    /// Execution and reuse counts per query name since metrics were enabled
+    #[must_use]
+    pub fn query_metrics(&self) -> HashMap<String, QueryMetrics> {
+        self.db.query_metrics.snapshot()
+    }
+
+    /// Send performance events to `sink`, or stop sending them with `None`
+    ///
+    /// Snapshots taken from this host report to the same sink.
+    pub fn set_metrics_sink(&self, sink: Option<Arc<dyn MetricsSink>>) {
+        self.db.query_metrics.sink.set(sink);
+    }
+
+    /// Set the cost model used by complexity analysis and operation hovers
+    pub fn set_complexity_config(&mut self, config: graphql_config::ComplexityConfig) {
+        if let Some(input) = self.db.complexity_config_input {
+            input.set_config(&mut self.db).to(Arc::new(config));
+        } else {
+            let input = ComplexityConfigInput::new(&self.db, Arc::new(config));
+            self.db.complexity_config_input = Some(input);
+        }
+    }
+
+    /// Set the severity profile applied to validation diagnostics
+    pub fn set_validation_config(&mut self, config: graphql_config::ValidationConfig) {
+        if let Some(input) = self.db.validation_config_input {
+            input.set_config(&mut self.db).to(Arc::new(config));
+        } else {
+            let input = ValidationConfigInput::new(&self.db, Arc::new(config));
+            self.db.validation_config_input = Some(input);
+        }
+    }
+
+    /// Set the operation usage report that hovers, code lenses and
+    /// `noUnusedFields` read call counts from, or clear it with `None`
+    pub fn set_operation_usage(&mut self, report: Option<graphql_config::OperationUsageReport>) {
+        let report = report.map(Arc::new);
+        if let Some(input) = self.db.operation_usage_input {
+            input.set_report(&mut self.db).to(report);
+        } else {
+            let input = OperationUsageInput::new(&self.db, report);
+            self.db.operation_usage_input = Some(input);
+        }
+    }
+
+    /// Set the persisted operation allow-list that operations are checked
+    /// against, or clear it with `None`
+    pub fn set_persisted_operations(
+        &mut self,
+        allow_list: Option<graphql_config::PersistedOperationAllowList>,
+    ) {
+        let allow_list = allow_list.map(Arc::new);
+        if let Some(input) = self.db.persisted_operations_input {
+            input.set_allow_list(&mut self.db).to(allow_list);
+        } else {
+            let input = PersistedOperationsInput::new(&self.db, allow_list);
+            self.db.persisted_operations_input = Some(input);
+        }
+    }
+
+    /// Set how nested lists are checked for N+1 selections, or turn the
+    /// check off with `None`
+    pub fn set_n_plus_one_config(&mut self, config: Option<graphql_config::NPlusOneConfig>) {
+        let config = config.map(Arc::new);
+        if let Some(input) = self.db.n_plus_one_config_input {
+            input.set_config(&mut self.db).to(config);
+        } else {
+            let input = NPlusOneConfigInput::new(&self.db, config);
+            self.db.n_plus_one_config_input = Some(input);
+        }
+    }
+
+    /// Set the custom scalar documentation shown in hovers, inlay hints and
+    /// completion details, and the formats their literals are validated
+    /// against
+    pub fn set_scalar_config(
+        &mut self,
+        scalars: std::collections::BTreeMap<String, graphql_config::ScalarConfig>,
+    ) {
+        let formats: std::collections::BTreeMap<_, _> = scalars
+            .iter()
+            .filter_map(|(name, scalar)| Some((name.clone(), scalar.format?)))
+            .collect();
+        // Only re-validate when a format changed, not the documentation
+        if let Some(input) = self.db.scalar_formats_input {
+            if *input.formats(&self.db) != formats {
+                input.set_formats(&mut self.db).to(Arc::new(formats));
+            }
+        } else {
+            let input = ScalarFormatsInput::new(&self.db, Arc::new(formats));
+            self.db.scalar_formats_input = Some(input);
+        }
+
+        if let Some(input) = self.db.scalar_config_input {
+            input.set_scalars(&mut self.db).to(Arc::new(scalars));
+        } else {
+            let input = ScalarConfigInput::new(&self.db, Arc::new(scalars));
+            self.db.scalar_config_input = Some(input);
+        }
+    }
+
+    /// Set the editor settings read from `.graphqlanalyzer.toml`
+    ///
+    /// Only the feature toggles, inlay hint kinds, formatting style and
+    /// memory budget are read from here; lint, complexity and validation
+    /// settings go through their own setters.
+    pub fn set_analyzer_settings(&mut self, settings: graphql_config::AnalyzerSettings) {
+        if let Some(input) = self.db.analyzer_settings_input {
+            input.set_settings(&mut self.db).to(Arc::new(settings));
+        } else {
+            let input = AnalyzerSettingsInput::new(&self.db, Arc::new(settings));
+            self.db.analyzer_settings_input = Some(input);
+        }
+        self.apply_memory_settings();
+    }
+
     /// Set the extract configuration for the project
     ///
     /// This properly invalidates all queries that depend on extract config via Salsa's
@@ -604,6 +921,17 @@ impl AnalysisHost {
             .unwrap_or_default()
     }
 
+    /// Allow client-controlled nullability designators (`field!`,
+    /// `field?`) in the project's documents
+    pub fn set_client_controlled_nullability(&mut self, enabled: bool) {
+        if let Some(input) = self.db.nullability_config_input {
+            input.set_enabled(&mut self.db).to(enabled);
+        } else {
+            let input = NullabilityConfigInput::new(&self.db, enabled);
+            self.db.nullability_config_input = Some(input);
+        }
+    }
+
     /// Load document files from a project configuration
     ///
     /// This method handles:
@@ -635,111 +963,61 @@ impl AnalysisHost {
             return (Vec::new(), DocumentLoadResult::default());
         };
 
-        let patterns: Vec<String> = documents_config
-            .patterns()
-            .into_iter()
-            .map(std::string::ToString::to_string)
-            .collect();
+        let globs = config.file_globs(&documents_config.patterns());
+        for error in globs.errors() {
+            tracing::error!("{error}");
+        }
+        let walk = globs.walk(workspace_path);
 
         let mut loaded_files: Vec<LoadedFile> = Vec::new();
         let mut files_to_add: Vec<(FilePath, String, Language, DocumentKind)> = Vec::new();
-        let mut unmatched_patterns: Vec<String> = Vec::new();
-
-        for pattern in patterns {
-            // Skip negation patterns
-            if pattern.trim().starts_with('!') {
-                continue;
-            }
 
-            let expanded_patterns = expand_braces(&pattern);
-            let mut pattern_matched_any_files = false;
-
-            for expanded_pattern in expanded_patterns {
-                let full_pattern = workspace_path.join(&expanded_pattern);
-
-                match glob::glob(&full_pattern.display().to_string()) {
-                    Ok(paths) => {
-                        for entry in paths {
-                            match entry {
-                                Ok(path) if path.is_file() => {
-                                    // Skip node_modules
-                                    if path.components().any(|c| c.as_os_str() == "node_modules") {
-                                        continue;
-                                    }
+        for path in walk.paths() {
+            let content = match std::fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    tracing::warn!("Failed to read file {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            let path_str = path.display().to_string();
+            let (language, document_kind) = determine_document_file_kind(&path_str, &content);
 
-                                    // Read file content
-                                    match std::fs::read_to_string(&path) {
-                                        Ok(content) => {
-                                            let path_str = path.display().to_string();
-                                            let (language, document_kind) =
-                                                determine_document_file_kind(&path_str, &content);
-
-                                            // Skip files that require extraction but contain no GraphQL
-                                            if language.requires_extraction() {
-                                                #[cfg(feature = "extract")]
-                                                {
-                                                    let blocks =
-                                                        graphql_extract::extract_from_source(
-                                                            &content,
-                                                            language,
-                                                            extract_config,
-                                                            &path_str,
-                                                        )
-                                                        .unwrap_or_default();
-                                                    if blocks.is_empty() {
-                                                        continue;
-                                                    }
-                                                }
-                                                #[cfg(not(feature = "extract"))]
-                                                {
-                                                    // No extractor available; skip files that need extraction.
-                                                    continue;
-                                                }
-                                            }
-
-                                            let file_path = path_to_file_path(&path);
-
-                                            pattern_matched_any_files = true;
-                                            loaded_files.push(LoadedFile {
-                                                path: file_path.clone(),
-                                                language,
-                                                document_kind,
-                                            });
-
-                                            files_to_add.push((
-                                                file_path,
-                                                content,
-                                                language,
-                                                document_kind,
-                                            ));
-                                        }
-                                        Err(e) => {
-                                            tracing::warn!(
-                                                "Failed to read file {}: {}",
-                                                path.display(),
-                                                e
-                                            );
-                                        }
-                                    }
-                                }
-                                Ok(_) => {}
-                                Err(e) => {
-                                    tracing::warn!("Glob entry error: {}", e);
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("Invalid glob pattern '{}': {}", expanded_pattern, e);
+            // Skip files that require extraction but contain no GraphQL
+            if language.requires_extraction() {
+                #[cfg(feature = "extract")]
+                {
+                    let blocks = graphql_extract::extract_from_source(
+                        &content,
+                        language,
+                        extract_config,
+                        &path_str,
+                    )
+                    .unwrap_or_default();
+                    if blocks.is_empty() {
+                        continue;
                     }
                 }
+                #[cfg(not(feature = "extract"))]
+                {
+                    // No extractor available; skip files that need extraction.
+                    continue;
+                }
             }
 
-            if !pattern_matched_any_files {
-                tracing::debug!("Document pattern matched no files: {}", pattern);
-                unmatched_patterns.push(pattern.clone());
-            }
+            let file_path = path_to_file_path(&path);
+            loaded_files.push(LoadedFile {
+                path: file_path.clone(),
+                language,
+                document_kind,
+            });
+            files_to_add.push((file_path, content, language, document_kind));
+        }
+
+        for pattern in &walk.unmatched {
+            tracing::debug!("Document pattern matched no files: {}", pattern);
         }
+        let unmatched_patterns = walk.unmatched;
 
         // Batch add all files using add_files_batch for O(n) performance
         // Convert owned strings to borrowed for the batch API
@@ -2,14 +2,22 @@
 //!
 //! This module provides IDE hover functionality including:
 //! - Field type and description information
+//! - The interface fields a field implements
+//! - Wire format and examples of documented custom scalars
 //! - Type kind and description
 //! - Fragment type condition
 //! - Field usage counts and deprecation info
+//! - Operation variables, complexity, persisted-query hash and recorded calls
+//! - Where a variable definition's value flows, and whether its type could be
+//!   non-null
+//! - Example response shapes for operations and fields with selection sets
 
 use std::fmt::Write as _;
 use std::sync::Arc;
 
-use crate::helpers::{find_block_for_position, format_type_ref, position_to_offset};
+use crate::helpers::{
+    designated_type_ref, find_block_for_position, format_type_ref, position_to_offset,
+};
 use crate::symbol::{find_parent_type_at_offset, find_symbol_at_offset, Symbol};
 use crate::types::{FilePath, HoverResult, Position};
 use crate::DbFiles;
@@ -21,15 +29,14 @@ pub fn hover(
     db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
     registry: DbFiles<'_>,
     project_files: Option<graphql_base_db::ProjectFiles>,
+    complexity_config: &graphql_config::ComplexityConfig,
+    scalars: &std::collections::BTreeMap<String, graphql_config::ScalarConfig>,
     file: &FilePath,
     position: Position,
 ) -> Option<HoverResult> {
-    let (content, metadata) = {
-        let file_id = registry.get_file_id(file)?;
-        let content = registry.get_content(file_id)?;
-        let metadata = registry.get_metadata(file_id)?;
-        (content, metadata)
-    };
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
 
     let parse = graphql_syntax::parse(db, content, metadata);
 
@@ -42,7 +49,7 @@ pub fn hover(
         adjusted_position
     );
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
     let symbol = find_symbol_at_offset(block_context.tree, offset);
@@ -55,25 +62,35 @@ pub fn hover(
         )));
     }
 
+    if symbol.is_none() {
+        let variable_hover = project_files.and_then(|project_files| {
+            variable_definition_hover(db, content, metadata, project_files, position)
+        });
+        if let Some(hover_text) = variable_hover {
+            return Some(HoverResult::new(hover_text));
+        }
+    }
+
     let symbol = symbol?;
     let project_files = project_files?;
 
     match symbol {
         Symbol::FieldName { name } => {
-            let types = graphql_hir::schema_types(db, project_files);
+            let types = graphql_hir::document_schema_types(db, project_files, file_id, content);
             let source_types = graphql_hir::source_schema_types(db, project_files);
 
-            let parent_type_name =
-                if let Some(parent_ctx) = find_parent_type_at_offset(block_context.tree, offset) {
-                    crate::symbol::walk_type_stack_to_offset(
-                        block_context.tree,
-                        types,
-                        offset,
-                        &parent_ctx.root_type,
-                    )?
-                } else {
-                    crate::symbol::find_schema_field_parent_type(block_context.tree, offset)?
-                };
+            let parent_ctx = find_parent_type_at_offset(block_context.tree, offset);
+            let in_document = parent_ctx.is_some();
+            let parent_type_name = if let Some(parent_ctx) = parent_ctx {
+                crate::symbol::walk_type_stack_to_offset(
+                    block_context.tree,
+                    types,
+                    offset,
+                    &parent_ctx.root_type,
+                )?
+            } else {
+                crate::symbol::find_schema_field_parent_type(block_context.tree, offset)?
+            };
 
             tracing::debug!(
                 "Hover: resolved parent type '{}' for field '{}'",
@@ -98,8 +115,44 @@ pub fn hover(
             if from_resolved {
                 write!(hover_text, "*(resolved schema)*\n\n").ok();
             }
-            let field_type = format_type_ref(&field.type_ref);
-            write!(hover_text, "**Type:** `{field_type}`\n\n").ok();
+            // A designator in the document overrides the schema's nullability
+            let designator = if in_document {
+                block_context.field_designator_at_offset(offset)
+            } else {
+                None
+            };
+            let type_ref = designated_type_ref(&field.type_ref, designator);
+            write!(hover_text, "**Type:** `{}`\n\n", format_type_ref(&type_ref)).ok();
+            if let Some(designator) = designator {
+                let made = match designator {
+                    graphql_syntax::Nullability::Required => "required",
+                    graphql_syntax::Nullability::Optional => "optional",
+                };
+                write!(
+                    hover_text,
+                    "*`{}` in the schema, made {made} by `{}`*\n\n",
+                    format_type_ref(&field.type_ref),
+                    designator.symbol()
+                )
+                .ok();
+            }
+            let semantic_levels = field.semantic_non_null_levels();
+            if !semantic_levels.is_empty() {
+                let subject = match semantic_levels.as_slice() {
+                    [0] => "The field",
+                    [1] => "The list items",
+                    _ => "The field and its list items",
+                };
+                write!(
+                    hover_text,
+                    "**Semantically non-null:** {subject} can only be null when an error occurs\n\n"
+                )
+                .ok();
+            }
+            write_implemented_fields(&mut hover_text, db, registry, types, parent_type, &name);
+            if let Some(scalar) = scalars.get(field.type_ref.name.as_ref()) {
+                write_scalar_info(&mut hover_text, scalar);
+            }
 
             let type_usages = graphql_analysis::field_usage_for_type(
                 db,
@@ -120,6 +173,26 @@ pub fn hover(
                 }
             }
 
+            let selections = in_document
+                .then(|| {
+                    crate::response_shape::field_selections_at_offset(block_context.tree, offset)
+                })
+                .flatten();
+            if let Some(selections) = selections {
+                let example = crate::response_shape::example_field_value(
+                    db,
+                    project_files,
+                    types,
+                    &type_ref,
+                    &selections,
+                );
+                write!(
+                    hover_text,
+                    "**Example response:**\n\n```jsonc\n{example}\n```\n\n"
+                )
+                .ok();
+            }
+
             if let Some(desc) = &field.description {
                 write!(hover_text, "---\n\n{desc}\n\n").ok();
             }
@@ -136,7 +209,7 @@ pub fn hover(
             Some(HoverResult::new(hover_text))
         }
         Symbol::TypeName { name } => {
-            let types = graphql_hir::schema_types(db, project_files);
+            let types = graphql_hir::document_schema_types(db, project_files, file_id, content);
             let type_def = types.get(name.as_str())?;
 
             let mut hover_text = format!("**Type:** `{name}`\n\n");
@@ -150,6 +223,9 @@ pub fn hover(
                 _ => "Unknown",
             };
             write!(hover_text, "**Kind:** {kind_str}\n\n").ok();
+            if let Some(scalar) = scalars.get(name.as_str()) {
+                write_scalar_info(&mut hover_text, scalar);
+            }
 
             if let Some(desc) = &type_def.description {
                 write!(hover_text, "---\n\n{desc}\n\n").ok();
@@ -236,10 +312,244 @@ pub fn hover(
 
             Some(HoverResult::new(hover_text))
         }
+        Symbol::OperationName { name } => {
+            let operations = graphql_hir::all_operations(db, project_files);
+            let operation = operations
+                .iter()
+                .find(|op| op.file_id == file_id && op.name.as_deref() == Some(name.as_str()))?;
+            Some(HoverResult::new(operation_hover_text(
+                db,
+                registry,
+                project_files,
+                complexity_config,
+                operation,
+            )))
+        }
+        Symbol::EnumValue { .. } => None,
         _ => Some(HoverResult::new(format!("Symbol: {symbol:?}"))),
     }
 }
 
+/// List the interface fields a field implements, each linked to its
+/// definition
+fn write_implemented_fields(
+    hover_text: &mut String,
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    types: &graphql_hir::TypeDefMap,
+    parent_type: &graphql_hir::TypeDef,
+    field_name: &str,
+) {
+    let links: Vec<String> = parent_type
+        .implements
+        .iter()
+        .filter_map(|interface| {
+            let field = types
+                .get(interface.as_ref())?
+                .fields
+                .iter()
+                .find(|f| f.name.as_ref() == field_name)?;
+            let label = format!("`{interface}.{field_name}`");
+            let (Some(path), Some(content)) = (
+                registry.get_path(field.file_id),
+                registry.get_content(field.file_id),
+            ) else {
+                return Some(label);
+            };
+            let (line, _) =
+                graphql_syntax::line_index(db, content).line_col(field.name_range.start().into());
+            Some(format!("[{label}]({}#L{})", path.as_str(), line + 1))
+        })
+        .collect();
+    if !links.is_empty() {
+        write!(hover_text, "**Implements:** {}\n\n", links.join(", ")).ok();
+    }
+}
+
+/// Describe how a documented custom scalar is serialized
+fn write_scalar_info(hover_text: &mut String, scalar: &graphql_config::ScalarConfig) {
+    if let Some(serialization) = &scalar.serialization {
+        write!(hover_text, "**Serialized as:** {serialization}\n\n").ok();
+    }
+    if let Some(example) = &scalar.example {
+        write!(hover_text, "**Example:** `{example}`\n\n").ok();
+    }
+    if let Some(description) = &scalar.description {
+        write!(hover_text, "{description}\n\n").ok();
+    }
+}
+
+/// Usage summary for the variable defined at `position`: the arguments and
+/// input fields it's passed to, and a note when it's declared nullable but
+/// every one of them requires a value.
+fn variable_definition_hover(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+    project_files: graphql_base_db::ProjectFiles,
+    position: Position,
+) -> Option<String> {
+    let flows = graphql_analysis::variable_flows(db, content, metadata, project_files);
+    let at = (position.line, position.character);
+    let flow = flows.iter().find(|flow| {
+        let range = flow.range;
+        (range.start.line, range.start.character) <= at
+            && at <= (range.end.line, range.end.character)
+    })?;
+
+    let mut hover_text = format!(
+        "**Variable:** `${}: {}`
+
+",
+        flow.name, flow.declared_type
+    );
+    if flow.usages.is_empty() {
+        write!(
+            hover_text,
+            "**Used by:** nothing
+
+"
+        )
+        .ok();
+        return Some(hover_text);
+    }
+
+    write!(
+        hover_text,
+        "**Used by:**
+
+"
+    )
+    .ok();
+    for usage in &flow.usages {
+        let target = usage.target.as_deref().unwrap_or("an unknown argument");
+        write!(hover_text, "- `{target}`").ok();
+        if let Some(expected) = &usage.expected_type {
+            write!(hover_text, " expects `{expected}`").ok();
+        }
+        if let Some(fragment) = &usage.fragment {
+            write!(hover_text, " (in fragment `{fragment}`)").ok();
+        }
+        writeln!(hover_text).ok();
+    }
+    writeln!(hover_text).ok();
+
+    if let Some(narrower) = flow.narrower_type() {
+        write!(
+            hover_text,
+            "Every usage requires a value, so this could be declared `{narrower}`\n\n"
+        )
+        .ok();
+    }
+
+    Some(hover_text)
+}
+
+/// Summary of an operation: variables, complexity, persisted-query hash and,
+/// with an operation usage report loaded, recorded calls.
+fn operation_hover_text(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+    complexity_config: &graphql_config::ComplexityConfig,
+    operation: &graphql_hir::OperationStructure,
+) -> String {
+    let keyword = match operation.operation_type {
+        graphql_hir::OperationType::Mutation => "mutation",
+        graphql_hir::OperationType::Subscription => "subscription",
+        _ => "query",
+    };
+    let name = operation.name.as_deref().unwrap_or_default();
+    let mut hover_text = format!("**Operation:** `{keyword} {name}`\n\n");
+
+    if operation.variables.is_empty() {
+        write!(hover_text, "**Variables:** none\n\n").ok();
+    } else {
+        write!(hover_text, "**Variables:**\n\n").ok();
+        for variable in &operation.variables {
+            let type_str = format_type_ref(&variable.type_ref);
+            if let Some(default) = &variable.default_value {
+                writeln!(
+                    hover_text,
+                    "- `${}: {} = {}`",
+                    variable.name, type_str, default
+                )
+                .ok();
+            } else {
+                writeln!(hover_text, "- `${}: {}`", variable.name, type_str).ok();
+            }
+        }
+        writeln!(hover_text).ok();
+    }
+
+    let Some(file) = registry.resolve(operation.file_id) else {
+        return hover_text;
+    };
+
+    if let Some(complexity) = crate::analysis::operation_complexity(
+        db,
+        &file,
+        project_files,
+        complexity_config,
+        operation,
+    ) {
+        write!(
+            hover_text,
+            "**Complexity:** {} (depth {})\n\n",
+            complexity.total_complexity, complexity.depth
+        )
+        .ok();
+    }
+
+    let document = graphql_analysis::persisted_query_document(
+        db,
+        file.content,
+        file.metadata,
+        operation.index,
+        project_files,
+    );
+    if let Some(document) = document {
+        let hash = graphql_analysis::persisted_query_hash(&document);
+        write!(hover_text, "**Persisted query hash:** `{hash}`\n\n").ok();
+    }
+
+    #[cfg(feature = "codegen")]
+    if let Some(name) = &operation.name {
+        let (result_type, variables_type) =
+            crate::operation_types::type_names(name, operation.operation_type);
+        write!(
+            hover_text,
+            "**Generated types:** `{result_type}`, `{variables_type}`\n\n"
+        )
+        .ok();
+    }
+
+    if let Some(calls) = db.operation_calls(operation.file_id, operation.index) {
+        let calls = crate::types::RecordedCalls::from(calls);
+        write!(hover_text, "**Traffic:** {calls}\n\n").ok();
+    }
+
+    let root_type_name =
+        graphql_apollo_ext::OperationType::from(operation.operation_type).root_type_name();
+    let types =
+        graphql_hir::document_schema_types(db, project_files, operation.file_id, file.content);
+    let body = graphql_hir::operation_body(db, file.content, file.metadata, operation.index);
+    let example = crate::response_shape::example_response(
+        db,
+        project_files,
+        types,
+        &body.selections,
+        root_type_name,
+    );
+    write!(
+        hover_text,
+        "**Example response:**\n\n```jsonc\n{example}\n```\n\n"
+    )
+    .ok();
+
+    hover_text
+}
+
 pub(crate) fn format_directive_location(
     location: graphql_hir::DirectiveLocationKind,
 ) -> &'static str {
@@ -0,0 +1,135 @@
+//! Which operations a schema field change would break.
+//!
+//! An operation depends on `Type.field` when it selects the field on that
+//! type, directly or through any of the fragments it spreads. Removing the
+//! field, or changing its type, invalidates every such operation; an
+//! operation selecting the field only through an interface the type
+//! implements isn't affected, since the interface field stays.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+
+use crate::helpers::{adjust_range_for_block, offset_range_to_range};
+use crate::types::{FieldImpact, ImpactedOperation, Location};
+use crate::DbFiles;
+
+/// Find the operations depending on `type_name.field_name`.
+///
+/// Returns `None` when the type has no such field.
+pub fn impact_of_change(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    type_name: &str,
+    field_name: &str,
+) -> Option<FieldImpact> {
+    let project_files = project_files?;
+    let types = graphql_hir::schema_types(db, project_files);
+    let type_def = types.get(type_name)?;
+    let field = type_def
+        .fields
+        .iter()
+        .find(|field| field.name.as_ref() == field_name)?;
+
+    let coordinate = graphql_hir::SchemaCoordinate {
+        type_name: type_def.name.clone(),
+        field_name: field.name.clone(),
+    };
+    let mut operations = Vec::new();
+    for_each_operation(db, registry, project_files, |operation, selected| {
+        if selected.contains(&coordinate) {
+            operations.push(operation.clone());
+        }
+    });
+
+    Some(FieldImpact {
+        type_name: type_name.to_string(),
+        field_name: field_name.to_string(),
+        operations,
+    })
+}
+
+/// The operations depending on each schema coordinate any operation selects
+pub(crate) fn operations_by_field(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+) -> HashMap<graphql_hir::SchemaCoordinate, Vec<ImpactedOperation>> {
+    let mut operations: HashMap<graphql_hir::SchemaCoordinate, Vec<ImpactedOperation>> =
+        HashMap::new();
+    for_each_operation(db, registry, project_files, |operation, selected| {
+        for coordinate in selected {
+            operations
+                .entry(coordinate.clone())
+                .or_default()
+                .push(operation.clone());
+        }
+    });
+    operations
+}
+
+/// Call `f` with every operation in the project's documents and the schema
+/// coordinates it selects, including through fragments.
+///
+/// An operation's location is its name, or the whole operation when it's
+/// anonymous.
+pub(crate) fn for_each_operation(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+    mut f: impl FnMut(&ImpactedOperation, &HashSet<graphql_hir::SchemaCoordinate>),
+) {
+    for file_id in project_files.document_file_ids(db).ids(db).iter() {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        else {
+            continue;
+        };
+        let Some(path) = registry.get_path(*file_id) else {
+            continue;
+        };
+        let coordinates: Arc<Vec<HashSet<graphql_hir::SchemaCoordinate>>> =
+            graphql_hir::operation_schema_coordinates(db, content, metadata, project_files);
+        let parse = graphql_syntax::parse(db, content, metadata);
+
+        // Operations are numbered across the file's documents, the same way
+        // `operation_schema_coordinates` indexes them
+        let mut operation_index = 0;
+        for doc in parse.documents() {
+            let doc_line_index = doc.line_index();
+            for definition in doc.tree.document().definitions() {
+                let cst::Definition::OperationDefinition(operation) = definition else {
+                    continue;
+                };
+                let selected = coordinates.get(operation_index);
+                operation_index += 1;
+                let Some(selected) = selected else {
+                    continue;
+                };
+
+                let name = operation.name();
+                let node = name
+                    .as_ref()
+                    .map_or_else(|| operation.syntax().clone(), |name| name.syntax().clone());
+                let range = node.text_range();
+                let location = Location::new(
+                    path.clone(),
+                    adjust_range_for_block(
+                        offset_range_to_range(
+                            &doc_line_index,
+                            range.start().into(),
+                            range.end().into(),
+                        ),
+                        doc.origin(),
+                    ),
+                );
+                let operation = ImpactedOperation {
+                    name: name.map(|name| name.text().to_string()),
+                    location,
+                };
+                f(&operation, selected);
+            }
+        }
+    }
+}
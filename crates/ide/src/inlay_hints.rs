@@ -1,17 +1,24 @@
 //! Inlay hints feature implementation.
 //!
 //! This module provides IDE inlay hints functionality:
-//! - Field return types (displayed after field selections)
+//! - Field return types (displayed after field selections), with the wire
+//!   format of documented custom scalars
+//! - Variable types where a variable is passed as an argument
+//! - The type condition of a spread fragment (`...UserFields on User`)
+//! - Defaults of omitted arguments and input object fields, the values the
+//!   server fills in (`users(limit = 10)`)
 //!
-//! Note: Variable definition types are NOT shown as hints since they already
-//! have explicit type annotations in the GraphQL syntax.
+//! Note: Variable definitions don't get hints since they already have
+//! explicit type annotations in the GraphQL syntax.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
-use apollo_parser::cst::{CstNode, Definition, Selection};
+use apollo_parser::cst::{CstNode, Definition, Selection, Value};
 
-use crate::helpers::{format_type_ref, offset_to_position};
+use crate::helpers::{
+    designated_type_ref, format_type_ref, format_type_with_scalar, offset_to_position,
+};
 use crate::types::{FilePath, InlayHint, InlayHintKind, Position, Range};
 use crate::DbFiles;
 
@@ -29,6 +36,8 @@ pub fn inlay_hints(
     project_files: Option<graphql_base_db::ProjectFiles>,
     file: &FilePath,
     range: Option<Range>,
+    settings: &graphql_config::InlayHintSettings,
+    scalars: &BTreeMap<String, graphql_config::ScalarConfig>,
 ) -> Vec<InlayHint> {
     let (content, metadata) = {
         let Some(file_id) = registry.get_file_id(file) else {
@@ -52,103 +61,112 @@ pub fn inlay_hints(
     let parse = graphql_syntax::parse(db, content, metadata);
     let schema_types = graphql_hir::schema_types(db, project_files);
 
+    let fragments = graphql_hir::all_fragments(db, project_files);
     let mut hints = Vec::new();
 
     for doc in parse.documents() {
-        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-        let line_offset = doc.line_offset;
-
-        collect_hints_from_tree(
-            doc.tree,
+        let line_index = doc.line_index();
+        let mut collector = HintCollector {
             schema_types,
-            &doc_line_index,
-            line_offset,
+            fragments,
+            line_index: &line_index,
+            source: doc.source,
+            designators: doc.designators,
+            origin: doc.origin(),
             range,
-            &mut hints,
-        );
+            settings: *settings,
+            scalars,
+            variables: HashMap::new(),
+            hints: &mut hints,
+        };
+        collector.collect_tree(doc.tree);
     }
 
     hints
 }
 
-/// Collect inlay hints from a syntax tree
-fn collect_hints_from_tree(
-    tree: &apollo_parser::SyntaxTree,
-    schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
-    line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+/// Hint collection state for one GraphQL block
+struct HintCollector<'a> {
+    schema_types: &'a HashMap<Arc<str>, graphql_hir::TypeDef>,
+    fragments: &'a graphql_hir::FragmentMap,
+    line_index: &'a graphql_syntax::LineIndex,
+    source: &'a str,
+    /// Client-controlled nullability designators in `source`
+    designators: &'a [graphql_syntax::NullabilityDesignator],
+    origin: graphql_syntax::BlockOrigin,
     range: Option<Range>,
-    hints: &mut Vec<InlayHint>,
-) {
-    let doc = tree.document();
-
-    for definition in doc.definitions() {
-        match definition {
-            Definition::OperationDefinition(op) => {
-                let root_type = match op.operation_type() {
-                    Some(op_type) if op_type.mutation_token().is_some() => "Mutation",
-                    Some(op_type) if op_type.subscription_token().is_some() => "Subscription",
-                    _ => "Query",
-                };
+    settings: graphql_config::InlayHintSettings,
+    scalars: &'a BTreeMap<String, graphql_config::ScalarConfig>,
+    /// Declared types of the enclosing operation's variables, as written
+    variables: HashMap<String, String>,
+    hints: &'a mut Vec<InlayHint>,
+}
 
-                // Collect field hints from selection set
-                if let Some(selection_set) = op.selection_set() {
-                    collect_selection_set_hints(
-                        &selection_set,
-                        root_type,
-                        schema_types,
-                        line_index,
-                        line_offset,
-                        range,
-                        hints,
-                    );
+impl HintCollector<'_> {
+    /// Collect inlay hints from a syntax tree
+    fn collect_tree(&mut self, tree: &apollo_parser::SyntaxTree) {
+        let doc = tree.document();
+
+        for definition in doc.definitions() {
+            match definition {
+                Definition::OperationDefinition(op) => {
+                    let root_type = match op.operation_type() {
+                        Some(op_type) if op_type.mutation_token().is_some() => "Mutation",
+                        Some(op_type) if op_type.subscription_token().is_some() => "Subscription",
+                        _ => "Query",
+                    };
+
+                    self.variables = op
+                        .variable_definitions()
+                        .into_iter()
+                        .flat_map(|defs| defs.variable_definitions())
+                        .filter_map(|def| {
+                            let name = def.variable()?.name()?.text().to_string();
+                            let ty = def.ty()?.syntax().text().to_string();
+                            Some((name, ty.trim().to_string()))
+                        })
+                        .collect();
+
+                    // Collect field hints from selection set
+                    if let Some(selection_set) = op.selection_set() {
+                        self.collect_selection_set(&selection_set, root_type);
+                    }
                 }
-            }
-            Definition::FragmentDefinition(frag) => {
-                let fragment_type = frag
-                    .type_condition()
-                    .and_then(|tc| tc.named_type())
-                    .and_then(|nt| nt.name())
-                    .map(|n| n.text().to_string());
-
-                if let (Some(type_name), Some(selection_set)) =
-                    (fragment_type, frag.selection_set())
-                {
-                    collect_selection_set_hints(
-                        &selection_set,
-                        &type_name,
-                        schema_types,
-                        line_index,
-                        line_offset,
-                        range,
-                        hints,
-                    );
+                Definition::FragmentDefinition(frag) => {
+                    self.variables.clear();
+
+                    let fragment_type = frag
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|nt| nt.name())
+                        .map(|n| n.text().to_string());
+
+                    if let (Some(type_name), Some(selection_set)) =
+                        (fragment_type, frag.selection_set())
+                    {
+                        self.collect_selection_set(&selection_set, &type_name);
+                    }
                 }
+                _ => {}
             }
-            _ => {}
         }
     }
-}
-
-/// Collect field type hints from a selection set
-fn collect_selection_set_hints(
-    selection_set: &apollo_parser::cst::SelectionSet,
-    parent_type: &str,
-    schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
-    line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
-    range: Option<Range>,
-    hints: &mut Vec<InlayHint>,
-) {
-    // Early return if parent type is unknown - no type info available for hints
-    let Some(type_def) = schema_types.get(parent_type) else {
-        return;
-    };
 
-    for selection in selection_set.selections() {
-        match selection {
-            Selection::Field(field) => {
-                if let Some(name) = field.name() {
+    /// Collect field type hints from a selection set
+    fn collect_selection_set(
+        &mut self,
+        selection_set: &apollo_parser::cst::SelectionSet,
+        parent_type: &str,
+    ) {
+        let type_def = self.schema_types.get(parent_type);
+
+        for selection in selection_set.selections() {
+            match selection {
+                Selection::Field(field) => {
+                    // No type info for fields of an unknown parent type
+                    let (Some(type_def), Some(name)) = (type_def, field.name()) else {
+                        continue;
+                    };
                     let field_name = name.text();
 
                     // Get position after the alias (if present) or field name.
@@ -161,102 +179,272 @@ fn collect_selection_set_hints(
                     // Handle __typename introspection field specially
                     // It's always available on any type and returns String!
                     if field_name == "__typename" {
-                        if field.selection_set().is_none() {
-                            let end_offset: usize = end_node.into();
-                            let position = offset_to_position(line_index, end_offset);
-                            let adjusted = adjust_position_for_line_offset(position, line_offset);
-
-                            if should_include_position(adjusted, range) {
-                                hints.push(InlayHint::new(
-                                    adjusted,
-                                    ": String!".to_string(),
-                                    InlayHintKind::Type,
-                                ));
-                            }
+                        if field.selection_set().is_none() && self.settings.typename {
+                            self.push(end_node.into(), ": String!".to_string());
                         }
                         continue;
                     }
 
                     // Find field type in schema
-                    if let Some(field_def) = type_def
+                    let Some(field_def) = type_def
                         .fields
                         .iter()
                         .find(|f| f.name.as_ref() == field_name)
-                    {
-                        let nested = field.selection_set();
-
-                        // For non-leaf fields, position hint after arguments
-                        // (before the opening brace) when present
-                        let hint_end_node = if nested.is_some() {
-                            field
-                                .arguments()
-                                .map_or(end_node, |args| args.syntax().text_range().end())
-                        } else {
-                            end_node
-                        };
-
-                        let end_offset: usize = hint_end_node.into();
-                        let position = offset_to_position(line_index, end_offset);
-                        let adjusted = adjust_position_for_line_offset(position, line_offset);
-
-                        if should_include_position(adjusted, range) {
-                            let type_str = format_type_ref(&field_def.type_ref);
-                            hints.push(InlayHint::new(
-                                adjusted,
-                                format!(": {type_str}"),
-                                InlayHintKind::Type,
-                            ));
-                        }
+                    else {
+                        continue;
+                    };
+                    let nested = field.selection_set();
+                    let designator =
+                        graphql_syntax::field_designator(self.source, self.designators, &field);
+
+                    // For non-leaf fields, position hint after arguments
+                    // (before the opening brace) when present. A designator
+                    // always comes last, so the hint follows it.
+                    let (hint_end, wanted): (usize, bool) = if nested.is_some() {
+                        let end = field
+                            .arguments()
+                            .map_or(end_node, |args| args.syntax().text_range().end());
+                        (end.into(), self.settings.selection_sets)
+                    } else {
+                        (end_node.into(), self.settings.leaf_fields)
+                    };
+                    let hint_end = designator.map_or(hint_end, |d| d.offset + 1);
+
+                    // Pushed before the type hint, which may share its position
+                    self.collect_default_hints(
+                        name.syntax().text_range().end().into(),
+                        field.arguments().as_ref(),
+                        &field_def.arguments,
+                    );
 
-                        // Recurse into nested selection sets
-                        if let Some(nested) = nested {
-                            let field_type_name = field_def.type_ref.name.as_ref();
-                            collect_selection_set_hints(
-                                &nested,
-                                field_type_name,
-                                schema_types,
-                                line_index,
-                                line_offset,
-                                range,
-                                hints,
-                            );
-                        }
+                    if wanted {
+                        let type_ref = designated_type_ref(
+                            &field_def.type_ref,
+                            designator.map(|d| d.nullability),
+                        );
+                        let type_str = format_type_with_scalar(&type_ref, self.scalars);
+                        self.push(hint_end, format!(": {type_str}"));
+                    }
+
+                    if let Some(arguments) = field.arguments() {
+                        self.collect_variable_hints(&arguments, &field_def.arguments);
+                    }
+
+                    // Recurse into nested selection sets
+                    if let Some(nested) = nested {
+                        let field_type_name = field_def.type_ref.name.clone();
+                        self.collect_selection_set(&nested, &field_type_name);
+                    }
+                }
+                Selection::InlineFragment(inline_frag) => {
+                    let fragment_type = inline_frag
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|nt| nt.name())
+                        .map_or_else(|| parent_type.to_string(), |n| n.text().to_string());
+
+                    if let Some(nested) = inline_frag.selection_set() {
+                        self.collect_selection_set(&nested, &fragment_type);
+                    }
+                }
+                Selection::FragmentSpread(spread) => {
+                    // Field hints live on the fragment definition; the spread
+                    // only shows the type the fragment applies to
+                    if !self.settings.fragment_spreads {
+                        continue;
+                    }
+                    let Some(name) = spread.fragment_name().and_then(|n| n.name()) else {
+                        continue;
+                    };
+                    if let Some(fragment) = self.fragments.get(name.text().as_str()) {
+                        let label = format!("on {}", fragment.type_condition);
+                        self.push(name.syntax().text_range().end().into(), label);
                     }
                 }
             }
-            Selection::InlineFragment(inline_frag) => {
-                let fragment_type = inline_frag
-                    .type_condition()
-                    .and_then(|tc| tc.named_type())
-                    .and_then(|nt| nt.name())
-                    .map_or_else(|| parent_type.to_string(), |n| n.text().to_string());
-
-                if let Some(nested) = inline_frag.selection_set() {
-                    collect_selection_set_hints(
-                        &nested,
-                        &fragment_type,
-                        schema_types,
-                        line_index,
-                        line_offset,
-                        range,
-                        hints,
-                    );
+        }
+    }
+
+    /// Hint the type of each variable passed directly as an argument: its
+    /// declared type in the enclosing operation, or the argument's type
+    /// inside a fragment.
+    fn collect_variable_hints(
+        &mut self,
+        arguments: &apollo_parser::cst::Arguments,
+        argument_defs: &[graphql_hir::ArgumentDef],
+    ) {
+        if !self.settings.variable_types {
+            return;
+        }
+        for argument in arguments.arguments() {
+            let Some(Value::Variable(variable)) = argument.value() else {
+                continue;
+            };
+            let Some(variable_name) = variable.name() else {
+                continue;
+            };
+            let declared = self.variables.get(variable_name.text().as_str()).cloned();
+            let label = declared.or_else(|| {
+                let arg_name = argument.name()?;
+                argument_defs
+                    .iter()
+                    .find(|def| def.name.as_ref() == arg_name.text())
+                    .map(|def| format_type_ref(&def.type_ref))
+            });
+            if let Some(label) = label {
+                self.push(
+                    variable.syntax().text_range().end().into(),
+                    format!(": {label}"),
+                );
+            }
+        }
+    }
+
+    /// Hint the defaults of omitted arguments, inside the argument list or
+    /// after the field name when there's none, then the defaults of omitted
+    /// fields in the input object literals passed as arguments.
+    fn collect_default_hints(
+        &mut self,
+        name_end: usize,
+        arguments: Option<&apollo_parser::cst::Arguments>,
+        argument_defs: &[graphql_hir::ArgumentDef],
+    ) {
+        if !self.settings.argument_defaults {
+            return;
+        }
+        let written: Vec<String> = arguments
+            .into_iter()
+            .flat_map(apollo_parser::cst::Arguments::arguments)
+            .filter_map(|argument| Some(argument.name()?.text().to_string()))
+            .collect();
+        let omitted = omitted_defaults(
+            argument_defs
+                .iter()
+                .map(|def| (def.name.as_ref(), def.default_value.as_deref())),
+            &written,
+        );
+        if let Some(omitted) = omitted {
+            match arguments.and_then(apollo_parser::cst::Arguments::r_paren_token) {
+                Some(r_paren) => {
+                    let padded = !written.is_empty();
+                    self.push_hint(r_paren.text_range().start().into(), |position| {
+                        InlayHint::new(position, omitted, InlayHintKind::Parameter)
+                            .with_padding(padded, false)
+                    });
                 }
+                None => self.push_hint(name_end, |position| {
+                    InlayHint::new(position, format!("({omitted})"), InlayHintKind::Parameter)
+                        .with_padding(false, false)
+                }),
             }
-            Selection::FragmentSpread(_) => {
-                // Fragment spreads don't get type hints here - the fragment definition has them
+        }
+
+        for argument in arguments
+            .into_iter()
+            .flat_map(apollo_parser::cst::Arguments::arguments)
+        {
+            let def = argument.name().and_then(|name| {
+                argument_defs
+                    .iter()
+                    .find(|def| def.name.as_ref() == name.text())
+            });
+            if let (Some(value), Some(def)) = (argument.value(), def) {
+                self.collect_object_default_hints(&value, &def.type_ref.name);
             }
         }
     }
-}
 
-/// Adjust position for line offset (for embedded GraphQL in TS/JS)
-const fn adjust_position_for_line_offset(position: Position, line_offset: u32) -> Position {
-    if line_offset == 0 {
-        position
-    } else {
-        Position::new(position.line + line_offset, position.character)
+    /// Hint the defaults of omitted fields in an input object literal of
+    /// type `type_name`, before its closing brace, and in nested literals
+    fn collect_object_default_hints(&mut self, value: &Value, type_name: &str) {
+        let types = self.schema_types;
+        match value {
+            // List items share the list's named type
+            Value::ListValue(list) => {
+                for item in list.values() {
+                    self.collect_object_default_hints(&item, type_name);
+                }
+            }
+            Value::ObjectValue(object) => {
+                let Some(type_def) = types
+                    .get(type_name)
+                    .filter(|t| t.kind == graphql_hir::TypeDefKind::InputObject)
+                else {
+                    return;
+                };
+                let written: Vec<String> = object
+                    .object_fields()
+                    .filter_map(|field| Some(field.name()?.text().to_string()))
+                    .collect();
+                let omitted = omitted_defaults(
+                    type_def
+                        .fields
+                        .iter()
+                        .map(|def| (def.name.as_ref(), def.default_value.as_deref())),
+                    &written,
+                );
+                if let (Some(omitted), Some(r_curly)) = (omitted, object.r_curly_token()) {
+                    self.push_hint(r_curly.text_range().start().into(), |position| {
+                        InlayHint::new(position, omitted, InlayHintKind::Parameter)
+                            .with_padding(true, true)
+                    });
+                }
+
+                for object_field in object.object_fields() {
+                    let def = object_field.name().and_then(|name| {
+                        type_def
+                            .fields
+                            .iter()
+                            .find(|def| def.name.as_ref() == name.text())
+                    });
+                    if let (Some(field_value), Some(def)) = (object_field.value(), def) {
+                        self.collect_object_default_hints(&field_value, &def.type_ref.name);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Push a type hint at `offset` within the block, if it's in the
+    /// requested range
+    fn push(&mut self, offset: usize, label: String) {
+        self.push_hint(offset, |position| {
+            InlayHint::new(position, label, InlayHintKind::Type)
+        });
     }
+
+    /// Push the hint built for `offset`'s position, if it's in the
+    /// requested range
+    fn push_hint(&mut self, offset: usize, hint: impl FnOnce(Position) -> InlayHint) {
+        let position = offset_to_position(self.line_index, offset);
+        let adjusted = adjust_position_for_block(position, self.origin);
+        if should_include_position(adjusted, self.range) {
+            self.hints.push(hint(adjusted));
+        }
+    }
+}
+
+/// `name = default` for each definition with a default that isn't in
+/// `written`, comma separated; `None` when there are none
+fn omitted_defaults<'d>(
+    defs: impl Iterator<Item = (&'d str, Option<&'d str>)>,
+    written: &[String],
+) -> Option<String> {
+    let omitted: Vec<String> = defs
+        .filter(|(name, _)| !written.iter().any(|w| w == name))
+        .filter_map(|(name, default)| Some(format!("{name} = {}", default?)))
+        .collect();
+    (!omitted.is_empty()).then(|| omitted.join(", "))
+}
+
+/// Map a block position into the host file (for embedded GraphQL in TS/JS)
+const fn adjust_position_for_block(
+    position: Position,
+    origin: graphql_syntax::BlockOrigin,
+) -> Position {
+    let (line, character) = origin.to_file(position.line, position.character);
+    Position::new(line, character)
 }
 
 /// Check if a position should be included based on the requested range.
@@ -366,16 +554,25 @@ mod tests {
     #[test]
     fn test_adjust_position_no_offset() {
         let pos = Position::new(5, 10);
-        let adjusted = adjust_position_for_line_offset(pos, 0);
+        let adjusted = adjust_position_for_block(pos, graphql_syntax::BlockOrigin::default());
         assert_eq!(adjusted.line, 5);
         assert_eq!(adjusted.character, 10);
     }
 
     #[test]
     fn test_adjust_position_with_offset() {
+        let origin = graphql_syntax::BlockOrigin {
+            line: 3,
+            column: 20,
+        };
         let pos = Position::new(5, 10);
-        let adjusted = adjust_position_for_line_offset(pos, 3);
+        let adjusted = adjust_position_for_block(pos, origin);
         assert_eq!(adjusted.line, 8);
         assert_eq!(adjusted.character, 10);
+
+        // The block's first line starts after the template literal's backtick
+        let adjusted = adjust_position_for_block(Position::new(0, 4), origin);
+        assert_eq!(adjusted.line, 3);
+        assert_eq!(adjusted.character, 24);
     }
 }
@@ -0,0 +1,123 @@
+//! Inlining a fragment spread.
+//!
+//! Replaces the spread under the cursor with an inline fragment on the
+//! fragment's type condition, carrying over the spread's directives and a
+//! copy of the fragment's selection set. The fragment definition is left in
+//! place, since other spreads may still use it.
+//!
+//! The copied selection set keeps its layout, re-indented so its lines sit
+//! under the spread they replace.
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_syntax::edit::line_indent;
+
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
+use crate::types::{FilePath, Position, TextEdit};
+use crate::DbFiles;
+
+/// The edit that inlines the fragment spread at `position`.
+///
+/// Returns `None` when the cursor isn't on a fragment spread or the
+/// fragment can't be found in the project.
+pub fn inline_fragment_spread(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+) -> Option<TextEdit> {
+    let project_files = project_files?;
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
+    let line_index = block_context.line_index();
+    let offset = position_to_offset(&line_index, adjusted_position)?;
+
+    let spread = block_context
+        .tree
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::FragmentSpread::cast)
+        .find(|spread| {
+            let range = spread.syntax().text_range();
+            offset >= usize::from(range.start()) && offset <= usize::from(range.end())
+        })?;
+    let name = spread.fragment_name()?.name()?.text();
+
+    // With `# import` comments, the imported fragment wins over same-named
+    // fragments elsewhere in the project
+    let fragment_file_id =
+        graphql_hir::file_fragment_scope(db, file_id, content, metadata, project_files)
+            .and_then(|scope| scope.get(name.as_str()).copied())
+            .or_else(|| {
+                graphql_hir::all_fragments(db, project_files)
+                    .get(name.as_str())
+                    .map(|f| f.file_id)
+            })?;
+    let def_parse = graphql_syntax::parse(
+        db,
+        registry.get_content(fragment_file_id)?,
+        registry.get_metadata(fragment_file_id)?,
+    );
+    let (fragment, def_source) = def_parse.documents().find_map(|doc| {
+        let fragment = doc
+            .tree
+            .document()
+            .definitions()
+            .filter_map(|def| match def {
+                cst::Definition::FragmentDefinition(fragment) => Some(fragment),
+                _ => None,
+            })
+            .find(|fragment| {
+                fragment
+                    .fragment_name()
+                    .and_then(|n| n.name())
+                    .is_some_and(|n| n.text() == name)
+            })?;
+        Some((fragment, doc.source))
+    })?;
+    let type_condition = fragment.type_condition()?.named_type()?.name()?.text();
+    let selection_set = fragment.selection_set()?;
+
+    let def_start: usize = fragment.syntax().text_range().start().into();
+    let spread_range = spread.syntax().text_range();
+    let spread_start: usize = spread_range.start().into();
+    let selections = reindent(
+        &selection_set.syntax().to_string(),
+        line_indent(def_source, def_start),
+        line_indent(block_context.block_source, spread_start),
+    );
+
+    let mut new_text = format!("... on {type_condition} ");
+    if let Some(directives) = spread.directives() {
+        new_text.push_str(&directives.syntax().to_string());
+        new_text.push(' ');
+    }
+    new_text.push_str(&selections);
+
+    let range = offset_range_to_range(&line_index, spread_start, spread_range.end().into());
+    Some(TextEdit::new(
+        adjust_range_for_block(range, block_context.origin()),
+        new_text,
+    ))
+}
+
+/// Move every line after the first from under `from` to under `to`
+fn reindent(text: &str, from: &str, to: &str) -> String {
+    let mut lines = text.split('\n');
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        if !line.trim().is_empty() {
+            result.push_str(to);
+            result.push_str(line.strip_prefix(from).unwrap_or(line));
+        }
+    }
+    result
+}
@@ -0,0 +1,88 @@
+//! Memory budget for parsed documents.
+//!
+//! Under lazy parsing, parse results live in an LRU cache whose capacity is
+//! derived from the configured budget and the project's average file
+//! size. Sizes here are estimates: Salsa doesn't report what its memos hold.
+
+use graphql_base_db::ProjectFiles;
+
+use crate::types::MemoryMetrics;
+
+/// Rough size of a parse result per byte of source: the CST, the AST built
+/// from it and the block's line index
+const PARSE_BYTES_PER_SOURCE_BYTE: usize = 8;
+
+/// Fewest parse results kept under a budget, so open files and the
+/// fragments they spread stay parsed even with a tiny budget
+const MIN_PARSE_CACHE_CAPACITY: usize = 64;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct SourceSizes {
+    files: usize,
+    bytes: usize,
+    documents: usize,
+}
+
+impl SourceSizes {
+    fn average_file_bytes(self) -> usize {
+        (self.bytes / self.files.max(1)).max(1)
+    }
+}
+
+fn source_sizes(
+    db: &dyn graphql_syntax::GraphQLSyntaxDatabase,
+    project_files: Option<ProjectFiles>,
+) -> SourceSizes {
+    let mut sizes = SourceSizes::default();
+    let Some(project_files) = project_files else {
+        return sizes;
+    };
+    for entry in project_files.file_entry_map(db).entries(db).values() {
+        sizes.files += 1;
+        sizes.bytes += entry.content(db).text(db).len();
+        if entry.metadata(db).is_document(db) {
+            sizes.documents += 1;
+        }
+    }
+    sizes
+}
+
+/// Parse cache capacity for `settings`, or `None` when every parse is kept
+pub(crate) fn parse_cache_capacity(
+    db: &dyn graphql_syntax::GraphQLSyntaxDatabase,
+    project_files: Option<ProjectFiles>,
+    settings: graphql_config::MemorySettings,
+) -> Option<usize> {
+    if !settings.lazy_parsing {
+        return None;
+    }
+    let per_parse =
+        source_sizes(db, project_files).average_file_bytes() * PARSE_BYTES_PER_SOURCE_BYTE;
+    let budget = settings.budget_mb as usize * BYTES_PER_MB;
+    Some((budget / per_parse).max(MIN_PARSE_CACHE_CAPACITY))
+}
+
+pub(crate) fn memory_metrics(
+    db: &dyn graphql_syntax::GraphQLSyntaxDatabase,
+    project_files: Option<ProjectFiles>,
+    settings: graphql_config::MemorySettings,
+    parse_cache_capacity: Option<usize>,
+) -> MemoryMetrics {
+    let sizes = source_sizes(db, project_files);
+    let parsed_files =
+        parse_cache_capacity.map_or(sizes.files, |capacity| capacity.min(sizes.files));
+    MemoryMetrics {
+        files: sizes.files,
+        source_bytes: sizes.bytes,
+        documents: sizes.documents,
+        parse_cache_capacity,
+        budget_bytes: settings
+            .lazy_parsing
+            .then(|| settings.budget_mb as usize * BYTES_PER_MB),
+        estimated_parse_bytes: parsed_files
+            * sizes.average_file_bytes()
+            * PARSE_BYTES_PER_SOURCE_BYTE,
+    }
+}
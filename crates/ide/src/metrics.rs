@@ -0,0 +1,76 @@
+//! Performance events for embedding applications.
+//!
+//! An application that embeds the analyzer can register a [`MetricsSink`] on
+//! an [`AnalysisHost`](crate::AnalysisHost) to receive a [`MetricsEvent`] for
+//! every query the database runs or reuses and every rebuild of the project's
+//! file set. The language server forwards request timings to the same sink,
+//! from its main loop. Events are delivered synchronously on the thread that
+//! produced them, so a sink should only aggregate or enqueue, and leave
+//! exporting to its own thread.
+//!
+//! This is independent of the `otel` feature: the sink gets plain values and
+//! can feed whatever telemetry the application already has.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Receives the analyzer's performance events
+pub trait MetricsSink: Send + Sync {
+    fn record(&self, event: &MetricsEvent<'_>);
+}
+
+impl<T: MetricsSink + ?Sized> MetricsSink for Arc<T> {
+    fn record(&self, event: &MetricsEvent<'_>) {
+        (**self).record(event);
+    }
+}
+
+/// A performance event, borrowed for the duration of [`MetricsSink::record`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MetricsEvent<'a> {
+    /// A database query was needed. On a cache hit its memoized result was
+    /// revalidated and reused; on a miss the query body is about to run.
+    Query { name: &'a str, cache_hit: bool },
+    /// The project's file set was rebuilt after files were added or removed
+    ProjectRebuilt { files: usize, duration: Duration },
+    /// The language server answered a request
+    Request {
+        method: &'a str,
+        /// The document the request was about, if any
+        file: Option<&'a str>,
+        duration: Duration,
+    },
+}
+
+/// The sink events go to, shared between a host, its snapshots and the
+/// database's event callback
+#[derive(Default)]
+pub(crate) struct SharedSink {
+    /// Checked first, so the event callback stays cheap without a sink
+    installed: AtomicBool,
+    sink: RwLock<Option<Arc<dyn MetricsSink>>>,
+}
+
+impl SharedSink {
+    pub(crate) fn set(&self, sink: Option<Arc<dyn MetricsSink>>) {
+        let mut current = self.sink.write().expect("metrics sink lock poisoned");
+        self.installed.store(sink.is_some(), Ordering::Relaxed);
+        *current = sink;
+    }
+
+    pub(crate) fn is_installed(&self) -> bool {
+        self.installed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn record(&self, event: &MetricsEvent<'_>) {
+        if !self.is_installed() {
+            return;
+        }
+        let sink = self.sink.read().expect("metrics sink lock poisoned");
+        if let Some(sink) = sink.as_ref() {
+            sink.record(event);
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! Batch migration of client documents across schema renames.
+//!
+//! Given a [`SchemaChangeManifest`] (old → new names for types, fields and
+//! enum values), rewrites every document in the project: type conditions,
+//! variable types, field selections and enum literals in arguments and
+//! default values. Renamed fields are rewritten in place without adding an
+//! alias, so response keys change along with the schema.
+//!
+//! The project schema may be either side of the change. Names are resolved
+//! against the schema as-is first and through the manifest second, and
+//! manifest keys are always matched on the old name.
+//!
+//! Some usages can't be rewritten from the document alone. Enum values and
+//! input objects passed through variables are supplied at runtime, and
+//! selections under a field the schema doesn't know have no parent type to
+//! match against. These are reported as [`UnmigratedUsage`]s instead.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+use graphql_hir::{FieldSignature, TypeDef, TypeDefKind};
+
+use crate::helpers::{adjust_range_for_block, innermost_named_type, offset_range_to_range};
+use crate::types::{
+    FilePath, Location, MigrationResult, SchemaChangeManifest, TextEdit, UnmigratedUsage,
+};
+use crate::DbFiles;
+
+/// Compute the edits that migrate all project documents across `manifest`.
+pub fn migrate_documents(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    manifest: &SchemaChangeManifest,
+) -> MigrationResult {
+    let mut result = MigrationResult::default();
+    let Some(project_files) = project_files else {
+        return result;
+    };
+    if manifest.is_empty() {
+        return result;
+    }
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+
+    for file_id in doc_ids.iter() {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        else {
+            continue;
+        };
+        let Some(file) = registry.get_path(*file_id) else {
+            continue;
+        };
+
+        let parse = graphql_syntax::parse(db, content, metadata);
+        let mut migrator = Migrator {
+            manifest,
+            types: &types,
+            file: &file,
+            line_index: None,
+            origin: graphql_syntax::BlockOrigin::default(),
+            edits: Vec::new(),
+            unmigrated: Vec::new(),
+        };
+        for doc in parse.documents() {
+            migrator.line_index = Some(doc.line_index());
+            migrator.origin = doc.origin();
+            migrator.migrate_document(doc.tree);
+        }
+
+        if !migrator.edits.is_empty() {
+            result.changes.insert(file.clone(), migrator.edits);
+        }
+        result.unmigrated.extend(migrator.unmigrated);
+    }
+
+    result
+}
+
+struct Migrator<'a> {
+    manifest: &'a SchemaChangeManifest,
+    types: &'a HashMap<Arc<str>, TypeDef>,
+    file: &'a FilePath,
+    line_index: Option<graphql_syntax::LineIndex>,
+    origin: graphql_syntax::BlockOrigin,
+    edits: Vec<TextEdit>,
+    unmigrated: Vec<UnmigratedUsage>,
+}
+
+impl<'a> Migrator<'a> {
+    fn migrate_document(&mut self, tree: &apollo_parser::SyntaxTree) {
+        for definition in tree.document().definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(op) => {
+                    if let Some(variables) = op.variable_definitions() {
+                        for variable in variables.variable_definitions() {
+                            self.migrate_variable_definition(&variable);
+                        }
+                    }
+                    let root = op.operation_kind().root_type_name();
+                    if let Some(selection_set) = op.selection_set() {
+                        let root = self.original_type_name(root).to_string();
+                        self.migrate_selection_set(&selection_set, Some(&root));
+                    }
+                }
+                cst::Definition::FragmentDefinition(fragment) => {
+                    let parent = fragment
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| self.migrate_named_type(&named));
+                    if let Some(selection_set) = fragment.selection_set() {
+                        self.migrate_selection_set(&selection_set, parent.as_deref());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn migrate_variable_definition(&mut self, variable: &cst::VariableDefinition) {
+        let Some(type_name) = variable
+            .ty()
+            .and_then(|ty| innermost_named_type(&ty))
+            .and_then(|named| self.migrate_named_type(&named))
+        else {
+            return;
+        };
+
+        if let Some(value) = variable.default_value().and_then(|d| d.value()) {
+            self.migrate_value(&value, Some(&type_name));
+        }
+
+        if self.has_member_renames(&type_name) {
+            if let Some(name) = variable.variable().and_then(|v| v.name()) {
+                self.report(
+                    name.syntax(),
+                    format!(
+                        "`${}` takes `{type_name}` values at runtime; update the callers",
+                        name.text()
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Rename a type reference if the manifest covers it, returning the
+    /// type's old name for further lookups.
+    fn migrate_named_type(&mut self, named: &cst::NamedType) -> Option<String> {
+        let name = named.name()?;
+        let text = name.text().to_string();
+        if let Some(new_name) = self.manifest.types.get(text.as_str()) {
+            self.edit(name.syntax(), new_name);
+        }
+        Some(self.original_type_name(&text).to_string())
+    }
+
+    fn migrate_selection_set(&mut self, selection_set: &cst::SelectionSet, parent: Option<&str>) {
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => self.migrate_field(&field, parent),
+                cst::Selection::InlineFragment(inline) => {
+                    let condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| self.migrate_named_type(&named));
+                    if let Some(nested) = inline.selection_set() {
+                        self.migrate_selection_set(&nested, condition.as_deref().or(parent));
+                    }
+                }
+                cst::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn migrate_field(&mut self, field: &cst::Field, parent: Option<&str>) {
+        let Some(name) = field.name() else {
+            return;
+        };
+        let field_name = name.text().to_string();
+        if field_name.starts_with("__") {
+            return;
+        }
+
+        let field_def = match parent {
+            Some(parent) => {
+                if let Some(new_name) = self.renamed_field(parent, &field_name) {
+                    self.edit(name.syntax(), new_name);
+                }
+                self.lookup_field(parent, &field_name)
+            }
+            None => {
+                if self.is_renamed_field_name(&field_name) {
+                    self.report(
+                        name.syntax(),
+                        format!(
+                            "`{field_name}` may be a renamed field; its parent type is unknown"
+                        ),
+                    );
+                }
+                None
+            }
+        };
+
+        if let Some(arguments) = field.arguments() {
+            for argument in arguments.arguments() {
+                let Some(value) = argument.value() else {
+                    continue;
+                };
+                let argument_type = argument.name().and_then(|arg_name| {
+                    field_def?
+                        .arguments
+                        .iter()
+                        .find(|a| a.name.as_ref() == arg_name.text())
+                        .map(|a| self.original_type_name(&a.type_ref.name).to_string())
+                });
+                self.migrate_value(&value, argument_type.as_deref());
+            }
+        }
+
+        if let Some(nested) = field.selection_set() {
+            let field_type =
+                field_def.map(|f| self.original_type_name(&f.type_ref.name).to_string());
+            self.migrate_selection_set(&nested, field_type.as_deref());
+        }
+    }
+
+    /// Rename enum literals and input object fields in a value of
+    /// `expected` type (an old type name).
+    fn migrate_value(&mut self, value: &cst::Value, expected: Option<&str>) {
+        match value {
+            cst::Value::EnumValue(enum_value) => {
+                let Some(name) = enum_value.name() else {
+                    return;
+                };
+                let text = name.text().to_string();
+                match expected {
+                    Some(enum_name) => {
+                        let key = format!("{enum_name}.{text}");
+                        if let Some(new_name) = self.manifest.enum_values.get(&key) {
+                            self.edit(name.syntax(), new_name);
+                        }
+                    }
+                    None => {
+                        let suffix = format!(".{text}");
+                        if self
+                            .manifest
+                            .enum_values
+                            .keys()
+                            .any(|k| k.ends_with(&suffix))
+                        {
+                            self.report(
+                                name.syntax(),
+                                format!(
+                                    "`{text}` may be a renamed enum value; its type is unknown"
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+            cst::Value::ListValue(list) => {
+                for item in list.values() {
+                    self.migrate_value(&item, expected);
+                }
+            }
+            cst::Value::ObjectValue(object) => {
+                for object_field in object.object_fields() {
+                    let field_name = object_field.name();
+                    let mut field_type = None;
+                    if let (Some(input), Some(name)) = (expected, &field_name) {
+                        let text = name.text().to_string();
+                        if let Some(new_name) = self.manifest.fields.get(&format!("{input}.{text}"))
+                        {
+                            self.edit(name.syntax(), new_name);
+                        }
+                        field_type = self
+                            .lookup_field(input, &text)
+                            .map(|f| self.original_type_name(&f.type_ref.name).to_string());
+                    }
+                    if let Some(value) = object_field.value() {
+                        self.migrate_value(&value, field_type.as_deref());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The old name for `name`, which may already be the renamed one when
+    /// the project schema has been updated.
+    fn original_type_name<'n>(&self, name: &'n str) -> &'n str
+    where
+        'a: 'n,
+    {
+        self.manifest
+            .types
+            .iter()
+            .find(|(_, new_name)| new_name.as_str() == name)
+            .map_or(name, |(old_name, _)| old_name.as_str())
+    }
+
+    fn lookup_type(&self, old_name: &str) -> Option<&'a TypeDef> {
+        let types = self.types;
+        types.get(old_name).or_else(|| {
+            let new_name = self.manifest.types.get(old_name)?;
+            types.get(new_name.as_str())
+        })
+    }
+
+    fn lookup_field(&self, parent: &str, field_name: &str) -> Option<&'a FieldSignature> {
+        let type_def = self.lookup_type(parent)?;
+        let find = |name: &str| type_def.fields.iter().find(|f| f.name.as_ref() == name);
+        find(field_name).or_else(|| find(self.renamed_field(parent, field_name)?))
+    }
+
+    /// The new name for `parent.field_name`, including renames declared on
+    /// an interface the parent implements.
+    fn renamed_field(&self, parent: &str, field_name: &str) -> Option<&'a str> {
+        let fields = &self.manifest.fields;
+        fields
+            .get(&format!("{parent}.{field_name}"))
+            .or_else(|| {
+                let type_def = self.lookup_type(parent)?;
+                type_def.implements.iter().find_map(|interface| {
+                    let interface = self.original_type_name(interface);
+                    fields.get(&format!("{interface}.{field_name}"))
+                })
+            })
+            .map(String::as_str)
+    }
+
+    fn is_renamed_field_name(&self, field_name: &str) -> bool {
+        let suffix = format!(".{field_name}");
+        self.manifest.fields.keys().any(|k| k.ends_with(&suffix))
+    }
+
+    /// Whether values of an enum or input type can carry renamed members
+    fn has_member_renames(&self, old_name: &str) -> bool {
+        let prefix = format!("{old_name}.");
+        match self.lookup_type(old_name).map(|t| t.kind) {
+            Some(TypeDefKind::Enum) => self
+                .manifest
+                .enum_values
+                .keys()
+                .any(|k| k.starts_with(&prefix)),
+            Some(TypeDefKind::InputObject) => {
+                self.manifest.fields.keys().any(|k| k.starts_with(&prefix))
+            }
+            _ => false,
+        }
+    }
+
+    fn location(&self, node: &apollo_parser::SyntaxNode) -> Option<Location> {
+        let line_index = self.line_index.as_ref()?;
+        let range = node.text_range();
+        let range = offset_range_to_range(line_index, range.start().into(), range.end().into());
+        Some(Location::new(
+            self.file.clone(),
+            adjust_range_for_block(range, self.origin),
+        ))
+    }
+
+    fn edit(&mut self, node: &apollo_parser::SyntaxNode, new_name: &str) {
+        if let Some(location) = self.location(node) {
+            self.edits.push(TextEdit::new(location.range, new_name));
+        }
+    }
+
+    fn report(&mut self, node: &apollo_parser::SyntaxNode, message: String) {
+        if let Some(location) = self.location(node) {
+            self.unmigrated.push(UnmigratedUsage { location, message });
+        }
+    }
+}
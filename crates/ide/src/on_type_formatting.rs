@@ -0,0 +1,181 @@
+//! On-type formatting feature implementation.
+//!
+//! Keeps selection sets indented as they're typed:
+//! - `{` gets a space before it when typed straight after a name (`user{`)
+//! - `}` typed at the start of a line is aligned with its opening line
+//! - a newline indents the new line one level per enclosing brace
+//!
+//! Nesting depth comes from the brace tokens in the CST, so braces inside
+//! strings and comments don't count. In embedded GraphQL every line also
+//! keeps the indentation of the template literal it sits in.
+
+use apollo_parser::cst::CstNode;
+use apollo_parser::SyntaxKind;
+
+use crate::helpers::{adjust_range_for_block, find_block_for_position, position_to_offset};
+use crate::types::{FilePath, Position, Range, TextEdit};
+use crate::DbFiles;
+
+/// Edits to apply after `ch` was typed, leaving the cursor at `position`.
+///
+/// `indent` is one level of indentation (e.g. two spaces or a tab).
+pub fn on_type_formatting(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    file: &FilePath,
+    position: Position,
+    ch: char,
+    indent: &str,
+) -> Vec<TextEdit> {
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let (Some(content), Some(metadata)) = (
+        registry.get_content(file_id),
+        registry.get_metadata(file_id),
+    ) else {
+        return Vec::new();
+    };
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let Some((block, adjusted)) = find_block_for_position(&parse, position) else {
+        return Vec::new();
+    };
+    // The first line of an embedded block starts mid-line, after the
+    // template literal's backtick; there's no indentation of ours to fix
+    if adjusted.line == 0 && block.column_offset() > 0 {
+        return Vec::new();
+    }
+
+    let line_index = block.line_index();
+    let source = block.block_source;
+    let edit = match ch {
+        '{' => position_to_offset(&line_index, adjusted)
+            .and_then(|offset| space_before_brace(block.tree, source, offset, adjusted)),
+        '}' | '\n' => position_to_offset(&line_index, Position::new(adjusted.line, 0)).and_then(
+            |line_start| reindent_line(block.tree, source, line_start, adjusted.line, ch, indent),
+        ),
+        _ => None,
+    };
+
+    edit.map(|edit| {
+        let range = adjust_range_for_block(edit.range, block.origin());
+        vec![TextEdit::new(range, edit.new_text)]
+    })
+    .unwrap_or_default()
+}
+
+/// `user{` → `user {`, when the `{` just typed is a real brace token.
+fn space_before_brace(
+    tree: &apollo_parser::SyntaxTree,
+    source: &str,
+    offset: usize,
+    position: Position,
+) -> Option<TextEdit> {
+    let brace_offset = offset.checked_sub(1)?;
+    if !is_brace_token(tree, brace_offset, SyntaxKind::L_CURLY) {
+        return None;
+    }
+    let before = source[..brace_offset].chars().next_back()?;
+    if !(before.is_alphanumeric() || before == '_' || before == ')') {
+        return None;
+    }
+    let column = position.character.checked_sub(1)?;
+    let at = Position::new(position.line, column);
+    Some(TextEdit::new(Range::new(at, at), " "))
+}
+
+/// Replace the leading whitespace of `line` with the indentation its
+/// nesting depth calls for.
+fn reindent_line(
+    tree: &apollo_parser::SyntaxTree,
+    source: &str,
+    line_start: usize,
+    line: u32,
+    ch: char,
+    indent: &str,
+) -> Option<TextEdit> {
+    let line_text = source[line_start..].split('\n').next().unwrap_or_default();
+    let line_text = line_text.strip_suffix('\r').unwrap_or(line_text);
+    let content = line_text.trim_start_matches([' ', '\t']);
+    let leading = &line_text[..line_text.len() - content.len()];
+    let content_start = line_start + leading.len();
+
+    let closes =
+        content.starts_with('}') && is_brace_token(tree, content_start, SyntaxKind::R_CURLY);
+    // A typed `}` only moves when it's the first thing on its line
+    if ch == '}' && !closes {
+        return None;
+    }
+
+    let mut depth = brace_depth(tree, content_start);
+    if closes {
+        depth = depth.saturating_sub(1);
+    }
+    let desired = format!(
+        "{}{}",
+        base_indent(tree, source, content_start),
+        indent.repeat(depth)
+    );
+    if desired == leading {
+        return None;
+    }
+
+    // Leading whitespace is ASCII, so its byte length is its UTF-16 length
+    let end = Position::new(line, leading.len() as u32);
+    Some(TextEdit::new(
+        Range::new(Position::new(line, 0), end),
+        desired,
+    ))
+}
+
+/// Number of braces opened and not yet closed before `offset`.
+fn brace_depth(tree: &apollo_parser::SyntaxTree, offset: usize) -> usize {
+    let mut depth = 0usize;
+    for token in tree
+        .document()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+    {
+        let start: usize = token.text_range().start().into();
+        if start >= offset {
+            break;
+        }
+        match token.kind() {
+            SyntaxKind::L_CURLY => depth += 1,
+            SyntaxKind::R_CURLY => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Indentation of the line that starts the top-level definition enclosing
+/// (or preceding) `offset`: the template literal's indentation in embedded
+/// GraphQL, and usually nothing in `.graphql` files.
+fn base_indent<'a>(tree: &apollo_parser::SyntaxTree, source: &'a str, offset: usize) -> &'a str {
+    let definition_start = tree
+        .document()
+        .definitions()
+        .map(|definition| usize::from(definition.syntax().text_range().start()))
+        .take_while(|start| *start < offset)
+        .last();
+    let Some(definition_start) = definition_start else {
+        return "";
+    };
+    let line_start = source[..definition_start].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..definition_start];
+    if line.trim_start_matches([' ', '\t']).is_empty() {
+        line
+    } else {
+        ""
+    }
+}
+
+fn is_brace_token(tree: &apollo_parser::SyntaxTree, offset: usize, kind: SyntaxKind) -> bool {
+    tree.document()
+        .syntax()
+        .descendants_with_tokens()
+        .filter_map(|element| element.into_token())
+        .any(|token| token.kind() == kind && usize::from(token.text_range().start()) == offset)
+}
@@ -0,0 +1,230 @@
+//! Operation dependency graph.
+//!
+//! Links each operation to the fragments it spreads and the schema types it
+//! touches, and each fragment to the fragments it spreads and its type
+//! condition. Following the edges backwards from a type shows which
+//! operations a change to it can affect. Exported as DOT or JSON.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use crate::types::{
+    FilePath, OperationGraph, OperationGraphEdge, OperationGraphEdgeKind, OperationGraphNode,
+    OperationGraphNodeKind,
+};
+
+/// Built-in scalars are touched by nearly every operation, so they're left
+/// out rather than connecting everything to `String`
+const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
+
+/// Build the dependency graph for every operation and fragment in the project.
+pub(crate) fn operation_graph(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+) -> OperationGraph {
+    let types = graphql_hir::schema_types(db, project_files);
+    let operations = graphql_hir::all_operations(db, project_files);
+    let fragments = graphql_hir::all_fragments(db, project_files);
+    let spreads_index = graphql_hir::fragment_spreads_index(db, project_files);
+
+    let mut builder = GraphBuilder::default();
+    let mut type_usage_by_file = HashMap::new();
+
+    for operation in operations.iter() {
+        let Some((content, metadata)) =
+            graphql_base_db::file_lookup(db, project_files, operation.file_id)
+        else {
+            continue;
+        };
+        let path = FilePath::new(metadata.uri(db).as_ref().to_string());
+        // Anonymous operations can't share a name-based id
+        let (id, label) = match &operation.name {
+            Some(name) => (format!("operation:{name}"), name.to_string()),
+            None => (
+                format!("operation:{}#{}", path.as_str(), operation.index),
+                "<anonymous>".to_string(),
+            ),
+        };
+        builder.node(&id, OperationGraphNodeKind::Operation, label, Some(path));
+
+        let body = graphql_hir::operation_body(db, content, metadata, operation.index);
+        for fragment_name in &body.fragment_spreads {
+            if fragments.contains_key(fragment_name) {
+                builder.edge(
+                    &id,
+                    &format!("fragment:{fragment_name}"),
+                    OperationGraphEdgeKind::Spreads,
+                );
+            }
+        }
+
+        let type_usage = type_usage_by_file
+            .entry(operation.file_id)
+            .or_insert_with(|| {
+                graphql_hir::operation_type_usage(db, content, metadata, project_files)
+            });
+        if let Some(touched) = type_usage.get(operation.index) {
+            for type_name in touched {
+                builder.touch_type(&id, type_name);
+            }
+        }
+    }
+
+    for (name, fragment) in &fragments {
+        let path = graphql_base_db::file_lookup(db, project_files, fragment.file_id)
+            .map(|(_, metadata)| FilePath::new(metadata.uri(db).as_ref().to_string()));
+        let id = format!("fragment:{name}");
+        builder.node(
+            &id,
+            OperationGraphNodeKind::Fragment,
+            name.to_string(),
+            path,
+        );
+
+        for spread in spreads_index.get(name).into_iter().flatten() {
+            if fragments.contains_key(spread) {
+                builder.edge(
+                    &id,
+                    &format!("fragment:{spread}"),
+                    OperationGraphEdgeKind::Spreads,
+                );
+            }
+        }
+        if types.contains_key(&fragment.type_condition) {
+            builder.touch_type(&id, &fragment.type_condition);
+        }
+    }
+
+    builder.finish()
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    nodes: BTreeMap<String, OperationGraphNode>,
+    edges: BTreeSet<OperationGraphEdge>,
+}
+
+impl GraphBuilder {
+    fn node(
+        &mut self,
+        id: &str,
+        kind: OperationGraphNodeKind,
+        label: String,
+        file: Option<FilePath>,
+    ) {
+        self.nodes
+            .entry(id.to_string())
+            .or_insert_with(|| OperationGraphNode {
+                id: id.to_string(),
+                kind,
+                label,
+                file,
+            });
+    }
+
+    fn edge(&mut self, from: &str, to: &str, kind: OperationGraphEdgeKind) {
+        self.edges.insert(OperationGraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+        });
+    }
+
+    fn touch_type(&mut self, from: &str, type_name: &Arc<str>) {
+        if BUILTIN_SCALARS.contains(&type_name.as_ref()) {
+            return;
+        }
+        let id = format!("type:{type_name}");
+        self.node(
+            &id,
+            OperationGraphNodeKind::Type,
+            type_name.to_string(),
+            None,
+        );
+        self.edge(from, &id, OperationGraphEdgeKind::Touches);
+    }
+
+    fn finish(self) -> OperationGraph {
+        OperationGraph {
+            nodes: self.nodes.into_values().collect(),
+            edges: self.edges.into_iter().collect(),
+        }
+    }
+}
+
+impl OperationGraph {
+    /// Render the graph in Graphviz DOT format.
+    ///
+    /// Operations are boxes, fragments ellipses and types notes; type edges
+    /// are dashed.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph operations {\n  rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                OperationGraphNodeKind::Operation => "box",
+                OperationGraphNodeKind::Fragment => "ellipse",
+                OperationGraphNodeKind::Type => "note",
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" [label=\"{}\", shape={shape}];",
+                escape_dot(&node.id),
+                escape_dot(&node.label)
+            );
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                OperationGraphEdgeKind::Spreads => "",
+                OperationGraphEdgeKind::Touches => " [style=dashed]",
+            };
+            let _ = writeln!(
+                dot,
+                "  \"{}\" -> \"{}\"{style};",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the graph as `{ "nodes": [...], "edges": [...] }`.
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        let nodes: Vec<serde_json::Value> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let kind = match node.kind {
+                    OperationGraphNodeKind::Operation => "operation",
+                    OperationGraphNodeKind::Fragment => "fragment",
+                    OperationGraphNodeKind::Type => "type",
+                };
+                serde_json::json!({
+                    "id": node.id,
+                    "kind": kind,
+                    "label": node.label,
+                    "file": node.file.as_ref().map(FilePath::as_str),
+                })
+            })
+            .collect();
+        let edges: Vec<serde_json::Value> = self
+            .edges
+            .iter()
+            .map(|edge| {
+                let kind = match edge.kind {
+                    OperationGraphEdgeKind::Spreads => "spreads",
+                    OperationGraphEdgeKind::Touches => "touches",
+                };
+                serde_json::json!({ "from": edge.from, "to": edge.to, "kind": kind })
+            })
+            .collect();
+        serde_json::json!({ "nodes": nodes, "edges": edges })
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
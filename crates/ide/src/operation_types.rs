@@ -0,0 +1,468 @@
+//! TypeScript types for operations.
+//!
+//! A deliberately small slice of what graphql-codegen's `typescript-operations`
+//! plugin produces: for each named operation, a result type and a variables
+//! type, both written out inline with no shared schema types. Fragments are
+//! flattened into the selection that spreads them; fields selected under a
+//! type condition narrower than the enclosing type become optional. Custom
+//! scalars are `unknown`. Where the output goes is left to the caller.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_hir::{Selection, TypeDefKind, TypeDefMap, TypeRef};
+
+use crate::helpers::{adjust_range_for_block, offset_range_to_range};
+use crate::types::{OperationTypes, Range};
+
+/// A TypeScript type expression
+#[derive(Debug, Clone, PartialEq)]
+enum TsType {
+    Object(Vec<Member>),
+    Array { item: Box<TsType>, nullable: bool },
+    Leaf(String),
+}
+
+/// A property of an object type
+#[derive(Debug, Clone, PartialEq)]
+struct Member {
+    key: Arc<str>,
+    ty: TsType,
+    nullable: bool,
+    optional: bool,
+}
+
+/// Names of the result and variables types generated for an operation,
+/// after its name and root type: `GetUserQuery` and `GetUserQueryVariables`
+pub(crate) fn type_names(
+    name: &str,
+    operation_type: graphql_hir::OperationType,
+) -> (String, String) {
+    let root_type = graphql_apollo_ext::OperationType::from(operation_type).root_type_name();
+    let result_type = format!("{name}{root_type}");
+    let variables_type = format!("{result_type}Variables");
+    (result_type, variables_type)
+}
+
+/// Generate types for every named operation in a file, in source order.
+///
+/// Anonymous operations are skipped since there is nothing to name their
+/// types after.
+pub(crate) fn generate_operation_types(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    file_id: graphql_base_db::FileId,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+) -> Vec<OperationTypes> {
+    let ranges = operation_ranges(db, content, metadata);
+    let types = graphql_hir::document_schema_types(db, project_files, file_id, content);
+    let operations = graphql_hir::file_operations(db, file_id, content, metadata);
+
+    let mut results = Vec::new();
+    for operation in operations.iter() {
+        let (Some(name), Some(range)) = (&operation.name, ranges.get(operation.index)) else {
+            continue;
+        };
+
+        let (result_type, variables_type) = type_names(name, operation.operation_type);
+
+        let mut generator = Generator::new(db, project_files, types);
+        let variables: Vec<Member> = operation
+            .variables
+            .iter()
+            .map(|variable| {
+                let nullable = !variable.type_ref.is_non_null;
+                Member {
+                    key: variable.name.clone(),
+                    ty: generator.input_type(&variable.type_ref),
+                    nullable,
+                    optional: nullable || variable.default_value.is_some(),
+                }
+            })
+            .collect();
+        let body = graphql_hir::operation_body(db, content, metadata, operation.index);
+        let mut members = Vec::new();
+        generator.collect(&body.selections, root_type, false, &mut members);
+
+        let mut code = String::new();
+        write_declaration(&mut code, &variables_type, &TsType::Object(variables));
+        code.push('\n');
+        write_declaration(&mut code, &result_type, &TsType::Object(members));
+
+        results.push(OperationTypes {
+            operation_name: name.to_string(),
+            result_type,
+            variables_type,
+            code,
+            range: *range,
+        });
+    }
+    results
+}
+
+/// Range of each operation definition in the file, indexed the way HIR
+/// numbers operations across a file's documents
+fn operation_ranges(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    content: graphql_base_db::FileContent,
+    metadata: graphql_base_db::FileMetadata,
+) -> Vec<Range> {
+    let parse = graphql_syntax::parse(db, content, metadata);
+    let mut ranges = Vec::new();
+    for doc in parse.documents() {
+        let line_index = doc.line_index();
+        for definition in doc.tree.document().definitions() {
+            let cst::Definition::OperationDefinition(operation) = definition else {
+                continue;
+            };
+            let range = operation.syntax().text_range();
+            ranges.push(adjust_range_for_block(
+                offset_range_to_range(&line_index, range.start().into(), range.end().into()),
+                doc.origin(),
+            ));
+        }
+    }
+    ranges
+}
+
+struct Generator<'a> {
+    db: &'a dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    types: &'a TypeDefMap,
+    /// Fragments and input objects being expanded, so cycles terminate
+    expanding: HashSet<Arc<str>>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(
+        db: &'a dyn graphql_hir::GraphQLHirDatabase,
+        project_files: graphql_base_db::ProjectFiles,
+        types: &'a TypeDefMap,
+    ) -> Self {
+        Self {
+            db,
+            project_files,
+            types,
+            expanding: HashSet::new(),
+        }
+    }
+
+    /// Append the members `selections` produce on `parent_type`. With
+    /// `optional`, the selections only apply to some runtime types of the
+    /// enclosing field, so every member they add is optional.
+    fn collect(
+        &mut self,
+        selections: &[Selection],
+        parent_type: &str,
+        optional: bool,
+        members: &mut Vec<Member>,
+    ) {
+        for selection in selections {
+            match selection {
+                Selection::Field {
+                    name,
+                    alias,
+                    selection_set,
+                    ..
+                } => {
+                    let key = alias.clone().unwrap_or_else(|| name.clone());
+                    let (ty, nullable) = if name.as_ref() == "__typename" {
+                        (self.typename(parent_type), false)
+                    } else {
+                        let types = self.types;
+                        let Some(field) = types
+                            .get(parent_type)
+                            .and_then(|t| t.fields.iter().find(|f| f.name == *name))
+                        else {
+                            continue;
+                        };
+                        (
+                            self.output_type(&field.type_ref, selection_set),
+                            !field.type_ref.is_non_null,
+                        )
+                    };
+                    merge_member(
+                        members,
+                        Member {
+                            key,
+                            ty,
+                            nullable,
+                            optional,
+                        },
+                    );
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    selection_set,
+                } => {
+                    let target = type_condition.as_deref().unwrap_or(parent_type);
+                    let narrowed = optional || target != parent_type;
+                    self.collect(selection_set, target, narrowed, members);
+                }
+                Selection::FragmentSpread { name } => {
+                    if !self.expanding.insert(name.clone()) {
+                        continue;
+                    }
+                    let fragments = graphql_hir::all_fragments(self.db, self.project_files);
+                    let index = graphql_hir::fragment_file_index(self.db, self.project_files);
+                    if let (Some(fragment), Some((content, metadata))) =
+                        (fragments.get(name), index.get(name))
+                    {
+                        let body =
+                            graphql_hir::fragment_body(self.db, *content, *metadata, name.clone());
+                        let type_condition = fragment.type_condition.clone();
+                        let narrowed = optional || *type_condition != *parent_type;
+                        self.collect(&body.selections, &type_condition, narrowed, members);
+                    }
+                    self.expanding.remove(name);
+                }
+            }
+        }
+    }
+
+    /// `__typename` is the type's name for objects, and any of the possible
+    /// type names for abstract types
+    fn typename(&self, parent_type: &str) -> TsType {
+        let possible: Vec<String> = match self.types.get(parent_type) {
+            Some(type_def) if type_def.kind == TypeDefKind::Union => type_def
+                .union_members
+                .iter()
+                .map(|member| format!("\"{member}\""))
+                .collect(),
+            Some(type_def) if type_def.kind == TypeDefKind::Interface => {
+                let mut implementors: Vec<String> = self
+                    .types
+                    .values()
+                    .filter(|t| t.implements.iter().any(|i| i.as_ref() == parent_type))
+                    .map(|t| format!("\"{}\"", t.name))
+                    .collect();
+                implementors.sort();
+                implementors
+            }
+            _ => vec![format!("\"{parent_type}\"")],
+        };
+        if possible.is_empty() {
+            TsType::Leaf("string".to_string())
+        } else {
+            TsType::Leaf(possible.join(" | "))
+        }
+    }
+
+    fn output_type(&mut self, type_ref: &TypeRef, selections: &[Selection]) -> TsType {
+        let item = self.named_output_type(&type_ref.name, selections);
+        wrap_list(type_ref, item)
+    }
+
+    fn named_output_type(&mut self, type_name: &str, selections: &[Selection]) -> TsType {
+        if let Some(ty) = builtin_scalar_type(type_name) {
+            return TsType::Leaf(ty.to_string());
+        }
+        let types = self.types;
+        let Some(type_def) = types.get(type_name) else {
+            return TsType::Leaf("unknown".to_string());
+        };
+        match type_def.kind {
+            TypeDefKind::Enum => enum_type(&type_def.enum_values),
+            TypeDefKind::Object | TypeDefKind::Interface | TypeDefKind::Union => {
+                let mut members = Vec::new();
+                self.collect(selections, type_name, false, &mut members);
+                TsType::Object(members)
+            }
+            _ => TsType::Leaf("unknown".to_string()),
+        }
+    }
+
+    fn input_type(&mut self, type_ref: &TypeRef) -> TsType {
+        let item = self.named_input_type(&type_ref.name);
+        wrap_list(type_ref, item)
+    }
+
+    fn named_input_type(&mut self, type_name: &str) -> TsType {
+        if let Some(ty) = builtin_scalar_type(type_name) {
+            return TsType::Leaf(ty.to_string());
+        }
+        let types = self.types;
+        let Some(type_def) = types.get(type_name) else {
+            return TsType::Leaf("unknown".to_string());
+        };
+        match type_def.kind {
+            TypeDefKind::Enum => enum_type(&type_def.enum_values),
+            TypeDefKind::InputObject => {
+                if !self.expanding.insert(type_def.name.clone()) {
+                    return TsType::Leaf("unknown".to_string());
+                }
+                let members = type_def
+                    .fields
+                    .iter()
+                    .map(|field| {
+                        let nullable = !field.type_ref.is_non_null;
+                        Member {
+                            key: field.name.clone(),
+                            ty: self.input_type(&field.type_ref),
+                            nullable,
+                            optional: nullable,
+                        }
+                    })
+                    .collect();
+                self.expanding.remove(&type_def.name);
+                TsType::Object(members)
+            }
+            _ => TsType::Leaf("unknown".to_string()),
+        }
+    }
+}
+
+fn wrap_list(type_ref: &TypeRef, item: TsType) -> TsType {
+    if type_ref.is_list {
+        TsType::Array {
+            item: Box::new(item),
+            nullable: !type_ref.inner_non_null,
+        }
+    } else {
+        item
+    }
+}
+
+fn enum_type(values: &[graphql_hir::EnumValue]) -> TsType {
+    if values.is_empty() {
+        return TsType::Leaf("never".to_string());
+    }
+    TsType::Leaf(
+        values
+            .iter()
+            .map(|value| format!("\"{}\"", value.name))
+            .collect::<Vec<_>>()
+            .join(" | "),
+    )
+}
+
+fn builtin_scalar_type(type_name: &str) -> Option<&'static str> {
+    match type_name {
+        "ID" | "String" => Some("string"),
+        "Int" | "Float" => Some("number"),
+        "Boolean" => Some("boolean"),
+        _ => None,
+    }
+}
+
+/// Add `member`, merging it into an earlier member with the same key when
+/// both are objects. Otherwise the first occurrence wins. A key is only
+/// optional if every selection of it is.
+fn merge_member(members: &mut Vec<Member>, member: Member) {
+    let Some(existing) = members.iter_mut().find(|m| m.key == member.key) else {
+        members.push(member);
+        return;
+    };
+    existing.optional &= member.optional;
+    if let (TsType::Object(existing_members), TsType::Object(new_members)) =
+        (unwrap_arrays(&mut existing.ty), into_item(member.ty))
+    {
+        for nested in new_members {
+            merge_member(existing_members, nested);
+        }
+    }
+}
+
+/// The type inside any array wrappers.
+fn unwrap_arrays(ty: &mut TsType) -> &mut TsType {
+    match ty {
+        TsType::Array { item, .. } => unwrap_arrays(item),
+        other => other,
+    }
+}
+
+fn into_item(ty: TsType) -> TsType {
+    match ty {
+        TsType::Array { item, .. } => into_item(*item),
+        other => other,
+    }
+}
+
+fn write_declaration(out: &mut String, name: &str, ty: &TsType) {
+    if matches!(ty, TsType::Object(members) if members.is_empty()) {
+        writeln!(out, "export type {name} = Record<string, never>;").ok();
+        return;
+    }
+    write!(out, "export type {name} = ").ok();
+    write_type(ty, 0, out);
+    out.push_str(";\n");
+}
+
+fn write_type(ty: &TsType, level: usize, out: &mut String) {
+    match ty {
+        TsType::Leaf(name) => out.push_str(name),
+        TsType::Object(members) if members.is_empty() => out.push_str("{}"),
+        TsType::Object(members) => {
+            let indent = "  ".repeat(level);
+            out.push_str("{\n");
+            for member in members {
+                let optional = if member.optional { "?" } else { "" };
+                write!(out, "{indent}  {}{optional}: ", member.key).ok();
+                write_type(&member.ty, level + 1, out);
+                if member.nullable {
+                    out.push_str(" | null");
+                }
+                out.push_str(";\n");
+            }
+            write!(out, "{indent}}}").ok();
+        }
+        TsType::Array { item, nullable } => {
+            out.push_str("Array<");
+            write_type(item, level, out);
+            if *nullable {
+                out.push_str(" | null");
+            }
+            out.push('>');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(key: &str, ty: &str, nullable: bool) -> Member {
+        Member {
+            key: Arc::from(key),
+            ty: TsType::Leaf(ty.to_string()),
+            nullable,
+            optional: false,
+        }
+    }
+
+    #[test]
+    fn test_write_nested_declaration() {
+        let ty = TsType::Object(vec![Member {
+            key: Arc::from("users"),
+            ty: TsType::Array {
+                item: Box::new(TsType::Object(vec![
+                    leaf("id", "string", false),
+                    leaf("name", "string", true),
+                ])),
+                nullable: true,
+            },
+            nullable: false,
+            optional: false,
+        }]);
+        let mut out = String::new();
+        write_declaration(&mut out, "UsersQuery", &ty);
+        assert_eq!(
+            out,
+            "export type UsersQuery = {\n  users: Array<{\n    id: string;\n    name: string | null;\n  } | null>;\n};\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_member_is_optional_only_when_every_selection_is() {
+        let mut members = vec![Member {
+            optional: true,
+            ..leaf("id", "string", false)
+        }];
+        merge_member(&mut members, leaf("id", "string", false));
+        assert_eq!(members.len(), 1);
+        assert!(!members[0].optional);
+    }
+}
@@ -0,0 +1,323 @@
+//! Hoisting repeated selection sets into shared fragments.
+//!
+//! Scans every GraphQL document in the project for fields whose selection
+//! sets are identical, token for token, and return the same type. Each
+//! group of duplicates becomes a fragment in a single target file, and every
+//! occurrence is rewritten to spread it.
+//!
+//! Only `.graphql` documents take part. A fragment spread in an embedded
+//! document also needs the fragment interpolated into the template, which
+//! is up to the host language's import conventions.
+//!
+//! When duplicates nest, the outermost selection set wins: occurrences inside
+//! a selection set that's already being hoisted are left alone, since they
+//! move into the new fragment with it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::{SyntaxElement, SyntaxKind};
+use graphql_apollo_ext::OperationExt;
+use graphql_base_db::Language;
+use graphql_hir::TypeDef;
+
+use crate::helpers::{offset_range_to_range, offset_to_position};
+use crate::types::{FilePath, HoistedFragment, OrganizeFragmentsResult, Range, TextEdit};
+use crate::DbFiles;
+
+/// Selection sets with fewer top-level selections aren't worth a fragment
+const MIN_SELECTIONS: usize = 2;
+
+/// Compute the edits that hoist duplicated selection sets into
+/// `fragment_file`.
+pub fn organize_fragments(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    fragment_file: &FilePath,
+) -> OrganizeFragmentsResult {
+    let mut result = OrganizeFragmentsResult {
+        fragments: Vec::new(),
+        fragment_file: fragment_file.clone(),
+        new_file_content: None,
+        changes: HashMap::new(),
+    };
+    let Some(project_files) = project_files else {
+        return result;
+    };
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+
+    // Sorted by path, so the first occurrence of a group (whose layout the
+    // fragment keeps) doesn't depend on load order
+    let mut doc_files: Vec<_> = doc_ids
+        .iter()
+        .filter_map(|file_id| Some((registry.get_path(*file_id)?, *file_id)))
+        .collect();
+    doc_files.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+
+    let mut files: Vec<(FilePath, Arc<str>)> = Vec::new();
+    let mut occurrences = Vec::new();
+    for (file, file_id) in doc_files {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, file_id)
+        else {
+            continue;
+        };
+        if metadata.language(db) != Language::GraphQL {
+            continue;
+        }
+
+        let parse = graphql_syntax::parse(db, content, metadata);
+        let mut collector = Collector {
+            types,
+            file: files.len(),
+            source: content.text(db),
+            occurrences: &mut occurrences,
+        };
+        for doc in parse.documents() {
+            collector.collect_document(doc.tree);
+        }
+        files.push((file, content.text(db)));
+    }
+
+    let mut groups: BTreeMap<(&str, &str), Vec<&Occurrence>> = BTreeMap::new();
+    for occurrence in &occurrences {
+        groups
+            .entry((occurrence.type_name.as_ref(), occurrence.key.as_str()))
+            .or_default()
+            .push(occurrence);
+    }
+    // Longest selections first, so outer duplicates claim their nested ones
+    let mut groups: Vec<_> = groups.into_iter().filter(|(_, o)| o.len() > 1).collect();
+    groups.sort_by_key(|((_, key), _)| std::cmp::Reverse(key.len()));
+
+    let mut taken: HashSet<String> = graphql_hir::all_fragments(db, project_files)
+        .keys()
+        .map(ToString::to_string)
+        .collect();
+    let mut hoisted: Vec<&Occurrence> = Vec::new();
+    let mut definitions = Vec::new();
+    let mut edits: HashMap<usize, Vec<TextEdit>> = HashMap::new();
+
+    for ((type_name, _), group) in groups {
+        let group: Vec<&Occurrence> = group
+            .into_iter()
+            .filter(|o| !hoisted.iter().any(|outer| outer.contains(o)))
+            .collect();
+        if group.len() < 2 {
+            continue;
+        }
+
+        let name = fragment_name(type_name, &mut taken);
+        definitions.push(format!(
+            "fragment {name} on {type_name} {}",
+            dedent(&group[0].text, &group[0].indent)
+        ));
+        for occurrence in &group {
+            let (_, source) = &files[occurrence.file];
+            let line_index = graphql_syntax::LineIndex::new(source);
+            let range = offset_range_to_range(&line_index, occurrence.start, occurrence.end);
+            edits
+                .entry(occurrence.file)
+                .or_default()
+                .push(TextEdit::new(range, spread(&name, occurrence)));
+        }
+        result.fragments.push(HoistedFragment {
+            name,
+            type_name: type_name.to_string(),
+            occurrences: group.len(),
+        });
+        hoisted.extend(group);
+    }
+
+    if definitions.is_empty() {
+        return result;
+    }
+
+    for (index, file_edits) in edits {
+        result.changes.insert(files[index].0.clone(), file_edits);
+    }
+
+    let mut content = definitions.join("\n\n");
+    content.push('\n');
+    match files.iter().find(|(path, _)| path == fragment_file) {
+        Some((_, source)) => {
+            let line_index = graphql_syntax::LineIndex::new(source);
+            let end = offset_to_position(&line_index, source.len());
+            let separator = if source.ends_with('\n') { "\n" } else { "\n\n" };
+            result
+                .changes
+                .entry(fragment_file.clone())
+                .or_default()
+                .push(TextEdit::new(
+                    Range::new(end, end),
+                    format!("{separator}{content}"),
+                ));
+        }
+        None => result.new_file_content = Some(content),
+    }
+
+    result
+}
+
+/// A field's selection set that could be replaced by a fragment spread
+struct Occurrence {
+    /// Index into the scanned files
+    file: usize,
+    type_name: Arc<str>,
+    /// The selection set's tokens, whitespace and comments dropped
+    key: String,
+    text: String,
+    /// Leading whitespace of the line the selection set opens on
+    indent: String,
+    start: usize,
+    end: usize,
+}
+
+impl Occurrence {
+    fn contains(&self, other: &Occurrence) -> bool {
+        self.file == other.file && self.start <= other.start && other.end <= self.end
+    }
+}
+
+struct Collector<'a> {
+    types: &'a HashMap<Arc<str>, TypeDef>,
+    file: usize,
+    source: Arc<str>,
+    occurrences: &'a mut Vec<Occurrence>,
+}
+
+impl Collector<'_> {
+    fn collect_document(&mut self, tree: &apollo_parser::SyntaxTree) {
+        for definition in tree.document().definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(op) => {
+                    let root = op.operation_kind().root_type_name();
+                    if let Some(selection_set) = op.selection_set() {
+                        self.collect_selection_set(&selection_set, Some(root));
+                    }
+                }
+                cst::Definition::FragmentDefinition(fragment) => {
+                    let parent = fragment
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map(|name| name.text().to_string());
+                    if let Some(selection_set) = fragment.selection_set() {
+                        self.collect_selection_set(&selection_set, parent.as_deref());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn collect_selection_set(&mut self, selection_set: &cst::SelectionSet, parent: Option<&str>) {
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let Some(nested) = field.selection_set() else {
+                        continue;
+                    };
+                    let field_type = field.name().and_then(|name| {
+                        self.types
+                            .get(parent?)?
+                            .fields
+                            .iter()
+                            .find(|f| f.name.as_ref() == name.text())
+                            .map(|f| f.type_ref.name.clone())
+                    });
+                    if let Some(field_type) = &field_type {
+                        self.record(&nested, field_type);
+                    }
+                    self.collect_selection_set(&nested, field_type.as_deref());
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map(|name| name.text().to_string());
+                    if let Some(nested) = inline.selection_set() {
+                        self.collect_selection_set(&nested, condition.as_deref().or(parent));
+                    }
+                }
+                cst::Selection::FragmentSpread(_) => {}
+            }
+        }
+    }
+
+    fn record(&mut self, selection_set: &cst::SelectionSet, type_name: &Arc<str>) {
+        if selection_set.selections().count() < MIN_SELECTIONS {
+            return;
+        }
+        let syntax = selection_set.syntax();
+        let key = syntax
+            .descendants_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| {
+                !matches!(
+                    token.kind(),
+                    SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::COMMA
+                )
+            })
+            .map(|token| token.text().to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let start: usize = syntax.text_range().start().into();
+        let end: usize = syntax.text_range().end().into();
+        let line_start = self.source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let indent = self.source[line_start..start]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .collect();
+
+        self.occurrences.push(Occurrence {
+            file: self.file,
+            type_name: type_name.clone(),
+            key,
+            text: syntax.to_string(),
+            indent,
+            start,
+            end,
+        });
+    }
+}
+
+/// `<Type>Fields`, numbered when the name is already in use
+fn fragment_name(type_name: &str, taken: &mut HashSet<String>) -> String {
+    let base = format!("{type_name}Fields");
+    let mut name = base.clone();
+    let mut suffix = 2;
+    while taken.contains(&name) {
+        name = format!("{base}{suffix}");
+        suffix += 1;
+    }
+    taken.insert(name.clone());
+    name
+}
+
+/// The selection set that replaces `occurrence`, keeping its layout
+fn spread(name: &str, occurrence: &Occurrence) -> String {
+    if occurrence.text.contains('\n') {
+        let indent = &occurrence.indent;
+        format!("{{\n{indent}  ...{name}\n{indent}}}")
+    } else {
+        format!("{{ ...{name} }}")
+    }
+}
+
+/// Strip the indentation of the line a selection set opened on, so it
+/// sits at the top level of the fragment file
+fn dedent(text: &str, indent: &str) -> String {
+    let mut lines = text.lines();
+    let mut result = lines.next().unwrap_or_default().to_string();
+    for line in lines {
+        result.push('\n');
+        result.push_str(line.strip_prefix(indent).unwrap_or(line));
+    }
+    result
+}
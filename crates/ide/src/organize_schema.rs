@@ -0,0 +1,367 @@
+//! Organizing a schema file.
+//!
+//! Sorts the definitions of a schema file by name, optionally grouped by
+//! kind, and sorts the fields of its object, interface and input types.
+//! Definitions or fields written more than once, token for token, are kept
+//! once. Extensions follow the definition they extend. Enum values, union
+//! members and arguments keep their order, since it's often meaningful.
+//!
+//! Comments directly above a definition or field move with it, as does a
+//! comment after it on the same line. A comment block at the top of the
+//! file, separated from the first definition by a blank line, stays at the
+//! top.
+//!
+//! The pass rewrites the whole file, and organizing its own output changes
+//! nothing, so an organized file gets no edit.
+
+use std::collections::HashSet;
+
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::{SyntaxElement, SyntaxKind, SyntaxNode};
+use graphql_base_db::Language;
+use graphql_syntax::edit::line_indent;
+
+use crate::helpers::offset_range_to_range;
+use crate::types::{FilePath, SchemaGroup, TextEdit};
+use crate::DbFiles;
+
+/// The edit that organizes `file`, or none when it's already organized.
+///
+/// `groups` orders definitions by kind before name; kinds it leaves out come
+/// after the listed ones. With no groups, definitions are sorted by name
+/// alone. Files with syntax errors, executable definitions or embedded
+/// GraphQL are left alone.
+pub fn organize_schema(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    file: &FilePath,
+    groups: &[SchemaGroup],
+) -> Vec<TextEdit> {
+    let Some(file_id) = registry.get_file_id(file) else {
+        return Vec::new();
+    };
+    let (Some(content), Some(metadata)) = (
+        registry.get_content(file_id),
+        registry.get_metadata(file_id),
+    ) else {
+        return Vec::new();
+    };
+    if metadata.language(db) != Language::GraphQL {
+        return Vec::new();
+    }
+
+    let parse = graphql_syntax::parse(db, content, metadata);
+    if parse.has_errors() {
+        return Vec::new();
+    }
+    let Some(doc) = parse.documents().next() else {
+        return Vec::new();
+    };
+    let Some(organized) = organize(doc.source, doc.tree, groups) else {
+        return Vec::new();
+    };
+    if organized == doc.source {
+        return Vec::new();
+    }
+
+    let range = offset_range_to_range(&doc.line_index(), 0, doc.source.len());
+    vec![TextEdit::new(range, organized)]
+}
+
+/// A definition or field with the comments that travel with it
+struct Item {
+    key: (String, String, bool),
+    rank: usize,
+    /// Significant tokens, for spotting duplicates
+    tokens: String,
+    comments: Vec<String>,
+    text: String,
+}
+
+/// The organized text of a schema document, or `None` if it has no
+/// definitions or has executable ones
+fn organize(
+    source: &str,
+    tree: &apollo_parser::SyntaxTree,
+    groups: &[SchemaGroup],
+) -> Option<String> {
+    let mut header = String::new();
+    let mut items = Vec::new();
+    let mut prev_end = 0;
+
+    for (index, definition) in tree.document().definitions().enumerate() {
+        let (group, name, extension) = definition_key(&definition)?;
+        let rank = match group {
+            // The schema definition always leads
+            None => 0,
+            Some(group) => {
+                1 + groups
+                    .iter()
+                    .position(|g| *g == group)
+                    .unwrap_or(groups.len())
+            }
+        };
+        let (start, end) = significant_range(definition.syntax())?;
+        let end_with_comment = same_line_comment_end(source, end);
+
+        let mut leading = &source[prev_end..start];
+        if index == 0 {
+            let (file_header, rest) = split_header(leading);
+            header = file_header;
+            leading = rest;
+        }
+
+        let mut text = definition_text(source, &definition, start, end);
+        text.push_str(&source[end..end_with_comment]);
+        items.push(Item {
+            key: (name.to_lowercase(), name, extension),
+            rank,
+            tokens: token_key(definition.syntax()),
+            comments: comment_lines(leading),
+            text,
+        });
+        prev_end = end_with_comment;
+    }
+    if items.is_empty() {
+        return None;
+    }
+    let trailing = comment_lines(&source[prev_end..]);
+
+    let items = sorted_unique(items);
+    let mut blocks: Vec<String> = Vec::new();
+    if !header.is_empty() {
+        blocks.push(header);
+    }
+    for item in items {
+        let mut block = String::new();
+        for comment in &item.comments {
+            block.push_str(comment);
+            block.push('\n');
+        }
+        block.push_str(&item.text);
+        blocks.push(block);
+    }
+    if !trailing.is_empty() {
+        blocks.push(trailing.join("\n"));
+    }
+
+    let mut organized = blocks.join("\n\n");
+    organized.push('\n');
+    Some(organized)
+}
+
+/// The group, name and whether it's an extension, for each kind of type
+/// system definition. The group is `None` for the schema definition.
+#[allow(clippy::type_complexity)]
+fn definition_key(definition: &cst::Definition) -> Option<(Option<SchemaGroup>, String, bool)> {
+    use cst::Definition as D;
+
+    let name = |name: Option<cst::Name>| name.map(|n| n.text().to_string()).unwrap_or_default();
+    let key = match definition {
+        D::SchemaDefinition(_) => (None, String::new(), false),
+        D::SchemaExtension(_) => (None, String::new(), true),
+        D::DirectiveDefinition(d) => (Some(SchemaGroup::Directives), name(d.name()), false),
+        D::ScalarTypeDefinition(d) => (Some(SchemaGroup::Scalars), name(d.name()), false),
+        D::ScalarTypeExtension(d) => (Some(SchemaGroup::Scalars), name(d.name()), true),
+        D::EnumTypeDefinition(d) => (Some(SchemaGroup::Enums), name(d.name()), false),
+        D::EnumTypeExtension(d) => (Some(SchemaGroup::Enums), name(d.name()), true),
+        D::InputObjectTypeDefinition(d) => (Some(SchemaGroup::Inputs), name(d.name()), false),
+        D::InputObjectTypeExtension(d) => (Some(SchemaGroup::Inputs), name(d.name()), true),
+        D::InterfaceTypeDefinition(d) => (Some(SchemaGroup::Interfaces), name(d.name()), false),
+        D::InterfaceTypeExtension(d) => (Some(SchemaGroup::Interfaces), name(d.name()), true),
+        D::ObjectTypeDefinition(d) => (Some(SchemaGroup::Objects), name(d.name()), false),
+        D::ObjectTypeExtension(d) => (Some(SchemaGroup::Objects), name(d.name()), true),
+        D::UnionTypeDefinition(d) => (Some(SchemaGroup::Unions), name(d.name()), false),
+        D::UnionTypeExtension(d) => (Some(SchemaGroup::Unions), name(d.name()), true),
+        D::OperationDefinition(_) | D::FragmentDefinition(_) => return None,
+    };
+    Some(key)
+}
+
+/// The text of a definition with its fields sorted
+fn definition_text(source: &str, definition: &cst::Definition, start: usize, end: usize) -> String {
+    use cst::Definition as D;
+
+    let fields = match definition {
+        D::ObjectTypeDefinition(d) => d.fields_definition().map(|f| fields_block(&f)),
+        D::ObjectTypeExtension(d) => d.fields_definition().map(|f| fields_block(&f)),
+        D::InterfaceTypeDefinition(d) => d.fields_definition().map(|f| fields_block(&f)),
+        D::InterfaceTypeExtension(d) => d.fields_definition().map(|f| fields_block(&f)),
+        D::InputObjectTypeDefinition(d) => {
+            d.input_fields_definition().map(|f| input_fields_block(&f))
+        }
+        D::InputObjectTypeExtension(d) => {
+            d.input_fields_definition().map(|f| input_fields_block(&f))
+        }
+        _ => None,
+    };
+    let Some(Some((open, fields, close))) = fields else {
+        return source[start..end].to_string();
+    };
+    let Some(organized) = organize_fields(source, &fields, open, close) else {
+        return source[start..end].to_string();
+    };
+    format!("{}{organized}{}", &source[start..open], &source[close..end])
+}
+
+/// Offsets just inside the braces of a fields definition, and its fields
+fn fields_block(fields: &cst::FieldsDefinition) -> Option<(usize, Vec<SyntaxNode>, usize)> {
+    Some((
+        fields.l_curly_token()?.text_range().end().into(),
+        fields
+            .field_definitions()
+            .map(|field| field.syntax().clone())
+            .collect(),
+        fields.r_curly_token()?.text_range().start().into(),
+    ))
+}
+
+fn input_fields_block(
+    fields: &cst::InputFieldsDefinition,
+) -> Option<(usize, Vec<SyntaxNode>, usize)> {
+    Some((
+        fields.l_curly_token()?.text_range().end().into(),
+        fields
+            .input_value_definitions()
+            .map(|field| field.syntax().clone())
+            .collect(),
+        fields.r_curly_token()?.text_range().start().into(),
+    ))
+}
+
+/// The sorted fields between `open` and `close`. Fields on separate lines
+/// are put one per line at the first field's indentation, with the closing
+/// brace on its own line; fields on a single line stay on one.
+fn organize_fields(
+    source: &str,
+    fields: &[SyntaxNode],
+    open: usize,
+    close: usize,
+) -> Option<String> {
+    let first = fields.first()?;
+    let indent = line_indent(source, significant_range(first)?.0);
+    let mut items = Vec::new();
+    let mut prev_end = open;
+    for field in fields {
+        let name = field
+            .children()
+            .find_map(cst::Name::cast)
+            .map(|n| n.text().to_string())
+            .unwrap_or_default();
+        let (start, end) = significant_range(field)?;
+        let end_with_comment = same_line_comment_end(source, end);
+        items.push(Item {
+            key: (name.to_lowercase(), name, false),
+            rank: 0,
+            tokens: token_key(field),
+            comments: comment_lines(&source[prev_end..start]),
+            text: source[start..end_with_comment].to_string(),
+        });
+        prev_end = end_with_comment;
+    }
+    let trailing = comment_lines(&source[prev_end..close]);
+    let items = sorted_unique(items);
+
+    if !source[open..close].contains('\n') {
+        let texts: Vec<&str> = items.iter().map(|item| item.text.as_str()).collect();
+        return Some(format!(" {} ", texts.join(" ")));
+    }
+    let mut organized = String::new();
+    let lines = items
+        .iter()
+        .flat_map(|item| item.comments.iter().chain(std::iter::once(&item.text)))
+        .chain(&trailing);
+    for line in lines {
+        organized.push('\n');
+        organized.push_str(indent);
+        organized.push_str(line);
+    }
+    organized.push('\n');
+    Some(organized)
+}
+
+/// `items` without repeats, sorted by rank then key. The first of each set
+/// of repeats is kept.
+fn sorted_unique(items: Vec<Item>) -> Vec<Item> {
+    let mut seen = HashSet::new();
+    let mut items: Vec<Item> = items
+        .into_iter()
+        .filter(|item| seen.insert(item.tokens.clone()))
+        .collect();
+    items.sort_by(|a, b| (a.rank, &a.key).cmp(&(b.rank, &b.key)));
+    items
+}
+
+/// Start and end of a node without the whitespace and comments around it
+fn significant_range(node: &SyntaxNode) -> Option<(usize, usize)> {
+    let mut tokens = node
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| !is_trivia(token.kind()));
+    let first = tokens.next()?;
+    let last = tokens.last().unwrap_or_else(|| first.clone());
+    Some((
+        first.text_range().start().into(),
+        last.text_range().end().into(),
+    ))
+}
+
+fn token_key(node: &SyntaxNode) -> String {
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| !is_trivia(token.kind()))
+        .map(|token| token.text().to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::COMMA
+    )
+}
+
+/// Past a comment that follows `end` on the same line, or `end` if there's
+/// none
+fn same_line_comment_end(source: &str, end: usize) -> usize {
+    let rest = &source[end..];
+    let line = rest.split('\n').next().unwrap_or_default();
+    if line.trim_start_matches([' ', '\t', ',']).starts_with('#') {
+        end + line.trim_end().len()
+    } else {
+        end
+    }
+}
+
+/// The comments in `text`, one per line, without indentation
+fn comment_lines(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Split the text before the first definition into the file's header
+/// comments and the rest. The header ends at the last blank line that
+/// follows a comment.
+fn split_header(leading: &str) -> (String, &str) {
+    let mut header_end = None;
+    let mut seen_comment = false;
+    let mut offset = 0;
+    for line in leading.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if seen_comment {
+                header_end = Some(offset);
+            }
+        } else {
+            seen_comment = true;
+        }
+        offset += line.len();
+    }
+    match header_end {
+        Some(end) => (comment_lines(&leading[..end]).join("\n"), &leading[end..]),
+        None => (String::new(), leading),
+    }
+}
@@ -4,14 +4,19 @@
 //! - Fragment references (spreads and definitions)
 //! - Type references (in schema and documents)
 //! - Field references (definitions and usages)
+//! - Enum value references (definitions and literals in documents)
 
 use std::sync::Arc;
 
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::OperationExt;
+
 use crate::helpers::{
-    find_block_for_position, find_directive_definition_in_parse, find_directive_usages_in_parse,
+    adjust_range_for_block, find_block_for_position, find_directive_definition_in_parse,
+    find_directive_usages_in_parse, find_enum_value_definitions_in_parse,
     find_field_usages_in_parse, find_fragment_definition_in_parse, find_fragment_spreads_in_parse,
-    find_type_definition_in_parse, find_type_references_in_parse, offset_range_to_range,
-    position_to_offset,
+    find_type_definition_in_parse, find_type_references_in_parse, innermost_named_type,
+    offset_range_to_range, position_to_offset,
 };
 use crate::symbol::{find_schema_field_parent_type, find_symbol_at_offset, Symbol};
 use crate::types::{FilePath, Location, Position};
@@ -46,7 +51,7 @@ pub fn find_references(
         adjusted_position
     );
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
     let symbol = find_symbol_at_offset(block_context.tree, offset)?;
@@ -84,6 +89,14 @@ pub fn find_references(
             &name,
             include_declaration,
         )),
+        Symbol::EnumValue { enum_name, value } => Some(find_enum_value_references(
+            db,
+            registry,
+            project_files,
+            &enum_name,
+            &value,
+            include_declaration,
+        )),
         _ => None,
     }
 }
@@ -255,8 +268,7 @@ pub fn find_field_references(
                     file_path,
                     graphql_base_db::file_lookup(db, project_files, field_file_id),
                 ) {
-                    let source_text: &str = &content.text(db);
-                    let line_index = graphql_syntax::LineIndex::new(source_text);
+                    let line_index = graphql_syntax::line_index(db, content);
                     let start = u32::from(field_sig.name_range.start()) as usize;
                     let end = u32::from(field_sig.name_range.end()) as usize;
                     let range = offset_range_to_range(&line_index, start, end);
@@ -396,3 +408,255 @@ fn find_directive_references(
 
     locations
 }
+
+/// Find all references to a value of an enum.
+///
+/// Usages are enum literals in document arguments (of fields and
+/// directives), variable default values and input object fields, matched by
+/// the type expected at each position so same-named values of other enums
+/// aren't included.
+fn find_enum_value_references(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    enum_name: &str,
+    value: &str,
+    include_declaration: bool,
+) -> Vec<Location> {
+    let mut locations = Vec::new();
+    let Some(project_files) = project_files else {
+        return locations;
+    };
+
+    if include_declaration {
+        let schema_ids = project_files.schema_file_ids(db).ids(db);
+        for file_id in schema_ids.iter() {
+            let Some((content, metadata)) =
+                graphql_base_db::file_lookup(db, project_files, *file_id)
+            else {
+                continue;
+            };
+            // Pre-filter: the enum is defined or extended in this file
+            let type_defs = graphql_hir::file_type_defs(db, *file_id, content, metadata);
+            if !type_defs.iter().any(|t| t.name.as_ref() == enum_name) {
+                continue;
+            }
+            let Some(file_path) = registry.get_path(*file_id) else {
+                continue;
+            };
+            let parse = graphql_syntax::parse(db, content, metadata);
+            for range in find_enum_value_definitions_in_parse(&parse, enum_name, value) {
+                locations.push(Location::new(file_path.clone(), range));
+            }
+        }
+    }
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let directives = graphql_hir::schema_directives(db, project_files);
+
+    let doc_ids = project_files.document_file_ids(db).ids(db);
+    for file_id in doc_ids.iter() {
+        let Some((content, metadata)) = graphql_base_db::file_lookup(db, project_files, *file_id)
+        else {
+            continue;
+        };
+        // Pre-filter: skip files that never mention the value
+        if !content.text(db).contains(value) {
+            continue;
+        }
+        let Some(file_path) = registry.get_path(*file_id) else {
+            continue;
+        };
+
+        let parse = graphql_syntax::parse(db, content, metadata);
+        for doc in parse.documents() {
+            let mut finder = EnumValueFinder {
+                enum_name,
+                value,
+                types,
+                directives: &directives,
+                ranges: Vec::new(),
+            };
+            finder.search_document(doc.tree);
+
+            let line_index = doc.line_index();
+            for (start, end) in finder.ranges {
+                let range = offset_range_to_range(&line_index, start, end);
+                locations.push(Location::new(
+                    file_path.clone(),
+                    adjust_range_for_block(range, doc.origin()),
+                ));
+            }
+        }
+    }
+
+    locations
+}
+
+/// Walks a document tracking the input type expected at each value, and
+/// collects the enum literals that name the target value of the target enum.
+struct EnumValueFinder<'a> {
+    enum_name: &'a str,
+    value: &'a str,
+    types: &'a graphql_hir::TypeDefMap,
+    directives: &'a graphql_hir::DirectiveDefMap,
+    ranges: Vec<(usize, usize)>,
+}
+
+impl EnumValueFinder<'_> {
+    fn search_document(&mut self, tree: &apollo_parser::SyntaxTree) {
+        for definition in tree.document().definitions() {
+            match definition {
+                cst::Definition::OperationDefinition(op) => {
+                    if let Some(variables) = op.variable_definitions() {
+                        for variable in variables.variable_definitions() {
+                            let type_name = variable
+                                .ty()
+                                .and_then(|ty| innermost_named_type(&ty))
+                                .and_then(|named| named.name())
+                                .map(|name| name.text().to_string());
+                            if let Some(default) = variable.default_value().and_then(|d| d.value())
+                            {
+                                self.search_value(&default, type_name.as_deref());
+                            }
+                            self.search_directives(variable.directives());
+                        }
+                    }
+                    let root = op.operation_kind().root_type_name();
+                    self.search_directives(op.directives());
+                    self.search_selection_set(op.selection_set(), Some(root));
+                }
+                cst::Definition::FragmentDefinition(fragment) => {
+                    let type_condition = fragment
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map(|name| name.text().to_string());
+                    self.search_directives(fragment.directives());
+                    self.search_selection_set(fragment.selection_set(), type_condition.as_deref());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn search_selection_set(
+        &mut self,
+        selection_set: Option<cst::SelectionSet>,
+        parent: Option<&str>,
+    ) {
+        let Some(selection_set) = selection_set else {
+            return;
+        };
+        let types = self.types;
+        for selection in selection_set.selections() {
+            match selection {
+                cst::Selection::Field(field) => {
+                    let field_def = parent.zip(field.name()).and_then(|(parent, name)| {
+                        types
+                            .get(parent)?
+                            .fields
+                            .iter()
+                            .find(|f| f.name.as_ref() == name.text())
+                    });
+                    for argument in field.arguments().iter().flat_map(|a| a.arguments()) {
+                        let argument_type = argument.name().and_then(|name| {
+                            field_def?
+                                .arguments
+                                .iter()
+                                .find(|a| a.name.as_ref() == name.text())
+                                .map(|a| a.type_ref.name.clone())
+                        });
+                        if let Some(value) = argument.value() {
+                            self.search_value(&value, argument_type.as_deref());
+                        }
+                    }
+                    self.search_directives(field.directives());
+                    let field_type = field_def.map(|f| f.type_ref.name.clone());
+                    self.search_selection_set(field.selection_set(), field_type.as_deref());
+                }
+                cst::Selection::InlineFragment(inline) => {
+                    let type_condition = inline
+                        .type_condition()
+                        .and_then(|tc| tc.named_type())
+                        .and_then(|named| named.name())
+                        .map(|name| name.text().to_string());
+                    self.search_directives(inline.directives());
+                    self.search_selection_set(
+                        inline.selection_set(),
+                        type_condition.as_deref().or(parent),
+                    );
+                }
+                cst::Selection::FragmentSpread(spread) => {
+                    self.search_directives(spread.directives());
+                }
+            }
+        }
+    }
+
+    fn search_directives(&mut self, directives: Option<cst::Directives>) {
+        let directive_defs = self.directives;
+        for directive in directives.iter().flat_map(|d| d.directives()) {
+            let directive_def = directive
+                .name()
+                .and_then(|name| directive_defs.get(name.text().as_str()));
+            for argument in directive.arguments().iter().flat_map(|a| a.arguments()) {
+                let argument_type = argument.name().and_then(|name| {
+                    directive_def?
+                        .arguments
+                        .iter()
+                        .find(|a| a.name.as_ref() == name.text())
+                        .map(|a| a.type_ref.name.clone())
+                });
+                if let Some(value) = argument.value() {
+                    self.search_value(&value, argument_type.as_deref());
+                }
+            }
+        }
+    }
+
+    /// Search a value whose expected type is the named type `expected`
+    /// (list items share their list's named type).
+    fn search_value(&mut self, value: &cst::Value, expected: Option<&str>) {
+        let Some(expected) = expected else {
+            return;
+        };
+        match value {
+            cst::Value::EnumValue(enum_value) => {
+                if expected != self.enum_name {
+                    return;
+                }
+                if let Some(name) = enum_value.name() {
+                    if name.text() == self.value {
+                        let range = name.syntax().text_range();
+                        self.ranges.push((range.start().into(), range.end().into()));
+                    }
+                }
+            }
+            cst::Value::ListValue(list) => {
+                for item in list.values() {
+                    self.search_value(&item, Some(expected));
+                }
+            }
+            cst::Value::ObjectValue(object) => {
+                let types = self.types;
+                let Some(input) = types.get(expected) else {
+                    return;
+                };
+                for object_field in object.object_fields() {
+                    let field_type = object_field.name().and_then(|name| {
+                        input
+                            .fields
+                            .iter()
+                            .find(|f| f.name.as_ref() == name.text())
+                            .map(|f| f.type_ref.name.clone())
+                    });
+                    if let Some(field_value) = object_field.value() {
+                        self.search_value(&field_value, field_type.as_deref());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -9,7 +9,9 @@ use std::collections::HashMap;
 
 use apollo_parser::cst::CstNode;
 
-use crate::helpers::{find_block_for_position, offset_range_to_range, position_to_offset};
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
 use crate::symbol::{find_symbol_at_offset, Symbol};
 use crate::types::{FilePath, Location, Position, Range, RenameResult, TextEdit};
 use crate::DbFiles;
@@ -30,7 +32,7 @@ pub fn prepare_rename(
 
     let parse = graphql_syntax::parse(db, content, metadata);
     let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
     let symbol = find_symbol_at_offset(block_context.tree, offset)?;
 
@@ -39,17 +41,16 @@ pub fn prepare_rename(
         | Symbol::OperationName { ref name }
         | Symbol::VariableReference { ref name } => {
             let (start, end) = find_name_range_at_offset(block_context.tree, offset, name)?;
-            let mut range = offset_range_to_range(&block_line_index, start, end);
-            range.start.line += block_context.line_offset;
-            range.end.line += block_context.line_offset;
-            Some(range)
+            let range = offset_range_to_range(&block_line_index, start, end);
+            Some(adjust_range_for_block(range, block_context.origin()))
         }
         // Schema symbols cannot be renamed through document operations
         Symbol::TypeName { .. }
         | Symbol::FieldName { .. }
         | Symbol::ArgumentName { .. }
         | Symbol::DirectiveName { .. }
-        | Symbol::DirectiveArgumentName { .. } => None,
+        | Symbol::DirectiveArgumentName { .. }
+        | Symbol::EnumValue { .. } => None,
     }
 }
 
@@ -71,7 +72,7 @@ pub fn rename(
 
     let parse = graphql_syntax::parse(db, content, metadata);
     let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
     let symbol = find_symbol_at_offset(block_context.tree, offset)?;
 
@@ -85,7 +86,8 @@ pub fn rename(
         | Symbol::FieldName { .. }
         | Symbol::ArgumentName { .. }
         | Symbol::DirectiveName { .. }
-        | Symbol::DirectiveArgumentName { .. } => None,
+        | Symbol::DirectiveArgumentName { .. }
+        | Symbol::EnumValue { .. } => None,
     }
 }
 
@@ -132,11 +134,9 @@ fn rename_operation(
     for doc in parse.documents() {
         let tree = doc.tree;
         if let Some(ranges) = crate::symbol::find_operation_definition_ranges(tree, old_name) {
-            let line_index = graphql_syntax::LineIndex::new(doc.source);
+            let line_index = doc.line_index();
             let range = offset_range_to_range(&line_index, ranges.name_start, ranges.name_end);
-            let mut adjusted = range;
-            adjusted.start.line += doc.line_offset;
-            adjusted.end.line += doc.line_offset;
+            let adjusted = adjust_range_for_block(range, doc.origin());
             locations.push(Location::new(file_path.clone(), adjusted));
         }
     }
@@ -168,7 +168,7 @@ fn rename_variable(
     let mut locations = Vec::new();
     for doc in parse.documents() {
         let tree = doc.tree;
-        let line_index = graphql_syntax::LineIndex::new(doc.source);
+        let line_index = doc.line_index();
         let document = tree.document();
 
         for definition in document.definitions() {
@@ -195,10 +195,11 @@ fn rename_variable(
                                     let range = name.syntax().text_range();
                                     let start: usize = range.start().into();
                                     let end: usize = range.end().into();
-                                    let mut r = offset_range_to_range(&line_index, start, end);
-                                    r.start.line += doc.line_offset;
-                                    r.end.line += doc.line_offset;
-                                    locations.push(Location::new(file_path.clone(), r));
+                                    let r = offset_range_to_range(&line_index, start, end);
+                                    locations.push(Location::new(
+                                        file_path.clone(),
+                                        adjust_range_for_block(r, doc.origin()),
+                                    ));
                                 }
                             }
                         }
@@ -211,7 +212,7 @@ fn rename_variable(
                         &selection_set,
                         old_name,
                         &line_index,
-                        doc.line_offset,
+                        doc.origin(),
                         &file_path,
                         &mut locations,
                     );
@@ -231,7 +232,7 @@ fn collect_variable_references_in_selection_set(
     selection_set: &apollo_parser::cst::SelectionSet,
     var_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     file_path: &FilePath,
     locations: &mut Vec<Location>,
 ) {
@@ -242,22 +243,12 @@ fn collect_variable_references_in_selection_set(
             cst::Selection::Field(field) => {
                 if let Some(arguments) = field.arguments() {
                     collect_variable_references_in_arguments(
-                        &arguments,
-                        var_name,
-                        line_index,
-                        line_offset,
-                        file_path,
-                        locations,
+                        &arguments, var_name, line_index, origin, file_path, locations,
                     );
                 }
                 if let Some(nested) = field.selection_set() {
                     collect_variable_references_in_selection_set(
-                        &nested,
-                        var_name,
-                        line_index,
-                        line_offset,
-                        file_path,
-                        locations,
+                        &nested, var_name, line_index, origin, file_path, locations,
                     );
                 }
             }
@@ -267,19 +258,14 @@ fn collect_variable_references_in_selection_set(
                         &directives,
                         var_name,
                         line_index,
-                        line_offset,
+                        origin,
                         file_path,
                         locations,
                     );
                 }
                 if let Some(nested) = inline_frag.selection_set() {
                     collect_variable_references_in_selection_set(
-                        &nested,
-                        var_name,
-                        line_index,
-                        line_offset,
-                        file_path,
-                        locations,
+                        &nested, var_name, line_index, origin, file_path, locations,
                     );
                 }
             }
@@ -289,7 +275,7 @@ fn collect_variable_references_in_selection_set(
                         &directives,
                         var_name,
                         line_index,
-                        line_offset,
+                        origin,
                         file_path,
                         locations,
                     );
@@ -303,19 +289,14 @@ fn collect_variable_references_in_arguments(
     arguments: &apollo_parser::cst::Arguments,
     var_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     file_path: &FilePath,
     locations: &mut Vec<Location>,
 ) {
     for arg in arguments.arguments() {
         if let Some(value) = arg.value() {
             collect_variable_references_in_value(
-                &value,
-                var_name,
-                line_index,
-                line_offset,
-                file_path,
-                locations,
+                &value, var_name, line_index, origin, file_path, locations,
             );
         }
     }
@@ -325,19 +306,14 @@ fn collect_variable_references_in_directives(
     directives: &apollo_parser::cst::Directives,
     var_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     file_path: &FilePath,
     locations: &mut Vec<Location>,
 ) {
     for directive in directives.directives() {
         if let Some(arguments) = directive.arguments() {
             collect_variable_references_in_arguments(
-                &arguments,
-                var_name,
-                line_index,
-                line_offset,
-                file_path,
-                locations,
+                &arguments, var_name, line_index, origin, file_path, locations,
             );
         }
     }
@@ -347,7 +323,7 @@ fn collect_variable_references_in_value(
     value: &apollo_parser::cst::Value,
     var_name: &str,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     file_path: &FilePath,
     locations: &mut Vec<Location>,
 ) {
@@ -360,22 +336,18 @@ fn collect_variable_references_in_value(
                     let range = name.syntax().text_range();
                     let start: usize = range.start().into();
                     let end: usize = range.end().into();
-                    let mut r = offset_range_to_range(line_index, start, end);
-                    r.start.line += line_offset;
-                    r.end.line += line_offset;
-                    locations.push(Location::new(file_path.clone(), r));
+                    let r = offset_range_to_range(line_index, start, end);
+                    locations.push(Location::new(
+                        file_path.clone(),
+                        adjust_range_for_block(r, origin),
+                    ));
                 }
             }
         }
         cst::Value::ListValue(list) => {
             for val in list.values() {
                 collect_variable_references_in_value(
-                    &val,
-                    var_name,
-                    line_index,
-                    line_offset,
-                    file_path,
-                    locations,
+                    &val, var_name, line_index, origin, file_path, locations,
                 );
             }
         }
@@ -383,12 +355,7 @@ fn collect_variable_references_in_value(
             for field in obj.object_fields() {
                 if let Some(val) = field.value() {
                     collect_variable_references_in_value(
-                        &val,
-                        var_name,
-                        line_index,
-                        line_offset,
-                        file_path,
-                        locations,
+                        &val, var_name, line_index, origin, file_path, locations,
                     );
                 }
             }
@@ -0,0 +1,361 @@
+//! Example response shapes.
+//!
+//! Synthesizes the JSON a selection set would return, from the schema types
+//! it selects: lists hold a single item, enums show their first value and
+//! scalars a typed placeholder. Nullable fields are marked with a trailing
+//! `// nullable` comment, so the result is rendered as JSONC.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_hir::{Selection, TypeDefKind, TypeDefMap, TypeRef};
+
+/// A synthesized response value
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    Object(Vec<Entry>),
+    List(Box<Shape>),
+    Leaf(String),
+}
+
+/// A response key with its value
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+    key: Arc<str>,
+    shape: Shape,
+    nullable: bool,
+}
+
+/// Render the example response for `selections` on `parent_type`.
+pub(crate) fn example_response(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    types: &TypeDefMap,
+    selections: &[Selection],
+    parent_type: &str,
+) -> String {
+    let mut synthesizer = Synthesizer::new(db, project_files, types);
+    let mut entries = Vec::new();
+    synthesizer.collect(selections, parent_type, &mut entries);
+    render(&Shape::Object(entries))
+}
+
+/// Render the example value of a field returning `type_ref` with
+/// `selections` as its selection set.
+pub(crate) fn example_field_value(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    types: &TypeDefMap,
+    type_ref: &TypeRef,
+    selections: &[Selection],
+) -> String {
+    let mut synthesizer = Synthesizer::new(db, project_files, types);
+    render(&synthesizer.field_shape(type_ref, selections))
+}
+
+/// The selection set of the field whose name contains `offset`, or `None`
+/// when there's no such field or it has no selection set.
+pub(crate) fn field_selections_at_offset(
+    tree: &apollo_parser::SyntaxTree,
+    offset: usize,
+) -> Option<Vec<Selection>> {
+    let field = tree
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::Field::cast)
+        .find(|field| {
+            field.name().is_some_and(|name| {
+                let start: usize = name.syntax().text_range().start().into();
+                let end: usize = name.syntax().text_range().end().into();
+                offset >= start && offset <= end
+            })
+        })?;
+    Some(lower_selection_set(&field.selection_set()?))
+}
+
+/// Lower a CST selection set to HIR selections. Arguments aren't needed for
+/// the response shape and are left out.
+fn lower_selection_set(selection_set: &cst::SelectionSet) -> Vec<Selection> {
+    selection_set
+        .selections()
+        .filter_map(|selection| match selection {
+            cst::Selection::Field(field) => Some(Selection::Field {
+                name: Arc::from(field.name()?.text().as_str()),
+                alias: field
+                    .alias()
+                    .and_then(|alias| alias.name())
+                    .map(|name| Arc::from(name.text().as_str())),
+                arguments: Vec::new(),
+                selection_set: field
+                    .selection_set()
+                    .map(|nested| lower_selection_set(&nested))
+                    .unwrap_or_default(),
+            }),
+            cst::Selection::FragmentSpread(spread) => Some(Selection::FragmentSpread {
+                name: Arc::from(spread.fragment_name()?.name()?.text().as_str()),
+            }),
+            cst::Selection::InlineFragment(inline) => Some(Selection::InlineFragment {
+                type_condition: inline
+                    .type_condition()
+                    .and_then(|tc| tc.named_type())
+                    .and_then(|nt| nt.name())
+                    .map(|name| Arc::from(name.text().as_str())),
+                selection_set: inline
+                    .selection_set()
+                    .map(|nested| lower_selection_set(&nested))
+                    .unwrap_or_default(),
+            }),
+        })
+        .collect()
+}
+
+struct Synthesizer<'a> {
+    db: &'a dyn graphql_hir::GraphQLHirDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    types: &'a TypeDefMap,
+    /// Fragments being expanded, so cyclic spreads terminate
+    expanding: HashSet<Arc<str>>,
+}
+
+impl<'a> Synthesizer<'a> {
+    fn new(
+        db: &'a dyn graphql_hir::GraphQLHirDatabase,
+        project_files: graphql_base_db::ProjectFiles,
+        types: &'a TypeDefMap,
+    ) -> Self {
+        Self {
+            db,
+            project_files,
+            types,
+            expanding: HashSet::new(),
+        }
+    }
+
+    /// Append the entries `selections` produce on `parent_type`. Fragments
+    /// are flattened into the enclosing object, merging repeated keys.
+    fn collect(&mut self, selections: &[Selection], parent_type: &str, entries: &mut Vec<Entry>) {
+        for selection in selections {
+            match selection {
+                Selection::Field {
+                    name,
+                    alias,
+                    selection_set,
+                    ..
+                } => {
+                    let key = alias.clone().unwrap_or_else(|| name.clone());
+                    let (shape, nullable) = if name.as_ref() == "__typename" {
+                        (Shape::Leaf(format!("\"{parent_type}\"")), false)
+                    } else {
+                        let types = self.types;
+                        let Some(field) = types
+                            .get(parent_type)
+                            .and_then(|t| t.fields.iter().find(|f| f.name == *name))
+                        else {
+                            continue;
+                        };
+                        (
+                            self.field_shape(&field.type_ref, selection_set),
+                            !field.type_ref.is_non_null,
+                        )
+                    };
+                    merge_entry(
+                        entries,
+                        Entry {
+                            key,
+                            shape,
+                            nullable,
+                        },
+                    );
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    selection_set,
+                } => {
+                    let target = type_condition.as_deref().unwrap_or(parent_type);
+                    self.collect(selection_set, target, entries);
+                }
+                Selection::FragmentSpread { name } => {
+                    if !self.expanding.insert(name.clone()) {
+                        continue;
+                    }
+                    let fragments = graphql_hir::all_fragments(self.db, self.project_files);
+                    let index = graphql_hir::fragment_file_index(self.db, self.project_files);
+                    if let (Some(fragment), Some((content, metadata))) =
+                        (fragments.get(name), index.get(name))
+                    {
+                        let body =
+                            graphql_hir::fragment_body(self.db, *content, *metadata, name.clone());
+                        let type_condition = fragment.type_condition.clone();
+                        self.collect(&body.selections, &type_condition, entries);
+                    }
+                    self.expanding.remove(name);
+                }
+            }
+        }
+    }
+
+    fn field_shape(&mut self, type_ref: &TypeRef, selections: &[Selection]) -> Shape {
+        let item = self.named_shape(&type_ref.name, selections);
+        if type_ref.is_list {
+            Shape::List(Box::new(item))
+        } else {
+            item
+        }
+    }
+
+    fn named_shape(&mut self, type_name: &str, selections: &[Selection]) -> Shape {
+        if let Some(placeholder) = builtin_scalar_placeholder(type_name) {
+            return Shape::Leaf(placeholder.to_string());
+        }
+        let types = self.types;
+        let Some(type_def) = types.get(type_name) else {
+            return Shape::Leaf(format!("\"<{type_name}>\""));
+        };
+        match type_def.kind {
+            TypeDefKind::Enum => Shape::Leaf(type_def.enum_values.first().map_or_else(
+                || format!("\"<{type_name}>\""),
+                |v| format!("\"{}\"", v.name),
+            )),
+            TypeDefKind::Object | TypeDefKind::Interface | TypeDefKind::Union => {
+                let mut entries = Vec::new();
+                self.collect(selections, type_name, &mut entries);
+                Shape::Object(entries)
+            }
+            _ => Shape::Leaf(format!("\"<{type_name}>\"")),
+        }
+    }
+}
+
+/// Add `entry`, merging it into an earlier entry with the same key when both
+/// are objects. Otherwise the first occurrence wins.
+fn merge_entry(entries: &mut Vec<Entry>, entry: Entry) {
+    let Some(existing) = entries.iter_mut().find(|e| e.key == entry.key) else {
+        entries.push(entry);
+        return;
+    };
+    if let (Shape::Object(existing_fields), Shape::Object(new_fields)) =
+        (unwrap_lists(&mut existing.shape), into_item(entry.shape))
+    {
+        for field in new_fields {
+            merge_entry(existing_fields, field);
+        }
+    }
+}
+
+/// The value inside any list wrappers.
+fn unwrap_lists(shape: &mut Shape) -> &mut Shape {
+    match shape {
+        Shape::List(item) => unwrap_lists(item),
+        other => other,
+    }
+}
+
+fn into_item(shape: Shape) -> Shape {
+    match shape {
+        Shape::List(item) => into_item(*item),
+        other => other,
+    }
+}
+
+fn builtin_scalar_placeholder(type_name: &str) -> Option<&'static str> {
+    match type_name {
+        "ID" => Some("\"1\""),
+        "String" => Some("\"string\""),
+        "Int" => Some("0"),
+        "Float" => Some("0.0"),
+        "Boolean" => Some("true"),
+        _ => None,
+    }
+}
+
+fn render(shape: &Shape) -> String {
+    let mut out = String::new();
+    write_value(shape, 0, "", None, &mut out);
+    out.trim_end().to_string()
+}
+
+/// Write `shape` starting at the current line. `suffix` follows the value
+/// (a separating comma), and `comment` ends the value's first line.
+fn write_value(shape: &Shape, level: usize, suffix: &str, comment: Option<&str>, out: &mut String) {
+    let comment = comment.map_or_else(String::new, |c| format!(" // {c}"));
+    let indent = "  ".repeat(level);
+    match shape {
+        Shape::Leaf(value) => {
+            writeln!(out, "{value}{suffix}{comment}").ok();
+        }
+        Shape::Object(entries) if entries.is_empty() => {
+            writeln!(out, "{{}}{suffix}{comment}").ok();
+        }
+        Shape::Object(entries) => {
+            writeln!(out, "{{{comment}").ok();
+            for (i, entry) in entries.iter().enumerate() {
+                write!(out, "{indent}  \"{}\": ", entry.key).ok();
+                let separator = if i + 1 == entries.len() { "" } else { "," };
+                let nullable = entry.nullable.then_some("nullable");
+                write_value(&entry.shape, level + 1, separator, nullable, out);
+            }
+            writeln!(out, "{indent}}}{suffix}").ok();
+        }
+        Shape::List(item) => {
+            writeln!(out, "[{comment}").ok();
+            write!(out, "{indent}  ").ok();
+            write_value(item, level + 1, "", None, out);
+            writeln!(out, "{indent}]{suffix}").ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(key: &str, value: &str, nullable: bool) -> Entry {
+        Entry {
+            key: Arc::from(key),
+            shape: Shape::Leaf(value.to_string()),
+            nullable,
+        }
+    }
+
+    #[test]
+    fn test_render_nested_shape() {
+        let shape = Shape::Object(vec![Entry {
+            key: Arc::from("users"),
+            shape: Shape::List(Box::new(Shape::Object(vec![
+                leaf("id", "\"1\"", false),
+                leaf("name", "\"string\"", true),
+            ]))),
+            nullable: false,
+        }]);
+        assert_eq!(
+            render(&shape),
+            "{\n  \"users\": [\n    {\n      \"id\": \"1\",\n      \"name\": \"string\" // nullable\n    }\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn test_merge_entry_combines_objects() {
+        let mut entries = vec![Entry {
+            key: Arc::from("user"),
+            shape: Shape::Object(vec![leaf("id", "\"1\"", false)]),
+            nullable: true,
+        }];
+        merge_entry(
+            &mut entries,
+            Entry {
+                key: Arc::from("user"),
+                shape: Shape::Object(vec![leaf("id", "\"1\"", false), leaf("age", "0", true)]),
+                nullable: true,
+            },
+        );
+        assert_eq!(entries.len(), 1);
+        let Shape::Object(fields) = &entries[0].shape else {
+            panic!("expected object");
+        };
+        let keys: Vec<&str> = fields.iter().map(|f| f.key.as_ref()).collect();
+        assert_eq!(keys, ["id", "age"]);
+    }
+}
@@ -0,0 +1,90 @@
+//! Schema registry findings as diagnostics.
+//!
+//! A registry check reports changes by schema coordinate. Each finding is
+//! placed on the name of the most specific local definition its coordinate
+//! still resolves to: a removed field lands on its parent type, a removed
+//! argument on its field. Findings whose type no longer exists locally are
+//! returned separately.
+
+use graphql_base_db::FileId;
+use graphql_hir::{TextRange, TypeDefMap};
+
+use crate::helpers::offset_range_to_range;
+use crate::types::{Diagnostic, SchemaChangeDiagnostics, SchemaChangeFinding};
+use crate::DbFiles;
+
+pub(crate) fn schema_change_diagnostics(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: graphql_base_db::ProjectFiles,
+    findings: &[SchemaChangeFinding],
+) -> SchemaChangeDiagnostics {
+    let types = graphql_hir::schema_types(db, project_files);
+    let mut result = SchemaChangeDiagnostics::default();
+
+    for finding in findings {
+        let target = finding
+            .coordinate
+            .as_deref()
+            .and_then(|coordinate| resolve_coordinate(types, coordinate));
+        let located = target.and_then(|(file_id, range)| {
+            let path = registry.get_path(file_id)?;
+            let (content, _) = graphql_base_db::file_lookup(db, project_files, file_id)?;
+            let line_index = graphql_syntax::line_index(db, content);
+            let range =
+                offset_range_to_range(&line_index, range.start().into(), range.end().into());
+            Some((path, range))
+        });
+
+        match located {
+            Some((path, range)) => {
+                let diagnostic =
+                    Diagnostic::new(range, finding.severity, &finding.message, "schema-registry");
+                result
+                    .diagnostics
+                    .entry(path)
+                    .or_insert_with(Vec::new)
+                    .push(diagnostic);
+            }
+            None => result.unlocated.push(finding.clone()),
+        }
+    }
+
+    result
+}
+
+/// The file and name range of the most specific definition `coordinate`
+/// resolves to, dropping trailing members that don't exist locally.
+///
+/// Handles `Type`, `Type.member` (field, input field or enum value) and
+/// `Type.field.argument`.
+fn resolve_coordinate(types: &TypeDefMap, coordinate: &str) -> Option<(FileId, TextRange)> {
+    let mut parts = coordinate.split('.');
+    let type_def = types.get(parts.next()?)?;
+    let type_target = (type_def.file_id, type_def.name_range);
+
+    let Some(member) = parts.next() else {
+        return Some(type_target);
+    };
+    if let Some(field) = type_def.fields.iter().find(|f| f.name.as_ref() == member) {
+        let field_target = (field.file_id, field.name_range);
+        let Some(argument) = parts.next() else {
+            return Some(field_target);
+        };
+        return Some(
+            field
+                .arguments
+                .iter()
+                .find(|a| a.name.as_ref() == argument)
+                .map_or(field_target, |a| (a.file_id, a.name_range)),
+        );
+    }
+    if let Some(value) = type_def
+        .enum_values
+        .iter()
+        .find(|v| v.name.as_ref() == member)
+    {
+        return Some((type_def.file_id, value.name_range));
+    }
+    Some(type_target)
+}
@@ -0,0 +1,548 @@
+//! Schema documentation.
+//!
+//! Renders the project's schema, with extensions merged in, as Markdown or as
+//! a standalone HTML page: each type with its description, fields, arguments,
+//! enum values, deprecations and applied directives, followed by the custom
+//! directive definitions. Built-in scalars and directives are left out.
+//! Types are grouped by kind and sorted by name, and every reference to a
+//! documented type links to its section, so output is stable across runs.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use graphql_hir::{ArgumentDef, DirectiveDef, DirectiveUsage, TypeDef, TypeDefKind, TypeRef};
+
+use crate::helpers::format_type_ref;
+use crate::types::{DocsFormat, DocsOptions};
+
+/// Sections in the order they're rendered
+const SECTIONS: [(TypeDefKind, &str); 6] = [
+    (TypeDefKind::Object, "Objects"),
+    (TypeDefKind::Interface, "Interfaces"),
+    (TypeDefKind::Union, "Unions"),
+    (TypeDefKind::Enum, "Enums"),
+    (TypeDefKind::InputObject, "Input objects"),
+    (TypeDefKind::Scalar, "Scalars"),
+];
+
+struct Docs<'a> {
+    options: &'a DocsOptions,
+    sections: Vec<(&'static str, Vec<&'a TypeDef>)>,
+    directives: Vec<&'a DirectiveDef>,
+    /// Types with a section of their own, which references link to
+    documented: HashSet<&'a str>,
+}
+
+/// Render documentation for the project schema.
+///
+/// Returns `None` when the schema has no types or directives beyond the
+/// built-in ones.
+pub(crate) fn generate_docs(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    options: &DocsOptions,
+) -> Option<String> {
+    let is_builtin = |file_id| {
+        graphql_base_db::file_lookup(db, project_files, file_id).is_some_and(|(_, metadata)| {
+            let uri = metadata.uri(db);
+            let uri = uri.as_str();
+            uri.ends_with("schema_builtins.graphql") || uri.ends_with("client_builtins.graphql")
+        })
+    };
+
+    let mut types: Vec<&TypeDef> = graphql_hir::schema_types(db, project_files)
+        .values()
+        .filter(|type_def| !is_builtin(type_def.file_id))
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut directives: Vec<&DirectiveDef> = graphql_hir::schema_directives(db, project_files)
+        .values()
+        .filter(|directive| !is_builtin(directive.file_id))
+        .collect();
+    directives.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if types.is_empty() && directives.is_empty() {
+        return None;
+    }
+
+    let docs = Docs {
+        options,
+        documented: types
+            .iter()
+            .map(|type_def| type_def.name.as_ref())
+            .collect(),
+        sections: SECTIONS
+            .iter()
+            .map(|(kind, title)| {
+                let of_kind: Vec<&TypeDef> = types
+                    .iter()
+                    .copied()
+                    .filter(|type_def| type_def.kind == *kind)
+                    .collect();
+                (*title, of_kind)
+            })
+            .filter(|(_, of_kind)| !of_kind.is_empty())
+            .collect(),
+        directives,
+    };
+
+    Some(match options.format {
+        DocsFormat::Markdown => docs.to_markdown(),
+        DocsFormat::Html => docs.to_html(),
+    })
+}
+
+impl Docs<'_> {
+    fn shown(&self, is_deprecated: bool) -> bool {
+        self.options.include_deprecated || !is_deprecated
+    }
+
+    fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.options.title);
+        for (title, types) in &self.sections {
+            let _ = write!(out, "\n## {title}\n");
+            for type_def in types {
+                self.markdown_type(&mut out, type_def);
+            }
+        }
+        if !self.directives.is_empty() {
+            out.push_str("\n## Directives\n");
+            for directive in &self.directives {
+                let _ = write!(
+                    out,
+                    "\n### @{}\n\n`{}`\n",
+                    directive.name,
+                    directive_signature(directive)
+                );
+                if let Some(description) = &directive.description {
+                    let _ = write!(out, "\n{}\n", description.trim());
+                }
+                self.markdown_arguments(&mut out, &directive.arguments, "");
+            }
+        }
+        out
+    }
+
+    fn markdown_type(&self, out: &mut String, type_def: &TypeDef) {
+        let _ = write!(out, "\n### {}\n", type_def.name);
+        if let Some(description) = &type_def.description {
+            let _ = write!(out, "\n{}\n", description.trim());
+        }
+        if !type_def.implements.is_empty() {
+            let interfaces: Vec<String> = type_def
+                .implements
+                .iter()
+                .map(|name| self.markdown_link(name, name))
+                .collect();
+            let _ = write!(out, "\nImplements {}.\n", interfaces.join(", "));
+        }
+        if !type_def.union_members.is_empty() {
+            let members: Vec<String> = type_def
+                .union_members
+                .iter()
+                .map(|name| self.markdown_link(name, name))
+                .collect();
+            let _ = write!(out, "\nOne of {}.\n", members.join(", "));
+        }
+        if let Some(directives) = applied_directives(&type_def.directives) {
+            let _ = write!(out, "\nDirectives: `{directives}`\n");
+        }
+
+        let fields: Vec<_> = type_def
+            .fields
+            .iter()
+            .filter(|field| self.shown(field.is_deprecated))
+            .collect();
+        if !fields.is_empty() {
+            out.push('\n');
+            for field in fields {
+                let _ = write!(
+                    out,
+                    "- `{}`: {}",
+                    field.name,
+                    self.markdown_type_ref(&field.type_ref)
+                );
+                if let Some(default) = &field.default_value {
+                    let _ = write!(out, " = `{default}`");
+                }
+                if let Some(directives) = applied_directives(&field.directives) {
+                    let _ = write!(out, " `{directives}`");
+                }
+                out.push_str(&deprecation_note(
+                    field.is_deprecated,
+                    field.deprecation_reason.as_deref(),
+                ));
+                out.push('\n');
+                if let Some(description) = &field.description {
+                    out.push('\n');
+                    push_indented(out, description.trim(), "  ");
+                }
+                self.markdown_arguments(out, &field.arguments, "  ");
+            }
+        }
+
+        let values: Vec<_> = type_def
+            .enum_values
+            .iter()
+            .filter(|value| self.shown(value.is_deprecated))
+            .collect();
+        if !values.is_empty() {
+            out.push('\n');
+            for value in values {
+                let _ = write!(out, "- `{}`", value.name);
+                if let Some(directives) = applied_directives(&value.directives) {
+                    let _ = write!(out, " `{directives}`");
+                }
+                out.push_str(&deprecation_note(
+                    value.is_deprecated,
+                    value.deprecation_reason.as_deref(),
+                ));
+                out.push('\n');
+                if let Some(description) = &value.description {
+                    out.push('\n');
+                    push_indented(out, description.trim(), "  ");
+                }
+            }
+        }
+    }
+
+    fn markdown_arguments(&self, out: &mut String, arguments: &[ArgumentDef], indent: &str) {
+        let arguments: Vec<_> = arguments
+            .iter()
+            .filter(|argument| self.shown(argument.is_deprecated))
+            .collect();
+        if arguments.is_empty() {
+            return;
+        }
+        out.push('\n');
+        for argument in arguments {
+            let _ = write!(
+                out,
+                "{indent}- `{}`: {}",
+                argument.name,
+                self.markdown_type_ref(&argument.type_ref)
+            );
+            if let Some(default) = &argument.default_value {
+                let _ = write!(out, " = `{default}`");
+            }
+            out.push_str(&deprecation_note(
+                argument.is_deprecated,
+                argument.deprecation_reason.as_deref(),
+            ));
+            if let Some(description) = &argument.description {
+                // Argument descriptions are usually one line; keep them on
+                // the item so nested lists stay compact
+                let _ = write!(out, " — {}", description.trim().replace('\n', " "));
+            }
+            out.push('\n');
+        }
+    }
+
+    fn markdown_type_ref(&self, type_ref: &TypeRef) -> String {
+        self.markdown_link(&type_ref.name, &format_type_ref(type_ref))
+    }
+
+    fn markdown_link(&self, type_name: &str, text: &str) -> String {
+        if self.documented.contains(type_name) {
+            format!("[`{text}`](#{})", type_name.to_lowercase())
+        } else {
+            format!("`{text}`")
+        }
+    }
+
+    fn to_html(&self) -> String {
+        let title = escape_html(&self.options.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        out.push_str("<nav>\n<ul>\n");
+        for (section, types) in &self.sections {
+            let _ = write!(out, "<li>{section}<ul>");
+            for type_def in types {
+                let _ = write!(
+                    out,
+                    "<li>{}</li>",
+                    self.html_link(&type_def.name, &type_def.name)
+                );
+            }
+            out.push_str("</ul></li>\n");
+        }
+        if !self.directives.is_empty() {
+            out.push_str("<li><a href=\"#directives\">Directives</a></li>\n");
+        }
+        out.push_str("</ul>\n</nav>\n");
+
+        for (section, types) in &self.sections {
+            let _ = writeln!(out, "<h2>{section}</h2>");
+            for type_def in types {
+                self.html_type(&mut out, type_def);
+            }
+        }
+        if !self.directives.is_empty() {
+            out.push_str("<h2 id=\"directives\">Directives</h2>\n");
+            for directive in &self.directives {
+                let _ = writeln!(
+                    out,
+                    "<section id=\"directive-{}\">\n<h3>@{}</h3>\n<p><code>{}</code></p>",
+                    directive.name,
+                    directive.name,
+                    escape_html(&directive_signature(directive))
+                );
+                html_description(&mut out, directive.description.as_deref());
+                self.html_arguments(&mut out, &directive.arguments);
+                out.push_str("</section>\n");
+            }
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn html_type(&self, out: &mut String, type_def: &TypeDef) {
+        let _ = writeln!(
+            out,
+            "<section id=\"{}\">\n<h3>{}</h3>",
+            type_def.name, type_def.name
+        );
+        html_description(out, type_def.description.as_deref());
+        if !type_def.implements.is_empty() {
+            let interfaces: Vec<String> = type_def
+                .implements
+                .iter()
+                .map(|name| self.html_link(name, name))
+                .collect();
+            let _ = writeln!(out, "<p>Implements {}.</p>", interfaces.join(", "));
+        }
+        if !type_def.union_members.is_empty() {
+            let members: Vec<String> = type_def
+                .union_members
+                .iter()
+                .map(|name| self.html_link(name, name))
+                .collect();
+            let _ = writeln!(out, "<p>One of {}.</p>", members.join(", "));
+        }
+        if let Some(directives) = applied_directives(&type_def.directives) {
+            let _ = writeln!(
+                out,
+                "<p>Directives: <code>{}</code></p>",
+                escape_html(&directives)
+            );
+        }
+
+        let fields: Vec<_> = type_def
+            .fields
+            .iter()
+            .filter(|field| self.shown(field.is_deprecated))
+            .collect();
+        if !fields.is_empty() {
+            out.push_str("<dl>\n");
+            for field in fields {
+                let _ = write!(
+                    out,
+                    "<dt><code>{}</code>: {}",
+                    field.name,
+                    self.html_type_ref(&field.type_ref)
+                );
+                if let Some(default) = &field.default_value {
+                    let _ = write!(out, " = <code>{}</code>", escape_html(default));
+                }
+                if let Some(directives) = applied_directives(&field.directives) {
+                    let _ = write!(out, " <code>{}</code>", escape_html(&directives));
+                }
+                out.push_str(&html_deprecation_note(
+                    field.is_deprecated,
+                    field.deprecation_reason.as_deref(),
+                ));
+                out.push_str("</dt>\n<dd>\n");
+                html_description(out, field.description.as_deref());
+                self.html_arguments(out, &field.arguments);
+                out.push_str("</dd>\n");
+            }
+            out.push_str("</dl>\n");
+        }
+
+        let values: Vec<_> = type_def
+            .enum_values
+            .iter()
+            .filter(|value| self.shown(value.is_deprecated))
+            .collect();
+        if !values.is_empty() {
+            out.push_str("<dl>\n");
+            for value in values {
+                let _ = write!(out, "<dt><code>{}</code>", value.name);
+                if let Some(directives) = applied_directives(&value.directives) {
+                    let _ = write!(out, " <code>{}</code>", escape_html(&directives));
+                }
+                out.push_str(&html_deprecation_note(
+                    value.is_deprecated,
+                    value.deprecation_reason.as_deref(),
+                ));
+                out.push_str("</dt>\n<dd>\n");
+                html_description(out, value.description.as_deref());
+                out.push_str("</dd>\n");
+            }
+            out.push_str("</dl>\n");
+        }
+
+        out.push_str("</section>\n");
+    }
+
+    fn html_arguments(&self, out: &mut String, arguments: &[ArgumentDef]) {
+        let arguments: Vec<_> = arguments
+            .iter()
+            .filter(|argument| self.shown(argument.is_deprecated))
+            .collect();
+        if arguments.is_empty() {
+            return;
+        }
+        out.push_str("<ul>\n");
+        for argument in arguments {
+            let _ = write!(
+                out,
+                "<li><code>{}</code>: {}",
+                argument.name,
+                self.html_type_ref(&argument.type_ref)
+            );
+            if let Some(default) = &argument.default_value {
+                let _ = write!(out, " = <code>{}</code>", escape_html(default));
+            }
+            out.push_str(&html_deprecation_note(
+                argument.is_deprecated,
+                argument.deprecation_reason.as_deref(),
+            ));
+            if let Some(description) = &argument.description {
+                let _ = write!(out, " — {}", escape_html(description.trim()));
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ul>\n");
+    }
+
+    fn html_type_ref(&self, type_ref: &TypeRef) -> String {
+        self.html_link(&type_ref.name, &format_type_ref(type_ref))
+    }
+
+    fn html_link(&self, type_name: &str, text: &str) -> String {
+        if self.documented.contains(type_name) {
+            format!("<a href=\"#{type_name}\"><code>{text}</code></a>")
+        } else {
+            format!("<code>{text}</code>")
+        }
+    }
+}
+
+const HTML_STYLE: &str = "body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; line-height: 1.5; }
+section { border-top: 1px solid #ddd; padding-top: 0.5rem; }
+.description { white-space: pre-line; }
+.deprecated { color: #a33; }
+dd { margin-bottom: 0.75rem; }
+";
+
+/// Directives applied to an element, other than `@deprecated`, which is
+/// rendered as a deprecation note instead
+fn applied_directives(directives: &[DirectiveUsage]) -> Option<String> {
+    let rendered: Vec<String> = directives
+        .iter()
+        .filter(|directive| directive.name.as_ref() != "deprecated")
+        .map(|directive| {
+            if directive.arguments.is_empty() {
+                format!("@{}", directive.name)
+            } else {
+                let arguments: Vec<String> = directive
+                    .arguments
+                    .iter()
+                    .map(|argument| format!("{}: {}", argument.name, argument.value))
+                    .collect();
+                format!("@{}({})", directive.name, arguments.join(", "))
+            }
+        })
+        .collect();
+    (!rendered.is_empty()).then(|| rendered.join(" "))
+}
+
+/// `@name(arg: Type = default) repeatable on LOCATION | LOCATION`
+fn directive_signature(directive: &DirectiveDef) -> String {
+    let mut signature = format!("@{}", directive.name);
+    if !directive.arguments.is_empty() {
+        let arguments: Vec<String> = directive
+            .arguments
+            .iter()
+            .map(|argument| match &argument.default_value {
+                Some(default) => format!(
+                    "{}: {} = {default}",
+                    argument.name,
+                    format_type_ref(&argument.type_ref)
+                ),
+                None => format!("{}: {}", argument.name, format_type_ref(&argument.type_ref)),
+            })
+            .collect();
+        let _ = write!(signature, "({})", arguments.join(", "));
+    }
+    if directive.repeatable {
+        signature.push_str(" repeatable");
+    }
+    let locations: Vec<String> = directive
+        .locations
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let _ = write!(signature, " on {}", locations.join(" | "));
+    signature
+}
+
+fn deprecation_note(is_deprecated: bool, reason: Option<&str>) -> String {
+    match (is_deprecated, reason) {
+        (false, _) => String::new(),
+        (true, Some(reason)) => format!(" _(deprecated: {reason})_"),
+        (true, None) => " _(deprecated)_".to_string(),
+    }
+}
+
+fn html_deprecation_note(is_deprecated: bool, reason: Option<&str>) -> String {
+    match (is_deprecated, reason) {
+        (false, _) => String::new(),
+        (true, Some(reason)) => format!(
+            " <em class=\"deprecated\">deprecated: {}</em>",
+            escape_html(reason)
+        ),
+        (true, None) => " <em class=\"deprecated\">deprecated</em>".to_string(),
+    }
+}
+
+/// Descriptions are Markdown by convention, but the HTML page has no
+/// renderer, so they're shown as text with their line breaks kept
+fn html_description(out: &mut String, description: Option<&str>) {
+    if let Some(description) = description {
+        let _ = writeln!(
+            out,
+            "<p class=\"description\">{}</p>",
+            escape_html(description.trim())
+        );
+    }
+}
+
+fn push_indented(out: &mut String, text: &str, indent: &str) {
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "{indent}{line}");
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
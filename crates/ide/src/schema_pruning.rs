@@ -0,0 +1,379 @@
+//! Operation-scoped schema pruning.
+//!
+//! Computes the smallest schema subset that a set of operations touches:
+//! root types, selected fields (with the arguments actually passed plus any
+//! required ones), the types those fields return, fragment type conditions,
+//! and variable input types. Input objects and enums are kept whole since a
+//! variable may supply any of their fields or values.
+//!
+//! A type reached only through `__typename` keeps one field, and a union one
+//! member, so the printed SDL never declares an empty type.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use graphql_hir::{FieldSignature, Selection, TypeDef, TypeDefKind, TypeDefMap};
+
+use crate::types::PrunedSchema;
+
+/// Fields kept on a type, each mapped to the argument names kept on it
+type KeptFields = HashMap<Arc<str>, HashSet<Arc<str>>>;
+
+/// Fragment name to the file that defines it
+type FragmentFileIndex =
+    HashMap<Arc<str>, (graphql_base_db::FileContent, graphql_base_db::FileMetadata)>;
+
+/// Prune the project schema to what the selected operations reach.
+///
+/// `operation_names` of `None` selects every operation in the project,
+/// including anonymous ones. Returns `None` when the project has no schema.
+pub(crate) fn prune_schema(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    operation_names: Option<&[String]>,
+) -> Option<PrunedSchema> {
+    let types = graphql_hir::schema_types(db, project_files);
+    if types.is_empty() {
+        return None;
+    }
+
+    let operations = graphql_hir::all_operations(db, project_files);
+    let mut pruner = Pruner {
+        db,
+        types,
+        fragments: graphql_hir::all_fragments(db, project_files),
+        fragment_index: graphql_hir::fragment_file_index(db, project_files),
+        kept: HashMap::new(),
+        visited_fragments: HashSet::new(),
+    };
+
+    let mut included = Vec::new();
+    for operation in operations.iter() {
+        let selected = match (operation_names, &operation.name) {
+            (None, _) => true,
+            (Some(names), Some(name)) => names.iter().any(|n| n.as_str() == name.as_ref()),
+            (Some(_), None) => false,
+        };
+        if !selected {
+            continue;
+        }
+
+        let Some((content, metadata)) =
+            graphql_base_db::file_lookup(db, project_files, operation.file_id)
+        else {
+            continue;
+        };
+
+        #[allow(clippy::match_same_arms)]
+        let root_type_name = match operation.operation_type {
+            graphql_hir::OperationType::Query => "Query",
+            graphql_hir::OperationType::Mutation => "Mutation",
+            graphql_hir::OperationType::Subscription => "Subscription",
+            _ => "Query", // fallback for future operation types
+        };
+
+        pruner.keep_type(root_type_name);
+        for variable in &operation.variables {
+            pruner.keep_type(&variable.type_ref.name);
+        }
+
+        let body = graphql_hir::operation_body(db, content, metadata, operation.index);
+        pruner.visit_selections(&body.selections, root_type_name);
+
+        included.push(
+            operation
+                .name
+                .as_ref()
+                .map_or_else(|| "<anonymous>".to_string(), ToString::to_string),
+        );
+    }
+
+    let unknown_operations = operation_names
+        .unwrap_or_default()
+        .iter()
+        .filter(|name| {
+            !operations
+                .iter()
+                .any(|op| op.name.as_deref() == Some(name.as_str()))
+        })
+        .cloned()
+        .collect();
+
+    pruner.inherit_interface_fields();
+    pruner.fill_empty_types();
+    let pruned = pruner.build();
+
+    Some(PrunedSchema {
+        sdl: crate::sdl_printer::print_schema_sdl(&pruned),
+        type_count: pruned.len(),
+        operations: included,
+        unknown_operations,
+    })
+}
+
+struct Pruner<'a> {
+    db: &'a dyn graphql_analysis::GraphQLAnalysisDatabase,
+    types: &'a TypeDefMap,
+    fragments: &'a graphql_hir::FragmentMap,
+    fragment_index: Arc<FragmentFileIndex>,
+    kept: HashMap<Arc<str>, KeptFields>,
+    visited_fragments: HashSet<Arc<str>>,
+}
+
+impl Pruner<'_> {
+    /// Mark a type as reachable. Input objects pull in every field type
+    /// recursively; output types only keep the fields that get selected.
+    fn keep_type(&mut self, name: &str) {
+        let Some(type_def) = self.types.get(name) else {
+            return;
+        };
+        if self.kept.contains_key(name) {
+            return;
+        }
+        self.kept.insert(type_def.name.clone(), KeptFields::new());
+
+        if type_def.kind == TypeDefKind::InputObject {
+            for field in &type_def.fields {
+                self.keep_type(&field.type_ref.name);
+            }
+        }
+    }
+
+    fn visit_selections(&mut self, selections: &[Selection], parent_type: &str) {
+        for selection in selections {
+            match selection {
+                Selection::Field {
+                    name,
+                    arguments,
+                    selection_set,
+                    ..
+                } => {
+                    // `__typename` and introspection fields aren't part of the schema SDL
+                    if name.starts_with("__") {
+                        continue;
+                    }
+                    let types = self.types;
+                    let Some(field) = types
+                        .get(parent_type)
+                        .and_then(|t| t.fields.iter().find(|f| f.name == *name))
+                    else {
+                        continue;
+                    };
+
+                    let mut kept_args = HashSet::new();
+                    for arg in &field.arguments {
+                        let passed = arguments.iter().any(|(n, _)| *n == arg.name);
+                        let required = arg.type_ref.is_non_null && arg.default_value.is_none();
+                        if passed || required {
+                            kept_args.insert(arg.name.clone());
+                            self.keep_type(&arg.type_ref.name);
+                        }
+                    }
+
+                    self.keep_type(parent_type);
+                    if let Some(fields) = self.kept.get_mut(parent_type) {
+                        fields
+                            .entry(field.name.clone())
+                            .or_default()
+                            .extend(kept_args);
+                    }
+
+                    self.keep_type(&field.type_ref.name);
+                    self.visit_selections(selection_set, &field.type_ref.name);
+                }
+                Selection::InlineFragment {
+                    type_condition,
+                    selection_set,
+                } => {
+                    let target = type_condition.as_deref().unwrap_or(parent_type);
+                    self.keep_type(target);
+                    self.visit_selections(selection_set, target);
+                }
+                Selection::FragmentSpread { name } => {
+                    if !self.visited_fragments.insert(name.clone()) {
+                        continue;
+                    }
+                    let Some(fragment) = self.fragments.get(name) else {
+                        continue;
+                    };
+                    let Some((content, metadata)) = self.fragment_index.get(name) else {
+                        continue;
+                    };
+                    let type_condition = fragment.type_condition.clone();
+                    let body =
+                        graphql_hir::fragment_body(self.db, *content, *metadata, name.clone());
+                    self.keep_type(&type_condition);
+                    self.visit_selections(&body.selections, &type_condition);
+                }
+            }
+        }
+    }
+
+    /// A type that implements a kept interface must keep the interface's
+    /// kept fields too, or the pruned schema would fail validation.
+    fn inherit_interface_fields(&mut self) {
+        loop {
+            let mut changed = false;
+            let names: Vec<Arc<str>> = self.kept.keys().cloned().collect();
+            for name in names {
+                let Some(type_def) = self.types.get(&name) else {
+                    continue;
+                };
+                for interface in &type_def.implements {
+                    let Some(interface_fields) = self.kept.get(interface).cloned() else {
+                        continue;
+                    };
+                    let Some(fields) = self.kept.get_mut(&name) else {
+                        continue;
+                    };
+                    for (field, args) in interface_fields {
+                        let entry = fields.entry(field).or_default();
+                        let before = entry.len();
+                        entry.extend(args);
+                        changed |= entry.len() != before;
+                    }
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        // Newly inherited fields may return types that weren't reached yet
+        let inherited: Vec<Arc<str>> = self
+            .kept
+            .iter()
+            .filter_map(|(name, fields)| self.types.get(name).map(|t| (t, fields)))
+            .flat_map(|(type_def, fields)| {
+                type_def
+                    .fields
+                    .iter()
+                    .filter(|f| fields.contains_key(&f.name))
+                    .map(|f| f.type_ref.name.clone())
+            })
+            .collect();
+        for type_name in inherited {
+            self.keep_type(&type_name);
+        }
+    }
+
+    /// Give every kept object and interface without a selected field one
+    /// field, and every union without a kept member one member. Selecting
+    /// only `__typename` leaves them empty otherwise, which isn't valid SDL.
+    fn fill_empty_types(&mut self) {
+        loop {
+            let mut changed = false;
+            let names: Vec<Arc<str>> = self.kept.keys().cloned().collect();
+            for name in names {
+                let types = self.types;
+                let Some(type_def) = types.get(&name) else {
+                    continue;
+                };
+                match type_def.kind {
+                    TypeDefKind::Object | TypeDefKind::Interface => {
+                        if !self.kept[&name].is_empty() {
+                            continue;
+                        }
+                        let Some(field) = self.placeholder_field(type_def) else {
+                            continue;
+                        };
+                        let required: HashSet<Arc<str>> = field
+                            .arguments
+                            .iter()
+                            .filter(|arg| arg.type_ref.is_non_null && arg.default_value.is_none())
+                            .map(|arg| {
+                                self.keep_type(&arg.type_ref.name);
+                                arg.name.clone()
+                            })
+                            .collect();
+                        if let Some(fields) = self.kept.get_mut(&name) {
+                            fields.insert(field.name.clone(), required);
+                        }
+                        self.keep_type(&field.type_ref.name);
+                        changed = true;
+                    }
+                    TypeDefKind::Union => {
+                        if type_def
+                            .union_members
+                            .iter()
+                            .any(|member| self.kept.contains_key(member))
+                        {
+                            continue;
+                        }
+                        if let Some(member) = type_def.union_members.first() {
+                            self.keep_type(member);
+                            changed = true;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            if !changed {
+                break;
+            }
+            // A placeholder on an interface must reach its implementations
+            self.inherit_interface_fields();
+        }
+    }
+
+    /// The field to keep on a type nothing selects a field of: the first
+    /// one returning a scalar or enum without required arguments, so it
+    /// pulls in as little as possible
+    fn placeholder_field<'t>(&self, type_def: &'t TypeDef) -> Option<&'t FieldSignature> {
+        let is_leaf = |field: &FieldSignature| {
+            self.types
+                .get(&field.type_ref.name)
+                .is_none_or(|t| matches!(t.kind, TypeDefKind::Scalar | TypeDefKind::Enum))
+        };
+        let no_required_args = |field: &FieldSignature| {
+            field
+                .arguments
+                .iter()
+                .all(|arg| !arg.type_ref.is_non_null || arg.default_value.is_some())
+        };
+        type_def
+            .fields
+            .iter()
+            .find(|field| is_leaf(field) && no_required_args(field))
+            .or_else(|| type_def.fields.first())
+    }
+
+    /// Build the pruned type map, preserving schema declaration order for
+    /// fields, arguments, and union members.
+    fn build(&self) -> TypeDefMap {
+        self.kept
+            .iter()
+            .filter_map(|(name, kept_fields)| {
+                let type_def = self.types.get(name)?;
+                Some((name.clone(), self.prune_type(type_def, kept_fields)))
+            })
+            .collect()
+    }
+
+    fn prune_type(&self, type_def: &TypeDef, kept_fields: &KeptFields) -> TypeDef {
+        let mut pruned = type_def.clone();
+        match type_def.kind {
+            TypeDefKind::Object | TypeDefKind::Interface => {
+                pruned.fields = type_def
+                    .fields
+                    .iter()
+                    .filter_map(|field| {
+                        let kept_args = kept_fields.get(&field.name)?;
+                        let mut field = field.clone();
+                        field.arguments.retain(|arg| kept_args.contains(&arg.name));
+                        Some(field)
+                    })
+                    .collect();
+                pruned
+                    .implements
+                    .retain(|iface| self.kept.contains_key(iface));
+            }
+            TypeDefKind::Union => {
+                pruned
+                    .union_members
+                    .retain(|member| self.kept.contains_key(member));
+            }
+            _ => {}
+        }
+        pruned
+    }
+}
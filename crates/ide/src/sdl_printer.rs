@@ -1,7 +1,8 @@
 //! SDL printer for reconstructing schema SDL from the merged HIR types.
 //!
-//! This generates valid GraphQL SDL from the resolved TypeDefMap, giving
-//! agents a single canonical view of the schema with all extensions merged.
+//! This generates valid GraphQL SDL from a resolved TypeDefMap, giving
+//! consumers (the MCP server, schema pruning) a single canonical view of the
+//! schema with all extensions merged.
 
 use graphql_hir::{TypeDef, TypeDefKind, TypeDefMap};
 
@@ -9,6 +10,7 @@ use graphql_hir::{TypeDef, TypeDefKind, TypeDefMap};
 const BUILTIN_SCALARS: &[&str] = &["String", "Int", "Float", "Boolean", "ID"];
 
 /// Print the full schema SDL from a merged TypeDefMap.
+#[must_use]
 pub fn print_schema_sdl(types: &TypeDefMap) -> String {
     let mut output = String::new();
     let mut sorted_types: Vec<_> = types.iter().collect();
@@ -144,6 +146,12 @@ fn print_object(out: &mut String, td: &TypeDef, keyword: &str) {
         }
     }
     print_directives_inline(out, &td.directives);
+    // A pruned type can end up with no fields (e.g. only `__typename` was
+    // selected); `{}` is a syntax error, so drop the braces entirely.
+    if td.fields.is_empty() {
+        out.push('\n');
+        return;
+    }
     out.push_str(" {\n");
     for field in &td.fields {
         print_field(out, field);
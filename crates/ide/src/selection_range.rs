@@ -6,7 +6,9 @@
 
 use apollo_parser::cst::{self, CstNode};
 
-use crate::helpers::{find_block_for_position, offset_range_to_range, position_to_offset};
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
 use crate::types::{FilePath, Position, Range, SelectionRange};
 use crate::DbFiles;
 
@@ -48,7 +50,7 @@ fn selection_range_at_position(
 ) -> Option<SelectionRange> {
     let (block_context, adjusted_position) = find_block_for_position(parse, position)?;
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
     // Find all ancestor ranges at this offset (outermost to innermost)
@@ -56,7 +58,7 @@ fn selection_range_at_position(
         block_context.tree,
         &block_line_index,
         offset,
-        block_context.line_offset,
+        block_context.origin(),
     );
 
     SelectionRange::from_ranges(&ranges)
@@ -69,12 +71,12 @@ fn find_ancestor_ranges(
     tree: &apollo_parser::SyntaxTree,
     line_index: &graphql_syntax::LineIndex,
     byte_offset: usize,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
 ) -> Vec<Range> {
     let doc = tree.document();
 
     // Start with the document range
-    let doc_range = syntax_range_to_ide_range(doc.syntax(), line_index, line_offset);
+    let doc_range = syntax_range_to_ide_range(doc.syntax(), line_index, origin);
     let mut ranges = vec![doc_range];
 
     // Find the definition containing the offset
@@ -85,19 +87,15 @@ fn find_ancestor_ranges(
         }
 
         // Add the definition range
-        ranges.push(syntax_range_to_ide_range(
-            def_syntax,
-            line_index,
-            line_offset,
-        ));
+        ranges.push(syntax_range_to_ide_range(def_syntax, line_index, origin));
 
         // Drill into the specific definition type
         match definition {
             cst::Definition::OperationDefinition(op) => {
-                collect_operation_ranges(&op, byte_offset, line_index, line_offset, &mut ranges);
+                collect_operation_ranges(&op, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::FragmentDefinition(frag) => {
-                collect_fragment_ranges(&frag, byte_offset, line_index, line_offset, &mut ranges);
+                collect_fragment_ranges(&frag, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::SchemaDefinition(schema) => {
                 // Schema definition - add root operation types if cursor is there
@@ -106,7 +104,7 @@ fn find_ancestor_ranges(
                         ranges.push(syntax_range_to_ide_range(
                             root_op.syntax(),
                             line_index,
-                            line_offset,
+                            origin,
                         ));
                         // Add the type name if cursor is on it
                         if let Some(named_type) = root_op.named_type() {
@@ -114,7 +112,7 @@ fn find_ancestor_ranges(
                                 ranges.push(syntax_range_to_ide_range(
                                     named_type.syntax(),
                                     line_index,
-                                    line_offset,
+                                    origin,
                                 ));
                             }
                         }
@@ -122,61 +120,33 @@ fn find_ancestor_ranges(
                 }
             }
             cst::Definition::ObjectTypeDefinition(obj) => {
-                collect_object_type_ranges(&obj, byte_offset, line_index, line_offset, &mut ranges);
+                collect_object_type_ranges(&obj, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::InterfaceTypeDefinition(iface) => {
-                collect_interface_type_ranges(
-                    &iface,
-                    byte_offset,
-                    line_index,
-                    line_offset,
-                    &mut ranges,
-                );
+                collect_interface_type_ranges(&iface, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::UnionTypeDefinition(union_def) => {
-                collect_union_type_ranges(
-                    &union_def,
-                    byte_offset,
-                    line_index,
-                    line_offset,
-                    &mut ranges,
-                );
+                collect_union_type_ranges(&union_def, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::EnumTypeDefinition(enum_def) => {
-                collect_enum_type_ranges(
-                    &enum_def,
-                    byte_offset,
-                    line_index,
-                    line_offset,
-                    &mut ranges,
-                );
+                collect_enum_type_ranges(&enum_def, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::ScalarTypeDefinition(scalar) => {
                 if let Some(name) = scalar.name() {
                     if contains_offset(name.syntax(), byte_offset) {
-                        ranges.push(syntax_range_to_ide_range(
-                            name.syntax(),
-                            line_index,
-                            line_offset,
-                        ));
+                        ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
                     }
                 }
             }
             cst::Definition::InputObjectTypeDefinition(input) => {
-                collect_input_type_ranges(
-                    &input,
-                    byte_offset,
-                    line_index,
-                    line_offset,
-                    &mut ranges,
-                );
+                collect_input_type_ranges(&input, byte_offset, line_index, origin, &mut ranges);
             }
             cst::Definition::DirectiveDefinition(dir_def) => {
                 collect_directive_definition_ranges(
                     &dir_def,
                     byte_offset,
                     line_index,
-                    line_offset,
+                    origin,
                     &mut ranges,
                 );
             }
@@ -203,17 +173,13 @@ fn collect_operation_ranges(
     op: &cst::OperationDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     // Check if cursor is in operation name
     if let Some(name) = op.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -224,14 +190,14 @@ fn collect_operation_ranges(
             ranges.push(syntax_range_to_ide_range(
                 var_defs.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for var_def in var_defs.variable_definitions() {
                 if contains_offset(var_def.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         var_def.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     // Add variable name if cursor is on it
                     if let Some(var) = var_def.variable() {
@@ -239,13 +205,13 @@ fn collect_operation_ranges(
                             ranges.push(syntax_range_to_ide_range(
                                 var.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     // Add type if cursor is on it
                     if let Some(ty) = var_def.ty() {
-                        collect_type_ranges(&ty, byte_offset, line_index, line_offset, ranges);
+                        collect_type_ranges(&ty, byte_offset, line_index, origin, ranges);
                     }
                     return;
                 }
@@ -260,16 +226,16 @@ fn collect_operation_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directives.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
-            collect_directives_ranges(&directives, byte_offset, line_index, line_offset, ranges);
+            collect_directives_ranges(&directives, byte_offset, line_index, origin, ranges);
             return;
         }
     }
 
     // Check selection set
     if let Some(selection_set) = op.selection_set() {
-        collect_selection_set_ranges(&selection_set, byte_offset, line_index, line_offset, ranges);
+        collect_selection_set_ranges(&selection_set, byte_offset, line_index, origin, ranges);
     }
 }
 
@@ -278,17 +244,13 @@ fn collect_fragment_ranges(
     frag: &cst::FragmentDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     // Check fragment name
     if let Some(name) = frag.fragment_name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -299,14 +261,14 @@ fn collect_fragment_ranges(
             ranges.push(syntax_range_to_ide_range(
                 type_cond.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             if let Some(named_type) = type_cond.named_type() {
                 if contains_offset(named_type.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         named_type.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                 }
             }
@@ -320,16 +282,16 @@ fn collect_fragment_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directives.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
-            collect_directives_ranges(&directives, byte_offset, line_index, line_offset, ranges);
+            collect_directives_ranges(&directives, byte_offset, line_index, origin, ranges);
             return;
         }
     }
 
     // Check selection set
     if let Some(selection_set) = frag.selection_set() {
-        collect_selection_set_ranges(&selection_set, byte_offset, line_index, line_offset, ranges);
+        collect_selection_set_ranges(&selection_set, byte_offset, line_index, origin, ranges);
     }
 }
 
@@ -338,7 +300,7 @@ fn collect_selection_set_ranges(
     selection_set: &cst::SelectionSet,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if !contains_offset(selection_set.syntax(), byte_offset) {
@@ -349,7 +311,7 @@ fn collect_selection_set_ranges(
     ranges.push(syntax_range_to_ide_range(
         selection_set.syntax(),
         line_index,
-        line_offset,
+        origin,
     ));
 
     // Find the selection containing the cursor
@@ -361,24 +323,20 @@ fn collect_selection_set_ranges(
 
         match selection {
             cst::Selection::Field(field) => {
-                collect_field_ranges(&field, byte_offset, line_index, line_offset, ranges);
+                collect_field_ranges(&field, byte_offset, line_index, origin, ranges);
             }
             cst::Selection::FragmentSpread(spread) => {
                 // Add the spread range
                 ranges.push(syntax_range_to_ide_range(
                     spread.syntax(),
                     line_index,
-                    line_offset,
+                    origin,
                 ));
 
                 // Add fragment name if cursor is on it
                 if let Some(name) = spread.fragment_name() {
                     if contains_offset(name.syntax(), byte_offset) {
-                        ranges.push(syntax_range_to_ide_range(
-                            name.syntax(),
-                            line_index,
-                            line_offset,
-                        ));
+                        ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
                     }
                 }
 
@@ -388,13 +346,13 @@ fn collect_selection_set_ranges(
                         ranges.push(syntax_range_to_ide_range(
                             directives.syntax(),
                             line_index,
-                            line_offset,
+                            origin,
                         ));
                         collect_directives_ranges(
                             &directives,
                             byte_offset,
                             line_index,
-                            line_offset,
+                            origin,
                             ranges,
                         );
                     }
@@ -405,7 +363,7 @@ fn collect_selection_set_ranges(
                 ranges.push(syntax_range_to_ide_range(
                     inline_frag.syntax(),
                     line_index,
-                    line_offset,
+                    origin,
                 ));
 
                 // Check type condition
@@ -414,14 +372,14 @@ fn collect_selection_set_ranges(
                         ranges.push(syntax_range_to_ide_range(
                             type_cond.syntax(),
                             line_index,
-                            line_offset,
+                            origin,
                         ));
                         if let Some(named_type) = type_cond.named_type() {
                             if contains_offset(named_type.syntax(), byte_offset) {
                                 ranges.push(syntax_range_to_ide_range(
                                     named_type.syntax(),
                                     line_index,
-                                    line_offset,
+                                    origin,
                                 ));
                             }
                         }
@@ -435,13 +393,13 @@ fn collect_selection_set_ranges(
                         ranges.push(syntax_range_to_ide_range(
                             directives.syntax(),
                             line_index,
-                            line_offset,
+                            origin,
                         ));
                         collect_directives_ranges(
                             &directives,
                             byte_offset,
                             line_index,
-                            line_offset,
+                            origin,
                             ranges,
                         );
                         return;
@@ -454,7 +412,7 @@ fn collect_selection_set_ranges(
                         &nested_selection_set,
                         byte_offset,
                         line_index,
-                        line_offset,
+                        origin,
                         ranges,
                     );
                 }
@@ -470,14 +428,14 @@ fn collect_field_ranges(
     field: &cst::Field,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     // Add the field range (entire field including nested selection set)
     ranges.push(syntax_range_to_ide_range(
         field.syntax(),
         line_index,
-        line_offset,
+        origin,
     ));
 
     // Check alias
@@ -486,7 +444,7 @@ fn collect_field_ranges(
             ranges.push(syntax_range_to_ide_range(
                 alias.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             return;
         }
@@ -495,11 +453,7 @@ fn collect_field_ranges(
     // Check field name
     if let Some(name) = field.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -510,28 +464,24 @@ fn collect_field_ranges(
             ranges.push(syntax_range_to_ide_range(
                 arguments.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for arg in arguments.arguments() {
                 if contains_offset(arg.syntax(), byte_offset) {
-                    ranges.push(syntax_range_to_ide_range(
-                        arg.syntax(),
-                        line_index,
-                        line_offset,
-                    ));
+                    ranges.push(syntax_range_to_ide_range(arg.syntax(), line_index, origin));
                     // Add argument name if cursor is on it
                     if let Some(name) = arg.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     // Add argument value if cursor is on it
                     if let Some(value) = arg.value() {
-                        collect_value_ranges(&value, byte_offset, line_index, line_offset, ranges);
+                        collect_value_ranges(&value, byte_offset, line_index, origin, ranges);
                     }
                     return;
                 }
@@ -546,16 +496,16 @@ fn collect_field_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directives.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
-            collect_directives_ranges(&directives, byte_offset, line_index, line_offset, ranges);
+            collect_directives_ranges(&directives, byte_offset, line_index, origin, ranges);
             return;
         }
     }
 
     // Check nested selection set (recurse)
     if let Some(selection_set) = field.selection_set() {
-        collect_selection_set_ranges(&selection_set, byte_offset, line_index, line_offset, ranges);
+        collect_selection_set_ranges(&selection_set, byte_offset, line_index, origin, ranges);
     }
 }
 
@@ -564,7 +514,7 @@ fn collect_directives_ranges(
     directives: &cst::Directives,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     for directive in directives.directives() {
@@ -572,17 +522,13 @@ fn collect_directives_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directive.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
 
             // Add directive name if cursor is on it
             if let Some(name) = directive.name() {
                 if contains_offset(name.syntax(), byte_offset) {
-                    ranges.push(syntax_range_to_ide_range(
-                        name.syntax(),
-                        line_index,
-                        line_offset,
-                    ));
+                    ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
                     return;
                 }
             }
@@ -593,21 +539,21 @@ fn collect_directives_ranges(
                     ranges.push(syntax_range_to_ide_range(
                         arguments.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     for arg in arguments.arguments() {
                         if contains_offset(arg.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 arg.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                             if let Some(name) = arg.name() {
                                 if contains_offset(name.syntax(), byte_offset) {
                                     ranges.push(syntax_range_to_ide_range(
                                         name.syntax(),
                                         line_index,
-                                        line_offset,
+                                        origin,
                                     ));
                                 }
                             }
@@ -616,7 +562,7 @@ fn collect_directives_ranges(
                                     &value,
                                     byte_offset,
                                     line_index,
-                                    line_offset,
+                                    origin,
                                     ranges,
                                 );
                             }
@@ -635,7 +581,7 @@ fn collect_value_ranges(
     value: &cst::Value,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if !contains_offset(value.syntax(), byte_offset) {
@@ -645,14 +591,14 @@ fn collect_value_ranges(
     ranges.push(syntax_range_to_ide_range(
         value.syntax(),
         line_index,
-        line_offset,
+        origin,
     ));
 
     match value {
         cst::Value::ListValue(list) => {
             for item in list.values() {
                 if contains_offset(item.syntax(), byte_offset) {
-                    collect_value_ranges(&item, byte_offset, line_index, line_offset, ranges);
+                    collect_value_ranges(&item, byte_offset, line_index, origin, ranges);
                     break;
                 }
             }
@@ -663,25 +609,19 @@ fn collect_value_ranges(
                     ranges.push(syntax_range_to_ide_range(
                         field.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(name) = field.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     if let Some(inner_value) = field.value() {
-                        collect_value_ranges(
-                            &inner_value,
-                            byte_offset,
-                            line_index,
-                            line_offset,
-                            ranges,
-                        );
+                        collect_value_ranges(&inner_value, byte_offset, line_index, origin, ranges);
                     }
                     break;
                 }
@@ -696,34 +636,26 @@ fn collect_type_ranges(
     ty: &cst::Type,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if !contains_offset(ty.syntax(), byte_offset) {
         return;
     }
 
-    ranges.push(syntax_range_to_ide_range(
-        ty.syntax(),
-        line_index,
-        line_offset,
-    ));
+    ranges.push(syntax_range_to_ide_range(ty.syntax(), line_index, origin));
 
     match ty {
         cst::Type::NamedType(named) => {
             if let Some(name) = named.name() {
                 if contains_offset(name.syntax(), byte_offset) {
-                    ranges.push(syntax_range_to_ide_range(
-                        name.syntax(),
-                        line_index,
-                        line_offset,
-                    ));
+                    ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
                 }
             }
         }
         cst::Type::ListType(list) => {
             if let Some(inner_ty) = list.ty() {
-                collect_type_ranges(&inner_ty, byte_offset, line_index, line_offset, ranges);
+                collect_type_ranges(&inner_ty, byte_offset, line_index, origin, ranges);
             }
         }
         cst::Type::NonNullType(non_null) => {
@@ -732,14 +664,14 @@ fn collect_type_ranges(
                     ranges.push(syntax_range_to_ide_range(
                         named.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(name) = named.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
@@ -747,19 +679,9 @@ fn collect_type_ranges(
             }
             if let Some(list) = non_null.list_type() {
                 if contains_offset(list.syntax(), byte_offset) {
-                    ranges.push(syntax_range_to_ide_range(
-                        list.syntax(),
-                        line_index,
-                        line_offset,
-                    ));
+                    ranges.push(syntax_range_to_ide_range(list.syntax(), line_index, origin));
                     if let Some(inner_ty) = list.ty() {
-                        collect_type_ranges(
-                            &inner_ty,
-                            byte_offset,
-                            line_index,
-                            line_offset,
-                            ranges,
-                        );
+                        collect_type_ranges(&inner_ty, byte_offset, line_index, origin, ranges);
                     }
                 }
             }
@@ -772,45 +694,37 @@ fn collect_directive_definition_ranges(
     dir_def: &cst::DirectiveDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = dir_def.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
 
     if let Some(args) = dir_def.arguments_definition() {
         if contains_offset(args.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                args.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(args.syntax(), line_index, origin));
             for input_value in args.input_value_definitions() {
                 if contains_offset(input_value.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         input_value.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(name) = input_value.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     if let Some(ty) = input_value.ty() {
-                        collect_type_ranges(&ty, byte_offset, line_index, line_offset, ranges);
+                        collect_type_ranges(&ty, byte_offset, line_index, origin, ranges);
                     }
                 }
             }
@@ -823,16 +737,12 @@ fn collect_object_type_ranges(
     obj: &cst::ObjectTypeDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = obj.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -842,14 +752,14 @@ fn collect_object_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 implements.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for named_type in implements.named_types() {
                 if contains_offset(named_type.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         named_type.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                 }
             }
@@ -862,9 +772,9 @@ fn collect_object_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directives.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
-            collect_directives_ranges(&directives, byte_offset, line_index, line_offset, ranges);
+            collect_directives_ranges(&directives, byte_offset, line_index, origin, ranges);
             return;
         }
     }
@@ -874,17 +784,11 @@ fn collect_object_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 fields_def.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for field in fields_def.field_definitions() {
                 if contains_offset(field.syntax(), byte_offset) {
-                    collect_schema_field_ranges(
-                        &field,
-                        byte_offset,
-                        line_index,
-                        line_offset,
-                        ranges,
-                    );
+                    collect_schema_field_ranges(&field, byte_offset, line_index, origin, ranges);
                 }
             }
         }
@@ -896,16 +800,12 @@ fn collect_interface_type_ranges(
     iface: &cst::InterfaceTypeDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = iface.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -915,9 +815,9 @@ fn collect_interface_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 directives.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
-            collect_directives_ranges(&directives, byte_offset, line_index, line_offset, ranges);
+            collect_directives_ranges(&directives, byte_offset, line_index, origin, ranges);
             return;
         }
     }
@@ -927,17 +827,11 @@ fn collect_interface_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 fields_def.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for field in fields_def.field_definitions() {
                 if contains_offset(field.syntax(), byte_offset) {
-                    collect_schema_field_ranges(
-                        &field,
-                        byte_offset,
-                        line_index,
-                        line_offset,
-                        ranges,
-                    );
+                    collect_schema_field_ranges(&field, byte_offset, line_index, origin, ranges);
                 }
             }
         }
@@ -949,16 +843,12 @@ fn collect_union_type_ranges(
     union_def: &cst::UnionTypeDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = union_def.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -968,14 +858,14 @@ fn collect_union_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 members.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for member in members.named_types() {
                 if contains_offset(member.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         member.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                 }
             }
@@ -988,16 +878,12 @@ fn collect_enum_type_ranges(
     enum_def: &cst::EnumTypeDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = enum_def.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -1007,21 +893,21 @@ fn collect_enum_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 values_def.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for value in values_def.enum_value_definitions() {
                 if contains_offset(value.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         value.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(enum_val) = value.enum_value() {
                         if contains_offset(enum_val.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 enum_val.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
@@ -1036,16 +922,12 @@ fn collect_input_type_ranges(
     input: &cst::InputObjectTypeDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     if let Some(name) = input.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
@@ -1055,26 +937,26 @@ fn collect_input_type_ranges(
             ranges.push(syntax_range_to_ide_range(
                 fields_def.syntax(),
                 line_index,
-                line_offset,
+                origin,
             ));
             for input_value in fields_def.input_value_definitions() {
                 if contains_offset(input_value.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         input_value.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(name) = input_value.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     if let Some(ty) = input_value.ty() {
-                        collect_type_ranges(&ty, byte_offset, line_index, line_offset, ranges);
+                        collect_type_ranges(&ty, byte_offset, line_index, origin, ranges);
                     }
                 }
             }
@@ -1087,51 +969,43 @@ fn collect_schema_field_ranges(
     field: &cst::FieldDefinition,
     byte_offset: usize,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     ranges: &mut Vec<Range>,
 ) {
     ranges.push(syntax_range_to_ide_range(
         field.syntax(),
         line_index,
-        line_offset,
+        origin,
     ));
 
     if let Some(name) = field.name() {
         if contains_offset(name.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                name.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(name.syntax(), line_index, origin));
             return;
         }
     }
 
     if let Some(args) = field.arguments_definition() {
         if contains_offset(args.syntax(), byte_offset) {
-            ranges.push(syntax_range_to_ide_range(
-                args.syntax(),
-                line_index,
-                line_offset,
-            ));
+            ranges.push(syntax_range_to_ide_range(args.syntax(), line_index, origin));
             for input_value in args.input_value_definitions() {
                 if contains_offset(input_value.syntax(), byte_offset) {
                     ranges.push(syntax_range_to_ide_range(
                         input_value.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                     ));
                     if let Some(name) = input_value.name() {
                         if contains_offset(name.syntax(), byte_offset) {
                             ranges.push(syntax_range_to_ide_range(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                             ));
                         }
                     }
                     if let Some(ty) = input_value.ty() {
-                        collect_type_ranges(&ty, byte_offset, line_index, line_offset, ranges);
+                        collect_type_ranges(&ty, byte_offset, line_index, origin, ranges);
                     }
                 }
             }
@@ -1140,7 +1014,7 @@ fn collect_schema_field_ranges(
     }
 
     if let Some(ty) = field.ty() {
-        collect_type_ranges(&ty, byte_offset, line_index, line_offset, ranges);
+        collect_type_ranges(&ty, byte_offset, line_index, origin, ranges);
     }
 }
 
@@ -1158,29 +1032,16 @@ fn contains_offset(node: &apollo_parser::SyntaxNode, offset: usize) -> bool {
 fn syntax_range_to_ide_range(
     node: &apollo_parser::SyntaxNode,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
 ) -> Range {
     let range = node.text_range();
     let start_offset: usize = range.start().into();
     let end_offset: usize = range.end().into();
 
-    let base_range = offset_range_to_range(line_index, start_offset, end_offset);
-
-    // Adjust for embedded GraphQL line offset
-    if line_offset > 0 {
-        Range::new(
-            crate::types::Position::new(
-                base_range.start.line + line_offset,
-                base_range.start.character,
-            ),
-            crate::types::Position::new(
-                base_range.end.line + line_offset,
-                base_range.end.character,
-            ),
-        )
-    } else {
-        base_range
-    }
+    adjust_range_for_block(
+        offset_range_to_range(line_index, start_offset, end_offset),
+        origin,
+    )
 }
 
 #[cfg(test)]
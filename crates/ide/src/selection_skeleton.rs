@@ -0,0 +1,429 @@
+//! Selection set skeletons.
+//!
+//! Generates the selection set for the field under the cursor on demand:
+//! every leaf field of its return type, `id` first, plus composite fields
+//! expanded up to a configurable depth. The result is a snippet that ends
+//! with a final tab stop, so the editor leaves the cursor inside the new
+//! selection set.
+//!
+//! The same expansion fills in an existing selection set: every field of
+//! its type that isn't selected yet is added after the current selections,
+//! at their indentation.
+//!
+//! Fields that take required arguments, deprecated fields and unions are
+//! left out; they can't be selected without further input.
+
+use apollo_parser::cst::{self, CstNode};
+use graphql_apollo_ext::{OperationExt, TypeConditionExt};
+use graphql_syntax::edit::line_indent;
+
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
+use crate::types::{FilePath, Position, SelectionSkeleton, TextEdit};
+use crate::DbFiles;
+
+/// Build the selection set skeleton for the field at `position`.
+///
+/// `depth` is how many levels of composite fields are expanded below the
+/// field itself; `0` selects leaf fields only. `indent` is one indentation
+/// level. Returns `None` when the cursor isn't on a field name, the field
+/// returns a leaf type, or it already has selections.
+pub fn selection_skeleton(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+    depth: usize,
+    indent: &str,
+) -> Option<SelectionSkeleton> {
+    let project_files = project_files?;
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    let (block, adjusted) = find_block_for_position(&parse, position)?;
+    let line_index = block.line_index();
+    let offset = position_to_offset(&line_index, adjusted)?;
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let target = find_field_at_offset(block.tree, types, offset)?;
+    let field_type = types
+        .get(target.parent_type.as_str())?
+        .fields
+        .iter()
+        .find(|f| f.name.as_ref() == target.field_name)
+        .and_then(|f| types.get(f.type_ref.name.as_ref()))?;
+
+    let mut lines = Vec::new();
+    push_selections(types, field_type, &[], depth, 1, indent, &mut lines);
+    if lines.is_empty() {
+        return None;
+    }
+    let snippet = format!("{{\n{}\n{indent}$0\n}}", lines.join("\n"));
+
+    let (start, end) = target.replace;
+    let range = adjust_range_for_block(
+        offset_range_to_range(&line_index, start, end),
+        block.origin(),
+    );
+
+    Some(SelectionSkeleton {
+        range,
+        snippet: if start == end {
+            format!(" {snippet}")
+        } else {
+            snippet
+        },
+    })
+}
+
+/// The edit that fills the selection set around `position` with the fields
+/// it doesn't select yet.
+///
+/// `depth` and `indent` work as for [`selection_skeleton`]. Returns `None`
+/// outside a selection set, or when every selectable field is already
+/// there.
+pub fn fill_selection_set(
+    db: &dyn graphql_hir::GraphQLHirDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+    depth: usize,
+    indent: &str,
+) -> Option<TextEdit> {
+    let project_files = project_files?;
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    let (block, adjusted) = find_block_for_position(&parse, position)?;
+    let line_index = block.line_index();
+    let offset = position_to_offset(&line_index, adjusted)?;
+
+    let types = graphql_hir::schema_types(db, project_files);
+    let (selection_set, parent_type) = find_selection_set_at_offset(block.tree, types, offset)?;
+    let type_def = types.get(parent_type.as_str())?;
+    let r_curly = selection_set.r_curly_token()?;
+
+    let selections: Vec<cst::Selection> = selection_set.selections().collect();
+    let selected: Vec<String> = selections
+        .iter()
+        .filter_map(|selection| match selection {
+            cst::Selection::Field(field) => field.name().map(|n| n.text().to_string()),
+            _ => None,
+        })
+        .collect();
+    let selected: Vec<&str> = selected.iter().map(String::as_str).collect();
+
+    let mut lines = Vec::new();
+    push_selections(types, type_def, &selected, depth, 0, indent, &mut lines);
+    if lines.is_empty() {
+        return None;
+    }
+
+    let source = block.block_source;
+    let set_start: usize = selection_set.syntax().text_range().start().into();
+    let base = line_indent(source, set_start);
+    let r_curly_start: usize = r_curly.text_range().start().into();
+
+    // New fields go after the last selection, lined up with it when it has
+    // a line of its own. A closing brace sharing that line moves down.
+    let (start, end, child_indent) = match selections.last() {
+        Some(last) => {
+            let last_start: usize = last.syntax().text_range().start().into();
+            let last_end: usize = last.syntax().text_range().end().into();
+            let child_indent = if source[set_start..last_start].contains('\n') {
+                line_indent(source, last_start).to_string()
+            } else {
+                format!("{base}{indent}")
+            };
+            if source[last_end..r_curly_start].contains('\n') {
+                (last_end, last_end, child_indent)
+            } else {
+                (last_end, r_curly_start, child_indent)
+            }
+        }
+        None => (
+            set_start,
+            r_curly.text_range().end().into(),
+            format!("{base}{indent}"),
+        ),
+    };
+
+    let mut new_text = if selections.is_empty() {
+        "{".to_string()
+    } else {
+        String::new()
+    };
+    for line in &lines {
+        new_text.push('\n');
+        new_text.push_str(&child_indent);
+        new_text.push_str(line);
+    }
+    if selections.is_empty() || end == r_curly_start {
+        new_text.push('\n');
+        new_text.push_str(base);
+    }
+    if selections.is_empty() {
+        new_text.push('}');
+    }
+
+    let range = offset_range_to_range(&line_index, start, end);
+    Some(TextEdit::new(
+        adjust_range_for_block(range, block.origin()),
+        new_text,
+    ))
+}
+
+/// Append one line per selected field of `type_def` at nesting `level`,
+/// leaving out the fields named in `skip`.
+fn push_selections(
+    types: &graphql_hir::TypeDefMap,
+    type_def: &graphql_hir::TypeDef,
+    skip: &[&str],
+    depth: usize,
+    level: usize,
+    indent: &str,
+    lines: &mut Vec<String>,
+) {
+    let prefix = indent.repeat(level);
+    let mut fields: Vec<&graphql_hir::FieldSignature> = type_def
+        .fields
+        .iter()
+        .filter(|f| is_selectable(f) && !skip.contains(&f.name.as_ref()))
+        .collect();
+    // `id` leads; the rest keep schema order
+    fields.sort_by_key(|f| f.name.as_ref() != "id");
+
+    let mut nested = Vec::new();
+    for field in fields {
+        let Some(field_type) = types.get(field.type_ref.name.as_ref()) else {
+            continue;
+        };
+        match field_type.kind {
+            graphql_hir::TypeDefKind::Scalar | graphql_hir::TypeDefKind::Enum => {
+                lines.push(format!("{prefix}{}", field.name));
+            }
+            graphql_hir::TypeDefKind::Object | graphql_hir::TypeDefKind::Interface if depth > 0 => {
+                nested.push((field, field_type));
+            }
+            _ => {}
+        }
+    }
+
+    // Composite fields follow the leaves so wide types read top-down
+    for (field, field_type) in nested {
+        let mut inner = Vec::new();
+        push_selections(
+            types,
+            field_type,
+            &[],
+            depth - 1,
+            level + 1,
+            indent,
+            &mut inner,
+        );
+        if inner.is_empty() {
+            continue;
+        }
+        lines.push(format!("{prefix}{} {{", field.name));
+        lines.append(&mut inner);
+        lines.push(format!("{prefix}}}"));
+    }
+}
+
+/// Whether a field can be selected without arguments or warnings
+fn is_selectable(field: &graphql_hir::FieldSignature) -> bool {
+    !field.name.starts_with("__")
+        && !field.is_deprecated
+        && !field
+            .arguments
+            .iter()
+            .any(|arg| arg.type_ref.is_non_null && arg.default_value.is_none())
+}
+
+/// The field whose name is under the cursor, with its parent type
+struct TargetField {
+    parent_type: String,
+    field_name: String,
+    /// Byte range the skeleton replaces: empty after the field, or its
+    /// empty selection set
+    replace: (usize, usize),
+}
+
+fn find_field_at_offset(
+    tree: &apollo_parser::SyntaxTree,
+    types: &graphql_hir::TypeDefMap,
+    offset: usize,
+) -> Option<TargetField> {
+    root_selection_sets(tree).find_map(|(selection_set, parent_type)| {
+        find_in_selection_set(&selection_set, types, &parent_type, offset)
+    })
+}
+
+/// The innermost selection set containing `offset`, with its parent type
+fn find_selection_set_at_offset(
+    tree: &apollo_parser::SyntaxTree,
+    types: &graphql_hir::TypeDefMap,
+    offset: usize,
+) -> Option<(cst::SelectionSet, String)> {
+    root_selection_sets(tree).find_map(|(selection_set, parent_type)| {
+        innermost_selection_set(selection_set, types, parent_type, offset)
+    })
+}
+
+fn innermost_selection_set(
+    selection_set: cst::SelectionSet,
+    types: &graphql_hir::TypeDefMap,
+    parent_type: String,
+    offset: usize,
+) -> Option<(cst::SelectionSet, String)> {
+    if !contains(selection_set.syntax(), offset) {
+        return None;
+    }
+    for selection in selection_set.selections() {
+        let (nested, type_name) = match selection {
+            cst::Selection::Field(field) => {
+                let Some(nested) = field.selection_set() else {
+                    continue;
+                };
+                let Some(name) = field.name() else {
+                    continue;
+                };
+                let Some(field_type) = types.get(parent_type.as_str()).and_then(|t| {
+                    t.fields
+                        .iter()
+                        .find(|f| name.text() == f.name.as_ref())
+                        .map(|f| f.type_ref.name.to_string())
+                }) else {
+                    continue;
+                };
+                (nested, field_type)
+            }
+            cst::Selection::InlineFragment(inline) => {
+                let Some(nested) = inline.selection_set() else {
+                    continue;
+                };
+                let type_name = inline
+                    .type_condition_name()
+                    .unwrap_or_else(|| parent_type.clone());
+                (nested, type_name)
+            }
+            cst::Selection::FragmentSpread(_) => continue,
+        };
+        if contains(nested.syntax(), offset) {
+            return innermost_selection_set(nested, types, type_name, offset);
+        }
+    }
+    Some((selection_set, parent_type))
+}
+
+/// The selection set of every operation and fragment, with its parent type
+fn root_selection_sets(
+    tree: &apollo_parser::SyntaxTree,
+) -> impl Iterator<Item = (cst::SelectionSet, String)> {
+    tree.document()
+        .definitions()
+        .filter_map(|definition| match definition {
+            cst::Definition::OperationDefinition(op) => {
+                let root = op.operation_kind().root_type_name();
+                Some((op.selection_set()?, root.to_string()))
+            }
+            cst::Definition::FragmentDefinition(frag) => {
+                Some((frag.selection_set()?, frag.type_condition_name()?))
+            }
+            _ => None,
+        })
+}
+
+fn find_in_selection_set(
+    selection_set: &cst::SelectionSet,
+    types: &graphql_hir::TypeDefMap,
+    parent_type: &str,
+    offset: usize,
+) -> Option<TargetField> {
+    if !contains(selection_set.syntax(), offset) {
+        return None;
+    }
+    for selection in selection_set.selections() {
+        match selection {
+            cst::Selection::Field(field) => {
+                let Some(name) = field.name() else {
+                    continue;
+                };
+                if contains(name.syntax(), offset) {
+                    return target_for_field(&field, &name, parent_type);
+                }
+                let Some(nested) = field.selection_set() else {
+                    continue;
+                };
+                if !contains(nested.syntax(), offset) {
+                    continue;
+                }
+                let field_type = types
+                    .get(parent_type)?
+                    .fields
+                    .iter()
+                    .find(|f| name.text() == f.name.as_ref())?
+                    .type_ref
+                    .name
+                    .clone();
+                return find_in_selection_set(&nested, types, &field_type, offset);
+            }
+            cst::Selection::InlineFragment(inline) => {
+                let Some(nested) = inline.selection_set() else {
+                    continue;
+                };
+                let type_name = inline
+                    .type_condition_name()
+                    .unwrap_or_else(|| parent_type.to_string());
+                if let Some(target) = find_in_selection_set(&nested, types, &type_name, offset) {
+                    return Some(target);
+                }
+            }
+            cst::Selection::FragmentSpread(_) => {}
+        }
+    }
+    None
+}
+
+fn target_for_field(
+    field: &cst::Field,
+    name: &cst::Name,
+    parent_type: &str,
+) -> Option<TargetField> {
+    let replace = match field.selection_set() {
+        Some(set) if set.selections().next().is_some() => return None,
+        Some(set) => {
+            let range = set.syntax().text_range();
+            (range.start().into(), range.end().into())
+        }
+        None => {
+            let end: usize = [
+                field.directives().map(|d| d.syntax().text_range().end()),
+                field.arguments().map(|a| a.syntax().text_range().end()),
+                Some(name.syntax().text_range().end()),
+            ]
+            .into_iter()
+            .flatten()
+            .max()?
+            .into();
+            (end, end)
+        }
+    };
+    Some(TargetField {
+        parent_type: parent_type.to_string(),
+        field_name: name.text().to_string(),
+        replace,
+    })
+}
+
+fn contains(node: &apollo_parser::SyntaxNode, offset: usize) -> bool {
+    let range = node.text_range();
+    offset >= range.start().into() && offset <= range.end().into()
+}
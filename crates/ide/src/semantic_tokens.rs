@@ -20,6 +20,7 @@ pub fn semantic_tokens(
     registry: DbFiles<'_>,
     project_files: Option<graphql_base_db::ProjectFiles>,
     file: &crate::FilePath,
+    range: Option<crate::Range>,
 ) -> Vec<SemanticToken> {
     let (content, metadata) = {
         let Some(file_id) = registry.get_file_id(file) else {
@@ -47,16 +48,23 @@ pub fn semantic_tokens(
     let mut tokens = Vec::new();
 
     for doc in parse.documents() {
-        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
+        let doc_line_index = doc.line_index();
         collect_semantic_tokens_from_document(
             &doc.tree.document(),
             &doc_line_index,
-            doc.line_offset,
+            doc.origin(),
             schema_types,
+            range,
             &mut tokens,
         );
     }
 
+    if let Some(range) = range {
+        tokens.retain(|token| {
+            token.start.line >= range.start.line && token.start.line <= range.end.line
+        });
+    }
+
     tokens.sort_by(|a, b| {
         a.start
             .line
@@ -74,13 +82,25 @@ pub fn semantic_tokens(
 fn collect_semantic_tokens_from_document(
     doc_cst: &apollo_parser::cst::Document,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     schema_types: Option<&HashMap<Arc<str>, graphql_hir::TypeDef>>,
+    range: Option<crate::Range>,
     tokens: &mut Vec<SemanticToken>,
 ) {
     use apollo_parser::cst::{self, CstNode};
 
     for definition in doc_cst.definitions() {
+        // Large schemas are requested a viewport at a time; skip definitions
+        // that can't contribute a token to it
+        if let Some(range) = range {
+            let text_range = definition.syntax().text_range();
+            let start_line = line_index.line_col(text_range.start().into()).0 as u32 + origin.line;
+            let end_line = line_index.line_col(text_range.end().into()).0 as u32 + origin.line;
+            if end_line < range.start.line || start_line > range.end.line {
+                continue;
+            }
+        }
+
         match definition {
             cst::Definition::OperationDefinition(operation) => {
                 if let Some(op_type) = operation.operation_type() {
@@ -92,7 +112,7 @@ fn collect_semantic_tokens_from_document(
                         emit_token_for_syntax_token(
                             &token,
                             line_index,
-                            line_offset,
+                            origin,
                             SemanticTokenType::Keyword,
                             SemanticTokenModifiers::NONE,
                             tokens,
@@ -118,7 +138,7 @@ fn collect_semantic_tokens_from_document(
                         Some(root_type_name),
                         schema_types,
                         line_index,
-                        line_offset,
+                        origin,
                         tokens,
                     );
                 }
@@ -128,7 +148,7 @@ fn collect_semantic_tokens_from_document(
                     emit_token_for_syntax_token(
                         &fragment_token,
                         line_index,
-                        line_offset,
+                        origin,
                         SemanticTokenType::Keyword,
                         SemanticTokenModifiers::NONE,
                         tokens,
@@ -140,7 +160,7 @@ fn collect_semantic_tokens_from_document(
                         emit_token_for_syntax_token(
                             &on_token,
                             line_index,
-                            line_offset,
+                            origin,
                             SemanticTokenType::Keyword,
                             SemanticTokenModifiers::NONE,
                             tokens,
@@ -151,7 +171,7 @@ fn collect_semantic_tokens_from_document(
                             emit_token_for_syntax_node(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                                 SemanticTokenType::Type,
                                 SemanticTokenModifiers::NONE,
                                 tokens,
@@ -172,7 +192,7 @@ fn collect_semantic_tokens_from_document(
                         type_name.as_deref(),
                         schema_types,
                         line_index,
-                        line_offset,
+                        origin,
                         tokens,
                     );
                 }
@@ -188,7 +208,7 @@ fn collect_tokens_from_selection_set(
     parent_type_name: Option<&str>,
     schema_types: Option<&HashMap<Arc<str>, graphql_hir::TypeDef>>,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     tokens: &mut Vec<SemanticToken>,
 ) {
     use apollo_parser::cst::{self, CstNode};
@@ -218,7 +238,7 @@ fn collect_tokens_from_selection_set(
                     emit_token_for_syntax_node(
                         field_name_node.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                         SemanticTokenType::Property,
                         modifiers,
                         tokens,
@@ -238,7 +258,7 @@ fn collect_tokens_from_selection_set(
                             field_return_type,
                             schema_types,
                             line_index,
-                            line_offset,
+                            origin,
                             tokens,
                         );
                     }
@@ -249,7 +269,7 @@ fn collect_tokens_from_selection_set(
                     emit_token_for_syntax_node(
                         name.syntax(),
                         line_index,
-                        line_offset,
+                        origin,
                         SemanticTokenType::Function,
                         SemanticTokenModifiers::NONE,
                         tokens,
@@ -262,7 +282,7 @@ fn collect_tokens_from_selection_set(
                         emit_token_for_syntax_token(
                             &on_token,
                             line_index,
-                            line_offset,
+                            origin,
                             SemanticTokenType::Keyword,
                             SemanticTokenModifiers::NONE,
                             tokens,
@@ -273,7 +293,7 @@ fn collect_tokens_from_selection_set(
                             emit_token_for_syntax_node(
                                 name.syntax(),
                                 line_index,
-                                line_offset,
+                                origin,
                                 SemanticTokenType::Type,
                                 SemanticTokenModifiers::NONE,
                                 tokens,
@@ -296,7 +316,7 @@ fn collect_tokens_from_selection_set(
                         type_name_ref,
                         schema_types,
                         line_index,
-                        line_offset,
+                        origin,
                         tokens,
                     );
                 }
@@ -309,17 +329,20 @@ fn collect_tokens_from_selection_set(
 fn emit_token_for_syntax_node(
     node: &apollo_parser::SyntaxNode,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     token_type: SemanticTokenType,
     modifiers: SemanticTokenModifiers,
     tokens: &mut Vec<SemanticToken>,
 ) {
-    let offset: usize = node.text_range().start().into();
-    let len: u32 = node.text_range().len().into();
+    let start: usize = node.text_range().start().into();
+    let end: usize = node.text_range().end().into();
+    // Clients count token lengths in UTF-16 code units, like columns
+    let len = line_index.utf16_len(start, end) as u32;
 
-    let (line, col) = line_index.line_col(offset);
+    let (line, col) = line_index.line_col(start);
+    let (line, col) = origin.to_file(line as u32, col as u32);
     tokens.push(SemanticToken::new(
-        Position::new(line as u32 + line_offset, col as u32),
+        Position::new(line, col),
         len,
         token_type,
         modifiers,
@@ -330,17 +353,19 @@ fn emit_token_for_syntax_node(
 fn emit_token_for_syntax_token(
     token: &apollo_parser::SyntaxToken,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
     token_type: SemanticTokenType,
     modifiers: SemanticTokenModifiers,
     tokens: &mut Vec<SemanticToken>,
 ) {
-    let offset: usize = token.text_range().start().into();
-    let len: u32 = token.text_range().len().into();
+    let start: usize = token.text_range().start().into();
+    let end: usize = token.text_range().end().into();
+    let len = line_index.utf16_len(start, end) as u32;
 
-    let (line, col) = line_index.line_col(offset);
+    let (line, col) = line_index.line_col(start);
+    let (line, col) = origin.to_file(line as u32, col as u32);
     tokens.push(SemanticToken::new(
-        Position::new(line as u32 + line_offset, col as u32),
+        Position::new(line, col),
         len,
         token_type,
         modifiers,
@@ -35,7 +35,7 @@ pub fn signature_help(
     let parse = graphql_syntax::parse(db, content, metadata);
     let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
 
-    let block_line_index = graphql_syntax::LineIndex::new(block_context.block_source);
+    let block_line_index = block_context.line_index();
     let offset = position_to_offset(&block_line_index, adjusted_position)?;
 
     // Try directive arguments first (more specific context)
@@ -24,9 +24,7 @@ pub fn walk_type_stack_to_offset(
         for selection in selection_set.selections() {
             if let cst::Selection::Field(field) = selection {
                 if let Some(nested) = field.selection_set() {
-                    let nstart: usize = nested.syntax().text_range().start().into();
-                    let nend: usize = nested.syntax().text_range().end().into();
-                    if offset >= nstart && offset <= nend {
+                    if is_inside_braces(&nested, offset) {
                         // Descend into this field's selection set
                         if let Some(field_name) = field.name().map(|n| n.text().to_string()) {
                             if let Some(parent_type) = type_stack.last().cloned() {
@@ -66,9 +64,9 @@ pub fn walk_type_stack_to_offset(
                 }
             } else if let cst::Selection::InlineFragment(inline_frag) = selection {
                 if let Some(nested) = inline_frag.selection_set() {
-                    let nstart: usize = nested.syntax().text_range().start().into();
-                    let nend: usize = nested.syntax().text_range().end().into();
-                    if offset >= nstart && offset <= nend {
+                    // The type condition narrows the scope whatever directives
+                    // and comments sit between it and the braces
+                    if is_inside_braces(&nested, offset) {
                         if let Some(type_cond) = inline_frag.type_condition() {
                             if let Some(named_type) = type_cond.named_type() {
                                 if let Some(name) = named_type.name() {
@@ -196,6 +194,8 @@ pub enum Symbol {
         directive_name: String,
         argument_name: String,
     },
+    /// An enum value in its enum's definition or extension
+    EnumValue { enum_name: String, value: String },
 }
 
 /// Find the symbol at a specific byte offset in the document
@@ -300,9 +300,7 @@ fn find_parent_field_path(
     for selection in selection_set.selections() {
         if let cst::Selection::Field(field) = selection {
             if let Some(nested) = field.selection_set() {
-                let start: usize = nested.syntax().text_range().start().into();
-                let end: usize = nested.syntax().text_range().end().into();
-                if byte_offset >= start && byte_offset <= end {
+                if is_inside_braces(&nested, byte_offset) {
                     // Cursor is inside the nested selection set, so add this field to the path
                     let field_name = field.name()?.text().to_string();
                     // The path should be [field_name] + path inside nested
@@ -323,9 +321,7 @@ fn find_parent_field_path(
             }
         } else if let cst::Selection::InlineFragment(inline_frag) = selection {
             if let Some(nested) = inline_frag.selection_set() {
-                let start: usize = nested.syntax().text_range().start().into();
-                let end: usize = nested.syntax().text_range().end().into();
-                if byte_offset >= start && byte_offset <= end {
+                if is_inside_braces(&nested, byte_offset) {
                     // For inline fragments, recurse but do NOT add to the path (fragments don't add a field)
                     if let Some(deeper_path) = find_parent_field_path(&nested, byte_offset) {
                         if best_path
@@ -353,6 +349,22 @@ fn find_parent_field_path(
     best_path
 }
 
+/// Check if the byte offset is between a nested selection set's braces.
+///
+/// The node's range runs on over the whitespace and comments after its `}`,
+/// which belong to the selections that follow, so the closing brace is the
+/// bound. A set that isn't closed yet extends to the end of its node.
+fn is_inside_braces(selection_set: &cst::SelectionSet, byte_offset: usize) -> bool {
+    let after_open = selection_set
+        .l_curly_token()
+        .is_some_and(|token| byte_offset >= usize::from(token.text_range().end()));
+    let before_close = selection_set.r_curly_token().map_or_else(
+        || byte_offset <= usize::from(selection_set.syntax().text_range().end()),
+        |token| byte_offset <= usize::from(token.text_range().start()),
+    );
+    after_open && before_close
+}
+
 /// Find the parent field's type name within a selection set (legacy wrapper)
 fn find_parent_field_type(selection_set: &cst::SelectionSet, byte_offset: usize) -> Option<String> {
     find_parent_field_path(selection_set, byte_offset).and_then(|path| path.last().cloned())
@@ -556,11 +568,33 @@ fn check_definition(definition: &cst::Definition, byte_offset: usize) -> Option<
                 })
         }
         cst::Definition::EnumTypeDefinition(enum_def) => {
-            check_type_definition_name(enum_def.name(), byte_offset).or_else(|| {
-                enum_def
-                    .directives()
-                    .and_then(|d| check_directives_for_symbol(&d, byte_offset))
-            })
+            check_type_definition_name(enum_def.name(), byte_offset)
+                .or_else(|| {
+                    enum_def
+                        .directives()
+                        .and_then(|d| check_directives_for_symbol(&d, byte_offset))
+                })
+                .or_else(|| {
+                    check_enum_values_definition(
+                        enum_def.name(),
+                        enum_def.enum_values_definition(),
+                        byte_offset,
+                    )
+                })
+        }
+        cst::Definition::EnumTypeExtension(ext) => {
+            check_type_definition_name(ext.name(), byte_offset)
+                .or_else(|| {
+                    ext.directives()
+                        .and_then(|d| check_directives_for_symbol(&d, byte_offset))
+                })
+                .or_else(|| {
+                    check_enum_values_definition(
+                        ext.name(),
+                        ext.enum_values_definition(),
+                        byte_offset,
+                    )
+                })
         }
         cst::Definition::ScalarTypeDefinition(scalar) => {
             check_type_definition_name(scalar.name(), byte_offset).or_else(|| {
@@ -680,6 +714,29 @@ fn check_input_fields_definition(
     None
 }
 
+fn check_enum_values_definition(
+    enum_name: Option<cst::Name>,
+    values: Option<cst::EnumValuesDefinition>,
+    byte_offset: usize,
+) -> Option<Symbol> {
+    for value_def in values?.enum_value_definitions() {
+        if let Some(name) = value_def.enum_value().and_then(|v| v.name()) {
+            if is_within_range(&name, byte_offset) {
+                return Some(Symbol::EnumValue {
+                    enum_name: enum_name?.text().to_string(),
+                    value: name.text().to_string(),
+                });
+            }
+        }
+        if let Some(directives) = value_def.directives() {
+            if let Some(symbol) = check_directives_for_symbol(&directives, byte_offset) {
+                return Some(symbol);
+            }
+        }
+    }
+    None
+}
+
 fn check_type_reference(ty: &cst::Type, byte_offset: usize) -> Option<Symbol> {
     match ty {
         cst::Type::NamedType(named) => {
@@ -6,7 +6,7 @@
 
 use std::collections::HashMap;
 
-use crate::helpers::{adjust_range_for_line_offset, format_type_ref, offset_range_to_range};
+use crate::helpers::{adjust_range_for_block, format_type_ref, offset_range_to_range};
 use crate::symbol::{
     extract_all_definitions, find_fragment_definition_full_range, find_operation_definition_ranges,
     find_type_definition_full_range, SymbolRanges,
@@ -16,8 +16,10 @@ use crate::DbFiles;
 
 /// Get document symbols for a file (hierarchical outline).
 ///
-/// Returns types, operations, and fragments with their fields as children.
-/// This powers the "Go to Symbol in Editor" (Cmd+Shift+O) feature.
+/// Returns types with their fields as children, and operations and
+/// fragments with their selections (fields, inline fragments and spreads)
+/// as nested children. This powers the "Go to Symbol in Editor"
+/// (Cmd+Shift+O) feature.
 pub fn document_symbols(
     db: &dyn graphql_hir::GraphQLHirDatabase,
     registry: DbFiles<'_>,
@@ -45,20 +47,27 @@ pub fn document_symbols(
     let mut symbols = Vec::new();
 
     for doc in parse.documents() {
-        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-        let doc_line_offset = doc.line_offset;
+        let doc_line_index = doc.line_index();
+        let doc_origin = doc.origin();
 
         let definitions = extract_all_definitions(doc.tree);
         let field_ranges_map = extract_all_field_ranges(doc.tree);
+        let selection_sets = extract_executable_selection_sets(doc.tree);
+        let selection_children = |def_start: usize| {
+            selection_sets
+                .get(&def_start)
+                .map(|set| selection_set_symbols(set, &doc_line_index, doc_origin))
+                .unwrap_or_default()
+        };
 
         for (name, kind, ranges) in definitions {
-            let range = adjust_range_for_line_offset(
+            let range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, ranges.def_start, ranges.def_end),
-                doc_line_offset,
+                doc_origin,
             );
-            let selection_range = adjust_range_for_line_offset(
+            let selection_range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, ranges.name_start, ranges.name_end),
-                doc_line_offset,
+                doc_origin,
             );
 
             let symbol = match kind {
@@ -68,7 +77,7 @@ pub fn document_symbols(
                         &name,
                         &field_ranges_map,
                         &doc_line_index,
-                        doc_line_offset,
+                        doc_origin,
                     );
                     let sym_kind = match kind {
                         "object" => SymbolKind::Type,
@@ -81,12 +90,15 @@ pub fn document_symbols(
                 "union" => DocumentSymbol::new(name, SymbolKind::Union, range, selection_range),
                 "enum" => DocumentSymbol::new(name, SymbolKind::Enum, range, selection_range),
                 "scalar" => DocumentSymbol::new(name, SymbolKind::Scalar, range, selection_range),
-                "query" => DocumentSymbol::new(name, SymbolKind::Query, range, selection_range),
+                "query" => DocumentSymbol::new(name, SymbolKind::Query, range, selection_range)
+                    .with_children(selection_children(ranges.def_start)),
                 "mutation" => {
                     DocumentSymbol::new(name, SymbolKind::Mutation, range, selection_range)
+                        .with_children(selection_children(ranges.def_start))
                 }
                 "subscription" => {
                     DocumentSymbol::new(name, SymbolKind::Subscription, range, selection_range)
+                        .with_children(selection_children(ranges.def_start))
                 }
                 "fragment" => {
                     let detail = structure
@@ -95,7 +107,8 @@ pub fn document_symbols(
                         .find(|f| f.name.as_ref() == name)
                         .map(|f| format!("on {}", f.type_condition));
                     let mut sym =
-                        DocumentSymbol::new(name, SymbolKind::Fragment, range, selection_range);
+                        DocumentSymbol::new(name, SymbolKind::Fragment, range, selection_range)
+                            .with_children(selection_children(ranges.def_start));
                     if let Some(d) = detail {
                         sym = sym.with_detail(d);
                     }
@@ -300,7 +313,7 @@ fn get_field_children_from_map(
     type_name: &str,
     field_ranges_map: &HashMap<String, HashMap<String, SymbolRanges>>,
     line_index: &graphql_syntax::LineIndex,
-    line_offset: u32,
+    origin: graphql_syntax::BlockOrigin,
 ) -> Vec<DocumentSymbol> {
     let Some(type_def) = structure
         .type_defs
@@ -318,13 +331,13 @@ fn get_field_children_from_map(
 
     for field in &type_def.fields {
         if let Some(ranges) = field_ranges.get(field.name.as_ref()) {
-            let range = adjust_range_for_line_offset(
+            let range = adjust_range_for_block(
                 offset_range_to_range(line_index, ranges.def_start, ranges.def_end),
-                line_offset,
+                origin,
             );
-            let selection_range = adjust_range_for_line_offset(
+            let selection_range = adjust_range_for_block(
                 offset_range_to_range(line_index, ranges.name_start, ranges.name_end),
-                line_offset,
+                origin,
             );
 
             let detail = format_type_ref(&field.type_ref);
@@ -343,6 +356,112 @@ fn get_field_children_from_map(
     children
 }
 
+/// Top-level selection sets of operations and fragments, keyed by the
+/// start offset of their definition (matching `SymbolRanges::def_start`).
+fn extract_executable_selection_sets(
+    tree: &apollo_parser::SyntaxTree,
+) -> HashMap<usize, apollo_parser::cst::SelectionSet> {
+    use apollo_parser::cst::{self, CstNode};
+
+    tree.document()
+        .definitions()
+        .filter_map(|definition| {
+            let (start, selection_set) = match &definition {
+                cst::Definition::OperationDefinition(op) => {
+                    (op.syntax().text_range().start(), op.selection_set()?)
+                }
+                cst::Definition::FragmentDefinition(frag) => {
+                    (frag.syntax().text_range().start(), frag.selection_set()?)
+                }
+                _ => return None,
+            };
+            Some((start.into(), selection_set))
+        })
+        .collect()
+}
+
+/// Outline entries for a selection set, mirroring its structure.
+///
+/// Fields are labelled with their response name (the field name is the
+/// detail when aliased) and nest their own selections. Inline fragments are
+/// labelled by type condition (`... on User`) and fragment spreads by name,
+/// so a large operation reads the same in the outline as in the source.
+fn selection_set_symbols(
+    selection_set: &apollo_parser::cst::SelectionSet,
+    line_index: &graphql_syntax::LineIndex,
+    origin: graphql_syntax::BlockOrigin,
+) -> Vec<DocumentSymbol> {
+    use apollo_parser::cst::{CstNode, Selection};
+
+    let to_range = |range: apollo_parser::TextRange| {
+        adjust_range_for_block(
+            offset_range_to_range(line_index, range.start().into(), range.end().into()),
+            origin,
+        )
+    };
+
+    selection_set
+        .selections()
+        .filter_map(|selection| match selection {
+            Selection::Field(field) => {
+                let name = field.name()?;
+                let alias = field.alias().and_then(|alias| alias.name());
+                let label = alias.as_ref().unwrap_or(&name);
+                let mut symbol = DocumentSymbol::new(
+                    label.text().to_string(),
+                    SymbolKind::Field,
+                    to_range(field.syntax().text_range()),
+                    to_range(label.syntax().text_range()),
+                );
+                if alias.is_some() {
+                    symbol = symbol.with_detail(name.text().to_string());
+                }
+                if let Some(nested) = field.selection_set() {
+                    symbol =
+                        symbol.with_children(selection_set_symbols(&nested, line_index, origin));
+                }
+                Some(symbol)
+            }
+            Selection::InlineFragment(inline) => {
+                let type_name = inline.type_condition().and_then(|tc| tc.named_type());
+                let label = type_name
+                    .as_ref()
+                    .and_then(|named| named.name())
+                    .map_or_else(
+                        || "...".to_string(),
+                        |name| format!("... on {}", name.text()),
+                    );
+                let selection_range = type_name.map_or_else(
+                    || inline.syntax().text_range(),
+                    |named| named.syntax().text_range(),
+                );
+                let children = inline
+                    .selection_set()
+                    .map(|nested| selection_set_symbols(&nested, line_index, origin))
+                    .unwrap_or_default();
+                Some(
+                    DocumentSymbol::new(
+                        label,
+                        SymbolKind::Fragment,
+                        to_range(inline.syntax().text_range()),
+                        to_range(selection_range),
+                    )
+                    .with_children(children),
+                )
+            }
+            Selection::FragmentSpread(spread) => {
+                let name = spread.fragment_name()?.name()?;
+                Some(DocumentSymbol::new(
+                    format!("...{}", name.text()),
+                    SymbolKind::Fragment,
+                    to_range(spread.syntax().text_range()),
+                    to_range(name.syntax().text_range()),
+                ))
+            }
+        })
+        .collect()
+}
+
 /// Get location for a type definition.
 fn get_type_location(
     db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
@@ -357,10 +476,10 @@ fn get_type_location(
 
     for doc in parse.documents() {
         if let Some(ranges) = find_type_definition_full_range(doc.tree, &type_def.name) {
-            let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-            let range = adjust_range_for_line_offset(
+            let doc_line_index = doc.line_index();
+            let range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, ranges.name_start, ranges.name_end),
-                doc.line_offset,
+                doc.origin(),
             );
             return Some(Location::new(file_path, range));
         }
@@ -383,10 +502,10 @@ fn get_fragment_location(
 
     for doc in parse.documents() {
         if let Some(ranges) = find_fragment_definition_full_range(doc.tree, &fragment.name) {
-            let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-            let range = adjust_range_for_line_offset(
+            let doc_line_index = doc.line_index();
+            let range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, ranges.name_start, ranges.name_end),
-                doc.line_offset,
+                doc.origin(),
             );
             return Some(Location::new(file_path, range));
         }
@@ -411,10 +530,10 @@ fn get_operation_location(
 
     for doc in parse.documents() {
         if let Some(ranges) = find_operation_definition_ranges(doc.tree, op_name) {
-            let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
-            let range = adjust_range_for_line_offset(
+            let doc_line_index = doc.line_index();
+            let range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, ranges.name_start, ranges.name_end),
-                doc.line_offset,
+                doc.origin(),
             );
             return Some(Location::new(file_path, range));
         }
@@ -437,15 +556,15 @@ fn get_directive_location(
 
     // Find the directive definition in the CST to get proper line offset context
     for doc in parse.documents() {
-        let doc_line_index = graphql_syntax::LineIndex::new(doc.source);
+        let doc_line_index = doc.line_index();
         let start: usize = directive.name_range.start().into();
         let end: usize = directive.name_range.end().into();
 
         // Check if this range falls within this document's source
         if start <= doc.source.len() {
-            let range = adjust_range_for_line_offset(
+            let range = adjust_range_for_block(
                 offset_range_to_range(&doc_line_index, start, end),
-                doc.line_offset,
+                doc.origin(),
             );
             return Some(Location::new(file_path, range));
         }
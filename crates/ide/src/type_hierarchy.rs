@@ -0,0 +1,144 @@
+//! Type hierarchy feature implementation.
+//!
+//! GraphQL's subtype relation comes from two places: `implements` clauses
+//! and union membership. An object type's supertypes are the interfaces it
+//! implements plus the unions that list it; an interface's subtypes are its
+//! implementors, and a union's subtypes are its members.
+
+use graphql_hir::{TypeDef, TypeDefKind};
+
+use crate::helpers::{
+    adjust_range_for_block, find_block_for_position, offset_range_to_range, position_to_offset,
+};
+use crate::symbol::{find_symbol_at_offset, find_type_definition_full_range, Symbol};
+use crate::types::{FilePath, Position, SymbolKind, TypeHierarchyItem};
+use crate::DbFiles;
+
+/// Resolve the type under the cursor to a hierarchy root.
+///
+/// Works on definitions and on any type reference (field types, fragment
+/// type conditions, `implements` clauses). Returns `None` for scalars,
+/// enums and input objects, which can't take part in a hierarchy.
+pub fn prepare_type_hierarchy(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    file: &FilePath,
+    position: Position,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let file_id = registry.get_file_id(file)?;
+    let content = registry.get_content(file_id)?;
+    let metadata = registry.get_metadata(file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    let (block_context, adjusted_position) = find_block_for_position(&parse, position)?;
+    let offset = position_to_offset(&block_context.line_index(), adjusted_position)?;
+    let Some(Symbol::TypeName { name }) = find_symbol_at_offset(block_context.tree, offset) else {
+        return None;
+    };
+
+    let types = graphql_hir::schema_types(db, project_files?);
+    let item = hierarchy_item(db, registry, types.get(name.as_str())?)?;
+    Some(vec![item])
+}
+
+/// Interfaces implemented by `type_name` and unions that contain it.
+pub fn supertypes(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    type_name: &str,
+) -> Vec<TypeHierarchyItem> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+    let types = graphql_hir::schema_types(db, project_files);
+    let Some(type_def) = types.get(type_name) else {
+        return Vec::new();
+    };
+
+    let unions = graphql_hir::union_memberships(db, project_files);
+    let mut names: Vec<_> = type_def
+        .implements
+        .iter()
+        .chain(unions.get(type_name).into_iter().flatten())
+        .collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| hierarchy_item(db, registry, types.get(name)?))
+        .collect()
+}
+
+/// Implementors of an interface, or members of a union. Object types have
+/// no subtypes.
+pub fn subtypes(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    project_files: Option<graphql_base_db::ProjectFiles>,
+    type_name: &str,
+) -> Vec<TypeHierarchyItem> {
+    let Some(project_files) = project_files else {
+        return Vec::new();
+    };
+    let types = graphql_hir::schema_types(db, project_files);
+    let Some(type_def) = types.get(type_name) else {
+        return Vec::new();
+    };
+
+    let implementors = graphql_hir::interface_implementors(db, project_files);
+    let mut names: Vec<_> = match type_def.kind {
+        TypeDefKind::Interface => implementors
+            .get(type_name)
+            .map(|names| names.iter().collect())
+            .unwrap_or_default(),
+        TypeDefKind::Union => type_def.union_members.iter().collect(),
+        _ => Vec::new(),
+    };
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| hierarchy_item(db, registry, types.get(name)?))
+        .collect()
+}
+
+fn hierarchy_item(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    registry: DbFiles<'_>,
+    type_def: &TypeDef,
+) -> Option<TypeHierarchyItem> {
+    let (kind, detail) = match type_def.kind {
+        TypeDefKind::Object => (SymbolKind::Type, "type"),
+        TypeDefKind::Interface => (SymbolKind::Interface, "interface"),
+        TypeDefKind::Union => (SymbolKind::Union, "union"),
+        _ => return None,
+    };
+
+    let file = registry.get_path(type_def.file_id)?;
+    let content = registry.get_content(type_def.file_id)?;
+    let metadata = registry.get_metadata(type_def.file_id)?;
+    let parse = graphql_syntax::parse(db, content, metadata);
+
+    parse.documents().find_map(|doc| {
+        let ranges = find_type_definition_full_range(doc.tree, &type_def.name)?;
+        let line_index = doc.line_index();
+        Some(TypeHierarchyItem {
+            name: type_def.name.to_string(),
+            kind,
+            detail: Some(detail.to_string()),
+            file: file.clone(),
+            range: adjust_range_for_block(
+                offset_range_to_range(&line_index, ranges.def_start, ranges.def_end),
+                doc.origin(),
+            ),
+            selection_range: adjust_range_for_block(
+                offset_range_to_range(&line_index, ranges.name_start, ranges.name_end),
+                doc.origin(),
+            ),
+        })
+    })
+}
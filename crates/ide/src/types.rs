@@ -150,6 +150,15 @@ impl Location {
     }
 }
 
+/// A span in a document that links to a definition elsewhere
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentLink {
+    /// The linked text (a fragment spread or type name)
+    pub range: Range,
+    /// Where the link points
+    pub target: Location,
+}
+
 /// Completion item kind
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CompletionKind {
@@ -161,6 +170,8 @@ pub enum CompletionKind {
     Argument,
     Variable,
     Keyword,
+    /// Multi-token template such as an operation scaffold
+    Snippet,
 }
 
 /// Insert text format for completion items
@@ -234,6 +245,114 @@ impl CompletionItem {
     }
 }
 
+/// Generated selection set for the field under the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectionSkeleton {
+    /// Range to replace: empty right after the field, or its empty `{}`
+    pub range: Range,
+    /// Snippet text, starting with the opening brace
+    pub snippet: String,
+}
+
+/// The analyzer's view of a cursor position, for debugging editor features
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorContext {
+    pub language: graphql_base_db::Language,
+    pub document_kind: graphql_base_db::DocumentKind,
+    /// Whether the file belongs to a project with a schema to resolve against
+    pub has_project: bool,
+    /// Number of GraphQL blocks found in the file
+    pub block_count: usize,
+    /// Why extraction skipped the file, if it did
+    pub extraction_skipped: Option<String>,
+    /// The block the cursor falls in; `None` outside every block
+    pub block: Option<CursorBlock>,
+    /// Keyword and name of the enclosing definition, e.g. `query GetUser`
+    pub enclosing_definition: Option<String>,
+    /// Symbol under the cursor, as hover, goto definition and references see it
+    pub symbol: Option<String>,
+    /// Type whose fields are selectable at the cursor
+    pub parent_type: Option<String>,
+    /// Input type expected at the cursor inside an argument value
+    pub expected_input_type: Option<String>,
+    /// The branch completion takes at the cursor, e.g. `field` or `argument`
+    pub completion_path: &'static str,
+}
+
+/// The GraphQL block a cursor position maps into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorBlock {
+    /// Index of the block among the file's blocks
+    pub index: usize,
+    /// Byte offset of the block in the file
+    pub file_offset: usize,
+    /// Where the block starts in the file
+    pub start: Position,
+    /// Length of the block in bytes
+    pub length: usize,
+    /// Cursor position relative to the block
+    pub position: Position,
+    /// Cursor byte offset in the block; `None` if the position is past its end
+    pub offset: Option<usize>,
+}
+
+/// Structured description of a cursor position, for clients building their
+/// own UI on top of the analyzer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionContext {
+    /// The top-level definition containing the cursor
+    pub definition: Option<CursorDefinition>,
+    /// Type whose fields are selectable at the cursor
+    pub parent_type: Option<String>,
+    /// The innermost field containing the cursor: the one under it, or the
+    /// one whose arguments or selection set it's in
+    pub field: Option<CursorField>,
+    /// The argument list the cursor is in
+    pub argument: Option<CursorArgument>,
+    /// Input type expected at the cursor inside an argument value
+    pub expected_input_type: Option<String>,
+}
+
+/// A top-level definition containing the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorDefinition {
+    /// `kind` is `query`, `mutation` or `subscription`
+    Operation { kind: String, name: Option<String> },
+    Fragment {
+        name: Option<String>,
+        type_condition: Option<String>,
+    },
+    /// Any other definition; `keyword` is e.g. `type` or `directive`
+    TypeSystem {
+        keyword: String,
+        name: Option<String>,
+    },
+}
+
+/// A selected field containing the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorField {
+    pub name: String,
+    pub alias: Option<String>,
+    /// Type declaring the field, if it resolves against the schema
+    pub parent_type: Option<String>,
+}
+
+/// An argument list containing the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CursorArgument {
+    pub owner: ArgumentOwner,
+    /// The argument whose value the cursor is in; `None` between arguments
+    pub name: Option<String>,
+}
+
+/// What an argument list belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentOwner {
+    Field(String),
+    Directive(String),
+}
+
 /// Hover information
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HoverResult {
@@ -298,6 +417,16 @@ pub struct Diagnostic {
     pub url: Option<String>,
     /// Diagnostic tags for additional classification
     pub tags: Vec<DiagnosticTag>,
+    /// Other locations involved in the issue, e.g. the other definitions
+    /// of a duplicated name
+    pub related: Vec<RelatedInformation>,
+}
+
+/// A location related to a diagnostic, with a short explanation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedInformation {
+    pub location: Location,
+    pub message: String,
 }
 
 impl Diagnostic {
@@ -319,6 +448,7 @@ impl Diagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -467,6 +597,55 @@ impl WorkspaceSymbol {
     }
 }
 
+/// An object, interface or union type in a type hierarchy view
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHierarchyItem {
+    /// Type name
+    pub name: String,
+    /// Symbol kind (`Type`, `Interface` or `Union`)
+    pub kind: SymbolKind,
+    /// Short description shown next to the name
+    pub detail: Option<String>,
+    /// File containing the type definition
+    pub file: FilePath,
+    /// Range of the whole definition
+    pub range: Range,
+    /// Range of the type name
+    pub selection_range: Range,
+}
+
+/// Calls recorded in the operation usage report, for an operation or summed
+/// over the operations selecting a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordedCalls {
+    pub count: u64,
+    /// Length of the report's window in days
+    pub window_days: u32,
+}
+
+impl From<graphql_hir::OperationCalls> for RecordedCalls {
+    fn from(calls: graphql_hir::OperationCalls) -> Self {
+        Self {
+            count: calls.count,
+            window_days: calls.window_days,
+        }
+    }
+}
+
+impl std::fmt::Display for RecordedCalls {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.count {
+            0 => write!(f, "not called in the last {} days", self.window_days),
+            1 => write!(f, "called once in the last {} days", self.window_days),
+            count => write!(
+                f,
+                "called {count} times in the last {} days",
+                self.window_days
+            ),
+        }
+    }
+}
+
 /// Code lens information for a deprecated field
 ///
 /// Used to show usage counts for deprecated fields in schema files.
@@ -486,6 +665,9 @@ pub struct CodeLensInfo {
     pub deprecation_reason: Option<String>,
     /// Locations of all usages (for navigation)
     pub usage_locations: Vec<Location>,
+    /// Traffic through the operations selecting this field, when an
+    /// operation usage report is loaded
+    pub recorded_calls: Option<RecordedCalls>,
 }
 
 impl CodeLensInfo {
@@ -505,6 +687,7 @@ impl CodeLensInfo {
             field_name: field_name.into(),
             deprecation_reason: None,
             usage_locations,
+            recorded_calls: None,
         }
     }
 
@@ -514,6 +697,80 @@ impl CodeLensInfo {
         self.deprecation_reason = Some(reason.into());
         self
     }
+
+    /// Add the calls recorded for operations selecting the field
+    #[must_use]
+    pub fn with_recorded_calls(mut self, calls: RecordedCalls) -> Self {
+        self.recorded_calls = Some(calls);
+        self
+    }
+}
+
+/// Code lens information for a schema type definition
+///
+/// Shows how many operations select at least one field of the type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeUsageLensInfo {
+    /// Range where the code lens should appear (type name range)
+    pub range: Range,
+    /// The type name
+    pub type_name: String,
+    /// Locations of the operations selecting the type's fields
+    pub operation_locations: Vec<Location>,
+}
+
+impl TypeUsageLensInfo {
+    /// Number of operations selecting the type's fields
+    #[must_use]
+    pub fn operation_count(&self) -> usize {
+        self.operation_locations.len()
+    }
+}
+
+/// The operations that depend on a schema field, and would break if it
+/// were removed or its type changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldImpact {
+    pub type_name: String,
+    pub field_name: String,
+    /// Operations selecting the field, directly or through fragments
+    pub operations: Vec<ImpactedOperation>,
+}
+
+impl FieldImpact {
+    #[must_use]
+    pub fn operation_count(&self) -> usize {
+        self.operations.len()
+    }
+}
+
+/// An operation in a [`FieldImpact`] report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImpactedOperation {
+    /// `None` for an anonymous operation
+    pub name: Option<String>,
+    /// The operation's name, or the whole operation when it's anonymous
+    pub location: Location,
+}
+
+/// Code lens information for a schema field
+///
+/// Shows how many operations depend on the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldImpactLensInfo {
+    /// Range where the code lens should appear (field name range)
+    pub range: Range,
+    pub impact: FieldImpact,
+}
+
+/// Code lens information for copying an operation with its fragments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationCopyLensInfo {
+    /// Range where the code lens should appear (start of the operation)
+    pub range: Range,
+    /// Index of the operation among those in the file, as taken by
+    /// [`Analysis::operation_with_fragments`](crate::Analysis::operation_with_fragments)
+    pub operation_index: usize,
 }
 
 /// Statistics about schema types
@@ -557,6 +814,8 @@ pub enum FoldingRangeKind {
     Region,
     /// Folding range for a comment
     Comment,
+    /// Folding range for a run of `#import` lines
+    Imports,
 }
 
 /// A folding range in a document
@@ -582,6 +841,31 @@ impl FoldingRange {
     }
 }
 
+/// How a highlighted occurrence uses its symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentHighlightKind {
+    /// Neither reads nor writes, e.g. fields sharing a response key
+    Text,
+    /// A usage, e.g. a variable passed as an argument or a fragment spread
+    Read,
+    /// A definition, e.g. a variable or fragment definition
+    Write,
+}
+
+/// An occurrence of the symbol under the cursor in the same file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentHighlight {
+    pub range: Range,
+    pub kind: DocumentHighlightKind,
+}
+
+impl DocumentHighlight {
+    #[must_use]
+    pub const fn new(range: Range, kind: DocumentHighlightKind) -> Self {
+        Self { range, kind }
+    }
+}
+
 /// A reference to a fragment spread
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FragmentReference {
@@ -798,7 +1082,8 @@ pub struct SchemaContentError {
 /// This represents a remote GraphQL endpoint that should be introspected
 /// to fetch its schema. The caller is responsible for performing the async
 /// introspection and registering the resulting SDL as a virtual file.
-#[derive(Debug, Clone)]
+/// `Debug` output leaves out header values.
+#[derive(Clone)]
 pub struct PendingIntrospection {
     /// The GraphQL endpoint URL to introspect
     pub url: String,
@@ -808,6 +1093,26 @@ pub struct PendingIntrospection {
     pub timeout: Option<u64>,
     /// Number of retry attempts on failure (default: 0)
     pub retry: Option<u32>,
+    /// Whether the URL holds a value interpolated from an environment
+    /// variable, which sending the request would disclose to the endpoint
+    pub url_from_env: bool,
+}
+
+impl std::fmt::Debug for PendingIntrospection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PendingIntrospection")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self.headers.as_ref().map(|headers| {
+                    graphql_config::RedactedHeaders::new(headers.keys().map(String::as_str))
+                }),
+            )
+            .field("timeout", &self.timeout)
+            .field("retry", &self.retry)
+            .field("url_from_env", &self.url_from_env)
+            .finish()
+    }
 }
 
 impl PendingIntrospection {
@@ -819,6 +1124,7 @@ impl PendingIntrospection {
             headers: config.headers.clone(),
             timeout: config.timeout,
             retry: config.retry,
+            url_from_env: false,
         }
     }
 
@@ -886,6 +1192,147 @@ impl RenameResult {
     }
 }
 
+/// Schema renames to carry client documents across, keyed by old name.
+///
+/// Field and enum value keys are schema coordinates on the *old* type name
+/// (`User.fullName`, `Status.ACTIVE`); their values are the new bare
+/// member name. Input object fields use the same `fields` map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaChangeManifest {
+    pub types: std::collections::HashMap<String, String>,
+    pub fields: std::collections::HashMap<String, String>,
+    pub enum_values: std::collections::HashMap<String, String>,
+}
+
+impl SchemaChangeManifest {
+    /// Whether the manifest renames nothing
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.fields.is_empty() && self.enum_values.is_empty()
+    }
+}
+
+/// A usage that a schema migration couldn't rewrite on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmigratedUsage {
+    pub location: Location,
+    pub message: String,
+}
+
+/// Edits that migrate project documents across a [`SchemaChangeManifest`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationResult {
+    pub changes: std::collections::HashMap<FilePath, Vec<TextEdit>>,
+    /// Usages that need a manual follow-up, e.g. enum values supplied
+    /// through variables at runtime
+    pub unmigrated: Vec<UnmigratedUsage>,
+}
+
+/// Identifies a diagnostic by its file, code and range, which together are
+/// stable for as long as the file is unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiagnosticId {
+    pub file: FilePath,
+    /// Lint rule or validation code of the diagnostic
+    pub code: String,
+    pub range: Range,
+}
+
+/// The edits a diagnostic's fix would make, not yet applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixPreview {
+    pub label: String,
+    pub changes: std::collections::HashMap<FilePath, Vec<TextEdit>>,
+}
+
+/// The fixes for one rule across the project, merged into a single set of
+/// edits. Nothing is applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FixAllResult {
+    pub changes: std::collections::HashMap<FilePath, Vec<TextEdit>>,
+    /// Number of fixes included in `changes`
+    pub applied: usize,
+    /// Diagnostics whose fix overlaps one already included. Running the
+    /// fix again once `changes` is applied picks them up.
+    pub skipped: Vec<DiagnosticId>,
+}
+
+/// The kind of schema element a [`DeprecatedUsage`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeprecatedElementKind {
+    Field,
+    Argument,
+    EnumValue,
+    InputField,
+}
+
+/// A use of a deprecated schema element in a project document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedUsage {
+    /// Location of the element's name
+    pub location: Location,
+    pub kind: DeprecatedElementKind,
+    pub name: String,
+    /// Schema coordinate of the element, e.g. `User.username` or
+    /// `Query.users(status:)`
+    pub coordinate: String,
+    pub reason: Option<String>,
+}
+
+/// A list field selected inside another list without pagination, which can
+/// resolve one backend query per item of the outer list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NPlusOneSelection {
+    /// Location of the nested field's name, or of the fragment spread that
+    /// selects it
+    pub location: Location,
+    /// The operation or fragment containing the selection; `None` for an
+    /// anonymous operation
+    pub definition: Option<String>,
+    /// Path of the enclosing list field, e.g. `users`
+    pub list_path: String,
+    /// Path of the nested list field, e.g. `users.posts`
+    pub nested_path: String,
+    /// Item type of the nested list
+    pub nested_type: String,
+    /// The fragment spread the nested list is selected through, if any
+    pub fragment: Option<String>,
+}
+
+/// A fragment created from a group of duplicated selection sets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoistedFragment {
+    pub name: String,
+    pub type_name: String,
+    /// Number of selection sets replaced by a spread of this fragment
+    pub occurrences: usize,
+}
+
+/// Edits that hoist duplicated selection sets into shared fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrganizeFragmentsResult {
+    pub fragments: Vec<HoistedFragment>,
+    /// The file the new fragments are written to
+    pub fragment_file: FilePath,
+    /// Content for `fragment_file` when it isn't a project document yet.
+    /// Otherwise the fragments are appended through `changes`.
+    pub new_file_content: Option<String>,
+    pub changes: std::collections::HashMap<FilePath, Vec<TextEdit>>,
+}
+
+/// A kind of schema definition, for grouping definitions when organizing a
+/// schema file with [`crate::Analysis::organize_schema`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SchemaGroup {
+    Directives,
+    Scalars,
+    Enums,
+    Inputs,
+    Interfaces,
+    Objects,
+    Unions,
+}
+
 /// Signature help result for displaying argument information.
 ///
 /// Shows the signature of a field or directive when the cursor is inside
@@ -1361,6 +1808,13 @@ pub struct OperationSummary {
     pub fragment_dependencies: Vec<String>,
 }
 
+/// An operation found by name, with where its name is defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationLocation {
+    pub location: Location,
+    pub summary: OperationSummary,
+}
+
 /// A variable defined on an operation
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OperationVariableInfo {
@@ -1369,6 +1823,167 @@ pub struct OperationVariableInfo {
     pub default_value: Option<String>,
 }
 
+/// A change reported by a schema registry check
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChangeFinding {
+    /// Schema coordinate of the changed element, e.g. `User.email`
+    pub coordinate: Option<String>,
+    pub message: String,
+    pub severity: DiagnosticSeverity,
+}
+
+/// Schema registry findings placed on the local schema definitions they
+/// concern
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaChangeDiagnostics {
+    pub diagnostics: std::collections::HashMap<FilePath, Vec<Diagnostic>>,
+    /// Findings with no local definition to attach to, such as a removed type
+    pub unlocated: Vec<SchemaChangeFinding>,
+}
+
+/// TypeScript types generated for one operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationTypes {
+    pub operation_name: String,
+    /// Name of the result type, e.g. `GetUserQuery`
+    pub result_type: String,
+    /// Name of the variables type, e.g. `GetUserQueryVariables`
+    pub variables_type: String,
+    /// Both declarations, variables first
+    pub code: String,
+    /// Range of the operation definition, for placing the types next to it
+    pub range: Range,
+}
+
+/// A schema subset reachable from a set of operations, printed as SDL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrunedSchema {
+    /// SDL for the reachable types, root types first
+    pub sdl: String,
+    /// Number of types in the pruned schema
+    pub type_count: usize,
+    /// Operations that contributed to the subset (`<anonymous>` for unnamed ones)
+    pub operations: Vec<String>,
+    /// Requested operation names that don't exist in the project
+    pub unknown_operations: Vec<String>,
+}
+
+/// Output format for [`crate::Analysis::usage_report`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageReportFormat {
+    /// Machine-readable, keyed by `Type.field`
+    Json,
+    /// A table per type
+    Markdown,
+}
+
+/// Output format for [`crate::Analysis::generate_docs`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocsFormat {
+    /// A heading per type, for a repository or a Markdown-based docs site
+    #[default]
+    Markdown,
+    /// A standalone page with no external assets
+    Html,
+}
+
+/// Options for [`crate::Analysis::generate_docs`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocsOptions {
+    pub format: DocsFormat,
+    /// Top-level heading, and the page title in HTML
+    pub title: String,
+    /// List deprecated fields, arguments and enum values, marked as such
+    pub include_deprecated: bool,
+}
+
+impl Default for DocsOptions {
+    fn default() -> Self {
+        Self {
+            format: DocsFormat::default(),
+            title: "Schema".to_string(),
+            include_deprecated: true,
+        }
+    }
+}
+
+/// Operations, the fragments they spread and the schema types they touch
+///
+/// Nodes are sorted by id and edges by endpoints, so exports are stable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OperationGraph {
+    pub nodes: Vec<OperationGraphNode>,
+    pub edges: Vec<OperationGraphEdge>,
+}
+
+/// An operation, fragment or schema type in an [`OperationGraph`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationGraphNode {
+    /// Unique id, prefixed with the kind: `operation:GetUser`,
+    /// `fragment:UserFields`, `type:User`
+    pub id: String,
+    pub kind: OperationGraphNodeKind,
+    /// Display name (`<anonymous>` for unnamed operations)
+    pub label: String,
+    /// Defining file, for operations and fragments
+    pub file: Option<FilePath>,
+}
+
+/// Kind of [`OperationGraphNode`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OperationGraphNodeKind {
+    Operation,
+    Fragment,
+    Type,
+}
+
+/// A directed edge between two [`OperationGraphNode`] ids
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OperationGraphEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: OperationGraphEdgeKind,
+}
+
+/// How the source of an [`OperationGraphEdge`] depends on its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OperationGraphEdgeKind {
+    /// An operation or fragment spreads a fragment
+    Spreads,
+    /// An operation selects fields on or returning a type, or a fragment
+    /// is defined on it
+    Touches,
+}
+
+/// How often a query ran or had its cached result reused while metrics were on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryMetrics {
+    /// Times the query body executed
+    pub executed: usize,
+    /// Times a memoized result was revalidated and reused without executing
+    pub reused: usize,
+}
+
+/// Estimated memory held by parsed files.
+///
+/// Byte counts are estimates derived from source sizes, not measurements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryMetrics {
+    /// Files in the project
+    pub files: usize,
+    /// Total source size of those files
+    pub source_bytes: usize,
+    /// How many of the files are documents
+    pub documents: usize,
+    /// Parse results kept before the least recently used is evicted, or
+    /// `None` when lazy parsing is off and every parse is kept
+    pub parse_cache_capacity: Option<usize>,
+    /// The configured budget, when lazy parsing is on
+    pub budget_bytes: Option<usize>,
+    /// Estimated size of the parse results currently retained
+    pub estimated_parse_bytes: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1511,6 +2126,7 @@ mod tests {
                 headers: None,
                 timeout: None,
                 retry: None,
+                url_from_env: false,
             }],
             content_errors: vec![],
             unmatched_patterns: vec![],
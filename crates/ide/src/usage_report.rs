@@ -0,0 +1,166 @@
+//! Schema usage report.
+//!
+//! Combines field coverage with the schema's type definitions into a heatmap
+//! of how many operations select each output field, for documentation
+//! tooling. The JSON form is keyed by `Type.field`; the Markdown form has a
+//! table per type. Types and fields are sorted by name so reports diff
+//! cleanly.
+
+use std::fmt::Write as _;
+
+use crate::helpers::format_type_ref;
+use crate::types::UsageReportFormat;
+
+struct TypeUsage {
+    name: String,
+    kind: &'static str,
+    used_fields: usize,
+    fields: Vec<FieldUsageRow>,
+}
+
+struct FieldUsageRow {
+    name: String,
+    return_type: String,
+    deprecated: bool,
+    operations: Vec<String>,
+}
+
+/// Render the usage report for every object and interface type.
+pub(crate) fn usage_report(
+    db: &dyn graphql_analysis::GraphQLAnalysisDatabase,
+    project_files: graphql_base_db::ProjectFiles,
+    format: UsageReportFormat,
+) -> String {
+    let schema = graphql_hir::schema_types(db, project_files);
+    let coverage = graphql_analysis::analyze_field_usage(db, project_files);
+
+    let mut types: Vec<TypeUsage> = schema
+        .iter()
+        .filter_map(|(type_name, type_def)| {
+            let kind = match type_def.kind {
+                graphql_hir::TypeDefKind::Object => "object",
+                graphql_hir::TypeDefKind::Interface => "interface",
+                _ => return None,
+            };
+            let mut fields: Vec<FieldUsageRow> = type_def
+                .fields
+                .iter()
+                .map(|field| {
+                    let mut operations: Vec<String> = coverage
+                        .field_usages
+                        .get(&(type_name.clone(), field.name.clone()))
+                        .map(|usage| usage.operations.iter().map(ToString::to_string).collect())
+                        .unwrap_or_default();
+                    operations.sort();
+                    FieldUsageRow {
+                        name: field.name.to_string(),
+                        return_type: format_type_ref(&field.type_ref),
+                        deprecated: field.is_deprecated,
+                        operations,
+                    }
+                })
+                .collect();
+            fields.sort_by(|a, b| a.name.cmp(&b.name));
+            Some(TypeUsage {
+                name: type_name.to_string(),
+                kind,
+                used_fields: fields.iter().filter(|f| !f.operations.is_empty()).count(),
+                fields,
+            })
+        })
+        .collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    match format {
+        UsageReportFormat::Json => to_json(&types, coverage.total_fields, coverage.used_fields),
+        UsageReportFormat::Markdown => {
+            to_markdown(&types, coverage.total_fields, coverage.used_fields)
+        }
+    }
+}
+
+fn to_json(types: &[TypeUsage], total_fields: usize, used_fields: usize) -> String {
+    let mut type_entries = serde_json::Map::new();
+    let mut field_entries = serde_json::Map::new();
+    for type_usage in types {
+        type_entries.insert(
+            type_usage.name.clone(),
+            serde_json::json!({
+                "kind": type_usage.kind,
+                "totalFields": type_usage.fields.len(),
+                "usedFields": type_usage.used_fields,
+            }),
+        );
+        for field in &type_usage.fields {
+            field_entries.insert(
+                format!("{}.{}", type_usage.name, field.name),
+                serde_json::json!({
+                    "type": type_usage.name,
+                    "field": field.name,
+                    "returnType": field.return_type,
+                    "deprecated": field.deprecated,
+                    "usageCount": field.operations.len(),
+                    "operations": field.operations,
+                }),
+            );
+        }
+    }
+    let report = serde_json::json!({
+        "totalFields": total_fields,
+        "usedFields": used_fields,
+        "coverage": percentage(used_fields, total_fields),
+        "types": type_entries,
+        "fields": field_entries,
+    });
+    serde_json::to_string_pretty(&report).unwrap_or_default()
+}
+
+fn to_markdown(types: &[TypeUsage], total_fields: usize, used_fields: usize) -> String {
+    let mut markdown = String::from("# Schema usage\n\n");
+    let _ = writeln!(
+        markdown,
+        "{used_fields} of {total_fields} fields ({:.1}%) are selected by at least one operation.",
+        percentage(used_fields, total_fields)
+    );
+    for type_usage in types {
+        let _ = write!(
+            markdown,
+            "\n## {}\n\n{} of {} fields used ({:.1}%)\n\n",
+            type_usage.name,
+            type_usage.used_fields,
+            type_usage.fields.len(),
+            percentage(type_usage.used_fields, type_usage.fields.len())
+        );
+        markdown.push_str("| Field | Type | Operations | Used by |\n");
+        markdown.push_str("| --- | --- | ---: | --- |\n");
+        for field in &type_usage.fields {
+            let deprecated = if field.deprecated {
+                " _(deprecated)_"
+            } else {
+                ""
+            };
+            let used_by = if field.operations.is_empty() {
+                "—".to_string()
+            } else {
+                field.operations.join(", ")
+            };
+            let _ = writeln!(
+                markdown,
+                "| `{}`{deprecated} | `{}` | {} | {used_by} |",
+                field.name,
+                field.return_type,
+                field.operations.len()
+            );
+        }
+    }
+    markdown
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(used: usize, total: usize) -> f64 {
+    if total == 0 {
+        100.0
+    } else {
+        used as f64 / total as f64 * 100.0
+    }
+}
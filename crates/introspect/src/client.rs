@@ -3,6 +3,7 @@
 //! This module provides a flexible client for executing introspection queries
 //! with support for custom headers, timeouts, and retry logic.
 
+use crate::query::LEGACY_INTROSPECTION_QUERY;
 use crate::{IntrospectionError, IntrospectionResponse, Result, INTROSPECTION_QUERY};
 use std::collections::HashMap;
 use std::time::Duration;
@@ -67,7 +68,7 @@ const DEFAULT_RETRIES: u32 = 0;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct IntrospectionClient {
     headers: HashMap<String, String>,
     timeout: Duration,
@@ -75,6 +76,20 @@ pub struct IntrospectionClient {
     retries: u32,
 }
 
+// Header values usually carry credentials, so only their names are shown
+impl std::fmt::Debug for IntrospectionClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut header_names: Vec<&str> = self.headers.keys().map(String::as_str).collect();
+        header_names.sort_unstable();
+        f.debug_struct("IntrospectionClient")
+            .field("headers", &header_names)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("retries", &self.retries)
+            .finish()
+    }
+}
+
 impl Default for IntrospectionClient {
     fn default() -> Self {
         Self::new()
@@ -207,7 +222,7 @@ impl IntrospectionClient {
                 tokio::time::sleep(delay).await;
             }
 
-            match self.execute_once(url).await {
+            match self.execute_with_fallback(url).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     tracing::warn!(attempt, error = %e, "Request failed");
@@ -227,8 +242,39 @@ impl IntrospectionClient {
             .unwrap_or_else(|| IntrospectionError::Network("No attempts made".to_string())))
     }
 
+    /// Executes a single introspection request, falling back to the legacy
+    /// query if the server rejects the full one.
+    async fn execute_with_fallback(&self, url: &str) -> Result<IntrospectionResponse> {
+        match self.execute_once(url, INTROSPECTION_QUERY).await {
+            Err(IntrospectionError::Rejected(reason)) => {
+                tracing::info!(%reason, "Retrying with the legacy introspection query");
+                self.execute_once(url, LEGACY_INTROSPECTION_QUERY).await
+            }
+            result => result,
+        }
+    }
+
     /// Executes a single introspection request without retry logic.
-    async fn execute_once(&self, url: &str) -> Result<IntrospectionResponse> {
+    async fn execute_once(&self, url: &str, query: &str) -> Result<IntrospectionResponse> {
+        let json = self.post(url, query).await?;
+
+        tracing::debug!("Parsing introspection response");
+        let introspection: IntrospectionResponse = serde_json::from_value(json).map_err(|e| {
+            tracing::error!(error = %e, "Failed to parse introspection response");
+            IntrospectionError::Parse(e.to_string())
+        })?;
+
+        tracing::info!(
+            types = introspection.data.schema.types.len(),
+            directives = introspection.data.schema.directives.len(),
+            "Introspection successful"
+        );
+
+        Ok(introspection)
+    }
+
+    /// Sends `query` to `url` and returns the JSON response body.
+    async fn post(&self, url: &str, query: &str) -> Result<serde_json::Value> {
         tracing::debug!("Creating HTTP client with timeouts");
         let builder = reqwest::Client::builder();
         #[cfg(not(target_arch = "wasm32"))]
@@ -240,7 +286,7 @@ impl IntrospectionClient {
         })?;
 
         let query_body = serde_json::json!({
-            "query": INTROSPECTION_QUERY
+            "query": query
         });
 
         tracing::info!("Sending introspection query");
@@ -260,36 +306,25 @@ impl IntrospectionClient {
         let status = response.status();
         tracing::debug!(status = status.as_u16(), "Received response");
 
-        if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            tracing::error!(status = status.as_u16(), body = %error_body, "HTTP error response");
-            return Err(IntrospectionError::Http(status.as_u16(), error_body));
-        }
-
-        tracing::debug!("Parsing introspection response");
-        let introspection: IntrospectionResponse = response.json().await.map_err(|e| {
-            tracing::error!(error = %e, "Failed to parse introspection response");
-            IntrospectionError::Parse(e.to_string())
-        })?;
-
-        tracing::info!(
-            types = introspection.data.schema.types.len(),
-            directives = introspection.data.schema.directives.len(),
-            "Introspection successful"
-        );
-
-        Ok(introspection)
+        let body = response
+            .text()
+            .await
+            .map_err(|e| IntrospectionError::Network(e.to_string()))?;
+        response_json(status, body)
     }
 
     /// Determines if an error is retryable.
     ///
     /// Network errors and 5xx server errors are retryable.
-    /// Parse errors, 4xx client errors, and invalid responses are not.
+    /// Parse errors, 4xx client errors, invalid responses, and rejected
+    /// queries are not.
     fn is_retryable(error: &IntrospectionError) -> bool {
         match error {
             IntrospectionError::Network(_) => true,
             IntrospectionError::Http(status, _) => *status >= 500,
-            IntrospectionError::Parse(_) | IntrospectionError::Invalid(_) => false,
+            IntrospectionError::Parse(_)
+            | IntrospectionError::Invalid(_)
+            | IntrospectionError::Rejected(_) => false,
         }
     }
 
@@ -313,7 +348,7 @@ impl IntrospectionClient {
                 tokio::time::sleep(delay).await;
             }
 
-            match self.execute_raw_once(url).await {
+            match self.execute_raw_with_fallback(url).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     tracing::warn!(attempt, error = %e, "Request failed");
@@ -332,47 +367,53 @@ impl IntrospectionClient {
             .unwrap_or_else(|| IntrospectionError::Network("No attempts made".to_string())))
     }
 
-    /// Executes a single raw introspection request.
-    async fn execute_raw_once(&self, url: &str) -> Result<serde_json::Value> {
-        let builder = reqwest::Client::builder();
-        #[cfg(not(target_arch = "wasm32"))]
-        let builder = builder
-            .timeout(self.timeout)
-            .connect_timeout(self.connect_timeout);
-        let client = builder.build().map_err(|e| {
-            IntrospectionError::Network(format!("Failed to create HTTP client: {e}"))
-        })?;
-
-        let query_body = serde_json::json!({
-            "query": INTROSPECTION_QUERY
-        });
-
-        let mut request = client.post(url).header("Content-Type", "application/json");
-
-        for (name, value) in &self.headers {
-            request = request.header(name, value);
+    /// Executes a single raw introspection request, falling back to the
+    /// legacy query if the server rejects the full one.
+    async fn execute_raw_with_fallback(&self, url: &str) -> Result<serde_json::Value> {
+        match self.post(url, INTROSPECTION_QUERY).await {
+            Err(IntrospectionError::Rejected(reason)) => {
+                tracing::info!(%reason, "Retrying with the legacy introspection query");
+                self.post(url, LEGACY_INTROSPECTION_QUERY).await
+            }
+            result => result,
         }
+    }
+}
 
-        let response = request
-            .json(&query_body)
-            .send()
-            .await
-            .map_err(|e| IntrospectionError::Network(e.to_string()))?;
-
-        let status = response.status();
-
-        if !status.is_success() {
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(IntrospectionError::Http(status.as_u16(), error_body));
+/// Decode a response body, reporting a query the server refused to run as
+/// [`IntrospectionError::Rejected`].
+///
+/// Servers answer an invalid query with `errors` and no `data`, some with
+/// status 200 and others with 400.
+fn response_json(status: reqwest::StatusCode, body: String) -> Result<serde_json::Value> {
+    let json = serde_json::from_str::<serde_json::Value>(&body);
+    if status.is_success() || status == reqwest::StatusCode::BAD_REQUEST {
+        if let Some(reason) = json.as_ref().ok().and_then(rejection_reason) {
+            return Err(IntrospectionError::Rejected(reason));
         }
+    }
+    if !status.is_success() {
+        tracing::error!(status = status.as_u16(), body = %body, "HTTP error response");
+        return Err(IntrospectionError::Http(status.as_u16(), body));
+    }
+    json.map_err(|e| {
+        tracing::error!(error = %e, "Failed to parse introspection response");
+        IntrospectionError::Parse(e.to_string())
+    })
+}
 
-        let json: serde_json::Value = response
-            .json()
-            .await
-            .map_err(|e| IntrospectionError::Parse(e.to_string()))?;
-
-        Ok(json)
+/// The error messages of a response that carries no `data`
+fn rejection_reason(json: &serde_json::Value) -> Option<String> {
+    if json.get("data").is_some_and(|data| !data.is_null()) {
+        return None;
     }
+    let messages: Vec<&str> = json
+        .get("errors")?
+        .as_array()?
+        .iter()
+        .filter_map(|error| error.get("message")?.as_str())
+        .collect();
+    (!messages.is_empty()).then(|| messages.join("; "))
 }
 
 #[cfg(test)]
@@ -400,6 +441,14 @@ mod tests {
         assert_eq!(client.headers.get("X-API-Key"), Some(&"key123".to_string()));
     }
 
+    #[test]
+    fn test_client_debug_hides_header_values() {
+        let client = IntrospectionClient::new().with_header("Authorization", "Bearer token");
+        let debug = format!("{client:?}");
+        assert!(debug.contains("Authorization"));
+        assert!(!debug.contains("Bearer token"));
+    }
+
     #[test]
     fn test_client_with_headers_iterator() {
         let headers = vec![("Authorization", "Bearer token"), ("X-API-Key", "key123")];
@@ -443,5 +492,34 @@ mod tests {
         assert!(!IntrospectionClient::is_retryable(
             &IntrospectionError::Invalid("error".into())
         ));
+        assert!(!IntrospectionClient::is_retryable(
+            &IntrospectionError::Rejected("error".into())
+        ));
+    }
+
+    #[test]
+    fn test_validation_errors_are_rejections() {
+        let body = r#"{"errors":[{"message":"Cannot query field \"isRepeatable\"."}]}"#;
+        for status in [reqwest::StatusCode::OK, reqwest::StatusCode::BAD_REQUEST] {
+            let err = response_json(status, body.to_string()).unwrap_err();
+            let IntrospectionError::Rejected(reason) = err else {
+                panic!("expected a rejection, got {err:?}");
+            };
+            assert!(reason.contains("isRepeatable"));
+        }
+    }
+
+    #[test]
+    fn test_partial_data_is_not_a_rejection() {
+        let body = r#"{"data":{"__schema":null},"errors":[{"message":"boom"}]}"#;
+        assert!(response_json(reqwest::StatusCode::OK, body.to_string()).is_ok());
+    }
+
+    #[test]
+    fn test_server_errors_stay_http_errors() {
+        let body = r#"{"errors":[{"message":"internal"}]}"#;
+        let err = response_json(reqwest::StatusCode::INTERNAL_SERVER_ERROR, body.to_string())
+            .unwrap_err();
+        assert!(matches!(err, IntrospectionError::Http(500, _)));
     }
 }
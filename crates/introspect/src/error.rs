@@ -16,6 +16,9 @@ pub enum IntrospectionError {
 
     #[error("Invalid introspection response: {0}")]
     Invalid(String),
+
+    #[error("Server rejected the introspection query: {0}")]
+    Rejected(String),
 }
 
 /// Produce an actionable message for common network error patterns.
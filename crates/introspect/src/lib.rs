@@ -59,12 +59,17 @@
 mod client;
 mod error;
 mod query;
+mod registry;
 mod sdl;
 mod types;
 
 pub use client::IntrospectionClient;
 pub use error::{IntrospectionError, Result};
 pub use query::{execute_introspection, INTROSPECTION_QUERY};
+pub use registry::{
+    ChangeCriticality, HiveRegistry, SchemaChange, SchemaCheckResult, SchemaRegistry,
+    HIVE_DEFAULT_URL,
+};
 pub use sdl::introspection_to_sdl;
 pub use types::*;
 
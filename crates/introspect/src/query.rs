@@ -1,20 +1,125 @@
 //! GraphQL introspection query execution.
 
-use crate::{IntrospectionError, IntrospectionResponse, Result};
-#[cfg(not(target_arch = "wasm32"))]
-use std::time::Duration;
+use crate::{IntrospectionClient, IntrospectionResponse, Result};
 
 /// Standard GraphQL introspection query.
 ///
 /// This query fetches the complete schema information including:
 /// - Query, mutation, and subscription root types
 /// - All type definitions with their fields and arguments
-/// - Directive definitions
-/// - Deprecation information
+/// - Directive definitions, including whether they are repeatable
+/// - Deprecation information for fields, enum values, arguments, and input fields
+/// - Custom scalar `@specifiedBy` URLs and `@oneOf` input objects
 ///
 /// The query includes nested type references up to 7 levels deep to handle
 /// complex type wrappers like `[[[String!]!]!]`.
 pub const INTROSPECTION_QUERY: &str = r"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      ...FullType
+    }
+    directives {
+      name
+      description
+      isRepeatable
+      locations
+      args(includeDeprecated: true) {
+        ...InputValue
+      }
+    }
+  }
+}
+
+fragment FullType on __Type {
+  kind
+  name
+  description
+  specifiedByURL
+  fields(includeDeprecated: true) {
+    name
+    description
+    args(includeDeprecated: true) {
+      ...InputValue
+    }
+    type {
+      ...TypeRef
+    }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields(includeDeprecated: true) {
+    ...InputValue
+  }
+  isOneOf
+  interfaces {
+    ...TypeRef
+  }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes {
+    ...TypeRef
+  }
+}
+
+fragment InputValue on __InputValue {
+  name
+  description
+  type {
+    ...TypeRef
+  }
+  defaultValue
+  isDeprecated
+  deprecationReason
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType {
+            kind
+            name
+            ofType {
+              kind
+              name
+              ofType {
+                kind
+                name
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+}
+";
+
+/// Introspection query for servers that predate the October 2021 spec.
+///
+/// Same as [`INTROSPECTION_QUERY`] without `isRepeatable`, `specifiedByURL`,
+/// and deprecated arguments and input fields, which older servers reject
+/// as unknown. It's sent when a server refuses the full query.
+pub(crate) const LEGACY_INTROSPECTION_QUERY: &str = r"
 query IntrospectionQuery {
   __schema {
     queryType { name }
@@ -114,7 +219,8 @@ fragment TypeRef on __Type {
 /// Executes an introspection query against a GraphQL endpoint.
 ///
 /// Sends a POST request with the standard introspection query to the specified URL
-/// and deserializes the response into an [`IntrospectionResponse`].
+/// and deserializes the response into an [`IntrospectionResponse`]. A server that
+/// refuses the query as invalid is asked again with an older one.
 ///
 /// # Arguments
 ///
@@ -129,8 +235,14 @@ fragment TypeRef on __Type {
 /// Returns an error if:
 /// - The network request fails ([`IntrospectionError::Network`])
 /// - The server returns an HTTP error status ([`IntrospectionError::Http`])
+/// - The server rejects both introspection queries ([`IntrospectionError::Rejected`])
 /// - The response cannot be parsed as JSON ([`IntrospectionError::Parse`])
 ///
+/// [`IntrospectionError::Network`]: crate::IntrospectionError::Network
+/// [`IntrospectionError::Http`]: crate::IntrospectionError::Http
+/// [`IntrospectionError::Rejected`]: crate::IntrospectionError::Rejected
+/// [`IntrospectionError::Parse`]: crate::IntrospectionError::Parse
+///
 /// # Examples
 ///
 /// ```no_run
@@ -144,51 +256,7 @@ fragment TypeRef on __Type {
 /// ```
 #[tracing::instrument]
 pub async fn execute_introspection(url: &str) -> Result<IntrospectionResponse> {
-    tracing::debug!("Creating HTTP client with timeouts");
-    let builder = reqwest::Client::builder();
-    #[cfg(not(target_arch = "wasm32"))]
-    let builder = builder
-        .timeout(Duration::from_secs(30))
-        .connect_timeout(Duration::from_secs(10));
-    let client = builder
-        .build()
-        .map_err(|e| IntrospectionError::Network(format!("Failed to create HTTP client: {e}")))?;
-
-    let query_body = serde_json::json!({
-        "query": INTROSPECTION_QUERY
-    });
-
-    tracing::info!("Sending introspection query");
-    let response = client
-        .post(url)
-        .header("Content-Type", "application/json")
-        .json(&query_body)
-        .send()
-        .await
-        .map_err(|e| IntrospectionError::Network(e.to_string()))?;
-
-    let status = response.status();
-    tracing::debug!(status = status.as_u16(), "Received response");
-
-    if !status.is_success() {
-        let error_body = response.text().await.unwrap_or_default();
-        tracing::error!(status = status.as_u16(), body = %error_body, "HTTP error response");
-        return Err(IntrospectionError::Http(status.as_u16(), error_body));
-    }
-
-    tracing::debug!("Parsing introspection response");
-    let introspection: IntrospectionResponse = response.json().await.map_err(|e| {
-        tracing::error!(error = %e, "Failed to parse introspection response");
-        IntrospectionError::Parse(e.to_string())
-    })?;
-
-    tracing::info!(
-        types = introspection.data.schema.types.len(),
-        directives = introspection.data.schema.directives.len(),
-        "Introspection successful"
-    );
-
-    Ok(introspection)
+    IntrospectionClient::new().execute(url).await
 }
 
 #[cfg(test)]
@@ -203,4 +271,12 @@ mod tests {
         assert!(INTROSPECTION_QUERY.contains("types"));
         assert!(INTROSPECTION_QUERY.contains("directives"));
     }
+
+    #[test]
+    fn test_legacy_query_leaves_out_newer_fields() {
+        assert!(LEGACY_INTROSPECTION_QUERY.contains("__schema"));
+        assert!(!LEGACY_INTROSPECTION_QUERY.contains("isRepeatable"));
+        assert!(!LEGACY_INTROSPECTION_QUERY.contains("specifiedByURL"));
+        assert!(!LEGACY_INTROSPECTION_QUERY.contains("args(includeDeprecated: true)"));
+    }
 }
@@ -0,0 +1,332 @@
+//! Schema registry checks.
+//!
+//! A registry holds the schema that's currently published and can compare a
+//! proposed schema against it, reporting breaking and dangerous changes. The
+//! [`SchemaRegistry`] trait is the extension point; [`HiveRegistry`] talks to
+//! GraphQL Hive's `schemaCheck` mutation.
+
+use crate::{IntrospectionError, Result};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// Hosted GraphQL Hive API.
+pub const HIVE_DEFAULT_URL: &str = "https://app.graphql-hive.com/graphql";
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+const HIVE_SCHEMA_CHECK_MUTATION: &str = r"mutation schemaCheck($input: SchemaCheckInput!) {
+  schemaCheck(input: $input) {
+    __typename
+    ... on SchemaCheckSuccess {
+      valid
+      changes { nodes { message criticality path } }
+      schemaCheck { webUrl }
+    }
+    ... on SchemaCheckError {
+      valid
+      changes { nodes { message criticality path } }
+      errors { nodes { message } }
+      schemaCheck { webUrl }
+    }
+  }
+}";
+
+/// How a change affects existing clients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeCriticality {
+    /// Existing operations may stop working.
+    Breaking,
+    /// Existing operations keep validating but may behave differently,
+    /// e.g. a new enum value.
+    Dangerous,
+    Safe,
+}
+
+/// A single change between the published and the proposed schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaChange {
+    pub criticality: ChangeCriticality,
+    pub message: String,
+    /// Schema coordinate of the changed element, e.g. `User.email`, when the
+    /// registry reports one.
+    pub coordinate: Option<String>,
+}
+
+/// Outcome of a schema check.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaCheckResult {
+    /// Whether the registry would accept the schema.
+    pub valid: bool,
+    pub changes: Vec<SchemaChange>,
+    /// Errors not tied to a change, such as composition failures.
+    pub errors: Vec<String>,
+    /// Link to the check in the registry's UI.
+    pub details_url: Option<String>,
+}
+
+/// A registry that can check a proposed schema against the published one.
+pub trait SchemaRegistry {
+    /// Check `sdl` against the published schema.
+    fn check<'a>(
+        &'a self,
+        sdl: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<SchemaCheckResult>> + 'a>>;
+}
+
+/// Client for GraphQL Hive's schema checks.
+///
+/// ```no_run
+/// use graphql_introspect::{HiveRegistry, SchemaRegistry};
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let registry = HiveRegistry::new("my-token").with_service("users");
+/// let result = registry.check("type Query { user: String }").await?;
+/// println!("{} change(s)", result.changes.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HiveRegistry {
+    url: String,
+    token: String,
+    service: Option<String>,
+    timeout: Duration,
+}
+
+impl HiveRegistry {
+    /// Creates a client for the hosted Hive API with a 30 second timeout.
+    #[must_use]
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            url: HIVE_DEFAULT_URL.to_string(),
+            token: token.into(),
+            service: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+        }
+    }
+
+    /// Uses a self-hosted Hive API instead of the hosted one.
+    #[must_use]
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Checks the schema as this service of a composed graph.
+    #[must_use]
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.service = Some(service.into());
+        self
+    }
+
+    /// Sets the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    async fn check_once(&self, sdl: &str) -> Result<SchemaCheckResult> {
+        let builder = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = builder.timeout(self.timeout);
+        let client = builder.build().map_err(|e| {
+            IntrospectionError::Network(format!("Failed to create HTTP client: {e}"))
+        })?;
+
+        let mut input = serde_json::json!({ "sdl": sdl });
+        if let Some(service) = &self.service {
+            input["service"] = serde_json::Value::String(service.clone());
+        }
+        let body = serde_json::json!({
+            "query": HIVE_SCHEMA_CHECK_MUTATION,
+            "variables": { "input": input },
+        });
+
+        tracing::info!(url = %self.url, "Sending schema check");
+        let response = client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("Authorization", format!("Bearer {}", self.token))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| IntrospectionError::Network(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_body = response.text().await.unwrap_or_default();
+            return Err(IntrospectionError::Http(status.as_u16(), error_body));
+        }
+
+        let value: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| IntrospectionError::Parse(e.to_string()))?;
+        parse_hive_response(&value)
+    }
+}
+
+impl SchemaRegistry for HiveRegistry {
+    fn check<'a>(
+        &'a self,
+        sdl: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<SchemaCheckResult>> + 'a>> {
+        Box::pin(self.check_once(sdl))
+    }
+}
+
+/// Read a `schemaCheck` response. GraphQL errors (bad token, unknown
+/// target) are returned as [`IntrospectionError::Invalid`].
+fn parse_hive_response(value: &serde_json::Value) -> Result<SchemaCheckResult> {
+    if let Some(errors) = value.get("errors").and_then(serde_json::Value::as_array) {
+        let messages: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| e.get("message").and_then(serde_json::Value::as_str))
+            .collect();
+        if !messages.is_empty() {
+            return Err(IntrospectionError::Invalid(format!(
+                "Schema check failed: {}",
+                messages.join("; ")
+            )));
+        }
+    }
+
+    let check = value
+        .get("data")
+        .and_then(|d| d.get("schemaCheck"))
+        .ok_or_else(|| {
+            IntrospectionError::Invalid("Response has no `data.schemaCheck`".to_string())
+        })?;
+
+    let nodes = |key: &str| {
+        check
+            .get(key)
+            .and_then(|c| c.get("nodes"))
+            .and_then(serde_json::Value::as_array)
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let changes = nodes("changes")
+        .iter()
+        .filter_map(|node| {
+            let message = node.get("message")?.as_str()?.to_string();
+            let criticality = match node.get("criticality")?.as_str()? {
+                "Breaking" => ChangeCriticality::Breaking,
+                "Dangerous" => ChangeCriticality::Dangerous,
+                _ => ChangeCriticality::Safe,
+            };
+            let coordinate = node
+                .get("path")
+                .and_then(serde_json::Value::as_array)
+                .map(|path| {
+                    path.iter()
+                        .filter_map(serde_json::Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(".")
+                })
+                .filter(|coordinate| !coordinate.is_empty());
+            Some(SchemaChange {
+                criticality,
+                message,
+                coordinate,
+            })
+        })
+        .collect();
+
+    let errors = nodes("errors")
+        .iter()
+        .filter_map(|node| Some(node.get("message")?.as_str()?.to_string()))
+        .collect();
+
+    Ok(SchemaCheckResult {
+        valid: check
+            .get("valid")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false),
+        changes,
+        errors,
+        details_url: check
+            .get("schemaCheck")
+            .and_then(|c| c.get("webUrl"))
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_failed_check() {
+        let value = serde_json::json!({
+            "data": {
+                "schemaCheck": {
+                    "__typename": "SchemaCheckError",
+                    "valid": false,
+                    "changes": {
+                        "nodes": [
+                            {
+                                "message": "Field 'email' was removed from object type 'User'",
+                                "criticality": "Breaking",
+                                "path": ["User", "email"]
+                            },
+                            {
+                                "message": "Enum value 'GUEST' was added to enum 'Role'",
+                                "criticality": "Dangerous",
+                                "path": ["Role", "GUEST"]
+                            }
+                        ]
+                    },
+                    "errors": { "nodes": [{ "message": "Composition failed" }] },
+                    "schemaCheck": { "webUrl": "https://hive.example.com/checks/1" }
+                }
+            }
+        });
+
+        let result = parse_hive_response(&value).unwrap();
+        assert!(!result.valid);
+        assert_eq!(result.changes.len(), 2);
+        assert_eq!(result.changes[0].criticality, ChangeCriticality::Breaking);
+        assert_eq!(result.changes[0].coordinate.as_deref(), Some("User.email"));
+        assert_eq!(result.changes[1].criticality, ChangeCriticality::Dangerous);
+        assert_eq!(result.errors, vec!["Composition failed".to_string()]);
+        assert_eq!(
+            result.details_url.as_deref(),
+            Some("https://hive.example.com/checks/1")
+        );
+    }
+
+    #[test]
+    fn parse_successful_check_without_changes() {
+        let value = serde_json::json!({
+            "data": {
+                "schemaCheck": {
+                    "__typename": "SchemaCheckSuccess",
+                    "valid": true,
+                    "changes": null,
+                    "schemaCheck": null
+                }
+            }
+        });
+
+        let result = parse_hive_response(&value).unwrap();
+        assert!(result.valid);
+        assert!(result.changes.is_empty());
+        assert!(result.details_url.is_none());
+    }
+
+    #[test]
+    fn graphql_errors_are_invalid() {
+        let value = serde_json::json!({
+            "errors": [{ "message": "Invalid token provided" }]
+        });
+
+        let err = parse_hive_response(&value).unwrap_err();
+        assert!(err.to_string().contains("Invalid token provided"));
+    }
+}
@@ -1,6 +1,8 @@
 //! SDL (Schema Definition Language) conversion from introspection responses.
 
-use crate::types::{IntrospectionField, IntrospectionResponse, IntrospectionType};
+use crate::types::{
+    IntrospectionField, IntrospectionInputValue, IntrospectionResponse, IntrospectionType,
+};
 use std::fmt::Write;
 
 /// Built-in GraphQL scalar types that should not be included in generated SDL.
@@ -9,6 +11,9 @@ const BUILTIN_SCALARS: &[&str] = &["Int", "Float", "String", "Boolean", "ID"];
 /// Built-in GraphQL directives that should not be included in generated SDL.
 const BUILTIN_DIRECTIVES: &[&str] = &["skip", "include", "deprecated", "specifiedBy", "oneOf"];
 
+/// Reason the spec assigns to `@deprecated` when none is given.
+const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
 /// Converts a GraphQL introspection response to SDL (Schema Definition Language).
 ///
 /// This function generates clean, readable SDL from an introspection response by:
@@ -82,19 +87,9 @@ pub fn introspection_to_sdl(introspection: &IntrospectionResponse) -> String {
 
         write_description(&mut sdl, directive.description.as_ref(), 0);
         write!(sdl, "directive @{}", directive.name).unwrap();
-
-        if !directive.args.is_empty() {
-            sdl.push('(');
-            for (i, arg) in directive.args.iter().enumerate() {
-                if i > 0 {
-                    sdl.push_str(", ");
-                }
-                write!(sdl, "{}: {}", arg.name, arg.type_ref.to_type_string()).unwrap();
-                if let Some(default) = &arg.default_value {
-                    write!(sdl, " = {default}").unwrap();
-                }
-            }
-            sdl.push(')');
+        write_arguments(&mut sdl, &directive.args, 0);
+        if directive.is_repeatable {
+            sdl.push_str(" repeatable");
         }
 
         sdl.push_str(" on ");
@@ -137,7 +132,10 @@ fn write_type(sdl: &mut String, type_def: &IntrospectionType) {
     match type_def {
         IntrospectionType::Scalar(t) => {
             write_description(sdl, t.description.as_ref(), 0);
-            writeln!(sdl, "scalar {}", t.name).unwrap();
+            write!(sdl, "scalar {}", t.name).unwrap();
+            if let Some(ref url) = t.specified_by_url {
+                write!(sdl, " @specifiedBy(url: \"{}\")", escape_string(url)).unwrap();
+            }
         }
         IntrospectionType::Object(t) => {
             write_description(sdl, t.description.as_ref(), 0);
@@ -151,11 +149,9 @@ fn write_type(sdl: &mut String, type_def: &IntrospectionType) {
         }
         IntrospectionType::Union(t) => {
             write_description(sdl, t.description.as_ref(), 0);
-            write!(sdl, "union {} = ", t.name).unwrap();
+            write!(sdl, "union {}", t.name).unwrap();
             for (i, member) in t.possible_types.iter().enumerate() {
-                if i > 0 {
-                    sdl.push_str(" | ");
-                }
+                sdl.push_str(if i == 0 { " = " } else { " | " });
                 sdl.push_str(&member.name);
             }
         }
@@ -165,13 +161,11 @@ fn write_type(sdl: &mut String, type_def: &IntrospectionType) {
             for value in &t.enum_values {
                 write_description(sdl, value.description.as_ref(), 1);
                 write!(sdl, "  {}", value.name).unwrap();
-                if value.is_deprecated {
-                    if let Some(ref reason) = value.deprecation_reason {
-                        write!(sdl, " @deprecated(reason: \"{}\")", escape_string(reason)).unwrap();
-                    } else {
-                        sdl.push_str(" @deprecated");
-                    }
-                }
+                write_deprecated(
+                    sdl,
+                    value.is_deprecated,
+                    value.deprecation_reason.as_deref(),
+                );
                 sdl.push('\n');
             }
             sdl.push('}');
@@ -185,10 +179,8 @@ fn write_type(sdl: &mut String, type_def: &IntrospectionType) {
             }
             for field in &t.input_fields {
                 write_description(sdl, field.description.as_ref(), 1);
-                write!(sdl, "  {}: {}", field.name, field.type_ref.to_type_string()).unwrap();
-                if let Some(default) = &field.default_value {
-                    write!(sdl, " = {default}").unwrap();
-                }
+                sdl.push_str("  ");
+                write_input_value(sdl, field);
                 sdl.push('\n');
             }
             sdl.push('}');
@@ -202,41 +194,96 @@ fn write_field(sdl: &mut String, field: &IntrospectionField, indent: usize) {
     write_description(sdl, field.description.as_ref(), indent);
     write!(sdl, "{indent_str}{}", field.name).unwrap();
 
-    if !field.args.is_empty() {
-        sdl.push('(');
-        for (i, arg) in field.args.iter().enumerate() {
-            if i > 0 {
-                sdl.push_str(", ");
-            }
-            write!(sdl, "{}: {}", arg.name, arg.type_ref.to_type_string()).unwrap();
-            if let Some(default) = &arg.default_value {
-                write!(sdl, " = {default}").unwrap();
-            }
+    write_arguments(sdl, &field.args, indent);
+    write!(sdl, ": {}", field.type_ref.to_type_string()).unwrap();
+    write_deprecated(
+        sdl,
+        field.is_deprecated,
+        field.deprecation_reason.as_deref(),
+    );
+
+    sdl.push('\n');
+}
+
+/// Writes a parenthesized argument list, or nothing when there are no arguments.
+///
+/// Arguments with descriptions are written one per line (one level deeper
+/// than `indent`) so the descriptions survive; otherwise the list stays inline.
+fn write_arguments(sdl: &mut String, args: &[IntrospectionInputValue], indent: usize) {
+    if args.is_empty() {
+        return;
+    }
+
+    if args.iter().any(|arg| arg.description.is_some()) {
+        let arg_indent = "  ".repeat(indent + 1);
+        sdl.push_str("(\n");
+        for arg in args {
+            write_description(sdl, arg.description.as_ref(), indent + 1);
+            sdl.push_str(&arg_indent);
+            write_input_value(sdl, arg);
+            sdl.push('\n');
         }
+        sdl.push_str(&"  ".repeat(indent));
         sdl.push(')');
+        return;
     }
 
-    write!(sdl, ": {}", field.type_ref.to_type_string()).unwrap();
+    sdl.push('(');
+    for (i, arg) in args.iter().enumerate() {
+        if i > 0 {
+            sdl.push_str(", ");
+        }
+        write_input_value(sdl, arg);
+    }
+    sdl.push(')');
+}
 
-    if field.is_deprecated {
-        if let Some(ref reason) = field.deprecation_reason {
+/// Writes `name: Type = default @deprecated(...)` for an argument or input field.
+fn write_input_value(sdl: &mut String, value: &IntrospectionInputValue) {
+    write!(sdl, "{}: {}", value.name, value.type_ref.to_type_string()).unwrap();
+    if let Some(default) = &value.default_value {
+        write!(sdl, " = {default}").unwrap();
+    }
+    write_deprecated(
+        sdl,
+        value.is_deprecated,
+        value.deprecation_reason.as_deref(),
+    );
+}
+
+/// Writes an `@deprecated` directive, omitting the reason when it is the
+/// spec default so round-tripped schemas don't grow a redundant argument.
+fn write_deprecated(sdl: &mut String, is_deprecated: bool, reason: Option<&str>) {
+    if !is_deprecated {
+        return;
+    }
+    match reason {
+        Some(reason) if reason != DEFAULT_DEPRECATION_REASON => {
             write!(sdl, " @deprecated(reason: \"{}\")", escape_string(reason)).unwrap();
-        } else {
-            sdl.push_str(" @deprecated");
         }
+        _ => sdl.push_str(" @deprecated"),
     }
-
-    sdl.push('\n');
 }
 
 fn write_description(sdl: &mut String, description: Option<&String>, indent: usize) {
-    if let Some(desc) = description {
-        let indent_str = "  ".repeat(indent);
-        if desc.contains('\n') {
-            writeln!(sdl, "{indent_str}\"\"\"\n{desc}\n{indent_str}\"\"\"").unwrap();
-        } else {
-            writeln!(sdl, "{indent_str}\"{}\"", escape_string(desc)).unwrap();
+    let Some(desc) = description else {
+        return;
+    };
+    let indent_str = "  ".repeat(indent);
+    if desc.contains('\n') {
+        // Block strings only need `\"\"\"` escaped; each line is indented so the
+        // description lines up with the definition it documents.
+        writeln!(sdl, "{indent_str}\"\"\"").unwrap();
+        for line in desc.replace("\"\"\"", "\\\"\"\"").lines() {
+            if line.is_empty() {
+                sdl.push('\n');
+            } else {
+                writeln!(sdl, "{indent_str}{line}").unwrap();
+            }
         }
+        writeln!(sdl, "{indent_str}\"\"\"").unwrap();
+    } else {
+        writeln!(sdl, "{indent_str}\"{}\"", escape_string(desc)).unwrap();
     }
 }
 
@@ -328,4 +375,182 @@ mod tests {
             "C:\\\\path\\\\to\\\\file"
         );
     }
+
+    fn sdl_for(types: &serde_json::Value, directives: &serde_json::Value) -> String {
+        let response: IntrospectionResponse = serde_json::from_value(serde_json::json!({
+            "data": {
+                "__schema": {
+                    "queryType": { "name": "Query" },
+                    "mutationType": null,
+                    "subscriptionType": null,
+                    "types": types,
+                    "directives": directives,
+                }
+            }
+        }))
+        .unwrap();
+        introspection_to_sdl(&response)
+    }
+
+    fn named(kind: &str, name: &str) -> serde_json::Value {
+        serde_json::json!({ "kind": kind, "name": name, "ofType": null })
+    }
+
+    fn input_value(name: &str, type_name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "description": null,
+            "type": named("SCALAR", type_name),
+            "defaultValue": null,
+        })
+    }
+
+    #[test]
+    fn test_scalar_specified_by() {
+        let sdl = sdl_for(
+            &serde_json::json!([{
+                "kind": "SCALAR",
+                "name": "DateTime",
+                "description": null,
+                "specifiedByURL": "https://scalars.graphql.org/andimarek/date-time"
+            }]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(
+            sdl,
+            "scalar DateTime @specifiedBy(url: \"https://scalars.graphql.org/andimarek/date-time\")"
+        );
+    }
+
+    #[test]
+    fn test_repeatable_directive() {
+        let sdl = sdl_for(
+            &serde_json::json!([]),
+            &serde_json::json!([{
+                "name": "tag",
+                "description": null,
+                "isRepeatable": true,
+                "locations": ["FIELD_DEFINITION", "OBJECT"],
+                "args": [input_value("name", "String")]
+            }]),
+        );
+        assert_eq!(
+            sdl,
+            "directive @tag(name: String) repeatable on FIELD_DEFINITION | OBJECT"
+        );
+    }
+
+    #[test]
+    fn test_input_object_defaults_deprecation_and_one_of() {
+        let mut limit = input_value("limit", "Int");
+        limit["defaultValue"] = serde_json::json!("10");
+        let mut offset = input_value("offset", "Int");
+        offset["isDeprecated"] = serde_json::json!(true);
+        offset["deprecationReason"] = serde_json::json!("Use `after`");
+        let sdl = sdl_for(
+            &serde_json::json!([
+                {
+                    "kind": "INPUT_OBJECT",
+                    "name": "Page",
+                    "description": null,
+                    "inputFields": [limit, offset],
+                },
+                {
+                    "kind": "INPUT_OBJECT",
+                    "name": "UserBy",
+                    "description": null,
+                    "inputFields": [input_value("id", "ID"), input_value("email", "String")],
+                    "isOneOf": true
+                }
+            ]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(
+            sdl,
+            "input Page {\n  limit: Int = 10\n  offset: Int @deprecated(reason: \"Use `after`\")\n}\n\n\
+             input UserBy @oneOf {\n  id: ID\n  email: String\n}"
+        );
+    }
+
+    #[test]
+    fn test_default_deprecation_reason_is_omitted() {
+        let sdl = sdl_for(
+            &serde_json::json!([{
+                "kind": "ENUM",
+                "name": "Color",
+                "description": null,
+                "enumValues": [{
+                    "name": "RED",
+                    "description": null,
+                    "isDeprecated": true,
+                    "deprecationReason": "No longer supported"
+                }]
+            }]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(sdl, "enum Color {\n  RED @deprecated\n}");
+    }
+
+    #[test]
+    fn test_argument_descriptions_are_preserved() {
+        let mut first = input_value("first", "Int");
+        first["description"] = serde_json::json!("Page size");
+        let sdl = sdl_for(
+            &serde_json::json!([{
+                "kind": "OBJECT",
+                "name": "Query",
+                "description": null,
+                "interfaces": [],
+                "fields": [{
+                    "name": "users",
+                    "description": null,
+                    "args": [first],
+                    "type": named("SCALAR", "String"),
+                    "isDeprecated": false,
+                    "deprecationReason": null
+                }]
+            }]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(
+            sdl,
+            "type Query {\n  users(\n    \"Page size\"\n    first: Int\n  ): String\n}"
+        );
+    }
+
+    #[test]
+    fn test_multiline_description_is_indented_and_escaped() {
+        let sdl = sdl_for(
+            &serde_json::json!([{
+                "kind": "ENUM",
+                "name": "Color",
+                "description": "Colors.\n\nSee \"\"\"docs\"\"\".",
+                "enumValues": [{
+                    "name": "RED",
+                    "description": "Warm\ncolor",
+                    "isDeprecated": false,
+                    "deprecationReason": null
+                }]
+            }]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(
+            sdl,
+            "\"\"\"\nColors.\n\nSee \\\"\"\"docs\\\"\"\".\n\"\"\"\nenum Color {\n  \"\"\"\n  Warm\n  color\n  \"\"\"\n  RED\n}"
+        );
+    }
+
+    #[test]
+    fn test_union_without_members() {
+        let sdl = sdl_for(
+            &serde_json::json!([{
+                "kind": "UNION",
+                "name": "Empty",
+                "description": null,
+                "possibleTypes": []
+            }]),
+            &serde_json::json!([]),
+        );
+        assert_eq!(sdl, "union Empty");
+    }
 }
@@ -56,6 +56,8 @@ pub enum IntrospectionType {
 pub struct IntrospectionScalarType {
     pub name: String,
     pub description: Option<String>,
+    #[serde(default, rename = "specifiedByURL")]
+    pub specified_by_url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,6 +125,10 @@ pub struct IntrospectionInputValue {
     #[serde(rename = "type")]
     pub type_ref: IntrospectionTypeRefFull,
     pub default_value: Option<String>,
+    #[serde(default)]
+    pub is_deprecated: bool,
+    #[serde(default)]
+    pub deprecation_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -135,9 +141,12 @@ pub struct IntrospectionEnumValue {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct IntrospectionDirective {
     pub name: String,
     pub description: Option<String>,
+    #[serde(default)]
+    pub is_repeatable: bool,
     pub locations: Vec<String>,
     pub args: Vec<IntrospectionInputValue>,
 }
@@ -230,6 +230,13 @@ pub struct FullLintConfig {
     /// Rule names use `camelCase` (e.g., `noDeprecated`), matching the config file format.
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub rules: HashMap<String, LintRuleConfig>,
+
+    /// Rules compiled to WebAssembly, mapping each rule name to its module
+    ///
+    /// Paths are relative to the config file. Plugin rules are configured
+    /// under `rules` like built-in ones, and run at `warn` when they aren't.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub plugins: HashMap<String, String>,
 }
 
 /// Overall lint configuration
@@ -260,6 +267,14 @@ pub struct FullLintConfig {
 ///   extends: [recommended, strict]
 ///   rules:
 ///     requireSelections: off
+///
+/// # Org-specific rules compiled to WebAssembly
+/// lint:
+///   extends: recommended
+///   plugins:
+///     requireOwnerDirective: ./lint/require-owner.wasm
+///   rules:
+///     requireOwnerDirective: error
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -277,6 +292,7 @@ impl Default for LintConfig {
         Self::Full(FullLintConfig {
             extends: None,
             rules: HashMap::new(),
+            plugins: HashMap::new(),
         })
     }
 }
@@ -297,22 +313,40 @@ impl LintConfig {
         if overrides.is_empty() {
             return self;
         }
-        let (extends, mut rules) = match self {
-            Self::Preset(presets) => (Some(presets), HashMap::new()),
-            Self::Full(FullLintConfig { extends, rules }) => (extends, rules),
+        let (extends, mut rules, plugins) = match self {
+            Self::Preset(presets) => (Some(presets), HashMap::new(), HashMap::new()),
+            Self::Full(FullLintConfig {
+                extends,
+                rules,
+                plugins,
+            }) => (extends, rules, plugins),
         };
         for (name, override_cfg) in overrides {
             rules.insert(name, override_cfg);
         }
-        Self::Full(FullLintConfig { extends, rules })
+        Self::Full(FullLintConfig {
+            extends,
+            rules,
+            plugins,
+        })
     }
 
     /// Validate the lint configuration against available rules
     ///
-    /// Returns an error if any configured rule names are invalid.
+    /// Returns an error if any configured rule names are invalid, or if a
+    /// plugin takes the name of a built-in rule.
     /// The error message includes a list of valid rule names.
     pub fn validate(&self) -> Result<(), String> {
-        let valid_rules = crate::registry::all_rule_names();
+        let mut valid_rules: Vec<&str> = crate::registry::all_rule_names();
+        for (name, _) in self.plugins() {
+            if valid_rules.contains(&name) {
+                return Err(format!(
+                    "Lint plugin '{name}' has the same name as a built-in rule"
+                ));
+            }
+            valid_rules.push(name);
+        }
+        valid_rules.sort_unstable();
         let valid_set: std::collections::HashSet<&str> = valid_rules.iter().copied().collect();
 
         let valid_presets = ["recommended"];
@@ -328,7 +362,7 @@ impl LintConfig {
                 }
                 return Ok(());
             }
-            Self::Full(FullLintConfig { extends, rules }) => {
+            Self::Full(FullLintConfig { extends, rules, .. }) => {
                 if let Some(ext) = extends {
                     for preset in ext.presets() {
                         if !valid_presets.contains(&preset) {
@@ -368,7 +402,7 @@ impl LintConfig {
     pub fn get_severity(&self, rule_name: &str) -> Option<LintSeverity> {
         match self {
             Self::Preset(presets) => Self::severity_from_presets(presets, rule_name),
-            Self::Full(FullLintConfig { extends, rules }) => {
+            Self::Full(FullLintConfig { extends, rules, .. }) => {
                 // Start with preset severities (if any)
                 let preset_severity = extends
                     .as_ref()
@@ -396,6 +430,20 @@ impl LintConfig {
         }
     }
 
+    /// Plugin rule names and module paths, sorted by name
+    #[must_use]
+    pub fn plugins(&self) -> Vec<(&str, &str)> {
+        let mut plugins: Vec<(&str, &str)> = match self {
+            Self::Preset(_) => Vec::new(),
+            Self::Full(FullLintConfig { plugins, .. }) => plugins
+                .iter()
+                .map(|(name, path)| (name.as_str(), path.as_str()))
+                .collect(),
+        };
+        plugins.sort_unstable();
+        plugins
+    }
+
     /// Get severity from a list of presets (later presets override earlier)
     fn severity_from_presets(presets: &ExtendsConfig, rule_name: &str) -> Option<LintSeverity> {
         let mut severity = None;
@@ -423,7 +471,7 @@ impl LintConfig {
     ///
     /// The `recommended` preset includes rules that are objectively beneficial
     /// without being opinionated about architecture choices.
-    fn recommended_severity(rule_name: &str) -> Option<LintSeverity> {
+    pub(crate) fn recommended_severity(rule_name: &str) -> Option<LintSeverity> {
         match rule_name {
             "noAnonymousOperations" => Some(LintSeverity::Error),
             "noDeprecated"
@@ -434,6 +482,7 @@ impl LintConfig {
             | "noUnreachableTypes"
             | "requireDeprecationReason"
             | "noHashtagDescription"
+            | "noUnsupportedIncrementalDelivery"
             | "uniqueEnumValueNames" => Some(LintSeverity::Warn),
             _ => None,
         }
@@ -572,6 +621,33 @@ rules:
         assert!(result.unwrap_err().contains("notARule"));
     }
 
+    #[test]
+    fn test_validate_plugin_rules() {
+        let yaml = r"
+plugins:
+  requireOwner: ./lint/require-owner.wasm
+rules:
+  requireOwner: [error, { directive: owner }]
+";
+        let config: LintConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.plugins(),
+            vec![("requireOwner", "./lint/require-owner.wasm")]
+        );
+        assert_eq!(
+            config.get_severity("requireOwner"),
+            Some(LintSeverity::Error)
+        );
+
+        let yaml = r"
+plugins:
+  noDeprecated: ./lint/no-deprecated.wasm
+";
+        let config: LintConfig = serde_saphyr::from_str(yaml).unwrap();
+        assert!(config.validate().unwrap_err().contains("noDeprecated"));
+    }
+
     #[test]
     fn test_default_no_rules_enabled() {
         let config = LintConfig::default();
@@ -36,6 +36,16 @@ impl TextEdit {
     }
 }
 
+impl From<graphql_syntax::edit::TextEdit> for TextEdit {
+    fn from(edit: graphql_syntax::edit::TextEdit) -> Self {
+        Self::new(
+            edit.offset_range.start,
+            edit.offset_range.end,
+            edit.new_text,
+        )
+    }
+}
+
 /// A code fix that can be applied to resolve a lint diagnostic
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CodeFix {
@@ -111,6 +121,28 @@ impl CodeFix {
             edits: vec![TextEdit::delete(start, end)],
         }
     }
+
+    /// Apply the edits to `source`, shifting each by `byte_offset` (the
+    /// block's offset in the file for embedded GraphQL, otherwise 0).
+    ///
+    /// Returns `None` if an edit falls outside `source` or off a char
+    /// boundary.
+    #[must_use]
+    pub fn apply(&self, source: &str, byte_offset: usize) -> Option<String> {
+        let mut edits: Vec<&TextEdit> = self.edits.iter().collect();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.offset_range.start));
+
+        let mut result = source.to_string();
+        for edit in edits {
+            let start = edit.offset_range.start + byte_offset;
+            let end = edit.offset_range.end + byte_offset;
+            if start > end || !result.is_char_boundary(start) || !result.is_char_boundary(end) {
+                return None;
+            }
+            result.replace_range(start..end, &edit.new_text);
+        }
+        Some(result)
+    }
 }
 
 /// A tag attached to a diagnostic providing additional classification
@@ -122,6 +154,18 @@ pub enum DiagnosticTag {
     Deprecated,
 }
 
+/// Another location that explains a diagnostic, such as the other
+/// definitions of a duplicated name. Surfaced as LSP related information.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedSpan {
+    /// File the span belongs to
+    pub file_id: graphql_base_db::FileId,
+    /// Source span with block context, like [`LintDiagnostic::span`]
+    pub span: graphql_syntax::SourceSpan,
+    /// Short description of why the location is relevant
+    pub message: String,
+}
+
 /// Lint-specific diagnostic with byte offsets (not line/column).
 ///
 /// The `span` field carries both the byte offset range and block context
@@ -154,6 +198,8 @@ pub struct LintDiagnostic {
     pub url: Option<String>,
     /// Diagnostic tags for additional classification
     pub tags: Vec<DiagnosticTag>,
+    /// Other locations involved in the issue, possibly in other files
+    pub related: Vec<RelatedSpan>,
 }
 
 impl LintDiagnostic {
@@ -176,6 +222,7 @@ impl LintDiagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -197,6 +244,7 @@ impl LintDiagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -218,6 +266,7 @@ impl LintDiagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -239,6 +288,7 @@ impl LintDiagnostic {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         }
     }
 
@@ -293,6 +343,22 @@ impl LintDiagnostic {
         self
     }
 
+    /// Point at another location involved in this diagnostic
+    #[must_use]
+    pub fn with_related(
+        mut self,
+        file_id: graphql_base_db::FileId,
+        span: graphql_syntax::SourceSpan,
+        message: impl Into<String>,
+    ) -> Self {
+        self.related.push(RelatedSpan {
+            file_id,
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
     /// Returns true if this diagnostic has an auto-fix available
     #[must_use]
     pub const fn has_fix(&self) -> bool {
@@ -331,7 +397,8 @@ impl OffsetRange {
 }
 
 /// Lint severity level
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum LintSeverity {
     Error,
     Warning,
@@ -470,6 +537,16 @@ mod tests {
         assert_eq!(diag.tags[1], DiagnosticTag::Deprecated);
     }
 
+    #[test]
+    fn test_code_fix_apply() {
+        let fix = CodeFix::new(
+            "Fix",
+            vec![TextEdit::delete(0, 6), TextEdit::insert(9, "!")],
+        );
+        assert_eq!(fix.apply("x = query Foo", 4), Some("x = Foo!".to_string()));
+        assert_eq!(fix.apply("short", 4), None);
+    }
+
     #[test]
     fn test_diagnostic_defaults() {
         let span = graphql_syntax::SourceSpan::default();
@@ -8,10 +8,17 @@
 //!
 //! # graphql-analyzer-ignore: noDeprecated, noUnusedVariables
 //! query { ... }
+//!
+//! # graphql-lint-disable-next-line noDeprecated
+//! query { ... }
+//!
+//! # graphql-lint-disable requireIdField
 //! ```
 //!
-//! The comment must appear on the line immediately before the diagnostic.
-//! Without rule names, all lint rules are suppressed for that line.
+//! `graphql-analyzer-ignore` and `graphql-lint-disable-next-line` cover the
+//! line immediately after the comment; `graphql-lint-disable` covers the
+//! whole file, wherever it appears. Without rule names, all lint rules are
+//! suppressed.
 
 /// Byte range of a single rule name within an ignore comment.
 /// Byte offsets are file-relative (not line-relative).
@@ -22,6 +29,15 @@ pub struct RuleSpan {
     pub byte_end: usize,
 }
 
+/// Which lines an ignore directive covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IgnoreScope {
+    /// The line after the comment
+    NextLine,
+    /// Every line of the file
+    File,
+}
+
 /// A parsed ignore directive from a comment.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct IgnoreDirective {
@@ -33,6 +49,9 @@ pub struct IgnoreDirective {
     pub byte_end: usize,
     /// The rules to ignore. Empty means ignore all rules.
     pub rules: Vec<RuleSpan>,
+    pub scope: IgnoreScope,
+    /// The comment keyword, e.g. `graphql-analyzer-ignore`, for messages
+    pub keyword: &'static str,
 }
 
 impl IgnoreDirective {
@@ -42,6 +61,15 @@ impl IgnoreDirective {
         self.rules.is_empty() || self.rules.iter().any(|r| r.name == rule_name)
     }
 
+    /// Returns true if this directive covers diagnostics on `line`.
+    #[must_use]
+    pub fn covers_line(&self, line: usize) -> bool {
+        match self.scope {
+            IgnoreScope::NextLine => line == self.line + 1,
+            IgnoreScope::File => true,
+        }
+    }
+
     #[must_use]
     pub fn rule_names(&self) -> Vec<&str> {
         self.rules.iter().map(|r| r.name.as_str()).collect()
@@ -49,15 +77,16 @@ impl IgnoreDirective {
 }
 
 const IGNORE_PREFIX: &str = "graphql-analyzer-ignore";
+const DISABLE_NEXT_LINE_PREFIX: &str = "graphql-lint-disable-next-line";
+const DISABLE_FILE_PREFIX: &str = "graphql-lint-disable";
 
 /// Parse all ignore directives from GraphQL source text.
 ///
-/// Scans each line for comments matching `# graphql-analyzer-ignore`
-/// or `# graphql-analyzer-ignore: rule1, rule2`.
+/// Scans each line for comments matching `# graphql-analyzer-ignore`,
+/// `# graphql-analyzer-ignore: rule1, rule2`,
+/// `# graphql-lint-disable-next-line rule1 rule2` or
+/// `# graphql-lint-disable rule1, rule2`.
 #[must_use]
-// The expect calls below are all provably safe: each is guarded by a prior
-// parse step (strip_prefix) that guarantees the substring exists.
-#[allow(clippy::expect_used)]
 pub fn parse_ignore_directives(source: &str) -> Vec<IgnoreDirective> {
     let mut directives = Vec::new();
     let mut byte_pos = 0;
@@ -68,93 +97,88 @@ pub fn parse_ignore_directives(source: &str) -> Vec<IgnoreDirective> {
         // Advance past line content + newline character
         byte_pos = line_end + usize::from(source[line_end..].starts_with('\n'));
 
-        let trimmed = line.trim();
-
         // GraphQL comments start with #
-        let Some(comment_body) = trimmed.strip_prefix('#') else {
+        let Some(comment_body) = line.trim_start().strip_prefix('#') else {
             continue;
         };
-
-        let comment_body = comment_body.trim();
-
-        let Some(rest) = comment_body.strip_prefix(IGNORE_PREFIX) else {
+        let comment_body = comment_body.trim_start();
+
+        // The longer prefix goes first, since it starts with the shorter one
+        let Some((keyword, scope, rest)) = [
+            (IGNORE_PREFIX, IgnoreScope::NextLine),
+            (DISABLE_NEXT_LINE_PREFIX, IgnoreScope::NextLine),
+            (DISABLE_FILE_PREFIX, IgnoreScope::File),
+        ]
+        .into_iter()
+        .find_map(|(keyword, scope)| {
+            let rest = comment_body.strip_prefix(keyword)?;
+            // `graphql-analyzer-ignorefoo` isn't a directive
+            let at_boundary =
+                rest.is_empty() || rest.starts_with(':') || rest.starts_with(char::is_whitespace);
+            at_boundary.then_some((keyword, scope, rest))
+        }) else {
             continue;
         };
 
-        let rest = rest.trim();
-
-        if rest.is_empty() {
-            directives.push(IgnoreDirective {
-                line: line_num,
-                byte_offset: line_start,
-                byte_end: line_end,
-                rules: Vec::new(),
-            });
-        } else if let Some(rule_list) = rest.strip_prefix(':') {
-            // Find the byte offset of rule_list within line by locating the
-            // colon after the ignore prefix — rule_list starts immediately
-            // after it. We search from the ignore prefix onward to avoid
-            // matching an unrelated colon earlier in the line.
-            // Both finds are infallible: we already parsed `rest` out of `line`
-            // (so IGNORE_PREFIX is present), and we're in the strip_prefix(':') branch
-            // (so the colon after the prefix is present).
-            let prefix_pos = line
-                .find(IGNORE_PREFIX)
-                .expect("IGNORE_PREFIX was found when parsing rest");
-            let colon_pos = prefix_pos
-                + IGNORE_PREFIX.len()
-                + line[prefix_pos + IGNORE_PREFIX.len()..]
-                    .find(':')
-                    .expect("colon exists: guarded by strip_prefix(':') branch")
-                + 1;
-            let rule_list_file_offset = line_start + colon_pos;
-
-            let mut rules = Vec::new();
-            let mut pos = 0;
-            for segment in rule_list.split(',') {
-                let trimmed = segment.trim();
-                if !trimmed.is_empty() {
-                    // `trimmed` is `segment.trim()`, so it's always a substring of `segment`.
-                    let trim_start = segment
-                        .find(trimmed)
-                        .expect("trimmed is a substring of segment");
-                    let name_start = rule_list_file_offset + pos + trim_start;
-                    let name_end = name_start + trimmed.len();
-                    rules.push(RuleSpan {
-                        name: trimmed.to_string(),
-                        byte_offset: name_start,
-                        byte_end: name_end,
-                    });
-                }
-                pos += segment.len() + 1; // +1 for the comma
-            }
+        // `graphql-analyzer-ignore` takes its rules after a colon; the
+        // disable comments list them directly, with an optional colon
+        let rest = rest.trim_start();
+        let rule_list = match rest.strip_prefix(':') {
+            Some(rule_list) => rule_list,
+            None if keyword == IGNORE_PREFIX && !rest.trim_end().is_empty() => continue,
+            None => rest,
+        };
 
-            directives.push(IgnoreDirective {
-                line: line_num,
-                byte_offset: line_start,
-                byte_end: line_end,
-                rules,
-            });
-        }
+        // `rule_list` is a suffix of `line`, so its file offset follows from
+        // the lengths
+        let rule_list_offset = line_end - rule_list.len();
+        // Anything after ` -- ` explains the suppression
+        let rule_list = rule_list.split(" --").next().unwrap_or_default();
+        directives.push(IgnoreDirective {
+            line: line_num,
+            byte_offset: line_start,
+            byte_end: line_end,
+            rules: parse_rule_list(rule_list, rule_list_offset),
+            scope,
+            keyword,
+        });
     }
 
     directives
 }
 
+/// Split a list of rule names separated by commas and/or whitespace.
+fn parse_rule_list(rule_list: &str, offset: usize) -> Vec<RuleSpan> {
+    let mut rules = Vec::new();
+    let mut name_start = None;
+    for (index, c) in rule_list.char_indices().chain([(rule_list.len(), ',')]) {
+        let is_separator = c == ',' || c.is_whitespace();
+        match (name_start, is_separator) {
+            (None, false) => name_start = Some(index),
+            (Some(start), true) => {
+                rules.push(RuleSpan {
+                    name: rule_list[start..index].to_string(),
+                    byte_offset: offset + start,
+                    byte_end: offset + index,
+                });
+                name_start = None;
+            }
+            _ => {}
+        }
+    }
+    rules
+}
+
 /// Check if a diagnostic at the given line should be suppressed.
 ///
 /// A diagnostic is suppressed if there is an ignore directive on the
-/// immediately preceding line that covers the diagnostic's rule.
+/// immediately preceding line, or a file-wide one, that covers the
+/// diagnostic's rule.
 #[must_use]
 pub fn is_suppressed(directives: &[IgnoreDirective], diagnostic_line: usize, rule: &str) -> bool {
-    if diagnostic_line == 0 {
-        return false;
-    }
-
-    let preceding_line = diagnostic_line - 1;
     directives
         .iter()
-        .any(|d| d.line == preceding_line && d.suppresses(rule))
+        .any(|d| d.covers_line(diagnostic_line) && d.suppresses(rule))
 }
 
 /// Result of checking whether an ignore directive (or individual rules) is unused.
@@ -181,10 +205,9 @@ pub fn find_unused_rules<'a>(
     directives
         .iter()
         .filter_map(|d| {
-            let target_line = d.line + 1;
             let has_any_diag = diagnostic_lines_and_rules
                 .iter()
-                .any(|(line, _)| *line == target_line);
+                .any(|(line, _)| d.covers_line(*line));
 
             if d.rules.is_empty() {
                 if has_any_diag {
@@ -199,7 +222,7 @@ pub fn find_unused_rules<'a>(
                     .filter(|r| {
                         !diagnostic_lines_and_rules
                             .iter()
-                            .any(|(line, rule)| *line == target_line && *rule == r.name)
+                            .any(|(line, rule)| d.covers_line(*line) && *rule == r.name)
                     })
                     .collect();
 
@@ -306,6 +329,8 @@ query Bar { world }";
                     byte_end: 0,
                 })
                 .collect(),
+            scope: IgnoreScope::NextLine,
+            keyword: IGNORE_PREFIX,
         }
     }
 
@@ -504,4 +529,46 @@ query Bar { world }";
             "requireIdField"
         );
     }
+
+    #[test]
+    fn parse_disable_next_line() {
+        let source =
+            "# graphql-lint-disable-next-line noDeprecated requireIdField -- legacy API\nquery { hello }";
+        let directives = parse_ignore_directives(source);
+        assert_eq!(directives.len(), 1);
+        assert_eq!(directives[0].scope, IgnoreScope::NextLine);
+        assert_eq!(directives[0].keyword, "graphql-lint-disable-next-line");
+        assert_eq!(
+            directives[0].rule_names(),
+            vec!["noDeprecated", "requireIdField"]
+        );
+        let rule = &directives[0].rules[1];
+        assert_eq!(&source[rule.byte_offset..rule.byte_end], "requireIdField");
+        assert!(is_suppressed(&directives, 1, "noDeprecated"));
+        assert!(!is_suppressed(&directives, 2, "noDeprecated"));
+    }
+
+    #[test]
+    fn file_level_disable_covers_every_line() {
+        let source = "query A { a }\n# graphql-lint-disable: requireIdField\nquery B { b }";
+        let directives = parse_ignore_directives(source);
+        assert_eq!(directives.len(), 1);
+        assert_eq!(directives[0].scope, IgnoreScope::File);
+        assert!(is_suppressed(&directives, 0, "requireIdField"));
+        assert!(is_suppressed(&directives, 2, "requireIdField"));
+        assert!(!is_suppressed(&directives, 0, "noDeprecated"));
+
+        assert!(find_unused_rules(&directives, &[(0, "requireIdField")]).is_empty());
+        assert_eq!(
+            find_unused_rules(&directives, &[(0, "noDeprecated")]).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn legacy_ignore_requires_colon_before_rules() {
+        let source =
+            "# graphql-analyzer-ignore noDeprecated\n# graphql-lint-disablefoo\nquery { hello }";
+        assert!(parse_ignore_directives(source).is_empty());
+    }
 }
@@ -4,6 +4,7 @@ mod config;
 mod diagnostics;
 pub mod eslint_disable;
 pub mod ignore;
+mod plugins;
 mod registry;
 mod rules;
 mod schema_utils;
@@ -14,12 +15,19 @@ pub use config::{LintConfig, LintRuleConfig, LintSeverity};
 // New architecture exports
 pub use diagnostics::{
     rule_doc_url, CodeFix, CodeSuggestion, DiagnosticTag, LintDiagnostic,
-    LintSeverity as DiagnosticSeverity, OffsetRange, TextEdit,
+    LintSeverity as DiagnosticSeverity, OffsetRange, RelatedSpan, TextEdit,
 };
 pub use graphql_syntax::SourceSpan;
+pub use plugins::{LintPlugin, LintPlugins};
 pub use registry::{
-    all_rule_info, all_rule_names, document_schema_rules, project_rules, standalone_document_rules,
-    standalone_schema_rules, RuleCategory, RuleInfo,
+    all_rule_info, all_rule_names, document_schema_rules, project_rules, rules_manifest,
+    standalone_document_rules, standalone_schema_rules, RuleCategory, RuleInfo, RuleManifestEntry,
+};
+// Structured deprecation usages and replacements, shared by `noDeprecated`,
+// schema validation and the IDE
+pub use rules::{
+    deprecation_replacement, deprecation_replacement_references, find_deprecated_usages,
+    DeprecatedElementKind, DeprecatedUsage, FieldReplacement, ReplacementReference,
 };
 pub use traits::{
     DocumentSchemaLintRule, LintRule, ProjectLintRule, StandaloneDocumentLintRule,
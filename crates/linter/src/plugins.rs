@@ -0,0 +1,410 @@
+//! Lint rules loaded from WebAssembly modules.
+//!
+//! Organizations can ship rules of their own without forking the linter: a
+//! plugin is a core WebAssembly module declared under `lint.plugins` and
+//! configured under `lint.rules` like any built-in rule. It runs once per
+//! file, each time in a fresh instance with no imports, a fuel budget and
+//! a memory cap, so it can't reach the filesystem or the network, hang the
+//! analyzer or exhaust its memory.
+//!
+//! The module exports its `memory` and two functions:
+//!
+//! - `alloc(len: i32) -> i32` returns a buffer of `len` bytes for the input
+//! - `check(ptr: i32, len: i32) -> i64` reads the input and returns where
+//!   it wrote its output, as `ptr << 32 | len`
+//!
+//! The input is a JSON object:
+//!
+//! ```json
+//! {"uri":"file:///app/schema.graphql","kind":"schema","source":"...","hir":{...},"options":null}
+//! ```
+//!
+//! `kind` is `schema` or `executable`, and `hir` is the file's type,
+//! directive, operation and fragment definitions as `graphql-hir`
+//! serializes them, with ranges relative to the GraphQL block they're in.
+//! `options` are the rule's configured options. The output is a JSON array
+//! of diagnostics, with byte offsets into `source`:
+//!
+//! ```json
+//! [{"message":"`User` has no @owner","start":5,"end":9,"help":"Add @owner(team: ...)"}]
+//! ```
+//!
+//! `help` and a documentation `url` are optional.
+//!
+//! Only builds with the `wasm-plugins` feature can run plugins; elsewhere,
+//! declaring one is a configuration error.
+
+use std::path::Path;
+
+use graphql_base_db::{FileContent, FileId, FileMetadata};
+use serde::Deserialize;
+
+use crate::config::LintConfig;
+use crate::diagnostics::LintDiagnostic;
+
+/// Fuel for one `check` call. Fuel is spent roughly one unit per
+/// instruction, so this is far more than a rule needs for any real file.
+#[cfg(feature = "wasm-plugins")]
+const FUEL_PER_CHECK: u64 = 2_000_000_000;
+
+/// The most linear memory one `check` call's instance may grow to. Growing
+/// past it traps, which fails the plugin rather than the process.
+#[cfg(feature = "wasm-plugins")]
+const MEMORY_PER_CHECK: usize = 256 << 20;
+
+/// The plugin rules declared by a lint config, compiled and ready to run
+#[derive(Default)]
+pub struct LintPlugins {
+    plugins: Vec<LintPlugin>,
+}
+
+impl std::fmt::Debug for LintPlugins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.plugins.iter().map(LintPlugin::name))
+            .finish()
+    }
+}
+
+impl LintPlugins {
+    /// Compile the plugins declared in `config`, resolving their paths
+    /// against `base_dir`
+    pub fn load(config: &LintConfig, base_dir: &Path) -> Result<Self, String> {
+        let declared = config.plugins();
+        if declared.is_empty() {
+            return Ok(Self::default());
+        }
+
+        compile(&declared, base_dir).map(|plugins| Self { plugins })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    #[must_use]
+    pub fn plugins(&self) -> &[LintPlugin] {
+        &self.plugins
+    }
+}
+
+/// A lint rule implemented by a WebAssembly module
+pub struct LintPlugin {
+    name: String,
+    #[cfg(feature = "wasm-plugins")]
+    module: wasmtime::Module,
+}
+
+/// What a plugin's store holds while `check` runs
+#[cfg(feature = "wasm-plugins")]
+struct PluginState {
+    limits: wasmtime::StoreLimits,
+}
+
+/// One diagnostic in a plugin's output
+#[derive(Debug, Deserialize)]
+struct PluginDiagnostic {
+    message: String,
+    start: usize,
+    end: usize,
+    #[serde(default)]
+    help: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+impl LintPlugin {
+    /// The rule name the plugin was declared under
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Run the rule on a file
+    ///
+    /// A plugin that traps, runs out of fuel or returns malformed output
+    /// reports that as a diagnostic at the start of the file, so a broken
+    /// rule is visible rather than silently passing.
+    pub fn check(
+        &self,
+        db: &dyn graphql_hir::GraphQLHirDatabase,
+        file_id: FileId,
+        content: FileContent,
+        metadata: FileMetadata,
+        options: Option<&serde_json::Value>,
+    ) -> Vec<LintDiagnostic> {
+        let source = content.text(db);
+        let structure = graphql_hir::file_structure(db, file_id, content, metadata);
+        let kind = if metadata.is_schema(db) {
+            "schema"
+        } else {
+            "executable"
+        };
+        let input = serde_json::json!({
+            "uri": metadata.uri(db).as_str(),
+            "kind": kind,
+            "source": &*source,
+            "hir": &*structure,
+            "options": options,
+        });
+
+        match self.run(input.to_string().as_bytes()) {
+            Ok(diagnostics) => diagnostics
+                .into_iter()
+                .map(|diagnostic| {
+                    // Offsets are clamped into the file and widened to whole
+                    // characters, so a sloppy plugin can't split one
+                    let end = ceil_char_boundary(&source, diagnostic.end.min(source.len()));
+                    let start = floor_char_boundary(&source, diagnostic.start.min(end));
+                    let lint =
+                        LintDiagnostic::warning(span(start, end), diagnostic.message, &self.name);
+                    let lint = match diagnostic.help {
+                        Some(help) => lint.with_help(help),
+                        None => lint,
+                    };
+                    match diagnostic.url {
+                        Some(url) => lint.with_url(url),
+                        None => lint,
+                    }
+                })
+                .collect(),
+            Err(e) => {
+                tracing::warn!(
+                    plugin = %self.name,
+                    uri = %metadata.uri(db),
+                    "Lint plugin failed: {e}"
+                );
+                vec![LintDiagnostic::error(
+                    span(0, 0),
+                    format!("Lint plugin `{}` failed: {e}", self.name),
+                    &self.name,
+                )]
+            }
+        }
+    }
+
+    /// Pass `input` to the module's `check` and parse what it returns
+    #[cfg(feature = "wasm-plugins")]
+    fn run(&self, input: &[u8]) -> Result<Vec<PluginDiagnostic>, String> {
+        let limits = wasmtime::StoreLimitsBuilder::new()
+            .memory_size(MEMORY_PER_CHECK)
+            .trap_on_grow_failure(true)
+            .build();
+        let mut store = wasmtime::Store::new(self.module.engine(), PluginState { limits });
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(FUEL_PER_CHECK).map_err(|e| e.to_string())?;
+        let instance =
+            wasmtime::Instance::new(&mut store, &self.module, &[]).map_err(|e| e.to_string())?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or("the module doesn't export `memory`")?;
+        let alloc = instance
+            .get_typed_func::<u32, u32>(&mut store, "alloc")
+            .map_err(|e| e.to_string())?;
+        let check = instance
+            .get_typed_func::<(u32, u32), u64>(&mut store, "check")
+            .map_err(|e| e.to_string())?;
+
+        let len = u32::try_from(input.len()).map_err(|_| "the file is too large")?;
+        let ptr = alloc.call(&mut store, len).map_err(|e| e.to_string())?;
+        memory
+            .write(&mut store, ptr as usize, input)
+            .map_err(|e| e.to_string())?;
+        let packed = check
+            .call(&mut store, (ptr, len))
+            .map_err(|e| e.to_string())?;
+
+        let out_ptr = usize::try_from(packed >> 32).map_err(|e| e.to_string())?;
+        let out_len = usize::try_from(packed & 0xffff_ffff).map_err(|e| e.to_string())?;
+        // Checked before allocating, so a bogus length can't ask for gigabytes
+        if out_ptr
+            .checked_add(out_len)
+            .is_none_or(|end| end > memory.data_size(&store))
+        {
+            return Err("the output lies outside the module's memory".to_string());
+        }
+        let mut output = vec![0; out_len];
+        memory
+            .read(&store, out_ptr, &mut output)
+            .map_err(|e| e.to_string())?;
+        serde_json::from_slice(&output).map_err(|e| format!("invalid output: {e}"))
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    #[allow(clippy::unused_self)]
+    fn run(&self, _input: &[u8]) -> Result<Vec<PluginDiagnostic>, String> {
+        Err("this build doesn't include the `wasm-plugins` feature".to_string())
+    }
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn compile(declared: &[(&str, &str)], base_dir: &Path) -> Result<Vec<LintPlugin>, String> {
+    let mut engine_config = wasmtime::Config::new();
+    engine_config.consume_fuel(true);
+    let engine = wasmtime::Engine::new(&engine_config)
+        .map_err(|e| format!("Failed to start the WebAssembly runtime: {e}"))?;
+    declared
+        .iter()
+        .map(|(name, path)| {
+            let path = base_dir.join(path);
+            let module = wasmtime::Module::from_file(&engine, &path).map_err(|e| {
+                format!(
+                    "Failed to load lint plugin '{name}' from {}: {e}",
+                    path.display()
+                )
+            })?;
+            Ok(LintPlugin {
+                name: (*name).to_string(),
+                module,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn compile(declared: &[(&str, &str)], _base_dir: &Path) -> Result<Vec<LintPlugin>, String> {
+    let names: Vec<&str> = declared.iter().map(|(name, _)| *name).collect();
+    Err(format!(
+        "Lint plugins ({}) can't run: this build doesn't include the `wasm-plugins` feature",
+        names.join(", ")
+    ))
+}
+
+/// The start of the character `offset` falls inside
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    (0..=offset)
+        .rev()
+        .find(|&i| source.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// The end of the character `offset` falls inside
+fn ceil_char_boundary(source: &str, offset: usize) -> usize {
+    (offset..=source.len())
+        .find(|&i| source.is_char_boundary(i))
+        .unwrap_or(source.len())
+}
+
+fn span(start: usize, end: usize) -> graphql_syntax::SourceSpan {
+    graphql_syntax::SourceSpan {
+        start,
+        end,
+        line_offset: 0,
+        byte_offset: 0,
+        source: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_without_plugins() {
+        let plugins = LintPlugins::load(&LintConfig::recommended(), Path::new(".")).unwrap();
+        assert!(plugins.is_empty());
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_run_plugin() {
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&engine_config).unwrap();
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (data (i32.const 0) "[{\"message\":\"from plugin\",\"start\":0,\"end\":4}]")
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "check") (param i32 i32) (result i64) (i64.const 45)))
+            "#,
+        )
+        .unwrap();
+        let plugin = LintPlugin {
+            name: "fromPlugin".to_string(),
+            module,
+        };
+
+        let diagnostics = plugin.run(b"{}").unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "from plugin");
+        assert_eq!((diagnostics[0].start, diagnostics[0].end), (0, 4));
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_run_rejects_output_outside_memory() {
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&engine_config).unwrap();
+        // Claims 4 GiB of output at address 0 of a 64 KiB memory
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "check") (param i32 i32) (result i64) (i64.const 0xffffffff)))
+            "#,
+        )
+        .unwrap();
+        let plugin = LintPlugin {
+            name: "outOfBounds".to_string(),
+            module,
+        };
+
+        let error = plugin.run(b"{}").unwrap_err();
+        assert!(error.contains("outside the module's memory"), "{error}");
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    #[test]
+    fn test_run_fails_when_memory_grows_past_limit() {
+        let mut engine_config = wasmtime::Config::new();
+        engine_config.consume_fuel(true);
+        let engine = wasmtime::Engine::new(&engine_config).unwrap();
+        // Asks for 1 GiB more memory (16384 pages of 64 KiB) before answering
+        let module = wasmtime::Module::new(
+            &engine,
+            r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "check") (param i32 i32) (result i64)
+                (drop (memory.grow (i32.const 16384)))
+                (i64.const 0)))
+            "#,
+        )
+        .unwrap();
+        let plugin = LintPlugin {
+            name: "memoryHog".to_string(),
+            module,
+        };
+
+        assert!(plugin.run(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_offsets_snap_to_char_boundaries() {
+        let source = "# héllo";
+        // Byte 4 is inside the two-byte `é`
+        assert_eq!(floor_char_boundary(source, 4), 3);
+        assert_eq!(ceil_char_boundary(source, 4), 5);
+        assert_eq!(floor_char_boundary(source, 3), 3);
+        assert_eq!(ceil_char_boundary(source, source.len()), source.len());
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    #[test]
+    fn test_load_needs_feature() {
+        let config: LintConfig = serde_json::from_value(serde_json::json!({
+            "plugins": { "requireOwner": "./require-owner.wasm" }
+        }))
+        .unwrap();
+        let error = LintPlugins::load(&config, Path::new(".")).unwrap_err();
+        assert!(error.contains("requireOwner"));
+    }
+}
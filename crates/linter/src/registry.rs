@@ -1,11 +1,12 @@
-use crate::diagnostics::LintSeverity;
+use crate::diagnostics::{rule_doc_url, LintSeverity};
 /// Registry of all available lint rules
 use crate::rules::{
-    AlphabetizeRuleImpl, DescriptionStyleRuleImpl, InputNameRuleImpl,
-    LoneExecutableDefinitionRuleImpl, MatchDocumentFilenameRuleImpl, NamingConventionRuleImpl,
-    NoAnonymousOperationsRuleImpl, NoDeprecatedRuleImpl, NoDuplicateFieldsRuleImpl,
-    NoHashtagDescriptionRuleImpl, NoOnePlaceFragmentsRuleImpl, NoRootTypeRuleImpl,
-    NoScalarResultTypeOnMutationRuleImpl, NoTypenamePrefixRuleImpl, NoUnreachableTypesRuleImpl,
+    AlphabetizeRuleImpl, DescriptionStyleRuleImpl, EnumValueConventionRuleImpl,
+    FieldNamingConventionRuleImpl, InputNameRuleImpl, LoneExecutableDefinitionRuleImpl,
+    MatchDocumentFilenameRuleImpl, NamingConventionRuleImpl, NoAnonymousOperationsRuleImpl,
+    NoDeprecatedRuleImpl, NoDuplicateFieldsRuleImpl, NoHashtagDescriptionRuleImpl,
+    NoOnePlaceFragmentsRuleImpl, NoRootTypeRuleImpl, NoScalarResultTypeOnMutationRuleImpl,
+    NoTypenamePrefixRuleImpl, NoUnreachableTypesRuleImpl, NoUnsupportedIncrementalDeliveryRuleImpl,
     NoUnusedFieldsRuleImpl, NoUnusedFragmentsRuleImpl, NoUnusedVariablesRuleImpl,
     OperationNameSuffixRuleImpl, RedundantFieldsRuleImpl, RelayArgumentsRuleImpl,
     RelayConnectionTypesRuleImpl, RelayEdgeTypesRuleImpl, RelayPageInfoRuleImpl,
@@ -13,13 +14,14 @@ use crate::rules::{
     RequireFieldOfTypeQueryInMutationResultRuleImpl, RequireImportFragmentRuleImpl,
     RequireNullableFieldsWithOneofRuleImpl, RequireNullableResultInRootRuleImpl,
     RequireSelectionsRuleImpl, RequireTypePatternWithOneofRuleImpl, RestyFieldNamesRuleImpl,
-    SelectionSetDepthRuleImpl, StrictIdInTypesRuleImpl, UniqueEnumValueNamesRuleImpl,
-    UniqueNamesRuleImpl,
+    SelectionSetDepthRuleImpl, StrictIdInTypesRuleImpl, TypeNamingConventionRuleImpl,
+    UniqueEnumValueNamesRuleImpl, UniqueNamesRuleImpl,
 };
 use crate::traits::{
     DocumentSchemaLintRule, LintRule, ProjectLintRule, StandaloneDocumentLintRule,
     StandaloneSchemaLintRule,
 };
+use serde::Serialize;
 use std::fmt;
 use std::sync::{Arc, LazyLock};
 
@@ -49,6 +51,7 @@ static DOCUMENT_SCHEMA_RULES: LazyLock<Vec<Arc<dyn DocumentSchemaLintRule>>> =
     LazyLock::new(|| {
         vec![
             Arc::new(NoDeprecatedRuleImpl),
+            Arc::new(NoUnsupportedIncrementalDeliveryRuleImpl),
             Arc::new(RequireSelectionsRuleImpl),
         ]
     });
@@ -71,6 +74,8 @@ static STANDALONE_SCHEMA_RULES: LazyLock<Vec<Arc<dyn StandaloneSchemaLintRule>>>
         vec![
             Arc::new(AlphabetizeRuleImpl),
             Arc::new(DescriptionStyleRuleImpl),
+            Arc::new(EnumValueConventionRuleImpl),
+            Arc::new(FieldNamingConventionRuleImpl),
             Arc::new(InputNameRuleImpl),
             Arc::new(NamingConventionRuleImpl),
             Arc::new(NoHashtagDescriptionRuleImpl),
@@ -91,6 +96,7 @@ static STANDALONE_SCHEMA_RULES: LazyLock<Vec<Arc<dyn StandaloneSchemaLintRule>>>
             Arc::new(RequireTypePatternWithOneofRuleImpl),
             Arc::new(RestyFieldNamesRuleImpl),
             Arc::new(StrictIdInTypesRuleImpl),
+            Arc::new(TypeNamingConventionRuleImpl),
             Arc::new(UniqueEnumValueNamesRuleImpl),
         ]
     });
@@ -144,7 +150,8 @@ pub fn all_rule_names() -> Vec<&'static str> {
 }
 
 /// Category of a lint rule based on what it analyzes.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RuleCategory {
     Schema,
     Document,
@@ -179,39 +186,85 @@ fn collect_rule_info(rule: &dyn LintRule, category: RuleCategory) -> RuleInfo {
     }
 }
 
-/// Returns metadata for all registered lint rules, grouped by category.
-///
-/// Rules implemented for multiple sides (e.g. a rule that runs on both
-/// schemas and documents) are reported once, using the category of the
-/// first registry the rule appears in. Schema-side wins over document-side.
-#[must_use]
-pub fn all_rule_info() -> Vec<RuleInfo> {
+/// Visit every registered lint rule once, with its category.
+fn for_each_unique_rule(mut visit: impl FnMut(&dyn LintRule, RuleCategory)) {
     let mut seen = std::collections::HashSet::new();
-    let mut info = Vec::new();
-
-    let mut push_unique =
-        |rule: &dyn LintRule, category: RuleCategory, info: &mut Vec<RuleInfo>| {
-            if seen.insert(rule.name()) {
-                info.push(collect_rule_info(rule, category));
-            }
-        };
+    let mut visit_unique = |rule: &dyn LintRule, category: RuleCategory| {
+        if seen.insert(rule.name()) {
+            visit(rule, category);
+        }
+    };
 
     for rule in standalone_schema_rules() {
-        push_unique(rule.as_ref(), RuleCategory::Schema, &mut info);
+        visit_unique(rule.as_ref(), RuleCategory::Schema);
     }
     for rule in standalone_document_rules() {
-        push_unique(rule.as_ref(), RuleCategory::Document, &mut info);
+        visit_unique(rule.as_ref(), RuleCategory::Document);
     }
     for rule in document_schema_rules() {
-        push_unique(rule.as_ref(), RuleCategory::Document, &mut info);
+        visit_unique(rule.as_ref(), RuleCategory::Document);
     }
     for rule in project_rules() {
-        push_unique(rule.as_ref(), RuleCategory::Project, &mut info);
+        visit_unique(rule.as_ref(), RuleCategory::Project);
     }
+}
 
+/// Returns metadata for all registered lint rules, grouped by category.
+///
+/// Rules implemented for multiple sides (e.g. a rule that runs on both
+/// schemas and documents) are reported once, using the category of the
+/// first registry the rule appears in. Schema-side wins over document-side.
+#[must_use]
+pub fn all_rule_info() -> Vec<RuleInfo> {
+    let mut info = Vec::new();
+    for_each_unique_rule(|rule, category| info.push(collect_rule_info(rule, category)));
     info
 }
 
+/// Serializable description of a lint rule, as published by
+/// [`rules_manifest`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleManifestEntry {
+    /// The rule name used in `lint.rules` config (e.g. `"noDeprecated"`)
+    pub id: &'static str,
+    pub category: RuleCategory,
+    pub description: &'static str,
+    pub default_severity: LintSeverity,
+    /// Severity the `recommended` preset enables the rule at, if it's part
+    /// of the preset
+    pub recommended: Option<crate::config::LintSeverity>,
+    pub fixable: bool,
+    /// JSON Schema for the rule's options; `None` if it takes none
+    pub options_schema: Option<serde_json::Value>,
+    pub docs_url: String,
+}
+
+/// Machine-readable metadata for every lint rule, sorted by id.
+///
+/// This is the single source the docs site, editor settings UIs and config
+/// validation are generated from. GraphQL spec validation isn't included:
+/// those diagnostics come from apollo-compiler and have no stable rule ids
+/// or options.
+#[must_use]
+pub fn rules_manifest() -> Vec<RuleManifestEntry> {
+    let mut entries = Vec::new();
+    for_each_unique_rule(|rule, category| {
+        entries.push(RuleManifestEntry {
+            id: rule.name(),
+            category,
+            description: rule.description(),
+            default_severity: rule.default_severity(),
+            recommended: crate::config::LintConfig::recommended_severity(rule.name()),
+            fixable: rule.fixable(),
+            options_schema: rule.options_schema(),
+            docs_url: rule_doc_url(rule.name()),
+        });
+    });
+    entries.sort_by_key(|entry| entry.id);
+    entries
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -358,4 +411,51 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_rules_manifest_covers_all_rules() {
+        let manifest = rules_manifest();
+        let ids: Vec<_> = manifest.iter().map(|entry| entry.id).collect();
+        assert_eq!(ids, all_rule_names());
+
+        for entry in &manifest {
+            if let Some(schema) = &entry.options_schema {
+                assert_eq!(
+                    schema["type"], "object",
+                    "options schema for {} should describe an object",
+                    entry.id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rules_manifest_metadata() {
+        let manifest = rules_manifest();
+        let entry = |id: &str| manifest.iter().find(|entry| entry.id == id).unwrap();
+
+        assert!(entry("noUnusedFragments").fixable);
+        assert!(!entry("noDeprecated").fixable);
+        assert!(entry("selectionSetDepth").options_schema.is_some());
+        assert!(entry("noAnonymousOperations").options_schema.is_none());
+        assert_eq!(
+            entry("noAnonymousOperations").recommended,
+            Some(crate::config::LintSeverity::Error)
+        );
+        assert_eq!(entry("alphabetize").recommended, None);
+
+        let json = serde_json::to_value(entry("requireSelections")).unwrap();
+        assert_eq!(json["id"], "requireSelections");
+        assert_eq!(json["category"], "document");
+        assert_eq!(json["defaultSeverity"], "error");
+        assert_eq!(json["fixable"], true);
+        assert_eq!(
+            json["optionsSchema"]["properties"]["requireAllFields"]["type"],
+            "boolean"
+        );
+        assert!(json["docsUrl"]
+            .as_str()
+            .unwrap()
+            .ends_with("/rules/requireSelections/"));
+    }
 }
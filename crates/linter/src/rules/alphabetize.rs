@@ -149,6 +149,37 @@ impl LintRule for AlphabetizeRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "selections": {
+                    "oneOf": [
+                        { "type": "boolean" },
+                        { "type": "array", "items": { "enum": ["OperationDefinition", "FragmentDefinition"] } }
+                    ],
+                    "default": true
+                },
+                "arguments": {
+                    "oneOf": [{ "type": "boolean" }, { "type": "array", "items": { "type": "string" } }],
+                    "default": false
+                },
+                "variables": { "type": "boolean", "default": false },
+                "definitions": { "type": "boolean", "default": false },
+                "fields": {
+                    "oneOf": [{ "type": "boolean" }, { "type": "array", "items": { "type": "string" } }],
+                    "default": false
+                },
+                "values": { "type": "boolean", "default": false },
+                "groups": { "type": "array", "items": { "type": "string" } }
+            }
+        }))
+    }
 }
 
 impl StandaloneDocumentLintRule for AlphabetizeRuleImpl {
@@ -55,6 +55,15 @@ impl LintRule for DescriptionStyleRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "style": { "enum": ["inline", "block"], "default": "block" }
+            }
+        }))
+    }
 }
 
 impl StandaloneSchemaLintRule for DescriptionStyleRuleImpl {
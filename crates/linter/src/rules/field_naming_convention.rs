@@ -0,0 +1,160 @@
+use crate::diagnostics::{LintDiagnostic, LintSeverity};
+use crate::rules::naming_convention::NamingCase;
+use crate::rules::schema_naming::{allow_list_schema, naming_diagnostic, NamingAllowListOptions};
+use crate::schema_utils::raw_schema_type_defs;
+use crate::traits::{LintRule, StandaloneSchemaLintRule};
+use graphql_base_db::{FileId, ProjectFiles};
+use graphql_hir::TypeDefKind;
+use std::collections::HashMap;
+
+/// Lint rule that requires `camelCase` field names on object, interface and
+/// input object types
+///
+/// A single-purpose alternative to configuring `namingConvention` for
+/// fields; names listed in `allow` are skipped.
+pub struct FieldNamingConventionRuleImpl;
+
+impl LintRule for FieldNamingConventionRuleImpl {
+    fn name(&self) -> &'static str {
+        "fieldNamingConvention"
+    }
+
+    fn description(&self) -> &'static str {
+        "Requires schema field names to be camelCase"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(allow_list_schema())
+    }
+}
+
+impl StandaloneSchemaLintRule for FieldNamingConventionRuleImpl {
+    fn check(
+        &self,
+        db: &dyn graphql_hir::GraphQLHirDatabase,
+        project_files: ProjectFiles,
+        options: Option<&serde_json::Value>,
+    ) -> HashMap<FileId, Vec<LintDiagnostic>> {
+        let opts = NamingAllowListOptions::from_json(options);
+        let mut diagnostics_by_file: HashMap<FileId, Vec<LintDiagnostic>> = HashMap::new();
+
+        for (file_id, type_def) in raw_schema_type_defs(db, project_files) {
+            if !matches!(
+                type_def.kind,
+                TypeDefKind::Object | TypeDefKind::Interface | TypeDefKind::InputObject
+            ) {
+                continue;
+            }
+            for field in &type_def.fields {
+                if opts.is_exempt(&field.name) || NamingCase::Camel.check(&field.name) {
+                    continue;
+                }
+                diagnostics_by_file
+                    .entry(file_id)
+                    .or_default()
+                    .push(naming_diagnostic(
+                        "fieldNamingConvention",
+                        "FIELD_NAMING_CONVENTION",
+                        "Field",
+                        &field.name,
+                        field.name_range,
+                        NamingCase::Camel,
+                    ));
+            }
+        }
+
+        diagnostics_by_file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::StandaloneSchemaLintRule;
+    use graphql_base_db::{
+        DocumentFileIds, DocumentKind, FileContent, FileEntry, FileEntryMap, FileId, FileMetadata,
+        FileUri, Language, ProjectFiles, SchemaFileIds,
+    };
+    use graphql_ide_db::RootDatabase;
+    use std::sync::Arc;
+
+    fn create_schema_project(db: &RootDatabase, schema: &str) -> ProjectFiles {
+        let file_id = FileId::new(0);
+        let content = FileContent::new(db, Arc::from(schema));
+        let metadata = FileMetadata::new(
+            db,
+            file_id,
+            FileUri::new("file:///schema.graphql"),
+            Language::GraphQL,
+            DocumentKind::Schema,
+        );
+        let entry = FileEntry::new(db, content, metadata);
+        let mut entries = std::collections::HashMap::new();
+        entries.insert(file_id, entry);
+        let schema_file_ids = SchemaFileIds::new(db, Arc::new(vec![file_id]));
+        let document_file_ids = DocumentFileIds::new(db, Arc::new(vec![]));
+        let file_entry_map = FileEntryMap::new(db, Arc::new(entries));
+        ProjectFiles::new(
+            db,
+            schema_file_ids,
+            document_file_ids,
+            graphql_base_db::ResolvedSchemaFileIds::new(db, std::sync::Arc::new(vec![])),
+            file_entry_map,
+            graphql_base_db::FilePathMap::new(
+                db,
+                Arc::new(std::collections::HashMap::new()),
+                Arc::new(std::collections::HashMap::new()),
+            ),
+        )
+    }
+
+    fn check(schema: &str, options: Option<&serde_json::Value>) -> Vec<LintDiagnostic> {
+        let db = RootDatabase::default();
+        let project_files = create_schema_project(&db, schema);
+        RULE.check(&db, project_files, options)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    const RULE: FieldNamingConventionRuleImpl = FieldNamingConventionRuleImpl;
+
+    #[test]
+    fn test_camel_case_fields_pass() {
+        let all = check(
+            "type User { id: ID! firstName: String __internal: String }\ninput UserInput { lastName: String }",
+            None,
+        );
+        assert!(all.is_empty());
+    }
+
+    #[test]
+    fn test_reports_with_rename_suggestion() {
+        let all = check(
+            "type User { first_name: String }\ninput UserInput { LastName: String }",
+            None,
+        );
+        assert_eq!(all.len(), 2);
+        assert_eq!(
+            all.iter()
+                .find(|d| d.message.contains("first_name"))
+                .unwrap()
+                .suggestions[0]
+                .fix
+                .edits[0]
+                .new_text,
+            "firstName"
+        );
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let options = serde_json::json!({ "allow": ["legacy_id"] });
+        let all = check("type User { legacy_id: ID! }", Some(&options));
+        assert!(all.is_empty());
+    }
+}
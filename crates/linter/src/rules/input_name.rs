@@ -65,6 +65,18 @@ impl LintRule for InputNameRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "checkInputType": { "type": "boolean", "default": false },
+                "caseSensitiveInputType": { "type": "boolean", "default": true },
+                "checkQueries": { "type": "boolean", "default": false },
+                "checkMutations": { "type": "boolean", "default": true }
+            }
+        }))
+    }
 }
 
 impl StandaloneSchemaLintRule for InputNameRuleImpl {
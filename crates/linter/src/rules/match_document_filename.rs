@@ -174,6 +174,35 @@ impl LintRule for MatchDocumentFilenameRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        let style = serde_json::json!({
+            "enum": ["camelCase", "PascalCase", "snake_case", "UPPER_CASE", "kebab-case", "matchDocumentStyle"]
+        });
+        let definition = serde_json::json!({
+            "oneOf": [
+                style,
+                {
+                    "type": "object",
+                    "properties": {
+                        "style": style,
+                        "prefix": { "type": "string" },
+                        "suffix": { "type": "string" }
+                    }
+                }
+            ]
+        });
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": definition,
+                "mutation": definition,
+                "subscription": definition,
+                "fragment": definition,
+                "fileExtension": { "type": "string" }
+            }
+        }))
+    }
 }
 
 impl StandaloneDocumentLintRule for MatchDocumentFilenameRuleImpl {
@@ -400,7 +429,7 @@ fn split_words(name: &str) -> Vec<String> {
     words
 }
 
-fn to_camel_case(name: &str) -> String {
+pub(super) fn to_camel_case(name: &str) -> String {
     let words = split_words(name);
     let mut result = String::new();
     for (i, word) in words.iter().enumerate() {
@@ -417,7 +446,7 @@ fn to_camel_case(name: &str) -> String {
     result
 }
 
-fn to_pascal_case(name: &str) -> String {
+pub(super) fn to_pascal_case(name: &str) -> String {
     let words = split_words(name);
     let mut result = String::new();
     for word in &words {
@@ -430,7 +459,7 @@ fn to_pascal_case(name: &str) -> String {
     result
 }
 
-fn to_snake_case(name: &str) -> String {
+pub(super) fn to_snake_case(name: &str) -> String {
     let words = split_words(name);
     words
         .iter()
@@ -439,7 +468,7 @@ fn to_snake_case(name: &str) -> String {
         .join("_")
 }
 
-fn to_upper_case(name: &str) -> String {
+pub(super) fn to_upper_case(name: &str) -> String {
     let words = split_words(name);
     words
         .iter()
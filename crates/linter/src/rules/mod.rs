@@ -27,6 +27,8 @@ pub fn get_operation_kind(op_type: &cst::OperationType) -> OperationKind {
 
 mod alphabetize;
 mod description_style;
+mod enum_value_convention;
+mod field_naming_convention;
 mod input_name;
 mod lone_executable_definition;
 mod match_document_filename;
@@ -40,6 +42,7 @@ mod no_root_type;
 mod no_scalar_result_type_on_mutation;
 mod no_typename_prefix;
 mod no_unreachable_types;
+mod no_unsupported_incremental_delivery;
 mod no_unused_fields;
 mod no_unused_fragments;
 mod no_unused_variables;
@@ -59,8 +62,10 @@ mod require_nullable_result_in_root;
 mod require_selections;
 mod require_type_pattern_with_oneof;
 mod resty_field_names;
+mod schema_naming;
 mod selection_set_depth;
 mod strict_id_in_types;
+mod type_naming_convention;
 mod unique_enum_value_names;
 mod unique_names;
 
@@ -69,12 +74,18 @@ mod upstream;
 
 pub use alphabetize::AlphabetizeRuleImpl;
 pub use description_style::DescriptionStyleRuleImpl;
+pub use enum_value_convention::EnumValueConventionRuleImpl;
+pub use field_naming_convention::FieldNamingConventionRuleImpl;
 pub use input_name::InputNameRuleImpl;
 pub use lone_executable_definition::LoneExecutableDefinitionRuleImpl;
 pub use match_document_filename::MatchDocumentFilenameRuleImpl;
 pub use naming_convention::NamingConventionRuleImpl;
 pub use no_anonymous_operations::NoAnonymousOperationsRuleImpl;
-pub use no_deprecated::NoDeprecatedRuleImpl;
+pub use no_deprecated::{
+    deprecation_replacement, deprecation_replacement_references, find_deprecated_usages,
+    DeprecatedElementKind, DeprecatedUsage, FieldReplacement, NoDeprecatedRuleImpl,
+    ReplacementReference,
+};
 pub use no_duplicate_fields::NoDuplicateFieldsRuleImpl;
 pub use no_hashtag_description::NoHashtagDescriptionRuleImpl;
 pub use no_one_place_fragments::NoOnePlaceFragmentsRuleImpl;
@@ -82,6 +93,7 @@ pub use no_root_type::NoRootTypeRuleImpl;
 pub use no_scalar_result_type_on_mutation::NoScalarResultTypeOnMutationRuleImpl;
 pub use no_typename_prefix::NoTypenamePrefixRuleImpl;
 pub use no_unreachable_types::NoUnreachableTypesRuleImpl;
+pub use no_unsupported_incremental_delivery::NoUnsupportedIncrementalDeliveryRuleImpl;
 pub use no_unused_fields::NoUnusedFieldsRuleImpl;
 pub use no_unused_fragments::NoUnusedFragmentsRuleImpl;
 pub use no_unused_variables::NoUnusedVariablesRuleImpl;
@@ -103,5 +115,6 @@ pub use require_type_pattern_with_oneof::RequireTypePatternWithOneofRuleImpl;
 pub use resty_field_names::RestyFieldNamesRuleImpl;
 pub use selection_set_depth::SelectionSetDepthRuleImpl;
 pub use strict_id_in_types::StrictIdInTypesRuleImpl;
+pub use type_naming_convention::TypeNamingConventionRuleImpl;
 pub use unique_enum_value_names::UniqueEnumValueNamesRuleImpl;
 pub use unique_names::UniqueNamesRuleImpl;
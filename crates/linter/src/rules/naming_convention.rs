@@ -1,4 +1,7 @@
 use crate::diagnostics::{LintDiagnostic, LintSeverity};
+use crate::rules::match_document_filename::{
+    to_camel_case, to_pascal_case, to_snake_case, to_upper_case,
+};
 use crate::rules::{get_operation_kind, OperationKind};
 use crate::traits::{LintRule, StandaloneDocumentLintRule, StandaloneSchemaLintRule};
 use apollo_parser::cst::{self, CstNode};
@@ -28,7 +31,7 @@ pub enum NamingCase {
 }
 
 impl NamingCase {
-    fn check(self, name: &str) -> bool {
+    pub(super) fn check(self, name: &str) -> bool {
         match self {
             NamingCase::Camel => is_camel_case(name),
             NamingCase::Pascal => is_pascal_case(name),
@@ -37,7 +40,7 @@ impl NamingCase {
         }
     }
 
-    fn label(self) -> &'static str {
+    pub(super) fn label(self) -> &'static str {
         match self {
             NamingCase::Camel => "camelCase",
             NamingCase::Pascal => "PascalCase",
@@ -45,6 +48,16 @@ impl NamingCase {
             NamingCase::Upper => "UPPER_CASE",
         }
     }
+
+    /// `name` rewritten in this case, for rename suggestions
+    pub(super) fn convert(self, name: &str) -> String {
+        match self {
+            NamingCase::Camel => to_camel_case(name),
+            NamingCase::Pascal => to_pascal_case(name),
+            NamingCase::Snake => to_snake_case(name),
+            NamingCase::Upper => to_upper_case(name),
+        }
+    }
 }
 
 #[allow(clippy::expect_used)] // safe: empty check above guarantees chars().next() is Some
@@ -893,6 +906,56 @@ impl LintRule for NamingConventionRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        let kind = serde_json::json!({
+            "oneOf": [
+                { "enum": ["camelCase", "PascalCase", "snake_case", "UPPER_CASE"] },
+                {
+                    "type": "object",
+                    "properties": {
+                        "style": { "enum": ["camelCase", "PascalCase", "snake_case", "UPPER_CASE"] },
+                        "prefix": { "type": "string" },
+                        "suffix": { "type": "string" },
+                        "forbiddenPrefixes": { "type": "array", "items": { "type": "string" } },
+                        "forbiddenSuffixes": { "type": "array", "items": { "type": "string" } },
+                        "requiredPrefixes": { "type": "array", "items": { "type": "string" } },
+                        "requiredSuffixes": { "type": "array", "items": { "type": "string" } },
+                        "requiredPattern": { "type": "string" },
+                        "forbiddenPatterns": { "type": "array", "items": { "type": "string" } },
+                        "ignorePattern": { "type": "string" },
+                        "allowLeadingUnderscore": { "type": "boolean" },
+                        "allowTrailingUnderscore": { "type": "boolean" }
+                    }
+                }
+            ]
+        });
+        // Any other key is an ESLint-style selector (e.g.
+        // `FieldDefinition[parent.name.value=Query]`) with the same shape
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "allowLeadingUnderscore": { "type": "boolean", "default": false },
+                "allowTrailingUnderscore": { "type": "boolean", "default": false },
+                "types": kind,
+                "OperationDefinition": kind,
+                "FragmentDefinition": kind,
+                "VariableDefinition": kind,
+                "FieldDefinition": kind,
+                "InputValueDefinition": kind,
+                "Argument": kind,
+                "DirectiveDefinition": kind,
+                "EnumValueDefinition": kind,
+                "ObjectTypeDefinition": kind,
+                "InterfaceTypeDefinition": kind,
+                "EnumTypeDefinition": kind,
+                "UnionTypeDefinition": kind,
+                "ScalarTypeDefinition": kind,
+                "InputObjectTypeDefinition": kind
+            },
+            "additionalProperties": kind
+        }))
+    }
 }
 
 impl StandaloneDocumentLintRule for NamingConventionRuleImpl {
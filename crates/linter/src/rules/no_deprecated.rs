@@ -50,20 +50,345 @@ impl DocumentSchemaLintRule for NoDeprecatedRuleImpl {
 
         // Unified: process all documents (works for both pure GraphQL and TS/JS)
         for doc in parse.documents() {
-            let doc_cst = doc.tree.document();
-            check_document_for_deprecated(&doc_cst, schema_types, &mut diagnostics, &doc);
+            for usage in find_deprecated_usages(doc.tree, schema_types) {
+                diagnostics.push(usage_diagnostic(&usage, &doc));
+            }
         }
 
         diagnostics
     }
 }
 
+/// The kind of schema element a [`DeprecatedUsage`] refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeprecatedElementKind {
+    Field,
+    Argument,
+    EnumValue,
+    InputField,
+}
+
+/// A use of a deprecated schema element in an executable document.
+///
+/// Ranges are byte offsets into the GraphQL block the usage was found in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecatedUsage {
+    pub kind: DeprecatedElementKind,
+    pub name: String,
+    /// Schema coordinate of the element, e.g. `User.username`,
+    /// `Query.users(status:)` or `Status.ARCHIVED`
+    pub coordinate: String,
+    /// The `@deprecated` reason, if the schema gives one
+    pub reason: Option<String>,
+    /// Range of the element's name
+    pub name_range: (usize, usize),
+    /// Range of the whole field, argument or value, i.e. what removing the
+    /// usage deletes
+    pub node_range: (usize, usize),
+    /// The field the deprecation reason points to, when it can be swapped in.
+    /// Only set for fields.
+    pub replacement: Option<FieldReplacement>,
+}
+
+/// A field that can stand in for a deprecated one, named by its deprecation
+/// reason
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldReplacement {
+    /// Name of the replacement field
+    pub name: String,
+    /// Text to put in place of the deprecated field's name. Unaliased fields
+    /// keep their response key by aliasing the replacement to the old name.
+    pub text: String,
+}
+
+/// Find the field a deprecation reason points to, following the
+/// "Use `newField` instead" convention.
+///
+/// The reference may be a field name or a `Type.field` coordinate, with or
+/// without backticks. Returns its byte range within `reason`.
+pub fn deprecation_replacement(reason: &str) -> Option<std::ops::Range<usize>> {
+    for (index, _) in reason.char_indices() {
+        let rest = &reason[index..];
+        if !(rest.starts_with("Use ") || rest.starts_with("use ")) {
+            continue;
+        }
+        // "Use" must start a word, so "Reuse `x` instead" doesn't count
+        if reason[..index]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric)
+        {
+            continue;
+        }
+
+        let after_use = &reason[index + 4..];
+        let trimmed = after_use.trim_start();
+        let backticked = trimmed.starts_with('`');
+        let start = reason.len() - trimmed.len() + usize::from(backticked);
+        let end = reason[start..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .map_or(reason.len(), |len| start + len);
+        let reference = &reason[start..end];
+        let valid = reference.split('.').count() <= 2
+            && reference
+                .split('.')
+                .all(|part| part.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_'));
+        if !valid {
+            continue;
+        }
+
+        let mut tail = &reason[end..];
+        if backticked {
+            let Some(closed) = tail.strip_prefix('`') else {
+                continue;
+            };
+            tail = closed;
+        }
+        let trimmed_tail = tail.trim_start();
+        if trimmed_tail.len() < tail.len() && trimmed_tail.starts_with("instead") {
+            return Some(start..end);
+        }
+    }
+    None
+}
+
+/// Find every use of a deprecated field, argument, enum value or input
+/// object field in `tree`.
+pub fn find_deprecated_usages(
+    tree: &apollo_parser::SyntaxTree,
+    schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
+) -> Vec<DeprecatedUsage> {
+    let mut usages = Vec::new();
+    check_document_for_deprecated(&tree.document(), schema_types, &mut usages);
+    usages
+}
+
+/// A replacement named by the `@deprecated(reason:)` of a schema field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplacementReference {
+    /// The object or interface type the deprecated field belongs to
+    pub type_name: String,
+    /// The replacement's type: the one given in a `Type.field` reference,
+    /// otherwise `type_name`
+    pub target_type: String,
+    /// The replacement field's name
+    pub target_field: String,
+    /// Range of the reference inside the reason string
+    pub range: (usize, usize),
+}
+
+/// Find the replacements named by deprecation reasons on the fields of
+/// object and interface types in `tree`.
+pub fn deprecation_replacement_references(
+    tree: &apollo_parser::SyntaxTree,
+) -> Vec<ReplacementReference> {
+    let mut references = Vec::new();
+    for field in tree
+        .document()
+        .syntax()
+        .descendants()
+        .filter_map(cst::FieldDefinition::cast)
+    {
+        let Some(type_name) = field.syntax().ancestors().find_map(|node| {
+            let name = cst::ObjectTypeDefinition::cast(node.clone())
+                .and_then(|def| def.name())
+                .or_else(|| cst::ObjectTypeExtension::cast(node.clone()).and_then(|e| e.name()))
+                .or_else(|| cst::InterfaceTypeDefinition::cast(node.clone()).and_then(|d| d.name()))
+                .or_else(|| cst::InterfaceTypeExtension::cast(node).and_then(|e| e.name()))?;
+            Some(name.text().to_string())
+        }) else {
+            continue;
+        };
+
+        let reasons = field
+            .directives()
+            .into_iter()
+            .flat_map(|directives| directives.directives())
+            .filter(|directive| directive.name().is_some_and(|n| n.text() == "deprecated"))
+            .flat_map(|directive| directive.arguments())
+            .flat_map(|arguments| arguments.arguments())
+            .filter(|argument| argument.name().is_some_and(|n| n.text() == "reason"));
+        for argument in reasons {
+            let Some(cst::Value::StringValue(value)) = argument.value() else {
+                continue;
+            };
+            // The raw text, quotes included, so the range maps straight
+            // back to the source
+            let text = value.syntax().text().to_string();
+            let Some(range) = deprecation_replacement(&text) else {
+                continue;
+            };
+            let (target_type, target_field) = match text[range.clone()].split_once('.') {
+                Some((target_type, target_field)) => (target_type.to_string(), target_field),
+                None => (type_name.clone(), &text[range.clone()]),
+            };
+            let start: usize = value.syntax().text_range().start().into();
+            references.push(ReplacementReference {
+                type_name: type_name.clone(),
+                target_type,
+                target_field: target_field.to_string(),
+                range: (start + range.start, start + range.end),
+            });
+        }
+    }
+    references
+}
+
+fn usage_diagnostic(
+    usage: &DeprecatedUsage,
+    doc: &graphql_syntax::DocumentRef<'_>,
+) -> LintDiagnostic {
+    // graphql-eslint format:
+    //   `Field "name" is marked as deprecated in your GraphQL schema (reason: ...)`
+    // displayNodeName uses the kind label ("field") capitalized in the
+    // rule's reporter.
+    let (label, removal) = match usage.kind {
+        DeprecatedElementKind::Field => ("Field", "field"),
+        DeprecatedElementKind::Argument => ("Argument", "argument"),
+        DeprecatedElementKind::EnumValue => ("Enum", "enum"),
+        DeprecatedElementKind::InputField => ("Object field", "field"),
+    };
+    let message = format!(
+        "{label} \"{}\" is marked as deprecated in your GraphQL schema (reason: {})",
+        usage.name,
+        usage.reason.as_deref().unwrap_or("No longer supported")
+    );
+
+    // Mirror upstream's `fixer.remove(node)`: remove the whole node. Use
+    // block-local offsets — the convert_fix layer handles block→file
+    // translation for embedded blocks.
+    let suggestion = CodeSuggestion::delete(
+        format!("Remove {removal} \"{}\"", usage.name),
+        usage.node_range.0,
+        usage.node_range.1,
+    );
+
+    let mut diagnostic = LintDiagnostic::new(
+        doc.span(usage.name_range.0, usage.name_range.1),
+        LintSeverity::Warning,
+        message,
+        "noDeprecated",
+    )
+    .with_message_id("no-deprecated")
+    .with_help("Use the replacement field if one is specified in the deprecation reason")
+    .with_tag(crate::diagnostics::DiagnosticTag::Deprecated);
+
+    if let Some(replacement) = &usage.replacement {
+        diagnostic = diagnostic.with_suggestion(CodeSuggestion::replace(
+            format!("Replace with \"{}\"", replacement.name),
+            usage.name_range.0,
+            usage.name_range.1,
+            replacement.text.clone(),
+        ));
+    }
+    diagnostic.with_suggestion(suggestion)
+}
+
+fn push_usage(
+    usages: &mut Vec<DeprecatedUsage>,
+    kind: DeprecatedElementKind,
+    name: &cst::Name,
+    node: &apollo_parser::SyntaxNode,
+    coordinate: String,
+    reason: Option<&str>,
+) {
+    let name_start: usize = name.syntax().text_range().start().into();
+    let name_text = name.text();
+    let node_range = node.text_range();
+    usages.push(DeprecatedUsage {
+        kind,
+        name: name_text.to_string(),
+        coordinate,
+        reason: reason.map(ToString::to_string),
+        name_range: (name_start, name_start + name_text.len()),
+        node_range: (node_range.start().into(), node_range.end().into()),
+        replacement: None,
+    });
+}
+
+/// The field `field_def`'s deprecation reason points to, if it can stand in
+/// for `field` without changing the selection's shape: a leaf for a leaf or
+/// the same composite type, matching list-ness, accepting every argument
+/// written and requiring none that isn't.
+fn field_replacement(
+    field: &cst::Field,
+    field_def: &graphql_hir::FieldSignature,
+    parent_type: &graphql_hir::TypeDef,
+    schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
+) -> Option<FieldReplacement> {
+    let reason = field_def.deprecation_reason.as_deref()?;
+    let reference = &reason[deprecation_replacement(reason)?];
+    let name = match reference.split_once('.') {
+        Some((type_name, name)) if type_name == parent_type.name.as_ref() => name,
+        Some(_) => return None,
+        None => reference,
+    };
+    let replacement = parent_type
+        .fields
+        .iter()
+        .find(|f| f.name.as_ref() == name)?;
+    if replacement.is_deprecated || replacement.name == field_def.name {
+        return None;
+    }
+
+    let is_leaf = |type_name: &str| {
+        schema_types.get(type_name).is_none_or(|type_def| {
+            !matches!(
+                type_def.kind,
+                graphql_hir::TypeDefKind::Object
+                    | graphql_hir::TypeDefKind::Interface
+                    | graphql_hir::TypeDefKind::Union
+            )
+        })
+    };
+    let (old_type, new_type) = (&field_def.type_ref, &replacement.type_ref);
+    let same_shape = old_type.is_list == new_type.is_list
+        && if is_leaf(&old_type.name) {
+            is_leaf(&new_type.name)
+        } else {
+            old_type.name == new_type.name
+        };
+    if !same_shape {
+        return None;
+    }
+
+    let written: Vec<String> = field
+        .arguments()
+        .into_iter()
+        .flat_map(|arguments| arguments.arguments())
+        .filter_map(|argument| argument.name().map(|name| name.text().to_string()))
+        .collect();
+    let accepts_written = written.iter().all(|arg| {
+        replacement
+            .arguments
+            .iter()
+            .any(|def| def.name.as_ref() == arg)
+    });
+    let requires_missing = replacement.arguments.iter().any(|def| {
+        def.type_ref.is_non_null
+            && def.default_value.is_none()
+            && !written.iter().any(|arg| arg == def.name.as_ref())
+    });
+    if !accepts_written || requires_missing {
+        return None;
+    }
+
+    let text = if field.alias().is_some() {
+        replacement.name.to_string()
+    } else {
+        format!("{}: {}", field_def.name, replacement.name)
+    };
+    Some(FieldReplacement {
+        name: replacement.name.to_string(),
+        text,
+    })
+}
+
 /// Check a document for deprecated field, argument, and enum usage
 fn check_document_for_deprecated(
     doc_cst: &cst::Document,
     schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
-    diagnostics: &mut Vec<LintDiagnostic>,
-    doc: &graphql_syntax::DocumentRef<'_>,
+    usages: &mut Vec<DeprecatedUsage>,
 ) {
     for definition in doc_cst.definitions() {
         match definition {
@@ -80,13 +405,7 @@ fn check_document_for_deprecated(
                         });
 
                 if let Some(selection_set) = operation.selection_set() {
-                    check_selection_set(
-                        &selection_set,
-                        Some(root_type_name),
-                        schema_types,
-                        diagnostics,
-                        doc,
-                    );
+                    check_selection_set(&selection_set, Some(root_type_name), schema_types, usages);
                 }
             }
             cst::Definition::FragmentDefinition(fragment) => {
@@ -98,13 +417,7 @@ fn check_document_for_deprecated(
                     .map(|name| name.text().to_string());
 
                 if let Some(selection_set) = fragment.selection_set() {
-                    check_selection_set(
-                        &selection_set,
-                        type_name.as_deref(),
-                        schema_types,
-                        diagnostics,
-                        doc,
-                    );
+                    check_selection_set(&selection_set, type_name.as_deref(), schema_types, usages);
                 }
             }
             _ => {
@@ -119,8 +432,7 @@ fn check_selection_set(
     selection_set: &cst::SelectionSet,
     parent_type_name: Option<&str>,
     schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
-    diagnostics: &mut Vec<LintDiagnostic>,
-    doc: &graphql_syntax::DocumentRef<'_>,
+    usages: &mut Vec<DeprecatedUsage>,
 ) {
     let Some(parent_type_name) = parent_type_name else {
         // Skip if we don't know the parent type
@@ -135,159 +447,89 @@ fn check_selection_set(
     for selection in selection_set.selections() {
         match selection {
             cst::Selection::Field(field) => {
-                if let Some(field_name_node) = field.name() {
-                    let field_name = field_name_node.text();
+                let Some(field_name_node) = field.name() else {
+                    continue;
+                };
+                let field_name = field_name_node.text();
 
-                    // Check if this field is deprecated
-                    if let Some(field_def) = parent_type
-                        .fields
-                        .iter()
-                        .find(|f| f.name.as_ref() == field_name.as_ref())
-                    {
-                        if field_def.is_deprecated {
-                            let reason = field_def
-                                .deprecation_reason
-                                .as_deref()
-                                .unwrap_or("No longer supported");
-                            let syntax_node = field_name_node.syntax();
-                            let offset: usize = syntax_node.text_range().start().into();
-
-                            // graphql-eslint format:
-                            //   `Field "name" is marked as deprecated in your GraphQL schema (reason: ...)`
-                            // displayNodeName uses the kind label ("field")
-                            // capitalized in the rule's reporter.
-                            let message = format!(
-                                "Field \"{}\" is marked as deprecated in your GraphQL schema (reason: {})",
-                                field_name.as_ref(),
-                                reason
-                            );
-
-                            // Mirror upstream's `fixer.remove(node)`: remove the
-                            // entire Field selection (including args/sub-selections).
-                            // Use block-local offsets — the convert_fix layer
-                            // handles block→file translation for embedded blocks.
-                            let field_range = field.syntax().text_range();
-                            let field_start: usize = field_range.start().into();
-                            let field_end: usize = field_range.end().into();
-                            let suggestion = CodeSuggestion::delete(
-                                format!("Remove field \"{}\"", field_name.as_ref()),
-                                field_start,
-                                field_end,
-                            );
-
-                            diagnostics.push(
-                                LintDiagnostic::new(
-                                    doc.span(offset, offset + field_name.as_ref().len()),
-                                    LintSeverity::Warning,
-                                    message,
-                                    "noDeprecated",
-                                )
-                                .with_message_id("no-deprecated")
-                                .with_help(
-                                    "Use the replacement field if one is specified in the deprecation reason",
-                                )
-                                .with_suggestion(suggestion)
-                                .with_tag(crate::diagnostics::DiagnosticTag::Deprecated),
-                            );
-                        }
+                let Some(field_def) = parent_type
+                    .fields
+                    .iter()
+                    .find(|f| f.name.as_ref() == field_name.as_ref())
+                else {
+                    continue;
+                };
+
+                if field_def.is_deprecated {
+                    push_usage(
+                        usages,
+                        DeprecatedElementKind::Field,
+                        &field_name_node,
+                        field.syntax(),
+                        format!("{}.{}", parent_type.name, field_def.name),
+                        field_def.deprecation_reason.as_deref(),
+                    );
+                    if let Some(usage) = usages.last_mut() {
+                        usage.replacement =
+                            field_replacement(&field, field_def, parent_type, schema_types);
+                    }
+                }
+
+                // Check arguments for deprecation
+                if let Some(arguments) = field.arguments() {
+                    for arg in arguments.arguments() {
+                        let Some(arg_name_node) = arg.name() else {
+                            continue;
+                        };
+                        let arg_name = arg_name_node.text();
 
-                        // Check arguments for deprecation
-                        if let Some(arguments) = field.arguments() {
-                            for arg in arguments.arguments() {
-                                if let Some(arg_name_node) = arg.name() {
-                                    let arg_name = arg_name_node.text();
-
-                                    if let Some(arg_def) = field_def
-                                        .arguments
-                                        .iter()
-                                        .find(|a| a.name.as_ref() == arg_name.as_ref())
-                                    {
-                                        if arg_def.is_deprecated {
-                                            let reason = arg_def
-                                                .deprecation_reason
-                                                .as_deref()
-                                                .unwrap_or("No longer supported");
-                                            let syntax_node = arg_name_node.syntax();
-                                            let offset: usize =
-                                                syntax_node.text_range().start().into();
-
-                                            let message = format!(
-                                                "Argument \"{}\" is marked as deprecated in your GraphQL schema (reason: {})",
-                                                arg_name.as_ref(),
-                                                reason
-                                            );
-
-                                            // Mirror upstream's `fixer.remove(node)`:
-                                            // remove the entire Argument node.
-                                            let arg_range = arg.syntax().text_range();
-                                            let arg_start: usize = arg_range.start().into();
-                                            let arg_end: usize = arg_range.end().into();
-                                            let suggestion = CodeSuggestion::delete(
-                                                format!(
-                                                    "Remove argument \"{}\"",
-                                                    arg_name.as_ref()
-                                                ),
-                                                arg_start,
-                                                arg_end,
-                                            );
-
-                                            diagnostics.push(
-                                                LintDiagnostic::new(
-                                                    doc.span(offset, offset + arg_name.as_ref().len()),
-                                                    LintSeverity::Warning,
-                                                    message,
-                                                    "noDeprecated",
-                                                )
-                                                .with_message_id("no-deprecated")
-                                                .with_help(
-                                                    "Use the replacement field if one is specified in the deprecation reason",
-                                                )
-                                                .with_suggestion(suggestion)
-                                                .with_tag(crate::diagnostics::DiagnosticTag::Deprecated),
-                                            );
-                                        }
-
-                                        // Check value: enum values (best-effort) and
-                                        // input object fields (type-aware via arg type).
-                                        if let Some(value) = arg.value() {
-                                            check_value_for_deprecated(
-                                                &value,
-                                                Some(arg_def.type_ref.name.as_ref()),
-                                                schema_types,
-                                                diagnostics,
-                                                doc,
-                                            );
-                                        }
-                                    } else {
-                                        // Arg not in schema — still check enum/input values
-                                        if let Some(value) = arg.value() {
-                                            check_value_for_deprecated(
-                                                &value,
-                                                None,
-                                                schema_types,
-                                                diagnostics,
-                                                doc,
-                                            );
-                                        }
-                                    }
-                                }
+                        if let Some(arg_def) = field_def
+                            .arguments
+                            .iter()
+                            .find(|a| a.name.as_ref() == arg_name.as_ref())
+                        {
+                            if arg_def.is_deprecated {
+                                push_usage(
+                                    usages,
+                                    DeprecatedElementKind::Argument,
+                                    &arg_name_node,
+                                    arg.syntax(),
+                                    format!(
+                                        "{}.{}({}:)",
+                                        parent_type.name, field_def.name, arg_def.name
+                                    ),
+                                    arg_def.deprecation_reason.as_deref(),
+                                );
                             }
-                        }
 
-                        // Recurse into nested selection set with field's return type
-                        if let Some(nested_selection_set) = field.selection_set() {
-                            // Get the named type (unwrap list/non-null wrappers)
-                            let field_type_name = field_def.type_ref.name.as_ref();
-                            check_selection_set(
-                                &nested_selection_set,
-                                Some(field_type_name),
-                                schema_types,
-                                diagnostics,
-                                doc,
-                            );
+                            // Check value: enum values (best-effort) and
+                            // input object fields (type-aware via arg type).
+                            if let Some(value) = arg.value() {
+                                check_value_for_deprecated(
+                                    &value,
+                                    Some(arg_def.type_ref.name.as_ref()),
+                                    schema_types,
+                                    usages,
+                                );
+                            }
+                        } else if let Some(value) = arg.value() {
+                            // Arg not in schema — still check enum/input values
+                            check_value_for_deprecated(&value, None, schema_types, usages);
                         }
                     }
                 }
+
+                // Recurse into nested selection set with field's return type
+                if let Some(nested_selection_set) = field.selection_set() {
+                    // Get the named type (unwrap list/non-null wrappers)
+                    let field_type_name = field_def.type_ref.name.as_ref();
+                    check_selection_set(
+                        &nested_selection_set,
+                        Some(field_type_name),
+                        schema_types,
+                        usages,
+                    );
+                }
             }
             cst::Selection::FragmentSpread(_spread) => {
                 // Fragment spreads don't directly use schema elements
@@ -304,13 +546,7 @@ fn check_selection_set(
                 let type_name_ref = type_name.as_deref().or(Some(parent_type_name));
 
                 if let Some(selection_set) = inline.selection_set() {
-                    check_selection_set(
-                        &selection_set,
-                        type_name_ref,
-                        schema_types,
-                        diagnostics,
-                        doc,
-                    );
+                    check_selection_set(&selection_set, type_name_ref, schema_types, usages);
                 }
             }
         }
@@ -327,80 +563,45 @@ fn check_value_for_deprecated(
     value: &cst::Value,
     expected_type_name: Option<&str>,
     schema_types: &HashMap<Arc<str>, graphql_hir::TypeDef>,
-    diagnostics: &mut Vec<LintDiagnostic>,
-    doc: &graphql_syntax::DocumentRef<'_>,
+    usages: &mut Vec<DeprecatedUsage>,
 ) {
     match value {
         cst::Value::EnumValue(enum_value) => {
-            if let Some(enum_name_node) = enum_value.name() {
-                let enum_name = enum_name_node.text();
-
-                // Try to find which enum type this value belongs to.
-                // Best-effort across all enum types since we may not have
-                // full type context at this call site.
-                for type_def in schema_types.values() {
-                    if type_def.kind == graphql_hir::TypeDefKind::Enum {
-                        if let Some(enum_val) = type_def
-                            .enum_values
-                            .iter()
-                            .find(|v| v.name.as_ref() == enum_name.as_ref())
-                        {
-                            if enum_val.is_deprecated {
-                                let reason = enum_val
-                                    .deprecation_reason
-                                    .as_deref()
-                                    .unwrap_or("No longer supported");
-                                let syntax_node = enum_name_node.syntax();
-                                let offset: usize = syntax_node.text_range().start().into();
-
-                                let message = format!(
-                                    "Enum \"{}\" is marked as deprecated in your GraphQL schema (reason: {})",
-                                    enum_name.as_ref(),
-                                    reason
-                                );
-
-                                // Mirror upstream's `fixer.remove(node)`: remove
-                                // the EnumValue node (the bare identifier).
-                                let ev_range = enum_value.syntax().text_range();
-                                let ev_start: usize = ev_range.start().into();
-                                let ev_end: usize = ev_range.end().into();
-                                let suggestion = CodeSuggestion::delete(
-                                    format!("Remove enum \"{}\"", enum_name.as_ref()),
-                                    ev_start,
-                                    ev_end,
-                                );
-
-                                diagnostics.push(
-                                    LintDiagnostic::new(
-                                        doc.span(offset, offset + enum_name.as_ref().len()),
-                                        LintSeverity::Warning,
-                                        message,
-                                        "noDeprecated",
-                                    )
-                                    .with_message_id("no-deprecated")
-                                    .with_help(
-                                        "Use the replacement field if one is specified in the deprecation reason",
-                                    )
-                                    .with_suggestion(suggestion)
-                                    .with_tag(crate::diagnostics::DiagnosticTag::Deprecated),
-                                );
-                                // Found the enum; no need to scan remaining types.
-                                break;
-                            }
-                        }
+            let Some(enum_name_node) = enum_value.name() else {
+                return;
+            };
+            let enum_name = enum_name_node.text();
+
+            // Try to find which enum type this value belongs to.
+            // Best-effort across all enum types since we may not have
+            // full type context at this call site.
+            for type_def in schema_types.values() {
+                if type_def.kind != graphql_hir::TypeDefKind::Enum {
+                    continue;
+                }
+                if let Some(enum_val) = type_def
+                    .enum_values
+                    .iter()
+                    .find(|v| v.name.as_ref() == enum_name.as_ref())
+                {
+                    if enum_val.is_deprecated {
+                        push_usage(
+                            usages,
+                            DeprecatedElementKind::EnumValue,
+                            &enum_name_node,
+                            enum_value.syntax(),
+                            format!("{}.{}", type_def.name, enum_val.name),
+                            enum_val.deprecation_reason.as_deref(),
+                        );
+                        // Found the enum; no need to scan remaining types.
+                        break;
                     }
                 }
             }
         }
         cst::Value::ListValue(list) => {
             for item in list.values() {
-                check_value_for_deprecated(
-                    &item,
-                    expected_type_name,
-                    schema_types,
-                    diagnostics,
-                    doc,
-                );
+                check_value_for_deprecated(&item, expected_type_name, schema_types, usages);
             }
         }
         cst::Value::ObjectValue(obj) => {
@@ -415,66 +616,34 @@ fn check_value_for_deprecated(
                 let field_name = field_name_node.text();
 
                 // If we have the input type, check this field for deprecation.
-                let nested_type_name: Option<&str> = if let Some(type_def) = input_type_def {
-                    if let Some(field_sig) = type_def
+                let field_sig = input_type_def.and_then(|type_def| {
+                    type_def
                         .fields
                         .iter()
                         .find(|f| f.name.as_ref() == field_name.as_ref())
-                    {
-                        if field_sig.is_deprecated {
-                            let reason = field_sig
-                                .deprecation_reason
-                                .as_deref()
-                                .unwrap_or("No longer supported");
-                            let syntax_node = field_name_node.syntax();
-                            let offset: usize = syntax_node.text_range().start().into();
-
-                            let message = format!(
-                                "Object field \"{}\" is marked as deprecated in your GraphQL schema (reason: {})",
-                                field_name.as_ref(),
-                                reason
-                            );
-
-                            let field_range = obj_field.syntax().text_range();
-                            let field_start: usize = field_range.start().into();
-                            let field_end: usize = field_range.end().into();
-                            let suggestion = CodeSuggestion::delete(
-                                format!("Remove field \"{}\"", field_name.as_ref()),
-                                field_start,
-                                field_end,
-                            );
-
-                            diagnostics.push(
-                                LintDiagnostic::new(
-                                    doc.span(offset, offset + field_name.as_ref().len()),
-                                    LintSeverity::Warning,
-                                    message,
-                                    "noDeprecated",
-                                )
-                                .with_message_id("no-deprecated")
-                                .with_help(
-                                    "Use the replacement field if one is specified in the deprecation reason",
-                                )
-                                .with_suggestion(suggestion)
-                                .with_tag(crate::diagnostics::DiagnosticTag::Deprecated),
-                            );
-                        }
-                        // Pass the nested field's type down for further recursion.
-                        Some(field_sig.type_ref.name.as_ref())
-                    } else {
-                        None
+                        .map(|field_sig| (type_def, field_sig))
+                });
+                if let Some((type_def, field_sig)) = field_sig {
+                    if field_sig.is_deprecated {
+                        push_usage(
+                            usages,
+                            DeprecatedElementKind::InputField,
+                            &field_name_node,
+                            obj_field.syntax(),
+                            format!("{}.{}", type_def.name, field_sig.name),
+                            field_sig.deprecation_reason.as_deref(),
+                        );
                     }
-                } else {
-                    None
-                };
+                }
+                // Pass the nested field's type down for further recursion.
+                let nested_type_name = field_sig.map(|(_, f)| f.type_ref.name.as_ref());
 
                 if let Some(field_value) = obj_field.value() {
                     check_value_for_deprecated(
                         &field_value,
                         nested_type_name,
                         schema_types,
-                        diagnostics,
-                        doc,
+                        usages,
                     );
                 }
             }
@@ -914,4 +1083,124 @@ query GetUser {
             "Field \"legacyField\" is marked as deprecated in your GraphQL schema (reason: No longer supported)"
         );
     }
+
+    #[test]
+    fn test_deprecation_replacement_parsing() {
+        let reference = |reason: &str| deprecation_replacement(reason).map(|range| &reason[range]);
+
+        assert_eq!(reference("Use `name` instead"), Some("name"));
+        assert_eq!(reference("Use name instead"), Some("name"));
+        assert_eq!(
+            reference("Deprecated since v2. Use `User.fullName` instead."),
+            Some("User.fullName")
+        );
+        assert_eq!(reference("Use 'b' instead."), None);
+        assert_eq!(reference("Reuse `name` instead"), None);
+        assert_eq!(reference("Use `name` for this"), None);
+        assert_eq!(reference("No longer supported"), None);
+    }
+
+    #[test]
+    fn test_replacement_suggestion_keeps_response_key() {
+        let db = RootDatabase::default();
+        let rule = NoDeprecatedRuleImpl;
+
+        let schema = r#"
+type Query {
+    user: User
+}
+
+type User {
+    name: String!
+    username: String @deprecated(reason: "Use `name` instead")
+    friends(first: Int): [User!]!
+    buddies: [User!]! @deprecated(reason: "Use `User.friends` instead")
+}
+"#;
+
+        let source = r"
+query GetUser {
+    user {
+        username
+        login: username
+        buddies {
+            name
+        }
+    }
+}
+";
+
+        let (file_id, content, metadata, project_files) = create_test_project(&db, schema, source);
+
+        let diagnostics = rule.check(&db, file_id, content, metadata, project_files, None);
+
+        assert_eq!(diagnostics.len(), 3);
+        let replacements: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let suggestion = &diagnostic.suggestions[0];
+                (
+                    suggestion.desc.as_str(),
+                    suggestion.fix.edits[0].new_text.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            replacements,
+            vec![
+                ("Replace with \"name\"", "username: name"),
+                ("Replace with \"name\"", "name"),
+                ("Replace with \"friends\"", "buddies: friends"),
+            ]
+        );
+        // Removing the field is still offered after the replacement
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.suggestions.len() == 2));
+    }
+
+    #[test]
+    fn test_no_replacement_suggestion_for_incompatible_field() {
+        let db = RootDatabase::default();
+        let rule = NoDeprecatedRuleImpl;
+
+        let schema = r#"
+type Query {
+    user: User
+}
+
+type User {
+    name: String!
+    profile: Profile
+    avatar(size: Int!): String
+    nickname: String @deprecated(reason: "Use profile instead")
+    picture: String @deprecated(reason: "Use avatar instead")
+    title: String @deprecated(reason: "Use subtitle instead")
+}
+
+type Profile {
+    bio: String
+}
+"#;
+
+        let source = r"
+query GetUser {
+    user {
+        nickname
+        picture
+        title
+    }
+}
+";
+
+        let (file_id, content, metadata, project_files) = create_test_project(&db, schema, source);
+
+        let diagnostics = rule.check(&db, file_id, content, metadata, project_files, None);
+
+        assert_eq!(diagnostics.len(), 3);
+        for diagnostic in &diagnostics {
+            assert_eq!(diagnostic.suggestions.len(), 1);
+            assert!(diagnostic.suggestions[0].desc.starts_with("Remove field"));
+        }
+    }
 }
@@ -55,6 +55,20 @@ impl LintRule for NoRootTypeRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Error
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "disallow": {
+                    "type": "array",
+                    "items": { "enum": ["query", "mutation", "subscription"] },
+                    "uniqueItems": true
+                }
+            },
+            "required": ["disallow"]
+        }))
+    }
 }
 
 impl StandaloneSchemaLintRule for NoRootTypeRuleImpl {
@@ -4,7 +4,14 @@ use graphql_base_db::{FileId, ProjectFiles};
 use graphql_hir::TypeDefKind;
 use std::collections::{HashMap, HashSet, VecDeque};
 
-const BUILTIN_DIRECTIVES: &[&str] = &["deprecated", "skip", "include", "specifiedBy", "defer"];
+const BUILTIN_DIRECTIVES: &[&str] = &[
+    "deprecated",
+    "skip",
+    "include",
+    "specifiedBy",
+    "defer",
+    "stream",
+];
 
 /// Lint rule that detects unreachable types in the schema
 ///
@@ -266,8 +273,8 @@ impl StandaloneSchemaLintRule for NoUnreachableTypesRuleImpl {
         //      implicitly considered reachable by upstream's request-location pass, OR
         //   b) it is actually applied somewhere in the schema (on a type def, field,
         //      argument, enum value, or schema def).
-        // Built-in directives (@deprecated, @skip, @include, @specifiedBy, @defer) are
-        // spec-defined and never emitted by user schemas, so we skip them.
+        // Built-in directives (@deprecated, @skip, @include, @specifiedBy, @defer,
+        // @stream) are spec-defined and never emitted by user schemas, so we skip them.
 
         // Collect every directive name that is applied anywhere in the schema.
         let mut applied_directives: HashSet<String> = HashSet::new();
@@ -0,0 +1,230 @@
+use crate::diagnostics::{rule_doc_url, LintDiagnostic, LintSeverity};
+use crate::traits::{DocumentSchemaLintRule, LintRule};
+use apollo_parser::cst::{self, CstNode};
+use graphql_base_db::{FileContent, FileId, FileMetadata, ProjectFiles};
+use std::collections::HashMap;
+
+/// Lint rule that warns about `@defer` and `@stream` when the schema doesn't
+/// declare them
+///
+/// The analyzer defines both directives for every project, so documents
+/// using them validate either way. A server that supports incremental
+/// delivery advertises it by declaring the directives in its schema; one
+/// that doesn't will reject the operation or ignore the directive.
+///
+/// Example:
+/// ```graphql
+/// # Warning when the schema has no `directive @defer`
+/// query GetUser {
+///   user {
+///     id
+///     ... @defer { reviews { body } }
+///   }
+/// }
+/// ```
+pub struct NoUnsupportedIncrementalDeliveryRuleImpl;
+
+impl LintRule for NoUnsupportedIncrementalDeliveryRuleImpl {
+    fn name(&self) -> &'static str {
+        "noUnsupportedIncrementalDelivery"
+    }
+
+    fn description(&self) -> &'static str {
+        "Warns when @defer or @stream is used but the schema doesn't declare it"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+}
+
+impl DocumentSchemaLintRule for NoUnsupportedIncrementalDeliveryRuleImpl {
+    fn check(
+        &self,
+        db: &dyn graphql_hir::GraphQLHirDatabase,
+        _file_id: FileId,
+        content: FileContent,
+        metadata: FileMetadata,
+        project_files: ProjectFiles,
+        _options: Option<&serde_json::Value>,
+    ) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let parse = graphql_syntax::parse(db, content, metadata);
+        if parse.has_errors() {
+            return diagnostics;
+        }
+
+        let mut declared: HashMap<&str, bool> = HashMap::new();
+        for doc in parse.documents() {
+            for directive in doc
+                .tree
+                .document()
+                .syntax()
+                .descendants()
+                .filter_map(cst::Directive::cast)
+            {
+                let Some(name) = directive.name() else {
+                    continue;
+                };
+                let name = match name.text().as_str() {
+                    "defer" => "defer",
+                    "stream" => "stream",
+                    _ => continue,
+                };
+                let supported = *declared.entry(name).or_insert_with(|| {
+                    graphql_hir::schema_declares_directive(db, project_files, name)
+                });
+                if supported {
+                    continue;
+                }
+
+                let start: usize = directive.syntax().text_range().start().into();
+                let end = start + 1 + name.len();
+                diagnostics.push(
+                    LintDiagnostic::new(
+                        doc.span(start, end),
+                        LintSeverity::Warning,
+                        format!(
+                            "The schema doesn't declare `@{name}`, so the server may not support incremental delivery"
+                        ),
+                        "noUnsupportedIncrementalDelivery",
+                    )
+                    .with_message_id("no-unsupported-incremental-delivery")
+                    .with_help(format!(
+                        "Check that the server supports `@{name}`, and add its directive definition to the schema if it does"
+                    ))
+                    .with_url(rule_doc_url("noUnsupportedIncrementalDelivery")),
+                );
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_base_db::{DocumentKind, FileUri, Language};
+    use graphql_ide_db::RootDatabase;
+    use std::sync::Arc;
+
+    fn create_test_project(
+        db: &dyn graphql_hir::GraphQLHirDatabase,
+        schema_source: &str,
+        document_source: &str,
+    ) -> (FileId, FileContent, FileMetadata, ProjectFiles) {
+        let schema_file_id = FileId::new(0);
+        let schema_content = FileContent::new(db, Arc::from(schema_source));
+        let schema_metadata = FileMetadata::new(
+            db,
+            schema_file_id,
+            FileUri::new("file:///schema.graphql"),
+            Language::GraphQL,
+            DocumentKind::Schema,
+        );
+
+        let doc_file_id = FileId::new(1);
+        let doc_content = FileContent::new(db, Arc::from(document_source));
+        let doc_metadata = FileMetadata::new(
+            db,
+            doc_file_id,
+            FileUri::new("file:///query.graphql"),
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+
+        let schema_file_ids =
+            graphql_base_db::SchemaFileIds::new(db, Arc::new(vec![schema_file_id]));
+        let document_file_ids =
+            graphql_base_db::DocumentFileIds::new(db, Arc::new(vec![doc_file_id]));
+        let mut file_entries = HashMap::new();
+        let schema_entry = graphql_base_db::FileEntry::new(db, schema_content, schema_metadata);
+        let doc_entry = graphql_base_db::FileEntry::new(db, doc_content, doc_metadata);
+        file_entries.insert(schema_file_id, schema_entry);
+        file_entries.insert(doc_file_id, doc_entry);
+        let file_entry_map = graphql_base_db::FileEntryMap::new(db, Arc::new(file_entries));
+        let project_files = ProjectFiles::new(
+            db,
+            schema_file_ids,
+            document_file_ids,
+            graphql_base_db::ResolvedSchemaFileIds::new(db, Arc::new(vec![])),
+            file_entry_map,
+            graphql_base_db::FilePathMap::new(
+                db,
+                Arc::new(HashMap::new()),
+                Arc::new(HashMap::new()),
+            ),
+        );
+
+        (doc_file_id, doc_content, doc_metadata, project_files)
+    }
+
+    const DOCUMENT: &str = r#"
+query GetUser {
+  user {
+    id
+    ... @defer(label: "details") { name }
+    friends @stream(initialCount: 2) { id }
+  }
+}
+"#;
+
+    #[test]
+    fn test_warns_when_schema_does_not_declare_directives() {
+        let db = RootDatabase::default();
+        let (file_id, content, metadata, project_files) = create_test_project(
+            &db,
+            "type Query { user: User } type User { id: ID! name: String friends: [User!]! }",
+            DOCUMENT,
+        );
+
+        let diagnostics = NoUnsupportedIncrementalDeliveryRuleImpl.check(
+            &db,
+            file_id,
+            content,
+            metadata,
+            project_files,
+            None,
+        );
+
+        let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "The schema doesn't declare `@defer`, so the server may not support incremental delivery",
+                "The schema doesn't declare `@stream`, so the server may not support incremental delivery",
+            ]
+        );
+        let start = DOCUMENT.find("@defer").unwrap();
+        assert_eq!(diagnostics[0].span.start, start);
+        assert_eq!(diagnostics[0].span.end, start + "@defer".len());
+    }
+
+    #[test]
+    fn test_no_warning_when_schema_declares_directives() {
+        let db = RootDatabase::default();
+        let (file_id, content, metadata, project_files) = create_test_project(
+            &db,
+            r"
+directive @defer(if: Boolean! = true, label: String) on FRAGMENT_SPREAD | INLINE_FRAGMENT
+directive @stream(if: Boolean! = true, label: String, initialCount: Int! = 0) on FIELD
+type Query { user: User }
+type User { id: ID! name: String friends: [User!]! }
+",
+            DOCUMENT,
+        );
+
+        let diagnostics = NoUnsupportedIncrementalDeliveryRuleImpl.check(
+            &db,
+            file_id,
+            content,
+            metadata,
+            project_files,
+            None,
+        );
+
+        assert!(diagnostics.is_empty());
+    }
+}
@@ -188,6 +188,16 @@ impl LintRule for NoUnusedFieldsRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "ignoredFieldSelectors": { "type": "array", "items": { "type": "string" } },
+                "skipRootTypes": { "type": "boolean", "default": true }
+            }
+        }))
+    }
 }
 
 /// Information about a schema field for diagnostic reporting
@@ -271,6 +281,40 @@ impl ProjectLintRule for NoUnusedFieldsRuleImpl {
             }
         }
 
+        // Step 2b: With an operation usage report loaded, collect the coordinates
+        // selected by operations that were actually called. A field that's used
+        // only by operations with no recorded traffic is reported as well.
+        let mut traffic: Option<(u32, HashMap<String, HashSet<String>>)> = None;
+        for file_id in doc_ids.iter() {
+            let Some((content, metadata)) =
+                graphql_base_db::file_lookup(db, project_files, *file_id)
+            else {
+                continue;
+            };
+            let structure = graphql_hir::file_structure(db, *file_id, content, metadata);
+            for operation in structure.operations.iter() {
+                let Some(calls) = db.operation_calls(*file_id, operation.index) else {
+                    continue;
+                };
+                let (_, called) =
+                    traffic.get_or_insert_with(|| (calls.window_days, HashMap::new()));
+                if calls.count == 0 {
+                    continue;
+                }
+                let operations =
+                    graphql_hir::operation_schema_coordinates(db, content, metadata, project_files);
+                let Some(coords) = operations.get(operation.index) else {
+                    continue;
+                };
+                for coord in coords {
+                    called
+                        .entry(coord.type_name.to_string())
+                        .or_default()
+                        .insert(coord.field_name.to_string());
+                }
+            }
+        }
+
         // Step 3: Report unused fields (no auto-fix - removing schema fields is a breaking change)
         for field_info in &all_fields {
             // Skip introspection types
@@ -325,6 +369,30 @@ impl ProjectLintRule for NoUnusedFieldsRuleImpl {
                         .with_suggestion(suggestion)
                         .with_tag(crate::diagnostics::DiagnosticTag::Unnecessary);
 
+                diagnostics_by_file
+                    .entry(field_info.file_id)
+                    .or_default()
+                    .push(diag);
+            } else if let Some((window_days, called)) = &traffic {
+                let is_called = called
+                    .get(&field_info.type_name)
+                    .is_some_and(|set| set.contains(&field_info.field_name));
+                if is_called {
+                    continue;
+                }
+
+                let message = format!(
+                    "Field \"{}\" is only selected by operations with no calls in the last {} days",
+                    field_info.field_name, window_days
+                );
+                let diag =
+                    LintDiagnostic::warning(field_info.span.clone(), message, "noUnusedFields")
+                        .with_message_id("uncalled-field")
+                        .with_help(
+                            "The operation usage report has no traffic for any operation \
+                             selecting this field; consider deprecating it",
+                        );
+
                 diagnostics_by_file
                     .entry(field_info.file_id)
                     .or_default()
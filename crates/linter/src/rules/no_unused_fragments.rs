@@ -1,8 +1,10 @@
 use crate::diagnostics::{CodeFix, LintDiagnostic, LintSeverity, TextEdit};
 use crate::traits::{LintRule, ProjectLintRule};
+use apollo_parser::cst;
 use graphql_apollo_ext::{DocumentExt, NameExt, RangeExt};
 use graphql_base_db::{FileId, ProjectFiles};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
 
 /// Length of the literal `fragment` keyword in bytes — graphql-eslint's
 /// adapter re-anchors the diagnostic onto this token, so we span the same
@@ -20,10 +22,10 @@ struct FragmentInfo {
     file_id: FileId,
     /// Source span for the fragment name (carries block context for TS/JS)
     name_span: graphql_syntax::SourceSpan,
-    /// Byte offset of the entire fragment definition (for fix range)
+    /// Byte offset of the start of the fragment definition
     def_start: usize,
-    /// Byte offset of the end of the fragment definition (for fix range)
-    def_end: usize,
+    /// Edit removing the definition from its document
+    removal: TextEdit,
     /// File-level byte range of the enclosing TS/JS declaration
     declaration_range: Option<(usize, usize)>,
     /// Number of GraphQL definitions in the containing block
@@ -42,6 +44,10 @@ impl LintRule for NoUnusedFragmentsRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn fixable(&self) -> bool {
+        true
+    }
 }
 
 impl ProjectLintRule for NoUnusedFragmentsRuleImpl {
@@ -79,14 +85,18 @@ impl ProjectLintRule for NoUnusedFragmentsRuleImpl {
                     let Some(name_range) = frag.name_range() else {
                         continue;
                     };
-                    let def_range = frag.byte_range();
+                    let def_start = frag.byte_range().start;
+                    let removal = graphql_syntax::edit::remove_definition(
+                        doc.source,
+                        &cst::Definition::FragmentDefinition(frag),
+                    );
 
                     all_fragments.push(FragmentInfo {
                         name,
                         file_id: *file_id,
                         name_span: doc.span(name_range.start, name_range.end),
-                        def_start: def_range.start,
-                        def_end: def_range.end,
+                        def_start,
+                        removal: removal.into(),
                         declaration_range: doc.declaration_range,
                         block_def_count: doc.ast.definitions.len(),
                     });
@@ -94,8 +104,12 @@ impl ProjectLintRule for NoUnusedFragmentsRuleImpl {
             }
         }
 
-        // Step 2: Collect all used fragment names using per-file cached queries
-        let mut used_fragments: HashSet<String> = HashSet::new();
+        // Step 2: Collect the fragments reachable from an operation. Only
+        // spreads inside operations seed the set; fragment-to-fragment
+        // spreads are followed from there, so a fragment spread only by
+        // other unused fragments is itself unused (as in graphql-js).
+        let mut used_fragments: HashSet<Arc<str>> = HashSet::new();
+        let mut to_visit: VecDeque<Arc<str>> = VecDeque::new();
 
         for file_id in doc_ids.iter() {
             let Some((content, metadata)) =
@@ -103,15 +117,30 @@ impl ProjectLintRule for NoUnusedFragmentsRuleImpl {
             else {
                 continue;
             };
-            let used = graphql_hir::file_used_fragment_names(db, *file_id, content, metadata);
-            for fragment_name in used.iter() {
-                used_fragments.insert(fragment_name.to_string());
+            let spreads =
+                graphql_hir::file_operation_fragment_spreads(db, *file_id, content, metadata);
+            for fragment_name in spreads.iter() {
+                if used_fragments.insert(fragment_name.clone()) {
+                    to_visit.push_back(fragment_name.clone());
+                }
+            }
+        }
+
+        let spreads_index = graphql_hir::fragment_spreads_index(db, project_files);
+        while let Some(name) = to_visit.pop_front() {
+            let Some(spreads) = spreads_index.get(&name) else {
+                continue;
+            };
+            for spread in spreads {
+                if used_fragments.insert(spread.clone()) {
+                    to_visit.push_back(spread.clone());
+                }
             }
         }
 
         // Step 3: Report unused fragments with fixes
         for frag_info in all_fragments {
-            if !used_fragments.contains(&frag_info.name) {
+            if !used_fragments.contains(frag_info.name.as_str()) {
                 // Mirror graphql-eslint exactly: drop-in users see the same
                 // text and source positions on `LintMessage` as
                 // `@graphql-eslint/eslint-plugin`. graphql-eslint's adapter
@@ -159,7 +188,7 @@ impl ProjectLintRule for NoUnusedFragmentsRuleImpl {
 
                     let fix = CodeFix::new(
                         format!("Remove unused fragment '{}'", frag_info.name),
-                        vec![TextEdit::delete(frag_info.def_start, frag_info.def_end)],
+                        vec![frag_info.removal],
                     );
                     (keyword_span, fix)
                 };
@@ -332,6 +361,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fragment_spread_only_by_unused_fragment_is_unused() {
+        let db = RootDatabase::default();
+        let rule = NoUnusedFragmentsRuleImpl;
+
+        let source = "fragment Outer on User { ...Inner } fragment Inner on User { name }";
+        let file_id = FileId::new(0);
+        let content = FileContent::new(&db, Arc::from(source));
+        let metadata = FileMetadata::new(
+            &db,
+            file_id,
+            FileUri::new("file:///test.graphql"),
+            Language::GraphQL,
+            DocumentKind::Executable,
+        );
+
+        let project_files = create_test_project_files(&db, &[(file_id, content, metadata)]);
+        let diagnostics = rule.check(&db, project_files, None);
+
+        let file_diags = diagnostics
+            .get(&file_id)
+            .expect("Expected diagnostics for file");
+        let mut names: Vec<_> = file_diags.iter().map(|d| d.message.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec![
+                "Fragment \"Inner\" is never used.",
+                "Fragment \"Outer\" is never used."
+            ]
+        );
+    }
+
     #[test]
     fn test_multiple_unused_fragments() {
         let db = RootDatabase::default();
@@ -394,10 +456,10 @@ mod tests {
         let diag = &file_diags[0];
         let fix = diag.fix.as_ref().unwrap();
 
-        // Verify the fix deletes exactly the fragment definition
+        // Verify the fix deletes the fragment definition and the space
+        // separating it from the query
         let deleted_text = &source[fix.edits[0].offset_range.start..fix.edits[0].offset_range.end];
-        assert!(deleted_text.starts_with("fragment Unused"));
-        assert!(deleted_text.ends_with('}'));
+        assert_eq!(deleted_text, " fragment Unused on User { name email }");
     }
 
     #[test]
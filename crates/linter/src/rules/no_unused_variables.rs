@@ -35,6 +35,10 @@ impl LintRule for NoUnusedVariablesRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn fixable(&self) -> bool {
+        true
+    }
 }
 
 impl StandaloneDocumentLintRule for NoUnusedVariablesRuleImpl {
@@ -41,6 +41,10 @@ impl LintRule for RedundantFieldsRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn fixable(&self) -> bool {
+        true
+    }
 }
 
 impl StandaloneDocumentLintRule for RedundantFieldsRuleImpl {
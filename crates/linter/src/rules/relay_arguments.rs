@@ -111,6 +111,15 @@ impl LintRule for RelayArgumentsRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "includeBoth": { "type": "boolean", "default": true }
+            }
+        }))
+    }
 }
 
 impl StandaloneSchemaLintRule for RelayArgumentsRuleImpl {
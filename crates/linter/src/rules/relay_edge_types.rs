@@ -32,6 +32,17 @@ impl LintRule for RelayEdgeTypesRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "withEdgeSuffix": { "type": "boolean", "default": true },
+                "shouldImplementNode": { "type": "boolean", "default": true },
+                "listTypeCanWrapOnlyEdgeType": { "type": "boolean", "default": true }
+            }
+        }))
+    }
 }
 
 #[derive(Debug)]
@@ -54,6 +54,15 @@ impl LintRule for RequireDeprecationDateRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "argumentName": { "type": "string", "default": "deletionDate" }
+            }
+        }))
+    }
 }
 
 /// Find the `@deprecated` directive in a directive list, if any.
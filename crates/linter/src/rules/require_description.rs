@@ -270,6 +270,38 @@ impl LintRule for RequireDescriptionRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        let kinds = [
+            "types",
+            "rootField",
+            "ObjectTypeDefinition",
+            "InterfaceTypeDefinition",
+            "EnumTypeDefinition",
+            "ScalarTypeDefinition",
+            "InputObjectTypeDefinition",
+            "UnionTypeDefinition",
+            "FieldDefinition",
+            "InputValueDefinition",
+            "EnumValueDefinition",
+            "DirectiveDefinition",
+            "OperationDefinition",
+        ];
+        let mut properties: serde_json::Map<String, serde_json::Value> = kinds
+            .iter()
+            .map(|kind| {
+                (
+                    (*kind).to_string(),
+                    serde_json::json!({ "type": "boolean" }),
+                )
+            })
+            .collect();
+        properties.insert(
+            "ignoredSelectors".to_string(),
+            serde_json::json!({ "type": "array", "items": { "type": "string" } }),
+        );
+        Some(serde_json::json!({ "type": "object", "properties": properties }))
+    }
 }
 
 impl StandaloneSchemaLintRule for RequireDescriptionRuleImpl {
@@ -1,4 +1,4 @@
-use crate::diagnostics::{CodeFix, CodeSuggestion, LintDiagnostic, LintSeverity, TextEdit};
+use crate::diagnostics::{CodeFix, CodeSuggestion, LintDiagnostic, LintSeverity};
 use crate::schema_utils::extract_root_type_names;
 use crate::traits::{DocumentSchemaLintRule, LintRule};
 use apollo_parser::cst::{self, CstNode};
@@ -79,6 +79,23 @@ impl LintRule for RequireSelectionsRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Error
     }
+
+    fn fixable(&self) -> bool {
+        true
+    }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "fieldName": {
+                    "oneOf": [{ "type": "string" }, { "type": "array", "items": { "type": "string" } }],
+                    "default": ["id"]
+                },
+                "requireAllFields": { "type": "boolean", "default": false }
+            }
+        }))
+    }
 }
 
 impl DocumentSchemaLintRule for RequireSelectionsRuleImpl {
@@ -509,20 +526,6 @@ fn check_selection_set(
     }
 
     let selection_set_start: usize = selection_set.syntax().text_range().start().into();
-    let selection_set_source = selection_set.syntax().to_string();
-
-    let (insert_pos, indent) = selection_set.selections().next().map_or_else(
-        || {
-            // Empty selection set - insert after the opening brace with default indent
-            (selection_set_start + 1, "  ".to_string())
-        },
-        |first| {
-            let pos: usize = first.syntax().text_range().start().into();
-            let relative_pos = pos - selection_set_start;
-            let indent = extract_indentation(&selection_set_source, relative_pos);
-            (pos, indent)
-        },
-    );
 
     // graphql-eslint appends ` or add to used fragment(s) X` when the
     // missing field is reachable through fragment(s) walked above that
@@ -545,23 +548,21 @@ fn check_selection_set(
     if context.require_all_fields {
         // AND mode: one diagnostic per missing field.
         for missing_field in &missing_fields {
-            let fix_label = format!("Add `{missing_field}` selection");
-            let fix_text = format!("{missing_field}\n{indent}");
-            let fix = CodeFix::new(fix_label, vec![TextEdit::insert(insert_pos, fix_text)]);
             let field_ref = format!("`{parent_display_name}.{missing_field}`");
             // Mirror graphql-eslint: diagnostic points at the SelectionSet's
             // opening `{` with a start-only `loc` (no end position).
-            diagnostics.push(
-                LintDiagnostic::error(
-                    doc.span(selection_set_start, selection_set_start),
-                    format!(
-                        "Field {field_ref} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
-                    ),
-                    "requireSelections",
-                )
-                .with_message_id("require-selections")
-                .with_fix(fix),
-            );
+            let mut diag = LintDiagnostic::error(
+                doc.span(selection_set_start, selection_set_start),
+                format!(
+                    "Field {field_ref} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
+                ),
+                "requireSelections",
+            )
+            .with_message_id("require-selections");
+            if let Some(fix) = add_selections_fix(doc.source, selection_set, &[*missing_field]) {
+                diag = diag.with_fix(fix);
+            }
+            diagnostics.push(diag);
         }
     } else {
         // OR mode: one grouped diagnostic listing all candidates. Mirror
@@ -571,12 +572,12 @@ fn check_selection_set(
         // single-suggestion case still gets the same shape (one entry).
         let suggestions: Vec<CodeSuggestion> = missing_fields
             .iter()
-            .map(|f| {
-                let edit_text = format!("{f}\n{indent}");
-                CodeSuggestion {
-                    desc: format!("Add `{f}` selection"),
-                    fix: CodeFix::new(String::new(), vec![TextEdit::insert(insert_pos, edit_text)]),
-                }
+            .filter_map(|f| {
+                let fix = add_selections_fix(doc.source, selection_set, &[*f])?;
+                Some(CodeSuggestion {
+                    desc: fix.label,
+                    fix: CodeFix::new(String::new(), fix.edits),
+                })
             })
             .collect();
 
@@ -585,11 +586,7 @@ fn check_selection_set(
         // to pick one (or stack all of them, which over-fetches), so we
         // leave the choice to the user via the suggestion menu.
         let single_fix = if missing_fields.len() == 1 {
-            let f = missing_fields[0];
-            Some(CodeFix::new(
-                format!("Add `{f}` selection"),
-                vec![TextEdit::insert(insert_pos, format!("{f}\n{indent}"))],
-            ))
+            add_selections_fix(doc.source, selection_set, &missing_fields)
         } else {
             None
         };
@@ -781,17 +778,6 @@ fn check_inline_fragment_type(
     }
 
     let selection_set_start: usize = inline_selection_set.syntax().text_range().start().into();
-    let selection_set_source = inline_selection_set.syntax().to_string();
-
-    let (insert_pos, indent) = inline_selection_set.selections().next().map_or_else(
-        || (selection_set_start + 1, "  ".to_string()),
-        |first| {
-            let pos: usize = first.syntax().text_range().start().into();
-            let relative_pos = pos - selection_set_start;
-            let indent = extract_indentation(&selection_set_source, relative_pos);
-            (pos, indent)
-        },
-    );
 
     let addition = if walked_fragments.is_empty() {
         String::new()
@@ -808,40 +794,24 @@ fn check_inline_fragment_type(
 
     if context.require_all_fields {
         for missing_field in &missing_fields {
-            let fix_label = format!("Add `{missing_field}` selection");
-            let fix_text = format!("{missing_field}\n{indent}");
-            let fix = CodeFix::new(fix_label, vec![TextEdit::insert(insert_pos, fix_text)]);
             let field_ref = format!("`{inline_type_name}.{missing_field}`");
-            diagnostics.push(
-                LintDiagnostic::error(
-                    doc.span(selection_set_start, selection_set_start),
-                    format!(
-                        "Field {field_ref} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
-                    ),
-                    "requireSelections",
-                )
-                .with_message_id("require-selections")
-                .with_fix(fix),
-            );
+            let mut diag = LintDiagnostic::error(
+                doc.span(selection_set_start, selection_set_start),
+                format!(
+                    "Field {field_ref} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
+                ),
+                "requireSelections",
+            )
+            .with_message_id("require-selections");
+            if let Some(fix) =
+                add_selections_fix(doc.source, inline_selection_set, &[*missing_field])
+            {
+                diag = diag.with_fix(fix);
+            }
+            diagnostics.push(diag);
         }
     } else {
-        let fix_label = if missing_fields.len() == 1 {
-            format!("Add `{}` selection", missing_fields[0])
-        } else {
-            let joined = missing_fields
-                .iter()
-                .map(|f| format!("`{f}`"))
-                .collect::<Vec<_>>()
-                .join(", ");
-            format!("Add {joined} selections")
-        };
-        let mut fix_text = String::new();
-        for f in &missing_fields {
-            fix_text.push_str(f);
-            fix_text.push('\n');
-            fix_text.push_str(&indent);
-        }
-        let fix = CodeFix::new(fix_label, vec![TextEdit::insert(insert_pos, fix_text)]);
+        let fix = add_selections_fix(doc.source, inline_selection_set, &missing_fields);
 
         let plural_suffix = if missing_fields.len() > 1 { "s" } else { "" };
         let joined_field_refs = english_join_words(
@@ -851,20 +821,47 @@ fn check_inline_fragment_type(
                 .collect::<Vec<_>>(),
         );
 
-        diagnostics.push(
-            LintDiagnostic::error(
-                doc.span(selection_set_start, selection_set_start),
-                format!(
-                    "Field{plural_suffix} {joined_field_refs} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
-                ),
-                "requireSelections",
-            )
-            .with_message_id("require-selections")
-            .with_fix(fix),
-        );
+        let mut diag = LintDiagnostic::error(
+            doc.span(selection_set_start, selection_set_start),
+            format!(
+                "Field{plural_suffix} {joined_field_refs} must be selected when it's available on a type.\nInclude it in your selection set{addition}."
+            ),
+            "requireSelections",
+        )
+        .with_message_id("require-selections");
+        if let Some(fix) = fix {
+            diag = diag.with_fix(fix);
+        }
+        diagnostics.push(diag);
     }
 }
 
+/// Fix that appends `fields` to the end of `selection_set`, laid out like the
+/// selections already there
+fn add_selections_fix(
+    source: &str,
+    selection_set: &cst::SelectionSet,
+    fields: &[&String],
+) -> Option<CodeFix> {
+    // Every insertion lands at the same offset, so they combine into one edit
+    let mut edit = graphql_syntax::edit::insert_field(source, selection_set, fields.first()?)?;
+    for field in &fields[1..] {
+        let next = graphql_syntax::edit::insert_field(source, selection_set, field)?;
+        edit.new_text.push_str(&next.new_text);
+    }
+    let label = if let [field] = fields {
+        format!("Add `{field}` selection")
+    } else {
+        let joined = fields
+            .iter()
+            .map(|f| format!("`{f}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Add {joined} selections")
+    };
+    Some(CodeFix::new(label, vec![edit.into()]))
+}
+
 /// Format a list of items using English-style disjunction (matching
 /// `Intl.ListFormat("en-US", { type: "disjunction" })` used by graphql-eslint):
 /// `a`, `a or b`, `a, b, or c`.
@@ -904,20 +901,6 @@ fn get_field_type(
     Some(field.type_ref.name.to_string())
 }
 
-/// Extract the indentation (whitespace) before a given position in source
-fn extract_indentation(source: &str, pos: usize) -> String {
-    let before = &source[..pos];
-    if let Some(newline_pos) = before.rfind('\n') {
-        let indent_slice = &before[newline_pos + 1..];
-        indent_slice
-            .chars()
-            .take_while(|c| c.is_whitespace() && *c != '\n')
-            .collect()
-    } else {
-        "  ".to_string()
-    }
-}
-
 /// Walk a fragment body and emit diagnostics for nested selection-set violations.
 ///
 /// Upstream graphql-eslint processes every `SelectionSet` node it encounters,
@@ -67,6 +67,19 @@ impl LintRule for RestyFieldNamesRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "prefixes": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "default": ["get", "list", "post", "put", "patch", "delete", "fetch"]
+                }
+            }
+        }))
+    }
 }
 
 /// Check whether a field name starts with a REST-style prefix.
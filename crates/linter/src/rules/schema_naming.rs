@@ -0,0 +1,81 @@
+use crate::diagnostics::{CodeSuggestion, LintDiagnostic, LintSeverity};
+use crate::rules::naming_convention::NamingCase;
+use graphql_hir::TextRange;
+use serde::Deserialize;
+
+/// Options shared by `fieldNamingConvention`, `typeNamingConvention` and
+/// `enumValueConvention`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct NamingAllowListOptions {
+    /// Names exempt from the convention, e.g. legacy fields kept for
+    /// backwards compatibility
+    pub allow: Vec<String>,
+}
+
+impl NamingAllowListOptions {
+    pub(super) fn from_json(value: Option<&serde_json::Value>) -> Self {
+        value
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Introspection names (`__typename`, `__Type`) are reserved by the spec
+    /// and never checked.
+    pub(super) fn is_exempt(&self, name: &str) -> bool {
+        name.starts_with("__") || self.allow.iter().any(|allowed| allowed == name)
+    }
+}
+
+pub(super) fn allow_list_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "allow": { "type": "array", "items": { "type": "string" } }
+        }
+    })
+}
+
+/// Report a schema name that doesn't follow `case`, suggesting the
+/// converted name.
+///
+/// The rename is a suggestion rather than an automatic fix: it only edits
+/// the definition, and operations or resolvers using the old name have to
+/// change with it.
+pub(super) fn naming_diagnostic(
+    rule: &'static str,
+    message_id: &'static str,
+    element: &str,
+    name: &str,
+    name_range: TextRange,
+    case: NamingCase,
+) -> LintDiagnostic {
+    let start: usize = name_range.start().into();
+    let end: usize = name_range.end().into();
+    let span = graphql_syntax::SourceSpan {
+        start,
+        end,
+        line_offset: 0,
+        byte_offset: 0,
+        source: None,
+    };
+
+    let mut diagnostic = LintDiagnostic::new(
+        span,
+        LintSeverity::Warning,
+        format!("{element} \"{name}\" should be in {} format", case.label()),
+        rule,
+    )
+    .with_message_id(message_id);
+
+    let renamed = case.convert(name);
+    if !renamed.is_empty() && renamed != name && case.check(&renamed) {
+        diagnostic = diagnostic.with_suggestion(CodeSuggestion::replace(
+            format!("Rename to `{renamed}`"),
+            start,
+            end,
+            renamed,
+        ));
+    }
+    diagnostic
+}
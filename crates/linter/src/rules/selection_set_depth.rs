@@ -46,6 +46,17 @@ impl LintRule for SelectionSetDepthRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "maxDepth": { "type": "integer", "minimum": 0 },
+                "ignore": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["maxDepth"]
+        }))
+    }
 }
 
 impl StandaloneDocumentLintRule for SelectionSetDepthRuleImpl {
@@ -87,6 +87,23 @@ impl LintRule for StrictIdInTypesRuleImpl {
     fn default_severity(&self) -> LintSeverity {
         LintSeverity::Warning
     }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(serde_json::json!({
+            "type": "object",
+            "properties": {
+                "acceptedIdNames": { "type": "array", "items": { "type": "string" }, "default": ["id"] },
+                "acceptedIdTypes": { "type": "array", "items": { "type": "string" }, "default": ["ID"] },
+                "exceptions": {
+                    "type": "object",
+                    "properties": {
+                        "types": { "type": "array", "items": { "type": "string" } },
+                        "suffixes": { "type": "array", "items": { "type": "string" } }
+                    }
+                }
+            }
+        }))
+    }
 }
 
 impl StandaloneSchemaLintRule for StrictIdInTypesRuleImpl {
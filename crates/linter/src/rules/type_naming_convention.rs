@@ -0,0 +1,144 @@
+use crate::diagnostics::{LintDiagnostic, LintSeverity};
+use crate::rules::naming_convention::NamingCase;
+use crate::rules::schema_naming::{allow_list_schema, naming_diagnostic, NamingAllowListOptions};
+use crate::schema_utils::raw_schema_type_defs;
+use crate::traits::{LintRule, StandaloneSchemaLintRule};
+use graphql_base_db::{FileId, ProjectFiles};
+use std::collections::HashMap;
+
+/// Lint rule that requires `PascalCase` names on every type definition
+///
+/// Extensions (`extend type user`) aren't reported separately; the
+/// definition they extend carries the diagnostic.
+pub struct TypeNamingConventionRuleImpl;
+
+impl LintRule for TypeNamingConventionRuleImpl {
+    fn name(&self) -> &'static str {
+        "typeNamingConvention"
+    }
+
+    fn description(&self) -> &'static str {
+        "Requires type names to be PascalCase"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        Some(allow_list_schema())
+    }
+}
+
+impl StandaloneSchemaLintRule for TypeNamingConventionRuleImpl {
+    fn check(
+        &self,
+        db: &dyn graphql_hir::GraphQLHirDatabase,
+        project_files: ProjectFiles,
+        options: Option<&serde_json::Value>,
+    ) -> HashMap<FileId, Vec<LintDiagnostic>> {
+        let opts = NamingAllowListOptions::from_json(options);
+        let mut diagnostics_by_file: HashMap<FileId, Vec<LintDiagnostic>> = HashMap::new();
+
+        for (file_id, type_def) in raw_schema_type_defs(db, project_files) {
+            if type_def.is_extension
+                || opts.is_exempt(&type_def.name)
+                || NamingCase::Pascal.check(&type_def.name)
+            {
+                continue;
+            }
+            diagnostics_by_file
+                .entry(file_id)
+                .or_default()
+                .push(naming_diagnostic(
+                    "typeNamingConvention",
+                    "TYPE_NAMING_CONVENTION",
+                    "Type",
+                    &type_def.name,
+                    type_def.name_range,
+                    NamingCase::Pascal,
+                ));
+        }
+
+        diagnostics_by_file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::StandaloneSchemaLintRule;
+    use graphql_base_db::{
+        DocumentFileIds, DocumentKind, FileContent, FileEntry, FileEntryMap, FileId, FileMetadata,
+        FileUri, Language, ProjectFiles, SchemaFileIds,
+    };
+    use graphql_ide_db::RootDatabase;
+    use std::sync::Arc;
+
+    fn create_schema_project(db: &RootDatabase, schema: &str) -> ProjectFiles {
+        let file_id = FileId::new(0);
+        let content = FileContent::new(db, Arc::from(schema));
+        let metadata = FileMetadata::new(
+            db,
+            file_id,
+            FileUri::new("file:///schema.graphql"),
+            Language::GraphQL,
+            DocumentKind::Schema,
+        );
+        let entry = FileEntry::new(db, content, metadata);
+        let mut entries = std::collections::HashMap::new();
+        entries.insert(file_id, entry);
+        let schema_file_ids = SchemaFileIds::new(db, Arc::new(vec![file_id]));
+        let document_file_ids = DocumentFileIds::new(db, Arc::new(vec![]));
+        let file_entry_map = FileEntryMap::new(db, Arc::new(entries));
+        ProjectFiles::new(
+            db,
+            schema_file_ids,
+            document_file_ids,
+            graphql_base_db::ResolvedSchemaFileIds::new(db, std::sync::Arc::new(vec![])),
+            file_entry_map,
+            graphql_base_db::FilePathMap::new(
+                db,
+                Arc::new(std::collections::HashMap::new()),
+                Arc::new(std::collections::HashMap::new()),
+            ),
+        )
+    }
+
+    fn check(schema: &str, options: Option<&serde_json::Value>) -> Vec<LintDiagnostic> {
+        let db = RootDatabase::default();
+        let project_files = create_schema_project(&db, schema);
+        RULE.check(&db, project_files, options)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    const RULE: TypeNamingConventionRuleImpl = TypeNamingConventionRuleImpl;
+
+    #[test]
+    fn test_pascal_case_types_pass() {
+        let all = check(
+            "type User { id: ID! }\nenum Role { ADMIN }\nscalar DateTime",
+            None,
+        );
+        assert!(all.is_empty());
+    }
+
+    #[test]
+    fn test_reports_definition_but_not_extension() {
+        let all = check(
+            "type user_profile { id: ID! }\nextend type user_profile { name: String }",
+            None,
+        );
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].suggestions[0].fix.edits[0].new_text, "UserProfile");
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let options = serde_json::json!({ "allow": ["iOSDevice"] });
+        let all = check("type iOSDevice { id: ID! }", Some(&options));
+        assert!(all.is_empty());
+    }
+}
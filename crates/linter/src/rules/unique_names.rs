@@ -57,28 +57,33 @@ impl ProjectLintRule for UniqueNamesRuleImpl {
         for (name, locations) in &operations_by_name {
             if locations.len() > 1 {
                 // Found duplicate operation names
-                for (file_id, op_info) in locations {
+                let spans: Vec<(FileId, SourceSpan)> = locations
+                    .iter()
+                    .map(|(file_id, op_info)| {
+                        // Use the actual name range if available, otherwise fall back to start of file
+                        let (start, end) = op_info.name_range.map_or_else(
+                            || (0usize, name.len()),
+                            |range| (range.start().into(), range.end().into()),
+                        );
+                        let span = SourceSpan::with_block_context(
+                            start,
+                            end,
+                            op_info.block_line_offset.unwrap_or(0),
+                            op_info.block_byte_offset.unwrap_or(0),
+                            op_info.block_source.clone(),
+                        );
+                        (*file_id, span)
+                    })
+                    .collect();
+
+                for (index, (file_id, span)) in spans.iter().enumerate() {
                     let message = format!(
                         "Operation name '{name}' is not unique across the project. Found {} definitions.",
                         locations.len()
                     );
 
-                    // Use the actual name range if available, otherwise fall back to start of file
-                    let (start, end) = op_info.name_range.map_or_else(
-                        || (0usize, name.len()),
-                        |range| (range.start().into(), range.end().into()),
-                    );
-
-                    let span = SourceSpan::with_block_context(
-                        start,
-                        end,
-                        op_info.block_line_offset.unwrap_or(0),
-                        op_info.block_byte_offset.unwrap_or(0),
-                        op_info.block_source.clone(),
-                    );
-
                     let diag = LintDiagnostic::new(
-                        span,
+                        span.clone(),
                         self.default_severity(),
                         message,
                         self.name().to_string(),
@@ -86,6 +91,7 @@ impl ProjectLintRule for UniqueNamesRuleImpl {
                     .with_help(
                         "Rename one of the operations so each operation has a unique name across the project",
                     );
+                    let diag = with_other_definitions(diag, &spans, index, "operation", name);
 
                     diagnostics_by_file.entry(*file_id).or_default().push(diag);
                 }
@@ -117,23 +123,28 @@ impl ProjectLintRule for UniqueNamesRuleImpl {
         for (name, locations) in &fragments_by_name {
             if locations.len() > 1 {
                 // Found duplicate fragment names
-                for (file_id, frag_info) in locations {
+                let spans: Vec<(FileId, SourceSpan)> = locations
+                    .iter()
+                    .map(|(file_id, frag_info)| {
+                        let span = SourceSpan::with_block_context(
+                            frag_info.name_range.start().into(),
+                            frag_info.name_range.end().into(),
+                            frag_info.block_line_offset.unwrap_or(0),
+                            frag_info.block_byte_offset.unwrap_or(0),
+                            frag_info.block_source.clone(),
+                        );
+                        (*file_id, span)
+                    })
+                    .collect();
+
+                for (index, (file_id, span)) in spans.iter().enumerate() {
                     let message = format!(
                         "Fragment name '{name}' is not unique across the project. Found {} definitions.",
                         locations.len()
                     );
 
-                    // Use the actual name range
-                    let span = SourceSpan::with_block_context(
-                        frag_info.name_range.start().into(),
-                        frag_info.name_range.end().into(),
-                        frag_info.block_line_offset.unwrap_or(0),
-                        frag_info.block_byte_offset.unwrap_or(0),
-                        frag_info.block_source.clone(),
-                    );
-
                     let diag = LintDiagnostic::new(
-                        span,
+                        span.clone(),
                         self.default_severity(),
                         message,
                         self.name().to_string(),
@@ -141,6 +152,7 @@ impl ProjectLintRule for UniqueNamesRuleImpl {
                     .with_help(
                         "Rename one of the fragments so each fragment has a unique name across the project",
                     );
+                    let diag = with_other_definitions(diag, &spans, index, "fragment", name);
 
                     diagnostics_by_file.entry(*file_id).or_default().push(diag);
                 }
@@ -151,6 +163,26 @@ impl ProjectLintRule for UniqueNamesRuleImpl {
     }
 }
 
+/// Link a duplicate's diagnostic to every other definition of the name
+fn with_other_definitions(
+    mut diag: LintDiagnostic,
+    spans: &[(FileId, SourceSpan)],
+    index: usize,
+    kind: &str,
+    name: &str,
+) -> LintDiagnostic {
+    for (other, (file_id, span)) in spans.iter().enumerate() {
+        if other != index {
+            diag = diag.with_related(
+                *file_id,
+                span.clone(),
+                format!("Another {kind} named '{name}' is defined here"),
+            );
+        }
+    }
+    diag
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -264,6 +296,29 @@ query GetUser { user { name } }
         assert_eq!(total_diags, 2);
     }
 
+    #[test]
+    fn test_duplicate_names_link_to_other_definitions() {
+        let db = RootDatabase::default();
+        let rule = UniqueNamesRuleImpl;
+
+        let documents = [
+            ("file:///file1.graphql", "query GetUser { user { id } }"),
+            ("file:///file2.graphql", "query GetUser { user { name } }"),
+            ("file:///file3.graphql", "query GetUser { user { email } }"),
+        ];
+
+        let project_files = create_multi_file_project(&db, &documents);
+        let diagnostics = rule.check(&db, project_files, None);
+
+        for (file_id, file_diags) in &diagnostics {
+            let related = &file_diags[0].related;
+            assert_eq!(related.len(), 2);
+            assert!(related.iter().all(|r| r.file_id != *file_id));
+            assert!(related[0].message.contains("GetUser"));
+            assert_eq!(related[0].span.end - related[0].span.start, "GetUser".len());
+        }
+    }
+
     #[test]
     fn test_unique_fragment_names_no_warning() {
         let db = RootDatabase::default();
@@ -16,6 +16,17 @@ pub trait LintRule: Send + Sync {
 
     /// Default severity (can be overridden by config)
     fn default_severity(&self) -> LintSeverity;
+
+    /// Whether the rule attaches automatic fixes to its diagnostics
+    fn fixable(&self) -> bool {
+        false
+    }
+
+    /// JSON Schema describing the rule's options, or `None` if the rule
+    /// takes no options
+    fn options_schema(&self) -> Option<serde_json::Value> {
+        None
+    }
 }
 
 /// Lint rule that runs on standalone documents (no schema required)
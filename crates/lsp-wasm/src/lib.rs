@@ -117,7 +117,9 @@ impl Server {
                 version: Some(env!("CARGO_PKG_VERSION").to_string()),
             }),
         };
-        let value = serde_json::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut value =
+            serde_json::to_value(&result).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        graphql_lsp::add_unmodeled_capabilities(&mut value["capabilities"]);
         let response = lsp_server::Response::new_ok(id, value);
         let out = serde_json::to_string(&Message::Response(response))
             .map_err(|e| JsValue::from_str(&e.to_string()))?;
@@ -10,8 +10,8 @@
 use std::path::PathBuf;
 
 use lsp_types::{
-    CodeLens, Command, Diagnostic, DiagnosticSeverity, FoldingRange, FoldingRangeKind, InlayHint,
-    InlayHintKind, InlayHintLabel, Location, Position, Range, Uri,
+    CodeLens, Command, Diagnostic, DiagnosticSeverity, DocumentLink, FoldingRange,
+    FoldingRangeKind, InlayHint, InlayHintKind, InlayHintLabel, Location, Position, Range, Uri,
 };
 
 // =============================================================================
@@ -37,6 +37,14 @@ pub const fn convert_lsp_position(pos: Position) -> graphql_ide::Position {
     graphql_ide::Position::new(pos.line, pos.character)
 }
 
+/// Convert LSP Range to graphql-ide Range
+pub const fn convert_lsp_range(range: Range) -> graphql_ide::Range {
+    graphql_ide::Range::new(
+        convert_lsp_position(range.start),
+        convert_lsp_position(range.end),
+    )
+}
+
 /// Convert graphql-ide Position to LSP Position
 pub const fn convert_ide_position(pos: graphql_ide::Position) -> Position {
     Position {
@@ -61,6 +69,20 @@ pub fn convert_ide_location(loc: &graphql_ide::Location) -> Location {
     }
 }
 
+/// Convert graphql-ide `DocumentHighlight` to LSP `DocumentHighlight`
+pub fn convert_ide_document_highlight(
+    highlight: graphql_ide::DocumentHighlight,
+) -> lsp_types::DocumentHighlight {
+    lsp_types::DocumentHighlight {
+        range: convert_ide_range(highlight.range),
+        kind: Some(match highlight.kind {
+            graphql_ide::DocumentHighlightKind::Text => lsp_types::DocumentHighlightKind::TEXT,
+            graphql_ide::DocumentHighlightKind::Read => lsp_types::DocumentHighlightKind::READ,
+            graphql_ide::DocumentHighlightKind::Write => lsp_types::DocumentHighlightKind::WRITE,
+        }),
+    }
+}
+
 /// Convert graphql-ide `CompletionItem` to LSP `CompletionItem`
 pub fn convert_ide_completion_item(item: graphql_ide::CompletionItem) -> lsp_types::CompletionItem {
     lsp_types::CompletionItem {
@@ -68,7 +90,9 @@ pub fn convert_ide_completion_item(item: graphql_ide::CompletionItem) -> lsp_typ
         kind: Some(match item.kind {
             graphql_ide::CompletionKind::Field => lsp_types::CompletionItemKind::FIELD,
             graphql_ide::CompletionKind::Type => lsp_types::CompletionItemKind::CLASS,
-            graphql_ide::CompletionKind::Fragment => lsp_types::CompletionItemKind::SNIPPET,
+            graphql_ide::CompletionKind::Fragment | graphql_ide::CompletionKind::Snippet => {
+                lsp_types::CompletionItemKind::SNIPPET
+            }
             graphql_ide::CompletionKind::Directive | graphql_ide::CompletionKind::Keyword => {
                 lsp_types::CompletionItemKind::KEYWORD
             }
@@ -84,6 +108,9 @@ pub fn convert_ide_completion_item(item: graphql_ide::CompletionItem) -> lsp_typ
             })
         }),
         deprecated: Some(item.deprecated),
+        tags: item
+            .deprecated
+            .then(|| vec![lsp_types::CompletionItemTag::DEPRECATED]),
         insert_text: item.insert_text,
         insert_text_format: item.insert_text_format.map(|format| match format {
             graphql_ide::InsertTextFormat::PlainText => lsp_types::InsertTextFormat::PLAIN_TEXT,
@@ -131,6 +158,22 @@ pub fn convert_ide_diagnostic(diag: graphql_ide::Diagnostic) -> Diagnostic {
         })
         .collect();
 
+    // Skip related locations whose URI the client can't parse instead of
+    // dropping the whole diagnostic
+    let related_information: Vec<lsp_types::DiagnosticRelatedInformation> = diag
+        .related
+        .iter()
+        .filter_map(|related| {
+            Some(lsp_types::DiagnosticRelatedInformation {
+                location: Location {
+                    uri: related.location.file.as_str().parse().ok()?,
+                    range: convert_ide_range(related.location.range),
+                },
+                message: related.message.clone(),
+            })
+        })
+        .collect();
+
     // LSP has no dedicated `help` field, so we append help text to the message.
     // Clients that render `codeDescription` will still see the doc link separately.
     let mut message = diag.message;
@@ -146,6 +189,11 @@ pub fn convert_ide_diagnostic(diag: graphql_ide::Diagnostic) -> Diagnostic {
         source: Some(diag.source),
         message,
         tags: if tags.is_empty() { None } else { Some(tags) },
+        related_information: if related_information.is_empty() {
+            None
+        } else {
+            Some(related_information)
+        },
         ..Default::default()
     }
 }
@@ -171,6 +219,22 @@ pub const fn convert_ide_symbol_kind(kind: graphql_ide::SymbolKind) -> lsp_types
     }
 }
 
+/// Convert graphql-ide `TypeHierarchyItem` to LSP `TypeHierarchyItem`
+pub fn convert_ide_type_hierarchy_item(
+    item: graphql_ide::TypeHierarchyItem,
+) -> lsp_types::TypeHierarchyItem {
+    lsp_types::TypeHierarchyItem {
+        name: item.name,
+        kind: convert_ide_symbol_kind(item.kind),
+        tags: None,
+        detail: item.detail,
+        uri: item.file.as_str().parse().expect("Invalid URI"),
+        range: convert_ide_range(item.range),
+        selection_range: convert_ide_range(item.selection_range),
+        data: None,
+    }
+}
+
 /// Convert graphql-ide `DocumentSymbol` to LSP `DocumentSymbol`
 #[allow(deprecated)] // LSP requires deprecated field
 pub fn convert_ide_document_symbol(
@@ -218,11 +282,14 @@ pub fn convert_ide_workspace_symbol(
 /// Creates a code lens that shows the usage count for deprecated fields.
 /// When clicked, it navigates to the usages using the "find all references" command.
 pub fn convert_ide_code_lens_info(info: &graphql_ide::CodeLensInfo, uri: &Uri) -> CodeLens {
-    let title = if info.usage_count == 1 {
+    let mut title = if info.usage_count == 1 {
         "1 usage remaining".to_string()
     } else {
         format!("{} usages remaining", info.usage_count)
     };
+    if let Some(calls) = info.recorded_calls {
+        title = format!("{title} · {calls}");
+    }
 
     // Create the command that will be executed when the code lens is clicked.
     // We use our custom graphql-analyzer.showReferences command which handles the
@@ -263,6 +330,71 @@ pub fn convert_ide_code_lens_info(info: &graphql_ide::CodeLensInfo, uri: &Uri) -
     }
 }
 
+/// Convert graphql-ide `TypeUsageLensInfo` to LSP `CodeLens`
+///
+/// Shows how many operations select the type; clicking lists them.
+pub fn convert_ide_type_usage_lens(info: &graphql_ide::TypeUsageLensInfo, uri: &Uri) -> CodeLens {
+    let count = info.operation_count();
+    let title = if count == 1 {
+        "1 operation uses this type".to_string()
+    } else {
+        format!("{count} operations use this type")
+    };
+    let references: Vec<Location> = info
+        .operation_locations
+        .iter()
+        .map(convert_ide_location)
+        .collect();
+    let lens = graphql_ide::CodeLens::new(info.range, title);
+    convert_ide_code_lens(&lens, uri, &references)
+}
+
+/// Convert graphql-ide `FieldImpactLensInfo` to LSP `CodeLens`
+///
+/// Shows how many operations depend on the field; clicking lists them.
+pub fn convert_ide_field_impact_lens(
+    info: &graphql_ide::FieldImpactLensInfo,
+    uri: &Uri,
+) -> CodeLens {
+    let count = info.impact.operation_count();
+    let title = if count == 1 {
+        "1 operation depends on this field".to_string()
+    } else {
+        format!("{count} operations depend on this field")
+    };
+    let references: Vec<Location> = info
+        .impact
+        .operations
+        .iter()
+        .map(|operation| convert_ide_location(&operation.location))
+        .collect();
+    let lens = graphql_ide::CodeLens::new(info.range, title);
+    convert_ide_code_lens(&lens, uri, &references)
+}
+
+/// Convert graphql-ide `OperationCopyLensInfo` to LSP `CodeLens`
+///
+/// Runs the client's `graphql-analyzer.copyOperation` command, which asks
+/// the server for the operation with its fragments through
+/// `graphql-analyzer.operationWithFragments` and copies the result.
+pub fn convert_ide_operation_copy_lens(
+    info: &graphql_ide::OperationCopyLensInfo,
+    uri: &Uri,
+) -> CodeLens {
+    CodeLens {
+        range: convert_ide_range(info.range),
+        command: Some(Command {
+            title: "Copy with fragments".to_string(),
+            command: "graphql-analyzer.copyOperation".to_string(),
+            arguments: Some(vec![
+                serde_json::to_value(uri.to_string()).expect("String is always serializable"),
+                serde_json::to_value(info.operation_index).expect("usize is always serializable"),
+            ]),
+        }),
+        data: None,
+    }
+}
+
 /// Convert graphql-ide `CodeLens` to LSP `CodeLens`
 ///
 /// Creates a code lens for fragment definitions showing reference counts.
@@ -310,11 +442,33 @@ pub fn convert_ide_folding_range(range: &graphql_ide::FoldingRange) -> FoldingRa
         kind: Some(match range.kind {
             graphql_ide::FoldingRangeKind::Region => FoldingRangeKind::Region,
             graphql_ide::FoldingRangeKind::Comment => FoldingRangeKind::Comment,
+            graphql_ide::FoldingRangeKind::Imports => FoldingRangeKind::Imports,
         }),
         collapsed_text: None,
     }
 }
 
+/// Convert graphql-ide `DocumentLink` to LSP `DocumentLink`
+///
+/// LSP link targets are plain URIs, so the definition position goes in a
+/// `#L<line>,<column>` fragment (1-based), which editors resolve to a
+/// cursor position.
+pub fn convert_ide_document_link(link: &graphql_ide::DocumentLink) -> Option<DocumentLink> {
+    let start = link.target.range.start;
+    let target = format!(
+        "{}#L{},{}",
+        link.target.file.as_str(),
+        start.line + 1,
+        start.character + 1
+    );
+    Some(DocumentLink {
+        range: convert_ide_range(link.range),
+        target: Some(target.parse().ok()?),
+        tooltip: None,
+        data: None,
+    })
+}
+
 /// Convert graphql-ide `InlayHint` to LSP `InlayHint`
 pub fn convert_ide_inlay_hint(hint: &graphql_ide::InlayHint) -> InlayHint {
     InlayHint {
@@ -502,6 +656,7 @@ mod tests {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         assert_eq!(lsp_diag.severity, Some(DiagnosticSeverity::ERROR));
@@ -526,6 +681,7 @@ mod tests {
             help: None,
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         assert_eq!(lsp_diag.severity, Some(DiagnosticSeverity::WARNING));
@@ -548,6 +704,7 @@ mod tests {
             help: Some("Use the replacement field".to_string()),
             url: None,
             tags: Vec::new(),
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         assert_eq!(
@@ -573,6 +730,7 @@ mod tests {
             help: None,
             url: Some("https://graphql-analyzer.dev/rules/noDeprecated".to_string()),
             tags: Vec::new(),
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         let desc = lsp_diag
@@ -601,6 +759,7 @@ mod tests {
             help: None,
             url: Some("not a valid url".to_string()),
             tags: Vec::new(),
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         assert!(
@@ -629,6 +788,7 @@ mod tests {
                 graphql_ide::DiagnosticTag::Unnecessary,
                 graphql_ide::DiagnosticTag::Deprecated,
             ],
+            related: Vec::new(),
         };
         let lsp_diag = convert_ide_diagnostic(ide_diag);
         let tags = lsp_diag.tags.expect("tags should be present");
@@ -637,6 +797,37 @@ mod tests {
         assert_eq!(tags[1], lsp_types::DiagnosticTag::DEPRECATED);
     }
 
+    #[test]
+    fn test_convert_ide_diagnostic_related_information() {
+        let range = graphql_ide::Range::new(
+            graphql_ide::Position::new(2, 6),
+            graphql_ide::Position::new(2, 13),
+        );
+        let ide_diag = graphql_ide::Diagnostic {
+            related: vec![graphql_ide::RelatedInformation {
+                location: graphql_ide::Location::new(
+                    graphql_ide::FilePath::new("file:///other.graphql"),
+                    range,
+                ),
+                message: "Also defined here".to_string(),
+            }],
+            ..graphql_ide::Diagnostic::new(
+                range,
+                graphql_ide::DiagnosticSeverity::Error,
+                "msg",
+                "graphql-linter",
+            )
+        };
+        let lsp_diag = convert_ide_diagnostic(ide_diag);
+        let related = lsp_diag
+            .related_information
+            .expect("related information should be present");
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].location.uri.as_str(), "file:///other.graphql");
+        assert_eq!(related[0].location.range.start.line, 2);
+        assert_eq!(related[0].message, "Also defined here");
+    }
+
     #[test]
     fn test_convert_ide_symbol_kind() {
         assert_eq!(
@@ -678,6 +869,29 @@ mod tests {
         assert_eq!(lsp_range.kind, Some(FoldingRangeKind::Region));
     }
 
+    #[test]
+    fn test_convert_ide_document_link() {
+        let link = graphql_ide::DocumentLink {
+            range: graphql_ide::Range::new(
+                graphql_ide::Position::new(2, 7),
+                graphql_ide::Position::new(2, 18),
+            ),
+            target: graphql_ide::Location::new(
+                graphql_ide::FilePath::new("file:///fragments.graphql"),
+                graphql_ide::Range::new(
+                    graphql_ide::Position::new(4, 9),
+                    graphql_ide::Position::new(4, 20),
+                ),
+            ),
+        };
+        let lsp_link = convert_ide_document_link(&link).unwrap();
+        assert_eq!(lsp_link.range.start, Position::new(2, 7));
+        assert_eq!(
+            lsp_link.target.unwrap().as_str(),
+            "file:///fragments.graphql#L5,10"
+        );
+    }
+
     #[test]
     fn test_convert_ide_inlay_hint() {
         let ide_hint = graphql_ide::InlayHint {
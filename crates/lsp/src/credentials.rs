@@ -0,0 +1,372 @@
+//! Guarding the credentials sent with remote schema introspection.
+//!
+//! Introspection headers come from the workspace's config, so opening an
+//! untrusted repository could otherwise send a token from the user's
+//! environment to any endpoint the repository names. The same goes for a URL
+//! with environment variables interpolated into it. Before the first such
+//! request goes to an endpoint, the user is asked through
+//! `window/showMessageRequest`; the introspection waits for the answer and
+//! is skipped if it's no. Approvals are per origin and last until the
+//! server exits, so reloading the config doesn't ask again.
+//!
+//! Header values may reference `${secret:NAME}`, resolved through the
+//! [`SecretsProvider`] once the request is approved. The default provider
+//! reads environment variables; embedders install their own with
+//! [`GlobalState::set_secrets_provider`].
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use graphql_config::{EnvSecrets, SecretsProvider};
+use lsp_server::{Message, RequestId};
+use lsp_types::request::Request as _;
+use lsp_types::{MessageActionItem, MessageType, ShowMessageParams, ShowMessageRequestParams};
+
+use crate::global_state::{GlobalState, IntrospectionRequest};
+
+const SEND: &str = "Send credentials";
+const DONT_SEND: &str = "Don't send";
+
+pub struct CredentialGuard {
+    pub secrets: Arc<dyn SecretsProvider>,
+    /// Ask before sending headers to an endpoint. Off when there's no user
+    /// to ask, as in the one-shot JSON lines mode.
+    pub prompt: bool,
+    /// Origins the user has agreed to send credentials to
+    approved: HashSet<String>,
+    /// Introspections waiting on the answer to a prompt, by the id of the
+    /// `window/showMessageRequest` that asked
+    prompts: HashMap<RequestId, Prompt>,
+    next_id: u32,
+}
+
+struct Prompt {
+    origin: String,
+    requests: Vec<IntrospectionRequest>,
+}
+
+impl Default for CredentialGuard {
+    fn default() -> Self {
+        Self {
+            secrets: Arc::new(EnvSecrets),
+            prompt: true,
+            approved: HashSet::new(),
+            prompts: HashMap::new(),
+            next_id: 0,
+        }
+    }
+}
+
+/// Queue a remote schema introspection, asking the user first if it would
+/// send headers or environment values to an endpoint they haven't approved.
+pub fn request_introspection(state: &mut GlobalState, request: IntrospectionRequest) {
+    let has_headers = request
+        .pending
+        .headers
+        .as_ref()
+        .is_some_and(|headers| !headers.is_empty());
+    let url_from_env = request.pending.url_from_env;
+    let origin = origin(&request.pending.url).to_string();
+    if !(has_headers || url_from_env)
+        || !state.credentials.prompt
+        || state.credentials.approved.contains(&origin)
+    {
+        send(state, request);
+        return;
+    }
+
+    // Projects sharing an endpoint wait on the same prompt
+    state.pending_introspections += 1;
+    if let Some(prompt) = state
+        .credentials
+        .prompts
+        .values_mut()
+        .find(|prompt| prompt.origin == origin)
+    {
+        prompt.requests.push(request);
+        return;
+    }
+
+    let guard = &mut state.credentials;
+    guard.next_id += 1;
+    let id = RequestId::from(format!("graphql-analyzer/credentials/{}", guard.next_id));
+    let what = match (has_headers, url_from_env) {
+        (true, true) => {
+            "the headers configured for its remote schema, and environment variables in its URL,"
+        }
+        (true, false) => "the headers configured for its remote schema",
+        (false, _) => "environment variables in its remote schema URL",
+    };
+    let params = ShowMessageRequestParams {
+        typ: MessageType::WARNING,
+        message: format!(
+            "Project '{}' wants to send {what} to {origin}. \
+             They may contain credentials. Send them?",
+            request.project_name
+        ),
+        actions: Some(vec![
+            MessageActionItem {
+                title: SEND.to_string(),
+                properties: HashMap::new(),
+            },
+            MessageActionItem {
+                title: DONT_SEND.to_string(),
+                properties: HashMap::new(),
+            },
+        ]),
+    };
+    guard.prompts.insert(
+        id.clone(),
+        Prompt {
+            origin,
+            requests: vec![request],
+        },
+    );
+    let message = lsp_server::Request::new(
+        id,
+        lsp_types::request::ShowMessageRequest::METHOD.to_owned(),
+        params,
+    );
+    state
+        .sender
+        .send(Message::Request(message))
+        .expect("client channel open");
+}
+
+/// Handle the client's answer to a credentials prompt. Returns `false` for
+/// responses to anything else.
+pub fn handle_response(state: &mut GlobalState, response: &lsp_server::Response) -> bool {
+    let Some(prompt) = state.credentials.prompts.remove(&response.id) else {
+        return false;
+    };
+    state.pending_introspections = state
+        .pending_introspections
+        .saturating_sub(prompt.requests.len());
+
+    let approved = response
+        .result
+        .clone()
+        .and_then(|result| serde_json::from_value::<Option<MessageActionItem>>(result).ok())
+        .flatten()
+        .is_some_and(|action| action.title == SEND);
+    if !approved {
+        tracing::info!(origin = %prompt.origin, "Credentials not approved, skipping introspection");
+        state.send_notification::<lsp_types::notification::ShowMessage>(ShowMessageParams {
+            typ: MessageType::INFO,
+            message: format!(
+                "Remote schema from {} not loaded: sending its credentials wasn't allowed",
+                prompt.origin
+            ),
+        });
+        return true;
+    }
+
+    state.credentials.approved.insert(prompt.origin);
+    for request in prompt.requests {
+        send(state, request);
+    }
+    true
+}
+
+/// Resolve the request's secrets and hand it to the introspection thread
+fn send(state: &mut GlobalState, mut request: IntrospectionRequest) {
+    if let Some(headers) = &request.pending.headers {
+        match graphql_config::resolve_header_secrets(headers, &*state.credentials.secrets) {
+            Ok(resolved) => request.pending.headers = Some(resolved),
+            Err(e) => {
+                tracing::error!(url = %request.pending.url, "Not introspecting: {e}");
+                state.send_notification::<lsp_types::notification::ShowMessage>(
+                    ShowMessageParams {
+                        typ: MessageType::ERROR,
+                        message: format!(
+                            "Failed to load remote schema from {}: {e}",
+                            request.pending.url
+                        ),
+                    },
+                );
+                return;
+            }
+        }
+    }
+
+    if state.introspection_request_sender.send(request).is_ok() {
+        state.pending_introspections += 1;
+    }
+}
+
+/// The scheme, host and port of a URL, which is what an approval covers
+fn origin(url: &str) -> &str {
+    let authority_start = url.find("://").map_or(0, |i| i + 3);
+    let end = url[authority_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |i| authority_start + i);
+    &url[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossbeam_channel::{unbounded, Receiver};
+
+    fn make_state() -> (
+        GlobalState,
+        Receiver<Message>,
+        Receiver<IntrospectionRequest>,
+    ) {
+        let (msg_sender, msg_receiver) = unbounded();
+        let (intro_req_sender, intro_req_receiver) = unbounded();
+        let (_intro_res_sender, intro_res_receiver) = unbounded();
+        let state = GlobalState::new(
+            msg_sender,
+            Box::new(crate::global_state::InlineDispatcher),
+            intro_req_sender,
+            intro_res_receiver,
+        );
+        (state, msg_receiver, intro_req_receiver)
+    }
+
+    fn introspection(project: &str, url: &str, token: &str) -> IntrospectionRequest {
+        IntrospectionRequest {
+            workspace_uri: "file:///app".to_string(),
+            project_name: project.to_string(),
+            pending: graphql_ide::PendingIntrospection {
+                url: url.to_string(),
+                headers: Some(HashMap::from([(
+                    "Authorization".to_string(),
+                    token.to_string(),
+                )])),
+                timeout: None,
+                retry: None,
+                url_from_env: false,
+            },
+        }
+    }
+
+    fn answer(id: RequestId, title: Option<&str>) -> lsp_server::Response {
+        let result = title.map(|title| MessageActionItem {
+            title: title.to_string(),
+            properties: HashMap::new(),
+        });
+        lsp_server::Response::new_ok(id, result)
+    }
+
+    #[test]
+    fn test_origin() {
+        assert_eq!(
+            origin("https://api.example.com:8443/graphql?x=1"),
+            "https://api.example.com:8443"
+        );
+        assert_eq!(origin("http://localhost:4000"), "http://localhost:4000");
+    }
+
+    #[test]
+    fn test_prompts_once_per_origin() {
+        let (mut state, messages, introspections) = make_state();
+
+        request_introspection(
+            &mut state,
+            introspection("a", "https://api.example.com/graphql", "Bearer a"),
+        );
+        request_introspection(
+            &mut state,
+            introspection("b", "https://api.example.com/v2", "Bearer b"),
+        );
+        let Ok(Message::Request(prompt)) = messages.try_recv() else {
+            panic!("expected a showMessageRequest");
+        };
+        assert_eq!(prompt.method, "window/showMessageRequest");
+        assert!(messages.try_recv().is_err());
+        assert!(introspections.try_recv().is_err());
+        assert_eq!(state.pending_introspections, 2);
+
+        assert!(handle_response(&mut state, &answer(prompt.id, Some(SEND))));
+        assert_eq!(introspections.try_iter().count(), 2);
+        assert_eq!(state.pending_introspections, 2);
+
+        // Approved origins don't ask again
+        request_introspection(
+            &mut state,
+            introspection("c", "https://api.example.com/graphql", "Bearer c"),
+        );
+        assert!(messages.try_recv().is_err());
+        assert_eq!(introspections.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_declined_prompt_skips_introspection() {
+        let (mut state, messages, introspections) = make_state();
+        request_introspection(
+            &mut state,
+            introspection("a", "https://evil.example.com/graphql", "Bearer a"),
+        );
+        let Ok(Message::Request(prompt)) = messages.try_recv() else {
+            panic!("expected a showMessageRequest");
+        };
+
+        assert!(handle_response(&mut state, &answer(prompt.id, None)));
+        assert!(introspections.try_recv().is_err());
+        assert_eq!(state.pending_introspections, 0);
+        assert!(!handle_response(
+            &mut state,
+            &answer(RequestId::from(1), Some(SEND))
+        ));
+    }
+
+    #[test]
+    fn test_prompts_for_env_values_in_url() {
+        let (mut state, messages, introspections) = make_state();
+        let request = |url_from_env| {
+            let mut request = introspection("a", "https://evil.example.com/?k=AKIA", "");
+            request.pending.headers = None;
+            request.pending.url_from_env = url_from_env;
+            request
+        };
+
+        request_introspection(&mut state, request(false));
+        assert!(messages.try_recv().is_err());
+        assert_eq!(introspections.try_iter().count(), 1);
+
+        request_introspection(&mut state, request(true));
+        let Ok(Message::Request(prompt)) = messages.try_recv() else {
+            panic!("expected a showMessageRequest");
+        };
+        assert_eq!(prompt.method, "window/showMessageRequest");
+        assert!(introspections.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_resolves_secrets_on_send() {
+        struct Secrets;
+        impl SecretsProvider for Secrets {
+            fn secret(&self, name: &str) -> Option<String> {
+                (name == "API_TOKEN").then(|| "s3cret".to_string())
+            }
+        }
+
+        let (mut state, _messages, introspections) = make_state();
+        state.credentials.prompt = false;
+        state.set_secrets_provider(Arc::new(Secrets));
+
+        request_introspection(
+            &mut state,
+            introspection(
+                "a",
+                "https://api.example.com/graphql",
+                "Bearer ${secret:API_TOKEN}",
+            ),
+        );
+        let sent = introspections.try_recv().unwrap();
+        assert_eq!(
+            sent.pending.headers.unwrap().get("Authorization"),
+            Some(&"Bearer s3cret".to_string())
+        );
+
+        request_introspection(
+            &mut state,
+            introspection("a", "https://api.example.com/graphql", "${secret:MISSING}"),
+        );
+        assert!(introspections.try_recv().is_err());
+        assert_eq!(state.pending_introspections, 1);
+    }
+}
@@ -0,0 +1,167 @@
+//! Debounced, deduplicated diagnostics publishing.
+//!
+//! Every keystroke used to spawn a diagnostics computation for the edited
+//! file, and every save republished the whole project. The scheduler sits
+//! between the handlers and the worker pool:
+//!
+//! - Edits only mark a file as pending. It's computed once no edit has
+//!   arrived for [`DEBOUNCE`], against a snapshot taken at that point.
+//! - The focused file (the one most recently opened or edited) goes first
+//!   when several files come due together.
+//! - Before a publish goes out, the diagnostics are hashed and compared with
+//!   the last set sent for that file. Project-wide recomputation on save
+//!   therefore only sends the files whose diagnostics actually changed.
+
+// Debouncing needs the native main loop's timer; the wasm server only uses
+// the publish deduplication
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use lsp_types::Uri;
+
+/// Quiet period after the last edit before a file's diagnostics are computed
+pub const DEBOUNCE: Duration = Duration::from_millis(150);
+
+#[derive(Default)]
+pub struct DiagnosticsScheduler {
+    /// Files waiting for their quiet period to end, keyed by URI string
+    pending: HashMap<String, (Uri, Instant)>,
+    focused: Option<String>,
+    /// Hash of the last diagnostics published per URI string
+    published: HashMap<String, u64>,
+}
+
+impl DiagnosticsScheduler {
+    /// Mark `uri` as edited at `now`, pushing back its deadline if it was
+    /// already pending. The edited file becomes the focused one.
+    pub fn schedule(&mut self, uri: Uri, now: Instant) {
+        let key = uri.to_string();
+        self.focused = Some(key.clone());
+        self.pending.insert(key, (uri, now + DEBOUNCE));
+    }
+
+    pub fn set_focused(&mut self, uri: &Uri) {
+        self.focused = Some(uri.to_string());
+    }
+
+    /// When the main loop should wake up to flush pending files
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().map(|(_, due)| *due).min()
+    }
+
+    /// Remove and return the files whose quiet period has ended, focused
+    /// file first and the rest in deadline order
+    pub fn take_due(&mut self, now: Instant) -> Vec<Uri> {
+        let mut due: Vec<(String, Uri, Instant)> = Vec::new();
+        self.pending.retain(|key, (uri, deadline)| {
+            if *deadline > now {
+                return true;
+            }
+            due.push((key.clone(), uri.clone(), *deadline));
+            false
+        });
+
+        let focused = self.focused.as_deref();
+        due.sort_by_key(|(key, _, deadline)| (Some(key.as_str()) != focused, *deadline));
+        due.into_iter().map(|(_, uri, _)| uri).collect()
+    }
+
+    /// Record `diagnostics` as published for `uri`, returning `false` when
+    /// they're identical to what the client already has
+    pub fn record_publish(&mut self, uri: &Uri, diagnostics: &[lsp_types::Diagnostic]) -> bool {
+        let hash = hash_diagnostics(diagnostics);
+        self.published.insert(uri.to_string(), hash) != Some(hash)
+    }
+
+    /// Drop what was last published for `uri`, once it's closed, deleted or
+    /// renamed away; publishing to it again always goes through
+    pub fn forget(&mut self, uri: &Uri) {
+        self.published.remove(uri.as_str());
+    }
+}
+
+fn hash_diagnostics(diagnostics: &[lsp_types::Diagnostic]) -> u64 {
+    // `lsp_types::Diagnostic` doesn't implement `Hash`; its JSON form is what
+    // the client sees anyway
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_vec(diagnostics)
+        .expect("diagnostics are serializable")
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::str::FromStr;
+
+    fn uri(path: &str) -> Uri {
+        Uri::from_str(&format!("file:///{path}")).unwrap()
+    }
+
+    #[test]
+    fn edits_push_back_the_deadline() {
+        let mut scheduler = DiagnosticsScheduler::default();
+        let start = Instant::now();
+        scheduler.schedule(uri("a.graphql"), start);
+        scheduler.schedule(uri("a.graphql"), start + DEBOUNCE / 2);
+
+        assert!(scheduler.take_due(start + DEBOUNCE).is_empty());
+        assert_eq!(
+            scheduler.next_deadline(),
+            Some(start + DEBOUNCE / 2 + DEBOUNCE)
+        );
+        assert_eq!(
+            scheduler.take_due(start + DEBOUNCE * 2),
+            vec![uri("a.graphql")]
+        );
+        assert_eq!(scheduler.next_deadline(), None);
+    }
+
+    #[test]
+    fn focused_file_comes_first() {
+        let mut scheduler = DiagnosticsScheduler::default();
+        let start = Instant::now();
+        scheduler.schedule(uri("a.graphql"), start);
+        scheduler.schedule(uri("b.graphql"), start);
+        scheduler.schedule(uri("c.graphql"), start + Duration::from_millis(1));
+        scheduler.set_focused(&uri("b.graphql"));
+
+        assert_eq!(
+            scheduler.take_due(start + DEBOUNCE * 2),
+            vec![uri("b.graphql"), uri("a.graphql"), uri("c.graphql")]
+        );
+    }
+
+    #[test]
+    fn unchanged_diagnostics_are_not_republished() {
+        let mut scheduler = DiagnosticsScheduler::default();
+        let file = uri("a.graphql");
+        let diagnostic = lsp_types::Diagnostic {
+            message: "Unknown field".to_owned(),
+            ..Default::default()
+        };
+
+        assert!(scheduler.record_publish(&file, &[]));
+        assert!(!scheduler.record_publish(&file, &[]));
+        assert!(scheduler.record_publish(&file, std::slice::from_ref(&diagnostic)));
+        assert!(!scheduler.record_publish(&file, &[diagnostic]));
+        assert!(scheduler.record_publish(&file, &[]));
+    }
+
+    #[test]
+    fn forgotten_uri_is_republished() {
+        let mut scheduler = DiagnosticsScheduler::default();
+        let file = uri("a.graphql");
+
+        assert!(scheduler.record_publish(&file, &[]));
+        scheduler.forget(&file);
+        assert!(scheduler.published.is_empty());
+        assert!(scheduler.record_publish(&file, &[]));
+    }
+}
@@ -1,7 +1,10 @@
+use std::time::Instant;
+
 use lsp_server::{ExtractError, Notification, Request};
 use lsp_types::Uri;
 
 use crate::global_state::{GlobalState, GlobalStateSnapshot};
+use crate::request_stats::RequestTiming;
 
 /// Routes one incoming request to the matching handler.
 ///
@@ -44,7 +47,7 @@ impl<'a> RequestDispatcher<'a> {
             Ok((id, params)) => {
                 let uri = extract_uri(&params);
                 self.state
-                    .spawn_with_snapshot(id, &uri, move |snap| handler(snap, params));
+                    .spawn_with_snapshot(id, R::METHOD, &uri, move |snap| handler(snap, params));
             }
             Err(ExtractError::JsonError { error, .. }) => {
                 respond_invalid_params(self.state, req_id, R::METHOD, &error);
@@ -74,7 +77,10 @@ impl<'a> RequestDispatcher<'a> {
         let req_id = req.id.clone();
         match req.extract::<R::Params>(R::METHOD) {
             Ok((id, params)) => {
+                let started = Instant::now();
                 let result = handler(self.state, params);
+                self.state
+                    .record_request(RequestTiming::new(R::METHOD, None, started.elapsed()));
                 let value = serde_json::to_value(&result).expect("handler result is serializable");
                 self.state.respond(lsp_server::Response::new_ok(id, value));
             }
@@ -86,6 +92,34 @@ impl<'a> RequestDispatcher<'a> {
         self
     }
 
+    /// Route a request to a main-thread handler that answers it later, by
+    /// spawning pool work whose task `handle_task` turns into the response.
+    /// For requests whose worker needs state only the main thread may read.
+    pub fn on_main_deferred<R, F>(&mut self, handler: F) -> &mut Self
+    where
+        R: lsp_types::request::Request,
+        R::Params: serde::de::DeserializeOwned,
+        F: FnOnce(&mut GlobalState, lsp_server::RequestId, R::Params),
+    {
+        let Some(req) = self.req.take() else {
+            return self;
+        };
+        if req.method != R::METHOD {
+            self.req = Some(req);
+            return self;
+        }
+
+        let req_id = req.id.clone();
+        match req.extract::<R::Params>(R::METHOD) {
+            Ok((id, params)) => handler(self.state, id, params),
+            Err(ExtractError::JsonError { error, .. }) => {
+                respond_invalid_params(self.state, req_id, R::METHOD, &error);
+            }
+            Err(ExtractError::MethodMismatch(_)) => unreachable!("method checked above"),
+        }
+        self
+    }
+
     pub fn finish(&mut self) {
         if let Some(req) = self.req.take() {
             tracing::warn!(method = %req.method, "unhandled request");
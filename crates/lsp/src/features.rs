@@ -0,0 +1,361 @@
+//! Feature groups the editor switches on and off.
+//!
+//! `.graphqlanalyzer.toml` decides which features a workspace uses. An
+//! editor can override that for its user with a `features` object in its
+//! `initializationOptions` or `workspace/didChangeConfiguration` settings,
+//! at the top level or under a `graphql-analyzer` section:
+//!
+//! ```json
+//! {"features": {"inlayHints": false, "projectLints": false}}
+//! ```
+//!
+//! Features the editor leaves out keep the file's setting. When the client
+//! can register inlay hints, code lenses or semantic tokens dynamically,
+//! they're left out of the initialize result and registered once the server
+//! is running, then unregistered while switched off so the editor stops
+//! asking for them. Otherwise the capability stays and the disabled feature
+//! answers with nothing.
+
+use std::collections::HashSet;
+
+use lsp_server::{Message, RequestId};
+use lsp_types::request::{RegisterCapability, Request, UnregisterCapability};
+use lsp_types::{
+    ClientCapabilities, Registration, RegistrationParams, ServerCapabilities, Unregistration,
+    UnregistrationParams,
+};
+use serde::Deserialize;
+
+use crate::global_state::GlobalState;
+
+/// The features an editor set, each overriding the workspace's
+/// `[features]` setting of the same name
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EditorFeatures {
+    pub inlay_hints: Option<bool>,
+    pub code_lens: Option<bool>,
+    pub semantic_tokens: Option<bool>,
+    pub project_lints: Option<bool>,
+    pub complexity_warnings: Option<bool>,
+}
+
+impl EditorFeatures {
+    /// Read the `features` object from initialization options or
+    /// configuration settings, if they have one
+    #[must_use]
+    pub fn from_settings(settings: &serde_json::Value) -> Option<Self> {
+        let features = features_section(settings)?;
+        match serde_json::from_value(features.clone()) {
+            Ok(features) => Some(features),
+            Err(e) => {
+                tracing::warn!("Ignoring invalid editor feature settings: {e}");
+                None
+            }
+        }
+    }
+
+    /// Whether `settings` has a `features` object, valid or not
+    #[must_use]
+    pub fn declared_in(settings: &serde_json::Value) -> bool {
+        features_section(settings).is_some()
+    }
+
+    /// Override `flags` with the features the editor set
+    #[cfg_attr(not(feature = "native"), allow(dead_code))]
+    pub fn apply(&self, flags: &mut graphql_config::FeatureFlags) {
+        let overrides = [
+            (self.inlay_hints, &mut flags.inlay_hints),
+            (self.code_lens, &mut flags.code_lens),
+            (self.semantic_tokens, &mut flags.semantic_tokens),
+            (self.project_lints, &mut flags.project_lints),
+            (self.complexity_warnings, &mut flags.complexity_warnings),
+        ];
+        for (setting, flag) in overrides {
+            *flag = setting.unwrap_or(*flag);
+        }
+    }
+}
+
+fn features_section(settings: &serde_json::Value) -> Option<&serde_json::Value> {
+    settings
+        .get("graphql-analyzer")
+        .and_then(|section| section.get("features"))
+        .or_else(|| settings.get("features"))
+}
+
+/// A capability the server can register after initialization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Capability {
+    InlayHint,
+    CodeLens,
+    SemanticTokens,
+}
+
+impl Capability {
+    const ALL: [Self; 3] = [Self::InlayHint, Self::CodeLens, Self::SemanticTokens];
+
+    fn method(self) -> &'static str {
+        match self {
+            Self::InlayHint => "textDocument/inlayHint",
+            Self::CodeLens => "textDocument/codeLens",
+            Self::SemanticTokens => "textDocument/semanticTokens",
+        }
+    }
+
+    fn enabled(self, features: &EditorFeatures) -> bool {
+        let setting = match self {
+            Self::InlayHint => features.inlay_hints,
+            Self::CodeLens => features.code_lens,
+            Self::SemanticTokens => features.semantic_tokens,
+        };
+        setting != Some(false)
+    }
+
+    fn client_registers(self, client: &ClientCapabilities) -> bool {
+        let Some(text_document) = &client.text_document else {
+            return false;
+        };
+        let dynamic_registration = match self {
+            Self::InlayHint => text_document
+                .inlay_hint
+                .as_ref()
+                .and_then(|c| c.dynamic_registration),
+            Self::CodeLens => text_document
+                .code_lens
+                .as_ref()
+                .and_then(|c| c.dynamic_registration),
+            Self::SemanticTokens => text_document
+                .semantic_tokens
+                .as_ref()
+                .and_then(|c| c.dynamic_registration),
+        };
+        dynamic_registration == Some(true)
+    }
+
+    fn remove_from(self, capabilities: &mut ServerCapabilities) {
+        match self {
+            Self::InlayHint => capabilities.inlay_hint_provider = None,
+            Self::CodeLens => capabilities.code_lens_provider = None,
+            Self::SemanticTokens => capabilities.semantic_tokens_provider = None,
+        }
+    }
+
+    /// The options the capability would have been advertised with, for the
+    /// documents the client sends the server
+    fn register_options(self) -> Option<serde_json::Value> {
+        let capabilities = crate::build_server_capabilities();
+        let options = match self {
+            Self::InlayHint => serde_json::to_value(capabilities.inlay_hint_provider?),
+            Self::CodeLens => serde_json::to_value(capabilities.code_lens_provider?),
+            Self::SemanticTokens => serde_json::to_value(capabilities.semantic_tokens_provider?),
+        };
+        let mut options = options.ok()?;
+        if let Some(object) = options.as_object_mut() {
+            object.insert("documentSelector".to_string(), serde_json::Value::Null);
+        }
+        Some(options)
+    }
+}
+
+/// The editor's feature settings and the capabilities registered for them
+#[derive(Debug, Default)]
+pub struct FeatureRegistrations {
+    pub editor: EditorFeatures,
+    /// Capabilities left out of the initialize result for the server to
+    /// register itself
+    dynamic: HashSet<Capability>,
+    registered: HashSet<Capability>,
+    next_id: u32,
+}
+
+impl FeatureRegistrations {
+    /// Take the capabilities the client can register dynamically out of the
+    /// initialize result, for [`sync_registrations`] to register instead
+    #[cfg_attr(not(feature = "native"), allow(dead_code))]
+    pub fn take_dynamic(
+        &mut self,
+        client: &ClientCapabilities,
+        capabilities: &mut ServerCapabilities,
+    ) {
+        for capability in Capability::ALL {
+            if capability.client_registers(client) {
+                capability.remove_from(capabilities);
+                self.dynamic.insert(capability);
+            }
+        }
+    }
+}
+
+/// Register the dynamic capabilities whose feature is on, and unregister
+/// those switched off since
+pub fn sync_registrations(state: &mut GlobalState) {
+    let features = &mut state.features;
+    let mut registrations = Vec::new();
+    let mut unregistrations = Vec::new();
+    for capability in Capability::ALL {
+        if !features.dynamic.contains(&capability) {
+            continue;
+        }
+        let enabled = capability.enabled(&features.editor);
+        let registered = features.registered.contains(&capability);
+        if enabled && !registered {
+            let Some(options) = capability.register_options() else {
+                continue;
+            };
+            registrations.push(Registration {
+                id: capability.method().to_string(),
+                method: capability.method().to_string(),
+                register_options: Some(options),
+            });
+            features.registered.insert(capability);
+        } else if !enabled && registered {
+            unregistrations.push(Unregistration {
+                id: capability.method().to_string(),
+                method: capability.method().to_string(),
+            });
+            features.registered.remove(&capability);
+        }
+    }
+
+    if !registrations.is_empty() {
+        send_request::<RegisterCapability>(state, RegistrationParams { registrations });
+    }
+    if !unregistrations.is_empty() {
+        send_request::<UnregisterCapability>(
+            state,
+            UnregistrationParams {
+                unregisterations: unregistrations,
+            },
+        );
+    }
+}
+
+/// Send a request whose response the server doesn't wait for
+fn send_request<R: Request>(state: &mut GlobalState, params: R::Params) {
+    state.features.next_id += 1;
+    let id = RequestId::from(format!(
+        "graphql-analyzer/features/{}",
+        state.features.next_id
+    ));
+    let request = lsp_server::Request::new(id, R::METHOD.to_owned(), params);
+    state
+        .sender
+        .send(Message::Request(request))
+        .expect("client channel open");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crossbeam_channel::{unbounded, Receiver};
+
+    fn make_state() -> (GlobalState, Receiver<Message>) {
+        let (msg_sender, msg_receiver) = unbounded();
+        let (intro_req_sender, _intro_req_receiver) = unbounded();
+        let (_intro_res_sender, intro_res_receiver) = unbounded();
+        let state = GlobalState::new(
+            msg_sender,
+            Box::new(crate::global_state::InlineDispatcher),
+            intro_req_sender,
+            intro_res_receiver,
+        );
+        (state, msg_receiver)
+    }
+
+    #[test]
+    fn test_editor_features_override_settings() {
+        let editor = EditorFeatures::from_settings(&serde_json::json!({
+            "graphql-analyzer": { "features": { "inlayHints": false, "projectLints": false } }
+        }))
+        .unwrap();
+        let mut flags = graphql_config::FeatureFlags {
+            code_lens: false,
+            ..Default::default()
+        };
+        editor.apply(&mut flags);
+        assert!(!flags.inlay_hints);
+        assert!(!flags.project_lints);
+        assert!(!flags.code_lens);
+        assert!(flags.semantic_tokens);
+
+        assert_eq!(
+            EditorFeatures::from_settings(&serde_json::json!({ "features": { "codeLens": true } })),
+            Some(EditorFeatures {
+                code_lens: Some(true),
+                ..Default::default()
+            })
+        );
+        assert_eq!(
+            EditorFeatures::from_settings(&serde_json::Value::Null),
+            None
+        );
+    }
+
+    #[test]
+    fn test_configuration_without_features_resets_them() {
+        use lsp_types::DidChangeConfigurationParams;
+
+        let (mut state, _messages) = make_state();
+        let change = |settings| DidChangeConfigurationParams { settings };
+        crate::handlers::document_sync::handle_did_change_configuration(
+            &mut state,
+            change(serde_json::json!({ "features": { "codeLens": false } })),
+        );
+        assert_eq!(state.features.editor.code_lens, Some(false));
+
+        // Invalid toggles and clients that send no settings keep the old ones
+        crate::handlers::document_sync::handle_did_change_configuration(
+            &mut state,
+            change(serde_json::json!({ "features": { "codeLens": "off" } })),
+        );
+        crate::handlers::document_sync::handle_did_change_configuration(
+            &mut state,
+            change(serde_json::Value::Null),
+        );
+        assert_eq!(state.features.editor.code_lens, Some(false));
+
+        crate::handlers::document_sync::handle_did_change_configuration(
+            &mut state,
+            change(serde_json::json!({ "graphql-analyzer": {} })),
+        );
+        assert_eq!(state.features.editor, EditorFeatures::default());
+    }
+
+    #[test]
+    fn test_registers_and_unregisters_dynamic_capabilities() {
+        let client: ClientCapabilities = serde_json::from_value(serde_json::json!({
+            "textDocument": {
+                "inlayHint": { "dynamicRegistration": true },
+                "codeLens": { "dynamicRegistration": false }
+            }
+        }))
+        .unwrap();
+        let mut capabilities = crate::build_server_capabilities();
+        let (mut state, messages) = make_state();
+        state.features.take_dynamic(&client, &mut capabilities);
+        assert!(capabilities.inlay_hint_provider.is_none());
+        assert!(capabilities.code_lens_provider.is_some());
+
+        sync_registrations(&mut state);
+        let Ok(Message::Request(request)) = messages.try_recv() else {
+            panic!("expected a registration");
+        };
+        assert_eq!(request.method, "client/registerCapability");
+        let params: RegistrationParams = serde_json::from_value(request.params).unwrap();
+        assert_eq!(params.registrations.len(), 1);
+        assert_eq!(params.registrations[0].method, "textDocument/inlayHint");
+
+        // Unchanged settings don't register again
+        sync_registrations(&mut state);
+        assert!(messages.try_recv().is_err());
+
+        state.features.editor.inlay_hints = Some(false);
+        sync_registrations(&mut state);
+        let Ok(Message::Request(request)) = messages.try_recv() else {
+            panic!("expected an unregistration");
+        };
+        assert_eq!(request.method, "client/unregisterCapability");
+    }
+}
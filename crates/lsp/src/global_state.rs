@@ -1,9 +1,17 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
 
 use crossbeam_channel::Sender;
 use lsp_server::{Message, RequestId};
 use lsp_types::Uri;
 
+use crate::credentials::CredentialGuard;
+use crate::diagnostics_scheduler::DiagnosticsScheduler;
+use crate::features::FeatureRegistrations;
+use crate::indexing::IndexingState;
+use crate::request_stats::{RequestTiming, RequestTimings};
+use crate::semantic_tokens::SemanticTokensCache;
 use crate::workspace::WorkspaceManager;
 
 pub trait TaskDispatcher: Send + Sync {
@@ -53,16 +61,40 @@ pub struct GlobalState {
     pub introspection_request_sender: Sender<IntrospectionRequest>,
     pub introspection_result_receiver: crossbeam_channel::Receiver<IntrospectionResult>,
     pub in_flight: HashSet<RequestId>,
+    /// Tokens for requests running on the worker pool, so `$/cancelRequest`
+    /// can stop the queries behind them rather than just drop the response.
+    pub cancellation_tokens: HashMap<RequestId, graphql_ide::CancellationToken>,
     /// Per-URI generation counter for diagnostics requests. Bumped each time
     /// we spawn a single-URI diagnostics computation; the worker captures the
     /// value and the publish step drops results whose generation no longer
     /// matches (because a newer keystroke has superseded them).
-    pub diagnostics_seq: std::collections::HashMap<String, u64>,
+    pub diagnostics_seq: HashMap<String, u64>,
+    pub diagnostics_scheduler: DiagnosticsScheduler,
+    /// Last semantic tokens sent per document, for delta requests
+    pub semantic_tokens: SemanticTokensCache,
+    /// Projects whose documents are still being read in the background
+    pub indexing: IndexingState,
+    /// How long recent requests took, for `graphql-analyzer/perfStats`
+    pub request_timings: RequestTimings,
+    /// The embedding application's metrics sink, sent each request timing
+    pub metrics_sink: Option<Box<dyn graphql_ide::MetricsSink>>,
+    /// Remote schemas requested from the introspection thread whose result
+    /// hasn't arrived yet, including those waiting for the user to approve
+    /// sending their credentials
+    pub pending_introspections: usize,
+    pub credentials: CredentialGuard,
+    /// Features the editor switched on or off, and the capabilities
+    /// registered for them
+    pub features: FeatureRegistrations,
 }
 
 /// A completed background task ready for the main thread to process.
 pub struct Task {
     pub response: TaskResponse,
+    /// How long the request handler behind `response` ran, recorded by the
+    /// main loop for `perfStats`. `None` for background work and for
+    /// cancelled or panicking handlers.
+    pub timing: Option<RequestTiming>,
 }
 
 pub enum TaskResponse {
@@ -78,6 +110,15 @@ pub enum TaskResponse {
     /// no generation check, so a save+rapid-typing race may briefly publish
     /// stale diagnostics, which the next keystroke corrects.
     PublishDiagnosticsBatch(Vec<(Uri, Vec<lsp_types::Diagnostic>)>),
+    /// Semantic tokens encoded on the pool, to be stored under a new result
+    /// id before they're sent.
+    SemanticTokens {
+        id: RequestId,
+        encoded: crate::semantic_tokens::EncodedTokens,
+    },
+    /// A project's documents, read from disk and ready to register.
+    #[cfg(feature = "native")]
+    DocumentsIndexed(crate::indexing::IndexedDocuments),
 }
 
 /// Request to fetch a remote schema via introspection (sent to async thread)
@@ -99,6 +140,8 @@ pub struct IntrospectionResult {
 pub struct GlobalStateSnapshot {
     pub analysis: graphql_ide::Analysis,
     pub file_path: graphql_ide::FilePath,
+    /// Whether the client applies workspace edits that delete files
+    pub client_deletes_files: bool,
 }
 
 impl GlobalState {
@@ -122,8 +165,44 @@ impl GlobalState {
             introspection_request_sender,
             introspection_result_receiver,
             in_flight: HashSet::new(),
-            diagnostics_seq: std::collections::HashMap::new(),
+            cancellation_tokens: HashMap::new(),
+            diagnostics_seq: HashMap::new(),
+            diagnostics_scheduler: DiagnosticsScheduler::default(),
+            semantic_tokens: SemanticTokensCache::default(),
+            indexing: IndexingState::default(),
+            request_timings: RequestTimings::default(),
+            metrics_sink: None,
+            pending_introspections: 0,
+            credentials: CredentialGuard::default(),
+            features: FeatureRegistrations::default(),
+        }
+    }
+
+    /// Register the embedding application's metrics sink, which receives
+    /// query, project rebuild and request events from then on
+    pub fn set_metrics_sink(&mut self, sink: Option<Arc<dyn graphql_ide::MetricsSink>>) {
+        self.metrics_sink = sink
+            .clone()
+            .map(|sink| Box::new(sink) as Box<dyn graphql_ide::MetricsSink>);
+        self.workspace.set_metrics_sink(sink);
+    }
+
+    /// Record a finished request for `perfStats` and the metrics sink
+    pub fn record_request(&mut self, timing: RequestTiming) {
+        if let Some(sink) = &self.metrics_sink {
+            sink.record(&graphql_ide::MetricsEvent::Request {
+                method: timing.method,
+                file: timing.file.as_deref(),
+                duration: timing.duration,
+            });
         }
+        self.request_timings.record(timing);
+    }
+
+    /// Register the embedding application's source of `${secret:NAME}`
+    /// values for introspection headers, replacing the environment
+    pub fn set_secrets_provider(&mut self, provider: Arc<dyn graphql_config::SecretsProvider>) {
+        self.credentials.secrets = provider;
     }
 
     pub fn send_notification<N: lsp_types::notification::Notification>(&self, params: N::Params) {
@@ -138,17 +217,26 @@ impl GlobalState {
 
     pub fn respond(&mut self, response: lsp_server::Response) {
         self.in_flight.remove(&response.id);
+        self.cancellation_tokens.remove(&response.id);
         self.sender
             .send(Message::Response(response))
             .expect("client channel open");
     }
 
+    /// Send diagnostics for `uri`, unless they match what was last sent
     pub fn publish_diagnostics(
-        &self,
+        &mut self,
         uri: Uri,
         diagnostics: Vec<lsp_types::Diagnostic>,
         version: Option<i32>,
     ) {
+        if !self
+            .diagnostics_scheduler
+            .record_publish(&uri, &diagnostics)
+        {
+            tracing::trace!(uri = %uri.as_str(), "diagnostics unchanged, skipping publish");
+            return;
+        }
         self.send_notification::<lsp_types::notification::PublishDiagnostics>(
             lsp_types::PublishDiagnosticsParams {
                 uri,
@@ -167,39 +255,115 @@ impl GlobalState {
         Some(GlobalStateSnapshot {
             analysis,
             file_path,
+            client_deletes_files: self.client_deletes_files(),
         })
     }
 
+    /// Whether the client accepts `DeleteFile` operations in workspace edits
+    fn client_deletes_files(&self) -> bool {
+        self.client_capabilities
+            .as_ref()
+            .and_then(|caps| caps.workspace.as_ref())
+            .and_then(|workspace| workspace.workspace_edit.as_ref())
+            .is_some_and(|edit| {
+                edit.document_changes == Some(true)
+                    && edit
+                        .resource_operations
+                        .as_ref()
+                        .is_some_and(|ops| ops.contains(&lsp_types::ResourceOperationKind::Delete))
+            })
+    }
+
     /// Dispatch a read-only query to the thread pool. The handler returns the
     /// LSP `R::Result` directly (typically `Option<T>`), which is serialized
     /// straight into the JSON-RPC response — `None` becomes `null`.
-    pub fn spawn_with_snapshot<F, R>(&mut self, id: lsp_server::RequestId, uri: &Uri, f: F)
-    where
+    ///
+    /// The handler is timed on the worker under `method` and the file it
+    /// targets; the main loop records the timing when the task arrives.
+    pub fn spawn_with_snapshot<F, R>(
+        &mut self,
+        id: lsp_server::RequestId,
+        method: &'static str,
+        uri: &Uri,
+        f: F,
+    ) where
         F: FnOnce(GlobalStateSnapshot) -> R + Send + 'static,
         R: serde::Serialize + 'static,
+    {
+        self.spawn_task_with_snapshot(id, method, uri, f, |id, value| {
+            TaskResponse::Response(lsp_server::Response::new_ok(id, value))
+        });
+    }
+
+    /// Like `spawn_with_snapshot`, but `finish` turns the handler's result
+    /// into the task sent back, for responses the main loop completes from
+    /// state it owns. Cancelled and panicking handlers still get an error
+    /// response.
+    pub fn spawn_task_with_snapshot<F, T, W>(
+        &mut self,
+        id: lsp_server::RequestId,
+        method: &'static str,
+        uri: &Uri,
+        f: F,
+        finish: W,
+    ) where
+        F: FnOnce(GlobalStateSnapshot) -> T + Send + 'static,
+        W: FnOnce(lsp_server::RequestId, T) -> TaskResponse + Send + 'static,
     {
         let Some(snap) = self.snapshot_for_uri(uri) else {
             self.respond(lsp_server::Response::new_ok(id, serde_json::Value::Null));
             return;
         };
 
+        let token = graphql_ide::CancellationToken::new();
+        self.cancellation_tokens.insert(id.clone(), token.clone());
+
         let task_sender = self.task_sender.clone();
         self.dispatcher.execute(Box::new(move || {
-            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(snap)));
+            let file = snap.file_path.0.clone();
+            let started = Instant::now();
+            let result =
+                std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| token.run(|| f(snap))));
+            let timing = matches!(result, Ok(Ok(_)))
+                .then(|| RequestTiming::new(method, Some(file), started.elapsed()));
             let response = match result {
-                Ok(value) => lsp_server::Response::new_ok(id, value),
-                Err(_) => lsp_server::Response::new_err(
+                Ok(Ok(value)) => finish(id, value),
+                // Either the client cancelled (and already got its response)
+                // or an edit invalidated the snapshot mid-query
+                Ok(Err(graphql_ide::Cancelled)) => {
+                    TaskResponse::Response(lsp_server::Response::new_err(
+                        id,
+                        lsp_server::ErrorCode::ContentModified as i32,
+                        "content modified".to_owned(),
+                    ))
+                }
+                Err(_) => TaskResponse::Response(lsp_server::Response::new_err(
                     id,
                     lsp_server::ErrorCode::InternalError as i32,
                     "internal error: handler panicked".to_owned(),
-                ),
+                )),
             };
-            let _ = task_sender.send(Task {
-                response: TaskResponse::Response(response),
-            });
+            let _ = task_sender.send(Task { response, timing });
         }));
     }
 
+    /// Spawn diagnostics for every file whose debounce period has ended.
+    #[cfg(feature = "native")]
+    pub fn flush_scheduled_diagnostics(&mut self, now: std::time::Instant) {
+        for uri in self.diagnostics_scheduler.take_due(now) {
+            let Some(snap) = self.snapshot_for_uri(&uri) else {
+                continue;
+            };
+            self.spawn_diagnostics_for_uri(uri, move || {
+                snap.analysis
+                    .diagnostics(&snap.file_path)
+                    .into_iter()
+                    .map(crate::conversions::convert_ide_diagnostic)
+                    .collect()
+            });
+        }
+    }
+
     /// Spawn a diagnostics computation for a single URI, tagged with the next
     /// generation for that URI. If a later spawn for the same URI bumps the
     /// generation before this one returns, the publish step drops the stale
@@ -225,6 +389,7 @@ impl GlobalState {
                         diagnostics,
                         seq: captured_seq,
                     },
+                    timing: None,
                 });
             }
         }));
@@ -242,6 +407,7 @@ impl GlobalState {
             if let Ok(diagnostics) = result {
                 let _ = task_sender.send(Task {
                     response: TaskResponse::PublishDiagnosticsBatch(diagnostics),
+                    timing: None,
                 });
             }
         }));
@@ -285,6 +451,42 @@ mod tests {
         // Independent counters: bumping b doesn't move a.
         assert_eq!(state.diagnostics_seq.get(a.as_str()).copied(), Some(2));
     }
+
+    #[test]
+    fn recorded_requests_reach_the_metrics_sink() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Default)]
+        struct CountingSink(AtomicUsize);
+
+        impl graphql_ide::MetricsSink for CountingSink {
+            fn record(&self, event: &graphql_ide::MetricsEvent<'_>) {
+                if let graphql_ide::MetricsEvent::Request { method, .. } = event {
+                    assert_eq!(*method, "textDocument/hover");
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        let mut state = make_state();
+        let sink = Arc::new(CountingSink::default());
+        state.set_metrics_sink(Some(sink.clone()));
+        state.record_request(RequestTiming::new(
+            "textDocument/hover",
+            None,
+            std::time::Duration::from_millis(3),
+        ));
+        assert_eq!(sink.0.load(Ordering::SeqCst), 1);
+        assert_eq!(state.request_timings.stats().requests, 1);
+
+        state.set_metrics_sink(None);
+        state.record_request(RequestTiming::new(
+            "textDocument/hover",
+            None,
+            std::time::Duration::from_millis(3),
+        ));
+        assert_eq!(sink.0.load(Ordering::SeqCst), 1);
+    }
 }
 
 #[cfg(all(test, feature = "native"))]
@@ -1,28 +1,306 @@
 #![allow(clippy::needless_pass_by_value)]
 
-use crate::global_state::GlobalState;
-use crate::server::{PingResponse, VirtualFileContentParams};
+use crate::conversions::{convert_ide_location, convert_ide_position, convert_lsp_position};
+use crate::global_state::{GlobalState, GlobalStateSnapshot};
+use crate::server::{
+    ClearCacheResponse, ComplexitySummary, ContextAtPositionResponse, ContextBlock,
+    CoverageSummary, DiagnosticSummary, FieldComplexity, FieldCoverageResponse, FieldUsage,
+    FragmentNode, FragmentSummary, GenerateDocsParams, ImpactOfChangeParams,
+    ImpactOfChangeResponse, ImpactedOperation, MemoryMetricsResponse, OperationComplexity,
+    PingResponse, ProjectReportParams, ProjectReportResponse, SchemaStatsResponse, TypeCoverage,
+    VirtualFileContentParams,
+};
 
 pub(crate) fn handle_virtual_file_content(
     state: &mut GlobalState,
     params: VirtualFileContentParams,
 ) -> Option<String> {
-    tracing::debug!("Virtual file content requested: {}", params.uri);
+    virtual_file_content(state, &params.uri)
+}
+
+pub(crate) fn handle_text_document_content(
+    state: &mut GlobalState,
+    params: TextDocumentContentParams,
+) -> Option<TextDocumentContentResult> {
+    virtual_file_content(state, params.uri.as_str()).map(|text| TextDocumentContentResult { text })
+}
 
-    let file_path = graphql_ide::FilePath::new(&params.uri);
+/// The content registered for a virtual file such as an introspected
+/// schema's `schema://` URI, searched across every project.
+fn virtual_file_content(state: &GlobalState, uri: &str) -> Option<String> {
+    tracing::debug!("Virtual file content requested: {}", uri);
+
+    let file_path = graphql_ide::FilePath::new(uri);
 
     for (_, host) in state.workspace.all_hosts() {
         let analysis = host.snapshot();
         if let Some(content) = analysis.file_content(&file_path) {
             tracing::debug!("Found virtual file content ({} bytes)", content.len());
-            return Some(content);
+            return Some(content.to_string());
         }
     }
 
-    tracing::debug!("Virtual file not found: {}", params.uri);
+    tracing::debug!("Virtual file not found: {}", uri);
     None
 }
 
+pub(crate) fn handle_context_at_position(
+    snap: GlobalStateSnapshot,
+    params: lsp_types::TextDocumentPositionParams,
+) -> Option<ContextAtPositionResponse> {
+    let position = convert_lsp_position(params.position);
+    let context = snap.analysis.cursor_context(&snap.file_path, position)?;
+    Some(ContextAtPositionResponse {
+        language: format!("{:?}", context.language),
+        document_kind: format!("{:?}", context.document_kind),
+        has_project: context.has_project,
+        block_count: context.block_count,
+        extraction_skipped: context.extraction_skipped,
+        block: context.block.map(|block| ContextBlock {
+            index: block.index,
+            file_offset: block.file_offset,
+            start: convert_ide_position(block.start),
+            length: block.length,
+            position: convert_ide_position(block.position),
+            offset: block.offset,
+        }),
+        enclosing_definition: context.enclosing_definition,
+        symbol: context.symbol,
+        parent_type: context.parent_type,
+        expected_input_type: context.expected_input_type,
+        completion_path: context.completion_path.to_string(),
+    })
+}
+
+pub(crate) fn handle_field_coverage(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> Option<FieldCoverageResponse> {
+    let report = snap.analysis.field_coverage()?;
+    let mut fields: Vec<FieldUsage> = report
+        .field_usages
+        .iter()
+        .map(|((type_name, field_name), usage)| FieldUsage {
+            type_name: type_name.clone(),
+            field_name: field_name.clone(),
+            usage_count: usage.usage_count,
+            operations: usage.operations.clone(),
+        })
+        .collect();
+    fields.sort_by(|a, b| (&a.type_name, &a.field_name).cmp(&(&b.type_name, &b.field_name)));
+
+    Some(FieldCoverageResponse {
+        total_fields: report.total_fields,
+        used_fields: report.used_fields,
+        coverage_percentage: report.coverage_percentage(),
+        types: report
+            .types
+            .iter()
+            .map(|t| TypeCoverage {
+                type_name: t.type_name.clone(),
+                total_fields: t.total_fields,
+                used_fields: t.used_fields,
+                coverage_percentage: t.coverage_percentage(),
+            })
+            .collect(),
+        fields,
+    })
+}
+
+pub(crate) fn handle_generate_docs(
+    snap: GlobalStateSnapshot,
+    params: GenerateDocsParams,
+) -> Option<String> {
+    let mut options = graphql_ide::DocsOptions {
+        include_deprecated: params.include_deprecated,
+        ..graphql_ide::DocsOptions::default()
+    };
+    match params.format.as_deref() {
+        None | Some("markdown") => {}
+        Some("html") => options.format = graphql_ide::DocsFormat::Html,
+        Some(format) => tracing::warn!("Unknown docs format {format}, using markdown"),
+    }
+    if let Some(title) = params.title {
+        options.title = title;
+    }
+    snap.analysis.generate_docs(&options)
+}
+
+pub(crate) fn handle_complexity(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> Vec<OperationComplexity> {
+    snap.analysis
+        .complexity_analysis()
+        .into_iter()
+        .map(|analysis| OperationComplexity {
+            location: convert_ide_location(&graphql_ide::Location::new(
+                analysis.file,
+                analysis.range,
+            )),
+            operation_name: analysis.operation_name,
+            operation_type: analysis.operation_type,
+            total_complexity: analysis.total_complexity,
+            depth: analysis.depth,
+            breakdown: analysis
+                .breakdown
+                .into_iter()
+                .map(|field| FieldComplexity {
+                    path: field.path,
+                    name: field.name,
+                    complexity: field.complexity,
+                    multiplier: field.multiplier,
+                    depth: field.depth,
+                    is_connection: field.is_connection,
+                    warning: field.warning,
+                })
+                .collect(),
+            warnings: analysis.warnings,
+        })
+        .collect()
+}
+
+pub(crate) fn handle_schema_stats(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> SchemaStatsResponse {
+    schema_stats_response(&snap.analysis.schema_stats())
+}
+
+fn schema_stats_response(stats: &graphql_ide::SchemaStats) -> SchemaStatsResponse {
+    SchemaStatsResponse {
+        objects: stats.objects,
+        interfaces: stats.interfaces,
+        unions: stats.unions,
+        enums: stats.enums,
+        scalars: stats.scalars,
+        input_objects: stats.input_objects,
+        total_types: stats.total_types(),
+        total_fields: stats.total_fields,
+        directives: stats.directives,
+    }
+}
+
+pub(crate) fn handle_memory_metrics(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> MemoryMetricsResponse {
+    let metrics = snap.analysis.memory_metrics();
+    MemoryMetricsResponse {
+        files: metrics.files,
+        source_bytes: metrics.source_bytes,
+        documents: metrics.documents,
+        lazy_parsing: metrics.parse_cache_capacity.is_some(),
+        parse_cache_capacity: metrics.parse_cache_capacity,
+        budget_bytes: metrics.budget_bytes,
+        estimated_parse_bytes: metrics.estimated_parse_bytes,
+    }
+}
+
+pub(crate) fn handle_fragment_graph(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> Vec<FragmentNode> {
+    snap.analysis
+        .fragment_usages()
+        .into_iter()
+        .map(|fragment| FragmentNode {
+            definition: convert_ide_location(&graphql_ide::Location::new(
+                fragment.definition_file,
+                fragment.definition_range,
+            )),
+            usages: fragment
+                .usages
+                .iter()
+                .map(|usage| convert_ide_location(&usage.location))
+                .collect(),
+            name: fragment.name,
+            transitive_dependencies: fragment.transitive_dependencies,
+        })
+        .collect()
+}
+
+pub(crate) fn handle_impact_of_change(
+    snap: GlobalStateSnapshot,
+    params: ImpactOfChangeParams,
+) -> Option<ImpactOfChangeResponse> {
+    let impact = snap
+        .analysis
+        .impact_of_change(&params.type_name, &params.field_name)?;
+    Some(ImpactOfChangeResponse {
+        operations: impact
+            .operations
+            .iter()
+            .map(|operation| ImpactedOperation {
+                name: operation.name.clone(),
+                location: convert_ide_location(&operation.location),
+            })
+            .collect(),
+        type_name: impact.type_name,
+        field_name: impact.field_name,
+    })
+}
+
+pub(crate) fn handle_project_report(
+    snap: GlobalStateSnapshot,
+    _params: ProjectReportParams,
+) -> ProjectReportResponse {
+    let analysis = &snap.analysis;
+
+    let field_coverage = analysis.field_coverage().map(|report| CoverageSummary {
+        total_fields: report.total_fields,
+        used_fields: report.used_fields,
+        coverage_percentage: report.coverage_percentage(),
+    });
+
+    let fragment_usages = analysis.fragment_usages();
+    let mut unused: Vec<String> = fragment_usages
+        .iter()
+        .filter(|fragment| fragment.is_unused())
+        .map(|fragment| fragment.name.clone())
+        .collect();
+    unused.sort();
+
+    let operations = analysis.complexity_analysis();
+    let most_complex = operations.iter().max_by_key(|op| op.total_complexity);
+    let complexity = ComplexitySummary {
+        operations: operations.len(),
+        max_complexity: most_complex.map_or(0, |op| op.total_complexity),
+        max_depth: operations.iter().map(|op| op.depth).max().unwrap_or(0),
+        most_complex_operation: most_complex.map(|op| op.operation_name.clone()),
+        warnings: operations.iter().map(|op| op.warnings.len()).sum(),
+    };
+
+    let mut diagnostics = DiagnosticSummary {
+        errors: 0,
+        warnings: 0,
+        lint_by_rule: std::collections::BTreeMap::new(),
+    };
+    for diagnostic in analysis.all_diagnostics().values().flatten() {
+        match diagnostic.severity {
+            graphql_ide::DiagnosticSeverity::Error => diagnostics.errors += 1,
+            graphql_ide::DiagnosticSeverity::Warning => diagnostics.warnings += 1,
+            _ => {}
+        }
+        if diagnostic.source == "graphql-linter" {
+            if let Some(code) = &diagnostic.code {
+                *diagnostics.lint_by_rule.entry(code.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    ProjectReportResponse {
+        schema: schema_stats_response(&analysis.schema_stats()),
+        field_coverage,
+        fragments: FragmentSummary {
+            total: fragment_usages.len(),
+            unused,
+        },
+        complexity,
+        diagnostics,
+    }
+}
+
 pub(crate) fn handle_ping(_state: &mut GlobalState, _params: serde_json::Value) -> PingResponse {
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -30,6 +308,30 @@ pub(crate) fn handle_ping(_state: &mut GlobalState, _params: serde_json::Value)
     PingResponse { timestamp }
 }
 
+pub(crate) fn handle_clear_cache(
+    state: &mut GlobalState,
+    _params: serde_json::Value,
+) -> ClearCacheResponse {
+    let mut projects = 0;
+    for ((workspace_uri, project_name), host) in state.workspace.all_hosts() {
+        match host.clear_structure_cache() {
+            Ok(()) => projects += 1,
+            Err(e) => tracing::warn!(
+                "Failed to clear file structure cache for '{project_name}' in {workspace_uri}: {e}"
+            ),
+        }
+    }
+    tracing::info!("Cleared file structure cache for {projects} project(s)");
+    ClearCacheResponse { projects }
+}
+
+pub(crate) fn handle_perf_stats(
+    state: &mut GlobalState,
+    _params: serde_json::Value,
+) -> crate::request_stats::PerfStatsResult {
+    state.request_timings.stats()
+}
+
 pub(crate) fn handle_trace_capture(
     state: &mut GlobalState,
     params: crate::trace_capture::TraceCaptureParams,
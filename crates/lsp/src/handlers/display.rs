@@ -2,15 +2,21 @@
 #![allow(clippy::needless_pass_by_value)]
 
 use crate::conversions::{
-    convert_ide_code_lens, convert_ide_code_lens_info, convert_ide_folding_range,
-    convert_ide_hover, convert_ide_inlay_hint, convert_ide_location, convert_ide_selection_range,
-    convert_lsp_position,
+    convert_ide_code_lens, convert_ide_code_lens_info, convert_ide_document_link,
+    convert_ide_field_impact_lens, convert_ide_folding_range, convert_ide_hover,
+    convert_ide_inlay_hint, convert_ide_location, convert_ide_operation_copy_lens,
+    convert_ide_selection_range, convert_ide_type_usage_lens, convert_lsp_position,
+    convert_lsp_range,
 };
-use crate::global_state::GlobalStateSnapshot;
+use crate::global_state::{GlobalState, GlobalStateSnapshot, TaskResponse};
+use crate::semantic_tokens;
+use lsp_server::RequestId;
+use lsp_types::request::{Request as _, SemanticTokensFullDeltaRequest, SemanticTokensFullRequest};
 use lsp_types::{
-    CodeLens, CodeLensParams, FoldingRange, FoldingRangeParams, Hover, HoverParams,
-    InlayHint as LspInlayHint, InlayHintParams, SelectionRange, SelectionRangeParams,
-    SemanticToken, SemanticTokens, SemanticTokensParams, SemanticTokensResult, Uri,
+    CodeLens, CodeLensParams, DocumentLink, DocumentLinkParams, FoldingRange, FoldingRangeParams,
+    Hover, HoverParams, InlayHint as LspInlayHint, InlayHintParams, SelectionRange,
+    SelectionRangeParams, SemanticTokens, SemanticTokensDeltaParams, SemanticTokensParams,
+    SemanticTokensRangeParams, SemanticTokensRangeResult, Uri,
 };
 use std::str::FromStr;
 
@@ -22,42 +28,77 @@ pub(crate) fn handle_hover(snap: GlobalStateSnapshot, params: HoverParams) -> Op
 }
 
 pub(crate) fn handle_semantic_tokens_full(
-    snap: GlobalStateSnapshot,
+    state: &mut GlobalState,
+    id: RequestId,
     params: SemanticTokensParams,
-) -> Option<SemanticTokensResult> {
-    let _ = params;
-    let tokens = snap.analysis.semantic_tokens(&snap.file_path);
-    if tokens.is_empty() {
-        return None;
-    }
-
-    let mut encoded_tokens = Vec::with_capacity(tokens.len() * 5);
-    let mut prev_line = 0u32;
-    let mut prev_start = 0u32;
+) {
+    spawn_semantic_tokens(
+        state,
+        id,
+        SemanticTokensFullRequest::METHOD,
+        &params.text_document.uri,
+        None,
+    );
+}
 
-    for token in tokens {
-        let delta_line = token.start.line - prev_line;
-        let delta_start = if delta_line == 0 {
-            token.start.character - prev_start
-        } else {
-            token.start.character
-        };
+pub(crate) fn handle_semantic_tokens_full_delta(
+    state: &mut GlobalState,
+    id: RequestId,
+    params: SemanticTokensDeltaParams,
+) {
+    let uri = params.text_document.uri;
+    // Unknown to the cache (evicted, or from before a restart) means
+    // there's nothing to diff against, and the client gets full tokens
+    let previous = state
+        .semantic_tokens
+        .previous(uri.as_str(), &params.previous_result_id);
+    spawn_semantic_tokens(
+        state,
+        id,
+        SemanticTokensFullDeltaRequest::METHOD,
+        &uri,
+        previous,
+    );
+}
 
-        encoded_tokens.push(SemanticToken {
-            delta_line,
-            delta_start,
-            length: token.length,
-            token_type: token.token_type.index(),
-            token_modifiers_bitset: token.modifiers.raw(),
-        });
+/// Encode the document's tokens on the pool; `handle_task` assigns their
+/// result id and diffs them against `previous`
+fn spawn_semantic_tokens(
+    state: &mut GlobalState,
+    id: RequestId,
+    method: &'static str,
+    uri: &Uri,
+    previous: Option<Vec<lsp_types::SemanticToken>>,
+) {
+    state.spawn_task_with_snapshot(
+        id,
+        method,
+        uri,
+        move |snap| semantic_tokens::EncodedTokens {
+            uri: snap.file_path.0.clone(),
+            tokens: semantic_tokens::encode(&snap.analysis.semantic_tokens(&snap.file_path)),
+            previous,
+        },
+        |id, encoded| TaskResponse::SemanticTokens { id, encoded },
+    );
+}
 
-        prev_line = token.start.line;
-        prev_start = token.start.character;
+pub(crate) fn handle_semantic_tokens_range(
+    snap: GlobalStateSnapshot,
+    params: SemanticTokensRangeParams,
+) -> Option<SemanticTokensRangeResult> {
+    let range = convert_lsp_range(params.range);
+    let tokens = snap
+        .analysis
+        .semantic_tokens_in_range(&snap.file_path, range);
+    if tokens.is_empty() {
+        return None;
     }
 
-    Some(SemanticTokensResult::Tokens(SemanticTokens {
+    // Range results aren't diffed against, so they don't get a result id
+    Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
         result_id: None,
-        data: encoded_tokens,
+        data: semantic_tokens::encode(&tokens),
     }))
 }
 
@@ -109,6 +150,27 @@ pub(crate) fn handle_code_lens(
             .map(|cl| convert_ide_code_lens_info(cl, &uri)),
     );
 
+    let type_lenses = snap.analysis.type_usage_code_lenses(&snap.file_path);
+    lsp_code_lenses.extend(
+        type_lenses
+            .iter()
+            .map(|lens| convert_ide_type_usage_lens(lens, &uri)),
+    );
+
+    let field_lenses = snap.analysis.field_impact_code_lenses(&snap.file_path);
+    lsp_code_lenses.extend(
+        field_lenses
+            .iter()
+            .map(|lens| convert_ide_field_impact_lens(lens, &uri)),
+    );
+
+    let copy_lenses = snap.analysis.operation_copy_code_lenses(&snap.file_path);
+    lsp_code_lenses.extend(
+        copy_lenses
+            .iter()
+            .map(|lens| convert_ide_operation_copy_lens(lens, &uri)),
+    );
+
     let fragment_lenses = snap.analysis.code_lenses(&snap.file_path);
     for lens in &fragment_lenses {
         let fragment_name = lens
@@ -150,6 +212,18 @@ pub(crate) fn handle_folding_range(
     Some(lsp_ranges)
 }
 
+pub(crate) fn handle_document_link(
+    snap: GlobalStateSnapshot,
+    params: DocumentLinkParams,
+) -> Option<Vec<DocumentLink>> {
+    let _ = params;
+    let links = snap.analysis.document_links(&snap.file_path);
+    if links.is_empty() {
+        return None;
+    }
+    Some(links.iter().filter_map(convert_ide_document_link).collect())
+}
+
 pub(crate) fn handle_inlay_hint(
     snap: GlobalStateSnapshot,
     params: InlayHintParams,
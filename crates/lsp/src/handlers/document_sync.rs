@@ -1,6 +1,7 @@
 #![allow(clippy::needless_pass_by_value)]
 
 use crate::conversions::convert_ide_diagnostic;
+use crate::features::{self, EditorFeatures};
 use crate::global_state::GlobalState;
 #[cfg(feature = "native")]
 use crate::loading;
@@ -8,8 +9,9 @@ use graphql_ide::{DocumentKind, Language};
 #[cfg(feature = "native")]
 use lsp_types::FileChangeType;
 use lsp_types::{
-    DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidCloseTextDocumentParams,
-    DidOpenTextDocumentParams, DidSaveTextDocumentParams, Uri,
+    DeleteFilesParams, DidChangeConfigurationParams, DidChangeTextDocumentParams,
+    DidChangeWatchedFilesParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, RenameFilesParams, Uri, WorkspaceEdit,
 };
 use std::path::Path;
 use std::str::FromStr;
@@ -20,6 +22,7 @@ pub(crate) fn handle_did_open(state: &mut GlobalState, params: DidOpenTextDocume
     let version = params.text_document.version;
 
     tracing::info!("File opened: {}", uri.path());
+    state.diagnostics_scheduler.set_focused(&uri);
 
     let uri_string = uri.to_string();
     state
@@ -131,17 +134,29 @@ pub(crate) fn handle_did_change(state: &mut GlobalState, params: DidChangeTextDo
     let host = state
         .workspace
         .get_or_create_host(&workspace_uri, &project_name);
-    let (_is_new, snapshot) =
-        host.update_file_and_snapshot(&file_path, &current_content, language, document_kind);
 
-    let file_path_clone = graphql_ide::FilePath::new(uri.as_str());
-    state.spawn_diagnostics_for_uri(uri, move || {
-        snapshot
-            .diagnostics(&file_path_clone)
-            .into_iter()
-            .map(convert_ide_diagnostic)
-            .collect()
-    });
+    // The native main loop wakes up when the debounce period ends; the wasm
+    // server only runs when a message arrives, so it computes right away.
+    #[cfg(feature = "native")]
+    {
+        host.add_file(&file_path, &current_content, language, document_kind);
+        state
+            .diagnostics_scheduler
+            .schedule(uri, std::time::Instant::now());
+    }
+    #[cfg(not(feature = "native"))]
+    {
+        let (_is_new, snapshot) =
+            host.update_file_and_snapshot(&file_path, &current_content, language, document_kind);
+        let file_path_clone = graphql_ide::FilePath::new(uri.as_str());
+        state.spawn_diagnostics_for_uri(uri, move || {
+            snapshot
+                .diagnostics(&file_path_clone)
+                .into_iter()
+                .map(convert_ide_diagnostic)
+                .collect()
+        });
+    }
 }
 
 pub(crate) fn handle_did_save(state: &mut GlobalState, params: DidSaveTextDocumentParams) {
@@ -189,6 +204,186 @@ pub(crate) fn handle_did_close(state: &mut GlobalState, params: DidCloseTextDocu
     let uri_string = params.text_document.uri.to_string();
     state.workspace.document_versions.remove(&uri_string);
     state.workspace.document_contents.remove(&uri_string);
+    state.semantic_tokens.remove(&uri_string);
+    state
+        .diagnostics_scheduler
+        .forget(&params.text_document.uri);
+}
+
+/// Files or folders were deleted. Drop them from their project's host,
+/// clear their diagnostics and recheck what's left of the project, since
+/// other files may have used fragments the deleted ones defined.
+pub(crate) fn handle_did_delete_files(state: &mut GlobalState, params: DeleteFilesParams) {
+    // Folder deletes are expanded to the loaded files under them
+    let mut per_host: Vec<((String, String), Vec<graphql_ide::FilePath>)> = Vec::new();
+    for (key, host) in state.workspace.all_hosts() {
+        let deleted: Vec<_> = host
+            .files()
+            .into_iter()
+            .filter(|file| {
+                params.files.iter().any(|delete| {
+                    file.as_str() == delete.uri
+                        || file
+                            .as_str()
+                            .strip_prefix(delete.uri.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                })
+            })
+            .collect();
+        if !deleted.is_empty() {
+            per_host.push((key.clone(), deleted));
+        }
+    }
+
+    for ((workspace_uri, project_name), deleted) in per_host {
+        let Some(host) = state.workspace.get_host_mut(&workspace_uri, &project_name) else {
+            continue;
+        };
+        for file in &deleted {
+            host.remove_file(file);
+        }
+        let snapshot = host.snapshot();
+
+        for file in &deleted {
+            tracing::info!("File deleted: {}", file.as_str());
+            let workspace = &mut state.workspace;
+            workspace.document_versions.remove(file.as_str());
+            workspace.document_contents.remove(file.as_str());
+            workspace.file_to_project.remove(file.as_str());
+            state.semantic_tokens.remove(file.as_str());
+            if let Ok(uri) = Uri::from_str(file.as_str()) {
+                state.publish_diagnostics(uri.clone(), Vec::new(), None);
+                state.diagnostics_scheduler.forget(&uri);
+            }
+        }
+
+        state.spawn_diagnostics_batch(move || {
+            snapshot
+                .all_diagnostics()
+                .into_iter()
+                .filter_map(|(file_path, diags)| {
+                    let file_uri = Uri::from_str(file_path.as_str()).ok()?;
+                    let lsp_diagnostics = diags.into_iter().map(convert_ide_diagnostic).collect();
+                    Some((file_uri, lsp_diagnostics))
+                })
+                .collect()
+        });
+    }
+}
+
+/// Files or folders are about to be renamed. Move them in their project's
+/// host now and answer with the edits that keep `# import` paths resolving.
+///
+/// The host keeps each file's content and cached analysis under its new
+/// path in one rebuild. Waiting for the watcher's delete and create events
+/// instead leaves a window where both paths are loaded, and every fragment
+/// in the file is reported as a duplicate. A file moved out of its project
+/// is dropped from the host; the project it lands in picks it up when it's
+/// opened or indexed.
+pub(crate) fn handle_will_rename_files(
+    state: &mut GlobalState,
+    params: RenameFilesParams,
+) -> Option<WorkspaceEdit> {
+    // Folder renames are expanded to the loaded files under them
+    let mut per_host: Vec<(
+        (String, String),
+        Vec<(graphql_ide::FilePath, graphql_ide::FilePath)>,
+    )> = Vec::new();
+    for (key, host) in state.workspace.all_hosts() {
+        let mut renames = Vec::new();
+        for file in host.files() {
+            for rename in &params.files {
+                let new_uri = if file.as_str() == rename.old_uri {
+                    rename.new_uri.clone()
+                } else if let Some(rest) = file
+                    .as_str()
+                    .strip_prefix(rename.old_uri.as_str())
+                    .filter(|rest| rest.starts_with('/'))
+                {
+                    format!("{}{rest}", rename.new_uri)
+                } else {
+                    continue;
+                };
+                renames.push((file.clone(), graphql_ide::FilePath::new(new_uri)));
+                break;
+            }
+        }
+        if !renames.is_empty() {
+            per_host.push((key.clone(), renames));
+        }
+    }
+
+    let mut changes = std::collections::HashMap::new();
+    for ((workspace_uri, project_name), renames) in per_host {
+        let Some(host) = state.workspace.get_host(&workspace_uri, &project_name) else {
+            continue;
+        };
+        changes.extend(host.snapshot().import_edits_for_renames(&renames));
+
+        let project = Some((workspace_uri.clone(), project_name.clone()));
+        let (moved, removed): (Vec<_>, Vec<_>) = renames.into_iter().partition(|(_, to)| {
+            Uri::from_str(to.as_str())
+                .ok()
+                .and_then(|uri| state.workspace.find_workspace_and_project(&uri))
+                == project
+        });
+        let Some(host) = state.workspace.get_host_mut(&workspace_uri, &project_name) else {
+            continue;
+        };
+        host.rename_files(&moved);
+        for (from, _) in &removed {
+            host.remove_file(from);
+        }
+        let snapshot = host.snapshot();
+
+        for (from, to) in moved.iter().chain(&removed) {
+            tracing::info!("File renamed: {} -> {}", from.as_str(), to.as_str());
+            let workspace = &mut state.workspace;
+            if let Some(version) = workspace.document_versions.remove(from.as_str()) {
+                workspace
+                    .document_versions
+                    .insert(to.as_str().to_string(), version);
+            }
+            if let Some(content) = workspace.document_contents.remove(from.as_str()) {
+                workspace
+                    .document_contents
+                    .insert(to.as_str().to_string(), content);
+            }
+            workspace.file_to_project.remove(from.as_str());
+            state.semantic_tokens.remove(from.as_str());
+            if let Ok(uri) = Uri::from_str(from.as_str()) {
+                state.publish_diagnostics(uri.clone(), Vec::new(), None);
+                state.diagnostics_scheduler.forget(&uri);
+            }
+        }
+        for (_, to) in &moved {
+            state.workspace.file_to_project.insert(
+                to.as_str().to_string(),
+                (workspace_uri.clone(), project_name.clone()),
+            );
+        }
+
+        let moved: Vec<_> = moved.into_iter().map(|(_, to)| to).collect();
+        state.spawn_diagnostics_batch(move || {
+            let mut all_diagnostics = std::collections::HashMap::new();
+            for file in &moved {
+                all_diagnostics.extend(snapshot.all_diagnostics_for_change(file));
+            }
+            all_diagnostics
+                .into_iter()
+                .filter_map(|(file_path, diags)| {
+                    let file_uri = Uri::from_str(file_path.as_str()).ok()?;
+                    let lsp_diagnostics = diags.into_iter().map(convert_ide_diagnostic).collect();
+                    Some((file_uri, lsp_diagnostics))
+                })
+                .collect()
+        });
+    }
+
+    if changes.is_empty() {
+        return None;
+    }
+    crate::handlers::editing::convert_changes(changes)
 }
 
 pub(crate) fn handle_did_change_watched_files(
@@ -235,6 +430,27 @@ pub(crate) fn handle_did_change_watched_files(
                 continue;
             }
 
+            if change.typ == FileChangeType::CREATED {
+                if let Some(workspace_uri) = inferred_workspace_for_config(state, &config_path) {
+                    tracing::info!("Config file created for workspace: {}", workspace_uri);
+                    loading::reload_workspace_config(state, &workspace_uri);
+                    continue;
+                }
+            }
+
+            let settings_workspace: Option<String> = state
+                .workspace
+                .settings_paths
+                .iter()
+                .find(|(_, path)| **path == config_path)
+                .map(|(ws_uri, _)| ws_uri.clone());
+
+            if let Some(workspace_uri) = settings_workspace {
+                tracing::info!("Analyzer settings changed for workspace: {}", workspace_uri);
+                loading::reload_analyzer_settings(state, &workspace_uri);
+                continue;
+            }
+
             let resolved_match: Option<(String, String)> = state
                 .workspace
                 .resolved_schema_paths
@@ -258,3 +474,54 @@ pub(crate) fn handle_did_change_watched_files(
     #[cfg(not(feature = "native"))]
     let _ = (state, params);
 }
+
+/// The inferred-project workspace a newly created config file at `path`
+/// would be found for, if any
+#[cfg(feature = "native")]
+fn inferred_workspace_for_config(state: &GlobalState, path: &std::path::Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+    if !graphql_config::CONFIG_FILES.contains(&file_name) {
+        return None;
+    }
+    let dir = path.parent()?;
+    state
+        .workspace
+        .inferred_workspaces
+        .iter()
+        .find(|ws_uri| {
+            state
+                .workspace
+                .workspace_roots
+                .get(*ws_uri)
+                .is_some_and(|root| root.starts_with(dir))
+        })
+        .cloned()
+}
+
+/// The client's settings changed. Take the editor's feature toggles from
+/// the payload, then re-read `.graphqlanalyzer.toml` for every workspace;
+/// clients without file watching rely on this to pick up edits.
+///
+/// Settings without a `features` object clear the toggles. An invalid one,
+/// or a payload that isn't an object at all (as pull-model clients send),
+/// leaves them as they were.
+pub(crate) fn handle_did_change_configuration(
+    state: &mut GlobalState,
+    params: DidChangeConfigurationParams,
+) {
+    if let Some(editor) = EditorFeatures::from_settings(&params.settings) {
+        state.features.editor = editor;
+    } else if params.settings.is_object() && !EditorFeatures::declared_in(&params.settings) {
+        state.features.editor = EditorFeatures::default();
+    }
+
+    #[cfg(feature = "native")]
+    {
+        let workspace_uris: Vec<String> = state.workspace.settings_paths.keys().cloned().collect();
+        for workspace_uri in workspace_uris {
+            loading::reload_analyzer_settings(state, &workspace_uri);
+        }
+    }
+
+    features::sync_registrations(state);
+}
@@ -1,16 +1,25 @@
 #![allow(clippy::needless_pass_by_value)]
 
 use crate::conversions::{
-    convert_ide_completion_item, convert_ide_diagnostic, convert_ide_range,
-    convert_ide_signature_help, convert_lsp_position,
+    convert_ide_completion_item, convert_ide_diagnostic, convert_ide_location, convert_ide_range,
+    convert_ide_signature_help, convert_lsp_position, convert_lsp_range,
 };
 use crate::global_state::{GlobalState, GlobalStateSnapshot};
+use crate::server::{
+    FixAllParams, FixAllResponse, MigrateFieldParams, MigrateFieldResponse,
+    OrganizeFragmentsParams, OrganizeSchemaParams, PreviewFixParams, PreviewFixResponse,
+    SelectionSkeletonParams, SelectionSkeletonResponse, UnmigratedFieldUsage,
+};
 use lsp_types::{
     CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionResponse,
-    CompletionParams, CompletionResponse, ExecuteCommandParams, PrepareRenameResponse,
-    RenameParams, SignatureHelpParams, TextDocumentPositionParams, TextEdit, Uri, WorkspaceEdit,
+    CompletionParams, CompletionResponse, CreateFile, DeleteFile, DeleteFileOptions,
+    DocumentChangeOperation, DocumentChanges, DocumentOnTypeFormattingParams, ExecuteCommandParams,
+    OneOf, OptionalVersionedTextDocumentIdentifier, PrepareRenameResponse, RenameParams,
+    ResourceOp, SignatureHelpParams, TextDocumentEdit, TextDocumentPositionParams, TextEdit, Uri,
+    WorkspaceEdit,
 };
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 pub(crate) fn handle_completion(
@@ -24,6 +33,43 @@ pub(crate) fn handle_completion(
     Some(CompletionResponse::Array(lsp_items))
 }
 
+pub(crate) fn handle_on_type_formatting(
+    snap: GlobalStateSnapshot,
+    params: DocumentOnTypeFormattingParams,
+) -> Option<Vec<TextEdit>> {
+    let position = convert_lsp_position(params.text_document_position.position);
+    let ch = params.ch.chars().next()?;
+    let indent = indent_unit(&snap, &params.options);
+    let edits = snap
+        .analysis
+        .on_type_formatting(&snap.file_path, position, ch, &indent);
+    if edits.is_empty() {
+        return None;
+    }
+    Some(
+        edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: convert_ide_range(edit.range),
+                new_text: edit.new_text,
+            })
+            .collect(),
+    )
+}
+
+/// One indentation level: the analyzer settings' format style when set,
+/// otherwise the editor's formatting options
+fn indent_unit(snap: &GlobalStateSnapshot, options: &lsp_types::FormattingOptions) -> String {
+    let settings = snap.analysis.analyzer_settings();
+    settings.format.indent(options.tab_size).unwrap_or_else(|| {
+        if options.insert_spaces {
+            " ".repeat(options.tab_size as usize)
+        } else {
+            "\t".to_string()
+        }
+    })
+}
+
 pub(crate) fn handle_signature_help(
     snap: GlobalStateSnapshot,
     params: SignatureHelpParams,
@@ -34,6 +80,21 @@ pub(crate) fn handle_signature_help(
         .map(convert_ide_signature_help)
 }
 
+pub(crate) fn handle_selection_skeleton(
+    snap: GlobalStateSnapshot,
+    params: SelectionSkeletonParams,
+) -> Option<SelectionSkeletonResponse> {
+    let position = convert_lsp_position(params.position);
+    let indent = indent_unit(&snap, &params.options);
+    let skeleton =
+        snap.analysis
+            .selection_skeleton(&snap.file_path, position, params.depth, &indent)?;
+    Some(SelectionSkeletonResponse {
+        range: convert_ide_range(skeleton.range),
+        snippet: skeleton.snippet,
+    })
+}
+
 pub(crate) fn handle_prepare_rename(
     snap: GlobalStateSnapshot,
     params: TextDocumentPositionParams,
@@ -50,9 +111,16 @@ pub(crate) fn handle_rename(
     let position = convert_lsp_position(params.text_document_position.position);
     let new_name = params.new_name;
     let result = snap.analysis.rename(&snap.file_path, position, &new_name)?;
+    convert_changes(result.changes)
+}
+
+/// Build a `WorkspaceEdit` from graphql-ide edits per file.
+pub(crate) fn convert_changes(
+    changes: HashMap<graphql_ide::FilePath, Vec<graphql_ide::TextEdit>>,
+) -> Option<WorkspaceEdit> {
     #[allow(clippy::mutable_key_type)]
-    let mut changes: HashMap<Uri, Vec<TextEdit>> = HashMap::new();
-    for (ide_path, edits) in result.changes {
+    let mut lsp_changes: HashMap<Uri, Vec<TextEdit>> = HashMap::new();
+    for (ide_path, edits) in changes {
         let uri: Uri = ide_path.as_str().parse().ok()?;
         let lsp_edits = edits
             .into_iter()
@@ -61,15 +129,175 @@ pub(crate) fn handle_rename(
                 new_text: edit.new_text,
             })
             .collect();
-        changes.insert(uri, lsp_edits);
+        lsp_changes.insert(uri, lsp_edits);
     }
     Some(WorkspaceEdit {
-        changes: Some(changes),
+        changes: Some(lsp_changes),
         document_changes: None,
         change_annotations: None,
     })
 }
 
+pub(crate) fn handle_preview_fix(
+    snap: GlobalStateSnapshot,
+    params: PreviewFixParams,
+) -> Option<PreviewFixResponse> {
+    let id = graphql_ide::DiagnosticId {
+        file: snap.file_path.clone(),
+        code: params.code,
+        range: convert_lsp_range(params.range),
+    };
+    let preview = snap.analysis.preview_fix(&id)?;
+    Some(PreviewFixResponse {
+        label: preview.label,
+        edit: convert_changes(preview.changes)?,
+    })
+}
+
+pub(crate) fn handle_fix_all(snap: GlobalStateSnapshot, params: FixAllParams) -> FixAllResponse {
+    let result = snap.analysis.fix_all(&params.rule);
+    let skipped = result
+        .skipped
+        .iter()
+        .filter_map(|id| {
+            Some(lsp_types::Location {
+                uri: id.file.as_str().parse().ok()?,
+                range: convert_ide_range(id.range),
+            })
+        })
+        .collect();
+    FixAllResponse {
+        edit: convert_changes(result.changes).unwrap_or_default(),
+        applied: result.applied,
+        skipped,
+    }
+}
+
+pub(crate) fn handle_migrate_field(
+    snap: GlobalStateSnapshot,
+    params: MigrateFieldParams,
+) -> Option<MigrateFieldResponse> {
+    let (type_name, old_field) = params.from.split_once('.')?;
+    let new_field = match params.to.split_once('.') {
+        Some((to_type, new_field)) if to_type == type_name => new_field,
+        Some(_) => return None,
+        None => params.to.as_str(),
+    };
+    let result = snap
+        .analysis
+        .migrate_field(type_name, old_field, new_field)?;
+    let unmigrated = result
+        .unmigrated
+        .iter()
+        .map(|usage| UnmigratedFieldUsage {
+            location: convert_ide_location(&usage.location),
+            message: usage.message.clone(),
+        })
+        .collect();
+    Some(MigrateFieldResponse {
+        edit: convert_changes(result.changes).unwrap_or_default(),
+        unmigrated,
+    })
+}
+
+pub(crate) fn handle_organize_fragments(
+    snap: GlobalStateSnapshot,
+    params: OrganizeFragmentsParams,
+) -> Option<WorkspaceEdit> {
+    let fragment_file = graphql_ide::FilePath::new(params.fragment_file.as_str());
+    let result = snap.analysis.organize_fragments(&fragment_file);
+    if result.fragments.is_empty() {
+        return None;
+    }
+
+    let text_document_edit = |uri: Uri, edits: Vec<graphql_ide::TextEdit>| {
+        DocumentChangeOperation::Edit(TextDocumentEdit {
+            text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+            edits: edits
+                .into_iter()
+                .map(|edit| {
+                    OneOf::Left(TextEdit {
+                        range: convert_ide_range(edit.range),
+                        new_text: edit.new_text,
+                    })
+                })
+                .collect(),
+        })
+    };
+
+    let mut operations = Vec::new();
+    if let Some(content) = result.new_file_content {
+        operations.push(DocumentChangeOperation::Op(ResourceOp::Create(
+            CreateFile {
+                uri: params.fragment_file.clone(),
+                options: None,
+                annotation_id: None,
+            },
+        )));
+        let start = graphql_ide::Position::new(0, 0);
+        operations.push(text_document_edit(
+            params.fragment_file,
+            vec![graphql_ide::TextEdit::new(
+                graphql_ide::Range::new(start, start),
+                content,
+            )],
+        ));
+    }
+
+    let mut changes: Vec<_> = result.changes.into_iter().collect();
+    changes.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    for (file, edits) in changes {
+        let uri: Uri = file.as_str().parse().ok()?;
+        operations.push(text_document_edit(uri, edits));
+    }
+
+    Some(WorkspaceEdit {
+        changes: None,
+        document_changes: Some(DocumentChanges::Operations(operations)),
+        change_annotations: None,
+    })
+}
+
+pub(crate) fn handle_organize_schema(
+    snap: GlobalStateSnapshot,
+    params: OrganizeSchemaParams,
+) -> Option<Vec<TextEdit>> {
+    let groups: Vec<graphql_ide::SchemaGroup> = params
+        .groups
+        .iter()
+        .filter_map(|name| {
+            let group = match name.as_str() {
+                "directives" => graphql_ide::SchemaGroup::Directives,
+                "scalars" => graphql_ide::SchemaGroup::Scalars,
+                "enums" => graphql_ide::SchemaGroup::Enums,
+                "inputs" => graphql_ide::SchemaGroup::Inputs,
+                "interfaces" => graphql_ide::SchemaGroup::Interfaces,
+                "objects" => graphql_ide::SchemaGroup::Objects,
+                "unions" => graphql_ide::SchemaGroup::Unions,
+                _ => {
+                    tracing::warn!("Ignoring unknown schema group: {name}");
+                    return None;
+                }
+            };
+            Some(group)
+        })
+        .collect();
+
+    let edits = snap.analysis.organize_schema(&snap.file_path, &groups);
+    if edits.is_empty() {
+        return None;
+    }
+    Some(
+        edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: convert_ide_range(edit.range),
+                new_text: edit.new_text,
+            })
+            .collect(),
+    )
+}
+
 pub(crate) fn handle_execute_command(
     state: &mut GlobalState,
     params: ExecuteCommandParams,
@@ -148,12 +376,239 @@ pub(crate) fn handle_execute_command(
         );
 
         Some(serde_json::json!({ "success": true }))
+    } else if params.command.as_str() == "graphql-analyzer.downloadSchema" {
+        handle_download_schema(state, &params.arguments)
+    } else if params.command.as_str() == "graphql-analyzer.rulesManifest" {
+        serde_json::to_value(graphql_linter::rules_manifest()).ok()
+    } else if params.command.as_str() == "graphql-analyzer.generateOperationTypes" {
+        handle_generate_operation_types(state, &params.arguments)
+    } else if params.command.as_str() == "graphql-analyzer.operationGraph" {
+        handle_operation_graph(state, &params.arguments)
+    } else if params.command.as_str() == "graphql-analyzer.operationWithFragments" {
+        handle_operation_with_fragments(state, &params.arguments)
     } else {
         tracing::warn!("Unknown command: {}", params.command);
         None
     }
 }
 
+/// Return (and optionally save) the SDL of a remote schema the server has
+/// already introspected.
+///
+/// Expects a single argument `{ "url": string, "output"?: string }`. The SDL
+/// is the same text served for the endpoint's `schema://` virtual file, so
+/// descriptions, deprecations, and directives match what the editor shows.
+/// A relative `output` is resolved against the root of the workspace that
+/// loaded the schema, and an `output` outside that workspace is refused.
+fn handle_download_schema(
+    state: &mut GlobalState,
+    arguments: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    let args = arguments.first();
+    let Some(url) = args
+        .and_then(|a| a.get("url"))
+        .and_then(serde_json::Value::as_str)
+    else {
+        tracing::warn!("downloadSchema requires a `url` argument");
+        return None;
+    };
+    let output = args
+        .and_then(|a| a.get("output"))
+        .and_then(serde_json::Value::as_str);
+
+    let virtual_uri = graphql_ide::PendingIntrospection {
+        url: url.to_string(),
+        headers: None,
+        timeout: None,
+        retry: None,
+        url_from_env: false,
+    }
+    .virtual_uri();
+    let virtual_path = graphql_ide::FilePath::new(virtual_uri.clone());
+
+    let loaded = state
+        .workspace
+        .all_hosts()
+        .find_map(|((workspace_uri, _), host)| {
+            let sdl = host.snapshot().file_content(&virtual_path)?;
+            Some((workspace_uri.clone(), sdl))
+        });
+    let Some((workspace_uri, sdl)) = loaded else {
+        state.send_notification::<lsp_types::notification::ShowMessage>(
+            lsp_types::ShowMessageParams {
+                typ: lsp_types::MessageType::WARNING,
+                message: format!("No introspected schema loaded for {url}"),
+            },
+        );
+        return None;
+    };
+
+    if let Some(output) = output {
+        let path = state
+            .workspace
+            .workspace_roots
+            .get(&workspace_uri)
+            .and_then(|root| resolve_in_workspace(root, output));
+        let Some(path) = path else {
+            tracing::warn!("Refusing to write schema outside the workspace: {}", output);
+            state.send_notification::<lsp_types::notification::ShowMessage>(
+                lsp_types::ShowMessageParams {
+                    typ: lsp_types::MessageType::ERROR,
+                    message: format!("Schema output {output} is outside the workspace"),
+                },
+            );
+            return None;
+        };
+        if let Err(e) = std::fs::write(&path, sdl.as_bytes()) {
+            tracing::error!("Failed to write schema to {}: {}", path.display(), e);
+            state.send_notification::<lsp_types::notification::ShowMessage>(
+                lsp_types::ShowMessageParams {
+                    typ: lsp_types::MessageType::ERROR,
+                    message: format!("Failed to write schema to {}: {e}", path.display()),
+                },
+            );
+            return None;
+        }
+        tracing::info!("Saved schema from {} to {}", url, path.display());
+    }
+
+    Some(serde_json::json!({
+        "url": url,
+        "uri": virtual_uri,
+        "sdl": &*sdl,
+    }))
+}
+
+/// Resolve `output` against `root`, or `None` if it leads outside of it.
+///
+/// `..` is resolved lexically, since the file usually doesn't exist yet.
+fn resolve_in_workspace(root: &Path, output: &str) -> Option<PathBuf> {
+    let mut resolved = PathBuf::new();
+    for component in root.join(output).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !resolved.pop() {
+                    return None;
+                }
+            }
+            component => resolved.push(component),
+        }
+    }
+    resolved.starts_with(root).then_some(resolved)
+}
+
+/// Generate TypeScript types for the operations in a document.
+///
+/// Expects a single argument `{ "uri": string }` and returns one entry per
+/// named operation with the generated code and the operation's range, so
+/// the client decides whether to show the types or insert them.
+#[cfg(feature = "codegen")]
+fn handle_generate_operation_types(
+    state: &GlobalState,
+    arguments: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    let Some(uri) = arguments
+        .first()
+        .and_then(|a| a.get("uri"))
+        .and_then(serde_json::Value::as_str)
+    else {
+        tracing::warn!("generateOperationTypes requires a `uri` argument");
+        return None;
+    };
+    let uri: Uri = uri.parse().ok()?;
+    let snap = state.snapshot_for_uri(&uri)?;
+
+    let operations: Vec<serde_json::Value> = snap
+        .analysis
+        .generate_operation_types(&snap.file_path)
+        .into_iter()
+        .map(|types| {
+            serde_json::json!({
+                "operationName": types.operation_name,
+                "resultType": types.result_type,
+                "variablesType": types.variables_type,
+                "code": types.code,
+                "range": convert_ide_range(types.range),
+            })
+        })
+        .collect();
+    Some(serde_json::json!({ "operations": operations }))
+}
+
+#[cfg(not(feature = "codegen"))]
+fn handle_generate_operation_types(
+    _state: &GlobalState,
+    _arguments: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    tracing::warn!("generateOperationTypes requires the `codegen` feature");
+    None
+}
+
+/// Export the dependency graph of the project a document belongs to.
+///
+/// Expects a single argument `{ "uri": string, "format"?: "dot" | "json" }`
+/// and returns `{ "format", "graph" }`, with the graph as DOT text or as
+/// `{ "nodes", "edges" }`. Defaults to DOT.
+fn handle_operation_graph(
+    state: &GlobalState,
+    arguments: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    let args = arguments.first();
+    let Some(uri) = args
+        .and_then(|a| a.get("uri"))
+        .and_then(serde_json::Value::as_str)
+    else {
+        tracing::warn!("operationGraph requires a `uri` argument");
+        return None;
+    };
+    let format = args
+        .and_then(|a| a.get("format"))
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or("dot");
+    let uri: Uri = uri.parse().ok()?;
+    let snap = state.snapshot_for_uri(&uri)?;
+
+    let graph = snap.analysis.operation_graph();
+    match format {
+        "dot" => Some(serde_json::json!({ "format": "dot", "graph": graph.to_dot() })),
+        "json" => Some(serde_json::json!({ "format": "json", "graph": graph.to_json() })),
+        other => {
+            tracing::warn!("operationGraph: unknown format `{other}`");
+            None
+        }
+    }
+}
+
+/// Return an operation together with the fragments it spreads, as one
+/// document that runs on its own.
+///
+/// Expects a single argument `{ "uri": string, "operationIndex": number }`,
+/// where the index numbers the operations in the document, and returns
+/// `{ "document": string }`. Backs the "Copy with fragments" code lens.
+fn handle_operation_with_fragments(
+    state: &GlobalState,
+    arguments: &[serde_json::Value],
+) -> Option<serde_json::Value> {
+    let args = arguments.first();
+    let (Some(uri), Some(operation_index)) = (
+        args.and_then(|a| a.get("uri"))
+            .and_then(serde_json::Value::as_str),
+        args.and_then(|a| a.get("operationIndex"))
+            .and_then(serde_json::Value::as_u64),
+    ) else {
+        tracing::warn!("operationWithFragments requires `uri` and `operationIndex` arguments");
+        return None;
+    };
+    let uri: Uri = uri.parse().ok()?;
+    let snap = state.snapshot_for_uri(&uri)?;
+
+    let document = snap
+        .analysis
+        .operation_with_fragments(&snap.file_path, usize::try_from(operation_index).ok()?)?;
+    Some(serde_json::json!({ "document": document }))
+}
+
 #[allow(clippy::mutable_key_type)]
 pub(crate) fn handle_code_action(
     snap: GlobalStateSnapshot,
@@ -170,7 +625,48 @@ pub(crate) fn handle_code_action(
         lint_diagnostics.extend(project_diags_for_file.iter().cloned());
     }
 
-    if lint_diagnostics.is_empty() {
+    // Validation diagnostics (e.g. unused or undeclared variables) carry
+    // their fix in line/column form already.
+    let validation_diagnostics: Vec<graphql_ide::Diagnostic> = snap
+        .analysis
+        .validation_diagnostics(&snap.file_path)
+        .into_iter()
+        .filter(|d| d.fix.is_some())
+        .collect();
+
+    let inline_edit = snap
+        .analysis
+        .inline_fragment_spread(&snap.file_path, convert_lsp_position(range.start));
+
+    // Code action requests carry no formatting options, so the analyzer
+    // settings decide the indentation, falling back to two spaces
+    let indent = snap
+        .analysis
+        .analyzer_settings()
+        .format
+        .indent(2)
+        .unwrap_or_else(|| "  ".to_string());
+    let fill_edits: Vec<(&str, graphql_ide::TextEdit)> = [
+        ("Fill in scalar fields", 0),
+        ("Fill in scalar and nested fields", 1),
+    ]
+    .into_iter()
+    .filter_map(|(title, depth)| {
+        let edit = snap.analysis.fill_selection_set(
+            &snap.file_path,
+            convert_lsp_position(range.start),
+            depth,
+            &indent,
+        )?;
+        Some((title, edit))
+    })
+    .collect();
+
+    if lint_diagnostics.is_empty()
+        && validation_diagnostics.is_empty()
+        && inline_edit.is_none()
+        && fill_edits.is_empty()
+    {
         return None;
     }
 
@@ -181,7 +677,7 @@ pub(crate) fn handle_code_action(
 
     let content = snap.analysis.file_content(&snap.file_path)?;
 
-    let file_line_index = graphql_syntax::LineIndex::new(&content);
+    let file_line_index = graphql_syntax::LineIndex::from_shared(content);
     let uri = match Uri::from_str(&snap.file_path.0) {
         Ok(uri) => uri,
         Err(e) => {
@@ -194,106 +690,258 @@ pub(crate) fn handle_code_action(
         }
     };
 
-    for diag in lint_diagnostics {
-        let Some(ref fix) = diag.fix else {
+    for diag in validation_diagnostics {
+        if (diag.range.end.line as usize) < start_line
+            || (diag.range.start.line as usize) > end_line
+        {
+            continue;
+        }
+        let Some(fix) = diag.fix.clone() else {
             continue;
         };
 
-        let (line_offset, diag_line_index): (u32, std::borrow::Cow<'_, graphql_syntax::LineIndex>) =
-            if let Some(ref block_source) = diag.span.source {
-                (
-                    diag.span.line_offset,
-                    std::borrow::Cow::Owned(graphql_syntax::LineIndex::new(block_source)),
-                )
-            } else {
-                (0, std::borrow::Cow::Borrowed(&file_line_index))
-            };
+        let edits: Vec<TextEdit> = fix
+            .edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: convert_ide_range(edit.range),
+                new_text: edit.new_text,
+            })
+            .collect();
+
+        let mut changes = HashMap::new();
+        changes.insert(uri.clone(), edits);
+
+        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title: fix.label,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![convert_ide_diagnostic(diag)]),
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }),
+            command: None,
+            is_preferred: Some(true),
+            disabled: None,
+            data: None,
+        }));
+    }
+
+    for diag in lint_diagnostics {
+        if diag.fix.is_none() && diag.suggestions.is_empty() {
+            continue;
+        }
+
+        let (origin, diag_line_index): (
+            graphql_syntax::BlockOrigin,
+            std::borrow::Cow<'_, graphql_syntax::LineIndex>,
+        ) = if let Some(ref block_source) = diag.span.source {
+            (
+                file_line_index.block_origin(diag.span.byte_offset),
+                std::borrow::Cow::Owned(graphql_syntax::LineIndex::from_shared(
+                    block_source.clone(),
+                )),
+            )
+        } else {
+            (
+                graphql_syntax::BlockOrigin::default(),
+                std::borrow::Cow::Borrowed(&file_line_index),
+            )
+        };
 
         let (diag_start_line, _) = diag_line_index.line_col(diag.span.start);
         let (diag_end_line, _) = diag_line_index.line_col(diag.span.end);
-        let diag_start_line = diag_start_line + line_offset as usize;
-        let diag_end_line = diag_end_line + line_offset as usize;
+        let diag_start_line = diag_start_line + origin.line as usize;
+        let diag_end_line = diag_end_line + origin.line as usize;
 
         if diag_end_line < start_line || diag_start_line > end_line {
             continue;
         }
 
-        let edits: Vec<TextEdit> = fix
-            .edits
-            .iter()
-            .map(|edit| {
-                let (start_line, start_col) = diag_line_index.line_col(edit.offset_range.start);
-                let (end_line, end_col) = diag_line_index.line_col(edit.offset_range.end);
-
-                TextEdit {
-                    range: lsp_types::Range {
-                        start: lsp_types::Position {
-                            line: (start_line + line_offset as usize) as u32,
-                            character: start_col as u32,
-                        },
-                        end: lsp_types::Position {
-                            line: (end_line + line_offset as usize) as u32,
-                            character: end_col as u32,
-                        },
-                    },
-                    new_text: edit.new_text.clone(),
-                }
-            })
-            .collect();
+        let convert_edits = |edits: &[graphql_linter::TextEdit]| -> Vec<TextEdit> {
+            edits
+                .iter()
+                .map(|edit| {
+                    let position = |offset| {
+                        let (line, character) = diag_line_index.line_col(offset);
+                        let (line, character) = origin.to_file(line as u32, character as u32);
+                        lsp_types::Position { line, character }
+                    };
 
-        let mut changes = HashMap::new();
-        changes.insert(uri.clone(), edits);
+                    TextEdit {
+                        range: lsp_types::Range {
+                            start: position(edit.offset_range.start),
+                            end: position(edit.offset_range.end),
+                        },
+                        new_text: edit.new_text.clone(),
+                    }
+                })
+                .collect()
+        };
 
-        let workspace_edit = WorkspaceEdit {
-            changes: Some(changes),
-            document_changes: None,
-            change_annotations: None,
+        let workspace_edit = |edits: &[graphql_linter::TextEdit]| {
+            let mut changes = HashMap::new();
+            changes.insert(uri.clone(), convert_edits(edits));
+            WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }
         };
 
-        let action = CodeAction {
-            title: fix.label.clone(),
-            kind: Some(CodeActionKind::QUICKFIX),
-            diagnostics: Some(vec![convert_ide_diagnostic(graphql_ide::Diagnostic {
-                range: graphql_ide::Range {
-                    start: graphql_ide::Position {
-                        line: diag_start_line as u32,
-                        character: 0,
-                    },
-                    end: graphql_ide::Position {
-                        line: diag_end_line as u32,
-                        character: 0,
-                    },
+        let diagnostics = Some(vec![convert_ide_diagnostic(graphql_ide::Diagnostic {
+            range: graphql_ide::Range {
+                start: graphql_ide::Position {
+                    line: diag_start_line as u32,
+                    character: 0,
                 },
-                severity: graphql_ide::DiagnosticSeverity::Warning,
-                message: diag.message.clone(),
-                code: Some(diag.rule.clone()),
-                message_id: None,
-                source: "graphql-linter".to_string(),
-                fix: None,
-                suggestions: Vec::new(),
-                help: diag.help.clone(),
-                url: diag.url.clone(),
-                tags: diag
-                    .tags
-                    .iter()
-                    .map(|t| match t {
-                        graphql_linter::DiagnosticTag::Unnecessary => {
-                            graphql_ide::DiagnosticTag::Unnecessary
-                        }
-                        graphql_linter::DiagnosticTag::Deprecated => {
-                            graphql_ide::DiagnosticTag::Deprecated
-                        }
-                    })
-                    .collect(),
-            })]),
-            edit: Some(workspace_edit),
+                end: graphql_ide::Position {
+                    line: diag_end_line as u32,
+                    character: 0,
+                },
+            },
+            severity: graphql_ide::DiagnosticSeverity::Warning,
+            message: diag.message.clone(),
+            code: Some(diag.rule.clone()),
+            message_id: None,
+            source: "graphql-linter".to_string(),
+            fix: None,
+            suggestions: Vec::new(),
+            help: diag.help.clone(),
+            url: diag.url.clone(),
+            tags: diag
+                .tags
+                .iter()
+                .map(|t| match t {
+                    graphql_linter::DiagnosticTag::Unnecessary => {
+                        graphql_ide::DiagnosticTag::Unnecessary
+                    }
+                    graphql_linter::DiagnosticTag::Deprecated => {
+                        graphql_ide::DiagnosticTag::Deprecated
+                    }
+                })
+                .collect(),
+            related: Vec::new(),
+        })]);
+
+        if let Some(ref fix) = diag.fix {
+            let action = CodeAction {
+                title: fix.label.clone(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: diagnostics.clone(),
+                edit: Some(workspace_edit(&fix.edits)),
+                command: None,
+                is_preferred: Some(true),
+                disabled: None,
+                data: None,
+            };
+
+            // Removing the last definition leaves nothing worth keeping, so
+            // offer to delete the file itself as well, if the client can
+            let empties_file = snap.client_deletes_files
+                && fix
+                    .apply(&content, diag.span.byte_offset)
+                    .is_some_and(|remaining| remaining.trim().is_empty());
+            let delete_file_action = empties_file.then(|| CodeAction {
+                title: format!("{} and delete the file", fix.label),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: action.diagnostics.clone(),
+                edit: Some(WorkspaceEdit {
+                    changes: None,
+                    document_changes: Some(DocumentChanges::Operations(vec![
+                        DocumentChangeOperation::Op(ResourceOp::Delete(DeleteFile {
+                            uri: uri.clone(),
+                            options: Some(DeleteFileOptions {
+                                recursive: None,
+                                ignore_if_not_exists: Some(true),
+                                annotation_id: None,
+                            }),
+                        })),
+                    ])),
+                    change_annotations: None,
+                }),
+                command: None,
+                is_preferred: Some(false),
+                disabled: None,
+                data: None,
+            });
+
+            actions.push(CodeActionOrCommand::CodeAction(action));
+            if let Some(delete_file_action) = delete_file_action {
+                actions.push(CodeActionOrCommand::CodeAction(delete_file_action));
+            }
+        }
+
+        // Suggestions change what the document does, so they're offered
+        // alongside the fix but never preferred
+        for suggestion in &diag.suggestions {
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: suggestion.desc.clone(),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: diagnostics.clone(),
+                edit: Some(workspace_edit(&suggestion.fix.edits)),
+                command: None,
+                is_preferred: Some(false),
+                disabled: None,
+                data: None,
+            }));
+        }
+    }
+
+    // Only offer nested fields when they add something
+    let fill_edits = match fill_edits.as_slice() {
+        [(_, leaves), (_, nested)] if leaves.new_text == nested.new_text => &fill_edits[..1],
+        _ => &fill_edits[..],
+    };
+    for (title, edit) in fill_edits {
+        let mut changes = HashMap::new();
+        changes.insert(
+            uri.clone(),
+            vec![TextEdit {
+                range: convert_ide_range(edit.range),
+                new_text: edit.new_text.clone(),
+            }],
+        );
+        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title: (*title).to_string(),
+            kind: Some(CodeActionKind::REFACTOR_REWRITE),
+            diagnostics: None,
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }),
             command: None,
-            is_preferred: Some(true),
+            is_preferred: None,
             disabled: None,
             data: None,
-        };
+        }));
+    }
 
-        actions.push(CodeActionOrCommand::CodeAction(action));
+    if let Some(edit) = inline_edit {
+        let mut changes = HashMap::new();
+        changes.insert(
+            uri,
+            vec![TextEdit {
+                range: convert_ide_range(edit.range),
+                new_text: edit.new_text,
+            }],
+        );
+        actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+            title: "Inline fragment spread here".to_string(),
+            kind: Some(CodeActionKind::REFACTOR_INLINE),
+            diagnostics: None,
+            edit: Some(WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }),
+            command: None,
+            is_preferred: None,
+            disabled: None,
+            data: None,
+        }));
     }
 
     if actions.is_empty() {
@@ -302,3 +950,33 @@ pub(crate) fn handle_code_action(
         Some(actions)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_output_resolves_inside_the_workspace() {
+        let root = Path::new("/workspace");
+        assert_eq!(
+            resolve_in_workspace(root, "schema/remote.graphql"),
+            Some(PathBuf::from("/workspace/schema/remote.graphql"))
+        );
+        assert_eq!(
+            resolve_in_workspace(root, "./a/../remote.graphql"),
+            Some(PathBuf::from("/workspace/remote.graphql"))
+        );
+        assert_eq!(
+            resolve_in_workspace(root, "/workspace/remote.graphql"),
+            Some(PathBuf::from("/workspace/remote.graphql"))
+        );
+    }
+
+    #[test]
+    fn schema_output_outside_the_workspace_is_refused() {
+        let root = Path::new("/workspace");
+        assert_eq!(resolve_in_workspace(root, "../remote.graphql"), None);
+        assert_eq!(resolve_in_workspace(root, "a/../../remote.graphql"), None);
+        assert_eq!(resolve_in_workspace(root, "/etc/remote.graphql"), None);
+    }
+}
@@ -1,13 +1,14 @@
 #![allow(clippy::needless_pass_by_value)]
 
 use crate::conversions::{
-    convert_ide_document_symbol, convert_ide_location, convert_ide_workspace_symbol,
-    convert_lsp_position,
+    convert_ide_document_highlight, convert_ide_document_symbol, convert_ide_location,
+    convert_ide_type_hierarchy_item, convert_ide_workspace_symbol, convert_lsp_position,
 };
 use crate::global_state::{GlobalState, GlobalStateSnapshot};
 use lsp_types::{
-    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
-    Location, ReferenceParams,
+    DocumentHighlight, DocumentHighlightParams, DocumentSymbolParams, DocumentSymbolResponse,
+    GotoDefinitionParams, GotoDefinitionResponse, Location, ReferenceParams, TypeHierarchyItem,
+    TypeHierarchyPrepareParams, TypeHierarchySubtypesParams, TypeHierarchySupertypesParams,
 };
 
 pub(crate) fn handle_goto_definition(
@@ -44,6 +45,66 @@ pub(crate) fn handle_references(
     }
 }
 
+pub(crate) fn handle_document_highlight(
+    snap: GlobalStateSnapshot,
+    params: DocumentHighlightParams,
+) -> Option<Vec<DocumentHighlight>> {
+    let position = convert_lsp_position(params.text_document_position_params.position);
+    let highlights = snap.analysis.document_highlights(&snap.file_path, position);
+    if highlights.is_empty() {
+        None
+    } else {
+        Some(
+            highlights
+                .into_iter()
+                .map(convert_ide_document_highlight)
+                .collect(),
+        )
+    }
+}
+
+pub(crate) fn handle_prepare_type_hierarchy(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchyPrepareParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let position = convert_lsp_position(params.text_document_position_params.position);
+    let items = snap
+        .analysis
+        .prepare_type_hierarchy(&snap.file_path, position)?;
+    Some(
+        items
+            .into_iter()
+            .map(convert_ide_type_hierarchy_item)
+            .collect(),
+    )
+}
+
+pub(crate) fn handle_type_hierarchy_supertypes(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchySupertypesParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let items = snap.analysis.type_hierarchy_supertypes(&params.item.name);
+    Some(
+        items
+            .into_iter()
+            .map(convert_ide_type_hierarchy_item)
+            .collect(),
+    )
+}
+
+pub(crate) fn handle_type_hierarchy_subtypes(
+    snap: GlobalStateSnapshot,
+    params: TypeHierarchySubtypesParams,
+) -> Option<Vec<TypeHierarchyItem>> {
+    let items = snap.analysis.type_hierarchy_subtypes(&params.item.name);
+    Some(
+        items
+            .into_iter()
+            .map(convert_ide_type_hierarchy_item)
+            .collect(),
+    )
+}
+
 pub(crate) fn handle_document_symbol(
     snap: GlobalStateSnapshot,
     params: DocumentSymbolParams,
@@ -0,0 +1,336 @@
+//! Background document indexing.
+//!
+//! Globbing and reading every document of a large repo can take a while, so
+//! it runs on the worker pool once a workspace's config and schemas are
+//! loaded. Until a project's documents arrive, requests are answered from
+//! what's already there (the schema and any open files), and the project's
+//! diagnostics are refreshed when its documents are registered. Progress is
+//! reported through `window/workDoneProgress` when the client supports it.
+
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "native")]
+use std::path::PathBuf;
+#[cfg(feature = "native")]
+use std::str::FromStr;
+
+#[cfg(feature = "native")]
+use lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, Uri, WorkDoneProgress,
+    WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkDoneProgressReport,
+};
+
+#[cfg(feature = "native")]
+use crate::conversions::convert_ide_diagnostic;
+use crate::global_state::GlobalState;
+#[cfg(feature = "native")]
+use crate::global_state::{Task, TaskResponse};
+#[cfg(feature = "native")]
+use crate::server::{StatusNotification, StatusParams};
+
+#[cfg(feature = "native")]
+const PROGRESS_TOKEN: &str = "graphql-analyzer/indexing";
+
+/// Tracks the projects whose documents are still being read.
+#[derive(Default)]
+pub struct IndexingState {
+    /// Bumped each time a workspace is loaded. Results from an earlier load
+    /// (e.g. one superseded by a config reload) are dropped.
+    generations: HashMap<String, u64>,
+    /// (workspace URI, project name) pairs not indexed yet
+    pending: HashSet<(String, String)>,
+    /// Projects started since indexing last went idle, for progress
+    started: usize,
+    /// Documents registered since indexing last went idle
+    files: usize,
+    started_at: Option<std::time::Instant>,
+    progress_open: bool,
+    /// Numbers each `window/workDoneProgress/create` request
+    next_id: u32,
+}
+
+impl IndexingState {
+    /// Start a new load of `workspace_uri`, abandoning any indexing still
+    /// running for an earlier one. Returns the load's generation.
+    pub fn begin_workspace(&mut self, workspace_uri: &str) -> u64 {
+        self.pending.retain(|(ws, _)| ws != workspace_uri);
+        let generation = self
+            .generations
+            .entry(workspace_uri.to_string())
+            .or_default();
+        *generation += 1;
+        *generation
+    }
+
+    /// Whether any project is still being indexed
+    #[must_use]
+    pub fn is_indexing(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn is_current(&self, workspace_uri: &str, generation: u64) -> bool {
+        self.generations.get(workspace_uri) == Some(&generation)
+    }
+}
+
+/// A project's documents, read on a worker thread.
+#[cfg(feature = "native")]
+pub struct IndexedDocuments {
+    workspace_uri: String,
+    project_name: String,
+    generation: u64,
+    /// `None` when discovery panicked
+    discovery: Option<graphql_ide::FileDiscoveryResult>,
+}
+
+/// Read a project's documents on the worker pool. The main loop registers
+/// them with [`apply_indexed_documents`] when they arrive.
+#[cfg(feature = "native")]
+pub fn spawn_document_indexing(
+    state: &mut GlobalState,
+    workspace_uri: &str,
+    project_name: &str,
+    project_config: graphql_config::ProjectConfig,
+    workspace_path: PathBuf,
+    extract_config: graphql_extract::ExtractConfig,
+) {
+    let generation = state
+        .indexing
+        .generations
+        .get(workspace_uri)
+        .copied()
+        .unwrap_or_default();
+    if !state.indexing.is_indexing() {
+        state.indexing.started = 0;
+        state.indexing.files = 0;
+        state.indexing.started_at = Some(std::time::Instant::now());
+    }
+    state
+        .indexing
+        .pending
+        .insert((workspace_uri.to_string(), project_name.to_string()));
+    state.indexing.started += 1;
+    report_progress(state);
+
+    let workspace_uri = workspace_uri.to_string();
+    let project_name = project_name.to_string();
+    let task_sender = state.task_sender.clone();
+    state.dispatcher.execute(Box::new(move || {
+        let discovery = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            graphql_ide::discover_document_files(&project_config, &workspace_path, &extract_config)
+        }))
+        .ok();
+        // The server may have shut down in the meantime
+        let _ = task_sender.send(Task {
+            response: TaskResponse::DocumentsIndexed(IndexedDocuments {
+                workspace_uri,
+                project_name,
+                generation,
+                discovery,
+            }),
+            timing: None,
+        });
+    }));
+}
+
+/// Register a project's documents with its host and refresh their
+/// diagnostics. Results for a workspace that has been reloaded or closed
+/// since indexing started are dropped.
+#[cfg(feature = "native")]
+pub fn apply_indexed_documents(state: &mut GlobalState, indexed: IndexedDocuments) {
+    let IndexedDocuments {
+        workspace_uri,
+        project_name,
+        generation,
+        discovery,
+    } = indexed;
+    if !state.indexing.is_current(&workspace_uri, generation)
+        || !state
+            .indexing
+            .pending
+            .remove(&(workspace_uri.clone(), project_name.clone()))
+    {
+        tracing::debug!(
+            workspace = workspace_uri,
+            project = project_name,
+            "dropping documents indexed for a superseded load"
+        );
+        return;
+    }
+
+    let Some(discovery) = discovery else {
+        tracing::error!("Document discovery panicked for project '{project_name}'");
+        finish_if_idle(state);
+        return;
+    };
+
+    // Open files keep the editor's content rather than what's on disk
+    let (open, files): (Vec<_>, Vec<_>) = discovery.files.into_iter().partition(|file| {
+        state
+            .workspace
+            .document_contents
+            .contains_key(file.path.as_str())
+    });
+    let Some(host) = state.workspace.get_host_mut(&workspace_uri, &project_name) else {
+        finish_if_idle(state);
+        return;
+    };
+    let loaded_files = host.add_discovered_files(&files);
+    let snapshot = host.snapshot();
+
+    for path in loaded_files
+        .iter()
+        .map(|file| &file.path)
+        .chain(open.iter().map(|file| &file.path))
+    {
+        state.workspace.file_to_project.insert(
+            path.as_str().to_string(),
+            (workspace_uri.clone(), project_name.clone()),
+        );
+    }
+
+    let message = format!(
+        "Project '{project_name}' indexed: {} document file(s)",
+        loaded_files.len() + open.len()
+    );
+    tracing::info!("{}", message);
+    state.send_notification::<lsp_types::notification::LogMessage>(lsp_types::LogMessageParams {
+        typ: lsp_types::MessageType::INFO,
+        message,
+    });
+    state.indexing.files += loaded_files.len() + open.len();
+
+    // Open files were answered from partial state until now, so they're
+    // refreshed along with the newly loaded ones
+    let paths: Vec<graphql_ide::FilePath> = loaded_files
+        .into_iter()
+        .map(|file| file.path)
+        .chain(open.into_iter().map(|file| file.path))
+        .collect();
+    state.spawn_diagnostics_batch(move || {
+        let diagnostics = snapshot.all_diagnostics_for_files(&paths);
+        paths
+            .iter()
+            .filter_map(|path| {
+                let uri = Uri::from_str(path.as_str()).ok()?;
+                let lsp_diagnostics = diagnostics
+                    .get(path)
+                    .map(|diags| diags.iter().cloned().map(convert_ide_diagnostic).collect())
+                    .unwrap_or_default();
+                Some((uri, lsp_diagnostics))
+            })
+            .collect()
+    });
+
+    finish_if_idle(state);
+}
+
+/// Update the indexing progress, and report the server ready once every
+/// project has been indexed.
+#[cfg(feature = "native")]
+pub fn finish_if_idle(state: &mut GlobalState) {
+    report_progress(state);
+    if state.indexing.is_indexing() {
+        return;
+    }
+
+    let elapsed = state
+        .indexing
+        .started_at
+        .take()
+        .map_or(0.0, |started| started.elapsed().as_secs_f64());
+    let message = format!(
+        "{} files loaded in {elapsed:.1}s",
+        state.workspace.file_to_project.len()
+    );
+    tracing::info!("Indexing complete: {}", message);
+    state.send_notification::<StatusNotification>(StatusParams {
+        status: "ready".to_string(),
+        message: Some(message),
+    });
+}
+
+/// Begin, update or end the indexing progress, if the client shows it.
+#[cfg(feature = "native")]
+fn report_progress(state: &mut GlobalState) {
+    let supported = state
+        .client_capabilities
+        .as_ref()
+        .and_then(|caps| caps.window.as_ref())
+        .and_then(|window| window.work_done_progress)
+        .unwrap_or(false);
+    if !supported || (!state.indexing.progress_open && !state.indexing.is_indexing()) {
+        return;
+    }
+
+    let total = state.indexing.started;
+    let done = total - state.indexing.pending.len();
+    let percentage = u32::try_from(done * 100 / total.max(1)).unwrap_or(100);
+    let progress = if !state.indexing.progress_open {
+        state.indexing.progress_open = true;
+        state.indexing.next_id += 1;
+        // Progress can be reported right after asking the client to create
+        // the token; the response is handled like any other client response
+        let create = lsp_server::Request::new(
+            lsp_server::RequestId::from(format!(
+                "graphql-analyzer/indexing/{}",
+                state.indexing.next_id
+            )),
+            "window/workDoneProgress/create".to_string(),
+            WorkDoneProgressCreateParams {
+                token: NumberOrString::String(PROGRESS_TOKEN.to_string()),
+            },
+        );
+        state
+            .sender
+            .send(lsp_server::Message::Request(create))
+            .expect("client channel open");
+        WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: "Indexing GraphQL documents".to_string(),
+            cancellable: Some(false),
+            message: Some(format!("{done}/{total} projects")),
+            percentage: Some(percentage),
+        })
+    } else if state.indexing.is_indexing() {
+        WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(false),
+            message: Some(format!("{done}/{total} projects")),
+            percentage: Some(percentage),
+        })
+    } else {
+        state.indexing.progress_open = false;
+        WorkDoneProgress::End(WorkDoneProgressEnd {
+            message: Some(format!("{} document file(s) indexed", state.indexing.files)),
+        })
+    };
+
+    state.send_notification::<lsp_types::notification::Progress>(ProgressParams {
+        token: NumberOrString::String(PROGRESS_TOKEN.to_string()),
+        value: ProgressParamsValue::WorkDone(progress),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reloading_a_workspace_supersedes_its_indexing() {
+        let mut indexing = IndexingState::default();
+        let first = indexing.begin_workspace("file:///a");
+        indexing
+            .pending
+            .insert(("file:///a".to_string(), "default".to_string()));
+        indexing
+            .pending
+            .insert(("file:///b".to_string(), "default".to_string()));
+        assert!(indexing.is_current("file:///a", first));
+
+        let second = indexing.begin_workspace("file:///a");
+        assert!(!indexing.is_current("file:///a", first));
+        assert!(indexing.is_current("file:///a", second));
+        // Other workspaces keep indexing
+        assert!(indexing.is_indexing());
+        assert_eq!(indexing.pending.len(), 1);
+    }
+}
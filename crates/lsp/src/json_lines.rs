@@ -0,0 +1,160 @@
+//! One-shot analysis that streams diagnostics as JSON lines.
+//!
+//! Loads the workspace in the current directory exactly as the language
+//! server would, with an in-memory connection standing in for the client,
+//! waits for documents to be indexed and remote schemas to be introspected,
+//! then writes one JSON object per diagnostic to stdout:
+//!
+//! ```json
+//! {"file":"/app/query.graphql","range":{"start":{"line":2,"character":4},"end":{"line":2,"character":9}},"severity":"error","code":"unknown-field","source":"graphql","message":"..."}
+//! ```
+//!
+//! Positions are zero-based, with UTF-16 characters, as in LSP. Files are
+//! filesystem paths where the URI has one. Lines are grouped by file in path
+//! order.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use graphql_ide::{Diagnostic, DiagnosticSeverity};
+
+use crate::global_state::{GlobalState, ThreadPoolDispatcher};
+
+/// Analyze the workspace in the current directory and write its diagnostics
+/// to stdout.
+pub(crate) fn run() {
+    let workspace_path = match std::env::current_dir() {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::error!("Failed to read the current directory: {e}");
+            return;
+        }
+    };
+
+    // The client end only collects what the server would have sent an
+    // editor, which is discarded; it has to stay open for the sends to work
+    let (connection, client) = lsp_server::Connection::memory();
+    let (introspection_request_sender, introspection_request_receiver) =
+        crossbeam_channel::unbounded();
+    let (introspection_result_sender, introspection_result_receiver) =
+        crossbeam_channel::unbounded();
+    let mut state = GlobalState::new(
+        connection.sender.clone(),
+        Box::new(ThreadPoolDispatcher::new(
+            threadpool::ThreadPool::with_name("salsa-worker".into(), crate::num_cpus()),
+        )),
+        introspection_request_sender,
+        introspection_result_receiver,
+    );
+    // Like the CLI, this runs in a directory the user chose, with nobody to
+    // answer a prompt
+    state.credentials.prompt = false;
+    state.workspace.init_workspace_folders.insert(
+        graphql_ide::path_to_file_uri(&workspace_path),
+        workspace_path,
+    );
+    crate::spawn_introspection_thread(introspection_request_receiver, introspection_result_sender);
+    crate::handle_initialized(&mut state);
+
+    loop {
+        crate::main_loop::tick(&connection, &mut state);
+        while client.receiver.try_recv().is_ok() {}
+        if !state.indexing.is_indexing() && state.pending_introspections == 0 {
+            break;
+        }
+        let mut select = crossbeam_channel::Select::new();
+        select.recv(&state.task_receiver);
+        select.recv(&state.introspection_result_receiver);
+        // Only waits for work to arrive; `tick` takes it off the channels
+        let _ = select.ready_timeout(Duration::from_millis(100));
+    }
+
+    let mut files: Vec<(String, Vec<Diagnostic>)> = state
+        .workspace
+        .all_hosts()
+        .flat_map(|(_, host)| host.snapshot().all_diagnostics())
+        .map(|(file, diagnostics)| (display_path(file.as_str()), diagnostics))
+        .collect();
+    files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut stdout = std::io::stdout().lock();
+    for (file, diagnostics) in &files {
+        for diagnostic in diagnostics {
+            let line = serde_json::to_string(&diagnostic_json(file, diagnostic))
+                .expect("diagnostics are serializable");
+            if writeln!(stdout, "{line}").is_err() {
+                // The reader went away, e.g. a pipe into `head`
+                return;
+            }
+        }
+    }
+    let _ = stdout.flush();
+}
+
+/// The filesystem path of a `file://` URI, or the URI itself
+fn display_path(uri: &str) -> String {
+    uri.parse::<lsp_types::Uri>()
+        .ok()
+        .and_then(|uri| crate::conversions::uri_to_file_path(&uri))
+        .map_or_else(
+            || uri.to_string(),
+            |path: PathBuf| path.display().to_string(),
+        )
+}
+
+/// One line of output
+#[derive(Debug, serde::Serialize)]
+struct JsonDiagnostic<'a> {
+    file: &'a str,
+    range: lsp_types::Range,
+    severity: &'static str,
+    code: Option<&'a str>,
+    source: &'a str,
+    message: &'a str,
+}
+
+fn diagnostic_json<'a>(file: &'a str, diagnostic: &'a Diagnostic) -> JsonDiagnostic<'a> {
+    JsonDiagnostic {
+        file,
+        range: crate::conversions::convert_ide_range(diagnostic.range),
+        severity: match diagnostic.severity {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information => "information",
+            DiagnosticSeverity::Hint => "hint",
+        },
+        code: diagnostic.code.as_deref(),
+        source: &diagnostic.source,
+        message: &diagnostic.message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graphql_ide::{Position, Range};
+
+    #[test]
+    fn test_diagnostic_json() {
+        let diagnostic = Diagnostic {
+            range: Range::new(Position::new(2, 4), Position::new(2, 9)),
+            severity: DiagnosticSeverity::Warning,
+            message: "Field `name` is deprecated".to_string(),
+            code: Some("noDeprecated".to_string()),
+            message_id: None,
+            source: "graphql-linter".to_string(),
+            fix: None,
+            suggestions: Vec::new(),
+            help: None,
+            url: None,
+            tags: Vec::new(),
+            related: Vec::new(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&diagnostic_json("/app/query.graphql", &diagnostic)).unwrap(),
+            r#"{"file":"/app/query.graphql","range":{"start":{"line":2,"character":4},"end":{"line":2,"character":9}},"severity":"warning","code":"noDeprecated","source":"graphql-linter","message":"Field `name` is deprecated"}"#
+        );
+    }
+}
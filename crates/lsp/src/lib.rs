@@ -5,11 +5,19 @@
 //! for Salsa query execution.
 
 mod conversions;
+mod credentials;
+mod diagnostics_scheduler;
 mod dispatch;
+mod features;
 mod global_state;
 mod handlers;
+mod indexing;
+#[cfg(feature = "native")]
+mod json_lines;
 mod loading;
 mod main_loop;
+mod request_stats;
+mod semantic_tokens;
 pub(crate) mod server;
 pub mod trace_capture;
 mod workspace;
@@ -26,13 +34,15 @@ pub fn load_wasm_workspace(_state: &mut GlobalState) {}
 use std::path::PathBuf;
 
 use lsp_types::{
-    CodeActionKind, CodeActionOptions, CompletionOptions, ExecuteCommandOptions,
-    FoldingRangeProviderCapability, HoverProviderCapability, InlayHintOptions,
-    InlayHintServerCapabilities, OneOf, RenameOptions, SelectionRangeProviderCapability,
-    SemanticTokenModifier, SemanticTokenType, SemanticTokensFullOptions, SemanticTokensLegend,
-    SemanticTokensOptions, SemanticTokensServerCapabilities, ServerCapabilities,
-    SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
-    WorkDoneProgressOptions,
+    CodeActionKind, CodeActionOptions, CompletionOptions, DocumentLinkOptions,
+    ExecuteCommandOptions, FileOperationFilter, FileOperationPattern, FileOperationPatternKind,
+    FileOperationRegistrationOptions, FoldingRangeProviderCapability, HoverProviderCapability,
+    InlayHintOptions, InlayHintServerCapabilities, OneOf, RenameOptions,
+    SelectionRangeProviderCapability, SemanticTokenModifier, SemanticTokenType,
+    SemanticTokensFullOptions, SemanticTokensLegend, SemanticTokensOptions,
+    SemanticTokensServerCapabilities, ServerCapabilities, SignatureHelpOptions,
+    TextDocumentSyncCapability, TextDocumentSyncKind, WorkDoneProgressOptions,
+    WorkspaceFileOperationsServerCapabilities, WorkspaceServerCapabilities,
 };
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::Layer;
@@ -210,7 +220,11 @@ pub fn build_server_capabilities() -> ServerCapabilities {
         workspace_symbol_provider: Some(OneOf::Left(true)),
         code_action_provider: Some(lsp_types::CodeActionProviderCapability::Options(
             CodeActionOptions {
-                code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                code_action_kinds: Some(vec![
+                    CodeActionKind::QUICKFIX,
+                    CodeActionKind::REFACTOR_INLINE,
+                    CodeActionKind::REFACTOR_REWRITE,
+                ]),
                 work_done_progress_options: WorkDoneProgressOptions::default(),
                 resolve_provider: None,
             },
@@ -233,8 +247,8 @@ pub fn build_server_capabilities() -> ServerCapabilities {
                         SemanticTokenModifier::DEFINITION,
                     ],
                 },
-                full: Some(SemanticTokensFullOptions::Bool(true)),
-                range: None,
+                full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                range: Some(true),
                 work_done_progress_options: WorkDoneProgressOptions::default(),
             },
         )),
@@ -242,6 +256,14 @@ pub fn build_server_capabilities() -> ServerCapabilities {
             resolve_provider: Some(true),
         }),
         folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".to_string(),
+            more_trigger_character: Some(vec!["{".to_string(), "\n".to_string()]),
+        }),
+        document_link_provider: Some(DocumentLinkOptions {
+            resolve_provider: Some(false),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        }),
         inlay_hint_provider: Some(OneOf::Right(InlayHintServerCapabilities::Options(
             InlayHintOptions {
                 resolve_provider: Some(false),
@@ -249,18 +271,73 @@ pub fn build_server_capabilities() -> ServerCapabilities {
             },
         ))),
         selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+        document_highlight_provider: Some(OneOf::Left(true)),
         rename_provider: Some(OneOf::Right(RenameOptions {
             prepare_provider: Some(true),
             work_done_progress_options: WorkDoneProgressOptions::default(),
         })),
         execute_command_provider: Some(ExecuteCommandOptions {
-            commands: vec!["graphql-analyzer.checkStatus".to_string()],
+            commands: vec![
+                "graphql-analyzer.checkStatus".to_string(),
+                "graphql-analyzer.downloadSchema".to_string(),
+                "graphql-analyzer.rulesManifest".to_string(),
+                "graphql-analyzer.operationGraph".to_string(),
+                "graphql-analyzer.operationWithFragments".to_string(),
+                #[cfg(feature = "codegen")]
+                "graphql-analyzer.generateOperationTypes".to_string(),
+            ],
             work_done_progress_options: WorkDoneProgressOptions::default(),
         }),
+        workspace: Some(WorkspaceServerCapabilities {
+            workspace_folders: None,
+            file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                will_rename: Some(FileOperationRegistrationOptions {
+                    filters: file_operation_filters(),
+                }),
+                did_delete: Some(FileOperationRegistrationOptions {
+                    filters: file_operation_filters(),
+                }),
+                ..Default::default()
+            }),
+        }),
         ..Default::default()
     }
 }
 
+/// Files that can hold GraphQL, and any folder that might contain them
+fn file_operation_filters() -> Vec<FileOperationFilter> {
+    vec![
+        file_operation_filter(
+            "**/*.{graphql,gql,gqls,ts,tsx,js,jsx,mjs,cjs,vue,svelte,astro,py,rb}",
+            FileOperationPatternKind::File,
+        ),
+        file_operation_filter("**/*", FileOperationPatternKind::Folder),
+    ]
+}
+
+/// A `file://` filter for the file operations the server wants to hear about
+fn file_operation_filter(glob: &str, matches: FileOperationPatternKind) -> FileOperationFilter {
+    FileOperationFilter {
+        scheme: Some("file".to_string()),
+        pattern: FileOperationPattern {
+            glob: glob.to_string(),
+            matches: Some(matches),
+            options: None,
+        },
+    }
+}
+
+/// Advertise capabilities that `lsp_types::ServerCapabilities` has no field
+/// for. Takes the serialized capabilities object.
+pub fn add_unmodeled_capabilities(capabilities: &mut serde_json::Value) {
+    capabilities["typeHierarchyProvider"] = serde_json::Value::Bool(true);
+    // Introspected schemas are registered under `schema://` URIs, which
+    // clients can't read from disk
+    capabilities["workspace"]["textDocumentContent"] = serde_json::json!({
+        "schemes": ["schema"],
+    });
+}
+
 #[cfg(feature = "native")]
 fn spawn_introspection_thread(
     request_receiver: crossbeam_channel::Receiver<global_state::IntrospectionRequest>,
@@ -356,17 +433,26 @@ fn handle_initialized(state: &mut GlobalState) {
         loading::load_workspace_config(state, uri, path);
     }
 
-    let elapsed = loading_start.elapsed();
-    let total_files = state.workspace.file_to_project.len();
+    // Documents are still being read in the background; indexing reports
+    // "ready" once the last project is registered
+    if state.indexing.is_indexing() {
+        state.send_notification::<StatusNotification>(StatusParams {
+            status: "loading".to_string(),
+            message: Some("Indexing documents...".to_string()),
+        });
+    } else {
+        let elapsed = loading_start.elapsed();
+        let total_files = state.workspace.file_to_project.len();
 
-    state.send_notification::<StatusNotification>(StatusParams {
-        status: "ready".to_string(),
-        message: Some(format!(
-            "{} files loaded in {:.1}s",
-            total_files,
-            elapsed.as_secs_f64()
-        )),
-    });
+        state.send_notification::<StatusNotification>(StatusParams {
+            status: "ready".to_string(),
+            message: Some(format!(
+                "{} files loaded in {:.1}s",
+                total_files,
+                elapsed.as_secs_f64()
+            )),
+        });
+    }
 
     register_file_watchers(state);
 }
@@ -377,7 +463,7 @@ fn register_file_watchers(state: &GlobalState) {
 
     let config_paths: Vec<PathBuf> = state.workspace.config_paths.values().cloned().collect();
 
-    if config_paths.is_empty() {
+    if config_paths.is_empty() && state.workspace.inferred_workspaces.is_empty() {
         tracing::debug!("No config paths found to watch");
         return;
     }
@@ -393,6 +479,29 @@ fn register_file_watchers(state: &GlobalState) {
         })
         .collect();
 
+    // A config created in a workspace running on an inferred project
+    // replaces it
+    if !state.workspace.inferred_workspaces.is_empty() {
+        watchers.extend(
+            graphql_config::CONFIG_FILES
+                .iter()
+                .map(|filename| FileSystemWatcher {
+                    glob_pattern: lsp_types::GlobPattern::String(format!("**/{filename}")),
+                    kind: Some(lsp_types::WatchKind::Create),
+                }),
+        );
+    }
+
+    if !state.workspace.settings_paths.is_empty() {
+        watchers.push(FileSystemWatcher {
+            glob_pattern: lsp_types::GlobPattern::String(format!(
+                "**/{}",
+                graphql_config::ANALYZER_SETTINGS_FILE
+            )),
+            kind: Some(lsp_types::WatchKind::all()),
+        });
+    }
+
     // Also watch resolved schema files
     for path in state.workspace.resolved_schema_paths.values() {
         if let Some(filename) = path.file_name().and_then(|f| f.to_str()) {
@@ -429,19 +538,50 @@ fn register_file_watchers(state: &GlobalState) {
         .expect("client channel open");
 }
 
+/// How [`run_server_with_output`] reports to its caller
+#[cfg(feature = "native")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputMode {
+    /// Serve the Language Server Protocol over stdio
+    #[default]
+    Lsp,
+    /// Analyze the project in the current directory once, write each
+    /// diagnostic to stdout as a line of JSON, and return
+    JsonLines,
+}
+
 /// Run the GraphQL language server over stdio.
 #[cfg(feature = "native")]
 pub fn run_server() {
+    run_server_with_output(OutputMode::Lsp);
+}
+
+/// Run the analyzer in the given output mode.
+///
+/// [`OutputMode::JsonLines`] suits editor-agnostic integrations and
+/// pre-commit hooks: it loads the workspace the same way the server does,
+/// but needs no client.
+#[cfg(feature = "native")]
+pub fn run_server_with_output(mode: OutputMode) {
+    match mode {
+        OutputMode::Lsp => run_lsp_server(),
+        OutputMode::JsonLines => {
+            let _reload_handle = init_tracing();
+            install_panic_hook();
+            json_lines::run();
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+fn run_lsp_server() {
     let reload_handle = init_tracing();
     install_panic_hook();
 
     let (connection, io_threads) = lsp_server::Connection::stdio();
 
-    let server_capabilities = build_server_capabilities();
-    let initialization_params = match connection
-        .initialize(serde_json::to_value(server_capabilities).expect("caps serialize"))
-    {
-        Ok(params) => params,
+    let (initialize_id, initialization_params) = match connection.initialize_start() {
+        Ok(request) => request,
         Err(e) => {
             // If the protocol-level error is a "request was cancelled" (code -32800),
             // the client disconnected during handshake — exit cleanly.
@@ -452,6 +592,31 @@ pub fn run_server() {
             panic!("initialize handshake failed: {e}");
         }
     };
+    let init_params: lsp_types::InitializeParams =
+        serde_json::from_value(initialization_params).expect("valid init params");
+
+    // Capabilities for features the editor can switch off are registered
+    // after initialization when the client supports it
+    let mut features = features::FeatureRegistrations {
+        editor: init_params
+            .initialization_options
+            .as_ref()
+            .and_then(features::EditorFeatures::from_settings)
+            .unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut capabilities = build_server_capabilities();
+    features.take_dynamic(&init_params.capabilities, &mut capabilities);
+    let mut server_capabilities = serde_json::to_value(capabilities).expect("caps serialize");
+    add_unmodeled_capabilities(&mut server_capabilities);
+    let initialize_result = serde_json::json!({ "capabilities": server_capabilities });
+    if let Err(e) = connection.initialize_finish(initialize_id, initialize_result) {
+        if e.channel_is_disconnected() {
+            tracing::info!("Client disconnected during initialization");
+            return;
+        }
+        panic!("initialize handshake failed: {e}");
+    }
 
     // Create introspection channels before GlobalState so we can pass them in
     let (introspection_request_sender, introspection_request_receiver) =
@@ -470,10 +635,7 @@ pub fn run_server() {
         introspection_result_receiver,
     );
     state.trace_capture = reload_handle.map(trace_capture::TraceCaptureManager::new);
-
-    let init_params: lsp_types::InitializeParams =
-        serde_json::from_value(initialization_params).expect("valid init params");
-
+    state.features = features;
     state.client_capabilities = Some(init_params.capabilities);
 
     if let Some(folders) = init_params.workspace_folders {
@@ -490,8 +652,10 @@ pub fn run_server() {
     spawn_introspection_thread(introspection_request_receiver, introspection_result_sender);
 
     handle_initialized(&mut state);
+    features::sync_registrations(&mut state);
 
     main_loop::run(&connection, &mut state);
+    state.workspace.save_structure_caches();
 
     // Drop the state before joining IO threads to close channels
     drop(state);
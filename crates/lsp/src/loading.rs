@@ -23,6 +23,7 @@ use crate::server::validation_errors_to_diagnostics;
 pub fn load_workspace_config(state: &mut GlobalState, workspace_uri: &str, workspace_path: &Path) {
     tracing::debug!(path = ?workspace_path, "Loading GraphQL config");
 
+    state.indexing.begin_workspace(workspace_uri);
     state
         .workspace
         .workspace_roots
@@ -96,7 +97,7 @@ pub fn load_workspace_config(state: &mut GlobalState, workspace_uri: &str, works
                         workspace_uri,
                         workspace_path,
                         &config,
-                        &config_path,
+                        Some(&config_path),
                     );
                 }
                 Err(e) => {
@@ -111,12 +112,43 @@ pub fn load_workspace_config(state: &mut GlobalState, workspace_uri: &str, works
             }
         }
         Ok(None) => {
+            let Some(inferred) = crate::workspace::infer_project(workspace_path) else {
+                state.send_notification::<lsp_types::notification::ShowMessage>(
+                    lsp_types::ShowMessageParams {
+                        typ: MessageType::WARNING,
+                        message: "No GraphQL config found. Schema validation and full IDE features require a config file.".to_owned(),
+                    },
+                );
+                return;
+            };
+
+            tracing::info!(
+                schema_files = inferred.schema_files,
+                document_files = inferred.document_files,
+                "No GraphQL config found, inferring a project"
+            );
             state.send_notification::<lsp_types::notification::ShowMessage>(
                 lsp_types::ShowMessageParams {
-                    typ: MessageType::WARNING,
-                    message: "No GraphQL config found. Schema validation and full IDE features require a config file.".to_owned(),
+                    typ: MessageType::INFO,
+                    message: format!(
+                        "No GraphQL config found. Inferred a project from {} schema file(s) \
+                        and {} document file(s) in the workspace's .graphql files. \
+                        Add a graphql.config file to choose the schema and documents yourself.",
+                        inferred.schema_files, inferred.document_files,
+                    ),
                 },
             );
+
+            state
+                .workspace
+                .inferred_workspaces
+                .insert(workspace_uri.to_string());
+            state
+                .workspace
+                .configs
+                .insert(workspace_uri.to_string(), inferred.config.clone());
+
+            load_all_project_files(state, workspace_uri, workspace_path, &inferred.config, None);
         }
         Err(e) => {
             tracing::error!("Error searching for config: {}", e);
@@ -125,19 +157,23 @@ pub fn load_workspace_config(state: &mut GlobalState, workspace_uri: &str, works
 }
 
 /// Load all project files from a config into their respective `AnalysisHost` instances.
+///
+/// `config_path` is `None` for an inferred project, which has no config
+/// file to report content mismatches on.
 #[cfg(feature = "native")]
 fn load_all_project_files(
     state: &mut GlobalState,
     workspace_uri: &str,
     workspace_path: &Path,
     config: &graphql_config::GraphQLConfig,
-    config_path: &Path,
+    config_path: Option<&Path>,
 ) {
     let start = std::time::Instant::now();
     let projects: Vec<_> = config.projects().collect();
     tracing::debug!("Loading files for {} project(s)", projects.len());
 
     let mut content_mismatch_errors: Vec<graphql_config::ConfigValidationError> = Vec::new();
+    let settings = load_analyzer_settings(state, workspace_uri, workspace_path);
 
     for (project_name, project_config) in projects {
         let project_start = std::time::Instant::now();
@@ -163,54 +199,75 @@ fn load_all_project_files(
             "Resolved extract config",
         );
 
-        let lint_config =
-            project_config
-                .lint()
-                .map_or_else(graphql_linter::LintConfig::default, |lint_value| {
-                    match serde_json::from_value::<graphql_linter::LintConfig>(lint_value) {
-                        Ok(cfg) => cfg,
-                        Err(e) => {
-                            tracing::warn!(
-                                "Failed to parse lint config for project '{}': {}. Using default.",
-                                project_name,
-                                e
-                            );
-                            graphql_linter::LintConfig::default()
-                        }
-                    }
-                });
-
         let host = state
             .workspace
             .get_or_create_host(workspace_uri, project_name);
 
         host.set_extract_config(extract_config.clone());
-        host.set_lint_config(lint_config);
-
-        // Load local schemas AND documents in a single pass
-        let (schema_result, loaded_files, _doc_result) = {
-            let schema_result = match host.load_schemas_from_config(project_config, workspace_path)
-            {
-                Ok(result) => {
-                    tracing::debug!(
-                        "Loaded {} local schema file(s), {} remote schema(s) pending",
-                        result.loaded_count,
-                        result.pending_introspections.len()
+        let plugin_error = apply_project_settings(
+            host,
+            project_name,
+            project_config,
+            &settings,
+            workspace_path,
+            config_path.and_then(Path::parent).unwrap_or(workspace_path),
+        );
+        host.set_operation_usage(project_config.operation_usage().and_then(|report_path| {
+            let report_path = workspace_path.join(report_path);
+            graphql_config::OperationUsageReport::load(&report_path)
+                .map_err(|e| {
+                    tracing::warn!(
+                        "Failed to load operation usage report '{}' for project '{}': {}",
+                        report_path.display(),
+                        project_name,
+                        e
                     );
-                    result
-                }
-                Err(e) => {
-                    tracing::error!("Failed to load schemas: {}", e);
-                    graphql_ide::SchemaLoadResult::default()
-                }
-            };
-
-            let (docs, doc_result) =
-                host.load_documents_from_config(project_config, workspace_path, &extract_config);
-
-            (schema_result, docs, doc_result)
+                })
+                .ok()
+        }));
+
+        host.set_persisted_operations(project_config.persisted_operations().and_then(
+            |list_path| {
+                let list_path = workspace_path.join(list_path);
+                graphql_config::PersistedOperationAllowList::load(&list_path)
+                    .map_err(|e| {
+                        tracing::warn!(
+                        "Failed to load persisted operation allow-list '{}' for project '{}': {}",
+                        list_path.display(),
+                        project_name,
+                        e
+                    );
+                    })
+                    .ok()
+            },
+        ));
+
+        let schema_result = match host.load_schemas_from_config(project_config, workspace_path) {
+            Ok(result) => {
+                tracing::debug!(
+                    "Loaded {} local schema file(s), {} remote schema(s) pending",
+                    result.loaded_count,
+                    result.pending_introspections.len()
+                );
+                result
+            }
+            Err(e) => {
+                tracing::error!("Failed to load schemas: {}", e);
+                graphql_ide::SchemaLoadResult::default()
+            }
         };
 
+        // Documents are read in the background so requests aren't held up
+        // on large repos
+        crate::indexing::spawn_document_indexing(
+            state,
+            workspace_uri,
+            project_name,
+            project_config.clone(),
+            workspace_path.to_path_buf(),
+            extract_config,
+        );
+
         // Track resolved schema path for file watching
         if let Some(resolved_path) = project_config.resolved_schema() {
             let resolved_full = workspace_path.join(&resolved_path);
@@ -220,6 +277,10 @@ fn load_all_project_files(
             );
         }
 
+        if let Some(error) = plugin_error {
+            report_plugin_error(state, project_name, &error);
+        }
+
         let no_user_schema = schema_result.has_no_user_schema();
         let schema_errors = schema_result.content_errors.clone();
 
@@ -257,70 +318,20 @@ fn load_all_project_files(
 
         // Send introspection requests to the async thread
         for pending in &schema_result.pending_introspections {
-            let _ = state
-                .introspection_request_sender
-                .send(IntrospectionRequest {
+            crate::credentials::request_introspection(
+                state,
+                IntrospectionRequest {
                     workspace_uri: workspace_uri.to_string(),
                     project_name: project_name.to_string(),
                     pending: pending.clone(),
-                });
-        }
-
-        if !loaded_files.is_empty() {
-            let total_files_loaded = loaded_files.len();
-            tracing::debug!(
-                "Collected {} document files for project '{}'",
-                total_files_loaded,
-                project_name
+                },
             );
-
-            for loaded_file in &loaded_files {
-                state.workspace.file_to_project.insert(
-                    loaded_file.path.as_str().to_string(),
-                    (workspace_uri.to_string(), project_name.to_string()),
-                );
-            }
-
-            // The earlier &mut borrow ended above, so we can re-borrow the host
-            // immutably to take a snapshot for diagnostics.
-            let host = state
-                .workspace
-                .get_host(workspace_uri, project_name)
-                .expect("host was just created");
-            let snapshot = host.snapshot();
-
-            let loaded_file_paths: Vec<graphql_ide::FilePath> =
-                loaded_files.iter().map(|f| f.path.clone()).collect();
-
-            let all_diagnostics_map = snapshot.all_diagnostics_for_files(&loaded_file_paths);
-
-            for (file_path, diagnostics) in &all_diagnostics_map {
-                let Ok(file_uri) = Uri::from_str(file_path.as_str()) else {
-                    continue;
-                };
-                let lsp_diagnostics: Vec<Diagnostic> = diagnostics
-                    .iter()
-                    .cloned()
-                    .map(convert_ide_diagnostic)
-                    .collect();
-                state.publish_diagnostics(file_uri, lsp_diagnostics, None);
-            }
-
-            // Clear stale diagnostics for files with no issues
-            for loaded_file in &loaded_files {
-                if !all_diagnostics_map.contains_key(&loaded_file.path) {
-                    if let Ok(file_uri) = Uri::from_str(loaded_file.path.as_str()) {
-                        state.publish_diagnostics(file_uri, vec![], None);
-                    }
-                }
-            }
         }
 
         let project_msg = format!(
-            "Project '{}' loaded: {} schema file(s), {} document file(s) in {:.1}s",
+            "Project '{}' loaded: {} schema file(s) in {:.1}s, indexing documents",
             project_name,
             schema_result.loaded_count,
-            loaded_files.len(),
             project_start.elapsed().as_secs_f64()
         );
         tracing::info!("{}", project_msg);
@@ -333,7 +344,7 @@ fn load_all_project_files(
     }
 
     // Publish config file diagnostics (content mismatches)
-    if !content_mismatch_errors.is_empty() {
+    if let Some(config_path) = config_path.filter(|_| !content_mismatch_errors.is_empty()) {
         let config_uri =
             Uri::from_str(&graphql_ide::path_to_file_uri(config_path)).expect("valid config path");
         let config_content = std::fs::read_to_string(config_path).unwrap_or_default();
@@ -348,18 +359,10 @@ fn load_all_project_files(
         state.publish_diagnostics(config_uri, diagnostics, None);
     }
 
-    let elapsed = start.elapsed();
-    let total_files = state.workspace.file_to_project.len();
-    let init_message = format!(
-        "Project initialization complete: {} files loaded in {:.1}s",
-        total_files,
-        elapsed.as_secs_f64()
+    tracing::info!(
+        "Configuration loaded in {:.1}s",
+        start.elapsed().as_secs_f64()
     );
-    tracing::info!("{}", init_message);
-    state.send_notification::<lsp_types::notification::LogMessage>(lsp_types::LogMessageParams {
-        typ: MessageType::INFO,
-        message: init_message,
-    });
 }
 
 /// Reload configuration for a workspace.
@@ -378,6 +381,9 @@ pub fn reload_workspace_config(state: &mut GlobalState, workspace_uri: &str) {
     state.workspace.clear_workspace(workspace_uri);
     state.workspace.configs.remove(workspace_uri);
     load_workspace_config(state, workspace_uri, &workspace_path);
+    // Closes the progress of indexing the reload abandoned, if nothing
+    // replaced it
+    crate::indexing::finish_if_idle(state);
 
     if state.workspace.configs.contains_key(workspace_uri) {
         state.send_notification::<lsp_types::notification::ShowMessage>(
@@ -425,11 +431,207 @@ pub fn reload_resolved_schema(
         graphql_ide::DocumentKind::Schema,
     );
 
-    // Republish diagnostics for all files
-    let host = state
+    republish_project_diagnostics(state, workspace_uri, project_name);
+}
+
+/// Re-read `.graphqlanalyzer.toml` and push it into every project's host,
+/// without reloading any files.
+#[cfg(feature = "native")]
+pub fn reload_analyzer_settings(state: &mut GlobalState, workspace_uri: &str) {
+    let Some(workspace_path) = state.workspace.workspace_roots.get(workspace_uri).cloned() else {
+        return;
+    };
+    let Some(config) = state.workspace.configs.get(workspace_uri).cloned() else {
+        return;
+    };
+    tracing::info!(
+        "Reloading analyzer settings for workspace: {}",
+        workspace_uri
+    );
+
+    let config_dir = state
         .workspace
-        .get_host(workspace_uri, project_name)
-        .expect("host exists");
+        .config_paths
+        .get(workspace_uri)
+        .and_then(|path| path.parent())
+        .map_or_else(|| workspace_path.clone(), Path::to_path_buf);
+
+    let settings = load_analyzer_settings(state, workspace_uri, &workspace_path);
+    for (project_name, project_config) in config.projects() {
+        let Some(host) = state.workspace.get_host_mut(workspace_uri, project_name) else {
+            continue;
+        };
+        let plugin_error = apply_project_settings(
+            host,
+            project_name,
+            project_config,
+            &settings,
+            &workspace_path,
+            &config_dir,
+        );
+        if let Some(error) = plugin_error {
+            report_plugin_error(state, project_name, &error);
+        }
+        republish_project_diagnostics(state, workspace_uri, project_name);
+    }
+}
+
+/// Read `.graphqlanalyzer.toml` from the workspace root, falling back to the
+/// defaults when it's missing or doesn't parse.
+#[cfg(feature = "native")]
+fn load_analyzer_settings(
+    state: &mut GlobalState,
+    workspace_uri: &str,
+    workspace_path: &Path,
+) -> graphql_config::AnalyzerSettings {
+    let path = graphql_config::analyzer_settings_path(workspace_path);
+    let mut settings = match graphql_config::load_analyzer_settings(&path) {
+        Ok(settings) => settings.unwrap_or_default(),
+        Err(e) => {
+            tracing::warn!("Failed to load analyzer settings: {}", e);
+            state.send_notification::<lsp_types::notification::ShowMessage>(
+                lsp_types::ShowMessageParams {
+                    typ: MessageType::WARNING,
+                    message: format!(
+                        "Failed to load {}: {e}. Using default settings.",
+                        graphql_config::ANALYZER_SETTINGS_FILE
+                    ),
+                },
+            );
+            graphql_config::AnalyzerSettings::default()
+        }
+    };
+    state
+        .workspace
+        .settings_paths
+        .insert(workspace_uri.to_string(), path);
+    state.features.editor.apply(&mut settings.features);
+    settings
+}
+
+/// Set a project's lint, complexity, validation and scalar config and the
+/// analyzer settings on its host. Values from `.graphqlanalyzer.toml` take precedence
+/// over the project's `extensions.graphql-analyzer` block.
+///
+/// Lint plugin paths are relative to the file declaring them: the settings
+/// file at the workspace root, or the config file in `config_dir`. Returns
+/// why the plugins failed to load, if they did; the project is linted
+/// without them.
+#[cfg(feature = "native")]
+fn apply_project_settings(
+    host: &mut graphql_ide::AnalysisHost,
+    project_name: &str,
+    project_config: &graphql_config::ProjectConfig,
+    settings: &graphql_config::AnalyzerSettings,
+    workspace_path: &Path,
+    config_dir: &Path,
+) -> Option<String> {
+    let lint_config = settings
+        .lint
+        .clone()
+        .or_else(|| project_config.lint())
+        .map_or_else(
+            graphql_linter::LintConfig::default,
+            |lint_value| match serde_json::from_value::<graphql_linter::LintConfig>(lint_value) {
+                Ok(cfg) => cfg,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to parse lint config for project '{}': {}. Using default.",
+                        project_name,
+                        e
+                    );
+                    graphql_linter::LintConfig::default()
+                }
+            },
+        );
+
+    let plugin_dir = if settings.lint.is_some() {
+        workspace_path
+    } else {
+        config_dir
+    };
+    let (lint_plugins, plugin_error) =
+        match graphql_linter::LintPlugins::load(&lint_config, plugin_dir) {
+            Ok(plugins) => (plugins, None),
+            Err(e) => (graphql_linter::LintPlugins::default(), Some(e)),
+        };
+
+    host.set_lint_config(lint_config);
+    host.set_lint_plugins(lint_plugins);
+    host.set_complexity_config(
+        settings
+            .complexity
+            .clone()
+            .or_else(|| project_config.complexity())
+            .unwrap_or_default(),
+    );
+    host.set_validation_config(
+        settings
+            .validation
+            .clone()
+            .or_else(|| project_config.validation())
+            .unwrap_or_default(),
+    );
+    host.set_n_plus_one_config(
+        settings
+            .n_plus_one
+            .clone()
+            .or_else(|| project_config.n_plus_one()),
+    );
+    host.set_scalar_config(project_config.scalars());
+    host.set_client_controlled_nullability(project_config.client_controlled_nullability());
+    host.set_analyzer_settings(settings.clone());
+    if settings.cache.persist {
+        host.enable_structure_cache(structure_cache_path(
+            &settings.cache.resolve_directory(workspace_path),
+            project_name,
+        ));
+    } else {
+        host.disable_structure_cache();
+    }
+    plugin_error
+}
+
+/// Tell the user a project's lint plugins didn't load, since its plugin
+/// rules won't report anything until the config is fixed
+#[cfg(feature = "native")]
+fn report_plugin_error(state: &GlobalState, project_name: &str, error: &str) {
+    tracing::warn!(
+        "Failed to load lint plugins for project '{}': {}",
+        project_name,
+        error
+    );
+    state.send_notification::<lsp_types::notification::ShowMessage>(lsp_types::ShowMessageParams {
+        typ: MessageType::ERROR,
+        message: format!(
+            "GraphQL: Failed to load lint plugins for project '{project_name}': {error}"
+        ),
+    });
+}
+
+/// Each project keeps its own cache file, named after the project
+#[cfg(feature = "native")]
+fn structure_cache_path(cache_dir: &Path, project_name: &str) -> std::path::PathBuf {
+    let file_name: String = project_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    cache_dir.join(format!("{file_name}.structures.json"))
+}
+
+/// Publish fresh diagnostics for every file of a project, clearing files
+/// that no longer have any.
+#[cfg(feature = "native")]
+fn republish_project_diagnostics(state: &mut GlobalState, workspace_uri: &str, project_name: &str) {
+    let Some(host) = state.workspace.get_host(workspace_uri, project_name) else {
+        return;
+    };
     let snapshot = host.snapshot();
 
     let diag_map = snapshot.all_diagnostics();
@@ -444,6 +646,23 @@ pub fn reload_resolved_schema(
             .collect();
         state.publish_diagnostics(file_uri, lsp_diagnostics, None);
     }
+
+    let clean_files: Vec<String> = state
+        .workspace
+        .file_to_project
+        .iter()
+        .filter(|(uri, (ws, project))| {
+            ws == workspace_uri
+                && project == project_name
+                && !diag_map.contains_key(&graphql_ide::FilePath::new(uri.as_str()))
+        })
+        .map(|(uri, _)| uri.clone())
+        .collect();
+    for uri in clean_files {
+        if let Ok(file_uri) = Uri::from_str(&uri) {
+            state.publish_diagnostics(file_uri, vec![], None);
+        }
+    }
 }
 
 /// Install a workspace from LSP `initializationOptions` JSON, bypassing the on-disk
@@ -476,7 +695,7 @@ pub fn install_workspace_from_init_options(
 #[cfg(all(test, feature = "native"))]
 mod tests {
     use super::*;
-    use crate::global_state::{GlobalState, InlineDispatcher};
+    use crate::global_state::{GlobalState, InlineDispatcher, TaskResponse};
     use crossbeam_channel::{unbounded, Receiver};
     use lsp_server::Message;
     use std::io::Write;
@@ -502,6 +721,16 @@ mod tests {
         (state, msg_receiver, intro_req_receiver)
     }
 
+    /// Register the documents indexed in the background, as the main loop
+    /// would. The inline dispatcher has already run the discovery.
+    fn apply_background_tasks(state: &mut GlobalState) {
+        while let Ok(task) = state.task_receiver.try_recv() {
+            if let TaskResponse::DocumentsIndexed(indexed) = task.response {
+                crate::indexing::apply_indexed_documents(state, indexed);
+            }
+        }
+    }
+
     /// Regression test for issue #1035 in the LSP loading path.
     ///
     /// A fragment defined in a `.ts` file via a bare `gql` tag (no import,
@@ -555,6 +784,7 @@ mod tests {
         let (mut state, _msg_receiver, _intro_req_receiver) = make_state();
         let workspace_uri = format!("file://{}", workspace_path.display());
         load_workspace_config(&mut state, &workspace_uri, workspace_path);
+        apply_background_tasks(&mut state);
 
         // Project name defaults to "default" for single-project configs.
         let host = state
@@ -574,4 +804,162 @@ mod tests {
             "expected no validation errors but got: {errors:#?}",
         );
     }
+
+    #[test]
+    fn indexing_keeps_open_document_contents() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path();
+        std::fs::write(
+            workspace_path.join("schema.graphql"),
+            "type Query { hello: String, world: String }",
+        )
+        .unwrap();
+        std::fs::write(workspace_path.join("query.graphql"), "query { hello }").unwrap();
+        std::fs::write(
+            workspace_path.join(".graphqlrc.yaml"),
+            "schema: schema.graphql\ndocuments: '*.graphql'\n",
+        )
+        .unwrap();
+
+        let (mut state, _msg_receiver, _intro_req_receiver) = make_state();
+        let workspace_uri = format!("file://{}", workspace_path.display());
+        load_workspace_config(&mut state, &workspace_uri, workspace_path);
+        assert!(state.indexing.is_indexing());
+
+        // The document is opened and edited before its project is indexed
+        let query_uri = graphql_ide::path_to_file_uri(&workspace_path.join("query.graphql"));
+        state
+            .workspace
+            .document_contents
+            .insert(query_uri.clone(), "query { world }".to_string());
+        state
+            .workspace
+            .get_host_mut(&workspace_uri, "default")
+            .unwrap()
+            .add_file(
+                &graphql_ide::FilePath::new(query_uri.clone()),
+                "query { world }",
+                graphql_ide::Language::GraphQL,
+                graphql_ide::DocumentKind::Executable,
+            );
+
+        apply_background_tasks(&mut state);
+        assert!(!state.indexing.is_indexing());
+        assert!(state.workspace.file_to_project.contains_key(&query_uri));
+
+        let snapshot = state
+            .workspace
+            .get_host(&workspace_uri, "default")
+            .unwrap()
+            .snapshot();
+        let content = snapshot
+            .file_content(&graphql_ide::FilePath::new(query_uri))
+            .unwrap();
+        assert_eq!(content.as_ref(), "query { world }");
+    }
+
+    #[test]
+    fn workspace_without_config_infers_a_project() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path();
+        std::fs::write(
+            workspace_path.join("schema.graphql"),
+            "type Query { hello: String }",
+        )
+        .unwrap();
+        std::fs::create_dir(workspace_path.join("operations")).unwrap();
+        std::fs::write(
+            workspace_path.join("operations/query.graphql"),
+            "query Hello { hello }",
+        )
+        .unwrap();
+        std::fs::create_dir(workspace_path.join("node_modules")).unwrap();
+        std::fs::write(
+            workspace_path.join("node_modules/vendored.graphql"),
+            "type Vendored { id: ID }",
+        )
+        .unwrap();
+
+        let (mut state, _msg_receiver, _intro_req_receiver) = make_state();
+        let workspace_uri = format!("file://{}", workspace_path.display());
+        load_workspace_config(&mut state, &workspace_uri, workspace_path);
+        apply_background_tasks(&mut state);
+
+        assert!(state.workspace.inferred_workspaces.contains(&workspace_uri));
+        let project = state.workspace.configs[&workspace_uri]
+            .get_project("default")
+            .unwrap();
+        assert_eq!(project.schema.paths(), vec!["schema.graphql"]);
+        assert_eq!(
+            project.documents.as_ref().unwrap().patterns(),
+            vec!["operations/query.graphql"]
+        );
+
+        let query_uri =
+            graphql_ide::path_to_file_uri(&workspace_path.join("operations/query.graphql"));
+        assert!(state.workspace.file_to_project.contains_key(&query_uri));
+        let diagnostics = state
+            .workspace
+            .get_host(&workspace_uri, "default")
+            .unwrap()
+            .snapshot()
+            .diagnostics(&graphql_ide::FilePath::new(query_uri));
+        assert!(diagnostics.is_empty(), "{diagnostics:#?}");
+
+        // A config appearing later replaces the inferred project
+        std::fs::write(
+            workspace_path.join(".graphqlrc.yaml"),
+            "schema: schema.graphql\ndocuments: 'operations/*.graphql'\n",
+        )
+        .unwrap();
+        reload_workspace_config(&mut state, &workspace_uri);
+        assert!(!state.workspace.inferred_workspaces.contains(&workspace_uri));
+        assert!(state.workspace.config_paths.contains_key(&workspace_uri));
+    }
+
+    #[test]
+    fn lint_plugin_load_failure_is_shown_to_the_user() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let workspace_path = temp_dir.path();
+        std::fs::write(
+            workspace_path.join("schema.graphql"),
+            "type Query { a: Int }",
+        )
+        .unwrap();
+        std::fs::write(
+            workspace_path.join(".graphqlrc.yaml"),
+            "schema: schema.graphql\n\
+             extensions:\n  \
+               graphql-analyzer:\n    \
+                 lint:\n      \
+                   plugins:\n        \
+                     requireOwner: ./plugins/missing.wasm\n",
+        )
+        .unwrap();
+
+        let (mut state, msg_receiver, _intro_req_receiver) = make_state();
+        let workspace_uri = format!("file://{}", workspace_path.display());
+        load_workspace_config(&mut state, &workspace_uri, workspace_path);
+
+        let shown: Vec<String> = msg_receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                Message::Notification(notification)
+                    if notification.method == "window/showMessage" =>
+                {
+                    serde_json::from_value::<lsp_types::ShowMessageParams>(notification.params)
+                        .ok()
+                        .map(|params| params.message)
+                }
+                _ => None,
+            })
+            .collect();
+        assert!(
+            shown
+                .iter()
+                .any(|message| message.contains("Failed to load lint plugins")
+                    && message.contains("requireOwner")),
+            "{shown:#?}"
+        );
+    }
 }
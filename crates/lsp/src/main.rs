@@ -18,8 +18,14 @@ fn main() {
         return;
     }
 
+    // `--json-lines` analyzes the current directory once instead of serving
+    // LSP, for scripts and hooks
     #[cfg(feature = "native")]
-    graphql_lsp::run_server();
+    graphql_lsp::run_server_with_output(if args.iter().any(|a| a == "--json-lines") {
+        graphql_lsp::OutputMode::JsonLines
+    } else {
+        graphql_lsp::OutputMode::Lsp
+    });
 
     // Without the native feature there is no stdio entrypoint. The binary
     // target exists for completeness but is not intended to be run directly
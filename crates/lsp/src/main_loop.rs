@@ -5,7 +5,14 @@ use lsp_server::{Connection, Message, Notification, Request};
 use crate::dispatch::{NotificationDispatcher, RequestDispatcher};
 use crate::global_state::{GlobalState, TaskResponse};
 use crate::handlers;
-use crate::server::{PingRequest, VirtualFileContentRequest};
+use crate::request_stats::PerfStatsRequest;
+use crate::server::{
+    ClearCacheRequest, ComplexityRequest, ContextAtPositionRequest, FieldCoverageRequest,
+    FixAllRequest, FragmentGraphRequest, GenerateDocsRequest, ImpactOfChangeRequest,
+    MemoryMetricsRequest, MigrateFieldRequest, OrganizeFragmentsRequest, OrganizeSchemaRequest,
+    PingRequest, PreviewFixRequest, ProjectReportRequest, SchemaStatsRequest,
+    SelectionSkeletonRequest, TextDocumentContentRequest, VirtualFileContentRequest,
+};
 use crate::trace_capture::TraceCaptureRequest;
 
 pub enum ControlFlow {
@@ -27,13 +34,20 @@ pub fn tick(connection: &Connection, state: &mut GlobalState) -> ControlFlow {
                 handle_request(state, req);
             }
             Ok(Message::Notification(not)) => handle_notification(state, not),
-            Ok(Message::Response(resp)) => tracing::debug!(id = ?resp.id, "client response"),
+            Ok(Message::Response(resp)) => {
+                if !crate::credentials::handle_response(state, &resp) {
+                    tracing::debug!(id = ?resp.id, "client response");
+                }
+            }
             Err(TryRecvError::Empty) => break,
             Err(TryRecvError::Disconnected) => return ControlFlow::Shutdown,
         }
     }
 
     while let Ok(task) = state.task_receiver.try_recv() {
+        if let Some(timing) = task.timing {
+            state.record_request(timing);
+        }
         handle_task(state, task.response);
     }
 
@@ -41,6 +55,9 @@ pub fn tick(connection: &Connection, state: &mut GlobalState) -> ControlFlow {
         handle_introspection_result(state, result);
     }
 
+    #[cfg(feature = "native")]
+    state.flush_scheduled_diagnostics(std::time::Instant::now());
+
     ControlFlow::Continue
 }
 
@@ -49,10 +66,16 @@ pub fn tick(connection: &Connection, state: &mut GlobalState) -> ControlFlow {
 #[cfg(feature = "native")]
 pub fn run(connection: &Connection, state: &mut GlobalState) {
     loop {
+        // Wakes the loop when the next debounced diagnostics run is due
+        let diagnostics_timer = state
+            .diagnostics_scheduler
+            .next_deadline()
+            .map_or_else(crossbeam_channel::never, crossbeam_channel::at);
         select! {
             recv(connection.receiver) -> _ => {}
             recv(state.task_receiver) -> _ => {}
             recv(state.introspection_result_receiver) -> _ => {}
+            recv(diagnostics_timer) -> _ => {}
         }
         if matches!(tick(connection, state), ControlFlow::Shutdown) {
             return;
@@ -62,10 +85,13 @@ pub fn run(connection: &Connection, state: &mut GlobalState) {
 
 fn handle_request(state: &mut GlobalState, req: Request) {
     use lsp_types::request::{
-        CodeActionRequest, CodeLensRequest, CodeLensResolve, Completion, DocumentSymbolRequest,
-        ExecuteCommand, FoldingRangeRequest, GotoDefinition, HoverRequest, InlayHintRequest,
-        PrepareRenameRequest, References, Rename, SelectionRangeRequest, SemanticTokensFullRequest,
-        SignatureHelpRequest, WorkspaceSymbolRequest,
+        CodeActionRequest, CodeLensRequest, CodeLensResolve, Completion, DocumentHighlightRequest,
+        DocumentLinkRequest, DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest,
+        GotoDefinition, HoverRequest, InlayHintRequest, OnTypeFormatting, PrepareRenameRequest,
+        References, Rename, SelectionRangeRequest, SemanticTokensFullDeltaRequest,
+        SemanticTokensFullRequest, SemanticTokensRangeRequest, SignatureHelpRequest,
+        TypeHierarchyPrepare, TypeHierarchySubtypes, TypeHierarchySupertypes, WillRenameFiles,
+        WorkspaceSymbolRequest,
     };
 
     state.in_flight.insert(req.id.clone());
@@ -87,14 +113,36 @@ fn handle_request(state: &mut GlobalState, req: Request) {
             |p| p.text_document_position.text_document.uri.clone(),
             handlers::navigation::handle_references,
         )
+        .on_pool::<DocumentHighlightRequest, _, _>(
+            |p| p.text_document_position_params.text_document.uri.clone(),
+            handlers::navigation::handle_document_highlight,
+        )
+        .on_pool::<TypeHierarchyPrepare, _, _>(
+            |p| p.text_document_position_params.text_document.uri.clone(),
+            handlers::navigation::handle_prepare_type_hierarchy,
+        )
+        .on_pool::<TypeHierarchySupertypes, _, _>(
+            |p| p.item.uri.clone(),
+            handlers::navigation::handle_type_hierarchy_supertypes,
+        )
+        .on_pool::<TypeHierarchySubtypes, _, _>(
+            |p| p.item.uri.clone(),
+            handlers::navigation::handle_type_hierarchy_subtypes,
+        )
         .on_pool::<DocumentSymbolRequest, _, _>(
             |p| p.text_document.uri.clone(),
             handlers::navigation::handle_document_symbol,
         )
-        .on_pool::<SemanticTokensFullRequest, _, _>(
-            |p| p.text_document.uri.clone(),
+        .on_main_deferred::<SemanticTokensFullRequest, _>(
             handlers::display::handle_semantic_tokens_full,
         )
+        .on_main_deferred::<SemanticTokensFullDeltaRequest, _>(
+            handlers::display::handle_semantic_tokens_full_delta,
+        )
+        .on_pool::<SemanticTokensRangeRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::display::handle_semantic_tokens_range,
+        )
         .on_pool::<SelectionRangeRequest, _, _>(
             |p| p.text_document.uri.clone(),
             handlers::display::handle_selection_range,
@@ -111,14 +159,82 @@ fn handle_request(state: &mut GlobalState, req: Request) {
             |p| p.text_document.uri.clone(),
             handlers::display::handle_folding_range,
         )
+        .on_pool::<DocumentLinkRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::display::handle_document_link,
+        )
         .on_pool::<InlayHintRequest, _, _>(
             |p| p.text_document.uri.clone(),
             handlers::display::handle_inlay_hint,
         )
+        .on_pool::<OnTypeFormatting, _, _>(
+            |p| p.text_document_position.text_document.uri.clone(),
+            handlers::editing::handle_on_type_formatting,
+        )
         .on_pool::<SignatureHelpRequest, _, _>(
             |p| p.text_document_position_params.text_document.uri.clone(),
             handlers::editing::handle_signature_help,
         )
+        .on_pool::<SelectionSkeletonRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_selection_skeleton,
+        )
+        .on_pool::<ContextAtPositionRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_context_at_position,
+        )
+        .on_pool::<FieldCoverageRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_field_coverage,
+        )
+        .on_pool::<GenerateDocsRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_generate_docs,
+        )
+        .on_pool::<ComplexityRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_complexity,
+        )
+        .on_pool::<SchemaStatsRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_schema_stats,
+        )
+        .on_pool::<MemoryMetricsRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_memory_metrics,
+        )
+        .on_pool::<FragmentGraphRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_fragment_graph,
+        )
+        .on_pool::<ImpactOfChangeRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_impact_of_change,
+        )
+        .on_pool::<ProjectReportRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::custom::handle_project_report,
+        )
+        .on_pool::<OrganizeFragmentsRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_organize_fragments,
+        )
+        .on_pool::<OrganizeSchemaRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_organize_schema,
+        )
+        .on_pool::<PreviewFixRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_preview_fix,
+        )
+        .on_pool::<FixAllRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_fix_all,
+        )
+        .on_pool::<MigrateFieldRequest, _, _>(
+            |p| p.text_document.uri.clone(),
+            handlers::editing::handle_migrate_field,
+        )
         .on_pool::<Rename, _, _>(
             |p| p.text_document_position.text_document.uri.clone(),
             handlers::editing::handle_rename,
@@ -128,18 +244,22 @@ fn handle_request(state: &mut GlobalState, req: Request) {
             handlers::editing::handle_prepare_rename,
         )
         .on_main::<ExecuteCommand, _>(handlers::editing::handle_execute_command)
+        .on_main::<WillRenameFiles, _>(handlers::document_sync::handle_will_rename_files)
         .on_main::<WorkspaceSymbolRequest, _>(handlers::navigation::handle_workspace_symbol)
         .on_main::<VirtualFileContentRequest, _>(handlers::custom::handle_virtual_file_content)
+        .on_main::<TextDocumentContentRequest, _>(handlers::custom::handle_text_document_content)
         .on_main::<PingRequest, _>(handlers::custom::handle_ping)
+        .on_main::<ClearCacheRequest, _>(handlers::custom::handle_clear_cache)
         .on_main::<TraceCaptureRequest, _>(handlers::custom::handle_trace_capture)
+        .on_main::<PerfStatsRequest, _>(handlers::custom::handle_perf_stats)
         .on_main::<CodeLensResolve, _>(|_state, lens| lens)
         .finish();
 }
 
 fn handle_notification(state: &mut GlobalState, not: Notification) {
     use lsp_types::notification::{
-        DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidOpenTextDocument,
-        DidSaveTextDocument,
+        DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
+        DidDeleteFiles, DidOpenTextDocument, DidSaveTextDocument,
     };
 
     if not.method == "$/cancelRequest" {
@@ -148,6 +268,11 @@ fn handle_notification(state: &mut GlobalState, not: Notification) {
                 lsp_types::NumberOrString::Number(n) => lsp_server::RequestId::from(n),
                 lsp_types::NumberOrString::String(s) => lsp_server::RequestId::from(s),
             };
+            // Stop the worker at its next query call; whatever it returns
+            // afterwards is dropped below because the id leaves `in_flight`.
+            if let Some(token) = state.cancellation_tokens.remove(&id) {
+                token.cancel();
+            }
             // Only respond if the request is still pending; a response was
             // not yet sent (or the worker beat the cancel notification).
             if state.in_flight.contains(&id) {
@@ -167,7 +292,9 @@ fn handle_notification(state: &mut GlobalState, not: Notification) {
         .on::<DidChangeTextDocument>(handlers::document_sync::handle_did_change)
         .on::<DidSaveTextDocument>(handlers::document_sync::handle_did_save)
         .on::<DidCloseTextDocument>(handlers::document_sync::handle_did_close)
+        .on::<DidDeleteFiles>(handlers::document_sync::handle_did_delete_files)
         .on::<DidChangeWatchedFiles>(handlers::document_sync::handle_did_change_watched_files)
+        .on::<DidChangeConfiguration>(handlers::document_sync::handle_did_change_configuration)
         .finish();
 }
 
@@ -180,6 +307,15 @@ fn handle_task(state: &mut GlobalState, response: TaskResponse) {
                 tracing::debug!(id = ?resp.id, "dropping response for cancelled request");
             }
         }
+        TaskResponse::SemanticTokens { id, encoded } => {
+            if state.in_flight.contains(&id) {
+                let result = state.semantic_tokens.finish(encoded);
+                let value = serde_json::to_value(result).expect("semantic tokens are serializable");
+                state.respond(lsp_server::Response::new_ok(id, value));
+            } else {
+                tracing::debug!(?id, "dropping semantic tokens for cancelled request");
+            }
+        }
         TaskResponse::PublishDiagnosticsForUri {
             uri,
             diagnostics,
@@ -203,6 +339,10 @@ fn handle_task(state: &mut GlobalState, response: TaskResponse) {
                 state.publish_diagnostics(uri, diags, None);
             }
         }
+        #[cfg(feature = "native")]
+        TaskResponse::DocumentsIndexed(indexed) => {
+            crate::indexing::apply_indexed_documents(state, indexed);
+        }
     }
 }
 
@@ -210,6 +350,7 @@ fn handle_introspection_result(
     state: &mut GlobalState,
     result: crate::global_state::IntrospectionResult,
 ) {
+    state.pending_introspections = state.pending_introspections.saturating_sub(1);
     match result.result {
         Ok(sdl) => {
             if let Some(host) = state
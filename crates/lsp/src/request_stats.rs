@@ -0,0 +1,290 @@
+//! Recent request timings.
+//!
+//! Every request the server answers is timed and kept in a fixed-size ring
+//! buffer, so `graphql-analyzer/perfStats` can summarize latency per method
+//! and per file, and list the slowest recent requests. Unlike a trace
+//! capture, this is always on and needs no collector: it's meant to be
+//! pasted into a performance report as is.
+//!
+//! Cancelled and panicking requests aren't recorded; their duration says
+//! nothing about how long the work takes. Handlers on the worker pool are
+//! timed there, and the timing comes back with their task for the main loop
+//! to record.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// Number of recent requests kept
+const CAPACITY: usize = 1024;
+
+/// Number of requests listed in `slowest`
+const SLOWEST: usize = 10;
+
+/// How long one request's handler ran
+pub struct RequestTiming {
+    pub method: &'static str,
+    pub file: Option<String>,
+    pub duration: Duration,
+    finished_at: SystemTime,
+}
+
+impl RequestTiming {
+    /// A request to `file` (if it targets one) that just finished
+    pub fn new(method: &'static str, file: Option<String>, duration: Duration) -> Self {
+        Self {
+            method,
+            file,
+            duration,
+            finished_at: SystemTime::now(),
+        }
+    }
+}
+
+/// The last `CAPACITY` request timings
+#[derive(Default)]
+pub struct RequestTimings {
+    recent: VecDeque<RequestTiming>,
+}
+
+impl RequestTimings {
+    /// Record a completed request, dropping the oldest once full.
+    pub fn record(&mut self, timing: RequestTiming) {
+        if self.recent.len() == CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(timing);
+    }
+
+    /// Summarize the recorded requests.
+    pub fn stats(&self) -> PerfStatsResult {
+        let recent = &self.recent;
+
+        let mut by_method: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+        let mut by_file: BTreeMap<&str, Vec<Duration>> = BTreeMap::new();
+        for timing in recent.iter() {
+            by_method
+                .entry(timing.method)
+                .or_default()
+                .push(timing.duration);
+            if let Some(file) = &timing.file {
+                by_file
+                    .entry(file.as_str())
+                    .or_default()
+                    .push(timing.duration);
+            }
+        }
+
+        let methods = by_method
+            .into_iter()
+            .map(|(method, durations)| {
+                let latency = Latency::of(durations);
+                MethodStats {
+                    method: method.to_string(),
+                    count: latency.count,
+                    p50_ms: latency.p50_ms,
+                    p95_ms: latency.p95_ms,
+                    max_ms: latency.max_ms,
+                }
+            })
+            .collect();
+
+        // Slowest files first, since that's where a report should look
+        let mut files: Vec<FileStats> = by_file
+            .into_iter()
+            .map(|(file, durations)| {
+                let latency = Latency::of(durations);
+                FileStats {
+                    file: file.to_string(),
+                    count: latency.count,
+                    p50_ms: latency.p50_ms,
+                    p95_ms: latency.p95_ms,
+                    max_ms: latency.max_ms,
+                }
+            })
+            .collect();
+        files.sort_by(|a, b| b.p95_ms.total_cmp(&a.p95_ms));
+
+        let mut slowest: Vec<&RequestTiming> = recent.iter().collect();
+        slowest.sort_by(|a, b| b.duration.cmp(&a.duration));
+        let slowest = slowest
+            .into_iter()
+            .take(SLOWEST)
+            .map(|timing| SlowRequest {
+                method: timing.method.to_string(),
+                file: timing.file.clone(),
+                duration_ms: millis(timing.duration),
+                finished_at: timing
+                    .finished_at
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64),
+            })
+            .collect();
+
+        PerfStatsResult {
+            requests: recent.len(),
+            methods,
+            files,
+            slowest,
+        }
+    }
+}
+
+struct Latency {
+    count: usize,
+    p50_ms: f64,
+    p95_ms: f64,
+    max_ms: f64,
+}
+
+impl Latency {
+    fn of(mut durations: Vec<Duration>) -> Self {
+        durations.sort();
+        Self {
+            count: durations.len(),
+            p50_ms: millis(percentile(&durations, 50)),
+            p95_ms: millis(percentile(&durations, 95)),
+            max_ms: millis(durations.last().copied().unwrap_or_default()),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted `durations`
+fn percentile(durations: &[Duration], percent: usize) -> Duration {
+    if durations.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (durations.len() * percent).div_ceil(100).max(1);
+    durations[rank - 1]
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfStatsResult {
+    /// Requests the summary covers, at most the buffer's capacity
+    pub requests: usize,
+    /// Latency per request method, sorted by method
+    pub methods: Vec<MethodStats>,
+    /// Latency per file, slowest p95 first. Requests that don't target a
+    /// file are left out.
+    pub files: Vec<FileStats>,
+    /// The slowest recent requests, slowest first
+    pub slowest: Vec<SlowRequest>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MethodStats {
+    pub method: String,
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStats {
+    pub file: String,
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowRequest {
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub duration_ms: f64,
+    /// Milliseconds since the Unix epoch
+    pub finished_at: u64,
+}
+
+/// Custom request: latency summary of recent requests.
+pub enum PerfStatsRequest {}
+
+impl lsp_types::request::Request for PerfStatsRequest {
+    type Params = serde_json::Value;
+    type Result = PerfStatsResult;
+    const METHOD: &'static str = "graphql-analyzer/perfStats";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let durations: Vec<Duration> = (1..=20).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&durations, 50), Duration::from_millis(10));
+        assert_eq!(percentile(&durations, 95), Duration::from_millis(19));
+        assert_eq!(percentile(&durations[..1], 95), Duration::from_millis(1));
+        assert_eq!(percentile(&[], 50), Duration::ZERO);
+    }
+
+    #[test]
+    fn stats_group_by_method_and_file() {
+        let mut timings = RequestTimings::default();
+        for ms in [5, 10, 40] {
+            timings.record(RequestTiming::new(
+                "textDocument/hover",
+                Some("file:///a.graphql".to_string()),
+                Duration::from_millis(ms),
+            ));
+        }
+        timings.record(RequestTiming::new(
+            "textDocument/completion",
+            Some("file:///b.graphql".to_string()),
+            Duration::from_millis(80),
+        ));
+        timings.record(RequestTiming::new(
+            "workspace/symbol",
+            None,
+            Duration::from_millis(1),
+        ));
+
+        let stats = timings.stats();
+        assert_eq!(stats.requests, 5);
+
+        let methods: Vec<(&str, usize)> = stats
+            .methods
+            .iter()
+            .map(|m| (m.method.as_str(), m.count))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("textDocument/completion", 1),
+                ("textDocument/hover", 3),
+                ("workspace/symbol", 1),
+            ]
+        );
+        let hover = &stats.methods[1];
+        assert!((hover.p50_ms - 10.0).abs() < 1e-9);
+        assert!((hover.p95_ms - 40.0).abs() < 1e-9);
+
+        let files: Vec<&str> = stats.files.iter().map(|f| f.file.as_str()).collect();
+        assert_eq!(files, vec!["file:///b.graphql", "file:///a.graphql"]);
+
+        assert_eq!(stats.slowest[0].method, "textDocument/completion");
+        assert_eq!(stats.slowest.len(), 5);
+    }
+
+    #[test]
+    fn oldest_timings_are_dropped_when_full() {
+        let mut timings = RequestTimings::default();
+        timings.record(RequestTiming::new("slow", None, Duration::from_secs(1)));
+        for _ in 0..CAPACITY {
+            timings.record(RequestTiming::new("fast", None, Duration::from_millis(1)));
+        }
+        let stats = timings.stats();
+        assert_eq!(stats.requests, CAPACITY);
+        assert!(stats.methods.iter().all(|m| m.method == "fast"));
+    }
+}
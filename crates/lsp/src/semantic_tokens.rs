@@ -0,0 +1,218 @@
+//! Semantic token encoding and the per-file cache behind delta requests.
+//!
+//! Every full or delta response is stored under a fresh result id. When the
+//! client asks for a delta against the id it last received, only the span
+//! between the unchanged prefix and suffix of the token array is sent back.
+//! The cache lives on the main thread: it hands the client's previous tokens
+//! to the worker, and stores what the worker encoded once its task returns.
+
+use std::collections::HashMap;
+
+use lsp_types::{
+    SemanticToken, SemanticTokens, SemanticTokensDelta, SemanticTokensEdit,
+    SemanticTokensFullDeltaResult,
+};
+
+#[derive(Default)]
+pub struct SemanticTokensCache {
+    next_id: u64,
+    /// Last tokens sent per document URI, with their result id
+    entries: HashMap<String, (String, Vec<SemanticToken>)>,
+}
+
+/// Tokens a worker encoded for a full or delta request
+pub struct EncodedTokens {
+    pub uri: String,
+    pub tokens: Vec<SemanticToken>,
+    /// The client's tokens the delta is taken against. `None` for a full
+    /// request, or when the client's result id is no longer cached.
+    pub previous: Option<Vec<SemanticToken>>,
+}
+
+impl SemanticTokensCache {
+    /// Remember `tokens` as the latest sent for `uri`, returning their result id
+    pub fn store(&mut self, uri: &str, tokens: Vec<SemanticToken>) -> String {
+        let result_id = self.next_id.to_string();
+        self.next_id += 1;
+        self.entries
+            .insert(uri.to_string(), (result_id.clone(), tokens));
+        result_id
+    }
+
+    /// The tokens last sent for `uri`, if they were sent under `result_id`
+    pub fn previous(&self, uri: &str, result_id: &str) -> Option<Vec<SemanticToken>> {
+        self.entries
+            .get(uri)
+            .filter(|(id, _)| id == result_id)
+            .map(|(_, tokens)| tokens.clone())
+    }
+
+    pub fn remove(&mut self, uri: &str) {
+        self.entries.remove(uri);
+    }
+
+    /// Store encoded tokens under a new result id and build the response:
+    /// a delta when there are previous tokens to diff against, the full
+    /// tokens otherwise. A full request's `SemanticTokensResult::Tokens`
+    /// serializes the same as `SemanticTokensFullDeltaResult::Tokens`.
+    pub fn finish(&mut self, encoded: EncodedTokens) -> SemanticTokensFullDeltaResult {
+        let edits = encoded
+            .previous
+            .as_ref()
+            .map(|previous| diff(previous, &encoded.tokens));
+        let result_id = Some(self.store(&encoded.uri, encoded.tokens.clone()));
+        match edits {
+            Some(edits) => {
+                SemanticTokensFullDeltaResult::TokensDelta(SemanticTokensDelta { result_id, edits })
+            }
+            None => SemanticTokensFullDeltaResult::Tokens(SemanticTokens {
+                result_id,
+                data: encoded.tokens,
+            }),
+        }
+    }
+}
+
+/// Encode tokens, sorted by position, in the LSP relative format
+pub fn encode(tokens: &[graphql_ide::SemanticToken]) -> Vec<SemanticToken> {
+    let mut encoded_tokens = Vec::with_capacity(tokens.len());
+    let mut prev_line = 0u32;
+    let mut prev_start = 0u32;
+
+    for token in tokens {
+        let delta_line = token.start.line - prev_line;
+        let delta_start = if delta_line == 0 {
+            token.start.character - prev_start
+        } else {
+            token.start.character
+        };
+
+        encoded_tokens.push(SemanticToken {
+            delta_line,
+            delta_start,
+            length: token.length,
+            token_type: token.token_type.index(),
+            token_modifiers_bitset: token.modifiers.raw(),
+        });
+
+        prev_line = token.start.line;
+        prev_start = token.start.character;
+    }
+
+    encoded_tokens
+}
+
+/// The single edit turning `old` into `new`, or none if they're equal
+///
+/// Edit offsets count `u32`s of the flattened array, five per token.
+pub fn diff(old: &[SemanticToken], new: &[SemanticToken]) -> Vec<SemanticTokensEdit> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let deleted = old.len() - prefix - suffix;
+    let inserted = &new[prefix..new.len() - suffix];
+    if deleted == 0 && inserted.is_empty() {
+        return Vec::new();
+    }
+
+    vec![SemanticTokensEdit {
+        start: 5 * prefix as u32,
+        delete_count: 5 * deleted as u32,
+        data: Some(inserted.to_vec()),
+    }]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(delta_line: u32, delta_start: u32) -> SemanticToken {
+        SemanticToken {
+            delta_line,
+            delta_start,
+            length: 3,
+            token_type: 0,
+            token_modifiers_bitset: 0,
+        }
+    }
+
+    #[test]
+    fn diff_of_equal_tokens_is_empty() {
+        let tokens = vec![token(0, 1), token(1, 2)];
+        assert!(diff(&tokens, &tokens).is_empty());
+    }
+
+    #[test]
+    fn diff_replaces_only_the_changed_middle() {
+        let old = vec![token(0, 1), token(1, 2), token(1, 4)];
+        let new = vec![token(0, 1), token(2, 0), token(0, 5), token(1, 4)];
+
+        let edits = diff(&old, &new);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].start, 5);
+        assert_eq!(edits[0].delete_count, 5);
+        assert_eq!(edits[0].data, Some(vec![token(2, 0), token(0, 5)]));
+    }
+
+    #[test]
+    fn diff_handles_pure_deletion() {
+        let old = vec![token(0, 1), token(1, 2), token(1, 4)];
+        let new = vec![token(0, 1), token(1, 4)];
+
+        let edits = diff(&old, &new);
+        assert_eq!(edits[0].start, 5);
+        assert_eq!(edits[0].delete_count, 5);
+        assert_eq!(edits[0].data, Some(vec![]));
+    }
+
+    #[test]
+    fn previous_requires_matching_result_id() {
+        let mut cache = SemanticTokensCache::default();
+        let first = cache.store("file:///a.graphql", vec![token(0, 1)]);
+        let second = cache.store("file:///a.graphql", vec![token(0, 2)]);
+
+        assert!(cache.previous("file:///a.graphql", &first).is_none());
+        assert_eq!(
+            cache.previous("file:///a.graphql", &second),
+            Some(vec![token(0, 2)])
+        );
+
+        cache.remove("file:///a.graphql");
+        assert!(cache.previous("file:///a.graphql", &second).is_none());
+    }
+
+    #[test]
+    fn finish_sends_a_delta_only_against_previous_tokens() {
+        let mut cache = SemanticTokensCache::default();
+        let full = cache.finish(EncodedTokens {
+            uri: "file:///a.graphql".to_string(),
+            tokens: vec![token(0, 1)],
+            previous: None,
+        });
+        let SemanticTokensFullDeltaResult::Tokens(full) = full else {
+            panic!("expected full tokens");
+        };
+        let first = full.result_id.expect("full tokens get a result id");
+
+        let delta = cache.finish(EncodedTokens {
+            uri: "file:///a.graphql".to_string(),
+            tokens: vec![token(0, 2)],
+            previous: cache.previous("file:///a.graphql", &first),
+        });
+        let SemanticTokensFullDeltaResult::TokensDelta(delta) = delta else {
+            panic!("expected a delta");
+        };
+        let second = delta.result_id.expect("deltas get a result id");
+        assert_ne!(first, second);
+        assert_eq!(delta.edits.len(), 1);
+        assert_eq!(
+            cache.previous("file:///a.graphql", &second),
+            Some(vec![token(0, 2)])
+        );
+    }
+}
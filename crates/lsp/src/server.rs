@@ -16,6 +16,520 @@ impl lsp_types::request::Request for VirtualFileContentRequest {
     const METHOD: &'static str = "graphql-analyzer/virtualFileContent";
 }
 
+/// Parameters for `workspace/textDocumentContent` (LSP 3.18).
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct TextDocumentContentParams {
+    pub uri: lsp_types::Uri,
+}
+
+/// Result of `workspace/textDocumentContent`.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct TextDocumentContentResult {
+    pub text: String,
+}
+
+/// The standard counterpart of [`VirtualFileContentRequest`], for clients
+/// that open virtual documents themselves. `lsp_types` predates it.
+pub enum TextDocumentContentRequest {}
+
+impl lsp_types::request::Request for TextDocumentContentRequest {
+    type Params = TextDocumentContentParams;
+    type Result = Option<TextDocumentContentResult>;
+    const METHOD: &'static str = "workspace/textDocumentContent";
+}
+
+/// Parameters for the `graphql-analyzer/selectionSkeleton` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectionSkeletonParams {
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    pub position: lsp_types::Position,
+    /// Levels of composite fields to expand below the field
+    #[serde(default = "default_skeleton_depth")]
+    pub depth: usize,
+    pub options: lsp_types::FormattingOptions,
+}
+
+const fn default_skeleton_depth() -> usize {
+    1
+}
+
+/// Snippet to insert for the `graphql-analyzer/selectionSkeleton` request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct SelectionSkeletonResponse {
+    pub range: lsp_types::Range,
+    pub snippet: String,
+}
+
+/// Custom request: generate the selection set for the field at a position.
+pub enum SelectionSkeletonRequest {}
+
+impl lsp_types::request::Request for SelectionSkeletonRequest {
+    type Params = SelectionSkeletonParams;
+    type Result = Option<SelectionSkeletonResponse>;
+    const METHOD: &'static str = "graphql-analyzer/selectionSkeleton";
+}
+
+/// Parameters for the `graphql-analyzer/organizeFragments` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeFragmentsParams {
+    /// Any document of the project to organize
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// File the hoisted fragments are written to, created if needed
+    pub fragment_file: lsp_types::Uri,
+}
+
+/// Custom request: hoist selection sets duplicated across the project into
+/// shared fragments. Returns the edit for the client to apply.
+pub enum OrganizeFragmentsRequest {}
+
+impl lsp_types::request::Request for OrganizeFragmentsRequest {
+    type Params = OrganizeFragmentsParams;
+    type Result = Option<lsp_types::WorkspaceEdit>;
+    const METHOD: &'static str = "graphql-analyzer/organizeFragments";
+}
+
+/// Parameters for the `graphql-analyzer/organizeSchema` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizeSchemaParams {
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// Order of definition kinds: `directives`, `scalars`, `enums`,
+    /// `inputs`, `interfaces`, `objects`, `unions`. Empty sorts by name only.
+    #[serde(default)]
+    pub groups: Vec<String>,
+}
+
+/// Custom request: sort and deduplicate a schema file's definitions and
+/// fields. Returns the edits for the client to apply.
+pub enum OrganizeSchemaRequest {}
+
+impl lsp_types::request::Request for OrganizeSchemaRequest {
+    type Params = OrganizeSchemaParams;
+    type Result = Option<Vec<lsp_types::TextEdit>>;
+    const METHOD: &'static str = "graphql-analyzer/organizeSchema";
+}
+
+/// Parameters for the `graphql-analyzer/previewFix` custom request,
+/// identifying a diagnostic as it was published.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewFixParams {
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// The diagnostic's `code`
+    pub code: String,
+    pub range: lsp_types::Range,
+}
+
+/// Response for the `graphql-analyzer/previewFix` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct PreviewFixResponse {
+    pub label: String,
+    pub edit: lsp_types::WorkspaceEdit,
+}
+
+/// Custom request: the edit a diagnostic's fix would make, without applying
+/// it.
+pub enum PreviewFixRequest {}
+
+impl lsp_types::request::Request for PreviewFixRequest {
+    type Params = PreviewFixParams;
+    type Result = Option<PreviewFixResponse>;
+    const METHOD: &'static str = "graphql-analyzer/previewFix";
+}
+
+/// Parameters for the `graphql-analyzer/fixAll` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixAllParams {
+    /// Any document of the project to fix
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// Lint rule or validation code whose fixes to collect
+    pub rule: String,
+}
+
+/// Response for the `graphql-analyzer/fixAll` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FixAllResponse {
+    pub edit: lsp_types::WorkspaceEdit,
+    /// Number of fixes included in `edit`
+    pub applied: usize,
+    /// Diagnostics left out because their fix overlaps one in `edit`
+    pub skipped: Vec<lsp_types::Location>,
+}
+
+/// Custom request: every non-conflicting fix for one rule across the
+/// project, merged into a single edit for the client to apply.
+pub enum FixAllRequest {}
+
+impl lsp_types::request::Request for FixAllRequest {
+    type Params = FixAllParams;
+    type Result = FixAllResponse;
+    const METHOD: &'static str = "graphql-analyzer/fixAll";
+}
+
+/// Parameters for the `graphql-analyzer/migrateField` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateFieldParams {
+    /// Any document of the project to migrate
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// The field being replaced, as `Type.field`
+    pub from: String,
+    /// The replacement field on the same type, as `Type.field` or `field`
+    pub to: String,
+}
+
+/// A usage `graphql-analyzer/migrateField` left alone.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct UnmigratedFieldUsage {
+    pub location: lsp_types::Location,
+    pub message: String,
+}
+
+/// Response for the `graphql-analyzer/migrateField` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct MigrateFieldResponse {
+    pub edit: lsp_types::WorkspaceEdit,
+    /// Usages that need a manual migration, e.g. because the new field
+    /// requires an argument they don't pass
+    pub unmigrated: Vec<UnmigratedFieldUsage>,
+}
+
+/// Custom request: move every selection of a field to its replacement
+/// across the project. `null` when either field doesn't exist.
+pub enum MigrateFieldRequest {}
+
+impl lsp_types::request::Request for MigrateFieldRequest {
+    type Params = MigrateFieldParams;
+    type Result = Option<MigrateFieldResponse>;
+    const METHOD: &'static str = "graphql-analyzer/migrateField";
+}
+
+/// Parameters for the `graphql-analyzer/impactOfChange` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactOfChangeParams {
+    /// Any document of the project to search
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    pub type_name: String,
+    pub field_name: String,
+}
+
+/// An operation in a `graphql-analyzer/impactOfChange` response.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+pub struct ImpactedOperation {
+    /// `null` for an anonymous operation
+    pub name: Option<String>,
+    pub location: lsp_types::Location,
+}
+
+/// Response for the `graphql-analyzer/impactOfChange` custom request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactOfChangeResponse {
+    pub type_name: String,
+    pub field_name: String,
+    pub operations: Vec<ImpactedOperation>,
+}
+
+/// Custom request: the operations that would break if a field were removed
+/// or its type changed. `null` when the field doesn't exist.
+pub enum ImpactOfChangeRequest {}
+
+impl lsp_types::request::Request for ImpactOfChangeRequest {
+    type Params = ImpactOfChangeParams;
+    type Result = Option<ImpactOfChangeResponse>;
+    const METHOD: &'static str = "graphql-analyzer/impactOfChange";
+}
+
+/// Response for the `graphql-analyzer/contextAtPosition` debug request.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextAtPositionResponse {
+    pub language: String,
+    pub document_kind: String,
+    pub has_project: bool,
+    pub block_count: usize,
+    pub extraction_skipped: Option<String>,
+    pub block: Option<ContextBlock>,
+    pub enclosing_definition: Option<String>,
+    pub symbol: Option<String>,
+    pub parent_type: Option<String>,
+    pub expected_input_type: Option<String>,
+    pub completion_path: String,
+}
+
+/// The GraphQL block a `graphql-analyzer/contextAtPosition` position maps into.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextBlock {
+    pub index: usize,
+    pub file_offset: usize,
+    pub start: lsp_types::Position,
+    pub length: usize,
+    /// Cursor position relative to the block
+    pub position: lsp_types::Position,
+    pub offset: Option<usize>,
+}
+
+/// Custom request: report the analyzer's view of a position, for debugging
+/// editor features.
+pub enum ContextAtPositionRequest {}
+
+impl lsp_types::request::Request for ContextAtPositionRequest {
+    type Params = lsp_types::TextDocumentPositionParams;
+    type Result = Option<ContextAtPositionResponse>;
+    const METHOD: &'static str = "graphql-analyzer/contextAtPosition";
+}
+
+/// Parameters for the project analytics requests. Any document of the
+/// project selects which project to report on.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReportParams {
+    pub text_document: lsp_types::TextDocumentIdentifier,
+}
+
+/// Response for `graphql-analyzer/fieldCoverage`, mirroring
+/// `graphql_ide::FieldCoverageReport`.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldCoverageResponse {
+    pub total_fields: usize,
+    pub used_fields: usize,
+    pub coverage_percentage: f64,
+    pub types: Vec<TypeCoverage>,
+    /// Every schema field, sorted by type then field name
+    pub fields: Vec<FieldUsage>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeCoverage {
+    pub type_name: String,
+    pub total_fields: usize,
+    pub used_fields: usize,
+    pub coverage_percentage: f64,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldUsage {
+    pub type_name: String,
+    pub field_name: String,
+    pub usage_count: usize,
+    pub operations: Vec<String>,
+}
+
+/// Custom request: how much of the schema the project's operations select.
+pub enum FieldCoverageRequest {}
+
+impl lsp_types::request::Request for FieldCoverageRequest {
+    type Params = ProjectReportParams;
+    type Result = Option<FieldCoverageResponse>;
+    const METHOD: &'static str = "graphql-analyzer/fieldCoverage";
+}
+
+/// Parameters for the `graphql-analyzer/generateDocs` custom request. Any
+/// document of the project selects which schema to document.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateDocsParams {
+    pub text_document: lsp_types::TextDocumentIdentifier,
+    /// `markdown` (the default) or `html`
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Top-level heading; defaults to "Schema"
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Whether deprecated fields, arguments and enum values are listed
+    #[serde(default = "default_true")]
+    pub include_deprecated: bool,
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+/// Custom request: reference documentation for the project schema, as
+/// Markdown or a standalone HTML page. `None` when there's no schema.
+pub enum GenerateDocsRequest {}
+
+impl lsp_types::request::Request for GenerateDocsRequest {
+    type Params = GenerateDocsParams;
+    type Result = Option<String>;
+    const METHOD: &'static str = "graphql-analyzer/generateDocs";
+}
+
+/// One operation in the `graphql-analyzer/complexity` response, mirroring
+/// `graphql_ide::ComplexityAnalysis`.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationComplexity {
+    pub operation_name: String,
+    pub operation_type: String,
+    pub total_complexity: u32,
+    pub depth: u32,
+    pub breakdown: Vec<FieldComplexity>,
+    pub warnings: Vec<String>,
+    pub location: lsp_types::Location,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldComplexity {
+    pub path: String,
+    pub name: String,
+    pub complexity: u32,
+    pub multiplier: u32,
+    pub depth: u32,
+    pub is_connection: bool,
+    pub warning: Option<String>,
+}
+
+/// Custom request: complexity score and breakdown for every operation.
+pub enum ComplexityRequest {}
+
+impl lsp_types::request::Request for ComplexityRequest {
+    type Params = ProjectReportParams;
+    type Result = Vec<OperationComplexity>;
+    const METHOD: &'static str = "graphql-analyzer/complexity";
+}
+
+/// Response for `graphql-analyzer/schemaStats`, mirroring
+/// `graphql_ide::SchemaStats`.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaStatsResponse {
+    pub objects: usize,
+    pub interfaces: usize,
+    pub unions: usize,
+    pub enums: usize,
+    pub scalars: usize,
+    pub input_objects: usize,
+    pub total_types: usize,
+    pub total_fields: usize,
+    pub directives: usize,
+}
+
+/// Custom request: counts of the schema's types, fields and directives.
+pub enum SchemaStatsRequest {}
+
+impl lsp_types::request::Request for SchemaStatsRequest {
+    type Params = ProjectReportParams;
+    type Result = SchemaStatsResponse;
+    const METHOD: &'static str = "graphql-analyzer/schemaStats";
+}
+
+/// Response for `graphql-analyzer/memoryMetrics`, mirroring
+/// `graphql_ide::MemoryMetrics`. Byte counts are estimates.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryMetricsResponse {
+    pub files: usize,
+    pub source_bytes: usize,
+    pub documents: usize,
+    pub lazy_parsing: bool,
+    pub parse_cache_capacity: Option<usize>,
+    pub budget_bytes: Option<usize>,
+    pub estimated_parse_bytes: usize,
+}
+
+/// Custom request: estimated memory held by parsed files.
+pub enum MemoryMetricsRequest {}
+
+impl lsp_types::request::Request for MemoryMetricsRequest {
+    type Params = ProjectReportParams;
+    type Result = MemoryMetricsResponse;
+    const METHOD: &'static str = "graphql-analyzer/memoryMetrics";
+}
+
+/// A fragment in the `graphql-analyzer/fragmentGraph` response, mirroring
+/// `graphql_ide::FragmentUsage`.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FragmentNode {
+    pub name: String,
+    pub definition: lsp_types::Location,
+    /// Where the fragment is spread
+    pub usages: Vec<lsp_types::Location>,
+    /// Fragments this one spreads, directly or through other fragments
+    pub transitive_dependencies: Vec<String>,
+}
+
+/// Custom request: every fragment with its spreads and dependencies.
+pub enum FragmentGraphRequest {}
+
+impl lsp_types::request::Request for FragmentGraphRequest {
+    type Params = ProjectReportParams;
+    type Result = Vec<FragmentNode>;
+    const METHOD: &'static str = "graphql-analyzer/fragmentGraph";
+}
+
+/// Response for `graphql-analyzer/projectReport`: the headline numbers of
+/// the other analytics requests, all read from one snapshot so they agree
+/// with each other.
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectReportResponse {
+    pub schema: SchemaStatsResponse,
+    /// `None` when the project has no schema
+    pub field_coverage: Option<CoverageSummary>,
+    pub fragments: FragmentSummary,
+    pub complexity: ComplexitySummary,
+    pub diagnostics: DiagnosticSummary,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageSummary {
+    pub total_fields: usize,
+    pub used_fields: usize,
+    pub coverage_percentage: f64,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FragmentSummary {
+    pub total: usize,
+    /// Names of fragments that are never spread, sorted
+    pub unused: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComplexitySummary {
+    pub operations: usize,
+    pub max_complexity: u32,
+    pub max_depth: u32,
+    /// Name of the operation with the highest score
+    pub most_complex_operation: Option<String>,
+    /// Complexity warnings across all operations
+    pub warnings: usize,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticSummary {
+    pub errors: usize,
+    pub warnings: usize,
+    /// Lint diagnostics per rule, whatever their severity
+    pub lint_by_rule: std::collections::BTreeMap<String, usize>,
+}
+
+/// Custom request: schema, coverage, fragment, complexity and diagnostic
+/// totals in one response, for status panels.
+pub enum ProjectReportRequest {}
+
+impl lsp_types::request::Request for ProjectReportRequest {
+    type Params = ProjectReportParams;
+    type Result = ProjectReportResponse;
+    const METHOD: &'static str = "graphql-analyzer/projectReport";
+}
+
 /// Custom request: client-server health check.
 pub enum PingRequest {}
 
@@ -25,6 +539,15 @@ impl lsp_types::request::Request for PingRequest {
     const METHOD: &'static str = "graphql-analyzer/ping";
 }
 
+/// Custom request: delete every project's persisted file structure cache.
+pub enum ClearCacheRequest {}
+
+impl lsp_types::request::Request for ClearCacheRequest {
+    type Params = serde_json::Value;
+    type Result = ClearCacheResponse;
+    const METHOD: &'static str = "graphql-analyzer/clearCache";
+}
+
 /// Custom notification sent from server to client to indicate loading status.
 #[cfg(feature = "native")]
 pub enum StatusNotification {}
@@ -43,6 +566,14 @@ pub struct StatusParams {
     pub message: Option<String>,
 }
 
+/// Response for the `graphql-analyzer/clearCache` request.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClearCacheResponse {
+    /// Projects whose cache was cleared
+    pub projects: usize,
+}
+
 /// Response for the `graphql/ping` health check request.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct PingResponse {
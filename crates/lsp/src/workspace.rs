@@ -5,6 +5,7 @@
 //! - Configuration paths and loaded configs
 //! - `AnalysisHost` instances per workspace/project (directly owned, no locks)
 //! - File-to-project mapping for efficient lookups
+//! - The project inferred for a workspace without a GraphQL config
 //!
 //! ## Architecture
 //!
@@ -12,10 +13,13 @@
 //! because the main thread is the sole writer, and worker threads only receive
 //! immutable `Analysis` snapshots.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "native")]
+use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
-use graphql_ide::AnalysisHost;
+use graphql_ide::{AnalysisHost, MetricsSink};
 use lsp_types::Uri;
 
 #[cfg(feature = "native")]
@@ -45,6 +49,9 @@ pub struct WorkspaceManager {
     /// Config file paths indexed by workspace URI string
     pub config_paths: HashMap<String, PathBuf>,
 
+    /// `.graphqlanalyzer.toml` paths indexed by workspace URI string
+    pub settings_paths: HashMap<String, PathBuf>,
+
     /// Loaded GraphQL configs indexed by workspace URI string
     pub configs: HashMap<String, graphql_config::GraphQLConfig>,
 
@@ -62,6 +69,14 @@ pub struct WorkspaceManager {
 
     /// Resolved schema paths per (`workspace_uri`, `project_name`).
     pub resolved_schema_paths: HashMap<(String, String), PathBuf>,
+
+    /// Workspaces running on an inferred project because they have no
+    /// config. A config file appearing in one of them replaces it.
+    pub inferred_workspaces: HashSet<String>,
+
+    /// Metrics sink registered by the embedding application, installed on
+    /// every host as it's created
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
 }
 
 impl WorkspaceManager {
@@ -71,12 +86,15 @@ impl WorkspaceManager {
             init_workspace_folders: HashMap::new(),
             workspace_roots: HashMap::new(),
             config_paths: HashMap::new(),
+            settings_paths: HashMap::new(),
             configs: HashMap::new(),
             hosts: HashMap::new(),
             document_versions: HashMap::new(),
             document_contents: HashMap::new(),
             file_to_project: HashMap::new(),
             resolved_schema_paths: HashMap::new(),
+            inferred_workspaces: HashSet::new(),
+            metrics_sink: None,
         }
     }
 
@@ -88,7 +106,20 @@ impl WorkspaceManager {
     ) -> &mut AnalysisHost {
         self.hosts
             .entry((workspace_uri.to_string(), project_name.to_string()))
-            .or_default()
+            .or_insert_with(|| {
+                let host = AnalysisHost::new();
+                host.set_metrics_sink(self.metrics_sink.clone());
+                host
+            })
+    }
+
+    /// Send every host's performance events to `sink`, including hosts
+    /// created later
+    pub fn set_metrics_sink(&mut self, sink: Option<Arc<dyn MetricsSink>>) {
+        for host in self.hosts.values() {
+            host.set_metrics_sink(sink.clone());
+        }
+        self.metrics_sink = sink;
     }
 
     /// Get an existing `AnalysisHost` reference
@@ -184,12 +215,29 @@ impl WorkspaceManager {
         None
     }
 
+    /// Write every host's persisted file structures to disk
+    pub fn save_structure_caches(&self) {
+        for ((workspace_uri, _), host) in &self.hosts {
+            save_structure_cache(workspace_uri, host);
+        }
+    }
+
     /// Clear all state for a workspace
     pub fn clear_workspace(&mut self, workspace_uri: &str) {
-        self.hosts.retain(|(ws, _), _| ws != workspace_uri);
+        self.hosts.retain(|(ws, _), host| {
+            if ws != workspace_uri {
+                return true;
+            }
+            // Stop in-flight queries on the old host instead of letting them
+            // finish against a database that's about to be dropped
+            host.request_cancellation();
+            save_structure_cache(ws, host);
+            false
+        });
         self.file_to_project
             .retain(|_, (ws, _)| ws != workspace_uri);
         self.configs.remove(workspace_uri);
+        self.inferred_workspaces.remove(workspace_uri);
     }
 
     /// Get the file type (schema or document) for a file based on config patterns.
@@ -216,6 +264,108 @@ impl WorkspaceManager {
     }
 }
 
+/// A single project inferred from a workspace's `.graphql` files.
+#[cfg(feature = "native")]
+pub struct InferredProject {
+    pub config: graphql_config::GraphQLConfig,
+    pub schema_files: usize,
+    pub document_files: usize,
+}
+
+/// Infer a project for a workspace without a GraphQL config.
+///
+/// Every `.graphql` file under `workspace_path` that defines or extends a
+/// type, or declares a schema or directive, is schema; the rest are
+/// documents. Hidden directories and `node_modules` are skipped. Returns
+/// `None` when there are no `.graphql` files at all.
+#[cfg(feature = "native")]
+pub fn infer_project(workspace_path: &Path) -> Option<InferredProject> {
+    let pattern = format!(
+        "{}/**/*.graphql",
+        glob::Pattern::escape(&workspace_path.to_string_lossy())
+    );
+    let mut files: Vec<PathBuf> = glob::glob(&pattern)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|path| {
+            path.strip_prefix(workspace_path)
+                .is_ok_and(|relative| !relative.components().any(is_skipped_component))
+        })
+        .collect();
+    files.sort();
+
+    let mut schema = Vec::new();
+    let mut documents = Vec::new();
+    for path in files {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(relative) = path.strip_prefix(workspace_path) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if has_type_system_definitions(&content) {
+            schema.push(relative);
+        } else {
+            documents.push(relative);
+        }
+    }
+    if schema.is_empty() && documents.is_empty() {
+        return None;
+    }
+
+    let (schema_files, document_files) = (schema.len(), documents.len());
+    let project = graphql_config::ProjectConfig::new(
+        graphql_config::SchemaConfig::Paths(schema),
+        (!documents.is_empty()).then_some(graphql_config::DocumentsConfig::Patterns(documents)),
+        None,
+        None,
+        None,
+    );
+    Some(InferredProject {
+        config: graphql_config::GraphQLConfig::Single(Box::new(project)),
+        schema_files,
+        document_files,
+    })
+}
+
+#[cfg(feature = "native")]
+fn is_skipped_component(component: std::path::Component<'_>) -> bool {
+    component
+        .as_os_str()
+        .to_str()
+        .is_some_and(|name| name == "node_modules" || name.starts_with('.'))
+}
+
+/// Whether `source` declares anything that belongs in a schema
+#[cfg(feature = "native")]
+fn has_type_system_definitions(source: &str) -> bool {
+    use apollo_parser::cst::Definition;
+
+    apollo_parser::Parser::new(source)
+        .parse()
+        .document()
+        .definitions()
+        .any(|definition| {
+            !matches!(
+                definition,
+                Definition::OperationDefinition(_) | Definition::FragmentDefinition(_)
+            )
+        })
+}
+
+fn save_structure_cache(workspace_uri: &str, host: &AnalysisHost) {
+    match host.save_structure_cache() {
+        Ok(0) => {}
+        Ok(entries) => tracing::debug!(
+            workspace = workspace_uri,
+            entries,
+            "Saved file structure cache"
+        ),
+        Err(e) => tracing::warn!("Failed to save file structure cache for {workspace_uri}: {e}"),
+    }
+}
+
 /// Apply an incremental content change to document text.
 ///
 /// Handles both full-document replacements (range is None) and incremental
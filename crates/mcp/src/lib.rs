@@ -37,7 +37,6 @@
 //! GraphQLMcpServer::run_with_analysis(analysis, transport).await?;
 //! ```
 
-mod sdl_printer;
 mod service;
 mod tools;
 mod types;
@@ -148,33 +148,23 @@ impl McpService {
                 let patterns: Vec<_> = documents_config.patterns().into_iter().collect();
                 let mut files_to_add: Vec<(FilePath, String, Language, DocumentKind)> = Vec::new();
 
-                for pattern in patterns {
-                    let full_pattern = base_dir.join(pattern).display().to_string();
-
-                    if let Ok(paths) = glob::glob(&full_pattern) {
-                        for entry in paths.flatten() {
-                            if entry.is_file() {
-                                if let Ok(content) = std::fs::read_to_string(&entry) {
-                                    let file_path = FilePath::from_path(&entry);
-                                    let (language, document_kind) =
-                                        match entry.extension().and_then(|e| e.to_str()) {
-                                            Some("ts" | "tsx") => {
-                                                (Language::TypeScript, DocumentKind::Executable)
-                                            }
-                                            Some("js" | "jsx") => {
-                                                (Language::JavaScript, DocumentKind::Executable)
-                                            }
-                                            _ => (Language::GraphQL, DocumentKind::Executable),
-                                        };
-                                    files_to_add.push((
-                                        file_path,
-                                        content,
-                                        language,
-                                        document_kind,
-                                    ));
-                                }
-                            }
-                        }
+                let globs = project_config.file_globs(&patterns);
+                for entry in globs.walk(base_dir).paths() {
+                    if let Ok(content) = std::fs::read_to_string(&entry) {
+                        let file_path = FilePath::from_path(&entry);
+                        let (language, document_kind) = match entry
+                            .extension()
+                            .and_then(|e| e.to_str())
+                        {
+                            Some("ts" | "tsx") => (Language::TypeScript, DocumentKind::Executable),
+                            Some("js" | "jsx") => (Language::JavaScript, DocumentKind::Executable),
+                            Some("vue") => (Language::Vue, DocumentKind::Executable),
+                            Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+                            Some("py") => (Language::Python, DocumentKind::Executable),
+                            Some("rb") => (Language::Ruby, DocumentKind::Executable),
+                            _ => (Language::GraphQL, DocumentKind::Executable),
+                        };
+                        files_to_add.push((file_path, content, language, document_kind));
                     }
                 }
 
@@ -337,28 +327,21 @@ impl McpService {
             let patterns: Vec<_> = documents_config.patterns().into_iter().collect();
             let mut files_to_add: Vec<(FilePath, String, Language, DocumentKind)> = Vec::new();
 
-            for pattern in patterns {
-                let full_pattern = base_dir.join(pattern).display().to_string();
-
-                if let Ok(paths) = glob::glob(&full_pattern) {
-                    for entry in paths.flatten() {
-                        if entry.is_file() {
-                            if let Ok(content) = std::fs::read_to_string(&entry) {
-                                let file_path = FilePath::from_path(&entry);
-                                let (language, document_kind) =
-                                    match entry.extension().and_then(|e| e.to_str()) {
-                                        Some("ts" | "tsx") => {
-                                            (Language::TypeScript, DocumentKind::Executable)
-                                        }
-                                        Some("js" | "jsx") => {
-                                            (Language::JavaScript, DocumentKind::Executable)
-                                        }
-                                        _ => (Language::GraphQL, DocumentKind::Executable),
-                                    };
-                                files_to_add.push((file_path, content, language, document_kind));
-                            }
-                        }
-                    }
+            let globs = project_config.file_globs(&patterns);
+            for entry in globs.walk(&base_dir).paths() {
+                if let Ok(content) = std::fs::read_to_string(&entry) {
+                    let file_path = FilePath::from_path(&entry);
+                    let (language, document_kind) = match entry.extension().and_then(|e| e.to_str())
+                    {
+                        Some("ts" | "tsx") => (Language::TypeScript, DocumentKind::Executable),
+                        Some("js" | "jsx") => (Language::JavaScript, DocumentKind::Executable),
+                        Some("vue") => (Language::Vue, DocumentKind::Executable),
+                        Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+                        Some("py") => (Language::Python, DocumentKind::Executable),
+                        Some("rb") => (Language::Ruby, DocumentKind::Executable),
+                        _ => (Language::GraphQL, DocumentKind::Executable),
+                    };
+                    files_to_add.push((file_path, content, language, document_kind));
                 }
             }
 
@@ -788,7 +771,7 @@ impl McpService {
         let type_count = entries.len();
 
         // Access the HIR types directly for SDL printing
-        let sdl = analysis.with_schema_types(crate::sdl_printer::print_schema_sdl);
+        let sdl = analysis.with_schema_types(graphql_ide::print_schema_sdl);
 
         Some(SchemaSdlResult { sdl, type_count })
     }
@@ -837,13 +820,9 @@ impl McpService {
         let project_name = self.resolve_project(project)?;
         let analysis = self.analysis(&project_name)?;
 
-        let all = analysis.complexity_analysis();
-        let filtered: Vec<_> = if let Some(name) = operation_name {
-            all.into_iter()
-                .filter(|c| c.operation_name == name)
-                .collect()
-        } else {
-            all
+        let filtered: Vec<_> = match operation_name {
+            Some(name) => analysis.complexity_analysis_for(name).into_iter().collect(),
+            None => analysis.complexity_analysis(),
         };
 
         let count = filtered.len();
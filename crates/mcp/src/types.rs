@@ -794,6 +794,7 @@ fn completion_kind_str(kind: graphql_ide::CompletionKind) -> &'static str {
         graphql_ide::CompletionKind::Argument => "argument",
         graphql_ide::CompletionKind::Variable => "variable",
         graphql_ide::CompletionKind::Keyword => "keyword",
+        graphql_ide::CompletionKind::Snippet => "snippet",
     }
 }
 
@@ -77,6 +77,8 @@ impl NapiAnalysisHost {
                 .map_err(|e| anyhow::anyhow!("Invalid extract config in project '{name}': {e}"))?;
             let extract_config = graphql_extract::resolve_for_documents(extract_value.as_ref());
             host.set_extract_config(extract_config);
+            host.set_validation_config(project.validation().unwrap_or_default());
+            host.set_client_controlled_nullability(project.client_controlled_nullability());
 
             let mut known_files = HashSet::new();
             let schema_result = host.load_schemas_from_config(project, base_dir)?;
@@ -221,6 +223,8 @@ fn language_and_kind_from_path(path: &str) -> (Language, DocumentKind) {
         Some("js" | "jsx" | "mjs" | "cjs") => (Language::JavaScript, DocumentKind::Executable),
         Some("vue") => (Language::Vue, DocumentKind::Executable),
         Some("svelte") => (Language::Svelte, DocumentKind::Executable),
+        Some("py") => (Language::Python, DocumentKind::Executable),
+        Some("rb") => (Language::Ruby, DocumentKind::Executable),
         Some("astro") => (Language::Astro, DocumentKind::Executable),
         _ => (Language::GraphQL, DocumentKind::Executable),
     }
@@ -64,6 +64,8 @@ pub fn extract_graphql(source: String, language: String) -> napi::Result<Vec<JsE
         "vue" => graphql_extract::Language::Vue,
         "svelte" => graphql_extract::Language::Svelte,
         "astro" => graphql_extract::Language::Astro,
+        "py" | "python" => graphql_extract::Language::Python,
+        "rb" | "ruby" => graphql_extract::Language::Ruby,
         "graphql" | "gql" => graphql_extract::Language::GraphQL,
         other => {
             return Err(napi::Error::from_reason(format!(
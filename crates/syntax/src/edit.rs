@@ -0,0 +1,403 @@
+//! Minimal, formatting-preserving source edits over the CST.
+//!
+//! Quick fixes and refactorings describe *what* to change (add this field,
+//! drop that definition) and these helpers work out *where* the text goes,
+//! matching the surrounding layout: multi-line selection sets get a new
+//! indented line, single-line ones stay single-line, and comments and
+//! whitespace outside the touched span are left alone.
+//!
+//! All offsets are relative to `source`, the text the CST was parsed from
+//! (the block source for embedded GraphQL).
+
+use apollo_parser::cst::{self, CstNode};
+use apollo_parser::{SyntaxElement, SyntaxKind, SyntaxNode};
+pub use graphql_types::TextEdit;
+
+/// Node kinds that directives must precede (`field @dir { ... }`,
+/// `type T @dir { ... }`, `union U @dir = A | B`).
+const DIRECTIVE_FOLLOWERS: &[SyntaxKind] = &[
+    SyntaxKind::SELECTION_SET,
+    SyntaxKind::FIELDS_DEFINITION,
+    SyntaxKind::INPUT_FIELDS_DEFINITION,
+    SyntaxKind::ENUM_VALUES_DEFINITION,
+    SyntaxKind::UNION_MEMBER_TYPES,
+];
+
+/// Insert `field` (e.g. `"id"` or `"user { id }"`) as the last selection.
+///
+/// Returns `None` if the selection set has no opening brace (parse error).
+#[must_use]
+pub fn insert_field(
+    source: &str,
+    selection_set: &cst::SelectionSet,
+    field: &str,
+) -> Option<TextEdit> {
+    let l_curly = selection_set.l_curly_token()?;
+    let l_curly_end: usize = l_curly.text_range().end().into();
+
+    if let Some(last) = selection_set.selections().last() {
+        let first_start = selection_set
+            .selections()
+            .next()
+            .map_or(l_curly_end, |s| node_start(s.syntax()));
+        let last_start = node_start(last.syntax());
+        let last_end = significant_end(source, last.syntax());
+        if !source[l_curly_end..first_start].contains('\n') {
+            return Some(TextEdit::insert(last_end, format!(" {field}")));
+        }
+        // Keep a trailing `# comment` attached to the selection it follows
+        let line_end = source[last_end..]
+            .find('\n')
+            .map_or(source.len(), |i| last_end + i);
+        let offset = if source[last_end..line_end].trim_start().starts_with('#') {
+            line_end
+        } else {
+            last_end
+        };
+        return Some(TextEdit::insert(
+            offset,
+            format!("\n{}{field}", line_indent(source, last_start)),
+        ));
+    }
+
+    let inner_end = selection_set
+        .r_curly_token()
+        .map_or(l_curly_end, |t| t.text_range().start().into());
+    let inner = &source[l_curly_end..inner_end];
+    let new_text = if inner.contains('\n') {
+        format!(
+            "\n{}  {field}",
+            line_indent(source, l_curly.text_range().start().into())
+        )
+    } else if inner.is_empty() {
+        format!(" {field} ")
+    } else {
+        format!(" {field}")
+    };
+    Some(TextEdit::insert(l_curly_end, new_text))
+}
+
+/// Add `name: value` to a field's arguments, creating the parentheses if
+/// the field has none.
+///
+/// Returns `None` if the field has no name (parse error).
+#[must_use]
+pub fn add_argument(source: &str, field: &cst::Field, name: &str, value: &str) -> Option<TextEdit> {
+    let argument = format!("{name}: {value}");
+    if let Some(arguments) = field.arguments() {
+        if let Some(last) = arguments.arguments().last() {
+            return Some(append_to_list(source, last.syntax(), &argument));
+        }
+        let r_paren = arguments.r_paren_token()?;
+        return Some(TextEdit::insert(
+            r_paren.text_range().start().into(),
+            argument,
+        ));
+    }
+    let field_name = field.name()?;
+    Some(TextEdit::insert(
+        field_name.syntax().text_range().end().into(),
+        format!("({argument})"),
+    ))
+}
+
+/// Append `@directive` (pass the text without the `@`) to a node's
+/// directive list: after any existing directives, before its selection set
+/// or definition body.
+#[must_use]
+pub fn add_directive(source: &str, node: &SyntaxNode, directive: &str) -> TextEdit {
+    let start = node_start(node);
+    let offset = node
+        .children()
+        .find(|child| DIRECTIVE_FOLLOWERS.contains(&child.kind()))
+        .map_or_else(
+            || significant_end(source, node),
+            |body| trim_end(source, start, node_start(&body)),
+        );
+    TextEdit::insert(offset, format!(" @{directive}"))
+}
+
+/// Add a variable definition (e.g. `"$id: ID!"`) to an operation, creating
+/// the variable list if needed. Shorthand `{ ... }` queries gain a `query`
+/// keyword since they can't declare variables otherwise.
+#[must_use]
+pub fn add_variable_definition(
+    source: &str,
+    operation: &cst::OperationDefinition,
+    declaration: &str,
+) -> TextEdit {
+    if let Some(definitions) = operation.variable_definitions() {
+        if let Some(last) = definitions.variable_definitions().last() {
+            return append_to_list(source, last.syntax(), declaration);
+        }
+        if let Some(r_paren) = definitions.r_paren_token() {
+            return TextEdit::insert(r_paren.text_range().start().into(), declaration);
+        }
+    }
+    if let Some(name) = operation.name() {
+        return TextEdit::insert(
+            name.syntax().text_range().end().into(),
+            format!("({declaration})"),
+        );
+    }
+    if let Some(operation_type) = operation.operation_type() {
+        return TextEdit::insert(
+            operation_type.syntax().text_range().end().into(),
+            format!("({declaration})"),
+        );
+    }
+    TextEdit::insert(
+        node_start(operation.syntax()),
+        format!("query({declaration}) "),
+    )
+}
+
+/// Remove a top-level definition along with the blank lines that separated
+/// it from the next one, so neighbours keep their original spacing.
+#[must_use]
+pub fn remove_definition(source: &str, definition: &cst::Definition) -> TextEdit {
+    let node = definition.syntax();
+    let bytes = source.as_bytes();
+
+    let mut start = node_start(node);
+    while start > 0 && matches!(bytes[start - 1], b' ' | b'\t') {
+        start -= 1;
+    }
+    // A trailing `# comment` goes with the definition
+    let mut end = trim_end(source, start, node.text_range().end().into());
+    while end < bytes.len() && bytes[end].is_ascii_whitespace() {
+        end += 1;
+    }
+
+    // Removing the last definition: eat the separator before it instead,
+    // keeping the file's trailing newline if it had one
+    if end == bytes.len() && start > 0 {
+        let previous_end = trim_end(source, 0, start);
+        start = if previous_end < bytes.len() && bytes[previous_end] == b'\n' {
+            previous_end + 1
+        } else {
+            previous_end
+        };
+        if source.ends_with('\n') && start == previous_end {
+            end = end.saturating_sub(1).max(start);
+        }
+    }
+
+    TextEdit::delete(start, end)
+}
+
+/// End offset of a node with trailing whitespace, commas and comments
+/// trimmed off.
+///
+/// apollo-parser attaches trailing trivia to the preceding node, so a raw
+/// `text_range().end()` usually lands after the whitespace or `# comment`
+/// that follows it.
+#[must_use]
+pub fn significant_end(source: &str, node: &SyntaxNode) -> usize {
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| !is_trivia(token.kind()))
+        .last()
+        .map_or_else(
+            || trim_end(source, node_start(node), node.text_range().end().into()),
+            |token| token.text_range().end().into(),
+        )
+}
+
+/// Append `item` after `last`, the final entry of an argument or variable
+/// list. A trailing `# comment` stays attached to `last`, with `item` on
+/// the next line.
+fn append_to_list(source: &str, last: &SyntaxNode, item: &str) -> TextEdit {
+    let last_end = significant_end(source, last);
+    let line_end = source[last_end..]
+        .find('\n')
+        .map_or(source.len(), |i| last_end + i);
+    if source[last_end..line_end]
+        .trim_start_matches([' ', '\t', ','])
+        .starts_with('#')
+    {
+        return TextEdit::insert(
+            line_end,
+            format!("\n{}{item}", line_indent(source, node_start(last))),
+        );
+    }
+    TextEdit::insert(last_end, format!(", {item}"))
+}
+
+fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::COMMA
+    )
+}
+
+fn node_start(node: &SyntaxNode) -> usize {
+    node.text_range().start().into()
+}
+
+fn trim_end(source: &str, start: usize, end: usize) -> usize {
+    start
+        + source[start..end]
+            .trim_end_matches(|c: char| c.is_whitespace() || c == ',')
+            .len()
+}
+
+/// Leading whitespace of the line containing `offset`
+#[must_use]
+pub fn line_indent(source: &str, offset: usize) -> &str {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(source: &str, edit: &TextEdit) -> String {
+        let mut result = source.to_string();
+        result.replace_range(
+            edit.offset_range.start..edit.offset_range.end,
+            &edit.new_text,
+        );
+        result
+    }
+
+    fn first_operation(tree: &apollo_parser::SyntaxTree) -> cst::OperationDefinition {
+        tree.document()
+            .definitions()
+            .find_map(|def| match def {
+                cst::Definition::OperationDefinition(op) => Some(op),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    fn first_field(tree: &apollo_parser::SyntaxTree) -> cst::Field {
+        let op = first_operation(tree);
+        match op.selection_set().unwrap().selections().next().unwrap() {
+            cst::Selection::Field(field) => field,
+            _ => panic!("expected field"),
+        }
+    }
+
+    #[test]
+    fn test_insert_field_multiline_keeps_indentation() {
+        let source = "query Q {\n  user {\n    id # the id\n  }\n}\n";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let selection_set = first_field(&tree).selection_set().unwrap();
+        let edit = insert_field(source, &selection_set, "name").unwrap();
+        assert_eq!(
+            apply(source, &edit),
+            "query Q {\n  user {\n    id # the id\n    name\n  }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_insert_field_single_line_and_empty() {
+        let source = "query Q { user { id } }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let selection_set = first_field(&tree).selection_set().unwrap();
+        let edit = insert_field(source, &selection_set, "name").unwrap();
+        assert_eq!(apply(source, &edit), "query Q { user { id name } }");
+
+        let source = "query Q { user {} }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let selection_set = first_field(&tree).selection_set().unwrap();
+        let edit = insert_field(source, &selection_set, "id").unwrap();
+        assert_eq!(apply(source, &edit), "query Q { user { id } }");
+    }
+
+    #[test]
+    fn test_add_argument() {
+        let source = "query Q { user { id } }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let edit = add_argument(source, &first_field(&tree), "id", "1").unwrap();
+        assert_eq!(apply(source, &edit), "query Q { user(id: 1) { id } }");
+
+        let source = "query Q { user(id: 1) { id } }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let edit = add_argument(source, &first_field(&tree), "locale", "\"en\"").unwrap();
+        assert_eq!(
+            apply(source, &edit),
+            "query Q { user(id: 1, locale: \"en\") { id } }"
+        );
+    }
+
+    #[test]
+    fn test_add_argument_after_trailing_comment() {
+        let source = "query Q {\n  user(\n    id: 1 # the id\n  ) { id }\n}\n";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let edit = add_argument(source, &first_field(&tree), "locale", "\"en\"").unwrap();
+        assert_eq!(
+            apply(source, &edit),
+            "query Q {\n  user(\n    id: 1 # the id\n    locale: \"en\"\n  ) { id }\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_directive() {
+        let source = "query Q { user @a { id } }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let field = first_field(&tree);
+        let edit = add_directive(source, field.syntax(), "include(if: $x)");
+        assert_eq!(
+            apply(source, &edit),
+            "query Q { user @a @include(if: $x) { id } }"
+        );
+
+        let source = "type User {\n  id: ID!\n}\n";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let def = tree.document().definitions().next().unwrap();
+        let edit = add_directive(source, def.syntax(), "key(fields: \"id\")");
+        assert_eq!(
+            apply(source, &edit),
+            "type User @key(fields: \"id\") {\n  id: ID!\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_add_variable_definition() {
+        let cases = [
+            ("query Q { a }", "query Q($id: ID!) { a }"),
+            ("query Q($a: Int) { a }", "query Q($a: Int, $id: ID!) { a }"),
+            ("query { a }", "query($id: ID!) { a }"),
+            ("{ a }", "query($id: ID!) { a }"),
+        ];
+        for (source, expected) in cases {
+            let tree = apollo_parser::Parser::new(source).parse();
+            let edit = add_variable_definition(source, &first_operation(&tree), "$id: ID!");
+            assert_eq!(apply(source, &edit), expected, "for {source:?}");
+        }
+    }
+
+    #[test]
+    fn test_add_variable_definition_after_trailing_comment() {
+        let source = "query Q(\n  $a: Int # the a\n) { a }";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let edit = add_variable_definition(source, &first_operation(&tree), "$id: ID!");
+        assert_eq!(
+            apply(source, &edit),
+            "query Q(\n  $a: Int # the a\n  $id: ID!\n) { a }"
+        );
+    }
+
+    #[test]
+    fn test_remove_definition() {
+        let source = "# keep\nquery A { a }\n\nquery B { b }\n\nquery C { c }\n";
+        let tree = apollo_parser::Parser::new(source).parse();
+        let defs: Vec<_> = tree.document().definitions().collect();
+
+        let edit = remove_definition(source, &defs[1]);
+        assert_eq!(
+            apply(source, &edit),
+            "# keep\nquery A { a }\n\nquery C { c }\n"
+        );
+
+        let edit = remove_definition(source, &defs[2]);
+        assert_eq!(
+            apply(source, &edit),
+            "# keep\nquery A { a }\n\nquery B { b }\n"
+        );
+    }
+}
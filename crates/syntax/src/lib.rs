@@ -23,11 +23,23 @@
 //! - `ast`: AST for semantic analysis
 //! - `line_offset`: Line number in original file (0 for pure GraphQL)
 //! - `source`: The GraphQL source text
+//!
+//! Positions inside a block are mapped to and from the original file with
+//! [`BlockOrigin`]. Columns are UTF-16 code units throughout, as in LSP, so
+//! Unicode anywhere in the host file doesn't shift them.
 
 use graphql_base_db::{DocumentKind, FileContent, FileMetadata, Language};
 pub use graphql_types::SourceSpan;
+use std::borrow::Cow;
 use std::sync::Arc;
 
+pub mod edit;
+mod nullability;
+mod pragma;
+
+pub use nullability::{field_designator, find_designators, Nullability, NullabilityDesignator};
+pub use pragma::{file_pragmas, FilePragmas, PragmaValue};
+
 /// A parse error with position information
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParseError {
@@ -53,6 +65,8 @@ pub struct Parse {
     blocks: Vec<ExtractedBlock>,
     /// Parse errors (syntax errors only, not validation)
     errors: Vec<ParseError>,
+    /// Why extraction gave up on this file, if it did. Such a file has no blocks.
+    skipped: Option<String>,
 }
 
 /// A GraphQL block extracted from a TypeScript/JavaScript file
@@ -72,6 +86,9 @@ pub struct ExtractedBlock {
     pub character: u32,
     /// File-level byte range of the enclosing TS/JS declaration, if applicable
     pub declaration_range: Option<(usize, usize)>,
+    /// Client-controlled nullability designators, blanked out of `tree` and
+    /// `ast` but kept in `source`. Empty unless the project opts in.
+    pub designators: Vec<NullabilityDesignator>,
 }
 
 /// A reference to a GraphQL document within a parsed file.
@@ -95,6 +112,10 @@ pub struct DocumentRef<'a> {
     pub source: &'a str,
     /// File-level byte range of the enclosing TS/JS declaration, if applicable
     pub declaration_range: Option<(usize, usize)>,
+    /// Client-controlled nullability designators in `source`
+    pub designators: &'a [NullabilityDesignator],
+    /// Shared handle to `source`, so spans and line indexes don't copy it
+    shared_source: &'a Arc<str>,
 }
 
 impl DocumentRef<'_> {
@@ -111,12 +132,71 @@ impl DocumentRef<'_> {
             line_offset: self.line_offset,
             byte_offset: self.byte_offset,
             source: if self.byte_offset > 0 {
-                Some(Arc::from(self.source))
+                Some(self.shared_source())
             } else {
                 None
             },
         }
     }
+
+    /// The document's source text as a shared `Arc<str>` (no copy).
+    #[must_use]
+    pub fn shared_source(&self) -> Arc<str> {
+        Arc::clone(self.shared_source)
+    }
+
+    /// Build a [`LineIndex`] over this document's source without copying it.
+    #[must_use]
+    pub fn line_index(&self) -> LineIndex {
+        LineIndex::from_shared(self.shared_source())
+    }
+
+    /// Where this document starts in its file.
+    #[must_use]
+    pub const fn origin(&self) -> BlockOrigin {
+        BlockOrigin {
+            line: self.line_offset,
+            column: self.column_offset,
+        }
+    }
+}
+
+/// Where a GraphQL block starts in the file it was extracted from.
+///
+/// Maps positions between the block and the file. `column` is in UTF-16 code
+/// units, as measured by extraction, and only shifts the block's first line:
+/// later lines start at column 0 in both. Pure GraphQL files have the default
+/// origin, which maps every position to itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BlockOrigin {
+    /// Line of the block's first character (0-based)
+    pub line: u32,
+    /// UTF-16 column of the block's first character
+    pub column: u32,
+}
+
+impl BlockOrigin {
+    /// Translate a block-relative line and UTF-16 column to the file.
+    #[must_use]
+    pub const fn to_file(self, line: u32, character: u32) -> (u32, u32) {
+        if line == 0 {
+            (self.line, self.column + character)
+        } else {
+            (self.line + line, character)
+        }
+    }
+
+    /// Translate a file line and UTF-16 column into the block.
+    ///
+    /// Positions before the block's first character clamp to its start.
+    #[must_use]
+    pub const fn to_block(self, line: u32, character: u32) -> (u32, u32) {
+        if line <= self.line {
+            (0, character.saturating_sub(self.column))
+        } else {
+            (line - self.line, character)
+        }
+    }
 }
 
 impl Parse {
@@ -140,6 +220,8 @@ impl Parse {
             byte_offset: block.offset,
             source: &block.source,
             declaration_range: block.declaration_range,
+            designators: &block.designators,
+            shared_source: &block.source,
         })
     }
 
@@ -166,29 +248,78 @@ impl Parse {
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
+
+    /// Returns the reason extraction skipped this file, when it ran over its
+    /// time or size budget.
+    #[must_use]
+    pub fn skipped_reason(&self) -> Option<&str> {
+        self.skipped.as_deref()
+    }
 }
 
 /// Parse a file into a syntax tree
 /// This is the foundation - all semantic analysis builds on this
-#[salsa::tracked]
+///
+/// Results are kept in an LRU cache that is unbounded until a host sets a
+/// capacity with [`set_parse_cache_capacity`].
+#[salsa::tracked(lru = 0)]
 pub fn parse(
     db: &dyn GraphQLSyntaxDatabase,
     content: FileContent,
     metadata: FileMetadata,
 ) -> Parse {
     let uri = metadata.uri(db);
+    let nullability = db.client_controlled_nullability();
 
     #[cfg(feature = "extract")]
     if metadata.language(db).requires_extraction() {
-        return extract_and_parse(db, &content.text(db), uri.as_str());
+        return extract_and_parse(
+            db,
+            &content.text(db),
+            metadata.language(db),
+            uri.as_str(),
+            nullability,
+        );
     }
     // When the extract feature is off (wasm), all files parse as raw GraphQL.
-    parse_graphql(&content.text(db), uri.as_str())
+    parse_graphql(content.text(db), uri.as_str(), nullability)
+}
+
+/// Keep at most `capacity` parse results in memory, evicting the least
+/// recently used; `0` keeps every result.
+///
+/// Evicted files are re-parsed the next time a query needs them. Results
+/// derived from a parse, like a file's structure, stay cached.
+pub fn set_parse_cache_capacity(db: &mut dyn GraphQLSyntaxDatabase, capacity: usize) {
+    parse::set_lru_capacity(db, capacity);
+}
+
+/// Top-level bindings of a JavaScript/TypeScript file, for following a
+/// reference to a GraphQL document constant to the block it's defined with
+#[cfg(feature = "extract")]
+#[salsa::tracked]
+pub fn module_symbols(
+    db: &dyn GraphQLSyntaxDatabase,
+    content: FileContent,
+    metadata: FileMetadata,
+) -> Arc<graphql_extract::ModuleSymbols> {
+    let uri = metadata.uri(db);
+    match graphql_extract::module_symbols(&content.text(db), metadata.language(db), uri.as_str()) {
+        Ok(symbols) => Arc::new(symbols),
+        Err(e) => {
+            tracing::debug!(path = uri.as_str(), error = ?e, "Failed to collect module symbols");
+            Arc::default()
+        }
+    }
 }
 
 /// Parse pure GraphQL content into a single block at offset 0
-fn parse_graphql(content: &str, uri: &str) -> Parse {
-    let parser = apollo_parser::Parser::new(content);
+///
+/// Takes the file's `Arc<str>` so the block shares the input text rather
+/// than holding a second copy of the whole file.
+fn parse_graphql(content: Arc<str>, uri: &str, nullability: bool) -> Parse {
+    let (parsed, designators) = without_designators(&content, nullability);
+    let parser = apollo_parser::Parser::new(&parsed);
     let tree = parser.parse();
 
     let errors: Vec<ParseError> = tree
@@ -199,7 +330,7 @@ fn parse_graphql(content: &str, uri: &str) -> Parse {
         })
         .collect();
 
-    let ast = match apollo_compiler::ast::Document::parse(content, uri) {
+    let ast = match apollo_compiler::ast::Document::parse(&*parsed, uri) {
         Ok(doc) => doc,
         // apollo-parser already reports syntax errors with correct byte offsets;
         // apollo-compiler's parse errors are duplicates without usable positions
@@ -208,25 +339,33 @@ fn parse_graphql(content: &str, uri: &str) -> Parse {
 
     // Create a single block representing the entire file at offset 0
     let block = ExtractedBlock {
-        source: Arc::from(content),
+        source: Arc::clone(&content),
         tree: Arc::new(tree),
         ast: Arc::new(ast),
         offset: 0,
         line: 0,
         character: 0,
         declaration_range: None,
+        designators,
     };
 
     Parse {
         blocks: vec![block],
         errors,
+        skipped: None,
     }
 }
 
-/// Extract GraphQL from TypeScript/JavaScript and parse each block
+/// Extract GraphQL from a host-language file and parse each block
 #[cfg(feature = "extract")]
-fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -> Parse {
-    use graphql_extract::{extract_from_source, ExtractConfig, Language};
+fn extract_and_parse(
+    db: &dyn GraphQLSyntaxDatabase,
+    content: &str,
+    language: Language,
+    uri: &str,
+    nullability: bool,
+) -> Parse {
+    use graphql_extract::{extract_from_source_with_budget, ExtractConfig, ExtractError};
 
     tracing::debug!(content_len = content.len(), "extract_and_parse called");
 
@@ -240,12 +379,41 @@ fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -
         "Using extract config"
     );
 
-    let language = Language::TypeScript;
-    let extracted = match extract_from_source(content, language, &config, uri) {
+    // TypeScript's grammar is a superset of JavaScript's, so JS files share it
+    let language = if language.is_js_family() {
+        Language::TypeScript
+    } else {
+        language
+    };
+    let budget = config.budget();
+    let extracted = match extract_from_source_with_budget(content, language, &config, uri, &budget)
+    {
         Ok(blocks) => {
             tracing::debug!(blocks_extracted = blocks.len(), "Extraction successful");
             blocks
         }
+        Err(ExtractError::BudgetExceeded { reason, .. }) => {
+            tracing::warn!(path = uri, reason = %reason, "Skipping file over extraction budget");
+            return Parse {
+                skipped: Some(reason),
+                ..Parse::default()
+            };
+        }
+        Err(ExtractError::TimedOut { timeout, .. }) => {
+            tracing::warn!(
+                path = uri,
+                ?timeout,
+                "Skipping file whose extraction timed out"
+            );
+            // The skip is memoized like any other parse: the host-language
+            // parse can't be interrupted, so retrying on every revision
+            // would stall analysis for the whole budget again and again.
+            // Changing the file's content or the extract config retries it.
+            return Parse {
+                skipped: Some(format!("extraction took longer than {timeout:?}")),
+                ..Parse::default()
+            };
+        }
         Err(e) => {
             tracing::error!(path = uri, error = ?e, "Extraction failed");
             Vec::new()
@@ -256,7 +424,9 @@ fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -
     let mut all_errors = Vec::new();
 
     for block in extracted {
-        let parser = apollo_parser::Parser::new(&block.source);
+        let source: Arc<str> = Arc::from(block.source);
+        let (parsed, designators) = without_designators(&source, nullability);
+        let parser = apollo_parser::Parser::new(&parsed);
         let tree = parser.parse();
 
         let block_offset = block.location.offset;
@@ -265,7 +435,7 @@ fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -
             offset: block_offset + e.index(),
         }));
 
-        let ast = match apollo_compiler::ast::Document::parse(&block.source, uri) {
+        let ast = match apollo_compiler::ast::Document::parse(&*parsed, uri) {
             Ok(doc) => doc,
             // apollo-parser already reports syntax errors with correct byte offsets;
             // apollo-compiler's parse errors are duplicates without usable positions
@@ -273,20 +443,39 @@ fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -
         };
 
         blocks.push(ExtractedBlock {
-            source: Arc::from(block.source.as_str()),
+            source: Arc::clone(&source),
             tree: Arc::new(tree),
             ast: Arc::new(ast),
             offset: block.location.offset,
             line: block.location.range.start.line,
             character: block.location.range.start.character,
             declaration_range: block.declaration_range,
+            designators,
         });
     }
 
     Parse {
         blocks,
         errors: all_errors,
+        skipped: None,
+    }
+}
+
+/// The text to hand the parsers, with client-controlled nullability
+/// designators blanked out when `nullability` is on, and the designators
+fn without_designators(
+    source: &str,
+    nullability: bool,
+) -> (Cow<'_, str>, Vec<NullabilityDesignator>) {
+    if !nullability {
+        return (Cow::Borrowed(source), Vec::new());
     }
+    let designators = find_designators(source);
+    if designators.is_empty() {
+        return (Cow::Borrowed(source), designators);
+    }
+    let blanked = nullability::blank_designators(source, &designators);
+    (Cow::Owned(blanked), designators)
 }
 
 /// Line index for a file (for position conversions)
@@ -296,8 +485,9 @@ fn extract_and_parse(db: &dyn GraphQLSyntaxDatabase, content: &str, uri: &str) -
 pub struct LineIndex {
     /// Byte offset of the start of each line
     line_starts: Vec<usize>,
-    /// Source text, needed for UTF-16 column conversions
-    source: String,
+    /// Source text, needed for UTF-16 column conversions. Shared with the
+    /// file input when built via [`line_index`] so large files aren't copied.
+    source: Arc<str>,
 }
 
 /// Check if GraphQL content contains schema definitions
@@ -546,6 +736,14 @@ pub fn determine_file_kind_from_content(path: &str, content: &str) -> (Language,
         Language::TypeScript
     } else if has_extension(path, ".js") || has_extension(path, ".jsx") {
         Language::JavaScript
+    } else if has_extension(path, ".vue") {
+        Language::Vue
+    } else if has_extension(path, ".svelte") {
+        Language::Svelte
+    } else if has_extension(path, ".py") {
+        Language::Python
+    } else if has_extension(path, ".rb") {
+        Language::Ruby
     } else {
         Language::GraphQL
     };
@@ -567,17 +765,17 @@ impl LineIndex {
     /// Create a new line index from source text
     #[must_use]
     pub fn new(text: &str) -> Self {
-        let mut line_starts = vec![0];
-
-        for (i, c) in text.char_indices() {
-            if c == '\n' {
-                line_starts.push(i + 1);
-            }
-        }
+        Self::from_shared(Arc::from(text))
+    }
 
+    /// Create a line index that shares `text` instead of copying it.
+    #[must_use]
+    pub fn from_shared(text: Arc<str>) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
         Self {
             line_starts,
-            source: text.to_string(),
+            source: text,
         }
     }
 
@@ -603,11 +801,8 @@ impl LineIndex {
     #[must_use]
     pub fn line_col(&self, offset: usize) -> (usize, usize) {
         let safe_offset = self.clamp_offset_to_source(offset);
-        let (line, byte_col) = self.line_col_bytes(safe_offset);
-        let line_start = self.line_starts[line];
-        let line_text = &self.source[line_start..line_start + byte_col];
-        let utf16_col: usize = line_text.chars().map(char::len_utf16).sum();
-        (line, utf16_col)
+        let (line, _) = self.line_col_bytes(safe_offset);
+        (line, self.utf16_len(self.line_starts[line], safe_offset))
     }
 
     /// Clamp a byte offset into a value that is guaranteed to be in-bounds and
@@ -684,6 +879,27 @@ impl LineIndex {
         Some(line_start + byte_offset)
     }
 
+    /// Length of the text between two byte offsets in UTF-16 code units
+    #[must_use]
+    pub fn utf16_len(&self, start: usize, end: usize) -> usize {
+        let start = self.clamp_offset_to_source(start);
+        let end = self.clamp_offset_to_source(end).max(start);
+        self.source[start..end].chars().map(char::len_utf16).sum()
+    }
+
+    /// Origin of a block that starts at `byte_offset` in this file.
+    ///
+    /// For spans that only carry the block's byte offset, this recovers the
+    /// first-line column that [`DocumentRef::origin`] would give.
+    #[must_use]
+    pub fn block_origin(&self, byte_offset: usize) -> BlockOrigin {
+        let (line, column) = self.line_col(byte_offset);
+        BlockOrigin {
+            line: line as u32,
+            column: column as u32,
+        }
+    }
+
     /// Get the byte offset of the start of a line
     #[must_use]
     pub fn line_start(&self, line: usize) -> Option<usize> {
@@ -700,7 +916,7 @@ impl LineIndex {
 /// Compute line index for a file (for position conversions)
 #[salsa::tracked]
 pub fn line_index(db: &dyn GraphQLSyntaxDatabase, content: FileContent) -> Arc<LineIndex> {
-    Arc::new(LineIndex::new(&content.text(db)))
+    Arc::new(LineIndex::from_shared(content.text(db)))
 }
 
 /// The salsa database trait for syntax queries
@@ -713,6 +929,12 @@ pub trait GraphQLSyntaxDatabase: salsa::Database {
     fn extract_config(&self) -> Option<Arc<graphql_extract::ExtractConfig>> {
         None
     }
+
+    /// Whether documents may use client-controlled nullability designators
+    /// (`field!`, `field?`). Off by default, where they're syntax errors.
+    fn client_controlled_nullability(&self) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -745,7 +967,7 @@ mod tests {
     #[test]
     fn test_parse_graphql() {
         let content = "type User { id: ID! }";
-        let parse = parse_graphql(content, "test.graphql");
+        let parse = parse_graphql(content.into(), "test.graphql", false);
 
         assert!(!parse.has_errors());
         assert_eq!(parse.document_count(), 1);
@@ -756,10 +978,23 @@ mod tests {
         assert_eq!(docs[0].source, content);
     }
 
+    #[test]
+    fn test_parse_graphql_shares_source() {
+        let content: Arc<str> = Arc::from("type User { id: ID! }\ntype Post { id: ID! }");
+        let parse = parse_graphql(content.clone(), "test.graphql", false);
+
+        let doc = parse.documents().next().unwrap();
+        assert!(Arc::ptr_eq(&doc.shared_source(), &content));
+
+        let index = doc.line_index();
+        assert_eq!(index, LineIndex::new(&content));
+        assert_eq!(index.line_col(22), (1, 0));
+    }
+
     #[test]
     fn test_parse_graphql_with_error() {
         let content = "type User {";
-        let parse = parse_graphql(content, "test.graphql");
+        let parse = parse_graphql(content.into(), "test.graphql", false);
 
         assert!(parse.has_errors());
     }
@@ -890,6 +1125,38 @@ mod tests {
         assert_eq!(index.utf16_to_offset(0, 3), Some(5));
     }
 
+    #[test]
+    fn test_utf16_len() {
+        let index = LineIndex::new("\"\u{1F680} \u{7528}\"");
+        // quote(1) + rocket(2) + space(1) + CJK(1) + quote(1)
+        assert_eq!(index.utf16_len(0, index.source.len()), 6);
+        assert_eq!(index.utf16_len(1, 5), 2);
+    }
+
+    #[test]
+    fn test_block_origin_round_trip() {
+        // `const q = /* 🚀 */ gql`query {` puts the block at UTF-16 column 23,
+        // two columns short of its byte column
+        let file = "\n\n\nconst q = /* 🚀 */ gql`query {";
+        let origin = LineIndex::new(file).block_origin(file.find("query").unwrap());
+        assert_eq!(
+            origin,
+            BlockOrigin {
+                line: 3,
+                column: 23,
+            }
+        );
+        assert_eq!(origin.to_file(0, 2), (3, 25));
+        assert_eq!(origin.to_file(2, 4), (5, 4));
+        assert_eq!(origin.to_block(3, 25), (0, 2));
+        assert_eq!(origin.to_block(5, 4), (2, 4));
+        // Before the block's first character
+        assert_eq!(origin.to_block(3, 10), (0, 0));
+
+        assert_eq!(BlockOrigin::default().to_file(0, 7), (0, 7));
+        assert_eq!(BlockOrigin::default().to_block(4, 7), (4, 7));
+    }
+
     #[test]
     fn test_line_col_ascii_same_as_byte() {
         let text = "query {\n  user\n}";
@@ -1078,6 +1345,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_determine_file_kind_single_file_components() {
+        let content = "<script setup>\nconst query = gql`query { user { id } }`;\n</script>";
+        assert_eq!(
+            determine_file_kind_from_content("Component.vue", content),
+            (Language::Vue, DocumentKind::Executable)
+        );
+        assert_eq!(
+            determine_file_kind_from_content("Component.svelte", content),
+            (Language::Svelte, DocumentKind::Executable)
+        );
+    }
+
+    #[test]
+    fn test_determine_file_kind_python_and_ruby() {
+        assert_eq!(
+            determine_file_kind_from_content("queries.py", "Q = gql('query { a }')"),
+            (Language::Python, DocumentKind::Executable)
+        );
+        assert_eq!(
+            determine_file_kind_from_content("queries.rb", "Q = <<~GRAPHQL\nquery { a }\nGRAPHQL"),
+            (Language::Ruby, DocumentKind::Executable)
+        );
+    }
+
     #[test]
     fn test_determine_file_kind_schema() {
         let content = "type User { id: ID! }";
@@ -1107,7 +1399,7 @@ mod tests {
     #[test]
     fn test_documents_iterator_pure_graphql() {
         let content = "type User { id: ID! }\ntype Post { id: ID! }";
-        let parse = parse_graphql(content, "test.graphql");
+        let parse = parse_graphql(content.into(), "test.graphql", false);
 
         let docs: Vec<_> = parse.documents().collect();
         assert_eq!(docs.len(), 1);
@@ -1134,6 +1426,7 @@ mod tests {
                     line: 5,
                     character: 10,
                     declaration_range: None,
+                    designators: Vec::new(),
                 },
                 ExtractedBlock {
                     source: Arc::from("query Q2 { post { id } }"),
@@ -1146,9 +1439,11 @@ mod tests {
                     line: 10,
                     character: 15,
                     declaration_range: None,
+                    designators: Vec::new(),
                 },
             ],
             errors: vec![],
+            skipped: None,
         };
 
         let docs: Vec<_> = parse.documents().collect();
@@ -1166,7 +1461,7 @@ mod tests {
     #[test]
     fn test_documents_iterator_single_block() {
         let content = "type User { id: ID! }";
-        let parse = parse_graphql(content, "test.graphql");
+        let parse = parse_graphql(content.into(), "test.graphql", false);
 
         assert_eq!(parse.document_count(), 1);
         assert!(!parse.is_empty());
@@ -1183,6 +1478,7 @@ mod tests {
         let parse = Parse {
             blocks: vec![],
             errors: vec![],
+            skipped: None,
         };
 
         assert!(parse.is_empty());
@@ -0,0 +1,245 @@
+//! Client-controlled nullability designators.
+//!
+//! The client-controlled nullability proposal lets an operation override a
+//! field's nullability with a designator after the field's name or
+//! arguments: `name!` makes the field required, `name?` makes it optional.
+//! apollo-parser doesn't know the syntax, so when a project opts in, each
+//! designator is recorded and replaced with a space before the block is
+//! parsed. The tree then parses as plain GraphQL with every byte offset
+//! unchanged, and features that care about nullability look designators up
+//! by position.
+//!
+//! Only selection sets of executable definitions are scanned. There, a `!`
+//! or `?` outside an argument list can't mean anything else, so every one is
+//! recorded; [`field_designator`] tells which of them follow a field.
+
+use apollo_parser::cst::{self, CstNode};
+
+/// The nullability a designator asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Nullability {
+    /// `!`: the field is non-null in the response
+    Required,
+    /// `?`: the field may be null in the response
+    Optional,
+}
+
+impl Nullability {
+    /// The designator as written
+    #[must_use]
+    pub const fn symbol(self) -> char {
+        match self {
+            Self::Required => '!',
+            Self::Optional => '?',
+        }
+    }
+}
+
+/// A `!` or `?` written in a selection set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NullabilityDesignator {
+    /// Byte offset of the designator in its block
+    pub offset: usize,
+    pub nullability: Nullability,
+}
+
+/// Keywords that start a type system definition
+const TYPE_SYSTEM_KEYWORDS: &[&str] = &[
+    "schema",
+    "scalar",
+    "type",
+    "interface",
+    "union",
+    "enum",
+    "input",
+    "directive",
+    "extend",
+];
+
+/// Find the nullability designators in the selection sets of `source`.
+#[must_use]
+pub fn find_designators(source: &str) -> Vec<NullabilityDesignator> {
+    let bytes = source.as_bytes();
+    let mut designators = Vec::new();
+    let mut braces = 0usize;
+    let mut parens = 0usize;
+    // Whether the current top-level definition is executable, once its
+    // first keyword or its opening brace has been seen
+    let mut executable: Option<bool> = None;
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match byte {
+            b'#' => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            b'"' => {
+                i = string_end(bytes, i);
+                continue;
+            }
+            b'{' => {
+                if braces == 0 {
+                    executable.get_or_insert(true);
+                }
+                braces += 1;
+            }
+            b'}' => {
+                braces = braces.saturating_sub(1);
+                if braces == 0 && parens == 0 {
+                    executable = None;
+                }
+            }
+            b'(' => parens += 1,
+            b')' => parens = parens.saturating_sub(1),
+            b'!' | b'?' if braces > 0 && parens == 0 && executable == Some(true) => {
+                designators.push(NullabilityDesignator {
+                    offset: i,
+                    nullability: if byte == b'!' {
+                        Nullability::Required
+                    } else {
+                        Nullability::Optional
+                    },
+                });
+            }
+            b'_' | b'a'..=b'z' | b'A'..=b'Z' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i] == b'_' || bytes[i].is_ascii_alphanumeric()) {
+                    i += 1;
+                }
+                if braces == 0 && parens == 0 && executable.is_none() {
+                    let word = &source[start..i];
+                    executable = Some(!TYPE_SYSTEM_KEYWORDS.contains(&word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    designators
+}
+
+/// `source` with each designator replaced by a space, so it parses as
+/// plain GraphQL at the same offsets
+pub(crate) fn blank_designators(source: &str, designators: &[NullabilityDesignator]) -> String {
+    let mut blanked = source.to_string();
+    for designator in designators {
+        blanked.replace_range(designator.offset..=designator.offset, " ");
+    }
+    blanked
+}
+
+/// The designator written after `field`'s name or arguments, if any.
+///
+/// `source` is the block the field was parsed from and `designators` the
+/// block's designators.
+#[must_use]
+pub fn field_designator(
+    source: &str,
+    designators: &[NullabilityDesignator],
+    field: &cst::Field,
+) -> Option<NullabilityDesignator> {
+    let end = match field.arguments() {
+        Some(arguments) => arguments.syntax().text_range().end(),
+        None => field.name()?.syntax().text_range().end(),
+    };
+    let end: usize = end.into();
+    let rest = source.get(end..)?;
+    let offset = end + (rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r', ',']).len());
+    designators
+        .iter()
+        .find(|designator| designator.offset == offset)
+        .copied()
+}
+
+/// Where the string starting at `start` ends, just past its closing quote.
+/// An unterminated string ends at its line's end.
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    if bytes[start..].starts_with(b"\"\"\"") {
+        let mut i = start + 3;
+        while i < bytes.len() {
+            if bytes[i..].starts_with(b"\\\"\"\"") {
+                i += 4;
+            } else if bytes[i..].starts_with(b"\"\"\"") {
+                return i + 3;
+            } else {
+                i += 1;
+            }
+        }
+        return bytes.len();
+    }
+
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            b'\n' => return i,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(source: &str) -> Vec<(usize, char)> {
+        find_designators(source)
+            .iter()
+            .map(|d| (d.offset, d.nullability.symbol()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_designators_after_fields() {
+        let source = "query Q($id: ID!) { user(id: $id)! { name? email } }";
+        let designators = symbols(source);
+        assert_eq!(designators.len(), 2);
+        assert_eq!(&source[designators[0].0..=designators[0].0], "!");
+        assert_eq!(designators[0].1, '!');
+        assert_eq!(designators[1].1, '?');
+    }
+
+    #[test]
+    fn ignores_type_system_definitions_strings_and_comments() {
+        let source = r#"
+type User { name: String! friends(first: Int!): [User!]! }
+query { user { name # really?
+  bio(format: "{ md! }")
+} }
+"#;
+        assert!(symbols(source).is_empty());
+    }
+
+    #[test]
+    fn blanking_keeps_offsets() {
+        let source = "{ user! { name? } }";
+        let designators = find_designators(source);
+        let blanked = blank_designators(source, &designators);
+        assert_eq!(blanked, "{ user  { name  } }");
+        assert_eq!(blanked.len(), source.len());
+    }
+
+    #[test]
+    fn field_designator_skips_whitespace() {
+        let source = "{ user(id: 1) ! { name } other }";
+        let designators = find_designators(source);
+        let tree = apollo_parser::Parser::new(&blank_designators(source, &designators)).parse();
+        let fields: Vec<cst::Field> = tree
+            .document()
+            .syntax()
+            .descendants()
+            .filter_map(cst::Field::cast)
+            .collect();
+        let user = field_designator(source, &designators, &fields[0]);
+        assert_eq!(user.map(|d| d.nullability), Some(Nullability::Required));
+        assert!(field_designator(source, &designators, &fields[1]).is_none());
+    }
+}
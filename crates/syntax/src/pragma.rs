@@ -0,0 +1,144 @@
+//! File-level pragmas.
+//!
+//! A pragma is a comment in the file's leading comment block that opts the
+//! file in or out of analysis features without touching project config:
+//!
+//! ```graphql
+//! # graphql-analyzer: disable-lints
+//! # graphql-analyzer: schema-variant staging
+//! query GetUser { ... }
+//! ```
+//!
+//! TypeScript/JavaScript files use `//` comments at the top of the file.
+//! Only the leading comment block is scanned, so a pragma can't be switched
+//! on by a comment buried inside a query.
+
+use crate::GraphQLSyntaxDatabase;
+use graphql_base_db::FileContent;
+use std::sync::Arc;
+
+const PRAGMA_PREFIX: &str = "graphql-analyzer:";
+
+/// Pragmas declared at the top of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FilePragmas {
+    /// `disable-lints`: skip all lint rules for this file.
+    pub disable_lints: bool,
+    /// `schema-variant <name>`: validate against the named schema variant
+    /// instead of the project schema.
+    pub schema_variant: Option<PragmaValue>,
+    /// Pragma comments that didn't parse, for reporting.
+    pub unknown: Vec<PragmaValue>,
+}
+
+/// A pragma argument (or unrecognized pragma text) with its location.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PragmaValue {
+    pub value: Arc<str>,
+    /// 0-based line of the pragma comment.
+    pub line: u32,
+    /// Byte range of `value` within the file.
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Parse file-level pragmas from a file's leading comment block.
+///
+/// Derived from file content, so adding or removing a pragma takes effect
+/// on the next edit without re-registering the file.
+#[salsa::tracked]
+pub fn file_pragmas(db: &dyn GraphQLSyntaxDatabase, content: FileContent) -> Arc<FilePragmas> {
+    Arc::new(parse_pragmas(&content.text(db)))
+}
+
+fn parse_pragmas(source: &str) -> FilePragmas {
+    let mut pragmas = FilePragmas::default();
+    let mut line_start = 0;
+
+    for (line_num, line) in source.split('\n').enumerate() {
+        let this_line_start = line_start;
+        line_start += line.len() + 1;
+
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let Some(comment) = trimmed
+            .strip_prefix('#')
+            .or_else(|| trimmed.strip_prefix("//"))
+        else {
+            // First non-comment line ends the leading block
+            break;
+        };
+        let Some(body) = comment.trim_start().strip_prefix(PRAGMA_PREFIX) else {
+            continue;
+        };
+
+        let body = body.trim();
+        let body_start = this_line_start + line.find(body).unwrap_or(line.len());
+        let located = |text: &str| {
+            let start = body_start + body.find(text).unwrap_or(0);
+            PragmaValue {
+                value: Arc::from(text),
+                line: line_num as u32,
+                start,
+                end: start + text.len(),
+            }
+        };
+
+        let mut words = body.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("disable-lints"), None, _) => pragmas.disable_lints = true,
+            (Some("schema-variant"), Some(name), None) => {
+                pragmas.schema_variant = Some(located(name));
+            }
+            _ => pragmas.unknown.push(located(body)),
+        }
+    }
+
+    pragmas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_leading_pragmas() {
+        let source = "# graphql-analyzer: disable-lints\n\
+                      # graphql-analyzer: schema-variant staging\n\
+                      query Q { a }\n";
+        let pragmas = parse_pragmas(source);
+        assert!(pragmas.disable_lints);
+        let variant = pragmas.schema_variant.unwrap();
+        assert_eq!(&*variant.value, "staging");
+        assert_eq!(variant.line, 1);
+        assert_eq!(&source[variant.start..variant.end], "staging");
+        assert!(pragmas.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_ignores_pragmas_after_first_definition() {
+        let source = "query Q { a }\n# graphql-analyzer: disable-lints\n";
+        assert_eq!(parse_pragmas(source), FilePragmas::default());
+    }
+
+    #[test]
+    fn test_accepts_line_comments_and_plain_comments() {
+        let source = "// Generated by hand\n\
+                      // graphql-analyzer: disable-lints\n\
+                      import { gql } from 'graphql-tag';\n";
+        assert!(parse_pragmas(source).disable_lints);
+    }
+
+    #[test]
+    fn test_collects_unknown_pragmas() {
+        let source = "# graphql-analyzer: disable-everything\n\
+                      # graphql-analyzer: schema-variant\n";
+        let pragmas = parse_pragmas(source);
+        assert!(pragmas.schema_variant.is_none());
+        let unknown: Vec<&str> = pragmas.unknown.iter().map(|p| &*p.value).collect();
+        assert_eq!(unknown, vec!["disable-everything", "schema-variant"]);
+        assert_eq!(pragmas.unknown[1].line, 1);
+    }
+}
@@ -36,6 +36,8 @@ pub mod queries {
     pub const FILE_FRAGMENTS: &str = "file_fragments";
     pub const FILE_OPERATIONS: &str = "file_operations";
     pub const SCHEMA_TYPES: &str = "schema_types";
+    pub const FILE_TYPE_KINDS: &str = "file_type_kinds";
+    pub const SCHEMA_TYPE_KINDS: &str = "schema_type_kinds";
     pub const ALL_FRAGMENTS: &str = "all_fragments";
     pub const ALL_OPERATIONS: &str = "all_operations";
     pub const FILE_LOOKUP: &str = "file_lookup";
@@ -73,6 +73,10 @@ pub enum Language {
     Svelte,
     /// Astro components (.astro)
     Astro,
+    /// Python (.py), strings passed to `gql(...)`
+    Python,
+    /// Ruby (.rb), `<<~GRAPHQL` heredocs
+    Ruby,
 }
 
 impl Language {
@@ -90,14 +94,16 @@ impl Language {
             "vue" => Some(Self::Vue),
             "svelte" => Some(Self::Svelte),
             "astro" => Some(Self::Astro),
+            "py" => Some(Self::Python),
+            "rb" => Some(Self::Ruby),
             _ => None,
         }
     }
 
     /// Check if this language requires extraction (vs. direct GraphQL parsing).
     ///
-    /// Returns `true` for languages where GraphQL is embedded in template literals
-    /// (TypeScript, JavaScript, Vue, Svelte, Astro).
+    /// Returns `true` for languages where GraphQL is embedded in host source
+    /// (TypeScript, JavaScript, Vue, Svelte, Astro, Python, Ruby).
     /// Returns `false` for pure GraphQL files.
     #[must_use]
     pub const fn requires_extraction(&self) -> bool {
@@ -211,6 +217,14 @@ mod tests {
             Language::from_path(&PathBuf::from("page.astro")),
             Some(Language::Astro)
         );
+        assert_eq!(
+            Language::from_path(&PathBuf::from("queries.py")),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            Language::from_path(&PathBuf::from("queries.rb")),
+            Some(Language::Ruby)
+        );
         assert_eq!(Language::from_path(&PathBuf::from("README.md")), None);
     }
 
@@ -222,6 +236,8 @@ mod tests {
         assert!(Language::Vue.requires_extraction());
         assert!(Language::Svelte.requires_extraction());
         assert!(Language::Astro.requires_extraction());
+        assert!(Language::Python.requires_extraction());
+        assert!(Language::Ruby.requires_extraction());
     }
 
     #[test]
@@ -232,6 +248,8 @@ mod tests {
         assert!(!Language::Vue.is_js_family());
         assert!(!Language::Svelte.is_js_family());
         assert!(!Language::Astro.is_js_family());
+        assert!(!Language::Python.is_js_family());
+        assert!(!Language::Ruby.is_js_family());
     }
 
     #[test]
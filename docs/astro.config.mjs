@@ -48,6 +48,10 @@ export default defineConfig({
               label: "Multi-Project Workspaces",
               slug: "configuration/multi-project",
             },
+            {
+              label: "Analyzer Settings",
+              slug: "configuration/analyzer-settings",
+            },
           ],
         },
         {
@@ -100,6 +104,11 @@ export default defineConfig({
             { label: "Rules Catalog", slug: "rules/catalog" },
             { label: "alphabetize", slug: "rules/alphabetize" },
             { label: "descriptionStyle", slug: "rules/descriptionStyle" },
+            { label: "enumValueConvention", slug: "rules/enumValueConvention" },
+            {
+              label: "fieldNamingConvention",
+              slug: "rules/fieldNamingConvention",
+            },
             { label: "inputName", slug: "rules/inputName" },
             {
               label: "loneExecutableDefinition",
@@ -125,6 +134,10 @@ export default defineConfig({
             },
             { label: "noTypenamePrefix", slug: "rules/noTypenamePrefix" },
             { label: "noUnreachableTypes", slug: "rules/noUnreachableTypes" },
+            {
+              label: "noUnsupportedIncrementalDelivery",
+              slug: "rules/noUnsupportedIncrementalDelivery",
+            },
             { label: "noUnusedFields", slug: "rules/noUnusedFields" },
             { label: "noUnusedFragments", slug: "rules/noUnusedFragments" },
             { label: "noUnusedVariables", slug: "rules/noUnusedVariables" },
@@ -155,6 +168,7 @@ export default defineConfig({
             { label: "restyFieldNames", slug: "rules/restyFieldNames" },
             { label: "selectionSetDepth", slug: "rules/selectionSetDepth" },
             { label: "strictIdInTypes", slug: "rules/strictIdInTypes" },
+            { label: "typeNamingConvention", slug: "rules/typeNamingConvention" },
             {
               label: "uniqueEnumValueNames",
               slug: "rules/uniqueEnumValueNames",
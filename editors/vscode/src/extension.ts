@@ -18,11 +18,14 @@ import {
   Uri,
   Position,
   Range,
+  SnippetString,
+  WorkspaceEdit,
   ThemeColor,
   env,
   version,
 } from "vscode";
 import {
+  DidChangeConfigurationNotification,
   LanguageClient,
   LanguageClientOptions,
   ServerOptions,
@@ -31,6 +34,9 @@ import {
   Trace,
   Location as LspLocation,
   Position as LspPosition,
+  Range as LspRange,
+  TextEdit as LspTextEdit,
+  WorkspaceEdit as LspWorkspaceEdit,
 } from "vscode-languageclient/node";
 import { findServerBinary } from "./binaryManager";
 import {
@@ -343,6 +349,41 @@ function syncTraceLevel(): void {
   client.setTrace(enabled ? Trace.Verbose : Trace.Off);
 }
 
+const FEATURES = [
+  "inlayHints",
+  "codeLens",
+  "semanticTokens",
+  "projectLints",
+  "complexityWarnings",
+] as const;
+
+/**
+ * The feature toggles the user set explicitly. Features left unset aren't
+ * sent, so the server falls back to `.graphqlanalyzer.toml`.
+ */
+function editorFeatures(): Record<string, boolean> {
+  const config = workspace.getConfiguration("graphql-analyzer.features");
+  const features: Record<string, boolean> = {};
+  for (const feature of FEATURES) {
+    const inspected = config.inspect<boolean>(feature);
+    const value =
+      inspected?.workspaceFolderValue ?? inspected?.workspaceValue ?? inspected?.globalValue;
+    if (value !== undefined) {
+      features[feature] = value;
+    }
+  }
+  return features;
+}
+
+function syncFeatures(): void {
+  if (!client || !client.isRunning()) {
+    return;
+  }
+  client.sendNotification(DidChangeConfigurationNotification.type, {
+    settings: { features: editorFeatures() },
+  });
+}
+
 async function startLanguageServer(context: ExtensionContext): Promise<void> {
   const config = workspace.getConfiguration("graphql-analyzer");
   const customPath = config.get<string>("server.path");
@@ -387,14 +428,17 @@ async function startLanguageServer(context: ExtensionContext): Promise<void> {
       { scheme: "file", language: "vue" },
       { scheme: "file", language: "svelte" },
       { scheme: "file", language: "astro" },
+      { scheme: "file", language: "python" },
+      { scheme: "file", language: "ruby" },
       // Virtual files for remote schemas (introspected)
       { scheme: "schema", language: "graphql" },
     ],
     synchronize: {
       fileEvents: workspace.createFileSystemWatcher(
-        "**/*.{graphql,gql,ts,tsx,js,jsx,vue,svelte,astro}",
+        "**/*.{graphql,gql,ts,tsx,js,jsx,vue,svelte,astro,py,rb}",
       ),
     },
+    initializationOptions: { features: editorFeatures() },
     outputChannel: outputChannel,
     traceOutputChannel: traceOutputChannel,
   };
@@ -539,6 +583,35 @@ export async function activate(context: ExtensionContext) {
       },
     );
 
+    // Command behind the "Copy with fragments" CodeLens: the server assembles
+    // the operation and the fragments it needs, and we put it on the clipboard.
+    const copyOperationCommand = commands.registerCommand(
+      "graphql-analyzer.copyOperation",
+      async (uriString: string, operationIndex: number) => {
+        if (!client) {
+          return;
+        }
+
+        try {
+          const result = await client.sendRequest<{ document: string } | null>(
+            "workspace/executeCommand",
+            {
+              command: "graphql-analyzer.operationWithFragments",
+              arguments: [{ uri: uriString, operationIndex }],
+            },
+          );
+          if (!result) {
+            window.showWarningMessage("Could not find the operation to copy");
+            return;
+          }
+          await env.clipboard.writeText(result.document);
+          window.showInformationMessage("Copied the operation with its fragments");
+        } catch (error) {
+          window.showErrorMessage(`Failed to copy the operation: ${error}`);
+        }
+      },
+    );
+
     const reportIssueCommand = commands.registerCommand(
       "graphql-analyzer.reportIssue",
       async () => {
@@ -580,6 +653,185 @@ export async function activate(context: ExtensionContext) {
       },
     );
 
+    // Generate a selection set for the field under the cursor. The server
+    // returns a snippet so the cursor lands inside the new selection set.
+    const insertSelectionSkeletonCommand = commands.registerCommand(
+      "graphql-analyzer.insertSelectionSkeleton",
+      async () => {
+        const editor = window.activeTextEditor;
+        if (!client || !editor) {
+          return;
+        }
+
+        const converter = client.code2ProtocolConverter;
+        const depth = workspace
+          .getConfiguration("graphql-analyzer.selectionSkeleton", editor.document)
+          .get<number>("depth", 1);
+        const result = await client.sendRequest<{ range: LspRange; snippet: string } | null>(
+          "graphql-analyzer/selectionSkeleton",
+          {
+            textDocument: converter.asTextDocumentIdentifier(editor.document),
+            position: converter.asPosition(editor.selection.active),
+            depth,
+            options: {
+              tabSize: Number(editor.options.tabSize) || 2,
+              insertSpaces: editor.options.insertSpaces !== false,
+            },
+          },
+        );
+
+        if (!result) {
+          window.showInformationMessage(
+            "No selection set to generate for the field under the cursor",
+          );
+          return;
+        }
+
+        await editor.insertSnippet(
+          new SnippetString(result.snippet),
+          client.protocol2CodeConverter.asRange(result.range),
+        );
+      },
+    );
+
+    // Move selection sets repeated across the project into shared fragments.
+    // The server computes the edit; applying it here lets the user undo it.
+    const organizeFragmentsCommand = commands.registerCommand(
+      "graphql-analyzer.organizeFragments",
+      async () => {
+        const editor = window.activeTextEditor;
+        if (!client || !editor) {
+          return;
+        }
+
+        const folder = workspace.getWorkspaceFolder(editor.document.uri);
+        const target = await window.showSaveDialog({
+          defaultUri: Uri.joinPath(folder?.uri ?? editor.document.uri, "fragments.graphql"),
+          filters: { GraphQL: ["graphql", "gql"] },
+          saveLabel: "Write Fragments",
+          title: "File for the hoisted fragments",
+        });
+        if (!target) {
+          return;
+        }
+
+        const converter = client.code2ProtocolConverter;
+        const result = await client.sendRequest<LspWorkspaceEdit | null>(
+          "graphql-analyzer/organizeFragments",
+          {
+            textDocument: converter.asTextDocumentIdentifier(editor.document),
+            fragmentFile: target.toString(),
+          },
+        );
+
+        if (!result) {
+          window.showInformationMessage("No duplicated selection sets found");
+          return;
+        }
+
+        const edit = await client.protocol2CodeConverter.asWorkspaceEdit(result);
+        if (!(await workspace.applyEdit(edit))) {
+          window.showErrorMessage("Failed to apply the fragment edits");
+        }
+      },
+    );
+
+    // Sort and deduplicate the definitions of the schema file in the editor,
+    // grouped by kind in the configured order
+    const organizeSchemaCommand = commands.registerCommand(
+      "graphql-analyzer.organizeSchema",
+      async () => {
+        const editor = window.activeTextEditor;
+        if (!client || !editor) {
+          return;
+        }
+
+        const converter = client.code2ProtocolConverter;
+        const groups = workspace
+          .getConfiguration("graphql-analyzer.organizeSchema", editor.document)
+          .get<string[]>("groups", []);
+        const result = await client.sendRequest<LspTextEdit[] | null>(
+          "graphql-analyzer/organizeSchema",
+          {
+            textDocument: converter.asTextDocumentIdentifier(editor.document),
+            groups,
+          },
+        );
+
+        if (!result) {
+          window.showInformationMessage("Schema is already organized");
+          return;
+        }
+
+        const edit = new WorkspaceEdit();
+        edit.set(editor.document.uri, await client.protocol2CodeConverter.asTextEdits(result));
+        if (!(await workspace.applyEdit(edit))) {
+          window.showErrorMessage("Failed to organize the schema");
+        }
+      },
+    );
+
+    // Render reference docs for the schema of the project the active file
+    // belongs to, and open them in an untitled editor for saving anywhere
+    const generateSchemaDocsCommand = commands.registerCommand(
+      "graphql-analyzer.generateSchemaDocs",
+      async () => {
+        const editor = window.activeTextEditor;
+        if (!client || !editor) {
+          return;
+        }
+
+        const format = await window.showQuickPick(["markdown", "html"], {
+          placeHolder: "Documentation format",
+        });
+        if (!format) {
+          return;
+        }
+
+        const docs = await client.sendRequest<string | null>("graphql-analyzer/generateDocs", {
+          textDocument: client.code2ProtocolConverter.asTextDocumentIdentifier(editor.document),
+          format,
+        });
+        if (!docs) {
+          window.showInformationMessage("No schema to document for this file's project");
+          return;
+        }
+
+        const document = await workspace.openTextDocument({ language: format, content: docs });
+        await window.showTextDocument(document);
+      },
+    );
+
+    // Dump the server's view of the cursor position, for bug reports about
+    // completion or hover doing the wrong thing at a specific spot
+    const showContextAtCursorCommand = commands.registerCommand(
+      "graphql-analyzer.showContextAtCursor",
+      async () => {
+        const editor = window.activeTextEditor;
+        if (!client || !editor) {
+          return;
+        }
+
+        const converter = client.code2ProtocolConverter;
+        const result = await client.sendRequest<unknown>("graphql-analyzer/contextAtPosition", {
+          textDocument: converter.asTextDocumentIdentifier(editor.document),
+          position: converter.asPosition(editor.selection.active),
+        });
+
+        if (!result) {
+          window.showInformationMessage("This file isn't known to graphql-analyzer");
+          return;
+        }
+
+        const position = editor.selection.active;
+        outputChannel.appendLine(
+          `[Context] ${editor.document.uri.toString()}:${position.line + 1}:${position.character + 1}`,
+        );
+        outputChannel.appendLine(JSON.stringify(result, null, 2));
+        outputChannel.show(true);
+      },
+    );
+
     // Trace capture commands
     let traceStatusBarItem: StatusBarItem | undefined;
 
@@ -700,12 +952,36 @@ export async function activate(context: ExtensionContext) {
       }
     });
 
+    const showPerfStatsCommand = commands.registerCommand(
+      "graphql-analyzer.showPerfStats",
+      async () => {
+        if (!client || client.state !== State.Running) {
+          window.showErrorMessage("graphql-analyzer is not running");
+          return;
+        }
+
+        try {
+          const stats = await client.sendRequest("graphql-analyzer/perfStats", {});
+          const document = await workspace.openTextDocument({
+            language: "json",
+            content: JSON.stringify(stats, null, 2),
+          });
+          await window.showTextDocument(document);
+        } catch (error) {
+          window.showErrorMessage(`Failed to get request timings: ${error}`);
+        }
+      },
+    );
+
     // Listen for configuration changes
     context.subscriptions.push(
       workspace.onDidChangeConfiguration((event) => {
         if (event.affectsConfiguration("graphql-analyzer.lsp.trace")) {
           syncTraceLevel();
         }
+        if (event.affectsConfiguration("graphql-analyzer.features")) {
+          syncFeatures();
+        }
         if (event.affectsConfiguration("graphql-analyzer.debug.healthCheck")) {
           outputChannel.appendLine("[Health Check] Configuration changed, restarting...");
           startHealthCheck();
@@ -717,10 +993,17 @@ export async function activate(context: ExtensionContext) {
     context.subscriptions.push(
       reloadCommand,
       showReferencesCommand,
+      copyOperationCommand,
+      insertSelectionSkeletonCommand,
+      organizeFragmentsCommand,
+      organizeSchemaCommand,
+      generateSchemaDocsCommand,
+      showContextAtCursorCommand,
       reportIssueCommand,
       registerTestOtelCommand(outputChannel),
       startTraceCommand,
       stopTraceCommand,
+      showPerfStatsCommand,
     );
   } catch (error) {
     const errorMessage = `Failed to start graphql-analyzer: ${error}`;